@@ -0,0 +1,133 @@
+//! Parsing and rendering throughput, reported in bytes per second.
+//!
+//! Run with `cargo bench`; `cargo bench -- parse/large` runs a single benchmark.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use md_parser::{Parser, ParserConfig, Renderer, RendererConfig};
+
+/// A section exercising headings, inline formatting, lists, tables, and code blocks
+const SECTION: &str = "\
+## Section heading
+
+A paragraph with **bold**, *italic*, ~~struck~~, `code`, and a [link](https://example.com).
+It continues on a second line with an ![image](img.png) and <!-- a comment -->.
+
+- First item with **nested *emphasis***
+- Second item
+  - Nested item with `inline code`
+  - [ ] An open task
+- [x] A finished task
+
+1. One
+2. Two
+3. Three
+
+| Name | Value | Notes |
+| :--- | ---: | :---: |
+| alpha | 1 | *first* |
+| beta | 22 | `second` |
+
+> A blockquote with a [link](https://example.org) inside.
+
+```rust
+fn main() {
+    println!(\"hello\");
+}
+```
+
+---
+
+";
+
+const MERMAID: &str = "\
+```mermaid
+graph TD
+    A[Start] --> B{Is it working?}
+    B -->|Yes| C[Ship it]
+    B -->|No| D[Debug]
+    D --> B
+```
+
+```mermaid
+sequenceDiagram
+    Alice->>Bob: Hello Bob
+    Bob-->>Alice: Hi Alice
+```
+
+";
+
+/// Repeat `unit` until the document is at least `bytes` long
+fn document(unit: &str, bytes: usize) -> String {
+    unit.repeat(bytes.div_ceil(unit.len()))
+}
+
+/// Inputs that drive the inline scanner to its worst cases
+fn pathological() -> Vec<(&'static str, String)> {
+    vec![
+        ("unclosed_emphasis", "*a **b ~~c ".repeat(20_000)),
+        (
+            "nested_emphasis",
+            format!("{}x{}", "*".repeat(5_000), "*".repeat(5_000)),
+        ),
+        ("unclosed_brackets", "[a](".repeat(20_000)),
+        (
+            "backtick_runs",
+            (1..500).map(|n| "`".repeat(n) + " x ").collect(),
+        ),
+        ("unclosed_comments", "<!-- a ".repeat(20_000)),
+    ]
+}
+
+fn parse(input: &str) -> Vec<md_parser::Node> {
+    let config = ParserConfig {
+        error_recovery: true,
+        ..ParserConfig::default()
+    };
+    Parser::with_config(input.to_string(), config)
+        .and_then(|mut parser| parser.parse())
+        .unwrap_or_default()
+}
+
+fn documents() -> Vec<(&'static str, String)> {
+    vec![
+        ("small", include_str!("../assets/input.md").to_string()),
+        ("medium", document(SECTION, 100 * 1024)),
+        ("large", document(SECTION, 4 * 1024 * 1024)),
+        ("mermaid", document(MERMAID, 1024 * 1024)),
+    ]
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, input) in documents().into_iter().chain(pathological()) {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        if input.len() > 1024 * 1024 {
+            group.sample_size(10);
+        }
+        group.bench_with_input(BenchmarkId::from_parameter(name), &input, |b, input| {
+            b.iter(|| parse(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_render(c: &mut Criterion) {
+    let renderer = Renderer::new(RendererConfig::default()).expect("default renderer");
+    let mut group = c.benchmark_group("render_html");
+    for (name, input) in documents() {
+        let ast = parse(&input);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        if input.len() > 1024 * 1024 {
+            group.sample_size(10);
+        }
+        group.bench_with_input(BenchmarkId::from_parameter(name), &ast, |b, ast| {
+            b.iter(|| renderer.render_fragment(black_box(ast)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_render);
+criterion_main!(benches);
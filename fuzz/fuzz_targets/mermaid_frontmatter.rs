@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use md_parser::Parser;
+
+// The input becomes the body of a Mermaid block, so `%%{init: ...}%%` frontmatter
+// parsing and diagram validation see it directly
+fuzz_target!(|data: &str| {
+    let input = format!("```mermaid\n{}\n```\n", data.replace("```", "'''"));
+    let _ = Parser::new(input).unwrap().parse();
+});
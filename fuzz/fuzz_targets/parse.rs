@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use md_parser::{Parser, ParserConfig};
+
+fuzz_target!(|data: &str| {
+    // Every syntax extension that does not read files
+    let config = ParserConfig {
+        enable_attributes: true,
+        enable_sections: true,
+        enable_directives: true,
+        enable_multiline_tables: true,
+        enable_variables: true,
+        error_recovery: true,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config(data.to_string(), config).unwrap();
+    let _ = parser.parse();
+
+    let mut parser = Parser::new(data.to_string()).unwrap();
+    let _ = parser.parse();
+});
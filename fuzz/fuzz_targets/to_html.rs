@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use md_parser::{to_markdown, Parser, ParserConfig, Renderer, RendererConfig};
+
+fuzz_target!(|data: &str| {
+    let config = ParserConfig {
+        enable_attributes: true,
+        enable_directives: true,
+        error_recovery: true,
+        ..ParserConfig::default()
+    };
+    let Ok(ast) = Parser::with_config(data.to_string(), config).unwrap().parse() else {
+        return;
+    };
+
+    // Missing template files fall back to the built-in assets
+    let renderer = Renderer::new(RendererConfig::default()).unwrap();
+    let _ = renderer.render_html(&ast);
+    let _ = to_markdown(&ast);
+});
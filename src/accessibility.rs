@@ -0,0 +1,133 @@
+//! Accessibility audit of parsed documents.
+
+use crate::ast::{for_each_inline, plain_text, visit_inlines, Inline, LineRange, ListItem, Node};
+use crate::node_path::{iter_nodes, NodePath};
+use crate::parse_result::ParseResult;
+use std::fmt;
+
+/// Accessibility rule broken by an [`AccessibilityIssue`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessibilityRule {
+    /// Image without alt text, which screen readers announce by file name or not at all
+    MissingAltText,
+    /// Link whose text is empty, so it has no accessible name
+    EmptyLinkText,
+    /// Heading more than one level below the previous heading (`#` followed by `###`)
+    SkippedHeadingLevel,
+    /// Task list item without text to label its checkbox
+    UnlabeledCheckbox,
+}
+
+impl AccessibilityRule {
+    /// Kebab-case name used in reports (`missing-alt-text`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccessibilityRule::MissingAltText => "missing-alt-text",
+            AccessibilityRule::EmptyLinkText => "empty-link-text",
+            AccessibilityRule::SkippedHeadingLevel => "skipped-heading-level",
+            AccessibilityRule::UnlabeledCheckbox => "unlabeled-checkbox",
+        }
+    }
+}
+
+impl fmt::Display for AccessibilityRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A violation found by [`audit_accessibility`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibilityIssue {
+    pub rule: AccessibilityRule,
+    /// Human-readable description
+    pub message: String,
+    /// Position of the offending block in the AST
+    pub path: NodePath,
+    /// Source lines of the enclosing top-level block
+    pub span: Option<LineRange>,
+}
+
+impl fmt::Display for AccessibilityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(span) = &self.span {
+            write!(f, "{}: ", span)?;
+        }
+        write!(f, "accessibility: {} [{}]", self.message, self.rule)
+    }
+}
+
+/// Images without alt text, links without text, skipped heading levels, and task items
+/// without a label, in document order
+///
+/// Rendering with `RendererConfig::accessible` adds table header scopes, checkbox labels,
+/// and page landmarks, but cannot invent alt text or headings; this lists what the
+/// author has to fix.
+pub fn audit_accessibility(result: &ParseResult) -> Vec<AccessibilityIssue> {
+    let mut issues = Vec::new();
+    let mut previous_level = None;
+    for (path, node) in iter_nodes(&result.nodes) {
+        let span = result.spans.get(path.indices()[0]).copied();
+        let mut report = |rule, message: String| {
+            issues.push(AccessibilityIssue {
+                rule,
+                message,
+                path: path.clone(),
+                span,
+            });
+        };
+
+        if let Node::Heading { level, .. } | Node::Section { level, .. } = node {
+            if let Some(previous) = previous_level.filter(|previous| level > &(previous + 1)) {
+                report(
+                    AccessibilityRule::SkippedHeadingLevel,
+                    format!(
+                        "level-{} heading follows a level-{} heading",
+                        level, previous
+                    ),
+                );
+            }
+            previous_level = Some(*level);
+        }
+        if let Node::UnorderedList { items } | Node::OrderedList { items } = node {
+            check_checkboxes(items, &mut report);
+        }
+        for_each_own_inline(node, &mut |inline| match inline {
+            Inline::Image { url, alt, .. } if alt.trim().is_empty() => report(
+                AccessibilityRule::MissingAltText,
+                format!("image `{}` has no alt text", url),
+            ),
+            Inline::Link { text, url, .. } if plain_text(text).trim().is_empty() => report(
+                AccessibilityRule::EmptyLinkText,
+                format!("link to `{}` has no text", url),
+            ),
+            _ => {}
+        });
+    }
+    issues
+}
+
+fn check_checkboxes(items: &[ListItem], report: &mut impl FnMut(AccessibilityRule, String)) {
+    for item in items {
+        if item.checked.is_some() && plain_text(&item.content).trim().is_empty() {
+            report(
+                AccessibilityRule::UnlabeledCheckbox,
+                "task list item has no text to label its checkbox".to_string(),
+            );
+        }
+        check_checkboxes(&item.children, report);
+    }
+}
+
+/// Call `f` for the inlines of `node` itself, leaving those of its child blocks to
+/// their own visit
+fn for_each_own_inline(node: &Node, f: &mut impl FnMut(&Inline)) {
+    match node {
+        Node::Section { heading, .. } => visit_inlines(heading, f),
+        Node::Details { summary, .. } => visit_inlines(summary, f),
+        Node::Figure { caption, .. } => visit_inlines(caption, f),
+        Node::Admonition { title, .. } => visit_inlines(title.as_deref().unwrap_or_default(), f),
+        Node::Container { .. } => {}
+        _ => for_each_inline(std::slice::from_ref(node), f),
+    }
+}
@@ -0,0 +1,84 @@
+//! Per-node metadata kept beside the AST.
+
+use crate::ast::Node;
+use crate::node_path::{iter_nodes, NodePath};
+use std::collections::{BTreeMap, HashMap};
+
+/// Metadata of nodes by path, such as authorship from `git blame` or a review status
+///
+/// The AST is left untouched, so any tool can decorate any node.
+/// [`Renderer::render_html_annotated`](crate::Renderer::render_html_annotated) writes
+/// each entry as a `data-*` attribute of the node's element.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Annotations {
+    entries: BTreeMap<NodePath, HashMap<String, String>>,
+}
+
+impl Annotations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `key` of the node at `path`, replacing any earlier value
+    pub fn insert(&mut self, path: NodePath, key: impl Into<String>, value: impl Into<String>) {
+        self.entries
+            .entry(path)
+            .or_default()
+            .insert(key.into(), value.into());
+    }
+
+    /// Metadata of the node at `path`
+    pub fn get(&self, path: &NodePath) -> Option<&HashMap<String, String>> {
+        self.entries.get(path)
+    }
+
+    /// Remove all metadata of the node at `path`
+    pub fn remove(&mut self, path: &NodePath) -> Option<HashMap<String, String>> {
+        self.entries.remove(path)
+    }
+
+    /// Annotated nodes and their metadata, in document order
+    pub fn iter(&self) -> impl Iterator<Item = (&NodePath, &HashMap<String, String>)> {
+        self.entries.iter()
+    }
+
+    /// Number of annotated nodes
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Run an annotation pass: call `f` with every node and its path (in the order of
+/// [`iter_nodes`]) and keep the metadata it returns
+///
+/// Nodes for which `f` returns an empty map are not annotated.
+///
+/// ```
+/// use md_parser::{annotate, Node, Parser};
+/// use std::collections::HashMap;
+///
+/// let ast = Parser::new("# Title\n\nText\n".to_string())?.parse()?;
+/// let annotations = annotate(&ast, |_, node| match node {
+///     Node::Paragraph { .. } => HashMap::from([("review".to_string(), "approved".to_string())]),
+///     _ => HashMap::new(),
+/// });
+/// assert_eq!(annotations.len(), 1);
+/// # Ok::<(), md_parser::ParseError>(())
+/// ```
+pub fn annotate(
+    nodes: &[Node],
+    mut f: impl FnMut(&NodePath, &Node) -> HashMap<String, String>,
+) -> Annotations {
+    let mut annotations = Annotations::new();
+    for (path, node) in iter_nodes(nodes) {
+        let metadata = f(&path, node);
+        if !metadata.is_empty() {
+            annotations.entries.insert(path, metadata);
+        }
+    }
+    annotations
+}
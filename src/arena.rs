@@ -0,0 +1,697 @@
+//! Arena-allocated ASTs (`arena` feature).
+//!
+//! An owned AST makes one heap allocation for nearly every string and list, which
+//! dominates the memory of a service keeping many documents. [`to_arena_ast`] copies an
+//! AST into a [`Bump`] arena instead: the copy borrows all its text and children from a
+//! few large blocks, and is freed at once with the arena.
+
+use crate::ast::{
+    AdmonitionKind, Alignment, Attributes, Inline, LineRange, ListItem, MermaidConfig, Node,
+    ValidationStatus,
+};
+pub use bumpalo::Bump;
+use std::collections::{BTreeMap, HashMap};
+
+/// [`Attributes`] in an arena
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ArenaAttributes<'a> {
+    pub id: Option<&'a str>,
+    pub classes: &'a [&'a str],
+    /// Key/value pairs, sorted by key
+    pub pairs: &'a [(&'a str, &'a str)],
+}
+
+/// [`Inline`] in an arena
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaInline<'a> {
+    Text {
+        content: &'a str,
+    },
+    Bold {
+        content: &'a [ArenaInline<'a>],
+    },
+    Italic {
+        content: &'a [ArenaInline<'a>],
+    },
+    Strikethrough {
+        content: &'a [ArenaInline<'a>],
+    },
+    Highlight {
+        content: &'a [ArenaInline<'a>],
+    },
+    Spoiler {
+        content: &'a [ArenaInline<'a>],
+    },
+    Hashtag {
+        tag: &'a str,
+    },
+    Mention {
+        user: &'a str,
+    },
+    Link {
+        text: &'a [ArenaInline<'a>],
+        url: &'a str,
+    },
+    Image {
+        alt: &'a str,
+        url: &'a str,
+        attributes: ArenaAttributes<'a>,
+    },
+    Span {
+        content: &'a [ArenaInline<'a>],
+        attributes: ArenaAttributes<'a>,
+    },
+    Code {
+        content: &'a str,
+    },
+    Comment {
+        content: &'a str,
+    },
+    LineBreak,
+    Directive {
+        name: &'a str,
+        content: &'a [ArenaInline<'a>],
+        attributes: ArenaAttributes<'a>,
+    },
+}
+
+/// [`ListItem`] in an arena
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaListItem<'a> {
+    pub content: &'a [ArenaInline<'a>],
+    pub children: &'a [ArenaListItem<'a>],
+    pub blocks: &'a [ArenaNode<'a>],
+    pub checked: Option<bool>,
+}
+
+/// [`MermaidConfig`] in an arena
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArenaMermaidConfig<'a> {
+    pub theme: Option<&'a str>,
+    pub font_size: Option<&'a str>,
+    pub font_family: Option<&'a str>,
+    /// Theme variables, sorted by name
+    pub theme_variables: Option<&'a [(&'a str, &'a str)]>,
+}
+
+/// [`ValidationStatus`] in an arena
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaValidationStatus<'a> {
+    Valid,
+    Invalid { errors: &'a [&'a str] },
+    NotValidated,
+}
+
+/// Table cells: rows of cells of inline elements
+type ArenaRows<'a> = &'a [&'a [&'a [ArenaInline<'a>]]];
+
+/// [`Node`] in an arena
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaNode<'a> {
+    Heading {
+        level: u8,
+        content: &'a [ArenaInline<'a>],
+        attributes: ArenaAttributes<'a>,
+    },
+    Paragraph {
+        content: &'a [ArenaInline<'a>],
+    },
+    UnorderedList {
+        items: &'a [ArenaListItem<'a>],
+    },
+    OrderedList {
+        items: &'a [ArenaListItem<'a>],
+    },
+    CodeBlock {
+        lang: Option<&'a str>,
+        code: &'a str,
+        attributes: ArenaAttributes<'a>,
+    },
+    MermaidDiagram {
+        diagram: &'a str,
+        name: Option<&'a str>,
+        config: Option<ArenaMermaidConfig<'a>>,
+        validation_status: ArenaValidationStatus<'a>,
+        warnings: &'a [&'a str],
+    },
+    DiagramRef {
+        name: &'a str,
+        span: Option<LineRange>,
+    },
+    Table {
+        headers: &'a [&'a [ArenaInline<'a>]],
+        rows: ArenaRows<'a>,
+        alignments: &'a [Option<Alignment>],
+        caption: Option<&'a [ArenaInline<'a>]>,
+        number: Option<usize>,
+        span: Option<LineRange>,
+    },
+    Blockquote {
+        level: u8,
+        content: &'a [ArenaInline<'a>],
+        blocks: &'a [ArenaNode<'a>],
+    },
+    HorizontalRule {
+        span: Option<LineRange>,
+    },
+    Admonition {
+        kind: AdmonitionKind,
+        title: Option<&'a [ArenaInline<'a>]>,
+        content: &'a [ArenaNode<'a>],
+    },
+    Container {
+        attributes: ArenaAttributes<'a>,
+        children: &'a [ArenaNode<'a>],
+    },
+    Details {
+        summary: &'a [ArenaInline<'a>],
+        content: &'a [ArenaNode<'a>],
+    },
+    Section {
+        level: u8,
+        heading: &'a [ArenaInline<'a>],
+        attributes: ArenaAttributes<'a>,
+        children: &'a [ArenaNode<'a>],
+    },
+    Figure {
+        content: &'a [ArenaNode<'a>],
+        caption: &'a [ArenaInline<'a>],
+        number: Option<usize>,
+    },
+    Comment {
+        content: &'a str,
+    },
+}
+
+/// Copy `nodes` into `arena`
+///
+/// The owned AST can be dropped afterwards; convert back with [`from_arena_ast`] to render or
+/// transform the document. `arena.allocated_bytes()` reports the memory the copy uses.
+///
+/// ```
+/// use md_parser::{to_arena_ast, from_arena_ast, ArenaNode, Bump, Parser};
+///
+/// let ast = Parser::new("# Title\n\nText\n".to_string())?.parse()?;
+/// let arena = Bump::new();
+/// let nodes = to_arena_ast(&arena, &ast);
+/// assert!(matches!(nodes[0], ArenaNode::Heading { level: 1, .. }));
+/// assert_eq!(from_arena_ast(nodes), ast);
+/// # Ok::<(), md_parser::ParseError>(())
+/// ```
+pub fn to_arena_ast<'a>(arena: &'a Bump, nodes: &[Node]) -> &'a [ArenaNode<'a>] {
+    arena.alloc_slice_fill_iter(nodes.iter().map(|node| alloc_node(arena, node)))
+}
+
+/// Copy an arena AST back into owned nodes
+pub fn from_arena_ast(nodes: &[ArenaNode<'_>]) -> Vec<Node> {
+    nodes.iter().map(ArenaNode::to_node).collect()
+}
+
+impl ArenaNode<'_> {
+    /// Owned copy of the node
+    pub fn to_node(&self) -> Node {
+        match *self {
+            ArenaNode::Heading {
+                level,
+                content,
+                attributes,
+            } => Node::Heading {
+                level,
+                content: owned_inlines(content),
+                attributes: attributes.to_attributes(),
+            },
+            ArenaNode::Paragraph { content } => Node::Paragraph {
+                content: owned_inlines(content),
+            },
+            ArenaNode::UnorderedList { items } => Node::UnorderedList {
+                items: owned_items(items),
+            },
+            ArenaNode::OrderedList { items } => Node::OrderedList {
+                items: owned_items(items),
+            },
+            ArenaNode::CodeBlock {
+                lang,
+                code,
+                attributes,
+            } => Node::CodeBlock {
+                lang: lang.map(str::to_string),
+                code: code.to_string(),
+                attributes: attributes.to_attributes(),
+            },
+            ArenaNode::MermaidDiagram {
+                diagram,
+                name,
+                config,
+                validation_status,
+                warnings,
+            } => Node::MermaidDiagram {
+                diagram: diagram.to_string(),
+                name: name.map(str::to_string),
+                config: config.map(|config| MermaidConfig {
+                    theme: config.theme.map(str::to_string),
+                    font_size: config.font_size.map(str::to_string),
+                    font_family: config.font_family.map(str::to_string),
+                    theme_variables: config.theme_variables.map(|vars| {
+                        vars.iter()
+                            .map(|(k, v)| (k.to_string(), v.to_string()))
+                            .collect()
+                    }),
+                }),
+                validation_status: match validation_status {
+                    ArenaValidationStatus::Valid => ValidationStatus::Valid,
+                    ArenaValidationStatus::Invalid { errors } => ValidationStatus::Invalid {
+                        errors: owned_strs(errors),
+                    },
+                    ArenaValidationStatus::NotValidated => ValidationStatus::NotValidated,
+                },
+                warnings: owned_strs(warnings),
+            },
+            ArenaNode::DiagramRef { name, span } => Node::DiagramRef {
+                name: name.to_string(),
+                span,
+            },
+            ArenaNode::Table {
+                headers,
+                rows,
+                alignments,
+                caption,
+                number,
+                span,
+            } => Node::Table {
+                headers: headers.iter().map(|cell| owned_inlines(cell)).collect(),
+                rows: rows
+                    .iter()
+                    .map(|row| row.iter().map(|cell| owned_inlines(cell)).collect())
+                    .collect(),
+                alignments: alignments.to_vec(),
+                caption: caption.map(owned_inlines),
+                number,
+                span,
+            },
+            ArenaNode::Blockquote {
+                level,
+                content,
+                blocks,
+            } => Node::Blockquote {
+                level,
+                content: owned_inlines(content),
+                blocks: from_arena_ast(blocks),
+            },
+            ArenaNode::HorizontalRule { span } => Node::HorizontalRule { span },
+            ArenaNode::Admonition {
+                kind,
+                title,
+                content,
+            } => Node::Admonition {
+                kind,
+                title: title.map(owned_inlines),
+                content: from_arena_ast(content),
+            },
+            ArenaNode::Container {
+                attributes,
+                children,
+            } => Node::Container {
+                attributes: attributes.to_attributes(),
+                children: from_arena_ast(children),
+            },
+            ArenaNode::Details { summary, content } => Node::Details {
+                summary: owned_inlines(summary),
+                content: from_arena_ast(content),
+            },
+            ArenaNode::Section {
+                level,
+                heading,
+                attributes,
+                children,
+            } => Node::Section {
+                level,
+                heading: owned_inlines(heading),
+                attributes: attributes.to_attributes(),
+                children: from_arena_ast(children),
+            },
+            ArenaNode::Figure {
+                content,
+                caption,
+                number,
+            } => Node::Figure {
+                content: from_arena_ast(content),
+                caption: owned_inlines(caption),
+                number,
+            },
+            ArenaNode::Comment { content } => Node::Comment {
+                content: content.to_string(),
+            },
+        }
+    }
+}
+
+impl ArenaInline<'_> {
+    /// Owned copy of the inline element
+    pub fn to_inline(&self) -> Inline {
+        match *self {
+            ArenaInline::Text { content } => Inline::Text {
+                content: content.into(),
+            },
+            ArenaInline::Bold { content } => Inline::Bold {
+                content: owned_inlines(content),
+            },
+            ArenaInline::Italic { content } => Inline::Italic {
+                content: owned_inlines(content),
+            },
+            ArenaInline::Strikethrough { content } => Inline::Strikethrough {
+                content: owned_inlines(content),
+            },
+            ArenaInline::Highlight { content } => Inline::Highlight {
+                content: owned_inlines(content),
+            },
+            ArenaInline::Spoiler { content } => Inline::Spoiler {
+                content: owned_inlines(content),
+            },
+            ArenaInline::Hashtag { tag } => Inline::Hashtag { tag: tag.into() },
+            ArenaInline::Mention { user } => Inline::Mention { user: user.into() },
+            ArenaInline::Link { text, url } => Inline::Link {
+                text: owned_inlines(text),
+                url: url.to_string(),
+            },
+            ArenaInline::Image {
+                alt,
+                url,
+                attributes,
+            } => Inline::Image {
+                alt: alt.to_string(),
+                url: url.to_string(),
+                attributes: attributes.to_attributes(),
+            },
+            ArenaInline::Span {
+                content,
+                attributes,
+            } => Inline::Span {
+                content: owned_inlines(content),
+                attributes: attributes.to_attributes(),
+            },
+            ArenaInline::Code { content } => Inline::Code {
+                content: content.into(),
+            },
+            ArenaInline::Comment { content } => Inline::Comment {
+                content: content.to_string(),
+            },
+            ArenaInline::LineBreak => Inline::LineBreak,
+            ArenaInline::Directive {
+                name,
+                content,
+                attributes,
+            } => Inline::Directive {
+                name: name.to_string(),
+                content: owned_inlines(content),
+                attributes: attributes.to_attributes(),
+            },
+        }
+    }
+}
+
+impl ArenaAttributes<'_> {
+    /// Owned copy of the attributes
+    pub fn to_attributes(&self) -> Attributes {
+        Attributes {
+            id: self.id.map(str::to_string),
+            classes: owned_strs(self.classes),
+            pairs: self
+                .pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+}
+
+fn owned_inlines(inlines: &[ArenaInline<'_>]) -> Vec<Inline> {
+    inlines.iter().map(ArenaInline::to_inline).collect()
+}
+
+fn owned_items(items: &[ArenaListItem<'_>]) -> Vec<ListItem> {
+    items
+        .iter()
+        .map(|item| ListItem {
+            content: owned_inlines(item.content),
+            children: owned_items(item.children),
+            blocks: from_arena_ast(item.blocks),
+            checked: item.checked,
+        })
+        .collect()
+}
+
+fn owned_strs(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn alloc_node<'a>(arena: &'a Bump, node: &Node) -> ArenaNode<'a> {
+    match node {
+        Node::Heading {
+            level,
+            content,
+            attributes,
+        } => ArenaNode::Heading {
+            level: *level,
+            content: alloc_inlines(arena, content),
+            attributes: alloc_attributes(arena, attributes),
+        },
+        Node::Paragraph { content } => ArenaNode::Paragraph {
+            content: alloc_inlines(arena, content),
+        },
+        Node::UnorderedList { items } => ArenaNode::UnorderedList {
+            items: alloc_items(arena, items),
+        },
+        Node::OrderedList { items } => ArenaNode::OrderedList {
+            items: alloc_items(arena, items),
+        },
+        Node::CodeBlock {
+            lang,
+            code,
+            attributes,
+        } => ArenaNode::CodeBlock {
+            lang: alloc_opt_str(arena, lang),
+            code: arena.alloc_str(code),
+            attributes: alloc_attributes(arena, attributes),
+        },
+        Node::MermaidDiagram {
+            diagram,
+            name,
+            config,
+            validation_status,
+            warnings,
+        } => ArenaNode::MermaidDiagram {
+            diagram: arena.alloc_str(diagram),
+            name: alloc_opt_str(arena, name),
+            config: config.as_ref().map(|config| ArenaMermaidConfig {
+                theme: alloc_opt_str(arena, &config.theme),
+                font_size: alloc_opt_str(arena, &config.font_size),
+                font_family: alloc_opt_str(arena, &config.font_family),
+                theme_variables: config
+                    .theme_variables
+                    .as_ref()
+                    .map(|vars| alloc_sorted_pairs(arena, vars)),
+            }),
+            validation_status: match validation_status {
+                ValidationStatus::Valid => ArenaValidationStatus::Valid,
+                ValidationStatus::Invalid { errors } => ArenaValidationStatus::Invalid {
+                    errors: alloc_strs(arena, errors),
+                },
+                ValidationStatus::NotValidated => ArenaValidationStatus::NotValidated,
+            },
+            warnings: alloc_strs(arena, warnings),
+        },
+        Node::DiagramRef { name, span } => ArenaNode::DiagramRef {
+            name: arena.alloc_str(name),
+            span: *span,
+        },
+        Node::Table {
+            headers,
+            rows,
+            alignments,
+            caption,
+            number,
+            span,
+        } => ArenaNode::Table {
+            headers: alloc_cells(arena, headers),
+            rows: arena.alloc_slice_fill_iter(rows.iter().map(|row| alloc_cells(arena, row))),
+            alignments: arena.alloc_slice_copy(alignments),
+            caption: caption
+                .as_ref()
+                .map(|caption| alloc_inlines(arena, caption)),
+            number: *number,
+            span: *span,
+        },
+        Node::Blockquote {
+            level,
+            content,
+            blocks,
+        } => ArenaNode::Blockquote {
+            level: *level,
+            content: alloc_inlines(arena, content),
+            blocks: to_arena_ast(arena, blocks),
+        },
+        Node::HorizontalRule { span } => ArenaNode::HorizontalRule { span: *span },
+        Node::Admonition {
+            kind,
+            title,
+            content,
+        } => ArenaNode::Admonition {
+            kind: *kind,
+            title: title.as_ref().map(|title| alloc_inlines(arena, title)),
+            content: to_arena_ast(arena, content),
+        },
+        Node::Container {
+            attributes,
+            children,
+        } => ArenaNode::Container {
+            attributes: alloc_attributes(arena, attributes),
+            children: to_arena_ast(arena, children),
+        },
+        Node::Details { summary, content } => ArenaNode::Details {
+            summary: alloc_inlines(arena, summary),
+            content: to_arena_ast(arena, content),
+        },
+        Node::Section {
+            level,
+            heading,
+            attributes,
+            children,
+        } => ArenaNode::Section {
+            level: *level,
+            heading: alloc_inlines(arena, heading),
+            attributes: alloc_attributes(arena, attributes),
+            children: to_arena_ast(arena, children),
+        },
+        Node::Figure {
+            content,
+            caption,
+            number,
+        } => ArenaNode::Figure {
+            content: to_arena_ast(arena, content),
+            caption: alloc_inlines(arena, caption),
+            number: *number,
+        },
+        Node::Comment { content } => ArenaNode::Comment {
+            content: arena.alloc_str(content),
+        },
+    }
+}
+
+fn alloc_inlines<'a>(arena: &'a Bump, inlines: &[Inline]) -> &'a [ArenaInline<'a>] {
+    arena.alloc_slice_fill_iter(inlines.iter().map(|inline| alloc_inline(arena, inline)))
+}
+
+fn alloc_inline<'a>(arena: &'a Bump, inline: &Inline) -> ArenaInline<'a> {
+    match inline {
+        Inline::Text { content } => ArenaInline::Text {
+            content: arena.alloc_str(content),
+        },
+        Inline::Bold { content } => ArenaInline::Bold {
+            content: alloc_inlines(arena, content),
+        },
+        Inline::Italic { content } => ArenaInline::Italic {
+            content: alloc_inlines(arena, content),
+        },
+        Inline::Strikethrough { content } => ArenaInline::Strikethrough {
+            content: alloc_inlines(arena, content),
+        },
+        Inline::Highlight { content } => ArenaInline::Highlight {
+            content: alloc_inlines(arena, content),
+        },
+        Inline::Spoiler { content } => ArenaInline::Spoiler {
+            content: alloc_inlines(arena, content),
+        },
+        Inline::Hashtag { tag } => ArenaInline::Hashtag {
+            tag: arena.alloc_str(tag),
+        },
+        Inline::Mention { user } => ArenaInline::Mention {
+            user: arena.alloc_str(user),
+        },
+        Inline::Link { text, url } => ArenaInline::Link {
+            text: alloc_inlines(arena, text),
+            url: arena.alloc_str(url),
+        },
+        Inline::Image {
+            alt,
+            url,
+            attributes,
+        } => ArenaInline::Image {
+            alt: arena.alloc_str(alt),
+            url: arena.alloc_str(url),
+            attributes: alloc_attributes(arena, attributes),
+        },
+        Inline::Span {
+            content,
+            attributes,
+        } => ArenaInline::Span {
+            content: alloc_inlines(arena, content),
+            attributes: alloc_attributes(arena, attributes),
+        },
+        Inline::Code { content } => ArenaInline::Code {
+            content: arena.alloc_str(content),
+        },
+        Inline::Comment { content } => ArenaInline::Comment {
+            content: arena.alloc_str(content),
+        },
+        Inline::LineBreak => ArenaInline::LineBreak,
+        Inline::Directive {
+            name,
+            content,
+            attributes,
+        } => ArenaInline::Directive {
+            name: arena.alloc_str(name),
+            content: alloc_inlines(arena, content),
+            attributes: alloc_attributes(arena, attributes),
+        },
+    }
+}
+
+fn alloc_items<'a>(arena: &'a Bump, items: &[ListItem]) -> &'a [ArenaListItem<'a>] {
+    arena.alloc_slice_fill_iter(items.iter().map(|item| ArenaListItem {
+        content: alloc_inlines(arena, &item.content),
+        children: alloc_items(arena, &item.children),
+        blocks: to_arena_ast(arena, &item.blocks),
+        checked: item.checked,
+    }))
+}
+
+fn alloc_cells<'a>(arena: &'a Bump, cells: &[Vec<Inline>]) -> &'a [&'a [ArenaInline<'a>]] {
+    arena.alloc_slice_fill_iter(cells.iter().map(|cell| alloc_inlines(arena, cell)))
+}
+
+fn alloc_attributes<'a>(arena: &'a Bump, attributes: &Attributes) -> ArenaAttributes<'a> {
+    if attributes.is_empty() {
+        return ArenaAttributes::default();
+    }
+    ArenaAttributes {
+        id: alloc_opt_str(arena, &attributes.id),
+        classes: alloc_strs(arena, &attributes.classes),
+        pairs: arena.alloc_slice_fill_iter(
+            attributes
+                .pairs
+                .iter()
+                .map(|(key, value)| (&*arena.alloc_str(key), &*arena.alloc_str(value))),
+        ),
+    }
+}
+
+fn alloc_opt_str<'a>(arena: &'a Bump, text: &Option<String>) -> Option<&'a str> {
+    text.as_deref().map(|text| &*arena.alloc_str(text))
+}
+
+fn alloc_strs<'a>(arena: &'a Bump, strs: &[String]) -> &'a [&'a str] {
+    arena.alloc_slice_fill_iter(strs.iter().map(|text| &*arena.alloc_str(text)))
+}
+
+fn alloc_sorted_pairs<'a>(
+    arena: &'a Bump,
+    pairs: &HashMap<String, String>,
+) -> &'a [(&'a str, &'a str)] {
+    let mut sorted: Vec<(&String, &String)> = pairs.iter().collect();
+    sorted.sort();
+    arena.alloc_slice_fill_iter(
+        sorted
+            .into_iter()
+            .map(|(key, value)| (&*arena.alloc_str(key), &*arena.alloc_str(value))),
+    )
+}
@@ -1,16 +1,21 @@
 //! Shared AST types for the Markdown parser.
 
+use crate::diagnostics;
+use crate::line_index::LineIndex;
+pub use compact_str::CompactString;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fmt;
 
 /// Source location in the input (1-based line for user-facing messages).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Span {
     /// 1-based line number
     pub line: usize,
     /// Optional 1-based column (when available)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub column: Option<usize>,
 }
 
@@ -23,6 +28,25 @@ impl fmt::Display for Span {
     }
 }
 
+/// Range of source lines covered by a block (1-based, inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LineRange {
+    /// First line of the block
+    pub start: usize,
+    /// Last line of the block
+    pub end: usize,
+}
+
+impl fmt::Display for LineRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "line {}", self.start)
+        } else {
+            write!(f, "lines {}-{}", self.start, self.end)
+        }
+    }
+}
+
 /// Errors that can occur during parsing
 #[derive(Debug, Clone)]
 pub enum ParseError {
@@ -32,43 +56,394 @@ pub enum ParseError {
     InvalidCaptureError(String),
     /// Error serializing AST to JSON
     SerializationError(String),
-    /// Heading with more than 6 `#` characters
-    InvalidHeadingLevel { level: u8, span: Span },
+    /// Heading with more `#` characters than the configured maximum
+    InvalidHeadingLevel { level: u8, max: u8, span: Span },
     /// Code fence opened, EOF before closing ```
     UnclosedCodeBlock { span: Span },
     /// Generic structural issues (future use)
     MalformedMarkdown { message: String, span: Span },
+    /// Parsing was aborted through a `CancellationToken`
+    Cancelled { span: Span },
+    /// A file referenced by the document could not be read or parsed
+    IncludeFailed {
+        path: String,
+        message: String,
+        span: Span,
+    },
+    /// The document file could not be read
+    ReadFailed { path: String, message: String },
+    /// A warning whose code is listed in `ParserConfig::deny`
+    DeniedWarning(ParseWarning),
+    /// The input exceeded one of `ParserConfig::limits`
+    ///
+    /// `limit` is the configured maximum (milliseconds for `Timeout`). `span` is `None`
+    /// when the limit does not apply to one place, as for the input size.
+    LimitExceeded {
+        kind: LimitKind,
+        limit: usize,
+        span: Option<Span>,
+    },
 }
 
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+/// Which of `ParserConfig::limits` a parse exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LimitKind {
+    /// Input size in bytes, after includes are expanded
+    InputSize,
+    /// Nesting of inline formatting, list items, blockquotes, or container blocks
+    NestingDepth,
+    /// Number of block nodes and inline elements
+    Nodes,
+    /// Time spent parsing
+    Timeout,
+}
+
+impl LimitKind {
+    fn describe(&self, limit: usize) -> String {
         match self {
-            ParseError::RegexCompilationError(msg) => {
-                write!(f, "Regex compilation error: {}", msg)
+            LimitKind::InputSize => format!("input is larger than {} bytes", limit),
+            LimitKind::NestingDepth => format!("nesting is deeper than {} levels", limit),
+            LimitKind::Nodes => format!("document has more than {} nodes", limit),
+            LimitKind::Timeout => format!("parsing took longer than {} ms", limit),
+        }
+    }
+}
+
+impl ParseError {
+    /// Source location of the error, if it has one
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            ParseError::InvalidHeadingLevel { span, .. }
+            | ParseError::UnclosedCodeBlock { span }
+            | ParseError::MalformedMarkdown { span, .. }
+            | ParseError::Cancelled { span }
+            | ParseError::IncludeFailed { span, .. } => Some(span),
+            ParseError::DeniedWarning(warning) => Some(&warning.span),
+            ParseError::LimitExceeded { span, .. } => span.as_ref(),
+            ParseError::RegexCompilationError(_)
+            | ParseError::InvalidCaptureError(_)
+            | ParseError::SerializationError(_)
+            | ParseError::ReadFailed { .. } => None,
+        }
+    }
+
+    /// Give a span without a column the column where its line's content starts
+    pub(crate) fn with_column(mut self, index: &LineIndex) -> Self {
+        match &mut self {
+            ParseError::InvalidHeadingLevel { span, .. }
+            | ParseError::UnclosedCodeBlock { span }
+            | ParseError::MalformedMarkdown { span, .. }
+            | ParseError::Cancelled { span }
+            | ParseError::IncludeFailed { span, .. } => index.fill_column(span),
+            ParseError::DeniedWarning(warning) => index.fill_column(&mut warning.span),
+            ParseError::LimitExceeded { span, .. } => {
+                if let Some(span) = span {
+                    index.fill_column(span);
+                }
             }
-            ParseError::InvalidCaptureError(msg) => {
-                write!(f, "Invalid capture error: {}", msg)
+            ParseError::RegexCompilationError(_)
+            | ParseError::InvalidCaptureError(_)
+            | ParseError::SerializationError(_)
+            | ParseError::ReadFailed { .. } => {}
+        }
+        self
+    }
+
+    /// Format the error with the offending line of `source` and a caret under it
+    ///
+    /// `source` must be the Markdown that was parsed. The output follows rustc:
+    ///
+    /// ```text
+    /// error: unclosed code block
+    ///  --> line 3, column 1
+    ///   |
+    /// 3 | ```rust
+    ///   | ^
+    ///   = note: add a closing fence, or enable `error_recovery` to close the block at end of input
+    /// ```
+    pub fn display_with_source(&self, source: &str) -> String {
+        diagnostics::render(
+            "error",
+            &self.summary(),
+            self.span(),
+            source,
+            self.note().as_deref(),
+        )
+    }
+
+    /// Hint on how to fix the error, if there is one
+    pub(crate) fn note(&self) -> Option<String> {
+        match self {
+            ParseError::InvalidHeadingLevel { .. } => Some(
+                "headings use at most 6 `#` characters; enable `error_recovery` to keep the line as a paragraph"
+                    .to_string(),
+            ),
+            ParseError::UnclosedCodeBlock { .. } => Some(
+                "add a closing fence, or enable `error_recovery` to close the block at end of input"
+                    .to_string(),
+            ),
+            ParseError::DeniedWarning(warning) => {
+                Some(format!("`{}` is listed in `parser.deny`", warning.code))
+            }
+            _ => None,
+        }
+    }
+
+    /// Move the error's span down by `lines` (for errors raised inside nested content)
+    pub(crate) fn shift_lines(mut self, lines: usize) -> Self {
+        match &mut self {
+            ParseError::InvalidHeadingLevel { span, .. }
+            | ParseError::UnclosedCodeBlock { span }
+            | ParseError::MalformedMarkdown { span, .. }
+            | ParseError::Cancelled { span }
+            | ParseError::IncludeFailed { span, .. } => span.line += lines,
+            ParseError::DeniedWarning(warning) => warning.span.line += lines,
+            ParseError::LimitExceeded { span, .. } => {
+                if let Some(span) = span {
+                    span.line += lines;
+                }
+            }
+            ParseError::RegexCompilationError(_)
+            | ParseError::InvalidCaptureError(_)
+            | ParseError::SerializationError(_)
+            | ParseError::ReadFailed { .. } => {}
+        }
+        self
+    }
+
+    /// Give a `LimitExceeded` error raised without a location the 1-based `line`
+    pub(crate) fn at_line(mut self, line: usize) -> Self {
+        if let ParseError::LimitExceeded {
+            span: span @ None, ..
+        } = &mut self
+        {
+            *span = Some(Span { line, column: None });
+        }
+        self
+    }
+
+    /// Error message without the location prefix
+    pub(crate) fn summary(&self) -> String {
+        match self {
+            ParseError::RegexCompilationError(msg) => format!("Regex compilation error: {}", msg),
+            ParseError::InvalidCaptureError(msg) => format!("Invalid capture error: {}", msg),
+            ParseError::SerializationError(msg) => format!("Serialization error: {}", msg),
+            ParseError::InvalidHeadingLevel { level, max, .. } => {
+                format!("invalid heading level {} (max {})", level, max)
             }
-            ParseError::SerializationError(msg) => {
-                write!(f, "Serialization error: {}", msg)
+            ParseError::UnclosedCodeBlock { .. } => "unclosed code block".to_string(),
+            ParseError::MalformedMarkdown { message, .. } => {
+                format!("malformed markdown: {}", message)
             }
-            ParseError::InvalidHeadingLevel { level, span } => {
-                write!(f, "{}: invalid heading level {} (max 6)", span, level)
+            ParseError::Cancelled { .. } => "parsing cancelled".to_string(),
+            ParseError::IncludeFailed { path, message, .. } => {
+                format!("cannot include `{}`: {}", path, message)
             }
-            ParseError::UnclosedCodeBlock { span } => {
-                write!(f, "{}: unclosed code block", span)
+            ParseError::ReadFailed { path, message } => {
+                format!("cannot read `{}`: {}", path, message)
             }
-            ParseError::MalformedMarkdown { message, span } => {
-                write!(f, "{}: malformed markdown: {}", span, message)
+            ParseError::DeniedWarning(warning) => format!(
+                "{} [{}] (denied by configuration)",
+                warning.message, warning.code
+            ),
+            ParseError::LimitExceeded { kind, limit, .. } => {
+                format!("limit exceeded: {}", kind.describe(*limit))
             }
         }
     }
 }
 
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.span() {
+            Some(span) => write!(f, "{}: {}", span, self.summary()),
+            None => f.write_str(&self.summary()),
+        }
+    }
+}
+
 impl Error for ParseError {}
 
+/// How serious a warning is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Informational; the output is unaffected
+    Note,
+    /// The output may not be what the author intended
+    Warning,
+    /// Promoted to an error through `ParserConfig::deny`; fails the parse
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// Stable identifier of a warning class, usable in `ParserConfig::deny`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningCode {
+    /// `**`, `~~`, or `*` without a matching closing delimiter
+    UnclosedEmphasis,
+    /// Mermaid diagram that failed syntax validation
+    InvalidMermaid,
+    /// Suspicious but valid Mermaid syntax
+    MermaidWarning,
+    /// Mermaid CLI validation was requested but `mmdc` is not installed
+    MermaidCliUnavailable,
+    /// Heading deeper than `max_heading_level`, kept as a paragraph (`error_recovery`)
+    InvalidHeadingLevel,
+    /// Code fence without a closing fence, closed at end of input (`error_recovery`)
+    UnclosedCodeBlock,
+    /// `:::` container without a closing `:::`, closed at end of input
+    UnclosedContainer,
+    /// Table row whose cell count differs from the header; padded or truncated
+    TableColumnMismatch,
+    /// `{{name}}` placeholder without a value in `ParserConfig::variables`
+    UndefinedVariable,
+    /// `<!--` without a closing `-->`, closed at end of input
+    UnclosedComment,
+    /// `{{diagram:name}}` reference to a name no Mermaid diagram defines
+    UndefinedDiagram,
+    /// Heading more than one level below the previous heading (`#` followed by `###`)
+    SkippedHeadingLevel,
+    /// Level-1 heading after the first one
+    MultipleTopLevelHeadings,
+    /// Heading with the same text as an earlier one under the same parent heading
+    DuplicateHeading,
+    /// Heading without text
+    EmptyHeading,
+}
+
+impl WarningCode {
+    /// Every warning code, in declaration order
+    pub const ALL: &'static [WarningCode] = &[
+        WarningCode::UnclosedEmphasis,
+        WarningCode::InvalidMermaid,
+        WarningCode::MermaidWarning,
+        WarningCode::MermaidCliUnavailable,
+        WarningCode::InvalidHeadingLevel,
+        WarningCode::UnclosedCodeBlock,
+        WarningCode::UnclosedContainer,
+        WarningCode::TableColumnMismatch,
+        WarningCode::UndefinedVariable,
+        WarningCode::UnclosedComment,
+        WarningCode::UndefinedDiagram,
+        WarningCode::SkippedHeadingLevel,
+        WarningCode::MultipleTopLevelHeadings,
+        WarningCode::DuplicateHeading,
+        WarningCode::EmptyHeading,
+    ];
+
+    /// Kebab-case name used in configuration and reports (`unclosed-emphasis`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WarningCode::UnclosedEmphasis => "unclosed-emphasis",
+            WarningCode::InvalidMermaid => "invalid-mermaid",
+            WarningCode::MermaidWarning => "mermaid-warning",
+            WarningCode::MermaidCliUnavailable => "mermaid-cli-unavailable",
+            WarningCode::InvalidHeadingLevel => "invalid-heading-level",
+            WarningCode::UnclosedCodeBlock => "unclosed-code-block",
+            WarningCode::UnclosedContainer => "unclosed-container",
+            WarningCode::TableColumnMismatch => "table-column-mismatch",
+            WarningCode::UndefinedVariable => "undefined-variable",
+            WarningCode::UnclosedComment => "unclosed-comment",
+            WarningCode::UndefinedDiagram => "undefined-diagram",
+            WarningCode::SkippedHeadingLevel => "skipped-heading-level",
+            WarningCode::MultipleTopLevelHeadings => "multiple-top-level-headings",
+            WarningCode::DuplicateHeading => "duplicate-heading",
+            WarningCode::EmptyHeading => "empty-heading",
+        }
+    }
+
+    /// Look up a code by its kebab-case name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|code| code.as_str() == name)
+    }
+
+    /// Severity used unless the code is denied
+    pub fn default_severity(self) -> Severity {
+        match self {
+            WarningCode::MermaidCliUnavailable => Severity::Note,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for WarningCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Non-fatal issue found while parsing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseWarning {
+    /// Warning class
+    pub code: WarningCode,
+    /// Severity after applying `ParserConfig::deny`
+    pub severity: Severity,
+    /// Where the issue was found
+    pub span: Span,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl ParseWarning {
+    /// Warning with the code's default severity
+    pub(crate) fn new(code: WarningCode, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            severity: code.default_severity(),
+            span,
+            message: message.into(),
+        }
+    }
+
+    /// Format the warning with the offending line of `source`, like
+    /// [`ParseError::display_with_source`]
+    pub fn display_with_source(&self, source: &str) -> String {
+        diagnostics::render(
+            &self.severity.to_string(),
+            &format!("{} [{}]", self.message, self.code),
+            Some(&self.span),
+            source,
+            self.note(),
+        )
+    }
+
+    /// Hint on how to fix the warning, if there is one
+    pub(crate) fn note(&self) -> Option<&'static str> {
+        match self.code {
+            WarningCode::UnclosedEmphasis => Some("add the matching closing delimiter"),
+            WarningCode::MermaidCliUnavailable => {
+                Some("install `mmdc` or disable `use_cli_validation`")
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {} [{}]",
+            self.span, self.severity, self.message, self.code
+        )
+    }
+}
+
 /// Column alignment for tables
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Alignment {
     /// Left alignment
@@ -79,13 +454,120 @@ pub enum Alignment {
     Right,
 }
 
+/// Type of an admonition, matching the GitHub alert types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AdmonitionKind {
+    /// `[!NOTE]`
+    Note,
+    /// `[!TIP]`
+    Tip,
+    /// `[!IMPORTANT]`
+    Important,
+    /// `[!WARNING]`
+    Warning,
+    /// `[!CAUTION]`
+    Caution,
+}
+
+impl AdmonitionKind {
+    /// Look up a kind by name, ignoring case (`note`, `NOTE`, `Note`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "note" => Some(AdmonitionKind::Note),
+            "tip" => Some(AdmonitionKind::Tip),
+            "important" => Some(AdmonitionKind::Important),
+            "warning" => Some(AdmonitionKind::Warning),
+            "caution" => Some(AdmonitionKind::Caution),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, used in CSS classes (`note`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdmonitionKind::Note => "note",
+            AdmonitionKind::Tip => "tip",
+            AdmonitionKind::Important => "important",
+            AdmonitionKind::Warning => "warning",
+            AdmonitionKind::Caution => "caution",
+        }
+    }
+
+    /// Title shown above the admonition body (`Note`)
+    pub fn title(self) -> &'static str {
+        match self {
+            AdmonitionKind::Note => "Note",
+            AdmonitionKind::Tip => "Tip",
+            AdmonitionKind::Important => "Important",
+            AdmonitionKind::Warning => "Warning",
+            AdmonitionKind::Caution => "Caution",
+        }
+    }
+}
+
+/// Attribute list attached to a heading, code block, image, span, or container
+/// (`{#id .class key=val}`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Attributes {
+    /// Element identifier (`#id`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// CSS classes (`.class`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+    /// Arbitrary key/value pairs (`key=val`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pairs: BTreeMap<String, String>,
+}
+
+impl Attributes {
+    /// Returns true when no id, class, or key/value pair is set
+    pub fn is_empty(&self) -> bool {
+        self.id.is_none() && self.classes.is_empty() && self.pairs.is_empty()
+    }
+
+    /// Look up a key/value pair by key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs.get(key).map(String::as_str)
+    }
+}
+
+/// Code block attribute listing the lines to highlight (`highlight=1,3-5`)
+///
+/// A fence ending in a bare line range list (```` ```rust {1,3-5} ````) sets it.
+pub const HIGHLIGHT_ATTRIBUTE: &str = "highlight";
+
+/// Code block class requesting line numbers (```` ```rust {.line-numbers start=10} ````)
+pub const LINE_NUMBERS_CLASS: &str = "line-numbers";
+
+/// Parse line ranges such as `1,3-5` into inclusive 1-based `(first, last)` pairs
+///
+/// Returns `None` unless every comma-separated part is a line number or a range of
+/// line numbers with `first <= last`.
+pub(crate) fn parse_line_ranges(ranges: &str) -> Option<Vec<(usize, usize)>> {
+    ranges
+        .split(',')
+        .map(|part| {
+            let (first, last) = part.split_once('-').unwrap_or((part, part));
+            let first: usize = first.trim().parse().ok()?;
+            let last: usize = last.trim().parse().ok()?;
+            (1..=last).contains(&first).then_some((first, last))
+        })
+        .collect()
+}
+
 /// Represents inline elements within text (bold, italic, links, plain text)
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum Inline {
     /// Plain text content
     #[serde(rename = "text")]
-    Text { content: String },
+    Text {
+        // Text runs are mostly short; `CompactString` keeps up to 24 bytes inline
+        #[schemars(with = "String")]
+        content: CompactString,
+    },
     /// Bold text (**text**)
     #[serde(rename = "bold")]
     Bold { content: Vec<Inline> },
@@ -95,31 +577,86 @@ pub enum Inline {
     /// Strikethrough text (~~text~~)
     #[serde(rename = "strikethrough")]
     Strikethrough { content: Vec<Inline> },
+    /// Highlighted text (`==text==`, `enable_highlight`)
+    #[serde(rename = "highlight")]
+    Highlight { content: Vec<Inline> },
+    /// Spoiler hidden until revealed (`||text||`, `enable_spoilers`)
+    #[serde(rename = "spoiler")]
+    Spoiler { content: Vec<Inline> },
+    /// Hashtag (`#tag`, `enable_hashtags`); `tag` excludes the `#`
+    #[serde(rename = "hashtag")]
+    Hashtag {
+        #[schemars(with = "String")]
+        tag: CompactString,
+    },
+    /// User mention (`@user`, `enable_mentions`); `user` excludes the `@`
+    #[serde(rename = "mention")]
+    Mention {
+        #[schemars(with = "String")]
+        user: CompactString,
+    },
     /// Link [text](url)
     #[serde(rename = "link")]
     Link { text: Vec<Inline>, url: String },
     /// Image ![alt](url)
     #[serde(rename = "image")]
-    Image { alt: String, url: String },
+    Image {
+        alt: String,
+        url: String,
+        /// Trailing attribute list (`![alt](url){#id .class width=200}`, `enable_attributes`)
+        #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+        attributes: Attributes,
+    },
+    /// Bracketed span carrying attributes (`[text]{#id .class}`, `enable_attributes`)
+    #[serde(rename = "span")]
+    Span {
+        content: Vec<Inline>,
+        #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+        attributes: Attributes,
+    },
     /// Inline code (`code`)
     #[serde(rename = "code")]
-    Code { content: String },
+    Code {
+        #[schemars(with = "String")]
+        content: CompactString,
+    },
+    /// HTML comment inside text (`<!-- note -->`)
+    #[serde(rename = "comment")]
+    Comment { content: String },
+    /// Hard line break (`<br>` inside a table cell, or a continued multi-line table row)
+    #[serde(rename = "linebreak")]
+    LineBreak,
+    /// Generic inline directive (`:name[content]{#id .class key=val}`, `enable_directives`)
+    #[serde(rename = "directive")]
+    Directive {
+        /// Directive name
+        name: String,
+        /// Bracketed content
+        content: Vec<Inline>,
+        /// Optional attribute list
+        #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+        attributes: Attributes,
+    },
 }
 
 /// A single item in an unordered list; may contain nested sub-lists.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ListItem {
     /// Inline content of the list item
     pub content: Vec<Inline>,
     /// Nested sub-lists (indentation-based)
     pub children: Vec<ListItem>,
+    /// Block content indented under the item, such as fenced code blocks and tables, rendered
+    /// after its inline content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<Node>,
     /// Task list checkbox state: None for regular items, Some(false) for unchecked, Some(true) for checked
     #[serde(skip_serializing_if = "Option::is_none")]
     pub checked: Option<bool>,
 }
 
 /// Validation status for Mermaid diagrams
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "status", rename_all = "lowercase")]
 pub enum ValidationStatus {
     /// Diagram syntax is valid
@@ -131,7 +668,7 @@ pub enum ValidationStatus {
 }
 
 /// Configuration for Mermaid diagram rendering
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MermaidConfig {
     /// Theme name (default, neutral, dark, forest, base)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -148,12 +685,18 @@ pub struct MermaidConfig {
 }
 
 /// Represents a node in the Markdown Abstract Syntax Tree
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum Node {
     /// A heading with level (1-6) and content
     #[serde(rename = "heading")]
-    Heading { level: u8, content: Vec<Inline> },
+    Heading {
+        level: u8,
+        content: Vec<Inline>,
+        /// Trailing attribute list (`## Title {#id .class key=val}`)
+        #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+        attributes: Attributes,
+    },
     /// A paragraph of text
     #[serde(rename = "paragraph")]
     Paragraph { content: Vec<Inline> },
@@ -165,21 +708,42 @@ pub enum Node {
     OrderedList { items: Vec<ListItem> },
     /// A fenced code block with optional language identifier
     #[serde(rename = "code_block")]
-    CodeBlock { lang: Option<String>, code: String },
+    CodeBlock {
+        lang: Option<String>,
+        code: String,
+        /// Attribute list after the language (```` ```rust {#id .class} ````, `enable_attributes`)
+        #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+        attributes: Attributes,
+    },
     /// A Mermaid diagram (distinct from CodeBlock)
     #[serde(rename = "mermaid_diagram")]
     MermaidDiagram {
         /// The diagram content
         diagram: String,
+        /// Name given in the info string (```` ```mermaid name=arch ````), for `{{diagram:arch}}`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
         /// Diagram-specific configuration (merged from global and inline)
         #[serde(skip_serializing_if = "Option::is_none")]
         config: Option<MermaidConfig>,
         /// Validation status of the diagram
         validation_status: ValidationStatus,
         /// Validation warnings (non-fatal issues)
-        #[serde(skip_serializing_if = "Vec::is_empty")]
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
         warnings: Vec<String>,
     },
+    /// A `{{diagram:name}}` line referring to a named Mermaid diagram
+    ///
+    /// Renderers show a copy of the diagram; see
+    /// [`resolve_diagram_references`](crate::resolve_diagram_references).
+    #[serde(rename = "diagram_ref")]
+    DiagramRef {
+        /// Name of the referenced diagram
+        name: String,
+        /// Source line of the reference
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<LineRange>,
+    },
     /// A markdown table
     #[serde(rename = "table")]
     Table {
@@ -189,6 +753,15 @@ pub enum Node {
         rows: Vec<Vec<Vec<Inline>>>,
         /// Column alignments (None = default/left, Some(Alignment) for explicit alignment)
         alignments: Vec<Option<Alignment>>,
+        /// Caption from a `Table: ...` line following the table
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        caption: Option<Vec<Inline>>,
+        /// Table number, set by [`number_tables`](crate::number_tables)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        number: Option<usize>,
+        /// Source lines of the table, from the header row to the last data row
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<LineRange>,
     },
     /// A blockquote with nesting level and content
     #[serde(rename = "blockquote")]
@@ -197,8 +770,368 @@ pub enum Node {
         level: u8,
         /// Parsed inline content (supports bold, italic, links, etc.)
         content: Vec<Inline>,
+        /// Blocks after the inline content, from the first `>`-prefixed fenced code
+        /// block or table on
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        blocks: Vec<Node>,
     },
-    /// A horizontal rule (thematic break) using `---` or `***`
+    /// A horizontal rule (thematic break) using `---`, `***`, or `___`
     #[serde(rename = "horizontal_rule")]
-    HorizontalRule,
+    HorizontalRule {
+        /// Source line of the rule
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<LineRange>,
+    },
+    /// A GitHub-style alert (`> [!NOTE]`), fenced admonition (`:::note`), or Obsidian
+    /// callout (`> [!tip] Title`, `enable_obsidian`)
+    #[serde(rename = "admonition")]
+    Admonition {
+        /// Alert type
+        kind: AdmonitionKind,
+        /// Title given after a callout marker; renderers show the kind's title when unset
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<Vec<Inline>>,
+        /// Block content of the alert body
+        content: Vec<Node>,
+    },
+    /// A generic fenced div (`::: {.class #id}` ... `:::`, `enable_directives`)
+    ///
+    /// Renderers that know the container's classes can give it special treatment;
+    /// HTML output is a `<div>` carrying the attributes.
+    #[serde(rename = "container")]
+    Container {
+        /// Attributes from the opening fence (`::: name` is shorthand for `{.name}`)
+        #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+        attributes: Attributes,
+        /// Block content
+        children: Vec<Node>,
+    },
+    /// A collapsible block (`:::details Title` ... `:::`), rendered as `<details>`
+    #[serde(rename = "details")]
+    Details {
+        /// Always-visible summary line
+        summary: Vec<Inline>,
+        /// Block content shown when expanded
+        content: Vec<Node>,
+    },
+    /// A heading together with everything up to the next heading of the same or higher level
+    ///
+    /// Only produced by the opt-in section pass (`enable_sections`); see
+    /// [`nest_sections`](crate::nest_sections).
+    #[serde(rename = "section")]
+    Section {
+        /// Level of the heading that opens the section (1-6)
+        level: u8,
+        /// Heading inline content
+        heading: Vec<Inline>,
+        /// Heading attribute list
+        #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+        attributes: Attributes,
+        /// Body nodes followed by nested subsections
+        children: Vec<Node>,
+    },
+    /// A standalone image or Mermaid diagram followed by a `Figure: ...` caption line
+    #[serde(rename = "figure")]
+    Figure {
+        /// The figured block: a paragraph holding only the image, or the diagram
+        content: Vec<Node>,
+        /// Caption text after `Figure:`
+        caption: Vec<Inline>,
+        /// Figure number, set by [`number_figures`](crate::number_figures)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        number: Option<usize>,
+    },
+    /// An HTML comment (`<!-- ... -->`) on lines of its own
+    #[serde(rename = "comment")]
+    Comment {
+        /// Text between the comment markers, trimmed
+        content: String,
+    },
+}
+
+impl Node {
+    /// Node type name, matching the `type` tag used in JSON output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Node::Heading { .. } => "heading",
+            Node::Paragraph { .. } => "paragraph",
+            Node::UnorderedList { .. } => "unordered_list",
+            Node::OrderedList { .. } => "ordered_list",
+            Node::CodeBlock { .. } => "code_block",
+            Node::MermaidDiagram { .. } => "mermaid_diagram",
+            Node::DiagramRef { .. } => "diagram_ref",
+            Node::Table { .. } => "table",
+            Node::Blockquote { .. } => "blockquote",
+            Node::HorizontalRule { .. } => "horizontal_rule",
+            Node::Admonition { .. } => "admonition",
+            Node::Details { .. } => "details",
+            Node::Container { .. } => "container",
+            Node::Section { .. } => "section",
+            Node::Figure { .. } => "figure",
+            Node::Comment { .. } => "comment",
+        }
+    }
+
+    /// Attribute list of headings, code blocks, containers, and sections
+    pub fn attributes(&self) -> Option<&Attributes> {
+        match self {
+            Node::Heading { attributes, .. }
+            | Node::CodeBlock { attributes, .. }
+            | Node::Container { attributes, .. }
+            | Node::Section { attributes, .. } => Some(attributes),
+            _ => None,
+        }
+    }
+
+    /// Block children of admonitions, containers, details blocks, sections, figures, and
+    /// blockquotes
+    ///
+    /// `None` for nodes that cannot contain blocks. Blocks nested in list items are in
+    /// [`ListItem::blocks`].
+    pub fn children(&self) -> Option<&[Node]> {
+        match self {
+            Node::Admonition { content, .. }
+            | Node::Details { content, .. }
+            | Node::Container {
+                children: content, ..
+            }
+            | Node::Section {
+                children: content, ..
+            }
+            | Node::Figure { content, .. }
+            | Node::Blockquote {
+                blocks: content, ..
+            } => Some(content),
+            _ => None,
+        }
+    }
+
+    /// Mutable block children; see [`Node::children`]
+    pub fn children_mut(&mut self) -> Option<&mut Vec<Node>> {
+        match self {
+            Node::Admonition { content, .. }
+            | Node::Details { content, .. }
+            | Node::Container {
+                children: content, ..
+            }
+            | Node::Section {
+                children: content, ..
+            }
+            | Node::Figure { content, .. }
+            | Node::Blockquote {
+                blocks: content, ..
+            } => Some(content),
+            _ => None,
+        }
+    }
+
+    /// Move the recorded source lines of this node and its children down by `lines`
+    pub(crate) fn shift_lines(&mut self, lines: usize) {
+        match self {
+            Node::Table {
+                span: Some(span), ..
+            }
+            | Node::DiagramRef {
+                span: Some(span), ..
+            }
+            | Node::HorizontalRule { span: Some(span) } => {
+                span.start += lines;
+                span.end += lines;
+            }
+            Node::Admonition { content, .. }
+            | Node::Details { content, .. }
+            | Node::Figure { content, .. } => {
+                content
+                    .iter_mut()
+                    .for_each(|child| child.shift_lines(lines));
+            }
+            Node::Container { children, .. }
+            | Node::Section { children, .. }
+            | Node::Blockquote {
+                blocks: children, ..
+            } => {
+                children
+                    .iter_mut()
+                    .for_each(|child| child.shift_lines(lines));
+            }
+            Node::UnorderedList { items } | Node::OrderedList { items } => {
+                shift_item_lines(items, lines);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn shift_item_lines(items: &mut [ListItem], lines: usize) {
+    for item in items {
+        item.blocks
+            .iter_mut()
+            .for_each(|block| block.shift_lines(lines));
+        shift_item_lines(&mut item.children, lines);
+    }
+}
+
+/// Concatenate the plain text of inline elements, dropping all formatting
+pub fn plain_text(inlines: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text { content } | Inline::Code { content } => text.push_str(content),
+            Inline::Bold { content }
+            | Inline::Italic { content }
+            | Inline::Strikethrough { content }
+            | Inline::Highlight { content }
+            | Inline::Spoiler { content } => text.push_str(&plain_text(content)),
+            Inline::Link { text: inner, .. }
+            | Inline::Directive { content: inner, .. }
+            | Inline::Span { content: inner, .. } => text.push_str(&plain_text(inner)),
+            Inline::Image { alt, .. } => text.push_str(alt),
+            Inline::Hashtag { tag } => {
+                text.push('#');
+                text.push_str(tag);
+            }
+            Inline::Mention { user } => {
+                text.push('@');
+                text.push_str(user);
+            }
+            Inline::LineBreak => text.push(' '),
+            Inline::Comment { .. } => {}
+        }
+    }
+    text
+}
+
+/// The image of inline content made of a single image, ignoring surrounding whitespace
+pub(crate) fn standalone_image(content: &[Inline]) -> Option<&Inline> {
+    let mut content = content
+        .iter()
+        .filter(|inline| !matches!(inline, Inline::Text { content } if content.trim().is_empty()));
+    match (content.next(), content.next()) {
+        (Some(image @ Inline::Image { .. }), None) => Some(image),
+        _ => None,
+    }
+}
+
+/// The image of a figure whose content is a paragraph holding only an image
+pub(crate) fn figure_image(content: &[Node]) -> Option<&Inline> {
+    match content {
+        [Node::Paragraph { content }] => standalone_image(content),
+        _ => None,
+    }
+}
+
+/// Call `f` for every inline element in the nodes, recursing into nested inlines,
+/// list items, and table cells (document order, parents before children)
+pub(crate) fn for_each_inline(nodes: &[Node], f: &mut impl FnMut(&Inline)) {
+    for node in nodes {
+        match node {
+            Node::Heading { content, .. } | Node::Paragraph { content } => {
+                visit_inlines(content, f)
+            }
+            Node::Blockquote {
+                content, blocks, ..
+            } => {
+                visit_inlines(content, f);
+                for_each_inline(blocks, f);
+            }
+            Node::UnorderedList { items } | Node::OrderedList { items } => visit_items(items, f),
+            Node::Table { headers, rows, .. } => {
+                for cell in headers.iter().chain(rows.iter().flatten()) {
+                    visit_inlines(cell, f);
+                }
+            }
+            Node::Section {
+                heading, children, ..
+            } => {
+                visit_inlines(heading, f);
+                for_each_inline(children, f);
+            }
+            Node::Admonition { title, content, .. } => {
+                visit_inlines(title.as_deref().unwrap_or_default(), f);
+                for_each_inline(content, f);
+            }
+            Node::Container {
+                children: content, ..
+            } => for_each_inline(content, f),
+            Node::Details { summary, content } => {
+                visit_inlines(summary, f);
+                for_each_inline(content, f);
+            }
+            Node::Figure {
+                content, caption, ..
+            } => {
+                for_each_inline(content, f);
+                visit_inlines(caption, f);
+            }
+            Node::CodeBlock { .. }
+            | Node::MermaidDiagram { .. }
+            | Node::DiagramRef { .. }
+            | Node::HorizontalRule { .. }
+            | Node::Comment { .. } => {}
+        }
+    }
+}
+
+/// Call `f` for every node, recursing into the children of admonitions, details
+/// blocks, containers, sections, figures, blockquotes, and list items (document
+/// order, parents before children)
+pub(crate) fn for_each_node<'a>(nodes: &'a [Node], f: &mut impl FnMut(&'a Node)) {
+    for node in nodes {
+        f(node);
+        match node {
+            Node::UnorderedList { items } | Node::OrderedList { items } => {
+                visit_item_blocks(items, f)
+            }
+            _ => for_each_node(node.children().unwrap_or_default(), f),
+        }
+    }
+}
+
+fn visit_item_blocks<'a>(items: &'a [ListItem], f: &mut impl FnMut(&'a Node)) {
+    for item in items {
+        for_each_node(&item.blocks, f);
+        visit_item_blocks(&item.children, f);
+    }
+}
+
+fn visit_items(items: &[ListItem], f: &mut impl FnMut(&Inline)) {
+    for item in items {
+        visit_inlines(&item.content, f);
+        for_each_inline(&item.blocks, f);
+        visit_items(&item.children, f);
+    }
+}
+
+/// Call `f` for every inline element in `inlines` and their nested inlines
+pub(crate) fn visit_inlines(inlines: &[Inline], f: &mut impl FnMut(&Inline)) {
+    for inline in inlines {
+        f(inline);
+        match inline {
+            Inline::Link { text: content, .. }
+            | Inline::Bold { content }
+            | Inline::Italic { content }
+            | Inline::Strikethrough { content }
+            | Inline::Highlight { content }
+            | Inline::Spoiler { content }
+            | Inline::Span { content, .. }
+            | Inline::Directive { content, .. } => visit_inlines(content, f),
+            Inline::Text { .. }
+            | Inline::Image { .. }
+            | Inline::Code { .. }
+            | Inline::Hashtag { .. }
+            | Inline::Mention { .. }
+            | Inline::LineBreak
+            | Inline::Comment { .. } => {}
+        }
+    }
+}
+
+/// Collect every link and image URL in document order
+pub(crate) fn collect_link_urls(nodes: &[Node]) -> Vec<String> {
+    let mut urls = Vec::new();
+    for_each_inline(nodes, &mut |inline| match inline {
+        Inline::Link { url, .. } | Inline::Image { url, .. } => urls.push(url.clone()),
+        _ => {}
+    });
+    urls
 }
@@ -0,0 +1,23 @@
+//! `md-parser-lsp`: Markdown language server speaking LSP over stdin and stdout.
+
+use md_parser::{run_language_server, Config};
+use std::io;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    // Stdout carries the protocol, so problems go to stderr
+    let config = match Config::layered() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to load configuration: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match run_language_server(config.parser, io::stdin().lock(), io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
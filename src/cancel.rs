@@ -0,0 +1,31 @@
+//! Cooperative cancellation for long-running parses.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag used to abort an in-progress parse from another thread
+///
+/// Clones share the same flag, so a host (e.g. an editor) can keep one clone and
+/// hand another to [`Parser::parse_with_cancel`](crate::Parser::parse_with_cancel).
+/// The parser checks the token between blocks and before invoking the Mermaid CLI.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a new, non-cancelled token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of every parse observing this token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true once `cancel()` has been called on any clone of this token
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,285 @@
+//! Keep a Changelog structure (<https://keepachangelog.com>) read from an AST.
+
+use crate::ast::{plain_text, Inline, ListItem, Node};
+use crate::outline::{Outline, OutlineSection};
+use serde::{Deserialize, Serialize};
+
+/// Kind of change, from the heading of a category (`### Added`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeCategory {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+    /// Any other heading, as written
+    Other(String),
+}
+
+impl ChangeCategory {
+    /// Category named by a heading, ignoring case (`### fixed` is [`ChangeCategory::Fixed`])
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "added" => ChangeCategory::Added,
+            "changed" => ChangeCategory::Changed,
+            "deprecated" => ChangeCategory::Deprecated,
+            "removed" => ChangeCategory::Removed,
+            "fixed" => ChangeCategory::Fixed,
+            "security" => ChangeCategory::Security,
+            _ => ChangeCategory::Other(name.trim().to_string()),
+        }
+    }
+}
+
+/// One item of a category's list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    /// Item text without formatting
+    pub text: String,
+    /// Item inline content
+    pub content: Vec<Inline>,
+    /// Nested items
+    pub children: Vec<ListItem>,
+}
+
+/// The changes of one kind in a version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogCategory {
+    pub category: ChangeCategory,
+    /// Top-level items of the lists under the category heading
+    pub entries: Vec<ChangelogEntry>,
+}
+
+/// A version section (`## [1.2.0] - 2024-03-01`, `## [Unreleased]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogVersion {
+    /// Version as written without brackets (`1.2.0`, `v2.0.0-rc.1`), or `Unreleased`
+    pub version: String,
+    /// Release date after the version (`2024-03-01`)
+    pub date: Option<String>,
+    /// Link of the version: the heading's own link, or a `[1.2.0]: url` definition
+    pub url: Option<String>,
+    /// Whether the heading is marked `[YANKED]`
+    pub yanked: bool,
+    /// Heading anchor
+    pub anchor: String,
+    /// Nodes between the version heading and its first category
+    pub notes: Vec<Node>,
+    /// Categories in document order
+    pub categories: Vec<ChangelogCategory>,
+}
+
+impl ChangelogVersion {
+    /// Whether this is the `Unreleased` section
+    pub fn is_unreleased(&self) -> bool {
+        self.version.eq_ignore_ascii_case("unreleased")
+    }
+
+    /// Entries of one category, from every heading of that category in the version
+    pub fn entries(&self, category: &ChangeCategory) -> Vec<&ChangelogEntry> {
+        self.categories
+            .iter()
+            .filter(|c| &c.category == category)
+            .flat_map(|c| &c.entries)
+            .collect()
+    }
+}
+
+/// A changelog as a list of versions, newest first as written
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Changelog {
+    /// Version sections in document order
+    pub versions: Vec<ChangelogVersion>,
+}
+
+impl Changelog {
+    /// Find the version sections of a parsed changelog
+    ///
+    /// A version section is a heading at any level whose text starts with a version
+    /// number (`1.0.0`, `[v1.0.0]`) or `Unreleased`, optionally followed by ` - date`
+    /// and `[YANKED]`. Its subsections are categories; headings above the versions
+    /// (`# Changelog`) are looked through. Versions without a link in the heading get
+    /// the URL of a matching `[version]: url` line anywhere in the document.
+    pub fn from_nodes(nodes: &[Node]) -> Self {
+        let outline = Outline::from_nodes(nodes);
+        let mut changelog = Changelog::default();
+        for section in &outline.sections {
+            collect_versions(section, &mut changelog.versions);
+        }
+
+        let definitions = link_definitions(nodes);
+        for version in &mut changelog.versions {
+            if version.url.is_none() {
+                version.url = definitions
+                    .iter()
+                    .find(|(label, _)| label.eq_ignore_ascii_case(&version.version))
+                    .map(|(_, url)| url.clone());
+            }
+        }
+        changelog
+    }
+
+    /// All version sections, in document order
+    pub fn versions(&self) -> &[ChangelogVersion] {
+        &self.versions
+    }
+
+    /// The `Unreleased` section, if there is one
+    pub fn unreleased(&self) -> Option<&ChangelogVersion> {
+        self.versions.iter().find(|v| v.is_unreleased())
+    }
+
+    /// The released versions, in document order
+    pub fn released(&self) -> impl Iterator<Item = &ChangelogVersion> {
+        self.versions.iter().filter(|v| !v.is_unreleased())
+    }
+
+    /// The section of `version`, accepting a `v` prefix on either side
+    pub fn version(&self, version: &str) -> Option<&ChangelogVersion> {
+        let wanted = version.trim_start_matches('v');
+        self.versions
+            .iter()
+            .find(|v| v.version.trim_start_matches('v') == wanted)
+    }
+
+    /// The newest released version, taken to be the first one written
+    pub fn latest(&self) -> Option<&ChangelogVersion> {
+        self.released().next()
+    }
+
+    /// Entries of one category across all versions, with their version
+    pub fn entries<'a>(
+        &'a self,
+        category: &'a ChangeCategory,
+    ) -> impl Iterator<Item = (&'a ChangelogVersion, &'a ChangelogEntry)> {
+        self.versions.iter().flat_map(move |version| {
+            version
+                .entries(category)
+                .into_iter()
+                .map(move |entry| (version, entry))
+        })
+    }
+}
+
+/// Add the version sections in and below `section` to `versions`
+fn collect_versions(section: &OutlineSection, versions: &mut Vec<ChangelogVersion>) {
+    match version_heading(&section.heading) {
+        Some(heading) => versions.push(changelog_version(section, heading)),
+        None => {
+            for child in &section.children {
+                collect_versions(child, versions);
+            }
+        }
+    }
+}
+
+/// Parts of a version heading
+struct VersionHeading {
+    version: String,
+    date: Option<String>,
+    url: Option<String>,
+    yanked: bool,
+}
+
+/// Read a version heading: `[1.0.0] - 2017-06-20`, `1.0.0 (2017-06-20)`,
+/// `[Unreleased]`, or a version linked inline, `[1.0.0](url) - 2017-06-20`
+fn version_heading(heading: &[Inline]) -> Option<VersionHeading> {
+    let url = match heading.first() {
+        Some(Inline::Link { url, .. }) => Some(url.clone()),
+        _ => None,
+    };
+    let text = plain_text(heading);
+    let mut text = text.trim();
+    let yanked = text.to_ascii_uppercase().ends_with("[YANKED]");
+    if yanked {
+        text = text[..text.len() - "[YANKED]".len()].trim_end();
+    }
+
+    let (version, rest) = match text.strip_prefix('[') {
+        Some(bracketed) => bracketed.split_once(']')?,
+        None => text.split_at(text.find(char::is_whitespace).unwrap_or(text.len())),
+    };
+    let version = version.trim();
+    let numbered = version
+        .strip_prefix(['v', 'V'])
+        .unwrap_or(version)
+        .starts_with(|c: char| c.is_ascii_digit());
+    if !numbered && !version.eq_ignore_ascii_case("unreleased") {
+        return None;
+    }
+
+    let date = rest
+        .trim()
+        .trim_start_matches(['-', '–', '—'])
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim();
+    Some(VersionHeading {
+        version: version.to_string(),
+        date: (!date.is_empty()).then(|| date.to_string()),
+        url,
+        yanked,
+    })
+}
+
+fn changelog_version(section: &OutlineSection, heading: VersionHeading) -> ChangelogVersion {
+    ChangelogVersion {
+        version: heading.version,
+        date: heading.date,
+        url: heading.url,
+        yanked: heading.yanked,
+        anchor: section.anchor.clone(),
+        notes: section.body.clone(),
+        categories: section
+            .children
+            .iter()
+            .map(|child| ChangelogCategory {
+                category: ChangeCategory::from_name(&child.title),
+                entries: list_entries(&child.body),
+            })
+            .collect(),
+    }
+}
+
+/// Top-level items of the lists in `nodes`
+fn list_entries(nodes: &[Node]) -> Vec<ChangelogEntry> {
+    nodes
+        .iter()
+        .filter_map(|node| match node {
+            Node::UnorderedList { items } | Node::OrderedList { items } => Some(items),
+            _ => None,
+        })
+        .flatten()
+        .map(|item| ChangelogEntry {
+            text: plain_text(&item.content),
+            content: item.content.clone(),
+            children: item.children.clone(),
+        })
+        .collect()
+}
+
+/// Labels and URLs of `[label]: url` definitions in top-level paragraphs, which hold
+/// the comparison links at the end of a changelog
+///
+/// The lines of a paragraph are joined with spaces, so a definition is a `[label]:` word
+/// followed by the URL.
+fn link_definitions(nodes: &[Node]) -> Vec<(String, String)> {
+    let mut definitions = Vec::new();
+    for node in nodes {
+        let Node::Paragraph { content } = node else {
+            continue;
+        };
+        let text = plain_text(content);
+        let mut words = text.split_whitespace();
+        while let Some(word) = words.next() {
+            let label = word.strip_prefix('[').and_then(|w| w.strip_suffix("]:"));
+            if let (Some(label), Some(url)) = (label, words.next()) {
+                definitions.push((label.to_string(), url.to_string()));
+            }
+        }
+    }
+    definitions
+}
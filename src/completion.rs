@@ -0,0 +1,170 @@
+//! Completion candidates for editors: link targets, reference labels, and image paths.
+
+use crate::ast::{for_each_inline, Inline, LineRange};
+use crate::definitions::{DefinitionKind, DefinitionMap};
+use crate::parse_result::ParseResult;
+use crate::project::{heading_targets, Project};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+/// What a [`CompletionItem`] completes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionKind {
+    /// A heading anchor, for `[text](#anchor)` or `[text](doc.md#anchor)`
+    Heading,
+    /// Another document of a project, for `[text](doc.md)`
+    Document,
+    /// The label of a `[^label]: text` footnote definition, for `[^label]`
+    Footnote,
+    /// The label of a `[label]: url` link reference definition, for `[text][label]`
+    LinkReference,
+    /// The path of an image already used in the document, for `![alt](path)`
+    Image,
+}
+
+/// One completion candidate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionItem {
+    pub kind: CompletionKind,
+    /// Text to insert: `#anchor`, `guide/setup.md#install`, a label, or a path
+    pub label: String,
+    /// Text to show beside it: the heading text, definition target, or alt text
+    pub detail: String,
+    /// Source lines where the entity is defined (for nested headings and images, those of
+    /// the enclosing top-level block); `None` for items from other documents
+    pub span: Option<LineRange>,
+}
+
+/// Completion candidates defined in one document, in document order: heading anchors,
+/// footnote and link reference labels, and image paths (each path once)
+///
+/// Definitions come from the result's [`DefinitionMap`], so those in fenced code blocks
+/// are skipped.
+pub fn completion_items(result: &ParseResult) -> Vec<CompletionItem> {
+    let span = |top: usize| result.spans.get(top).copied();
+    let mut items: Vec<CompletionItem> = heading_targets(&result.nodes)
+        .into_iter()
+        .map(|heading| CompletionItem {
+            kind: CompletionKind::Heading,
+            label: format!("#{}", heading.anchor),
+            detail: heading.title,
+            span: span(heading.top),
+        })
+        .collect();
+
+    items.extend(definitions(&result.definitions));
+
+    let mut seen = HashSet::new();
+    for (top, node) in result.nodes.iter().enumerate() {
+        for_each_inline(std::slice::from_ref(node), &mut |inline| {
+            if let Inline::Image { url, alt, .. } = inline {
+                if seen.insert(url.clone()) {
+                    items.push(CompletionItem {
+                        kind: CompletionKind::Image,
+                        label: url.clone(),
+                        detail: alt.clone(),
+                        span: span(top),
+                    });
+                }
+            }
+        });
+    }
+    items
+}
+
+/// Footnote and link reference definitions in the document of `map`
+fn definitions(map: &DefinitionMap) -> impl Iterator<Item = CompletionItem> + '_ {
+    map.definitions
+        .iter()
+        .cloned()
+        .map(|definition| CompletionItem {
+            kind: match definition.kind {
+                DefinitionKind::Footnote => CompletionKind::Footnote,
+                DefinitionKind::LinkReference => CompletionKind::LinkReference,
+            },
+            label: definition.label,
+            detail: definition.target,
+            span: definition.span,
+        })
+}
+
+impl Project {
+    /// Link targets for a document of the project: its own headings as `#anchor`, and
+    /// every other document and its headings by paths relative to `from`
+    /// (`../guide/setup.md#install`)
+    ///
+    /// `from` is relative to the project root, like [`ProjectDocument::path`](crate::ProjectDocument::path).
+    pub fn link_completions(&self, from: &Path) -> Vec<CompletionItem> {
+        let from_dir = from.parent().unwrap_or(Path::new(""));
+        let mut items = Vec::new();
+        for doc in self.documents() {
+            let own = doc.path == from;
+            let path = relative_path(from_dir, &doc.path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            if !own {
+                items.push(CompletionItem {
+                    kind: CompletionKind::Document,
+                    label: path.clone(),
+                    detail: String::new(),
+                    span: None,
+                });
+            }
+            for heading in heading_targets(&doc.nodes) {
+                items.push(CompletionItem {
+                    kind: CompletionKind::Heading,
+                    label: if own {
+                        format!("#{}", heading.anchor)
+                    } else {
+                        format!("{}#{}", path, heading.anchor)
+                    },
+                    detail: heading.title,
+                    span: None,
+                });
+            }
+        }
+        items
+    }
+}
+
+/// `target` relative to directory `dir`, both relative to the same root
+fn relative_path(dir: &Path, target: &Path) -> PathBuf {
+    let dir: Vec<Component> = dir.components().collect();
+    let target_parts: Vec<Component> = target.components().collect();
+    let common = dir
+        .iter()
+        .zip(&target_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut path = PathBuf::new();
+    for _ in common..dir.len() {
+        path.push("..");
+    }
+    for part in &target_parts[common..] {
+        path.push(part);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(Path::new("guide"), Path::new("api/index.md")),
+            PathBuf::from("../api/index.md")
+        );
+        assert_eq!(
+            relative_path(Path::new(""), Path::new("guide/setup.md")),
+            PathBuf::from("guide/setup.md")
+        );
+        assert_eq!(
+            relative_path(Path::new("guide"), Path::new("guide/setup.md")),
+            PathBuf::from("setup.md")
+        );
+    }
+}
@@ -1,11 +1,20 @@
 //! Configuration management for the Markdown parser.
 
+use crate::ast::{AdmonitionKind, Inline, Node, WarningCode};
+use crate::doctest::DocTestConfig;
+use crate::format::FormatConfig;
+use crate::lint::LintConfig;
+use crate::notebook::NotebookConfig;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Configuration for Mermaid diagram parser settings
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct MermaidParserConfig {
     /// Default theme (default, neutral, dark, forest, base)
     #[serde(default = "default_mermaid_theme")]
@@ -22,6 +31,9 @@ pub struct MermaidParserConfig {
     /// Use Mermaid CLI for validation if available (optional)
     #[serde(default = "default_false")]
     pub use_cli_validation: bool,
+    /// Seconds to wait for one Mermaid CLI validation before giving up on it
+    #[serde(default = "default_cli_timeout_secs")]
+    pub cli_timeout_secs: u64,
 }
 
 fn default_mermaid_theme() -> String {
@@ -36,6 +48,26 @@ fn default_mermaid_font_family() -> String {
     "trebuchet ms, verdana, arial".to_string()
 }
 
+fn default_cli_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_nesting_depth() -> usize {
+    128
+}
+
+fn default_max_include_depth() -> usize {
+    8
+}
+
+fn default_list_indent() -> usize {
+    2
+}
+
+fn default_tab_width() -> usize {
+    4
+}
+
 fn default_true() -> bool {
     true
 }
@@ -52,15 +84,68 @@ impl Default for MermaidParserConfig {
             default_font_family: default_mermaid_font_family(),
             validate_syntax: true,
             use_cli_validation: false,
+            cli_timeout_secs: default_cli_timeout_secs(),
+        }
+    }
+}
+
+/// Heading structure checks, each reported with its own warning code
+///
+/// All are off by default. A check's code can be listed in `ParserConfig::deny` to fail
+/// the parse instead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct HeadingChecks {
+    /// Heading more than one level below the previous one (`skipped-heading-level`)
+    #[serde(default = "default_false")]
+    pub skipped_levels: bool,
+    /// More than one level-1 heading (`multiple-top-level-headings`)
+    #[serde(default = "default_false")]
+    pub multiple_top_level: bool,
+    /// Headings with the same text under the same parent heading (`duplicate-heading`)
+    #[serde(default = "default_false")]
+    pub duplicates: bool,
+    /// Headings without text (`empty-heading`)
+    #[serde(default = "default_false")]
+    pub empty: bool,
+}
+
+/// Limits that make a parse of untrusted input fail with `ParseError::LimitExceeded`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ParseLimits {
+    /// Largest input accepted, in bytes, checked before and after includes are expanded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_input_bytes: Option<usize>,
+    /// Deepest nesting of inline formatting, list items, blockquotes (`>>>`), and
+    /// container blocks, each counted on its own
+    #[serde(default = "default_max_nesting_depth")]
+    pub max_nesting_depth: usize,
+    /// Most block nodes and inline elements in the AST
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_nodes: Option<usize>,
+    /// Longest a parse may run, in milliseconds; checked between lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_input_bytes: None,
+            max_nesting_depth: default_max_nesting_depth(),
+            max_nodes: None,
+            timeout_ms: None,
         }
     }
 }
 
 /// Configuration for the parser settings
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct ParserConfig {
     /// Maximum heading level supported (1-6)
     pub max_heading_level: u8,
+    /// Require whitespace after a heading's leading `#`s, so `#hashtag` is a paragraph
+    #[serde(default = "default_false")]
+    pub strict_atx: bool,
     /// Length of code block fence (typically 3 for ```)
     pub code_fence_length: usize,
     /// Pattern for code block fence (typically "```")
@@ -70,22 +155,529 @@ pub struct ParserConfig {
     /// Mermaid diagram configuration
     #[serde(default)]
     pub mermaid: MermaidParserConfig,
+    /// Spaces of indentation per list nesting level (2, 3, or 4)
+    #[serde(default = "default_list_indent")]
+    pub list_indent: usize,
+    /// Column width of a tab in leading indentation; a tab advances to the next multiple
+    #[serde(default = "default_tab_width")]
+    pub tab_width: usize,
+    /// Let every list line interrupt a paragraph and end a list at unindented text, as
+    /// before CommonMark's rules were followed
+    ///
+    /// By default an empty item or an ordered item not numbered 1 continues a paragraph,
+    /// and unindented text after a list item continues the item (a lazy continuation).
+    #[serde(default = "default_false")]
+    pub legacy_list_interrupts: bool,
+    /// Convert `\r\n` and lone `\r` line endings to `\n` before parsing
+    ///
+    /// A leading byte order mark is always dropped. Without normalization, `\r\n` still
+    /// ends a line but a lone `\r` is kept as text.
+    #[serde(default = "default_true")]
+    pub normalize_line_endings: bool,
+    /// Enable attribute lists (`{#id .class key=val}`) on headings, code blocks, and images,
+    /// and bracketed spans (`[text]{.class}`)
+    #[serde(default = "default_false")]
+    pub enable_attributes: bool,
+    /// Nest nodes under `Node::Section` by heading level instead of returning a flat list
+    #[serde(default = "default_false")]
+    pub enable_sections: bool,
+    /// Parse GitHub alerts (`> [!NOTE]`) and `:::note` fences as `Node::Admonition`, and
+    /// `:::details Title` fences as `Node::Details`
+    #[serde(default = "default_true")]
+    pub enable_admonitions: bool,
+    /// Parse generic fenced divs (`::: {.class}` ... `:::`) as `Node::Container` and inline
+    /// directives (`:name[content]{attrs}`) as `Inline::Directive`
+    #[serde(default = "default_false")]
+    pub enable_directives: bool,
+    /// Pair emphasis by GFM's left- and right-flanking rules, and recognize `_text_`,
+    /// `__text__`, and `~text~`
+    ///
+    /// A run next to punctuation only opens or closes from the outer side, so `x*(y+z)*2`
+    /// stays text, and `_` never opens or closes inside a word (`snake_case_names`).
+    #[serde(default = "default_false")]
+    pub gfm_emphasis: bool,
+    /// Parse `==text==` as `Inline::Highlight`
+    #[serde(default = "default_false")]
+    pub enable_highlight: bool,
+    /// Parse `||text||` as `Inline::Spoiler`
+    ///
+    /// Table rows split cells at every `|` first, so spoilers do not work inside tables.
+    #[serde(default = "default_false")]
+    pub enable_spoilers: bool,
+    /// Parse `#tag` as `Inline::Hashtag`
+    ///
+    /// Implies `strict_atx`, so a line starting with `#tag` is a paragraph, not a heading.
+    #[serde(default = "default_false")]
+    pub enable_hashtags: bool,
+    /// Parse `@user` as `Inline::Mention`
+    #[serde(default = "default_false")]
+    pub enable_mentions: bool,
+    /// Read Obsidian syntax: callouts (`> [!tip] Title`, foldable with `+` or `-`),
+    /// `![[embeds]]`, and `%%comments%%`
+    ///
+    /// Embedded images become images and other embeds become links; with
+    /// `enable_includes`, a note embedded on a line of its own (`![[Note]]` or
+    /// `![[Note#Heading]]`) is transcluded instead.
+    #[serde(default = "default_false")]
+    pub enable_obsidian: bool,
+    /// Let a table row ending in `\` after its closing pipe continue on the next line; the
+    /// continuation's cells are appended to the row's cells after a line break
+    #[serde(default = "default_false")]
+    pub enable_multiline_tables: bool,
+    /// Replace lines of the form `{{csv:path/to/file.csv}}` with a `Node::Table` built from
+    /// the CSV file; its first record is the header row
+    #[serde(default = "default_false")]
+    pub enable_csv_tables: bool,
+    /// Replace lines of the form `{{include: other.md}}` with the contents of that file
+    /// before parsing
+    ///
+    /// Included files may include further files, relative to their own directory. Every
+    /// included file must lie inside `base_dir`; cycles and nesting deeper than
+    /// `max_include_depth` fail the parse. Line numbers in spans refer to the expanded text.
+    #[serde(default = "default_false")]
+    pub enable_includes: bool,
+    /// Maximum nesting of `{{include: ...}}` directives
+    #[serde(default = "default_max_include_depth")]
+    pub max_include_depth: usize,
+    /// Directory that referenced files (`{{csv:...}}`, `{{include:...}}`) are resolved
+    /// against, and that included files must stay inside; the current directory when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_dir: Option<String>,
+    /// Directory storing parse results by a hash of the input and of this configuration,
+    /// so unchanged documents are not parsed again (None disables the cache)
+    ///
+    /// Entries written by another release of the parser are ignored. Documents parsed
+    /// with `enable_includes`, `enable_csv_tables`, `mermaid.use_cli_validation`, or
+    /// custom block syntaxes are not cached, since their result depends on more than
+    /// their own text.
+    #[serde(default)]
+    pub cache_dir: Option<String>,
+    /// Warning codes promoted to errors (`deny = ["unclosed-emphasis"]`)
+    ///
+    /// A denied warning makes `Parser::parse` fail with `ParseError::DeniedWarning`.
+    #[serde(default)]
+    pub deny: Vec<WarningCode>,
+    /// Recover from malformed input instead of failing the parse
+    ///
+    /// Headings deeper than `max_heading_level` become paragraphs and unclosed code
+    /// fences are closed at end of input; each recovery is reported as a warning.
+    #[serde(default = "default_false")]
+    pub error_recovery: bool,
+    /// Replace `{{name}}` placeholders with values from `variables` before parsing
+    ///
+    /// Runs after includes are expanded. `\{{name}}` keeps a literal `{{name}}`; unknown
+    /// names are left as written with an `undefined-variable` warning.
+    #[serde(default = "default_false")]
+    pub enable_variables: bool,
+    /// Values for `{{name}}` placeholders (`[parser.variables]` in the config file)
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    /// Size, nesting, node count, and time limits (`[parser.limits]` in the config file)
+    #[serde(default)]
+    pub limits: ParseLimits,
+    /// Heading structure problems reported as warnings (`[parser.heading_checks]` in the
+    /// config file)
+    #[serde(default)]
+    pub heading_checks: HeadingChecks,
 }
 
 impl Default for ParserConfig {
     fn default() -> Self {
         Self {
             max_heading_level: 6,
+            strict_atx: false,
             code_fence_length: 3,
             code_fence_pattern: "```".to_string(),
             mermaid_language: "mermaid".to_string(),
             mermaid: MermaidParserConfig::default(),
+            list_indent: default_list_indent(),
+            tab_width: default_tab_width(),
+            legacy_list_interrupts: false,
+            normalize_line_endings: true,
+            enable_attributes: false,
+            enable_sections: false,
+            enable_admonitions: true,
+            enable_directives: false,
+            gfm_emphasis: false,
+            enable_highlight: false,
+            enable_spoilers: false,
+            enable_hashtags: false,
+            enable_mentions: false,
+            enable_obsidian: false,
+            enable_multiline_tables: false,
+            enable_csv_tables: false,
+            enable_includes: false,
+            max_include_depth: default_max_include_depth(),
+            base_dir: None,
+            cache_dir: None,
+            deny: Vec::new(),
+            error_recovery: false,
+            enable_variables: false,
+            variables: BTreeMap::new(),
+            limits: ParseLimits::default(),
+            heading_checks: HeadingChecks::default(),
+        }
+    }
+}
+
+impl ParserConfig {
+    /// Copy of the config that resolves referenced files next to `document`
+    ///
+    /// An explicit `base_dir` is kept.
+    pub fn for_document(&self, document: &Path) -> ParserConfig {
+        let mut config = self.clone();
+        if config.base_dir.is_none() {
+            config.base_dir = document
+                .parent()
+                .map(|dir| dir.to_string_lossy().into_owned());
         }
+        config
     }
 }
 
+/// Callback used by the renderer to rewrite link and image URLs
+///
+/// Cloning shares the underlying closure. Two rewriters compare equal only when they
+/// wrap the same closure instance.
+#[derive(Clone)]
+pub struct UrlRewriter(Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl UrlRewriter {
+    /// Wrap a closure, e.g. `UrlRewriter::new(|url| url.replace(".md", ".html"))`
+    pub fn new(rewrite: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self(Arc::new(rewrite))
+    }
+
+    /// Apply the rewrite to a URL
+    pub fn rewrite(&self, url: &str) -> String {
+        (self.0)(url)
+    }
+}
+
+impl fmt::Debug for UrlRewriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("UrlRewriter(..)")
+    }
+}
+
+impl PartialEq for UrlRewriter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Custom HTML for chosen node and inline types, consulted before the default rendering
+///
+/// Each method returns `None` to keep the default HTML. A closure taking a node and the
+/// renderer config is a `NodeRenderer` that only overrides blocks:
+///
+/// ```
+/// use md_parser::{CustomRenderer, Node, RendererConfig};
+///
+/// let charts = CustomRenderer::new(|node: &Node, _: &RendererConfig| match node {
+///     Node::CodeBlock { lang: Some(lang), code, .. } if lang == "chart" => {
+///         Some(format!("<div class=\"chart\" data-spec=\"{}\"></div>", code.len()))
+///     }
+///     _ => None,
+/// });
+/// let config = RendererConfig {
+///     custom_renderer: Some(charts),
+///     ..RendererConfig::default()
+/// };
+/// ```
+pub trait NodeRenderer: Send + Sync {
+    /// HTML for a block node, or `None` for the default
+    ///
+    /// Called for nodes at every depth, including the children of admonitions,
+    /// containers, and sections, after render-time transforms have run.
+    fn render_node(&self, node: &Node, config: &RendererConfig) -> Option<String> {
+        let _ = (node, config);
+        None
+    }
+
+    /// HTML for an inline element, or `None` for the default
+    fn render_inline(&self, inline: &Inline, config: &RendererConfig) -> Option<String> {
+        let _ = (inline, config);
+        None
+    }
+}
+
+impl<F> NodeRenderer for F
+where
+    F: Fn(&Node, &RendererConfig) -> Option<String> + Send + Sync,
+{
+    fn render_node(&self, node: &Node, config: &RendererConfig) -> Option<String> {
+        self(node, config)
+    }
+}
+
+/// A [`NodeRenderer`] set on a [`RendererConfig`]
+///
+/// Cloning shares the underlying renderer. Two custom renderers compare equal only
+/// when they wrap the same instance.
+#[derive(Clone)]
+pub struct CustomRenderer(Arc<dyn NodeRenderer>);
+
+impl CustomRenderer {
+    /// Wrap a renderer or a closure
+    pub fn new(renderer: impl NodeRenderer + 'static) -> Self {
+        Self(Arc::new(renderer))
+    }
+
+    /// HTML for a block node from the wrapped renderer, if it overrides it
+    pub fn render_node(&self, node: &Node, config: &RendererConfig) -> Option<String> {
+        self.0.render_node(node, config)
+    }
+
+    /// HTML for an inline element from the wrapped renderer, if it overrides it
+    pub fn render_inline(&self, inline: &Inline, config: &RendererConfig) -> Option<String> {
+        self.0.render_inline(inline, config)
+    }
+}
+
+impl fmt::Debug for CustomRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CustomRenderer(..)")
+    }
+}
+
+impl PartialEq for CustomRenderer {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// How the HTML renderer treats `<!-- ... -->` comments
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentMode {
+    /// Leave comments out of the output
+    #[default]
+    Strip,
+    /// Emit them as HTML comments, invisible in the browser
+    Keep,
+    /// Show them on the page (`<span class="comment">`), e.g. for review drafts
+    Visible,
+}
+
+/// Line ending written by the renderers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`, for tools on Windows that expect it
+    Crlf,
+}
+
+impl LineEnding {
+    /// `text` with every line ending, including those of templates, set to this one
+    pub fn apply(self, text: String) -> String {
+        match self {
+            LineEnding::Lf if !text.contains('\r') => text,
+            LineEnding::Lf => text.replace("\r\n", "\n"),
+            LineEnding::Crlf => text.replace("\r\n", "\n").replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// Whitespace and indentation of generated HTML
+///
+/// Written `output_style = "minified"` or `output_style = { pretty = { indent = 2 } }` in
+/// the config file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStyle {
+    /// One node per line and the templates as written
+    #[default]
+    Compact,
+    /// No whitespace between block-level elements and runs of whitespace collapsed
+    Minified,
+    /// Every block-level element on its own line, nested elements indented by `indent` spaces
+    Pretty {
+        #[serde(default = "default_indent")]
+        indent: usize,
+    },
+}
+
+fn default_indent() -> usize {
+    2
+}
+
+/// Copy-to-clipboard buttons on HTML code blocks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyButton {
+    /// No buttons, for output without JavaScript
+    #[default]
+    Off,
+    /// Buttons, with the script and styles that run them added to pages with code blocks
+    Inline,
+    /// Buttons only (`<button class="copy-button">` in a `<div class="code-block">`), for
+    /// sites that bring their own script
+    Markup,
+}
+
+/// Text the renderers insert into documents (`[renderer.strings]` in the config file)
+///
+/// Set these to publish in a language other than English.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct RendererStrings {
+    /// Title of `[!NOTE]` admonitions
+    #[serde(default = "default_note")]
+    pub note: String,
+    /// Title of `[!TIP]` admonitions
+    #[serde(default = "default_tip")]
+    pub tip: String,
+    /// Title of `[!IMPORTANT]` admonitions
+    #[serde(default = "default_important")]
+    pub important: String,
+    /// Title of `[!WARNING]` admonitions
+    #[serde(default = "default_warning")]
+    pub warning: String,
+    /// Title of `[!CAUTION]` admonitions
+    #[serde(default = "default_caution")]
+    pub caution: String,
+    /// Label before numbered figure captions, with `{number}` replaced by the number
+    #[serde(default = "default_figure")]
+    pub figure: String,
+    /// Label before numbered table captions, with `{number}` replaced by the number
+    #[serde(default = "default_table")]
+    pub table: String,
+    /// Text of the skip link added in accessible mode
+    #[serde(default = "default_skip_link")]
+    pub skip_link: String,
+    /// Label of code block copy buttons
+    #[serde(default = "default_copy")]
+    pub copy: String,
+    /// Label a copy button shows briefly after copying
+    #[serde(default = "default_copied")]
+    pub copied: String,
+}
+
+impl RendererStrings {
+    /// Title shown above an admonition of `kind`
+    pub fn admonition_title(&self, kind: AdmonitionKind) -> &str {
+        match kind {
+            AdmonitionKind::Note => &self.note,
+            AdmonitionKind::Tip => &self.tip,
+            AdmonitionKind::Important => &self.important,
+            AdmonitionKind::Warning => &self.warning,
+            AdmonitionKind::Caution => &self.caution,
+        }
+    }
+
+    /// Label of figure `number` (`Figure 3:`)
+    pub fn figure_label(&self, number: usize) -> String {
+        self.figure.replace("{number}", &number.to_string())
+    }
+
+    /// Label of table `number` (`Table 3:`)
+    pub fn table_label(&self, number: usize) -> String {
+        self.table.replace("{number}", &number.to_string())
+    }
+}
+
+fn default_note() -> String {
+    AdmonitionKind::Note.title().to_string()
+}
+
+fn default_tip() -> String {
+    AdmonitionKind::Tip.title().to_string()
+}
+
+fn default_important() -> String {
+    AdmonitionKind::Important.title().to_string()
+}
+
+fn default_warning() -> String {
+    AdmonitionKind::Warning.title().to_string()
+}
+
+fn default_caution() -> String {
+    AdmonitionKind::Caution.title().to_string()
+}
+
+fn default_figure() -> String {
+    "Figure {number}:".to_string()
+}
+
+fn default_table() -> String {
+    "Table {number}:".to_string()
+}
+
+fn default_skip_link() -> String {
+    "Skip to main content".to_string()
+}
+
+fn default_copy() -> String {
+    "Copy".to_string()
+}
+
+fn default_copied() -> String {
+    "Copied".to_string()
+}
+
+impl Default for RendererStrings {
+    fn default() -> Self {
+        Self {
+            note: default_note(),
+            tip: default_tip(),
+            important: default_important(),
+            warning: default_warning(),
+            caution: default_caution(),
+            figure: default_figure(),
+            table: default_table(),
+            skip_link: default_skip_link(),
+            copy: default_copy(),
+            copied: default_copied(),
+        }
+    }
+}
+
+/// Attributes of links to other sites (`[renderer.external_links]` in the config file)
+///
+/// A link is external when it is an `http(s)://` or `//` URL whose host differs from the
+/// host of `site`. Relative links, fragments, and other schemes such as `mailto:` are
+/// never external. Nothing is added until at least one attribute is configured.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ExternalLinks {
+    /// URL or host name of the site; `base_url` when unset. With neither, every absolute
+    /// link is external
+    #[serde(default)]
+    pub site: Option<String>,
+    /// Open external links in a new tab (`target="_blank"`)
+    #[serde(default)]
+    pub new_tab: bool,
+    /// `rel` attribute of external links, e.g. `noopener noreferrer`
+    #[serde(default)]
+    pub rel: Option<String>,
+    /// Class of external links, for styling them with an icon or marker
+    #[serde(default)]
+    pub class: Option<String>,
+}
+
+/// Where HTML pages load the Mermaid library from
+///
+/// The script is only added to pages that contain a client-side rendered diagram.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MermaidScript {
+    /// jsDelivr, pinned to `mermaid_version`
+    #[default]
+    Cdn,
+    /// `mermaid-<version>.min.js` next to the page, copied from `mermaid_script_path` when
+    /// the page is written to the output directory
+    Local,
+    /// The contents of `mermaid_script_path` in a `<script>` element, for self-contained pages
+    Inline,
+    /// No script; for templates that load Mermaid themselves
+    None,
+}
+
 /// Configuration for the renderer settings
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct RendererConfig {
     /// Output directory for rendered files
     pub output_directory: String,
@@ -97,6 +689,141 @@ pub struct RendererConfig {
     pub html_body_start_path: String,
     /// Path to CSS styles file
     pub styles_css_path: String,
+    /// Only render sections whose heading `audience` attribute matches (None renders everything)
+    #[serde(default)]
+    pub audience: Option<String>,
+    /// Shift every heading level by this amount (clamped to 1-6), e.g. 1 renders `#` as `<h2>`
+    #[serde(default)]
+    pub heading_offset: i8,
+    /// Shift headings so the shallowest level in the document becomes `<h1>` (before the offset)
+    #[serde(default = "default_false")]
+    pub normalize_headings: bool,
+    /// Base URL prepended to relative link and image URLs (e.g. a CDN host)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Callback applied to every link and image URL before the base URL is resolved
+    #[serde(skip)]
+    pub url_rewriter: Option<UrlRewriter>,
+    /// Attributes added to links that leave the site
+    #[serde(default)]
+    pub external_links: ExternalLinks,
+    /// Treatment of `<!-- ... -->` comments: `strip`, `keep`, or `visible`
+    #[serde(default)]
+    pub comments: CommentMode,
+    /// Render task list checkboxes enabled, so readers can tick them (disabled by default)
+    #[serde(default = "default_false")]
+    pub interactive_tasks: bool,
+    /// Render Mermaid diagrams to inline SVG with the Mermaid CLI (`mmdc`) at build time
+    #[serde(default = "default_false")]
+    pub prerender_mermaid: bool,
+    /// Directory caching pre-rendered SVGs by diagram source and theme (None disables the cache)
+    #[serde(default)]
+    pub mermaid_cache_dir: Option<String>,
+    /// Source of the Mermaid library on HTML pages: `cdn`, `local`, `inline`, or `none`
+    #[serde(default)]
+    pub mermaid_script: MermaidScript,
+    /// Exact Mermaid release loaded from the CDN and named in the local file name
+    #[serde(default = "default_mermaid_version")]
+    pub mermaid_version: String,
+    /// Local copy of `mermaid.min.js` used by the `local` and `inline` script modes
+    #[serde(default = "default_mermaid_script_path")]
+    pub mermaid_script_path: String,
+    /// Page `<title>`; the first level-1 heading when unset, or the template's title when
+    /// the document has none
+    #[serde(default)]
+    pub title: Option<String>,
+    /// `<meta name="description">` content; the start of the first paragraph when unset
+    #[serde(default)]
+    pub description: Option<String>,
+    /// `<meta name="author">` content (no tag when unset)
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Add Open Graph tags (`og:title`, `og:description`, `og:type`) for link previews
+    #[serde(default)]
+    pub open_graph: bool,
+    /// Further `<meta>` tags, by name (e.g. `robots = "noindex"`)
+    #[serde(default)]
+    pub meta: BTreeMap<String, String>,
+    /// Line ending of rendered HTML, AsciiDoc, and reStructuredText: `lf` or `crlf`
+    #[serde(default)]
+    pub line_ending: LineEnding,
+    /// Whitespace of rendered HTML: compact (default), minified, or pretty-printed
+    #[serde(default)]
+    pub output_style: OutputStyle,
+    /// Link target of hashtags, with `{tag}` replaced by the tag (e.g. `/tags/{tag}.html`)
+    ///
+    /// Hashtags are plain `<span class="hashtag">` elements when unset.
+    #[serde(default)]
+    pub hashtag_url: Option<String>,
+    /// Link target of mentions, with `{user}` replaced by the name (e.g. `https://github.com/{user}`)
+    ///
+    /// Mentions are plain `<span class="mention">` elements when unset.
+    #[serde(default)]
+    pub mention_url: Option<String>,
+    /// Number the lines of every code block, not just those with the `line-numbers` class
+    #[serde(default)]
+    pub code_line_numbers: bool,
+    /// Copy-to-clipboard buttons on code blocks: `off`, `inline` (with the script and styles
+    /// that run them), or `markup` (buttons only, for a site's own script)
+    #[serde(default)]
+    pub copy_button: CopyButton,
+    /// Accessibility mode: `scope` on table header cells, task checkboxes labelled with
+    /// their item text, and a skip link to a `<main>` landmark around the document
+    ///
+    /// The CLI also reports the issues found by
+    /// [`audit_accessibility`](crate::audit_accessibility), such as images without alt text.
+    #[serde(default)]
+    pub accessible: bool,
+    /// Number figures in document order, labelling captions `Figure 1:` (HTML and DOCX)
+    #[serde(default)]
+    pub number_figures: bool,
+    /// Prefix headings with section numbers (`1`, `1.1`, `1.2.3`), also in `md-parser toc`
+    #[serde(default)]
+    pub number_headings: bool,
+    /// Shallowest heading level that gets a number, e.g. 2 to leave a level-1 title unnumbered
+    #[serde(default = "default_number_headings_from")]
+    pub number_headings_from: u8,
+    /// Number captioned tables in document order, labelling captions `Table 1:` (HTML and DOCX)
+    #[serde(default)]
+    pub number_tables: bool,
+    /// Download `http(s)://` images at render time and link local copies instead (needs the
+    /// `http` feature; ignored without it)
+    #[serde(default)]
+    pub localize_images: bool,
+    /// Directory for downloaded images, relative to `output_directory`; existing files are
+    /// reused, so it doubles as the download cache
+    #[serde(default = "default_image_assets_dir")]
+    pub image_assets_dir: String,
+    /// Longest a single image download may take, in seconds; slower images stay remote
+    #[serde(default = "default_image_download_timeout_secs")]
+    pub image_download_timeout_secs: u64,
+    /// Text inserted by the renderers: admonition titles, figure labels, copy button labels,
+    /// and the skip link
+    #[serde(default)]
+    pub strings: RendererStrings,
+    /// HTML overrides for chosen node and inline types, consulted before the defaults
+    #[serde(skip)]
+    pub custom_renderer: Option<CustomRenderer>,
+}
+
+fn default_mermaid_version() -> String {
+    "10.9.1".to_string()
+}
+
+fn default_mermaid_script_path() -> String {
+    "assets/mermaid.min.js".to_string()
+}
+
+fn default_number_headings_from() -> u8 {
+    1
+}
+
+fn default_image_assets_dir() -> String {
+    "images".to_string()
+}
+
+fn default_image_download_timeout_secs() -> u64 {
+    30
 }
 
 impl Default for RendererConfig {
@@ -107,12 +834,46 @@ impl Default for RendererConfig {
             html_footer_path: "assets/html_footer.html".to_string(),
             html_body_start_path: "assets/html_body_start.html".to_string(),
             styles_css_path: "assets/styles.css".to_string(),
+            audience: None,
+            heading_offset: 0,
+            normalize_headings: false,
+            base_url: None,
+            url_rewriter: None,
+            external_links: ExternalLinks::default(),
+            comments: CommentMode::Strip,
+            interactive_tasks: false,
+            prerender_mermaid: false,
+            mermaid_cache_dir: None,
+            mermaid_script: MermaidScript::Cdn,
+            mermaid_version: default_mermaid_version(),
+            mermaid_script_path: default_mermaid_script_path(),
+            title: None,
+            description: None,
+            author: None,
+            open_graph: false,
+            meta: BTreeMap::new(),
+            line_ending: LineEnding::Lf,
+            output_style: OutputStyle::Compact,
+            hashtag_url: None,
+            mention_url: None,
+            code_line_numbers: false,
+            copy_button: CopyButton::Off,
+            accessible: false,
+            number_figures: false,
+            number_headings: false,
+            number_headings_from: default_number_headings_from(),
+            number_tables: false,
+            localize_images: false,
+            image_assets_dir: default_image_assets_dir(),
+            image_download_timeout_secs: default_image_download_timeout_secs(),
+            strings: RendererStrings::default(),
+            custom_renderer: None,
         }
     }
 }
 
 /// Configuration for output file settings
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct OutputConfig {
     /// Output directory for all generated files
     pub directory: String,
@@ -128,6 +889,99 @@ pub struct OutputConfig {
     pub enable_ast_json: bool,
     /// Enable HTML output
     pub enable_html: bool,
+    /// Audiences to render separate HTML variants for (e.g. `["public", "staff"]`)
+    #[serde(default)]
+    pub audiences: Vec<String>,
+    /// Filename for the per-file summary written by directory builds
+    #[serde(default = "default_build_report_filename")]
+    pub build_report_filename: String,
+    /// Filename for EPUB output
+    #[serde(default = "default_epub_filename")]
+    pub epub_filename: String,
+    /// Enable EPUB output
+    #[serde(default = "default_false")]
+    pub enable_epub: bool,
+    /// Filename for AsciiDoc output
+    #[serde(default = "default_asciidoc_filename")]
+    pub asciidoc_filename: String,
+    /// Enable AsciiDoc output
+    #[serde(default = "default_false")]
+    pub enable_asciidoc: bool,
+    /// Filename for reStructuredText output
+    #[serde(default = "default_rst_filename")]
+    pub rst_filename: String,
+    /// Enable reStructuredText output
+    #[serde(default = "default_false")]
+    pub enable_rst: bool,
+    /// Filename for the Reveal.js slide deck
+    #[serde(default = "default_slides_filename")]
+    pub slides_filename: String,
+    /// Enable slide deck output
+    #[serde(default = "default_false")]
+    pub enable_slides: bool,
+    /// Filename for Word output
+    #[serde(default = "default_docx_filename")]
+    pub docx_filename: String,
+    /// Enable Word (`.docx`) output; requires the `docx` feature
+    #[serde(default = "default_false")]
+    pub enable_docx: bool,
+    /// Filename for the search index of every document built
+    #[serde(default = "default_search_index_filename")]
+    pub search_index_filename: String,
+    /// Enable search index output
+    #[serde(default = "default_false")]
+    pub enable_search_index: bool,
+    /// Filename for the navigation tree written by directory builds
+    #[serde(default = "default_nav_filename")]
+    pub nav_filename: String,
+    /// Enable the navigation tree of directory builds
+    #[serde(default = "default_false")]
+    pub enable_nav: bool,
+    /// Filename for the sitemap written by directory builds
+    #[serde(default = "default_sitemap_filename")]
+    pub sitemap_filename: String,
+    /// Enable the sitemap of directory builds
+    #[serde(default = "default_false")]
+    pub enable_sitemap: bool,
+    /// URL of the site that sitemap page URLs start with; `renderer.base_url` when unset
+    #[serde(default)]
+    pub site_url: Option<String>,
+}
+
+fn default_asciidoc_filename() -> String {
+    "output.adoc".to_string()
+}
+
+fn default_rst_filename() -> String {
+    "output.rst".to_string()
+}
+
+fn default_docx_filename() -> String {
+    "output.docx".to_string()
+}
+
+fn default_search_index_filename() -> String {
+    "search-index.json".to_string()
+}
+
+fn default_nav_filename() -> String {
+    "nav.json".to_string()
+}
+
+fn default_sitemap_filename() -> String {
+    "sitemap.xml".to_string()
+}
+
+fn default_slides_filename() -> String {
+    "slides.html".to_string()
+}
+
+fn default_epub_filename() -> String {
+    "output.epub".to_string()
+}
+
+fn default_build_report_filename() -> String {
+    "build-report.json".to_string()
 }
 
 impl Default for OutputConfig {
@@ -140,12 +994,31 @@ impl Default for OutputConfig {
             enable_ast_debug: true,
             enable_ast_json: true,
             enable_html: true,
+            audiences: Vec::new(),
+            build_report_filename: default_build_report_filename(),
+            epub_filename: default_epub_filename(),
+            enable_epub: false,
+            asciidoc_filename: default_asciidoc_filename(),
+            enable_asciidoc: false,
+            rst_filename: default_rst_filename(),
+            enable_rst: false,
+            slides_filename: default_slides_filename(),
+            enable_slides: false,
+            docx_filename: default_docx_filename(),
+            enable_docx: false,
+            search_index_filename: default_search_index_filename(),
+            enable_search_index: false,
+            nav_filename: default_nav_filename(),
+            enable_nav: false,
+            sitemap_filename: default_sitemap_filename(),
+            enable_sitemap: false,
+            site_url: None,
         }
     }
 }
 
 /// Main configuration structure
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
 pub struct Config {
     /// Parser configuration
     pub parser: ParserConfig,
@@ -153,65 +1026,536 @@ pub struct Config {
     pub renderer: RendererConfig,
     /// Output configuration
     pub output: OutputConfig,
+    /// Commands for running code blocks (`md-parser test`)
+    #[serde(default)]
+    pub doctest: DocTestConfig,
+    /// Style rules checked by `md-parser lint`
+    #[serde(default)]
+    pub lint: LintConfig,
+    /// Style written by `md-parser fmt`
+    #[serde(default)]
+    pub format: FormatConfig,
+    /// How Jupyter notebooks (`.ipynb` inputs) are read
+    #[serde(default)]
+    pub notebook: NotebookConfig,
+}
+
+/// An invalid configuration value
+struct ConfigProblem {
+    /// TOML table containing the key (`parser`, `parser.mermaid`, ...)
+    table: &'static str,
+    key: &'static str,
+    message: String,
 }
 
 impl Config {
     /// Load configuration from `config.toml` file, or return default if file doesn't exist
     ///
+    /// Environment variable overrides are applied afterwards
+    /// (see [`Config::apply_env_overrides`]).
+    ///
     /// # Errors
     ///
     /// Returns an error if the config file exists but cannot be parsed
     pub fn load_config() -> Result<Self, String> {
+        Self::load_default_path(false)
+    }
+
+    /// Like [`Config::load_config`], but rejects unknown keys
+    ///
+    /// Every unknown key and invalid value is reported with the file path and line number
+    /// (`config.toml:12: unknown key 'enable_htm' in [output] (did you mean 'enable_html'?)`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing every problem found in the config file
+    pub fn load_config_strict() -> Result<Self, String> {
+        Self::load_default_path(true)
+    }
+
+    fn load_default_path(strict: bool) -> Result<Self, String> {
         const CONFIG_PATH: &str = "config.toml";
 
         if !Path::new(CONFIG_PATH).exists() {
-            return Ok(Self::default());
+            let mut config = Self::default();
+            config.apply_env_overrides()?;
+            return Ok(config);
         }
 
-        let contents = fs::read_to_string(CONFIG_PATH)
-            .map_err(|e| format!("Failed to read config file '{}': {}", CONFIG_PATH, e))?;
+        Self::load_file(Path::new(CONFIG_PATH), strict)
+    }
 
-        let config: Config = toml::from_str(&contents)
-            .map_err(|e| format!("Failed to parse config file '{}': {}", CONFIG_PATH, e))?;
+    /// Load configuration from every config file that applies to the current directory
+    ///
+    /// Layers are merged key by key, later layers overriding earlier ones:
+    ///
+    /// 1. built-in defaults
+    /// 2. the system config, `/etc/md-parser/config.toml` (Unix only)
+    /// 3. the user config, `$XDG_CONFIG_HOME/md-parser/config.toml` (falling back to
+    ///    `~/.config/md-parser/config.toml`)
+    /// 4. `config.toml` in the current directory
+    /// 5. the nearest `.md-parser.toml` in the current directory or one of its parents
+    ///
+    /// Each file only needs the keys it changes. Environment variable overrides are
+    /// applied last; command-line flags are expected to be applied on top of the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a config file cannot be read or parsed, or the merged
+    /// configuration is invalid
+    pub fn layered() -> Result<Self, String> {
+        Self::from_layers(Self::layer_paths())
+    }
 
+    /// Like [`Config::layered`], but rejects unknown keys in every layer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing every problem found in the config files
+    pub fn layered_strict() -> Result<Self, String> {
+        Self::from_layers_strict(Self::layer_paths())
+    }
+
+    /// Existing config files used by [`Config::layered`], lowest precedence first
+    pub fn layer_paths() -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+        if cfg!(unix) {
+            candidates.push(PathBuf::from("/etc/md-parser/config.toml"));
+        }
+        let user_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+        if let Some(dir) = user_dir {
+            candidates.push(dir.join("md-parser").join("config.toml"));
+        }
+        candidates.push(PathBuf::from("config.toml"));
+        if let Ok(cwd) = std::env::current_dir() {
+            if let Some(project) = cwd
+                .ancestors()
+                .map(|dir| dir.join(".md-parser.toml"))
+                .find(|path| path.is_file())
+            {
+                candidates.push(project);
+            }
+        }
+
+        candidates.retain(|path| path.is_file());
+        candidates
+    }
+
+    /// Merge the given config files over the built-in defaults, later files winning
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read or parsed, or the merged configuration
+    /// is invalid
+    pub fn from_layers<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> Result<Self, String> {
+        Self::load_layers(paths, false)
+    }
+
+    /// Like [`Config::from_layers`], but rejects unknown keys
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing every problem found in the files
+    pub fn from_layers_strict<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+    ) -> Result<Self, String> {
+        Self::load_layers(paths, true)
+    }
+
+    /// Load configuration from a specific TOML file
+    ///
+    /// Keys missing from the file keep their default values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, parsed, or contains invalid values
+    pub fn load_from_path(path: impl AsRef<Path>) -> Result<Self, String> {
+        Self::load_file(path.as_ref(), false)
+    }
+
+    /// Load configuration from a specific TOML file, rejecting unknown keys
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing every problem found in the file
+    pub fn load_from_path_strict(path: impl AsRef<Path>) -> Result<Self, String> {
+        Self::load_file(path.as_ref(), true)
+    }
+
+    fn load_file(path: &Path, strict: bool) -> Result<Self, String> {
+        Self::load_layers([path], strict)
+    }
+
+    fn load_layers<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+        strict: bool,
+    ) -> Result<Self, String> {
+        let mut merged = toml::Table::try_from(Config::default())
+            .map_err(|e| format!("Failed to serialize default configuration: {}", e))?;
+        // (path, contents) of every layer, for locating problems in strict mode
+        let mut sources: Vec<(PathBuf, String)> = Vec::new();
+        let mut errors = Vec::new();
+
+        for path in paths {
+            let path = path.as_ref();
+            let contents = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read config file '{}': {}", path.display(), e))?;
+            let layer: toml::Table = contents
+                .parse()
+                .map_err(|e| format!("Failed to parse config file '{}': {}", path.display(), e))?;
+            if strict {
+                errors.extend(unknown_keys(&contents, path));
+            }
+            merge_tables(&mut merged, layer);
+            sources.push((path.to_path_buf(), contents));
+        }
+
+        let parsed: Result<Config, String> = merged.try_into().map_err(|e| {
+            let files: Vec<String> = sources
+                .iter()
+                .map(|(path, _)| path.display().to_string())
+                .collect();
+            format!("Invalid configuration in '{}': {}", files.join("', '"), e)
+        });
+
+        if strict {
+            match &parsed {
+                Ok(config) => errors.extend(config.problems().into_iter().map(|problem| {
+                    // Blame the highest-precedence layer that sets the key
+                    let location = sources
+                        .iter()
+                        .rev()
+                        .find_map(|(path, contents)| {
+                            line_of_key(contents, problem.table, problem.key)
+                                .map(|line| format!("{}:{}", path.display(), line))
+                        })
+                        .unwrap_or_else(|| format!("[{}]", problem.table));
+                    format!("{}: {}", location, problem.message)
+                })),
+                Err(e) => errors.push(e.clone()),
+            }
+            if !errors.is_empty() {
+                return Err(errors.join("\n"));
+            }
+        }
+
+        let mut config = parsed?;
         // Validate config values
         config.validate()?;
-
+        config.apply_env_overrides()?;
         Ok(config)
     }
 
+    /// Apply configuration overrides from environment variables
+    ///
+    /// - `MD_PARSER_OUTPUT_DIR`: `output.directory` and `renderer.output_directory`
+    /// - `MD_PARSER_MAX_HEADING_LEVEL`: `parser.max_heading_level`
+    /// - `MD_PARSER_BASE_URL`: `renderer.base_url`
+    /// - `MD_PARSER_AUDIENCE`: `renderer.audience`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a variable holds an invalid value
+    pub fn apply_env_overrides(&mut self) -> Result<(), String> {
+        if let Ok(dir) = std::env::var("MD_PARSER_OUTPUT_DIR") {
+            self.output.directory = dir.clone();
+            self.renderer.output_directory = dir;
+        }
+        if let Ok(level) = std::env::var("MD_PARSER_MAX_HEADING_LEVEL") {
+            self.parser.max_heading_level = level
+                .parse()
+                .map_err(|_| format!("Invalid MD_PARSER_MAX_HEADING_LEVEL: '{}'", level))?;
+        }
+        if let Ok(base_url) = std::env::var("MD_PARSER_BASE_URL") {
+            self.renderer.base_url = Some(base_url);
+        }
+        if let Ok(audience) = std::env::var("MD_PARSER_AUDIENCE") {
+            self.renderer.audience = Some(audience);
+        }
+        self.validate()
+    }
+
     /// Validate configuration values
     ///
     /// # Errors
     ///
     /// Returns an error if any configuration value is invalid
     fn validate(&self) -> Result<(), String> {
-        // Validate max_heading_level (must be between 1 and 6)
-        if self.parser.max_heading_level == 0 || self.parser.max_heading_level > 6 {
-            return Err(format!(
-                "Invalid max_heading_level: {}. Must be between 1 and 6",
-                self.parser.max_heading_level
-            ));
+        match self.problems().into_iter().next() {
+            Some(problem) => Err(problem.message),
+            None => Ok(()),
         }
+    }
+
+    /// Every invalid value in the configuration
+    fn problems(&self) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+        let mut check = |valid: bool, table, key, message: String| {
+            if !valid {
+                problems.push(ConfigProblem {
+                    table,
+                    key,
+                    message,
+                });
+            }
+        };
 
-        // Validate code_fence_length (must be at least 1)
-        if self.parser.code_fence_length == 0 {
-            return Err(format!(
+        check(
+            (1..=6).contains(&self.parser.max_heading_level),
+            "parser",
+            "max_heading_level",
+            format!(
+                "Invalid max_heading_level: {}. Must be between 1 and 6",
+                self.parser.max_heading_level
+            ),
+        );
+        check(
+            self.parser.code_fence_length > 0,
+            "parser",
+            "code_fence_length",
+            format!(
                 "Invalid code_fence_length: {}. Must be at least 1",
                 self.parser.code_fence_length
-            ));
+            ),
+        );
+        check(
+            (2..=4).contains(&self.parser.list_indent),
+            "parser",
+            "list_indent",
+            format!(
+                "Invalid list_indent: {}. Must be between 2 and 4",
+                self.parser.list_indent
+            ),
+        );
+        check(
+            self.parser.tab_width > 0,
+            "parser",
+            "tab_width",
+            format!(
+                "Invalid tab_width: {}. Must be at least 1",
+                self.parser.tab_width
+            ),
+        );
+        check(
+            !self.parser.code_fence_pattern.is_empty(),
+            "parser",
+            "code_fence_pattern",
+            "code_fence_pattern cannot be empty".to_string(),
+        );
+        check(
+            !self.parser.mermaid_language.is_empty(),
+            "parser",
+            "mermaid_language",
+            "mermaid_language cannot be empty".to_string(),
+        );
+        check(
+            (-5..=5).contains(&self.renderer.heading_offset),
+            "renderer",
+            "heading_offset",
+            format!(
+                "Invalid heading_offset: {}. Must be between -5 and 5",
+                self.renderer.heading_offset
+            ),
+        );
+        check(
+            (1..=6).contains(&self.renderer.number_headings_from),
+            "renderer",
+            "number_headings_from",
+            format!(
+                "Invalid number_headings_from: {}. Must be between 1 and 6",
+                self.renderer.number_headings_from
+            ),
+        );
+        check(
+            !self.output.directory.is_empty(),
+            "output",
+            "directory",
+            "output directory cannot be empty".to_string(),
+        );
+
+        problems
+    }
+}
+
+/// Tables whose keys are chosen by the user rather than being fields
+const FREE_FORM_TABLES: &[&str] = &["parser.variables", "renderer.meta", "doctest.commands"];
+
+/// Report keys in `contents` that do not correspond to a configuration field
+fn unknown_keys(contents: &str, path: &Path) -> Vec<String> {
+    let Ok(table) = contents.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let schema = schemars::schema_for!(Config);
+    let schema = schema.as_value();
+    let known = known_keys(schema, &schema["$defs"]);
+    let mut errors = Vec::new();
+    collect_unknown_keys(&table, &known, "", contents, path, &mut errors);
+    errors
+}
+
+/// Fields described by `schema`, as an object of nested objects for tables and `null`
+/// for values
+///
+/// Read from the schema rather than a serialized default so that optional fields,
+/// which are skipped when `None`, are known too.
+fn known_keys(schema: &serde_json::Value, defs: &serde_json::Value) -> serde_json::Value {
+    match resolve_schema(schema, defs)
+        .get("properties")
+        .and_then(serde_json::Value::as_object)
+    {
+        Some(properties) => serde_json::Value::Object(
+            properties
+                .iter()
+                .map(|(key, field)| (key.clone(), known_keys(field, defs)))
+                .collect(),
+        ),
+        None => serde_json::Value::Null,
+    }
+}
+
+/// `schema` with references followed and `Option`s unwrapped
+fn resolve_schema<'a>(
+    schema: &'a serde_json::Value,
+    defs: &'a serde_json::Value,
+) -> &'a serde_json::Value {
+    if let Some(name) = schema
+        .get("$ref")
+        .and_then(serde_json::Value::as_str)
+        .and_then(|reference| reference.strip_prefix("#/$defs/"))
+    {
+        return resolve_schema(&defs[name], defs);
+    }
+    let is_null = |variant: &&serde_json::Value| variant.get("type").is_some_and(|t| t == "null");
+    match schema
+        .get("anyOf")
+        .and_then(serde_json::Value::as_array)
+        .and_then(|variants| variants.iter().find(|variant| !is_null(variant)))
+    {
+        Some(variant) => resolve_schema(variant, defs),
+        None => schema,
+    }
+}
+
+fn collect_unknown_keys(
+    table: &toml::Table,
+    known: &serde_json::Value,
+    table_path: &str,
+    contents: &str,
+    path: &Path,
+    errors: &mut Vec<String>,
+) {
+    for (key, value) in table {
+        match known.get(key) {
+            Some(known_value) => {
+                if let (toml::Value::Table(nested), serde_json::Value::Object(_)) =
+                    (value, known_value)
+                {
+                    let nested_path = if table_path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", table_path, key)
+                    };
+                    if !FREE_FORM_TABLES.contains(&nested_path.as_str()) {
+                        collect_unknown_keys(
+                            nested,
+                            known_value,
+                            &nested_path,
+                            contents,
+                            path,
+                            errors,
+                        );
+                    }
+                }
+            }
+            None => {
+                let table_name = if table_path.is_empty() {
+                    "top level".to_string()
+                } else {
+                    format!("[{}]", table_path)
+                };
+                let mut message = format!(
+                    "{}: unknown key '{}' in {}",
+                    location(contents, path, table_path, key),
+                    key,
+                    table_name
+                );
+                if let Some(suggestion) = known
+                    .as_object()
+                    .and_then(|fields| closest_key(key, fields.keys()))
+                {
+                    message.push_str(&format!(" (did you mean '{}'?)", suggestion));
+                }
+                errors.push(message);
+            }
         }
+    }
+}
 
-        // Validate code_fence_pattern (must not be empty)
-        if self.parser.code_fence_pattern.is_empty() {
-            return Err("code_fence_pattern cannot be empty".to_string());
+/// `path:line` of `key` inside `[table]`, or just the path if it cannot be found
+fn location(contents: &str, path: &Path, table: &str, key: &str) -> String {
+    match line_of_key(contents, table, key) {
+        Some(line) => format!("{}:{}", path.display(), line),
+        None => path.display().to_string(),
+    }
+}
+
+/// 1-based line of `key` inside `[table]`
+///
+/// Only handles the `[table]` / `key = value` layout used by `config.toml`.
+fn line_of_key(contents: &str, table: &str, key: &str) -> Option<usize> {
+    let mut current_table = "";
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current_table = header.trim();
+        } else if current_table == table
+            && line
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        {
+            return Some(idx + 1);
         }
+    }
+    None
+}
 
-        // Validate mermaid_language (must not be empty)
-        if self.parser.mermaid_language.is_empty() {
-            return Err("mermaid_language cannot be empty".to_string());
+/// Recursively overlay `layer` onto `base`: nested tables are merged, other values replaced
+fn merge_tables(base: &mut toml::Table, layer: toml::Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(nested)) => {
+                merge_tables(existing, nested)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
         }
+    }
+}
 
-        Ok(())
+/// Known key within edit distance 2 of `key`, if any
+fn closest_key<'a>(key: &str, candidates: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    candidates
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
     }
+    previous[b.len()]
 }
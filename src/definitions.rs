@@ -0,0 +1,232 @@
+//! Link reference and footnote definitions, and the references that use them.
+
+use crate::ast::{LineRange, ParseError};
+use crate::config::ParserConfig;
+use crate::parser::{is_code_fence, RegexPatterns};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// What a [`Definition`] defines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionKind {
+    /// `[label]: url`, used by `[text][label]`, `[label][]`, and `[label]`
+    LinkReference,
+    /// `[^label]: text`, used by `[^label]`
+    Footnote,
+}
+
+/// A link reference or footnote definition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Definition {
+    pub kind: DefinitionKind,
+    /// Label as written, without the brackets and the `^` of footnotes
+    pub label: String,
+    /// URL (and any title) of a link reference, or text of a footnote
+    pub target: String,
+    /// Source line of the definition in the text it comes from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<LineRange>,
+}
+
+/// A use of a definition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefinitionReference {
+    pub kind: DefinitionKind,
+    /// Label as written
+    pub label: String,
+    /// Source line of the reference
+    pub span: LineRange,
+}
+
+/// Definitions of a document, those supplied from outside it, and the references to them
+///
+/// The parser keeps definitions and references as text, so the map is read from the
+/// source lines, skipping fenced code blocks and code spans. Labels match
+/// case-insensitively, with runs of whitespace treated as one space.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DefinitionMap {
+    /// Definitions in the document, in document order
+    pub definitions: Vec<Definition>,
+    /// Definitions given with [`Parser::set_external_definitions`](crate::Parser::set_external_definitions)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external: Vec<Definition>,
+    /// References in document order; a shortcut `[label]` counts only if `label` is defined
+    pub references: Vec<DefinitionReference>,
+}
+
+impl DefinitionMap {
+    /// Definitions and references of `source`, such as a shared file of link references
+    /// whose [`definitions`](DefinitionMap::definitions) are given to the parser of each
+    /// document of a build
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::RegexCompilationError` if the reference pattern fails to compile
+    pub fn from_source(source: &str) -> Result<Self, ParseError> {
+        let config = ParserConfig::default();
+        let patterns = RegexPatterns::new(&config)?;
+        Ok(Self::scan(source, Vec::new(), &config, &patterns.reference))
+    }
+
+    /// Definitions and references of `source`, with code fences as `config` recognizes
+    /// them and references found by the parser's compiled `pattern`
+    pub(crate) fn scan(
+        source: &str,
+        external: Vec<Definition>,
+        config: &ParserConfig,
+        pattern: &Regex,
+    ) -> Self {
+        let mut map = DefinitionMap {
+            external,
+            ..DefinitionMap::default()
+        };
+        // References, and whether each is a shortcut that needs a definition to count
+        let mut candidates = Vec::new();
+        let mut in_fence = false;
+        for (idx, line) in source.lines().enumerate() {
+            let trimmed = line.trim();
+            if in_fence {
+                in_fence = trimmed != config.code_fence_pattern;
+                continue;
+            }
+            if is_code_fence(trimmed, config) {
+                in_fence = true;
+                continue;
+            }
+            let span = LineRange {
+                start: idx + 1,
+                end: idx + 1,
+            };
+            match parse_definition(line) {
+                Some((kind, label, target)) => map.definitions.push(Definition {
+                    kind,
+                    label: label.to_string(),
+                    target: target.to_string(),
+                    span: Some(span),
+                }),
+                None => collect_references(line, span, pattern, &mut candidates),
+            }
+        }
+        for (reference, shortcut) in candidates {
+            if !shortcut || map.get(reference.kind, &reference.label).is_some() {
+                map.references.push(reference);
+            }
+        }
+        map
+    }
+
+    /// The definition a reference to `label` uses: the first in the document, or else
+    /// the first external one
+    pub fn get(&self, kind: DefinitionKind, label: &str) -> Option<&Definition> {
+        let key = normalize_label(label);
+        self.definitions
+            .iter()
+            .chain(&self.external)
+            .find(|definition| definition.kind == kind && normalize_label(&definition.label) == key)
+    }
+
+    /// Definitions in the document that no reference can use, because the label is
+    /// defined earlier in the document, or that hide an external definition
+    pub fn duplicates(&self) -> Vec<&Definition> {
+        let external: HashSet<_> = self.external.iter().map(key).collect();
+        let mut seen = HashSet::new();
+        self.definitions
+            .iter()
+            .filter(|definition| {
+                let key = key(definition);
+                external.contains(&key) || !seen.insert(key)
+            })
+            .collect()
+    }
+
+    /// Definitions in the document whose label no reference uses
+    pub fn unused(&self) -> Vec<&Definition> {
+        let used: HashSet<_> = self
+            .references
+            .iter()
+            .map(|reference| (reference.kind, normalize_label(&reference.label)))
+            .collect();
+        self.definitions
+            .iter()
+            .filter(|definition| !used.contains(&key(definition)))
+            .collect()
+    }
+
+    /// References whose label is defined neither in the document nor externally
+    pub fn undefined(&self) -> Vec<&DefinitionReference> {
+        self.references
+            .iter()
+            .filter(|reference| self.get(reference.kind, &reference.label).is_none())
+            .collect()
+    }
+}
+
+fn key(definition: &Definition) -> (DefinitionKind, String) {
+    (definition.kind, normalize_label(&definition.label))
+}
+
+/// `label` lowercased, with runs of whitespace as one space
+fn normalize_label(label: &str) -> String {
+    label
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Kind, label, and target of a `[label]: target` or `[^label]: text` line
+///
+/// Lines indented by more than three spaces are code, as in CommonMark.
+fn parse_definition(line: &str) -> Option<(DefinitionKind, &str, &str)> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let (label, target) = trimmed.strip_prefix('[')?.split_once("]:")?;
+    let (kind, label) = match label.strip_prefix('^') {
+        Some(label) => (DefinitionKind::Footnote, label),
+        None => (DefinitionKind::LinkReference, label),
+    };
+    if label.trim().is_empty() || label.contains(['[', ']']) {
+        return None;
+    }
+    Some((kind, label.trim(), target.trim()))
+}
+
+/// Push the references on `line`, outside code spans, with whether each is a shortcut
+fn collect_references(
+    line: &str,
+    span: LineRange,
+    pattern: &Regex,
+    out: &mut Vec<(DefinitionReference, bool)>,
+) {
+    // Text between backticks is a code span
+    for text in line.split('`').step_by(2) {
+        for captures in pattern.captures_iter(text) {
+            let text_label = captures[2].trim();
+            let (kind, label, shortcut) = match (&captures[1], captures.get(3), captures.get(4)) {
+                ("^", _, _) => (DefinitionKind::Footnote, text_label, false),
+                // An inline link or image
+                (_, _, Some(_)) => continue,
+                (_, Some(label), _) if !label.as_str().trim().is_empty() => {
+                    (DefinitionKind::LinkReference, label.as_str().trim(), false)
+                }
+                (_, Some(_), _) => (DefinitionKind::LinkReference, text_label, false),
+                (_, None, None) => (DefinitionKind::LinkReference, text_label, true),
+            };
+            if label.is_empty() {
+                continue;
+            }
+            out.push((
+                DefinitionReference {
+                    kind,
+                    label: label.to_string(),
+                    span,
+                },
+                shortcut,
+            ));
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! Diagnostics collected across checks, with rustc-style rendering and SARIF output.
+
+use crate::accessibility::AccessibilityIssue;
+use crate::ast::{ParseError, ParseWarning, Severity, Span};
+use crate::lint::LintDiagnostic;
+use crate::project::{AnchorCollision, BrokenLink, BrokenLinkReason};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt::Write;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Check that found a [`Diagnostic`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSource {
+    /// Parse errors and warnings, including Mermaid validation
+    Parse,
+    /// Style rules of [`lint`](crate::lint)
+    Lint,
+    /// Relative links and images whose target document, heading, or file does not exist
+    Links,
+    /// Issues found by [`audit_accessibility`](crate::audit_accessibility)
+    Accessibility,
+    /// Heading anchors that collide across the documents of a [`Project`](crate::Project)
+    Anchors,
+}
+
+/// One problem in one file, in the JSON diagnostics format
+///
+/// ```json
+/// {"file": "docs/guide.md", "line": 12, "column": 5, "severity": "warning",
+///  "source": "parse", "rule": "unclosed-emphasis", "message": "unclosed `**`"}
+/// ```
+///
+/// `line` and `column` are 1-based and omitted when unknown; `note` is a hint on how
+/// to fix the problem, when there is one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Path of the file as given to the tool
+    pub file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub severity: Severity,
+    pub source: DiagnosticSource,
+    /// Warning code or rule name (`unclosed-emphasis`, `trailing-whitespace`)
+    pub rule: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl Diagnostic {
+    /// Format the diagnostic with the offending line of `source`, like
+    /// [`ParseWarning::display_with_source`]
+    pub fn display_with_source(&self, source: &str) -> String {
+        let span = self.line.map(|line| Span {
+            line,
+            column: self.column,
+        });
+        render(
+            &self.severity.to_string(),
+            &format!("{} [{}]", self.message, self.rule),
+            span.as_ref(),
+            source,
+            self.note.as_deref(),
+        )
+    }
+}
+
+/// Diagnostics of any number of files, convertible to JSON and SARIF for CI and editors
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Diagnostics {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Add the error that stopped parsing `file`
+    pub fn add_parse_error(&mut self, file: &str, error: &ParseError) {
+        let (rule, severity) = match error {
+            ParseError::DeniedWarning(warning) => (warning.code.to_string(), Severity::Error),
+            _ => ("parse-error".to_string(), Severity::Error),
+        };
+        self.push(Diagnostic {
+            file: file.to_string(),
+            line: error.span().map(|span| span.line),
+            column: error.span().and_then(|span| span.column),
+            severity,
+            source: DiagnosticSource::Parse,
+            rule,
+            message: error.summary(),
+            note: error.note(),
+        });
+    }
+
+    /// Add the warnings collected while parsing `file`
+    pub fn add_parse_warnings(&mut self, file: &str, warnings: &[ParseWarning]) {
+        for warning in warnings {
+            self.push(Diagnostic {
+                file: file.to_string(),
+                line: Some(warning.span.line),
+                column: warning.span.column,
+                severity: warning.severity,
+                source: DiagnosticSource::Parse,
+                rule: warning.code.to_string(),
+                message: warning.message.clone(),
+                note: warning.note().map(str::to_string),
+            });
+        }
+    }
+
+    /// Add the [`lint`](crate::lint) findings for `file`, as warnings
+    pub fn add_lint(&mut self, file: &str, diagnostics: &[LintDiagnostic]) {
+        for diagnostic in diagnostics {
+            self.push(Diagnostic {
+                file: file.to_string(),
+                line: Some(diagnostic.span.line),
+                column: diagnostic.span.column,
+                severity: Severity::Warning,
+                source: DiagnosticSource::Lint,
+                rule: diagnostic.rule.to_string(),
+                message: diagnostic.message.clone(),
+                note: diagnostic
+                    .fix
+                    .as_ref()
+                    .map(|fix| format!("fixable: `{}`", fix.replacement.trim())),
+            });
+        }
+    }
+
+    /// Add the accessibility issues of `file`, as warnings
+    pub fn add_accessibility(&mut self, file: &str, issues: &[AccessibilityIssue]) {
+        for issue in issues {
+            self.push(Diagnostic {
+                file: file.to_string(),
+                line: issue.span.map(|span| span.start),
+                column: None,
+                severity: Severity::Warning,
+                source: DiagnosticSource::Accessibility,
+                rule: issue.rule.to_string(),
+                message: issue.message.clone(),
+                note: None,
+            });
+        }
+    }
+
+    /// Add an error for each broken link of `file`, from
+    /// [`Project::broken_links`](crate::Project::broken_links)
+    pub fn add_broken_links(&mut self, file: &str, links: &[BrokenLink]) {
+        for link in links {
+            let problem = match link.reason {
+                BrokenLinkReason::MissingAnchor => "the target has no such heading",
+                BrokenLinkReason::MissingDocument | BrokenLinkReason::MissingFile => {
+                    "the target does not exist"
+                }
+            };
+            self.push(Diagnostic {
+                file: file.to_string(),
+                line: None,
+                column: None,
+                severity: Severity::Error,
+                source: DiagnosticSource::Links,
+                rule: "broken-link".to_string(),
+                message: format!("broken link `{}`: {}", link.url, problem),
+                note: None,
+            });
+        }
+    }
+
+    /// Add a warning for each document of a project whose anchor collides with one of an
+    /// earlier document, from [`Project::anchor_collisions`](crate::Project::anchor_collisions)
+    pub fn add_anchor_collisions(&mut self, collisions: &[AnchorCollision]) {
+        for collision in collisions {
+            let Some(((first, _), rest)) = collision.documents.split_first() else {
+                continue;
+            };
+            for (path, anchor) in rest {
+                self.push(Diagnostic {
+                    file: path.display().to_string(),
+                    line: None,
+                    column: None,
+                    severity: Severity::Warning,
+                    source: DiagnosticSource::Anchors,
+                    rule: "anchor-collision".to_string(),
+                    message: format!(
+                        "anchor `{}` (`#{}`) is also generated by `{}`",
+                        collision.anchor,
+                        anchor,
+                        first.display()
+                    ),
+                    note: Some("give one of the headings an explicit `{#id}`".to_string()),
+                });
+            }
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Whether any diagnostic is a warning or an error, which fails `md-parser check`
+    pub fn has_failures(&self) -> bool {
+        self.iter().any(|d| d.severity >= Severity::Warning)
+    }
+
+    /// Serialize as a pretty-printed JSON array of [`Diagnostic`]s
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Serialize as a SARIF 2.1.0 log with one run, for GitHub code scanning and
+    /// other SARIF viewers
+    ///
+    /// Each distinct rule is listed in the tool's rules; file paths become relative
+    /// URIs with `/` separators. Notes are appended to the result message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails
+    pub fn to_sarif(&self) -> Result<String, serde_json::Error> {
+        let mut rules: Vec<&str> = self.iter().map(|d| d.rule.as_str()).collect();
+        rules.sort_unstable();
+        rules.dedup();
+
+        let results: Vec<_> = self
+            .iter()
+            .map(|d| {
+                let mut location = json!({
+                    "artifactLocation": {"uri": d.file.replace('\\', "/")},
+                });
+                if let Some(line) = d.line {
+                    location["region"] = match d.column {
+                        Some(column) => json!({"startLine": line, "startColumn": column}),
+                        None => json!({"startLine": line}),
+                    };
+                }
+                let message = match &d.note {
+                    Some(note) => format!("{} ({})", d.message, note),
+                    None => d.message.clone(),
+                };
+                json!({
+                    "ruleId": d.rule,
+                    "level": d.severity.to_string(),
+                    "message": {"text": message},
+                    "locations": [{"physicalLocation": location}],
+                    "properties": {"source": d.source},
+                })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules.iter().map(|id| json!({"id": id})).collect::<Vec<_>>(),
+                    }
+                },
+                "results": results,
+            }],
+        }))
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Render a diagnostic with the offending source line and a caret marker
+///
+/// ```text
+/// error: invalid heading level 7 (max 6)
+///  --> line 3
+///   |
+/// 3 | ####### Title
+///   | ^^^^^^^^^^^^^
+///   = note: headings use at most 6 `#` characters
+/// ```
+///
+/// With a column the caret points at it; without one the whole line is underlined.
+/// The excerpt is omitted when the span lies outside `source`.
+pub(crate) fn render(
+    label: &str,
+    message: &str,
+    span: Option<&Span>,
+    source: &str,
+    note: Option<&str>,
+) -> String {
+    let mut out = format!("{}: {}", label, message);
+    let Some(span) = span else {
+        if let Some(note) = note {
+            let _ = write!(out, "\n = note: {}", note);
+        }
+        return out;
+    };
+
+    let gutter = " ".repeat(span.line.to_string().len());
+    let _ = write!(out, "\n{}--> {}", gutter, span);
+
+    if let Some(line) = span
+        .line
+        .checked_sub(1)
+        .and_then(|idx| source.lines().nth(idx))
+    {
+        let _ = write!(
+            out,
+            "\n{} |\n{} | {}\n{} | {}",
+            gutter,
+            span.line,
+            line,
+            gutter,
+            marker(line, span.column)
+        );
+    }
+    if let Some(note) = note {
+        let _ = write!(out, "\n{} = note: {}", gutter, note);
+    }
+    out
+}
+
+/// Caret line for `line`: `^` under `column`, or under the trimmed line if unknown
+///
+/// `column` counts characters. Tabs before the marker are kept and every other
+/// character is padded to its display width, so the caret lines up with the source
+/// in a terminal even after wide (CJK, emoji) or zero-width (combining) characters.
+fn marker(line: &str, column: Option<usize>) -> String {
+    let (start, width) = match column {
+        Some(column) => {
+            let width = line
+                .chars()
+                .nth(column.saturating_sub(1))
+                .and_then(UnicodeWidthChar::width)
+                .unwrap_or(1);
+            (column.saturating_sub(1), width.max(1))
+        }
+        None => {
+            let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+            let width = line.trim().width().max(1);
+            (indent, width)
+        }
+    };
+
+    let mut marker = String::new();
+    for c in line.chars().chain(std::iter::repeat(' ')).take(start) {
+        match c {
+            '\t' => marker.push('\t'),
+            c => marker.extend(std::iter::repeat_n(' ', c.width().unwrap_or(0))),
+        }
+    }
+    marker.push_str(&"^".repeat(width));
+    marker
+}
@@ -0,0 +1,313 @@
+//! Block and word level differences between two documents.
+
+use crate::ast::{plain_text, Inline, Node};
+use crate::config::RendererConfig;
+use crate::renderer;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::error::Error;
+use std::ops::Range;
+
+/// A change between two versions of a document
+///
+/// Indices refer to top-level nodes of the old or new AST.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum DocChange {
+    /// A block that only exists in the new document
+    Inserted { new_index: usize, node: Node },
+    /// A block that only exists in the old document
+    Removed { old_index: usize, node: Node },
+    /// A block of the same kind whose content changed
+    Modified {
+        old_index: usize,
+        new_index: usize,
+        old: Box<Node>,
+        new: Box<Node>,
+        /// Word-level changes for blocks with inline content (headings, paragraphs,
+        /// blockquotes); empty for other blocks
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        text_changes: Vec<TextChange>,
+    },
+}
+
+/// A run of words in a word-level diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "change", content = "text", rename_all = "lowercase")]
+pub enum TextChange {
+    /// Words present in both versions
+    Unchanged(String),
+    /// Words only present in the new version
+    Inserted(String),
+    /// Words only present in the old version
+    Removed(String),
+}
+
+/// One step of the alignment between the old and new node lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffOp {
+    Equal { old: usize, new: usize },
+    Removed { old: usize },
+    Inserted { new: usize },
+    Modified { old: usize, new: usize },
+}
+
+/// Report the blocks that were inserted, removed, or modified between `old` and `new`
+///
+/// Blocks are matched with a longest common subsequence. A removed block directly
+/// replaced by an inserted block of the same kind is reported as `Modified`, with a
+/// word-level diff when the block has inline content. Unchanged blocks are omitted.
+pub fn ast_diff(old: &[Node], new: &[Node]) -> Vec<DocChange> {
+    align(old, new)
+        .into_iter()
+        .filter_map(|op| match op {
+            DiffOp::Equal { .. } => None,
+            DiffOp::Removed { old: index } => Some(DocChange::Removed {
+                old_index: index,
+                node: old[index].clone(),
+            }),
+            DiffOp::Inserted { new: index } => Some(DocChange::Inserted {
+                new_index: index,
+                node: new[index].clone(),
+            }),
+            DiffOp::Modified {
+                old: old_index,
+                new: new_index,
+            } => Some(DocChange::Modified {
+                old_index,
+                new_index,
+                old: Box::new(old[old_index].clone()),
+                new: Box::new(new[new_index].clone()),
+                text_changes: text_diff(&old[old_index], &new[new_index]),
+            }),
+        })
+        .collect()
+}
+
+/// Render `new` as a full HTML document with the changes since `old` highlighted
+///
+/// Unchanged blocks render normally. Removed and inserted blocks are wrapped in
+/// `<div class="diff-removed">` / `<div class="diff-inserted">`; modified headings,
+/// paragraphs, and blockquotes are wrapped in `<div class="diff-modified">` and mark
+/// changed words with `<del>` and `<ins>`.
+///
+/// # Errors
+///
+/// Returns an error if template files cannot be read
+pub fn diff_to_html(
+    old: &[Node],
+    new: &[Node],
+    config: &RendererConfig,
+) -> Result<String, Box<dyn Error>> {
+    let html = renderer::wrap_document(&renderer::render_diff_fragment(old, new, config), config)?;
+    Ok(config.line_ending.apply(html))
+}
+
+/// Align two node lists, pairing same-kind replacements as modifications
+pub(crate) fn align(old: &[Node], new: &[Node]) -> Vec<DiffOp> {
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    let mut removed = Vec::new();
+    let mut inserted = Vec::new();
+
+    for step in lcs(old, new) {
+        match step {
+            Step::Equal(o, n) => {
+                pair_replacements(old, new, &mut removed, &mut inserted, &mut ops);
+                ops.push(DiffOp::Equal { old: o, new: n });
+            }
+            Step::Removed(o) => removed.push(o),
+            Step::Inserted(n) => inserted.push(n),
+        }
+    }
+    pair_replacements(old, new, &mut removed, &mut inserted, &mut ops);
+
+    ops
+}
+
+/// Flush a run of removed and inserted blocks between two unchanged blocks
+fn pair_replacements(
+    old: &[Node],
+    new: &[Node],
+    removed: &mut Vec<usize>,
+    inserted: &mut Vec<usize>,
+    ops: &mut Vec<DiffOp>,
+) {
+    let mut removed_iter = removed.drain(..).peekable();
+    let mut inserted_iter = inserted.drain(..).peekable();
+
+    loop {
+        match (removed_iter.peek().copied(), inserted_iter.peek().copied()) {
+            (Some(o), Some(n)) if old[o].kind() == new[n].kind() => {
+                ops.push(DiffOp::Modified { old: o, new: n });
+                removed_iter.next();
+                inserted_iter.next();
+            }
+            (Some(o), _) => {
+                ops.push(DiffOp::Removed { old: o });
+                removed_iter.next();
+            }
+            (None, Some(n)) => {
+                ops.push(DiffOp::Inserted { new: n });
+                inserted_iter.next();
+            }
+            (None, None) => break,
+        }
+    }
+}
+
+enum Step {
+    Equal(usize, usize),
+    Removed(usize),
+    Inserted(usize),
+}
+
+/// Longest common subsequence alignment of two slices
+///
+/// The common prefix and suffix are matched directly and the rest is aligned with
+/// Hirschberg's algorithm, so memory stays linear in the input length. Within each
+/// run of changes, removals come before insertions.
+fn lcs<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Step> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+
+    let mut steps = Vec::with_capacity(old.len().max(new.len()));
+    steps.extend((0..prefix).map(|i| Step::Equal(i, i)));
+    hirschberg(old, new, prefix..old_end, prefix..new_end, &mut steps);
+    steps.extend((0..suffix).map(|k| Step::Equal(old_end + k, new_end + k)));
+    group_changes(steps)
+}
+
+/// Align `old[olds]` with `new[news]`, appending the steps in order
+fn hirschberg<T: PartialEq>(
+    old: &[T],
+    new: &[T],
+    olds: Range<usize>,
+    news: Range<usize>,
+    steps: &mut Vec<Step>,
+) {
+    if olds.is_empty() {
+        steps.extend(news.map(Step::Inserted));
+        return;
+    }
+    if news.is_empty() {
+        steps.extend(olds.map(Step::Removed));
+        return;
+    }
+    if olds.len() == 1 {
+        let o = olds.start;
+        match news.clone().find(|&n| old[o] == new[n]) {
+            Some(n) => {
+                steps.extend((news.start..n).map(Step::Inserted));
+                steps.push(Step::Equal(o, n));
+                steps.extend((n + 1..news.end).map(Step::Inserted));
+            }
+            None => {
+                steps.push(Step::Removed(o));
+                steps.extend(news.map(Step::Inserted));
+            }
+        }
+        return;
+    }
+
+    // Split `new` where the LCS lengths of the two halves of `old` sum highest
+    let mid = olds.start + olds.len() / 2;
+    let forward = lcs_lengths(&old[olds.start..mid], &new[news.clone()], false);
+    let backward = lcs_lengths(&old[mid..olds.end], &new[news.clone()], true);
+    let split = (0..=news.len())
+        .max_by_key(|&k| (forward[k] + backward[news.len() - k], Reverse(k)))
+        .unwrap_or(0);
+
+    hirschberg(
+        old,
+        new,
+        olds.start..mid,
+        news.start..news.start + split,
+        steps,
+    );
+    hirschberg(old, new, mid..olds.end, news.start + split..news.end, steps);
+}
+
+/// LCS lengths of `old` against every prefix of `new` (suffixes when `reverse`)
+fn lcs_lengths<T: PartialEq>(old: &[T], new: &[T], reverse: bool) -> Vec<usize> {
+    let at = |len: usize, i: usize| if reverse { len - 1 - i } else { i };
+    let mut row = vec![0usize; new.len() + 1];
+    for i in 0..old.len() {
+        let item = &old[at(old.len(), i)];
+        let mut diagonal = 0;
+        for j in 0..new.len() {
+            let above = row[j + 1];
+            row[j + 1] = if *item == new[at(new.len(), j)] {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+/// Reorder each run of changes between two equal steps as removals, then insertions
+fn group_changes(steps: Vec<Step>) -> Vec<Step> {
+    let mut grouped = Vec::with_capacity(steps.len());
+    let mut inserted = Vec::new();
+    for step in steps {
+        match step {
+            Step::Removed(_) => grouped.push(step),
+            Step::Inserted(_) => inserted.push(step),
+            Step::Equal(..) => {
+                grouped.append(&mut inserted);
+                grouped.push(step);
+            }
+        }
+    }
+    grouped.append(&mut inserted);
+    grouped
+}
+
+/// Inline content of blocks that support word-level diffs
+fn inline_content(node: &Node) -> Option<&[Inline]> {
+    match node {
+        Node::Heading { content, .. }
+        | Node::Paragraph { content }
+        | Node::Blockquote { content, .. } => Some(content),
+        _ => None,
+    }
+}
+
+/// Word-level diff of two blocks' plain text (empty if either has no inline content)
+pub(crate) fn text_diff(old: &Node, new: &Node) -> Vec<TextChange> {
+    let (Some(old), Some(new)) = (inline_content(old), inline_content(new)) else {
+        return Vec::new();
+    };
+    let old_text = plain_text(old);
+    let new_text = plain_text(new);
+    let old_words: Vec<&str> = old_text.split_whitespace().collect();
+    let new_words: Vec<&str> = new_text.split_whitespace().collect();
+
+    let mut changes: Vec<TextChange> = Vec::new();
+    for step in lcs(&old_words, &new_words) {
+        let change = match step {
+            Step::Equal(i, _) => TextChange::Unchanged(old_words[i].to_string()),
+            Step::Removed(i) => TextChange::Removed(old_words[i].to_string()),
+            Step::Inserted(j) => TextChange::Inserted(new_words[j].to_string()),
+        };
+        // Merge consecutive words with the same change type
+        match (changes.last_mut(), change) {
+            (Some(TextChange::Unchanged(text)), TextChange::Unchanged(word))
+            | (Some(TextChange::Inserted(text)), TextChange::Inserted(word))
+            | (Some(TextChange::Removed(text)), TextChange::Removed(word)) => {
+                text.push(' ');
+                text.push_str(&word);
+            }
+            (_, change) => changes.push(change),
+        }
+    }
+    changes
+}
@@ -0,0 +1,316 @@
+//! Extracting fenced code blocks and running them as tests.
+
+use crate::ast::Node;
+use crate::node_path::{iter_nodes, NodePath};
+use crate::parse_result::ParseResult;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Counter keeping the directories of concurrent snippet runs apart
+static NEXT_RUN: AtomicUsize = AtomicUsize::new(0);
+
+/// Commands used to run code blocks (`[doctest]` in the config file)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct DocTestConfig {
+    /// Shell command per language; `{file}` is replaced by the snippet's file name
+    ///
+    /// The command runs in a fresh directory holding only the snippet, through `sh -c`
+    /// (`cmd /C` on Windows). Code blocks in languages without a command are skipped.
+    #[serde(default = "default_commands")]
+    pub commands: BTreeMap<String, String>,
+    /// Longest time a snippet may run before it is killed and fails, in milliseconds
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_commands() -> BTreeMap<String, String> {
+    [
+        ("rust", "rustc --edition 2021 -o main {file} && ./main"),
+        ("python", "python3 {file}"),
+        ("javascript", "node {file}"),
+        ("js", "node {file}"),
+        ("sh", "sh {file}"),
+        ("bash", "bash {file}"),
+    ]
+    .into_iter()
+    .map(|(lang, command)| (lang.to_string(), command.to_string()))
+    .collect()
+}
+
+fn default_timeout_ms() -> u64 {
+    60_000
+}
+
+impl Default for DocTestConfig {
+    fn default() -> Self {
+        Self {
+            commands: default_commands(),
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+}
+
+/// A fenced code block found by [`extract_code_blocks`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeSnippet {
+    /// Language, the info string up to the first `,` (`rust` for ```` ```rust,ignore ````)
+    pub lang: String,
+    /// Comma-separated flags after the language (`ignore`)
+    pub flags: Vec<String>,
+    /// Code between the fences
+    pub code: String,
+    /// 1-based source line of the opening fence
+    ///
+    /// For code blocks nested in admonitions, containers, or details blocks, the first
+    /// line of the enclosing top-level block.
+    pub line: usize,
+    /// Position of the code block in the AST
+    pub path: NodePath,
+}
+
+impl CodeSnippet {
+    /// Whether the block opts out of running (an `ignore` flag or `.ignore` class)
+    pub fn is_ignored(&self) -> bool {
+        self.flags.iter().any(|flag| flag == "ignore")
+    }
+
+    /// File extension used when writing the snippet (`rs` for Rust)
+    pub fn extension(&self) -> &str {
+        match self.lang.as_str() {
+            "rust" => "rs",
+            "python" => "py",
+            "javascript" => "js",
+            "typescript" => "ts",
+            "bash" => "sh",
+            "ruby" => "rb",
+            lang => lang,
+        }
+    }
+
+    /// Source written to the snippet's file
+    ///
+    /// As with rustdoc, Rust code without a `fn main` is wrapped in one.
+    pub fn source(&self) -> String {
+        if self.lang == "rust" && !self.code.contains("fn main") {
+            format!("fn main() {{\n{}\n}}\n", self.code)
+        } else {
+            format!("{}\n", self.code)
+        }
+    }
+}
+
+/// Result of running one snippet
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocTestOutcome {
+    /// The command exited successfully
+    Passed,
+    /// The command failed, could not be started, or timed out
+    Failed {
+        /// Exit status and the combined standard output and error of the command
+        output: String,
+    },
+    /// The snippet was not run
+    Skipped {
+        /// Why the snippet was not run
+        reason: String,
+    },
+}
+
+/// A snippet together with the outcome of running it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocTestResult {
+    pub snippet: CodeSnippet,
+    pub outcome: DocTestOutcome,
+}
+
+/// Code blocks with a language, in document order, optionally restricted to `langs`
+///
+/// An empty `langs` selects every language. The `ignore` class of a code block with
+/// an attribute list counts as an `ignore` flag.
+pub fn extract_code_blocks(result: &ParseResult, langs: &[String]) -> Vec<CodeSnippet> {
+    iter_nodes(&result.nodes)
+        .filter_map(|(path, node)| {
+            let Node::CodeBlock {
+                lang: Some(info),
+                code,
+                attributes,
+            } = node
+            else {
+                return None;
+            };
+            let mut parts = info.split(',').map(str::trim);
+            let lang = parts.next().filter(|lang| !lang.is_empty())?;
+            if !langs.is_empty() && !langs.iter().any(|wanted| wanted == lang) {
+                return None;
+            }
+            let mut flags: Vec<String> = parts
+                .filter(|flag| !flag.is_empty())
+                .map(str::to_string)
+                .collect();
+            if attributes.classes.iter().any(|class| class == "ignore") {
+                flags.push("ignore".to_string());
+            }
+            let line = result
+                .spans
+                .get(path.indices()[0])
+                .map_or(1, |span| span.start);
+            Some(CodeSnippet {
+                lang: lang.to_string(),
+                flags,
+                code: code.clone(),
+                line,
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Write each snippet to `dir` as `{stem}-{n}.{extension}`, numbered from 1
+///
+/// Returns the paths written, in the order of `snippets`.
+///
+/// # Errors
+///
+/// Returns an error if the directory or a file cannot be written
+pub fn write_code_blocks(
+    snippets: &[CodeSnippet],
+    dir: &Path,
+    stem: &str,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    snippets
+        .iter()
+        .enumerate()
+        .map(|(idx, snippet)| {
+            let path = dir.join(format!("{}-{}.{}", stem, idx + 1, snippet.extension()));
+            fs::write(&path, snippet.source())?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Run every snippet with the command configured for its language
+///
+/// Ignored snippets and snippets without a command are skipped. Each run gets its own
+/// temporary directory, removed afterwards.
+pub fn run_doc_tests(snippets: &[CodeSnippet], config: &DocTestConfig) -> Vec<DocTestResult> {
+    snippets
+        .iter()
+        .map(|snippet| DocTestResult {
+            snippet: snippet.clone(),
+            outcome: run_snippet(snippet, config),
+        })
+        .collect()
+}
+
+fn run_snippet(snippet: &CodeSnippet, config: &DocTestConfig) -> DocTestOutcome {
+    if snippet.is_ignored() {
+        return DocTestOutcome::Skipped {
+            reason: "ignored".to_string(),
+        };
+    }
+    let Some(command) = config.commands.get(&snippet.lang) else {
+        return DocTestOutcome::Skipped {
+            reason: format!("no command for `{}`", snippet.lang),
+        };
+    };
+
+    let dir = std::env::temp_dir().join(format!(
+        "md-parser-doctest-{}-{}",
+        std::process::id(),
+        NEXT_RUN.fetch_add(1, Ordering::Relaxed)
+    ));
+    let outcome = fs::create_dir_all(&dir)
+        .and_then(|()| {
+            let file = format!("snippet.{}", snippet.extension());
+            fs::write(dir.join(&file), snippet.source())?;
+            run_command(&command.replace("{file}", &file), &dir, config.timeout_ms)
+        })
+        .unwrap_or_else(|e| DocTestOutcome::Failed {
+            output: e.to_string(),
+        });
+    let _ = fs::remove_dir_all(&dir);
+    outcome
+}
+
+/// Run `command` through the shell in `dir`, killing it after `timeout_ms`
+fn run_command(command: &str, dir: &Path, timeout_ms: u64) -> io::Result<DocTestOutcome> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let mut child = shell
+        .arg(command)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain both pipes on other threads so a chatty process cannot block on a full pipe
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|pipe| Box::new(pipe) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|pipe| Box::new(pipe) as Box<dyn Read + Send>),
+    ]
+    .map(|pipe| {
+        thread::spawn(move || {
+            let mut output = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut output);
+            }
+            String::from_utf8_lossy(&output).into_owned()
+        })
+    });
+
+    let timeout = Duration::from_millis(timeout_ms);
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait()? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(DocTestOutcome::Failed {
+                    output: format!("timed out after {} ms", timeout_ms),
+                });
+            }
+            None => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+
+    let output: String = readers
+        .into_iter()
+        .map(|reader| reader.join().unwrap_or_default())
+        .collect();
+    Ok(if status.success() {
+        DocTestOutcome::Passed
+    } else {
+        DocTestOutcome::Failed {
+            output: format!(
+                "`{}` exited with {}\n{}",
+                command,
+                status,
+                output.trim_end()
+            ),
+        }
+    })
+}
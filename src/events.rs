@@ -0,0 +1,389 @@
+//! Event-based (SAX-style) view of a document.
+//!
+//! [`Parser::parse_events`](crate::Parser::parse_events) pushes events to an
+//! [`EventHandler`] as each top-level block is parsed, without building the whole
+//! AST; [`emit_events`] produces the same events from nodes already parsed.
+
+use crate::ast::{AdmonitionKind, Alignment, Attributes, Inline, ListItem, Node, ValidationStatus};
+
+/// A start tag, end tag, or leaf of the document
+///
+/// Every `Start*` event is matched by the corresponding `End*` event, with the
+/// events of the content in between. Text and attributes are borrowed from the node
+/// being emitted, which is dropped afterwards when streaming.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    /// Heading of the given level (1-6); inline events follow
+    StartHeading {
+        level: u8,
+        attributes: &'a Attributes,
+    },
+    EndHeading {
+        level: u8,
+    },
+    StartParagraph,
+    EndParagraph,
+    /// Ordered or unordered list; `StartListItem` events follow
+    StartList {
+        ordered: bool,
+    },
+    EndList {
+        ordered: bool,
+    },
+    /// List item with its task checkbox state; inline events, then events of its block
+    /// content, then any nested list
+    StartListItem {
+        checked: Option<bool>,
+    },
+    EndListItem,
+    CodeBlock {
+        lang: Option<&'a str>,
+        code: &'a str,
+        attributes: &'a Attributes,
+    },
+    MermaidDiagram {
+        diagram: &'a str,
+        name: Option<&'a str>,
+        validation_status: &'a ValidationStatus,
+    },
+    /// `{{diagram:name}}` reference
+    DiagramRef {
+        name: &'a str,
+    },
+    /// Table; a head, its rows, and an optional caption follow
+    StartTable {
+        alignments: &'a [Option<Alignment>],
+    },
+    EndTable,
+    /// Header row; `StartTableCell` events follow
+    StartTableHead,
+    EndTableHead,
+    StartTableRow,
+    EndTableRow,
+    StartTableCell,
+    EndTableCell,
+    /// Table caption (`Table: ...`); inline events follow
+    StartTableCaption,
+    EndTableCaption,
+    /// Blockquote of the given nesting level; inline events follow, then events of its
+    /// block content
+    StartBlockquote {
+        level: u8,
+    },
+    EndBlockquote {
+        level: u8,
+    },
+    HorizontalRule,
+    /// Admonition; its title events, if it has a title, and block events for its body
+    /// follow
+    StartAdmonition {
+        kind: AdmonitionKind,
+    },
+    EndAdmonition {
+        kind: AdmonitionKind,
+    },
+    /// Custom admonition title; inline events follow
+    StartAdmonitionTitle,
+    EndAdmonitionTitle,
+    StartContainer {
+        attributes: &'a Attributes,
+    },
+    EndContainer,
+    /// Collapsible block: the summary, then block events for its body
+    StartDetails,
+    EndDetails,
+    StartSummary,
+    EndSummary,
+    /// Section (`enable_sections`): its heading, then block events for its body
+    StartSection {
+        level: u8,
+        attributes: &'a Attributes,
+    },
+    EndSection {
+        level: u8,
+    },
+    /// Figure: its image paragraph or diagram, then the caption
+    StartFigure {
+        number: Option<usize>,
+    },
+    EndFigure,
+    /// Figure caption (`Figure: ...`); inline events follow
+    StartFigureCaption,
+    EndFigureCaption,
+    /// Comment on lines of its own
+    Comment(&'a str),
+    Text(&'a str),
+    StartBold,
+    EndBold,
+    StartItalic,
+    EndItalic,
+    StartStrikethrough,
+    EndStrikethrough,
+    StartHighlight,
+    EndHighlight,
+    StartSpoiler,
+    EndSpoiler,
+    /// Hashtag without the `#`
+    Hashtag(&'a str),
+    /// Mentioned user without the `@`
+    Mention(&'a str),
+    StartLink {
+        url: &'a str,
+    },
+    EndLink,
+    Image {
+        alt: &'a str,
+        url: &'a str,
+        attributes: &'a Attributes,
+    },
+    StartSpan {
+        attributes: &'a Attributes,
+    },
+    EndSpan,
+    /// Inline code
+    Code(&'a str),
+    /// Comment inside text
+    InlineComment(&'a str),
+    LineBreak,
+    StartDirective {
+        name: &'a str,
+        attributes: &'a Attributes,
+    },
+    EndDirective,
+}
+
+/// Receiver of parse events
+///
+/// Closures taking an [`Event`] are handlers.
+pub trait EventHandler {
+    /// Handle the next event
+    fn event(&mut self, event: Event<'_>);
+}
+
+impl<F: FnMut(Event<'_>)> EventHandler for F {
+    fn event(&mut self, event: Event<'_>) {
+        self(event)
+    }
+}
+
+/// Push the events of `nodes` to `handler`, in document order
+pub fn emit_events(nodes: &[Node], handler: &mut impl EventHandler) {
+    for node in nodes {
+        emit_node(node, handler);
+    }
+}
+
+pub(crate) fn emit_node(node: &Node, handler: &mut impl EventHandler) {
+    match node {
+        Node::Heading {
+            level,
+            content,
+            attributes,
+        } => emit_heading(*level, content, attributes, handler),
+        Node::Paragraph { content } => {
+            handler.event(Event::StartParagraph);
+            emit_inlines(content, handler);
+            handler.event(Event::EndParagraph);
+        }
+        Node::UnorderedList { items } => emit_list(items, false, handler),
+        Node::OrderedList { items } => emit_list(items, true, handler),
+        Node::CodeBlock {
+            lang,
+            code,
+            attributes,
+        } => handler.event(Event::CodeBlock {
+            lang: lang.as_deref(),
+            code,
+            attributes,
+        }),
+        Node::MermaidDiagram {
+            diagram,
+            name,
+            validation_status,
+            ..
+        } => handler.event(Event::MermaidDiagram {
+            diagram,
+            name: name.as_deref(),
+            validation_status,
+        }),
+        Node::DiagramRef { name, .. } => handler.event(Event::DiagramRef { name }),
+        Node::Table {
+            headers,
+            rows,
+            alignments,
+            caption,
+            ..
+        } => {
+            handler.event(Event::StartTable { alignments });
+            handler.event(Event::StartTableHead);
+            emit_cells(headers, handler);
+            handler.event(Event::EndTableHead);
+            for row in rows {
+                handler.event(Event::StartTableRow);
+                emit_cells(row, handler);
+                handler.event(Event::EndTableRow);
+            }
+            if let Some(caption) = caption {
+                handler.event(Event::StartTableCaption);
+                emit_inlines(caption, handler);
+                handler.event(Event::EndTableCaption);
+            }
+            handler.event(Event::EndTable);
+        }
+        Node::Blockquote {
+            level,
+            content,
+            blocks,
+        } => {
+            handler.event(Event::StartBlockquote { level: *level });
+            emit_inlines(content, handler);
+            emit_events(blocks, handler);
+            handler.event(Event::EndBlockquote { level: *level });
+        }
+        Node::HorizontalRule { .. } => handler.event(Event::HorizontalRule),
+        Node::Admonition {
+            kind,
+            title,
+            content,
+        } => {
+            handler.event(Event::StartAdmonition { kind: *kind });
+            if let Some(title) = title {
+                handler.event(Event::StartAdmonitionTitle);
+                emit_inlines(title, handler);
+                handler.event(Event::EndAdmonitionTitle);
+            }
+            emit_events(content, handler);
+            handler.event(Event::EndAdmonition { kind: *kind });
+        }
+        Node::Container {
+            attributes,
+            children,
+        } => {
+            handler.event(Event::StartContainer { attributes });
+            emit_events(children, handler);
+            handler.event(Event::EndContainer);
+        }
+        Node::Details { summary, content } => {
+            handler.event(Event::StartDetails);
+            handler.event(Event::StartSummary);
+            emit_inlines(summary, handler);
+            handler.event(Event::EndSummary);
+            emit_events(content, handler);
+            handler.event(Event::EndDetails);
+        }
+        Node::Section {
+            level,
+            heading,
+            attributes,
+            children,
+        } => {
+            handler.event(Event::StartSection {
+                level: *level,
+                attributes,
+            });
+            emit_heading(*level, heading, attributes, handler);
+            emit_events(children, handler);
+            handler.event(Event::EndSection { level: *level });
+        }
+        Node::Figure {
+            content,
+            caption,
+            number,
+        } => {
+            handler.event(Event::StartFigure { number: *number });
+            emit_events(content, handler);
+            handler.event(Event::StartFigureCaption);
+            emit_inlines(caption, handler);
+            handler.event(Event::EndFigureCaption);
+            handler.event(Event::EndFigure);
+        }
+        Node::Comment { content } => handler.event(Event::Comment(content)),
+    }
+}
+
+fn emit_heading(
+    level: u8,
+    content: &[Inline],
+    attributes: &Attributes,
+    handler: &mut impl EventHandler,
+) {
+    handler.event(Event::StartHeading { level, attributes });
+    emit_inlines(content, handler);
+    handler.event(Event::EndHeading { level });
+}
+
+fn emit_list(items: &[ListItem], ordered: bool, handler: &mut impl EventHandler) {
+    handler.event(Event::StartList { ordered });
+    for item in items {
+        handler.event(Event::StartListItem {
+            checked: item.checked,
+        });
+        emit_inlines(&item.content, handler);
+        emit_events(&item.blocks, handler);
+        if !item.children.is_empty() {
+            emit_list(&item.children, ordered, handler);
+        }
+        handler.event(Event::EndListItem);
+    }
+    handler.event(Event::EndList { ordered });
+}
+
+fn emit_cells(cells: &[Vec<Inline>], handler: &mut impl EventHandler) {
+    for cell in cells {
+        handler.event(Event::StartTableCell);
+        emit_inlines(cell, handler);
+        handler.event(Event::EndTableCell);
+    }
+}
+
+fn emit_inlines(inlines: &[Inline], handler: &mut impl EventHandler) {
+    for inline in inlines {
+        emit_inline(inline, handler);
+    }
+}
+
+fn emit_inline(inline: &Inline, handler: &mut impl EventHandler) {
+    let (start, content, end) = match inline {
+        Inline::Text { content } => return handler.event(Event::Text(content)),
+        Inline::Hashtag { tag } => return handler.event(Event::Hashtag(tag)),
+        Inline::Mention { user } => return handler.event(Event::Mention(user)),
+        Inline::Code { content } => return handler.event(Event::Code(content)),
+        Inline::Comment { content } => return handler.event(Event::InlineComment(content)),
+        Inline::LineBreak => return handler.event(Event::LineBreak),
+        Inline::Image {
+            alt,
+            url,
+            attributes,
+        } => {
+            return handler.event(Event::Image {
+                alt,
+                url,
+                attributes,
+            })
+        }
+        Inline::Bold { content } => (Event::StartBold, content, Event::EndBold),
+        Inline::Italic { content } => (Event::StartItalic, content, Event::EndItalic),
+        Inline::Strikethrough { content } => {
+            (Event::StartStrikethrough, content, Event::EndStrikethrough)
+        }
+        Inline::Highlight { content } => (Event::StartHighlight, content, Event::EndHighlight),
+        Inline::Spoiler { content } => (Event::StartSpoiler, content, Event::EndSpoiler),
+        Inline::Link { text, url } => (Event::StartLink { url }, text, Event::EndLink),
+        Inline::Span {
+            content,
+            attributes,
+        } => (Event::StartSpan { attributes }, content, Event::EndSpan),
+        Inline::Directive {
+            name,
+            content,
+            attributes,
+        } => (
+            Event::StartDirective { name, attributes },
+            content,
+            Event::EndDirective,
+        ),
+    };
+    handler.event(start);
+    emit_inlines(content, handler);
+    handler.event(end);
+}
@@ -0,0 +1,129 @@
+//! Foldable line ranges for editors.
+
+use crate::ast::{LineRange, Node};
+use crate::parse_result::ParseResult;
+use serde::{Deserialize, Serialize};
+
+/// What a [`FoldingRange`] folds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldingKind {
+    /// The YAML frontmatter block
+    Frontmatter,
+    /// A heading and everything up to the next heading of the same or a higher level
+    Section,
+    /// A list, including its nested items
+    List,
+    /// A fenced code block or Mermaid diagram
+    CodeBlock,
+    Table,
+    Blockquote,
+    /// An admonition, details block, container, or figure
+    Block,
+    /// An HTML or Obsidian comment
+    Comment,
+}
+
+/// Lines an editor can fold (1-based, inclusive; the first line stays visible)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoldingRange {
+    pub kind: FoldingKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Foldable ranges of a parsed document, ordered by first line (outer ranges first)
+///
+/// Ranges come from the spans of top-level nodes, so blocks nested in other blocks
+/// fold with their parent only. Blocks on a single line are left out. Sections end at
+/// the last non-blank line before the next heading of the same or a higher level; with
+/// `enable_sections`, only top-level sections have spans and nested ones are not folded.
+pub fn folding_ranges(result: &ParseResult) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+    if let Some(frontmatter) = &result.frontmatter {
+        // The text between the delimiters, plus both `---` lines
+        let lines = frontmatter.lines().count() + 2;
+        ranges.push(FoldingRange {
+            kind: FoldingKind::Frontmatter,
+            start: 1,
+            end: lines,
+        });
+    }
+
+    // Headings whose section is still open: level and first line
+    let mut open: Vec<(u8, usize)> = Vec::new();
+    let mut previous_end = 0;
+    for (node, span) in result.nodes.iter().zip(&result.spans) {
+        if let Node::Heading { level, .. } = node {
+            while open
+                .last()
+                .is_some_and(|(open_level, _)| open_level >= level)
+            {
+                let (_, start) = open.pop().unwrap_or_default();
+                push(&mut ranges, FoldingKind::Section, start, previous_end);
+            }
+            open.push((*level, span.start));
+        } else if let Some(kind) = block_kind(node) {
+            push(&mut ranges, kind, span.start, span.end);
+        }
+        previous_end = span.end;
+    }
+    while let Some((_, start)) = open.pop() {
+        push(&mut ranges, FoldingKind::Section, start, previous_end);
+    }
+
+    ranges.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+    ranges
+}
+
+/// Line ranges of the sections of a parsed document, one per top-level heading in
+/// document order, as folded by [`folding_ranges`] (a heading with nothing under it
+/// spans its own line)
+pub(crate) fn section_spans(result: &ParseResult) -> Vec<LineRange> {
+    let mut spans: Vec<LineRange> = Vec::new();
+    let mut open: Vec<(u8, usize)> = Vec::new();
+    let mut previous_end = 0;
+    for (node, span) in result.nodes.iter().zip(&result.spans) {
+        if let Node::Heading { level, .. } = node {
+            while open
+                .last()
+                .is_some_and(|(open_level, _)| open_level >= level)
+            {
+                let (_, index) = open.pop().unwrap_or_default();
+                spans[index].end = previous_end;
+            }
+            open.push((*level, spans.len()));
+            spans.push(*span);
+        }
+        previous_end = span.end;
+    }
+    for (_, index) in open {
+        spans[index].end = previous_end;
+    }
+    spans
+}
+
+fn push(ranges: &mut Vec<FoldingRange>, kind: FoldingKind, start: usize, end: usize) {
+    if end > start {
+        ranges.push(FoldingRange { kind, start, end });
+    }
+}
+
+fn block_kind(node: &Node) -> Option<FoldingKind> {
+    match node {
+        Node::Section { .. } => Some(FoldingKind::Section),
+        Node::UnorderedList { .. } | Node::OrderedList { .. } => Some(FoldingKind::List),
+        Node::CodeBlock { .. } | Node::MermaidDiagram { .. } => Some(FoldingKind::CodeBlock),
+        Node::Table { .. } => Some(FoldingKind::Table),
+        Node::Blockquote { .. } => Some(FoldingKind::Blockquote),
+        Node::Admonition { .. }
+        | Node::Details { .. }
+        | Node::Container { .. }
+        | Node::Figure { .. } => Some(FoldingKind::Block),
+        Node::Comment { .. } => Some(FoldingKind::Comment),
+        Node::Heading { .. }
+        | Node::Paragraph { .. }
+        | Node::HorizontalRule { .. }
+        | Node::DiagramRef { .. } => None,
+    }
+}
@@ -0,0 +1,217 @@
+//! Normalizing Markdown sources (`md-parser fmt`).
+
+use crate::ast::{Inline, ListItem, Node, ParseError};
+use crate::config::ParserConfig;
+use crate::node_path::visit_nodes_mut;
+use crate::parser::{detect_frontmatter, Parser};
+use crate::renderer::{render_markdown, MarkdownStyle};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+/// Marker written before unordered list items
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ListMarker {
+    /// `- item`
+    #[default]
+    Dash,
+    /// `* item`
+    Asterisk,
+    /// `+ item`
+    Plus,
+}
+
+impl ListMarker {
+    pub(crate) fn as_char(self) -> char {
+        match self {
+            ListMarker::Dash => '-',
+            ListMarker::Asterisk => '*',
+            ListMarker::Plus => '+',
+        }
+    }
+}
+
+/// Fence written around code blocks and Mermaid diagrams
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FenceStyle {
+    /// ```` ``` ````
+    #[default]
+    Backtick,
+    /// `~~~`, which the parser reads when `code_fence_pattern` is `~~~`
+    Tilde,
+}
+
+impl FenceStyle {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            FenceStyle::Backtick => "```",
+            FenceStyle::Tilde => "~~~",
+        }
+    }
+}
+
+/// Markup of level-1 and level-2 headings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadingMarkup {
+    /// `# Title`
+    #[default]
+    Atx,
+    /// `Title` underlined with `===` or `---`; deeper headings stay ATX
+    Setext,
+}
+
+/// Formatting settings (`[format]` in the config file)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct FormatConfig {
+    /// Marker of unordered list items
+    #[serde(default)]
+    pub list_marker: ListMarker,
+    /// Fence of code blocks
+    #[serde(default)]
+    pub fence: FenceStyle,
+    /// Markup of level-1 and level-2 headings
+    #[serde(default)]
+    pub headings: HeadingMarkup,
+    /// Pad table cells so the pipes of every row line up
+    #[serde(default = "default_true")]
+    pub pad_tables: bool,
+    /// Column at which paragraphs, list items, and quotes are wrapped; 0 puts each on one line
+    #[serde(default = "default_wrap_width")]
+    pub wrap_width: usize,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_wrap_width() -> usize {
+    80
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            list_marker: ListMarker::default(),
+            fence: FenceStyle::default(),
+            headings: HeadingMarkup::default(),
+            pad_tables: default_true(),
+            wrap_width: default_wrap_width(),
+        }
+    }
+}
+
+/// Errors that can occur while formatting a document
+#[derive(Debug, Clone)]
+pub enum FormatError {
+    /// The document failed to parse
+    Parse(ParseError),
+    /// The formatted document would parse differently from the original, starting with
+    /// the block at `line`, so it is left as written
+    Unstable { line: usize },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Parse(error) => write!(f, "{}", error),
+            FormatError::Unstable { line } => write!(
+                f,
+                "line {}: formatting would change how the document parses; left unformatted",
+                line
+            ),
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+impl From<ParseError> for FormatError {
+    fn from(error: ParseError) -> Self {
+        FormatError::Parse(error)
+    }
+}
+
+/// `source` rewritten in the style of `config`
+///
+/// The document is parsed with `parser` and written back from its AST, with
+/// frontmatter kept as written. Includes, variables, CSV tables, and sections are not
+/// expanded, so `{{...}}` placeholders survive. The result is parsed again and compared
+/// with the original; if the two differ (for example when inline `%%{init}%%` Mermaid
+/// settings would be dropped), [`FormatError::Unstable`] is returned instead.
+///
+/// # Errors
+///
+/// Returns `FormatError` if the document fails to parse or cannot be formatted without
+/// changing it
+pub fn format_markdown(
+    source: &str,
+    parser: &ParserConfig,
+    config: &FormatConfig,
+) -> Result<String, FormatError> {
+    let parser = ParserConfig {
+        enable_includes: false,
+        enable_variables: false,
+        enable_csv_tables: false,
+        enable_sections: false,
+        ..parser.clone()
+    };
+    let original = Parser::with_config(source.to_string(), parser.clone())?.parse_full()?;
+    let style = MarkdownStyle::formatted(parser.list_indent, config);
+    let body = render_markdown(&original.nodes, &style);
+
+    let lines: Vec<&str> = source.lines().collect();
+    let mut formatted = match detect_frontmatter(&lines) {
+        Some(end) => format!("{}\n", lines[..end].join("\n")),
+        None => String::new(),
+    };
+    if !formatted.is_empty() && !body.is_empty() {
+        formatted.push('\n');
+    }
+    formatted.push_str(&body);
+
+    let reparsed = Parser::with_config(formatted.clone(), parser)?.parse()?;
+    let (before, after) = (normalized(&original.nodes), normalized(&reparsed));
+    if let Some(idx) = (0..before.len().max(after.len())).find(|&i| before.get(i) != after.get(i)) {
+        let line = original
+            .spans
+            .get(idx.min(original.spans.len().saturating_sub(1)))
+            .map_or(1, |span| span.start);
+        return Err(FormatError::Unstable { line });
+    }
+    Ok(formatted)
+}
+
+/// Copy of `nodes` without the source lines some nodes record, and with the text runs
+/// of list items merged, which the parser keeps apart for each line of an item
+fn normalized(nodes: &[Node]) -> Vec<Node> {
+    let mut nodes = nodes.to_vec();
+    visit_nodes_mut(&mut nodes, |_, node| match node {
+        Node::DiagramRef { span, .. }
+        | Node::Table { span, .. }
+        | Node::HorizontalRule { span } => *span = None,
+        Node::UnorderedList { items } | Node::OrderedList { items } => merge_item_text(items),
+        _ => {}
+    });
+    nodes
+}
+
+fn merge_item_text(items: &mut [ListItem]) {
+    for item in items {
+        let mut merged: Vec<Inline> = Vec::with_capacity(item.content.len());
+        for inline in item.content.drain(..) {
+            match (merged.last_mut(), inline) {
+                (Some(Inline::Text { content: last }), Inline::Text { content }) => {
+                    last.push_str(&content)
+                }
+                (_, inline) => merged.push(inline),
+            }
+        }
+        item.content = merged;
+        item.blocks = normalized(&item.blocks);
+        merge_item_text(&mut item.children);
+    }
+}
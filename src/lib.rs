@@ -4,14 +4,124 @@
 //! It provides special handling for Mermaid diagrams, distinguishing them from
 //! standard code blocks.
 
+mod accessibility;
+mod annotations;
+#[cfg(feature = "arena")]
+mod arena;
 mod ast;
+#[cfg(feature = "async")]
+mod blocking;
+mod cancel;
+mod changelog;
+mod completion;
 mod config;
+mod definitions;
+mod diagnostics;
+mod diff;
+mod doctest;
+mod events;
+mod folding;
+mod format;
+mod line_index;
+mod lint;
+#[cfg(feature = "lsp")]
+mod lsp;
+mod memory;
+mod mermaid_cli;
+mod mermaid_export;
+mod node_path;
+mod notebook;
+mod outline;
+mod parse_result;
 mod parser;
+mod project;
+#[cfg(feature = "pulldown")]
+mod pulldown;
 mod renderer;
+mod report;
+mod schema;
+mod search_index;
+mod select;
+mod site;
+mod slug;
+mod stable_hash;
+mod tags;
+mod tasks;
+mod transform;
 
-pub use ast::{Alignment, Inline, MermaidConfig, Node, ParseError, Span, ValidationStatus};
-pub use config::{Config, MermaidParserConfig, OutputConfig, ParserConfig, RendererConfig};
-pub use parser::Parser;
+pub use accessibility::{audit_accessibility, AccessibilityIssue, AccessibilityRule};
+pub use annotations::{annotate, Annotations};
+#[cfg(feature = "arena")]
+pub use arena::{
+    from_arena_ast, to_arena_ast, ArenaAttributes, ArenaInline, ArenaListItem, ArenaMermaidConfig,
+    ArenaNode, ArenaValidationStatus, Bump,
+};
+pub use ast::{
+    plain_text, AdmonitionKind, Alignment, Attributes, CompactString, Inline, LimitKind, LineRange,
+    ListItem, MermaidConfig, Node, ParseError, ParseWarning, Severity, Span, ValidationStatus,
+    WarningCode, HIGHLIGHT_ATTRIBUTE, LINE_NUMBERS_CLASS,
+};
+pub use cancel::CancellationToken;
+pub use changelog::{
+    ChangeCategory, Changelog, ChangelogCategory, ChangelogEntry, ChangelogVersion,
+};
+pub use completion::{completion_items, CompletionItem, CompletionKind};
+pub use config::{
+    CommentMode, Config, CopyButton, CustomRenderer, ExternalLinks, HeadingChecks, LineEnding,
+    MermaidParserConfig, MermaidScript, NodeRenderer, OutputConfig, OutputStyle, ParseLimits,
+    ParserConfig, RendererConfig, RendererStrings, UrlRewriter,
+};
+pub use definitions::{Definition, DefinitionKind, DefinitionMap, DefinitionReference};
+pub use diagnostics::{Diagnostic, DiagnosticSource, Diagnostics};
+pub use diff::{ast_diff, diff_to_html, DocChange, TextChange};
+pub use doctest::{
+    extract_code_blocks, run_doc_tests, write_code_blocks, CodeSnippet, DocTestConfig,
+    DocTestOutcome, DocTestResult,
+};
+pub use events::{emit_events, Event, EventHandler};
+pub use folding::{folding_ranges, FoldingKind, FoldingRange};
+pub use format::{
+    format_markdown, FenceStyle, FormatConfig, FormatError, HeadingMarkup, ListMarker,
+};
+pub use line_index::LineIndex;
+pub use lint::{apply_fixes, lint, HeadingStyle, LintConfig, LintDiagnostic, LintFix, LintRule};
+#[cfg(feature = "lsp")]
+pub use lsp::{run_language_server, LanguageServer};
+pub use memory::{memory_usage, shrink_ast, MemoryUsage};
+pub use mermaid_export::{diagram_exports, DiagramExport, DiagramFormat};
+pub use node_path::{iter_nodes, node_at, node_at_mut, visit_nodes_mut, NodeIter, NodePath};
+pub use notebook::{
+    notebook_to_markdown, parse_notebook, NotebookConfig, NotebookError, OUTPUT_CLASS,
+};
+pub use outline::{Outline, OutlineSection};
+pub use parse_result::{ParseResult, ParseStats};
+pub use parser::{BlockSyntax, Parser};
+pub use project::{
+    collect_markdown_files, AnchorCollision, BrokenLink, BrokenLinkReason, Project,
+    ProjectDocument, ProjectError, SlugStrategy,
+};
+#[cfg(feature = "pulldown")]
+pub use pulldown::{from_pulldown_events, to_pulldown_events};
+#[cfg(feature = "pulldown")]
+pub use pulldown_cmark;
+#[cfg(feature = "docx")]
+pub use renderer::DocxOptions;
+pub use renderer::{
+    escape_html, to_markdown, EpubChapter, EpubOptions, FeedItem, FeedMermaid, FeedOptions,
+    Renderer, SlideOptions,
+};
+pub use report::{BuildReport, FileReport};
+pub use schema::{json_schema, AstDocument, SCHEMA_VERSION};
+pub use search_index::{SearchIndex, SearchRecord};
+pub use select::Select;
+pub use site::{navigation, sitemap_xml, NavEntry, SitePage};
+pub use slug::{slugify, SlugGenerator};
+pub use tags::{extract_hashtags, extract_mentions};
+pub use tasks::{extract_tasks, set_task_state, Task, TaskStats};
+pub use transform::{
+    filter_audience, nest_sections, normalize_heading_levels, number_figures, number_headings,
+    number_tables, resolve_diagram_references, shift_heading_levels, AUDIENCE_ATTRIBUTE,
+};
 
 use std::error::Error;
 
@@ -22,9 +132,9 @@ impl Parser {
     ///
     /// Returns `ParseError` if parsing fails, or `Box<dyn Error>` if template loading fails
     pub fn to_html(&mut self) -> Result<String, Box<dyn Error>> {
-        let ast = self.parse()?;
+        let ast = &self.parsed(None)?.nodes;
         let renderer_config = RendererConfig::default();
-        renderer::render_to_html(&ast, &renderer_config)
+        renderer::render_to_html(ast, &renderer_config)
     }
 
     /// Generate a complete HTML document from the AST using custom renderer config
@@ -36,8 +146,31 @@ impl Parser {
         &mut self,
         renderer_config: &RendererConfig,
     ) -> Result<String, Box<dyn Error>> {
-        let ast = self.parse()?;
-        renderer::render_to_html(&ast, renderer_config)
+        let ast = &self.parsed(None)?.nodes;
+        renderer::render_to_html(ast, renderer_config)
+    }
+
+    /// Generate a complete HTML document without blocking the calling task
+    ///
+    /// Parsing and rendering (including Mermaid CLI runs and template reads) happen on a
+    /// helper thread, like [`Parser::parse_async`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails, or `Box<dyn Error>` if template loading fails
+    #[cfg(feature = "async")]
+    pub async fn to_html_async(
+        &mut self,
+        renderer_config: &RendererConfig,
+    ) -> Result<String, Box<dyn Error>> {
+        let ast = self.parse_async().await?;
+        let renderer_config = renderer_config.clone();
+        // `Box<dyn Error>` cannot leave the thread, so template errors travel as text
+        blocking::spawn(move || {
+            renderer::render_to_html(&ast, &renderer_config).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(Into::into)
     }
 
     /// Save the HTML output to a file using default renderer config
@@ -46,9 +179,9 @@ impl Parser {
     ///
     /// Returns `ParseError` if parsing fails, or `Box<dyn Error>` if file operations fail
     pub fn to_html_file(&mut self, filename: &str) -> Result<(), Box<dyn Error>> {
-        let ast = self.parse()?;
+        let ast = &self.parsed(None)?.nodes;
         let renderer_config = RendererConfig::default();
-        renderer::render_to_html_file(&ast, filename, &renderer_config)
+        renderer::render_to_html_file(ast, filename, &renderer_config)
     }
 
     /// Save the HTML output to a file using custom renderer config
@@ -61,7 +194,157 @@ impl Parser {
         filename: &str,
         renderer_config: &RendererConfig,
     ) -> Result<(), Box<dyn Error>> {
-        let ast = self.parse()?;
-        renderer::render_to_html_file(&ast, filename, renderer_config)
+        let ast = &self.parsed(None)?.nodes;
+        renderer::render_to_html_file(ast, filename, renderer_config)
+    }
+
+    /// Convert the document to AsciiDoc
+    ///
+    /// Level-1 headings become `==` sections, fenced code becomes `[source]` blocks and
+    /// Mermaid diagrams become asciidoctor-diagram `[mermaid]` blocks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails
+    pub fn to_asciidoc(&mut self) -> Result<String, ParseError> {
+        let ast = &self.parsed(None)?.nodes;
+        Ok(renderer::render_asciidoc(ast))
+    }
+
+    /// Convert the document to reStructuredText
+    ///
+    /// Tables become `list-table` directives and Mermaid diagrams use the
+    /// sphinxcontrib-mermaid `.. mermaid::` directive. Strikethrough has no reST
+    /// equivalent and is rendered as plain text.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails
+    pub fn to_rst(&mut self) -> Result<String, ParseError> {
+        let ast = &self.parsed(None)?.nodes;
+        Ok(renderer::render_rst(ast))
+    }
+
+    /// Convert the document back to Markdown
+    ///
+    /// See [`to_markdown`] for what the output preserves.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails
+    pub fn to_markdown(&mut self) -> Result<String, ParseError> {
+        let list_indent = self.config.list_indent;
+        let ast = &self.parsed(None)?.nodes;
+        Ok(renderer::render_markdown(
+            ast,
+            &renderer::MarkdownStyle::plain(list_indent),
+        ))
+    }
+
+    /// Package the document as an EPUB book, one chapter per level-1 heading
+    ///
+    /// Relative image paths are resolved against `resource_dir` and embedded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails, or `Box<dyn Error>` if an image cannot be read
+    pub fn to_epub(
+        &mut self,
+        options: &EpubOptions,
+        resource_dir: impl AsRef<std::path::Path>,
+        renderer_config: &RendererConfig,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let ast = &self.parsed(None)?.nodes;
+        let chapters = EpubChapter::split_document(ast, &options.title, resource_dir);
+        renderer::render_epub(&chapters, options, renderer_config)
+    }
+
+    /// Render the document as a Word (`.docx`) document
+    ///
+    /// Relative image paths are resolved against `resource_dir` and embedded; Mermaid
+    /// diagrams are embedded as images when `options.render_mermaid` is set and the
+    /// Mermaid CLI is installed, and shown as code otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails, or `Box<dyn Error>` if an image cannot be read
+    #[cfg(feature = "docx")]
+    pub fn to_docx(
+        &mut self,
+        options: &DocxOptions,
+        resource_dir: impl AsRef<std::path::Path>,
+        renderer_config: &RendererConfig,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let ast = &self.parsed(None)?.nodes;
+        renderer::render_docx(ast, options, resource_dir.as_ref(), renderer_config)
+    }
+
+    /// Render the document as a Reveal.js slide deck (a standalone HTML page)
+    ///
+    /// Slides are split at `---` rules and level-2 (or higher) headings, as set in `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails
+    pub fn to_slides(
+        &mut self,
+        options: &SlideOptions,
+        renderer_config: &RendererConfig,
+    ) -> Result<String, ParseError> {
+        let ast = &self.parsed(None)?.nodes;
+        Ok(renderer::render_slides(ast, options, renderer_config))
+    }
+
+    /// Render the document as a feed entry, for RSS `<content:encoded>` or Atom `<content>`
+    ///
+    /// See [`FeedItem::from_nodes`] for how the HTML is made feed-safe; set
+    /// `renderer_config.base_url` so relative URLs become absolute.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails
+    pub fn to_feed_item(
+        &mut self,
+        options: &FeedOptions,
+        renderer_config: &RendererConfig,
+    ) -> Result<FeedItem, ParseError> {
+        let ast = &self.parsed(None)?.nodes;
+        Ok(FeedItem::from_nodes(ast, options, renderer_config))
+    }
+}
+
+impl Project {
+    /// Package every document of the project as an EPUB book, one chapter per document
+    ///
+    /// # Errors
+    ///
+    /// Returns `Box<dyn Error>` if an image cannot be read
+    pub fn to_epub(
+        &self,
+        options: &EpubOptions,
+        renderer_config: &RendererConfig,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let chapters: Vec<EpubChapter> = self
+            .documents()
+            .iter()
+            .map(|doc| EpubChapter {
+                title: doc
+                    .nodes
+                    .iter()
+                    .find_map(|node| match node {
+                        Node::Heading { content, .. }
+                        | Node::Section {
+                            heading: content, ..
+                        } => Some(plain_text(content)),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| doc.path.to_string_lossy().into_owned()),
+                nodes: doc.nodes.clone(),
+                resource_dir: self
+                    .root()
+                    .join(doc.path.parent().unwrap_or(std::path::Path::new(""))),
+            })
+            .collect();
+        renderer::render_epub(&chapters, options, renderer_config)
     }
 }
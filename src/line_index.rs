@@ -0,0 +1,104 @@
+//! Conversion between byte offsets and line/column positions.
+
+use crate::ast::Span;
+use std::ops::Range;
+
+/// Line start offsets of a text, for converting byte offsets to [`Span`]s and back in
+/// O(log n)
+///
+/// Lines end at `\n` or `\r\n`. Lines and columns are 1-based and columns count
+/// characters, as in the spans of parse warnings.
+///
+/// ```
+/// use md_parser::{LineIndex, Span};
+///
+/// let index = LineIndex::new("# Title\n\nSome *text*\n");
+/// let span = index.span(14);
+/// assert_eq!(span, Span { line: 3, column: Some(6) });
+/// assert_eq!(index.offset(&span), Some(14));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    /// Byte offset of the start of each line
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(pos, _)| pos + 1))
+            .collect();
+        Self { text, starts }
+    }
+
+    /// Number of lines; text ending in a newline has an empty last line
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Line and column of the character at byte `offset`
+    ///
+    /// Offsets inside a character are moved back to its start, and offsets past the
+    /// end of the text to the end.
+    pub fn span(&self, offset: usize) -> Span {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.starts.partition_point(|&start| start <= offset);
+        let start = self.starts[line - 1];
+        Span {
+            line,
+            column: Some(self.text[start..offset].chars().count() + 1),
+        }
+    }
+
+    /// Byte offset of a position, or of the start of its line when it has no column
+    ///
+    /// Returns `None` if the line does not exist or the column lies past the end of the
+    /// line (the column just after the last character is the line end).
+    pub fn offset(&self, span: &Span) -> Option<usize> {
+        let range = self.line_range(span.line)?;
+        let Some(column) = span.column else {
+            return Some(range.start);
+        };
+        let line = &self.text[range.clone()];
+        let skip = column.checked_sub(1)?;
+        match line.char_indices().nth(skip) {
+            Some((pos, _)) => Some(range.start + pos),
+            None => (line.chars().count() == skip).then_some(range.end),
+        }
+    }
+
+    /// Byte range of a 1-based line, without its line ending
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .starts
+            .get(line)
+            .map_or(self.text.len(), |next| next - 1);
+        let end = if self.text[start..end].ends_with('\r') {
+            end - 1
+        } else {
+            end
+        };
+        Some(start..end)
+    }
+
+    /// Text of a 1-based line, without its line ending
+    pub fn line(&self, line: usize) -> Option<&'a str> {
+        self.line_range(line).map(|range| &self.text[range])
+    }
+
+    /// Give `span` the column of the first non-blank character of its line, if it has
+    /// no column and the line exists
+    pub(crate) fn fill_column(&self, span: &mut Span) {
+        if span.column.is_some() {
+            return;
+        }
+        if let Some(line) = self.line(span.line) {
+            span.column = Some(line.chars().take_while(|c| c.is_whitespace()).count() + 1);
+        }
+    }
+}
@@ -0,0 +1,636 @@
+//! Style checks on Markdown sources (`md-parser lint`).
+
+use crate::ast::{ParseError, Span};
+use crate::diagnostics;
+use crate::slug::slugify;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A style rule checked by [`lint`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// Heading not in the configured `heading_style`
+    HeadingCapitalization,
+    /// Spaces or tabs at the end of a line
+    TrailingWhitespace,
+    /// `http(s)://` URL in running text instead of a link
+    BareUrl,
+    /// Heading with the same text as an earlier one, which makes its anchor ambiguous
+    DuplicateHeading,
+    /// Image without alt text (`![](chart.png)`)
+    MissingAltText,
+    /// List item marker (`-`, `*`, `+`) other than the first one used in the document
+    ListMarker,
+    /// Line longer than `max_line_length`
+    LineLength,
+}
+
+impl LintRule {
+    /// Every rule, in declaration order
+    pub const ALL: &'static [LintRule] = &[
+        LintRule::HeadingCapitalization,
+        LintRule::TrailingWhitespace,
+        LintRule::BareUrl,
+        LintRule::DuplicateHeading,
+        LintRule::MissingAltText,
+        LintRule::ListMarker,
+        LintRule::LineLength,
+    ];
+
+    /// Kebab-case name used in configuration and reports (`bare-url`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LintRule::HeadingCapitalization => "heading-capitalization",
+            LintRule::TrailingWhitespace => "trailing-whitespace",
+            LintRule::BareUrl => "bare-url",
+            LintRule::DuplicateHeading => "duplicate-heading",
+            LintRule::MissingAltText => "missing-alt-text",
+            LintRule::ListMarker => "list-marker",
+            LintRule::LineLength => "line-length",
+        }
+    }
+
+    /// Look up a rule by its kebab-case name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|rule| rule.as_str() == name)
+    }
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Capitalization expected of headings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadingStyle {
+    /// Only the first word capitalized (`Getting started`)
+    #[default]
+    Sentence,
+    /// Every word capitalized except articles, conjunctions, and short prepositions
+    /// (`Getting Started with the CLI`)
+    Title,
+}
+
+/// Lint settings (`[lint]` in the config file)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct LintConfig {
+    /// Rules that are not checked
+    #[serde(default)]
+    pub disable: Vec<LintRule>,
+    /// Capitalization expected of headings
+    #[serde(default)]
+    pub heading_style: HeadingStyle,
+    /// Words whose capitalization is always kept (`Rust`, `Markdown`)
+    ///
+    /// Acronyms and words with inner capitals (`API`, `GitHub`) are kept without listing them.
+    #[serde(default)]
+    pub proper_nouns: Vec<String>,
+    /// Longest line allowed, in characters; code blocks, tables, and lines whose end is a
+    /// single long word such as a URL are exempt
+    #[serde(default = "default_max_line_length")]
+    pub max_line_length: usize,
+}
+
+fn default_max_line_length() -> usize {
+    100
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            disable: Vec::new(),
+            heading_style: HeadingStyle::default(),
+            proper_nouns: Vec::new(),
+            max_line_length: default_max_line_length(),
+        }
+    }
+}
+
+impl LintConfig {
+    fn checks(&self, rule: LintRule) -> bool {
+        !self.disable.contains(&rule)
+    }
+}
+
+/// Replacement for one source line that resolves a diagnostic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintFix {
+    /// 1-based line to replace
+    pub line: usize,
+    /// New text of the line, without the line ending
+    pub replacement: String,
+}
+
+/// A style problem found by [`lint`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintDiagnostic {
+    pub rule: LintRule,
+    /// Human-readable description
+    pub message: String,
+    /// Where the problem is
+    pub span: Span,
+    /// Suggested fix, for rules that have one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<LintFix>,
+}
+
+impl LintDiagnostic {
+    /// Format the diagnostic with the offending line of `source`, like
+    /// [`ParseWarning::display_with_source`](crate::ParseWarning::display_with_source)
+    pub fn display_with_source(&self, source: &str) -> String {
+        let note = self
+            .fix
+            .as_ref()
+            .map(|fix| format!("fixable: `{}`", fix.replacement.trim()));
+        diagnostics::render(
+            "lint",
+            &format!("{} [{}]", self.message, self.rule),
+            Some(&self.span),
+            source,
+            note.as_deref(),
+        )
+    }
+}
+
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.span, self.message, self.rule)
+    }
+}
+
+/// Check `source` against the enabled rules
+///
+/// Diagnostics are ordered by line and column. Frontmatter and fenced code blocks are
+/// skipped, as are code spans, link destinations, and HTML tags within lines.
+///
+/// # Errors
+///
+/// Returns `ParseError::RegexCompilationError` if a rule's pattern fails to compile
+pub fn lint(source: &str, config: &LintConfig) -> Result<Vec<LintDiagnostic>, ParseError> {
+    let patterns = LintPatterns::new()?;
+    let mut linter = Linter {
+        config,
+        patterns: &patterns,
+        diagnostics: Vec::new(),
+        headings: HashMap::new(),
+        list_marker: None,
+    };
+    let mut fence: Option<(char, usize)> = None;
+    let frontmatter_end = frontmatter_end(source);
+    for (idx, line) in source.lines().enumerate() {
+        let number = idx + 1;
+        if number <= frontmatter_end {
+            continue;
+        }
+        let trimmed = line.trim_start();
+        let run = trimmed
+            .chars()
+            .take_while(|&c| c == '`' || c == '~')
+            .count();
+        match fence {
+            Some((marker, len)) => {
+                if run >= len && trimmed.starts_with(marker) && trimmed[run..].trim().is_empty() {
+                    fence = None;
+                }
+                continue;
+            }
+            None if run >= 3 => {
+                let marker = trimmed.chars().next().unwrap_or('`');
+                if trimmed.chars().take(run).all(|c| c == marker) {
+                    fence = Some((marker, run));
+                    linter.check_line_end(line, number);
+                    continue;
+                }
+            }
+            None => {}
+        }
+        linter.check_line(line, number);
+    }
+    let mut diagnostics = linter.diagnostics;
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.span.line, diagnostic.span.column));
+    Ok(diagnostics)
+}
+
+/// `source` with every fix in `diagnostics` applied
+///
+/// When several fixes target the same line, the first one wins; run [`lint`] again to
+/// find what is left.
+pub fn apply_fixes(source: &str, diagnostics: &[LintDiagnostic]) -> String {
+    let mut fixes: HashMap<usize, &str> = HashMap::new();
+    for fix in diagnostics
+        .iter()
+        .filter_map(|diagnostic| diagnostic.fix.as_ref())
+    {
+        fixes.entry(fix.line).or_insert(&fix.replacement);
+    }
+    let mut fixed = String::with_capacity(source.len());
+    for (idx, line) in source.split_inclusive('\n').enumerate() {
+        match fixes.get(&(idx + 1)) {
+            Some(replacement) => {
+                fixed.push_str(replacement);
+                let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                fixed.push_str(ending);
+            }
+            None => fixed.push_str(line),
+        }
+    }
+    fixed
+}
+
+struct Linter<'a> {
+    config: &'a LintConfig,
+    patterns: &'a LintPatterns,
+    diagnostics: Vec<LintDiagnostic>,
+    /// Line of the first heading with each slug
+    headings: HashMap<String, usize>,
+    /// First list marker used in the document
+    list_marker: Option<char>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, rule: LintRule, line: usize, column: usize, message: String) {
+        self.report_fix(rule, line, column, message, None);
+    }
+
+    fn report_fix(
+        &mut self,
+        rule: LintRule,
+        line: usize,
+        column: usize,
+        message: String,
+        replacement: Option<String>,
+    ) {
+        self.diagnostics.push(LintDiagnostic {
+            rule,
+            message,
+            span: Span {
+                line,
+                column: Some(column),
+            },
+            fix: replacement.map(|replacement| LintFix { line, replacement }),
+        });
+    }
+
+    /// Rules that apply to every line, including code fences
+    fn check_line_end(&mut self, line: &str, number: usize) {
+        let content = line.trim_end();
+        if self.config.checks(LintRule::TrailingWhitespace) && content.len() < line.len() {
+            self.report_fix(
+                LintRule::TrailingWhitespace,
+                number,
+                content.chars().count() + 1,
+                "trailing whitespace".to_string(),
+                Some(content.to_string()),
+            );
+        }
+    }
+
+    fn check_line(&mut self, line: &str, number: usize) {
+        let patterns = self.patterns;
+        self.check_line_end(line, number);
+        let protected = protected_ranges(line);
+        let is_protected = |at: usize| protected.iter().any(|range| range.contains(&at));
+
+        if self.config.checks(LintRule::LineLength) {
+            self.check_length(line, number);
+        }
+        if let Some(captures) = patterns.heading.captures(line) {
+            let text = captures.get(2).map_or(0..0, |text| text.range());
+            self.check_heading(line, number, text, &protected);
+        }
+        if let Some(captures) = patterns.list.captures(line) {
+            if !patterns.rule.is_match(line) && self.config.checks(LintRule::ListMarker) {
+                if let Some(marker) = captures.get(2) {
+                    self.check_list_marker(line, number, marker.start());
+                }
+            }
+        }
+        if self.config.checks(LintRule::MissingAltText) {
+            for found in patterns.missing_alt.find_iter(line) {
+                if !is_protected(found.start()) {
+                    self.report(
+                        LintRule::MissingAltText,
+                        number,
+                        column_of(line, found.start()),
+                        "image has no alt text".to_string(),
+                    );
+                }
+            }
+        }
+        if self.config.checks(LintRule::BareUrl) && !patterns.reference.is_match(line) {
+            for found in patterns.url.find_iter(line) {
+                let before = line[..found.start()].chars().next_back();
+                if is_protected(found.start())
+                    || matches!(before, Some('(' | '<' | '[' | '"' | '\'' | '='))
+                {
+                    continue;
+                }
+                let url = found
+                    .as_str()
+                    .trim_end_matches(['.', ',', ';', ':', '!', '?']);
+                let end = found.start() + url.len();
+                let replacement = format!(
+                    "{}[{}]({}){}",
+                    &line[..found.start()],
+                    url,
+                    url,
+                    &line[end..]
+                );
+                self.report_fix(
+                    LintRule::BareUrl,
+                    number,
+                    column_of(line, found.start()),
+                    format!("bare URL `{}`; use a link", url),
+                    Some(replacement),
+                );
+            }
+        }
+    }
+
+    fn check_length(&mut self, line: &str, number: usize) {
+        let max = self.config.max_line_length;
+        let length = line.trim_end().chars().count();
+        if max == 0 || length <= max || line.trim_start().starts_with('|') {
+            return;
+        }
+        // A long URL or other unbreakable word running past the limit is fine
+        let tail: String = line.trim_end().chars().skip(max).collect();
+        if !tail.contains(char::is_whitespace) {
+            return;
+        }
+        self.report(
+            LintRule::LineLength,
+            number,
+            max + 1,
+            format!("line is {} characters long (max {})", length, max),
+        );
+    }
+
+    fn check_heading(
+        &mut self,
+        line: &str,
+        number: usize,
+        text: std::ops::Range<usize>,
+        protected: &[std::ops::Range<usize>],
+    ) {
+        let heading = &line[text.clone()];
+        if self.config.checks(LintRule::DuplicateHeading) {
+            let slug = slugify(strip_attributes(heading));
+            match self.headings.get(&slug) {
+                Some(first) => {
+                    let first = *first;
+                    self.report(
+                        LintRule::DuplicateHeading,
+                        number,
+                        column_of(line, text.start),
+                        format!(
+                            "duplicate heading `{}` (first on line {})",
+                            heading.trim(),
+                            first
+                        ),
+                    );
+                }
+                None => {
+                    self.headings.insert(slug, number);
+                }
+            }
+        }
+        if self.config.checks(LintRule::HeadingCapitalization) {
+            let recased = recase(line, text.clone(), protected, self.config);
+            if recased != line {
+                let style = match self.config.heading_style {
+                    HeadingStyle::Sentence => "sentence case",
+                    HeadingStyle::Title => "title case",
+                };
+                self.report_fix(
+                    LintRule::HeadingCapitalization,
+                    number,
+                    column_of(line, text.start),
+                    format!("heading is not in {}", style),
+                    Some(recased),
+                );
+            }
+        }
+    }
+
+    fn check_list_marker(&mut self, line: &str, number: usize, at: usize) {
+        let marker = line[at..].chars().next().unwrap_or('-');
+        match self.list_marker {
+            None => self.list_marker = Some(marker),
+            Some(expected) if expected != marker => {
+                let replacement = format!("{}{}{}", &line[..at], expected, &line[at + 1..]);
+                self.report_fix(
+                    LintRule::ListMarker,
+                    number,
+                    column_of(line, at),
+                    format!(
+                        "list marker `{}`; this document uses `{}`",
+                        marker, expected
+                    ),
+                    Some(replacement),
+                );
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Words that stay lowercase inside a title-case heading
+const MINOR_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "or", "over", "per", "so", "the", "to", "up", "via", "vs", "with", "yet",
+];
+
+/// `line` with the words of the heading text at `text` cased in the configured style
+fn recase(
+    line: &str,
+    text: std::ops::Range<usize>,
+    protected: &[std::ops::Range<usize>],
+    config: &LintConfig,
+) -> String {
+    // Words as byte ranges of their letters, skipping code spans and link targets
+    let mut words = Vec::new();
+    let mut start = None;
+    let heading = &line[..text.end];
+    for (at, ch) in heading
+        .char_indices()
+        .skip_while(|(at, _)| *at < text.start)
+    {
+        if ch.is_whitespace() {
+            if let Some(begin) = start.take() {
+                words.push(begin..at);
+            }
+        } else if start.is_none() {
+            start = Some(at);
+        }
+    }
+    if let Some(begin) = start {
+        words.push(begin..text.end);
+    }
+    if words
+        .last()
+        .is_some_and(|word| line[word.clone()].starts_with('{'))
+    {
+        words.pop();
+    }
+
+    // Built left to right, since recasing can change a word's length in bytes
+    let mut recased = String::with_capacity(line.len());
+    let mut copied = 0;
+    let count = words.len();
+    let mut after_colon = false;
+    for (idx, word) in words.into_iter().enumerate() {
+        let raw = &line[word.clone()];
+        let previous_colon = std::mem::replace(&mut after_colon, raw.ends_with(':'));
+        if protected.iter().any(|range| range.contains(&word.start)) {
+            continue;
+        }
+        let core_start = raw.find(char::is_alphanumeric).map_or(raw.len(), |at| at);
+        let core_end = raw.rfind(char::is_alphanumeric).map_or(0, |at| {
+            at + raw[at..].chars().next().map_or(1, char::len_utf8)
+        });
+        if core_start >= core_end {
+            continue;
+        }
+        let core = &raw[core_start..core_end];
+        let first = core.chars().next().unwrap_or(' ');
+        let keep = !first.is_alphabetic()
+            || core.chars().skip(1).any(char::is_uppercase)
+            || core.chars().any(|c| c.is_ascii_digit())
+            || core == "I"
+            || config
+                .proper_nouns
+                .iter()
+                .any(|noun| noun.eq_ignore_ascii_case(core));
+        if keep {
+            continue;
+        }
+        let capitalize = match config.heading_style {
+            HeadingStyle::Sentence if idx == 0 => true,
+            HeadingStyle::Sentence if previous_colon => continue,
+            HeadingStyle::Sentence => false,
+            HeadingStyle::Title => {
+                idx == 0 || idx + 1 == count || !MINOR_WORDS.contains(&core.to_lowercase().as_str())
+            }
+        };
+        let cased = if capitalize {
+            first.to_uppercase().collect::<String>() + &core[first.len_utf8()..]
+        } else {
+            first.to_lowercase().collect::<String>() + &core[first.len_utf8()..]
+        };
+        let at = word.start + core_start;
+        recased.push_str(&line[copied..at]);
+        recased.push_str(&cased);
+        copied = at + core.len();
+    }
+    recased.push_str(&line[copied..]);
+    recased
+}
+
+/// Byte ranges of `line` whose text is not prose: code spans, link destinations,
+/// HTML tags, and attribute lists
+fn protected_ranges(line: &str) -> Vec<std::ops::Range<usize>> {
+    let bytes = line.as_bytes();
+    let mut ranges = Vec::new();
+    let mut at = 0;
+    while at < bytes.len() {
+        let end = match bytes[at] {
+            b'`' => {
+                let run = bytes[at..].iter().take_while(|&&b| b == b'`').count();
+                let fence = &line[at..at + run];
+                line[at + run..]
+                    .find(fence)
+                    .map(|offset| at + run + offset + run)
+            }
+            b'(' if at > 0 && bytes[at - 1] == b']' => line[at..].find(')').map(|end| at + end + 1),
+            b'<' if bytes
+                .get(at + 1)
+                .is_some_and(|b| b.is_ascii_alphabetic() || matches!(b, b'/' | b'!')) =>
+            {
+                line[at..].find('>').map(|end| at + end + 1)
+            }
+            b'{' => line[at..].find('}').map(|end| at + end + 1),
+            _ => None,
+        };
+        match end {
+            Some(end) => {
+                ranges.push(at..end);
+                at = end;
+            }
+            None => at += 1,
+        }
+    }
+    ranges
+}
+
+/// Heading text without a trailing `{#id .class}` attribute list
+fn strip_attributes(heading: &str) -> &str {
+    let trimmed = heading.trim_end();
+    match trimmed.rfind('{') {
+        Some(at) if trimmed.ends_with('}') => trimmed[..at].trim_end(),
+        _ => trimmed,
+    }
+}
+
+/// 1-based column of byte offset `at`
+fn column_of(line: &str, at: usize) -> usize {
+    line[..at].chars().count() + 1
+}
+
+/// Number of lines taken by a frontmatter block opening `source`, or 0
+fn frontmatter_end(source: &str) -> usize {
+    let lines: Vec<&str> = source.lines().collect();
+    crate::parser::detect_frontmatter(&lines).unwrap_or(0)
+}
+
+/// Line patterns the rules match against
+struct LintPatterns {
+    heading: Regex,
+    list: Regex,
+    /// Horizontal rules, which look like list items (`* * *`)
+    rule: Regex,
+    missing_alt: Regex,
+    url: Regex,
+    /// Link reference definitions (`[docs]: https://...`), whose URLs are not bare
+    reference: Regex,
+}
+
+impl LintPatterns {
+    fn new() -> Result<Self, ParseError> {
+        let compile = |name: &str, pattern: &str| {
+            Regex::new(pattern)
+                .map_err(|e| ParseError::RegexCompilationError(format!("{} regex: {}", name, e)))
+        };
+        Ok(LintPatterns {
+            heading: compile(
+                "Lint heading",
+                r"^ {0,3}(#{1,6})[ \t]+(.*?)(?:[ \t]+#+)?[ \t]*$",
+            )?,
+            list: compile("Lint list", r"^(\s*)([-*+])[ \t]+\S")?,
+            rule: compile("Lint rule", r"^\s*([-*_])(\s*[-*_]){2,}\s*$")?,
+            missing_alt: compile("Lint alt text", r"!\[\s*\]\(")?,
+            url: compile("Lint URL", r"https?://[^\s<>()\[\]`]+")?,
+            reference: compile("Lint reference", r"^\s{0,3}\[[^\]]+\]:\s")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protected_ranges_cover_code_links_and_tags() {
+        let line = "`a b` [x](http://y) <br> {#id}";
+        let ranges = protected_ranges(line);
+        let texts: Vec<_> = ranges.iter().map(|range| &line[range.clone()]).collect();
+        assert_eq!(texts, ["`a b`", "(http://y)", "<br>", "{#id}"]);
+    }
+}
@@ -0,0 +1,526 @@
+//! Language Server Protocol server for Markdown, over JSON-RPC on stdio.
+
+use crate::ast::{LineRange, ParseError, Severity};
+use crate::config::ParserConfig;
+use crate::diagnostics::{Diagnostic, Diagnostics};
+use crate::folding::{folding_ranges, FoldingKind};
+use crate::outline::{Outline, OutlineSection};
+use crate::parse_result::ParseResult;
+use crate::parser::Parser;
+use crate::project::{Project, ProjectDocument};
+use crate::renderer::is_absolute_url;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Most source lines shown by a hover preview
+const PREVIEW_LINES: usize = 15;
+
+/// JSON-RPC error code for requests the server does not implement
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// JSON-RPC error code for messages that are not valid JSON
+const PARSE_ERROR: i64 = -32700;
+
+/// JSON-RPC error code for requests the server failed to carry out
+const INTERNAL_ERROR: i64 = -32603;
+
+/// A Markdown language server
+///
+/// Offers document symbols (headings), folding ranges, hover previews of link targets,
+/// and diagnostics (parse and Mermaid validation warnings, parse errors, and broken
+/// relative links), published whenever a document is opened or changed. Documents are
+/// synced in full; each keeps a [`Parser`], whose cached result serves every request
+/// until the text changes.
+pub struct LanguageServer {
+    config: ParserConfig,
+    documents: HashMap<String, OpenDocument>,
+    exited: bool,
+}
+
+struct OpenDocument {
+    text: String,
+    path: Option<PathBuf>,
+    parser: Parser,
+}
+
+impl LanguageServer {
+    /// Create a server parsing documents with `config`
+    pub fn new(config: ParserConfig) -> Self {
+        Self {
+            config,
+            documents: HashMap::new(),
+            exited: false,
+        }
+    }
+
+    /// Whether the client sent `exit`
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Handle one JSON-RPC message, returning the responses and notifications to send
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let id = message.get("id").cloned();
+        match (message["method"].as_str(), id) {
+            (Some("initialize"), Some(id)) => vec![response(id, capabilities())],
+            (Some("shutdown"), Some(id)) => vec![response(id, Value::Null)],
+            (Some("exit"), _) => {
+                self.exited = true;
+                Vec::new()
+            }
+            (Some("textDocument/didOpen"), None) => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                vec![self.open(uri, text.to_string())]
+            }
+            (Some("textDocument/didChange"), None) => {
+                let changes = params["contentChanges"].as_array();
+                let Some(text) = changes.and_then(|c| c.last()?["text"].as_str()) else {
+                    return Vec::new();
+                };
+                match self.documents.get_mut(uri) {
+                    Some(document) => {
+                        document.text = text.to_string();
+                        document.parser.set_input(text.to_string());
+                        vec![self.publish_diagnostics(uri)]
+                    }
+                    None => vec![self.open(uri, text.to_string())],
+                }
+            }
+            (Some("textDocument/didClose"), None) => {
+                self.documents.remove(uri);
+                vec![notification(
+                    "textDocument/publishDiagnostics",
+                    json!({"uri": uri, "diagnostics": []}),
+                )]
+            }
+            (Some("textDocument/documentSymbol"), Some(id)) => {
+                vec![response(id, self.document_symbols(uri))]
+            }
+            (Some("textDocument/foldingRange"), Some(id)) => {
+                vec![response(id, self.folding_ranges(uri))]
+            }
+            (Some("textDocument/hover"), Some(id)) => {
+                let position = &params["position"];
+                let line = position["line"].as_u64().unwrap_or_default() as usize;
+                let character = position["character"].as_u64().unwrap_or_default() as usize;
+                match self.hover(uri, line, character) {
+                    Ok(hover) => vec![response(id, hover)],
+                    Err(e) => vec![error_response(id, INTERNAL_ERROR, &e.to_string())],
+                }
+            }
+            (Some(method), Some(id)) => vec![error_response(
+                id,
+                METHOD_NOT_FOUND,
+                &format!("unsupported method `{}`", method),
+            )],
+            // Other notifications, such as `initialized`, need no answer
+            _ => Vec::new(),
+        }
+    }
+
+    /// Start tracking a document and return its diagnostics notification
+    ///
+    /// A document whose parser cannot be created is not tracked; the error is published
+    /// as its only diagnostic.
+    fn open(&mut self, uri: &str, text: String) -> Value {
+        let path = uri_to_path(uri);
+        let config = match &path {
+            Some(path) => self.config.for_document(path),
+            None => self.config.clone(),
+        };
+        match Parser::with_config(text.clone(), config).or_else(|_| Parser::new(text.clone())) {
+            Ok(parser) => {
+                self.documents
+                    .insert(uri.to_string(), OpenDocument { text, path, parser });
+                self.publish_diagnostics(uri)
+            }
+            Err(error) => {
+                let mut diagnostics = Diagnostics::new();
+                diagnostics.add_parse_error(uri, &error);
+                diagnostics_notification(uri, &diagnostics, &text)
+            }
+        }
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> Value {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return Value::Null;
+        };
+        let file = uri.to_string();
+        let mut diagnostics = Diagnostics::new();
+        match document.parser.parse_full() {
+            Ok(result) => {
+                diagnostics.add_parse_warnings(&file, &result.warnings);
+                let path = document.path.as_deref();
+                if let (Some(dir), Some(name)) =
+                    (path.and_then(Path::parent), path.and_then(Path::file_name))
+                {
+                    let linked =
+                        ProjectDocument::new(name.into(), result.nodes.clone(), Vec::new());
+                    // A linked document that fails to parse leaves the links unchecked
+                    if let Ok(project) = Project::with_linked_documents(dir, linked, &self.config) {
+                        let broken = project.broken_links();
+                        let start = diagnostics.len();
+                        diagnostics.add_broken_links(&file, &broken);
+                        for (diagnostic, link) in
+                            diagnostics.diagnostics[start..].iter_mut().zip(&broken)
+                        {
+                            diagnostic.line = result.link_line(&link.url, &document.text);
+                        }
+                    }
+                }
+            }
+            Err(error) => diagnostics.add_parse_error(&file, &error),
+        }
+        diagnostics_notification(uri, &diagnostics, &document.text)
+    }
+
+    fn document_symbols(&mut self, uri: &str) -> Value {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return json!([]);
+        };
+        let Ok(result) = document.parser.parse_full() else {
+            return json!([]);
+        };
+        let lines: Vec<&str> = document.text.lines().collect();
+        let whole = LineRange {
+            start: 1,
+            end: lines.len().max(1),
+        };
+        let sections = Outline::from_result(&result).sections;
+        Value::Array(
+            sections
+                .iter()
+                .map(|section| document_symbol(section, whole, &lines))
+                .collect(),
+        )
+    }
+
+    fn folding_ranges(&mut self, uri: &str) -> Value {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return json!([]);
+        };
+        let Ok(result) = document.parser.parse_full() else {
+            return json!([]);
+        };
+        folding_ranges(&result)
+            .into_iter()
+            .map(|range| {
+                let kind = match range.kind {
+                    FoldingKind::Comment => "comment",
+                    _ => "region",
+                };
+                json!({
+                    "startLine": range.start - 1,
+                    "endLine": range.end - 1,
+                    "kind": kind,
+                })
+            })
+            .collect()
+    }
+
+    /// Preview of the heading or document that the link under the cursor points to
+    fn hover(&mut self, uri: &str, line: usize, character: usize) -> Result<Value, ParseError> {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return Ok(Value::Null);
+        };
+        let Some(line_text) = document.text.lines().nth(line) else {
+            return Ok(Value::Null);
+        };
+        let offset = byte_offset(line_text, character);
+        let Some((url, start, end)) = link_pattern()?.captures_iter(line_text).find_map(|c| {
+            let link = c.get(0)?;
+            (link.start() <= offset && offset < link.end())
+                .then(|| (c[1].to_string(), link.start(), link.end()))
+        }) else {
+            return Ok(Value::Null);
+        };
+        let range = json!({
+            "start": {"line": line, "character": utf16_len(&line_text[..start])},
+            "end": {"line": line, "character": utf16_len(&line_text[..end])},
+        });
+
+        let preview = if let Some(anchor) = url.strip_prefix('#') {
+            let text = document.text.clone();
+            let Ok(result) = document.parser.parse_full() else {
+                return Ok(Value::Null);
+            };
+            preview(&result, &text, Some(anchor))
+        } else if is_absolute_url(&url) {
+            None
+        } else {
+            let (target, anchor) = match url.split_once('#') {
+                Some((target, anchor)) => (target, Some(anchor)),
+                None => (url.as_str(), None),
+            };
+            let dir = document.path.as_deref().and_then(Path::parent);
+            let path = dir.map(|dir| dir.join(target.split('?').next().unwrap_or_default()));
+            path.filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+                .and_then(|path| self.preview_file(&path, anchor))
+        };
+        Ok(match preview {
+            Some(value) => json!({
+                "contents": {"kind": "markdown", "value": value},
+                "range": range,
+            }),
+            None => Value::Null,
+        })
+    }
+
+    /// Preview of a document on disk, or of its open text if the client has it open
+    fn preview_file(&mut self, path: &Path, anchor: Option<&str>) -> Option<String> {
+        let open = self
+            .documents
+            .values_mut()
+            .find(|document| document.path.as_deref() == Some(path));
+        if let Some(document) = open {
+            let text = document.text.clone();
+            let result = document.parser.parse_full().ok()?;
+            return preview(&result, &text, anchor);
+        }
+        let text = fs::read_to_string(path).ok()?;
+        let result = Parser::with_config(text.clone(), self.config.for_document(path))
+            .and_then(|mut parser| parser.parse_full())
+            .ok()?;
+        preview(&result, &text, anchor)
+    }
+}
+
+/// Serve LSP clients: read messages from `input` and write replies to `output` until
+/// the client sends `exit` or closes `input`
+///
+/// # Errors
+///
+/// Returns an error if reading or writing fails, or a message lacks a `Content-Length`
+pub fn run_language_server(
+    config: ParserConfig,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut server = LanguageServer::new(config);
+    while let Some(body) = read_message(&mut input)? {
+        let replies = match serde_json::from_slice::<Value>(&body) {
+            Ok(message) => server.handle(&message),
+            Err(e) => vec![error_response(Value::Null, PARSE_ERROR, &e.to_string())],
+        };
+        for reply in replies {
+            let body = reply.to_string();
+            write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+        }
+        output.flush()?;
+        if server.has_exited() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Body of the next message, or `None` at the end of `input`
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().ok();
+            }
+        }
+    }
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "message without Content-Length")
+    })?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            // Full text on every change
+            "textDocumentSync": 1,
+            "documentSymbolProvider": true,
+            "foldingRangeProvider": true,
+            "hoverProvider": true,
+        },
+        "serverInfo": {"name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION")},
+    })
+}
+
+fn response(id: Value, result: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "result": result})
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "method": method, "params": params})
+}
+
+/// `textDocument/publishDiagnostics` for `diagnostics` of the document `text`
+fn diagnostics_notification(uri: &str, diagnostics: &Diagnostics, text: &str) -> Value {
+    let lines: Vec<&str> = text.lines().collect();
+    let diagnostics: Vec<Value> = diagnostics
+        .iter()
+        .map(|diagnostic| lsp_diagnostic(diagnostic, &lines))
+        .collect();
+    notification(
+        "textDocument/publishDiagnostics",
+        json!({"uri": uri, "diagnostics": diagnostics}),
+    )
+}
+
+/// LSP diagnostic covering the rest of the line from the diagnostic's column
+fn lsp_diagnostic(diagnostic: &Diagnostic, lines: &[&str]) -> Value {
+    let line = diagnostic.line.unwrap_or(1).saturating_sub(1);
+    let text = lines.get(line).copied().unwrap_or_default();
+    let column = diagnostic.column.unwrap_or(1).saturating_sub(1);
+    let start: String = text.chars().take(column).collect();
+    let message = match &diagnostic.note {
+        Some(note) => format!("{}\n{}", diagnostic.message, note),
+        None => diagnostic.message.clone(),
+    };
+    json!({
+        "range": {
+            "start": {"line": line, "character": utf16_len(&start)},
+            "end": {"line": line, "character": utf16_len(text)},
+        },
+        "severity": match diagnostic.severity {
+            Severity::Error => 1,
+            Severity::Warning => 2,
+            Severity::Note => 3,
+        },
+        "code": diagnostic.rule,
+        "source": env!("CARGO_PKG_NAME"),
+        "message": message,
+    })
+}
+
+/// LSP document symbol for a section, whose lines default to those of its parent
+fn document_symbol(section: &OutlineSection, parent: LineRange, lines: &[&str]) -> Value {
+    let span = section.span.unwrap_or(parent);
+    let name = if section.title.trim().is_empty() {
+        "#".repeat(usize::from(section.level))
+    } else {
+        section.title.clone()
+    };
+    let line_end = |line: usize| utf16_len(lines.get(line - 1).copied().unwrap_or_default());
+    let children: Vec<Value> = section
+        .children
+        .iter()
+        .map(|child| document_symbol(child, span, lines))
+        .collect();
+    json!({
+        "name": name,
+        "detail": format!("#{}", section.anchor),
+        // SymbolKind.String, as Markdown has no closer kind
+        "kind": 15,
+        "range": {
+            "start": {"line": span.start - 1, "character": 0},
+            "end": {"line": span.end - 1, "character": line_end(span.end)},
+        },
+        "selectionRange": {
+            "start": {"line": span.start - 1, "character": 0},
+            "end": {"line": span.start - 1, "character": line_end(span.start)},
+        },
+        "children": children,
+    })
+}
+
+/// Source lines of the section with `anchor`, or the start of the document after its
+/// frontmatter, up to [`PREVIEW_LINES`]
+fn preview(result: &ParseResult, text: &str, anchor: Option<&str>) -> Option<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let (start, end) = match anchor {
+        Some(anchor) => {
+            let sections = Outline::from_result(result).sections;
+            let span = find_section(&sections, anchor)?.span?;
+            (span.start - 1, span.end)
+        }
+        None => {
+            let frontmatter = result.frontmatter.as_ref();
+            let start = frontmatter.map_or(0, |frontmatter| frontmatter.lines().count() + 2);
+            (start, lines.len())
+        }
+    };
+    let end = end.min(lines.len()).min(start + PREVIEW_LINES);
+    let preview = lines.get(start..end)?.join("\n");
+    Some(preview.trim().to_string()).filter(|preview| !preview.is_empty())
+}
+
+fn find_section<'a>(sections: &'a [OutlineSection], anchor: &str) -> Option<&'a OutlineSection> {
+    sections.iter().find_map(|section| {
+        if section.anchor == anchor {
+            Some(section)
+        } else {
+            find_section(&section.children, anchor)
+        }
+    })
+}
+
+/// `[text](url)` or `![alt](url)`, capturing the URL
+fn link_pattern() -> Result<Regex, ParseError> {
+    Regex::new(r#"!?\[[^\]]*\]\(\s*<?([^)\s>]*)>?(?:\s+"[^"]*")?\s*\)"#)
+        .map_err(|e| ParseError::RegexCompilationError(format!("Link regex: {}", e)))
+}
+
+/// Length of `text` in UTF-16 code units, the unit of LSP positions
+fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// Byte offset in `line` of the UTF-16 position `character`
+fn byte_offset(line: &str, character: usize) -> usize {
+    let mut units = 0;
+    for (offset, ch) in line.char_indices() {
+        if units >= character {
+            return offset;
+        }
+        units += ch.len_utf16();
+    }
+    line.len()
+}
+
+/// Path of a `file://` URI, with percent-escapes decoded
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    // `file:///C:/docs` on Windows
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => &path[1..],
+        _ => path,
+    };
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| std::str::from_utf8(tail.get(..2)?).ok())
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
@@ -1,8 +1,301 @@
-use md_parser::{Config, Parser};
-use std::env;
+use clap::{Args, CommandFactory, Parser as CliParser, Subcommand, ValueEnum};
+use md_parser::{
+    audit_accessibility, collect_markdown_files, notebook_to_markdown, number_headings,
+    BuildReport, Config, Diagnostic, DiagnosticSource, Diagnostics, DiagramFormat, EpubOptions,
+    FileReport, Node, Outline, OutlineSection, Parser, ParserConfig, Project, ProjectDocument,
+    SearchIndex, Severity, SitePage, SlideOptions, WarningCode,
+};
 use std::fs;
-use std::io::Write;
-use std::path::Path;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+
+/// Markdown parser with Mermaid diagram support
+///
+/// Without a subcommand, every input is built into the outputs enabled in `config.toml`.
+/// With `--format`, or when the only input is `-`, the document is read from stdin (or the
+/// given files) and the chosen format is written to stdout without touching any files.
+#[derive(CliParser)]
+#[command(name = "md-parser", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Markdown files or directories to build (`-` reads standard input)
+    inputs: Vec<PathBuf>,
+    /// Write this format to stdout instead of building the configured outputs
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    #[command(flatten)]
+    overrides: ConfigOverrides,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Render documents to standalone HTML
+    Html(IoArgs),
+    /// Serialize the AST as JSON
+    Json {
+        #[command(flatten)]
+        io: IoArgs,
+        /// Emit the nested section outline instead of the flat node list
+        #[arg(long)]
+        outline: bool,
+    },
+    /// Validate documents without writing any output: parse warnings (including Mermaid
+    /// validation), broken relative links, and lint rules; exits with status 1 if any
+    /// warning or error is found
+    Check {
+        /// Markdown files or directories to check
+        inputs: Vec<PathBuf>,
+        /// Read Markdown from standard input
+        #[arg(long, conflicts_with = "inputs")]
+        stdin: bool,
+        /// How to print the diagnostics
+        #[arg(long, value_enum, default_value = "human")]
+        format: CheckFormat,
+        /// Skip the style rules in `[lint]`
+        #[arg(long)]
+        no_lint: bool,
+        /// Skip the check of relative links and images
+        #[arg(long)]
+        no_links: bool,
+    },
+    /// Check documents against the style rules in `[lint]`; exits with status 1 if any
+    /// problem remains
+    Lint {
+        /// Markdown files or directories to lint
+        inputs: Vec<PathBuf>,
+        /// Read Markdown from standard input
+        #[arg(long, conflicts_with = "inputs")]
+        stdin: bool,
+        /// Apply suggested fixes: rewrite the files, or print the fixed document with --stdin
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Rewrite documents in the style set in `[format]`
+    Fmt {
+        /// Markdown files or directories to format
+        inputs: Vec<PathBuf>,
+        /// Read Markdown from standard input and write the formatted document to stdout
+        #[arg(long, conflicts_with = "inputs")]
+        stdin: bool,
+        /// Only list the documents that are not formatted; exits with status 1 if any
+        #[arg(long)]
+        check: bool,
+    },
+    /// Run fenced code blocks with the commands in `[doctest]` and report each result;
+    /// exits with status 1 if any block fails
+    Test {
+        /// Markdown files or directories to test
+        inputs: Vec<PathBuf>,
+        /// Read Markdown from standard input
+        #[arg(long, conflicts_with = "inputs")]
+        stdin: bool,
+        /// Only code blocks in these languages (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        lang: Vec<String>,
+        /// Write the code blocks to files in this directory instead of running them
+        #[arg(long, value_name = "DIR")]
+        extract: Option<PathBuf>,
+    },
+    /// Print a table of contents as a nested Markdown list
+    Toc(IoArgs),
+    /// Render documents as Reveal.js slide decks, split at `---` rules and level-2 headings
+    Slides(IoArgs),
+    /// Mermaid diagram tools
+    Mermaid {
+        #[command(subcommand)]
+        command: MermaidCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum MermaidCommand {
+    /// Write every Mermaid diagram to its own file, named after the diagram's `name=` or the
+    /// nearest heading above it (`guide-setup.svg`)
+    Export {
+        #[command(flatten)]
+        io: IoArgs,
+        /// Directory for the diagram files (same as `--output`)
+        #[arg(long, value_name = "DIR", conflicts_with_all = ["output", "stdout"])]
+        out_dir: Option<PathBuf>,
+        /// Mermaid source, or an image rendered with the Mermaid CLI (`mmdc`)
+        #[arg(long, value_enum, default_value = "mmd")]
+        format: DiagramFormatArg,
+    },
+}
+
+/// Output format of `check`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CheckFormat {
+    /// Diagnostics with source excerpts on stderr
+    Human,
+    /// A JSON array of diagnostics on stdout
+    Json,
+    /// A SARIF 2.1.0 log on stdout, for GitHub code scanning
+    Sarif,
+}
+
+/// File format of `mermaid export`
+#[derive(Clone, Copy, ValueEnum)]
+enum DiagramFormatArg {
+    /// Mermaid source
+    Mmd,
+    /// SVG image
+    Svg,
+    /// PNG image at twice the natural size
+    Png,
+}
+
+impl From<DiagramFormatArg> for DiagramFormat {
+    fn from(format: DiagramFormatArg) -> Self {
+        match format {
+            DiagramFormatArg::Mmd => DiagramFormat::Mmd,
+            DiagramFormatArg::Svg => DiagramFormat::Svg,
+            DiagramFormatArg::Png => DiagramFormat::Png,
+        }
+    }
+}
+
+/// Input and output selection shared by the subcommands
+///
+/// Documents read from standard input are written to standard output unless `--output`
+/// is given.
+#[derive(Args)]
+struct IoArgs {
+    /// Markdown files or directories (`-` reads standard input)
+    inputs: Vec<PathBuf>,
+    /// Read Markdown from standard input
+    #[arg(long, conflicts_with = "inputs")]
+    stdin: bool,
+    /// Write results to standard output instead of files
+    #[arg(long, conflicts_with = "output")]
+    stdout: bool,
+    /// Output file for a single input (a directory for `mermaid export`);
+    /// defaults to the configured output directory
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// Flags that override values from the configuration file
+#[derive(Args)]
+struct ConfigOverrides {
+    /// Configuration file [default: layered user, ./config.toml, and .md-parser.toml]
+    #[arg(short, long, global = true)]
+    config: Option<PathBuf>,
+    /// Reject unknown configuration keys
+    #[arg(long, global = true)]
+    strict_config: bool,
+    /// Directory for generated files
+    #[arg(long, global = true)]
+    output_dir: Option<String>,
+    /// Deepest heading level accepted by the parser
+    #[arg(long, global = true, value_parser = clap::value_parser!(u8).range(1..=6))]
+    max_heading_level: Option<u8>,
+    /// Parse `{#id .class key=val}` attribute lists on headings, code blocks, images, and spans
+    #[arg(long, global = true)]
+    attributes: bool,
+    /// Nest the AST into sections by heading level
+    #[arg(long, global = true)]
+    sections: bool,
+    /// Skip Mermaid syntax validation
+    #[arg(long, global = true)]
+    no_mermaid_validation: bool,
+    /// Recover from malformed input with warnings instead of failing
+    #[arg(long, global = true)]
+    error_recovery: bool,
+    /// Fail on warnings with these codes (e.g. `unclosed-emphasis`)
+    #[arg(long, global = true, value_delimiter = ',', value_parser = parse_warning_code)]
+    deny: Vec<WarningCode>,
+    /// Set a `{{name}}` variable (repeatable); enables variable substitution
+    #[arg(long = "var", global = true, value_name = "NAME=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+    /// Only render sections visible to this audience
+    #[arg(long, global = true)]
+    audience: Option<String>,
+    /// Shift rendered heading levels by this amount
+    #[arg(long, global = true, allow_hyphen_values = true)]
+    heading_offset: Option<i8>,
+    /// Render the shallowest heading as level 1
+    #[arg(long, global = true)]
+    normalize_headings: bool,
+    /// Base URL for relative links and images
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+}
+
+fn parse_warning_code(name: &str) -> Result<WarningCode, String> {
+    WarningCode::from_name(name).ok_or_else(|| {
+        let known: Vec<&str> = WarningCode::ALL.iter().map(|code| code.as_str()).collect();
+        format!(
+            "unknown warning code (expected one of: {})",
+            known.join(", ")
+        )
+    })
+}
+
+fn parse_variable(assignment: &str) -> Result<(String, String), String> {
+    let (name, value) = assignment
+        .split_once('=')
+        .ok_or_else(|| "expected NAME=VALUE".to_string())?;
+    Ok((name.trim().to_string(), value.to_string()))
+}
+
+impl ConfigOverrides {
+    /// Load the configuration file and apply the command-line overrides
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration file cannot be loaded
+    fn load(&self) -> Result<Config, String> {
+        let mut config = match (&self.config, self.strict_config) {
+            (Some(path), false) => Config::load_from_path(path),
+            (Some(path), true) => Config::load_from_path_strict(path),
+            (None, false) => Config::layered(),
+            (None, true) => Config::layered_strict(),
+        }
+        .map_err(|e| format!("Failed to load configuration: {}", e))?;
+
+        if let Some(dir) = &self.output_dir {
+            config.output.directory = dir.clone();
+            config.renderer.output_directory = dir.clone();
+        }
+        if let Some(level) = self.max_heading_level {
+            config.parser.max_heading_level = level;
+        }
+        config.parser.enable_attributes |= self.attributes;
+        config.parser.enable_sections |= self.sections;
+        if self.no_mermaid_validation {
+            config.parser.mermaid.validate_syntax = false;
+        }
+        config.parser.error_recovery |= self.error_recovery;
+        for code in &self.deny {
+            if !config.parser.deny.contains(code) {
+                config.parser.deny.push(*code);
+            }
+        }
+        if !self.variables.is_empty() {
+            config.parser.enable_variables = true;
+            config
+                .parser
+                .variables
+                .extend(self.variables.iter().cloned());
+        }
+        if let Some(audience) = &self.audience {
+            config.renderer.audience = Some(audience.clone());
+        }
+        if let Some(offset) = self.heading_offset {
+            config.renderer.heading_offset = offset;
+        }
+        config.renderer.normalize_headings |= self.normalize_headings;
+        if let Some(base_url) = &self.base_url {
+            config.renderer.base_url = Some(base_url.clone());
+        }
+
+        Ok(config)
+    }
+}
 
 /// Read the input markdown file
 ///
@@ -78,69 +371,364 @@ fn write_html_output(
     Ok(())
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input.md>", args[0]);
-        std::process::exit(1);
+/// Build the filename for an audience-specific variant (`output.html` -> `output.staff.html`)
+fn audience_variant_filename(filename: &str, audience: &str) -> String {
+    let path = Path::new(filename);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| filename.to_string());
+    match path.extension() {
+        Some(ext) => format!("{}.{}.{}", stem, audience, ext.to_string_lossy()),
+        None => format!("{}.{}", stem, audience),
     }
-    let file_path = &args[1];
-    let markdown = read_input_file(file_path)?;
-
-    // Load configuration
-    let config =
-        Config::load_config().map_err(|e| format!("Failed to load configuration: {}", e))?;
+}
 
-    // Create parser with config
-    let mut parser = Parser::with_config(markdown, config.parser.clone())?;
-    let ast = parser.parse()?;
+/// Output filenames for one document
+struct OutputNames {
+    ast_debug: String,
+    ast_json: String,
+    html: String,
+    epub: String,
+    asciidoc: String,
+    rst: String,
+    slides: String,
+    docx: String,
+}
 
-    // Check for warnings and display them
-    let warnings = parser.warnings();
-    if !warnings.is_empty() {
-        for warning in warnings {
-            eprintln!("Warning: {}", warning);
+impl OutputNames {
+    /// Filenames configured in `[output]`, used for single-file builds
+    fn from_config(config: &Config) -> Self {
+        Self {
+            ast_debug: config.output.ast_debug_filename.clone(),
+            ast_json: config.output.ast_json_filename.clone(),
+            html: config.output.html_filename.clone(),
+            epub: config.output.epub_filename.clone(),
+            asciidoc: config.output.asciidoc_filename.clone(),
+            rst: config.output.rst_filename.clone(),
+            slides: config.output.slides_filename.clone(),
+            docx: config.output.docx_filename.clone(),
         }
     }
 
-    // Ensure output directory exists
-    ensure_output_dir(&config.output.directory)?;
+    /// Filenames derived from a document's path relative to the build root
+    /// (`guide/setup.md` -> `guide/setup.ast.txt`, `guide/setup.json`, `guide/setup.html`)
+    fn for_document(relative: &Path) -> Self {
+        let stem = relative.with_extension("");
+        let stem = stem.to_string_lossy();
+        Self {
+            ast_debug: format!("{}.ast.txt", stem),
+            ast_json: format!("{}.json", stem),
+            html: format!("{}.html", stem),
+            epub: format!("{}.epub", stem),
+            asciidoc: format!("{}.adoc", stem),
+            rst: format!("{}.rst", stem),
+            slides: format!("{}.slides.html", stem),
+            docx: format!("{}.docx", stem),
+        }
+    }
+}
 
-    // Write outputs based on configuration
+/// Write every enabled output for a parsed document, and add it to the search index
+/// of the build if that is enabled
+///
+/// Returns the list of written paths for reporting.
+///
+/// # Errors
+///
+/// Returns an error if any output cannot be written
+fn write_outputs(
+    parser: &mut Parser,
+    ast: &[md_parser::Node],
+    source: &Path,
+    config: &Config,
+    names: &OutputNames,
+    search: &mut SearchIndex,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let mut outputs = Vec::new();
 
+    // Documents in subdirectories need their parent directories created first
+    for (dir, name) in [
+        (&config.output.directory, &names.ast_debug),
+        (&config.output.directory, &names.ast_json),
+        (&config.renderer.output_directory, &names.html),
+        (&config.output.directory, &names.epub),
+        (&config.output.directory, &names.asciidoc),
+        (&config.output.directory, &names.rst),
+        (&config.output.directory, &names.slides),
+        (&config.output.directory, &names.docx),
+    ] {
+        if let Some(parent) = Path::new(dir).join(name).parent() {
+            ensure_output_dir(&parent.to_string_lossy())?;
+        }
+    }
+
     if config.output.enable_ast_debug {
-        write_ast_debug(
-            &ast,
-            &config.output.directory,
-            &config.output.ast_debug_filename,
-        )?;
-        outputs.push(format!(
-            "{}/{}",
-            config.output.directory, config.output.ast_debug_filename
-        ));
+        write_ast_debug(ast, &config.output.directory, &names.ast_debug)?;
+        outputs.push(format!("{}/{}", config.output.directory, names.ast_debug));
     }
 
     if config.output.enable_ast_json {
-        write_ast_json(
-            &mut parser,
-            &config.output.directory,
-            &config.output.ast_json_filename,
-        )?;
-        outputs.push(format!(
-            "{}/{}",
-            config.output.directory, config.output.ast_json_filename
-        ));
+        write_ast_json(parser, &config.output.directory, &names.ast_json)?;
+        outputs.push(format!("{}/{}", config.output.directory, names.ast_json));
     }
 
     if config.output.enable_html {
-        write_html_output(&mut parser, &config.output.html_filename, &config.renderer)?;
-        outputs.push(format!(
-            "{}/{}",
-            config.output.directory, config.output.html_filename
+        write_html_output(parser, &names.html, &config.renderer)?;
+        outputs.push(format!("{}/{}", config.output.directory, names.html));
+
+        // One additional HTML variant per configured audience
+        for audience in &config.output.audiences {
+            let filename = audience_variant_filename(&names.html, audience);
+            let renderer_config = md_parser::RendererConfig {
+                audience: Some(audience.clone()),
+                ..config.renderer.clone()
+            };
+            write_html_output(parser, &filename, &renderer_config)?;
+            outputs.push(format!("{}/{}", config.output.directory, filename));
+        }
+    }
+
+    if config.output.enable_epub {
+        let options = EpubOptions {
+            title: document_title(ast, source),
+            ..EpubOptions::default()
+        };
+        let resource_dir = source.parent().unwrap_or(Path::new("."));
+        let epub = parser.to_epub(&options, resource_dir, &config.renderer)?;
+        let path = Path::new(&config.output.directory).join(&names.epub);
+        fs::write(&path, epub).map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+        outputs.push(format!("{}/{}", config.output.directory, names.epub));
+    }
+
+    if config.output.enable_asciidoc {
+        let path = Path::new(&config.output.directory).join(&names.asciidoc);
+        fs::write(
+            &path,
+            config.renderer.line_ending.apply(parser.to_asciidoc()?),
+        )
+        .map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+        outputs.push(format!("{}/{}", config.output.directory, names.asciidoc));
+    }
+
+    if config.output.enable_rst {
+        let path = Path::new(&config.output.directory).join(&names.rst);
+        fs::write(&path, config.renderer.line_ending.apply(parser.to_rst()?))
+            .map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+        outputs.push(format!("{}/{}", config.output.directory, names.rst));
+    }
+
+    if config.output.enable_slides {
+        let options = SlideOptions {
+            title: document_title(ast, source),
+            ..SlideOptions::default()
+        };
+        let path = Path::new(&config.output.directory).join(&names.slides);
+        fs::write(&path, parser.to_slides(&options, &config.renderer)?)
+            .map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+        outputs.push(format!("{}/{}", config.output.directory, names.slides));
+    }
+
+    if config.output.enable_docx {
+        #[cfg(feature = "docx")]
+        {
+            let options = md_parser::DocxOptions {
+                title: document_title(ast, source),
+                ..md_parser::DocxOptions::default()
+            };
+            let resource_dir = source.parent().unwrap_or(Path::new("."));
+            let docx = parser.to_docx(&options, resource_dir, &config.renderer)?;
+            let path = Path::new(&config.output.directory).join(&names.docx);
+            fs::write(&path, docx)
+                .map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+            outputs.push(format!("{}/{}", config.output.directory, names.docx));
+        }
+        #[cfg(not(feature = "docx"))]
+        eprintln!("Warning: enable_docx is set, but this build lacks the `docx` feature");
+    }
+
+    if config.output.enable_search_index {
+        search.add_document(&names.html, &document_title(ast, source), ast);
+    }
+
+    Ok(outputs)
+}
+
+/// Text of the first heading, or the file name without extension
+fn document_title(ast: &[md_parser::Node], source: &Path) -> String {
+    ast.iter()
+        .find_map(|node| match node {
+            md_parser::Node::Heading { content, .. }
+            | md_parser::Node::Section {
+                heading: content, ..
+            } => Some(md_parser::plain_text(content)),
+            _ => None,
+        })
+        .or_else(|| source.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_default()
+}
+
+/// Parse and render a single document of a directory build, adding its page to `pages`
+/// and its AST to `documents`
+///
+/// Errors are recorded in the returned report instead of aborting the build.
+fn build_document(
+    root: &Path,
+    path: &Path,
+    config: &Config,
+    search: &mut SearchIndex,
+    pages: &mut Vec<SitePage>,
+    documents: &mut Vec<ProjectDocument>,
+) -> FileReport {
+    let started = Instant::now();
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let display = relative.to_string_lossy().into_owned();
+
+    let result = (|| -> Result<FileReport, Box<dyn std::error::Error>> {
+        let markdown = read_input_file(&path.to_string_lossy())?;
+        let lint = md_parser::lint(&markdown, &config.lint)?;
+        let mut parser = Parser::with_config(markdown, config.parser.for_document(path))?;
+        let parsed = parser.parse_full()?;
+        let warnings = parser.warnings().to_vec();
+        for warning in &warnings {
+            eprintln!("{}: {}", display, warning);
+        }
+        report_accessibility(&display, &mut parser, config)?;
+
+        let names = OutputNames::for_document(relative);
+        pages.push(SitePage::from_result(
+            &names.html.replace('\\', "/"),
+            &parsed,
+        ));
+        let ast = parsed.nodes;
+        write_outputs(&mut parser, &ast, path, config, &names, search)?;
+
+        let mut report = FileReport::new(&display, &ast, &warnings, started.elapsed());
+        report.lint = lint;
+        documents.push(ProjectDocument::new(relative.to_path_buf(), ast, warnings));
+        Ok(report)
+    })();
+
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {}: {}", display, e);
+        FileReport::failed(&display, e.to_string(), started.elapsed())
+    })
+}
+
+/// Build every Markdown file below `root` and write a `build-report.json` summary, and
+/// the navigation tree and sitemap if they are enabled
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be scanned or the report cannot be written
+fn build_directory(
+    root: &Path,
+    config: &Config,
+    search: &mut SearchIndex,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    ensure_output_dir(&config.output.directory)?;
+
+    let mut files = Vec::new();
+    let mut pages = Vec::new();
+    let mut documents = Vec::new();
+    for path in collect_markdown_files(root)? {
+        files.push(build_document(
+            root,
+            &path,
+            config,
+            search,
+            &mut pages,
+            &mut documents,
         ));
     }
 
+    // Links are checked once every document is parsed, so anchors in other documents
+    // can be resolved
+    let broken = Project::from_documents(root, documents).broken_links();
+    let mut report = BuildReport::default();
+    for mut file in files {
+        file.broken_links = broken
+            .iter()
+            .filter(|link| link.source == Path::new(&file.path))
+            .map(|link| link.url.clone())
+            .collect();
+        report.push(file);
+    }
+    report.duration_ms = started.elapsed().as_millis();
+
+    if config.output.enable_nav {
+        let path = Path::new(&config.output.directory).join(&config.output.nav_filename);
+        let nav = serde_json::to_string_pretty(&md_parser::navigation(&pages))?;
+        fs::write(&path, nav).map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+        println!("Wrote: {}", path.display());
+    }
+    if config.output.enable_sitemap {
+        match config
+            .output
+            .site_url
+            .as_ref()
+            .or(config.renderer.base_url.as_ref())
+        {
+            Some(site_url) => {
+                let path =
+                    Path::new(&config.output.directory).join(&config.output.sitemap_filename);
+                fs::write(&path, md_parser::sitemap_xml(&pages, site_url))
+                    .map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+                println!("Wrote: {}", path.display());
+            }
+            None => {
+                eprintln!("Warning: enable_sitemap is set, but neither site_url nor base_url is")
+            }
+        }
+    }
+
+    let report_path =
+        Path::new(&config.output.directory).join(&config.output.build_report_filename);
+    fs::write(&report_path, report.to_json()?)
+        .map_err(|e| format!("Error writing '{}': {}", report_path.display(), e))?;
+
+    println!(
+        "Built {} file(s) ({} failed, {} warning(s), {} broken link(s)); report: {}",
+        report.total_files,
+        report.failed_files,
+        report.total_warnings,
+        report.total_broken_links,
+        report_path.display()
+    );
+
+    Ok(())
+}
+
+/// Build a single Markdown file into every output enabled in the configuration
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be parsed or an output cannot be written
+fn build_file(
+    file_path: &Path,
+    config: &Config,
+    names: &OutputNames,
+    search: &mut SearchIndex,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let markdown = read_input_file(&file_path.to_string_lossy())?;
+
+    // Create parser with config
+    let mut parser = Parser::with_config(markdown, config.parser.for_document(file_path))?;
+    let ast = parser.parse()?;
+
+    // Check for warnings and display them
+    for warning in parser.warnings() {
+        eprintln!("{}", warning);
+    }
+    report_accessibility(&file_path.to_string_lossy(), &mut parser, config)?;
+
+    // Ensure output directory exists
+    ensure_output_dir(&config.output.directory)?;
+
+    // Write outputs based on configuration
+    let outputs = write_outputs(&mut parser, &ast, file_path, config, names, search)?;
+
     if !outputs.is_empty() {
         println!("Wrote: {}", outputs.join(", "));
     } else {
@@ -149,3 +737,810 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Build every input with the outputs enabled in the configuration (no subcommand)
+///
+/// A single file uses the configured output filenames; with several inputs each file's
+/// outputs are named after the file so they do not overwrite each other.
+///
+/// # Errors
+///
+/// Returns an error if any input fails to build
+fn build_inputs(inputs: &[PathBuf], config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let mut search = SearchIndex::new();
+    for input in inputs {
+        if input.is_dir() {
+            build_directory(input, config, &mut search)?;
+        } else if inputs.len() == 1 {
+            build_file(
+                input,
+                config,
+                &OutputNames::from_config(config),
+                &mut search,
+            )?;
+        } else {
+            let name = input.file_name().map(PathBuf::from).unwrap_or_default();
+            build_file(
+                input,
+                config,
+                &OutputNames::for_document(&name),
+                &mut search,
+            )?;
+        }
+    }
+
+    if config.output.enable_search_index {
+        ensure_output_dir(&config.output.directory)?;
+        let path = Path::new(&config.output.directory).join(&config.output.search_index_filename);
+        fs::write(&path, search.to_json()?)
+            .map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+        println!("Wrote: {}", path.display());
+    }
+    Ok(())
+}
+
+/// A Markdown document selected on the command line
+struct Input {
+    /// Name used in messages
+    display: String,
+    /// Output path stem relative to the output directory
+    relative: PathBuf,
+    /// Source file, or `None` for standard input
+    path: Option<PathBuf>,
+    markdown: String,
+}
+
+/// Read the whole of standard input as one document
+///
+/// # Errors
+///
+/// Returns an error if standard input cannot be read
+fn read_stdin() -> Result<Input, Box<dyn std::error::Error>> {
+    let mut markdown = String::new();
+    io::stdin()
+        .read_to_string(&mut markdown)
+        .map_err(|e| format!("Error reading standard input: {}", e))?;
+    Ok(Input {
+        display: "<stdin>".to_string(),
+        relative: PathBuf::from("stdin"),
+        path: None,
+        markdown,
+    })
+}
+
+/// Read the documents named by `inputs` (expanding directories), or standard input
+///
+/// An input of `-` also reads standard input.
+///
+/// # Errors
+///
+/// Returns an error if no input is given or a file cannot be read
+fn read_inputs(inputs: &[PathBuf], stdin: bool) -> Result<Vec<Input>, Box<dyn std::error::Error>> {
+    if stdin {
+        return Ok(vec![read_stdin()?]);
+    }
+    if inputs.is_empty() {
+        return Err("No input files given (use `-` or --stdin to read standard input)".into());
+    }
+
+    let mut documents = Vec::new();
+    for input in inputs {
+        if input.as_os_str() == "-" {
+            documents.push(read_stdin()?);
+            continue;
+        }
+        let files = if input.is_dir() {
+            collect_markdown_files(input)?
+        } else {
+            vec![input.clone()]
+        };
+        for file in files {
+            let relative = if input.is_dir() {
+                file.strip_prefix(input).unwrap_or(&file).to_path_buf()
+            } else {
+                file.file_name().map(PathBuf::from).unwrap_or_default()
+            };
+            documents.push(Input {
+                display: file.display().to_string(),
+                markdown: read_input_file(&file.to_string_lossy())?,
+                relative: relative.with_extension(""),
+                path: Some(file),
+            });
+        }
+    }
+    Ok(documents)
+}
+
+/// Print the accessibility issues of a parsed document to stderr when the renderer's
+/// `accessible` mode is on, returning how many there are
+///
+/// # Errors
+///
+/// Returns an error if parsing fails
+fn report_accessibility(
+    display: &str,
+    parser: &mut Parser,
+    config: &Config,
+) -> Result<usize, md_parser::ParseError> {
+    if !config.renderer.accessible {
+        return Ok(0);
+    }
+    let issues = audit_accessibility(&parser.parse_full()?);
+    for issue in &issues {
+        eprintln!("{}: {}", display, issue);
+    }
+    Ok(issues.len())
+}
+
+/// Markdown source of an input and the parser settings for it
+///
+/// Notebooks are converted to Markdown, so every output format can be produced from them.
+///
+/// # Errors
+///
+/// Returns an error if a notebook is malformed
+fn input_source(input: &Input, config: &Config) -> Result<(String, ParserConfig), String> {
+    let parser_config = match &input.path {
+        Some(path) => config.parser.for_document(path),
+        None => config.parser.clone(),
+    };
+    let markdown = match &input.path {
+        Some(path) if path.extension().is_some_and(|ext| ext == "ipynb") => {
+            notebook_to_markdown(&input.markdown, &parser_config, &config.notebook)
+                .map_err(|e| e.to_string())?
+        }
+        _ => input.markdown.clone(),
+    };
+    Ok((markdown, parser_config))
+}
+
+/// Parse a document, printing its warnings to stderr with source excerpts
+///
+/// # Errors
+///
+/// Returns an error if parsing fails
+fn parse_input(
+    input: &Input,
+    config: &Config,
+) -> Result<(Parser, Vec<Node>), Box<dyn std::error::Error>> {
+    let (markdown, parser_config) =
+        input_source(input, config).map_err(|e| format!("{}: {}", input.display, e))?;
+    let mut parser = Parser::with_config(markdown.clone(), parser_config)
+        .map_err(|e| format!("{}: {}", input.display, e))?;
+    let ast = parser
+        .parse()
+        .map_err(|e| format!("{}: {}", input.display, e.display_with_source(&markdown)))?;
+    for warning in parser.warnings() {
+        eprintln!(
+            "{}: {}",
+            input.display,
+            warning.display_with_source(&markdown)
+        );
+    }
+    Ok((parser, ast))
+}
+
+/// Write one text result to stdout or to `path`
+///
+/// Text written to stdout always ends with a newline.
+///
+/// # Errors
+///
+/// Returns an error if the output cannot be written
+fn emit(stdout: bool, path: &Path, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if stdout && !content.ends_with('\n') {
+        return emit_bytes(stdout, path, format!("{}\n", content).as_bytes());
+    }
+    emit_bytes(stdout, path, content.as_bytes())
+}
+
+/// Write one result to stdout or to `path` as it is
+///
+/// # Errors
+///
+/// Returns an error if the output cannot be written
+fn emit_bytes(stdout: bool, path: &Path, content: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    if stdout {
+        let result = io::stdout().lock().write_all(content);
+        // A closed pipe (`md-parser html --stdout doc.md | head`) is not an error
+        return match result {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.into()),
+            _ => Ok(()),
+        };
+    }
+
+    if let Some(parent) = path.parent() {
+        ensure_output_dir(&parent.to_string_lossy())?;
+    }
+    fs::write(path, content).map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+    eprintln!("Wrote: {}", path.display());
+    Ok(())
+}
+
+/// Render the sections of an outline as a nested Markdown list of anchor links
+fn render_toc(sections: &[OutlineSection], depth: usize, toc: &mut String) {
+    for section in sections {
+        toc.push_str(&format!(
+            "{}- [{}](#{})\n",
+            "  ".repeat(depth),
+            section.title,
+            section.anchor
+        ));
+        render_toc(&section.children, depth + 1, toc);
+    }
+}
+
+/// Result produced for each input by a subcommand or `--format`
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Standalone HTML document
+    Html,
+    /// Flat AST as JSON
+    Json,
+    /// Nested section outline as JSON
+    Outline,
+    /// Table of contents as a Markdown list
+    Toc,
+    /// AsciiDoc
+    Asciidoc,
+    /// reStructuredText
+    Rst,
+    /// Reveal.js slide deck
+    Slides,
+    /// Flat AST as YAML
+    #[cfg(feature = "yaml")]
+    Yaml,
+    /// Flat AST as MessagePack (binary)
+    #[cfg(feature = "msgpack")]
+    Msgpack,
+    /// Flat AST as CBOR (binary)
+    #[cfg(feature = "cbor")]
+    Cbor,
+    /// Word document (binary)
+    #[cfg(feature = "docx")]
+    Docx,
+}
+
+/// Convert every input to `format`, writing to stdout, `--output`, or the output directory
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read, parsed, or written
+fn run_export(
+    io_args: &IoArgs,
+    format: Format,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let documents = read_inputs(&io_args.inputs, io_args.stdin)?;
+    if io_args.output.is_some() && documents.len() > 1 {
+        return Err("--output requires a single input file".into());
+    }
+
+    let output_dir = Path::new(&config.output.directory);
+    let default_path = |input: &Input, extension: &str| {
+        io_args
+            .output
+            .clone()
+            .unwrap_or_else(|| output_dir.join(input.relative.with_extension(extension)))
+    };
+
+    for input in &documents {
+        let (mut parser, ast) = parse_input(input, config)?;
+        let stdout = io_args.stdout || (input.path.is_none() && io_args.output.is_none());
+        let (content, extension) = match format {
+            Format::Html => {
+                report_accessibility(&input.display, &mut parser, config)?;
+                (parser.to_html_with_config(&config.renderer)?, "html")
+            }
+            Format::Json => (parser.to_json()?, "json"),
+            Format::Outline => (parser.to_outline_json()?, "outline.json"),
+            Format::Toc => {
+                let mut toc = String::new();
+                let ast = if config.renderer.number_headings {
+                    number_headings(&ast, config.renderer.number_headings_from)
+                } else {
+                    ast
+                };
+                render_toc(&Outline::from_nodes(&ast).sections, 0, &mut toc);
+                (toc, "toc.md")
+            }
+            Format::Asciidoc => (parser.to_asciidoc()?, "adoc"),
+            Format::Rst => (parser.to_rst()?, "rst"),
+            Format::Slides => {
+                let options = SlideOptions {
+                    title: document_title(&ast, &input.relative),
+                    ..SlideOptions::default()
+                };
+                (parser.to_slides(&options, &config.renderer)?, "slides.html")
+            }
+            #[cfg(feature = "yaml")]
+            Format::Yaml => (parser.to_yaml()?, "yaml"),
+            #[cfg(feature = "msgpack")]
+            Format::Msgpack => {
+                let path = default_path(input, "msgpack");
+                emit_bytes(stdout, &path, &parser.to_msgpack()?)?;
+                continue;
+            }
+            #[cfg(feature = "cbor")]
+            Format::Cbor => {
+                let path = default_path(input, "cbor");
+                emit_bytes(stdout, &path, &parser.to_cbor()?)?;
+                continue;
+            }
+            #[cfg(feature = "docx")]
+            Format::Docx => {
+                let path = default_path(input, "docx");
+                let options = md_parser::DocxOptions {
+                    title: document_title(&ast, &input.relative),
+                    ..md_parser::DocxOptions::default()
+                };
+                let resource_dir = input
+                    .path
+                    .as_deref()
+                    .and_then(Path::parent)
+                    .unwrap_or(Path::new("."));
+                emit_bytes(
+                    stdout,
+                    &path,
+                    &parser.to_docx(&options, resource_dir, &config.renderer)?,
+                )?;
+                continue;
+            }
+        };
+        emit(stdout, &default_path(input, extension), &content)?;
+    }
+
+    Ok(())
+}
+
+/// Write the Mermaid diagrams of every input to `--out-dir`, `--output`, or the output directory
+///
+/// Files are named `{document}-{diagram}.{extension}`. Diagrams that fail to render are
+/// reported and skipped.
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read or parsed, a file cannot be written, or any
+/// diagram could not be rendered
+fn run_mermaid_export(
+    io_args: &IoArgs,
+    out_dir: Option<&Path>,
+    format: DiagramFormat,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let documents = read_inputs(&io_args.inputs, io_args.stdin)?;
+    let dir = out_dir
+        .or(io_args.output.as_deref())
+        .unwrap_or(Path::new(&config.output.directory));
+    let mut failures = 0;
+    for input in &documents {
+        let (_, ast) = parse_input(input, config)?;
+        let stdout = io_args.stdout
+            || (input.path.is_none() && io_args.output.is_none() && out_dir.is_none());
+        for diagram in md_parser::diagram_exports(&ast) {
+            let name = format!(
+                "{}-{}.{}",
+                input.relative.to_string_lossy(),
+                diagram.name,
+                format.extension()
+            );
+            match diagram.render(format) {
+                Ok(content) if format == DiagramFormat::Mmd => {
+                    emit(stdout, &dir.join(name), &String::from_utf8_lossy(&content))?
+                }
+                Ok(content) => emit_bytes(stdout, &dir.join(name), &content)?,
+                Err(e) => {
+                    eprintln!("{}: diagram `{}`: {}", input.display, diagram.name, e);
+                    failures += 1;
+                }
+            }
+        }
+    }
+
+    match failures {
+        0 => Ok(()),
+        n => Err(format!("{} diagram(s) could not be rendered", n).into()),
+    }
+}
+
+/// Most rounds of fixes applied to one document; a fix can uncover another on its line
+const MAX_FIX_ROUNDS: usize = 10;
+
+/// Lint every input, optionally fixing what can be fixed, and report the rest
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read, the lint patterns fail to compile, or
+/// a fixed file cannot be written
+fn run_lint(
+    inputs: &[PathBuf],
+    stdin: bool,
+    fix: bool,
+    config: &Config,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let documents = read_inputs(inputs, stdin)?;
+    let (mut problems, mut fixed) = (0, 0);
+    for input in &documents {
+        let mut source = input.markdown.clone();
+        let mut diagnostics = md_parser::lint(&source, &config.lint)?;
+        if fix {
+            for _ in 0..MAX_FIX_ROUNDS {
+                let fixable = diagnostics.iter().filter(|d| d.fix.is_some()).count();
+                if fixable == 0 {
+                    break;
+                }
+                fixed += fixable;
+                source = md_parser::apply_fixes(&source, &diagnostics);
+                diagnostics = md_parser::lint(&source, &config.lint)?;
+            }
+            match &input.path {
+                Some(path) if source != input.markdown => fs::write(path, &source)
+                    .map_err(|e| format!("Error writing '{}': {}", path.display(), e))?,
+                Some(_) => {}
+                None => io::stdout().lock().write_all(source.as_bytes())?,
+            }
+        }
+        for diagnostic in &diagnostics {
+            eprintln!(
+                "{}: {}",
+                input.display,
+                diagnostic.display_with_source(&source)
+            );
+        }
+        problems += diagnostics.len();
+    }
+    if fix {
+        eprintln!(
+            "Linted {} file(s): {} fixed, {} problem(s) left",
+            documents.len(),
+            fixed,
+            problems
+        );
+    } else {
+        eprintln!(
+            "Linted {} file(s): {} problem(s)",
+            documents.len(),
+            problems
+        );
+    }
+
+    Ok(if problems == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Format every input, or with `check` list the ones that are not formatted
+///
+/// Documents that fail to parse or cannot be formatted without changing them are
+/// reported and left as written.
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read or written
+fn run_fmt(
+    inputs: &[PathBuf],
+    stdin: bool,
+    check: bool,
+    config: &Config,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let documents = read_inputs(inputs, stdin)?;
+    let (mut changed, mut failed) = (0, 0);
+    for input in &documents {
+        let parser_config = match &input.path {
+            Some(path) => config.parser.for_document(path),
+            None => config.parser.clone(),
+        };
+        let formatted =
+            match md_parser::format_markdown(&input.markdown, &parser_config, &config.format) {
+                Ok(formatted) => formatted,
+                Err(e) => {
+                    eprintln!("{}: {}", input.display, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+        if formatted != input.markdown {
+            changed += 1;
+        }
+        match &input.path {
+            _ if check => {
+                if formatted != input.markdown {
+                    eprintln!("{}: not formatted", input.display);
+                }
+            }
+            Some(path) if formatted != input.markdown => fs::write(path, &formatted)
+                .map_err(|e| format!("Error writing '{}': {}", path.display(), e))?,
+            Some(_) => {}
+            None => io::stdout().lock().write_all(formatted.as_bytes())?,
+        }
+    }
+    let verb = if check { "to reformat" } else { "reformatted" };
+    eprintln!(
+        "Formatted {} file(s): {} {}, {} failed",
+        documents.len(),
+        changed,
+        verb,
+        failed
+    );
+
+    Ok(if failed == 0 && !(check && changed > 0) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Optional checks of `md-parser check`
+#[derive(Clone, Copy)]
+struct Checks {
+    lint: bool,
+    links: bool,
+}
+
+/// Validate every input without writing output files
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read or the diagnostics cannot be serialized
+fn run_check(
+    inputs: &[PathBuf],
+    stdin: bool,
+    format: CheckFormat,
+    checks: Checks,
+    config: &Config,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let documents = read_inputs(inputs, stdin)?;
+    let mut all = Diagnostics::new();
+    for input in &documents {
+        let (diagnostics, source) = check_input(input, checks, config);
+        if format == CheckFormat::Human {
+            for diagnostic in &diagnostics {
+                eprintln!(
+                    "{}: {}",
+                    diagnostic.file,
+                    diagnostic.display_with_source(&source)
+                );
+            }
+        }
+        all.diagnostics.extend(diagnostics.diagnostics);
+    }
+
+    match format {
+        CheckFormat::Human => eprintln!(
+            "Checked {} file(s): {} problem(s)",
+            documents.len(),
+            all.len()
+        ),
+        CheckFormat::Json => println!("{}", all.to_json()?),
+        CheckFormat::Sarif => println!("{}", all.to_sarif()?),
+    }
+
+    Ok(if all.has_failures() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Run the checks of `md-parser check` on one input, returning its diagnostics and the
+/// source they point into
+fn check_input(input: &Input, checks: Checks, config: &Config) -> (Diagnostics, String) {
+    let file = &input.display;
+    let mut diagnostics = Diagnostics::new();
+    let failure = |message: String| Diagnostic {
+        file: file.clone(),
+        line: None,
+        column: None,
+        severity: Severity::Error,
+        source: DiagnosticSource::Parse,
+        rule: "parse-error".to_string(),
+        message,
+        note: None,
+    };
+    let (source, parser_config) = match input_source(input, config) {
+        Ok(source) => source,
+        Err(message) => {
+            diagnostics.push(failure(message));
+            return (diagnostics, input.markdown.clone());
+        }
+    };
+    let result = match Parser::with_config(source.clone(), parser_config)
+        .and_then(|mut parser| parser.parse_full())
+    {
+        Ok(result) => result,
+        Err(e) => {
+            diagnostics.add_parse_error(file, &e);
+            return (diagnostics, source);
+        }
+    };
+
+    diagnostics.add_parse_warnings(file, &result.warnings);
+    if checks.links {
+        let source_dir = input
+            .path
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new("."));
+        let name = input
+            .path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map_or_else(|| PathBuf::from("stdin.md"), PathBuf::from);
+        let document = ProjectDocument::new(name, result.nodes.clone(), Vec::new());
+        match Project::with_linked_documents(source_dir, document, &config.parser) {
+            Ok(project) => {
+                let broken = project.broken_links();
+                let start = diagnostics.len();
+                diagnostics.add_broken_links(file, &broken);
+                for (diagnostic, link) in diagnostics.diagnostics[start..].iter_mut().zip(&broken) {
+                    diagnostic.line = result.link_line(&link.url, &source);
+                }
+            }
+            Err(e) => diagnostics.push(failure(e.to_string())),
+        }
+    }
+    if checks.lint {
+        match md_parser::lint(&source, &config.lint) {
+            Ok(lint) => diagnostics.add_lint(file, &lint),
+            Err(e) => diagnostics.add_parse_error(file, &e),
+        }
+    }
+    if config.renderer.accessible {
+        diagnostics.add_accessibility(file, &audit_accessibility(&result));
+    }
+    (diagnostics, source)
+}
+
+/// Run or extract the code blocks of every input
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read or parsed, or a snippet cannot be written
+fn run_doc_tests(
+    inputs: &[PathBuf],
+    stdin: bool,
+    langs: &[String],
+    extract: Option<&Path>,
+    config: &Config,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let documents = read_inputs(inputs, stdin)?;
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    for input in &documents {
+        let (mut parser, _) = parse_input(input, config)?;
+        let snippets = md_parser::extract_code_blocks(&parser.parse_full()?, langs);
+        if let Some(dir) = extract {
+            let stem = input.relative.with_extension("");
+            for path in md_parser::write_code_blocks(&snippets, dir, &stem.to_string_lossy())? {
+                println!("{}", path.display());
+            }
+            continue;
+        }
+        for result in md_parser::run_doc_tests(&snippets, &config.doctest) {
+            let location = format!(
+                "{}:{} ({})",
+                input.display, result.snippet.line, result.snippet.lang
+            );
+            match result.outcome {
+                md_parser::DocTestOutcome::Passed => {
+                    passed += 1;
+                    println!("PASS {}", location);
+                }
+                md_parser::DocTestOutcome::Failed { output } => {
+                    failed += 1;
+                    println!("FAIL {}", location);
+                    for line in output.lines() {
+                        println!("    {}", line);
+                    }
+                }
+                md_parser::DocTestOutcome::Skipped { reason } => {
+                    skipped += 1;
+                    println!("SKIP {}: {}", location, reason);
+                }
+            }
+        }
+    }
+    if extract.is_some() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    eprintln!(
+        "Tested {} code block(s): {} passed, {} failed, {} skipped",
+        passed + failed + skipped,
+        passed,
+        failed,
+        skipped
+    );
+
+    Ok(if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn run() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let config = cli.overrides.load()?;
+
+    let (io_args, format) = match &cli.command {
+        None if cli.inputs.is_empty() && cli.format.is_none() => {
+            Cli::command().print_help()?;
+            return Ok(ExitCode::from(2));
+        }
+        None if cli.format.is_some() || cli.inputs.iter().all(|input| input.as_os_str() == "-") => {
+            // Streaming mode: render one format to stdout, no files written
+            let io_args = IoArgs {
+                stdin: cli.inputs.is_empty(),
+                inputs: cli.inputs,
+                stdout: true,
+                output: None,
+            };
+            run_export(&io_args, cli.format.unwrap_or(Format::Html), &config)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        None => {
+            build_inputs(&cli.inputs, &config)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Check {
+            inputs,
+            stdin,
+            format,
+            no_lint,
+            no_links,
+        }) => {
+            let checks = Checks {
+                lint: !no_lint,
+                links: !no_links,
+            };
+            return run_check(inputs, *stdin, *format, checks, &config);
+        }
+        Some(Command::Lint { inputs, stdin, fix }) => {
+            return run_lint(inputs, *stdin, *fix, &config)
+        }
+        Some(Command::Fmt {
+            inputs,
+            stdin,
+            check,
+        }) => return run_fmt(inputs, *stdin, *check, &config),
+        Some(Command::Test {
+            inputs,
+            stdin,
+            lang,
+            extract,
+        }) => return run_doc_tests(inputs, *stdin, lang, extract.as_deref(), &config),
+        Some(Command::Html(io_args)) => (io_args, Format::Html),
+        Some(Command::Json { io, outline }) => (
+            io,
+            if *outline {
+                Format::Outline
+            } else {
+                Format::Json
+            },
+        ),
+        Some(Command::Toc(io_args)) => (io_args, Format::Toc),
+        Some(Command::Slides(io_args)) => (io_args, Format::Slides),
+        Some(Command::Mermaid {
+            command:
+                MermaidCommand::Export {
+                    io,
+                    out_dir,
+                    format,
+                },
+        }) => {
+            run_mermaid_export(io, out_dir.as_deref(), (*format).into(), &config)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+    };
+    run_export(io_args, format, &config)?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    run().unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        ExitCode::FAILURE
+    })
+}
@@ -0,0 +1,502 @@
+//! Approximate memory footprint of an AST.
+
+use crate::ast::{
+    Alignment, Attributes, CompactString, Inline, ListItem, MermaidConfig, Node, ValidationStatus,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::mem::{size_of, size_of_val};
+
+/// Memory held by an AST, as reported by [`memory_usage`]
+///
+/// Sizes are estimates: heap blocks are counted at their capacity, without the
+/// allocator's own bookkeeping, and map entries at the size of their key and value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    /// Block nodes, including nested ones
+    pub blocks: usize,
+    /// Inline elements, including nested ones
+    pub inlines: usize,
+    /// Heap blocks: one per non-empty string, vector, or map entry
+    pub allocations: usize,
+    /// Bytes of text in strings
+    pub text_bytes: usize,
+    /// Bytes allocated but unused, which [`shrink_ast`] gives back
+    pub spare_bytes: usize,
+    /// Bytes of the nodes themselves and of everything they own
+    pub total_bytes: usize,
+}
+
+/// Estimate the memory held by `nodes` and everything they own
+///
+/// ```
+/// use md_parser::{memory_usage, Parser};
+///
+/// let ast = Parser::new("# Title\n\nSome *text*\n".to_string())?.parse()?;
+/// let usage = memory_usage(&ast);
+/// assert_eq!(usage.blocks, 2);
+/// assert!(usage.total_bytes > usage.text_bytes);
+/// # Ok::<(), md_parser::ParseError>(())
+/// ```
+pub fn memory_usage(nodes: &[Node]) -> MemoryUsage {
+    let mut usage = MemoryUsage {
+        total_bytes: size_of_val(nodes),
+        ..MemoryUsage::default()
+    };
+    for node in nodes {
+        node.measure(&mut usage);
+    }
+    usage
+}
+
+/// Release the spare capacity of every string and vector in `nodes`
+///
+/// The parser grows vectors as it goes, so a parsed AST holds more memory than it
+/// needs; shrinking is worthwhile for documents kept in memory for a long time.
+pub fn shrink_ast(nodes: &mut Vec<Node>) {
+    nodes.shrink_to_fit();
+    for node in nodes {
+        node.shrink();
+    }
+}
+
+/// Memory accounting and shrinking of one AST type
+trait HeapSize {
+    /// Add the heap memory owned by `self` (not `self` itself) to `usage`
+    fn measure(&self, usage: &mut MemoryUsage);
+    /// Release spare capacity owned by `self`
+    fn shrink(&mut self);
+}
+
+impl HeapSize for String {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        if self.capacity() > 0 {
+            usage.allocations += 1;
+        }
+        usage.text_bytes += self.len();
+        usage.spare_bytes += self.capacity() - self.len();
+        usage.total_bytes += self.capacity();
+    }
+
+    fn shrink(&mut self) {
+        self.shrink_to_fit();
+    }
+}
+
+impl HeapSize for CompactString {
+    /// Short strings are stored inline and own no heap memory
+    fn measure(&self, usage: &mut MemoryUsage) {
+        usage.text_bytes += self.len();
+        if self.is_heap_allocated() {
+            usage.allocations += 1;
+            usage.spare_bytes += self.capacity() - self.len();
+            usage.total_bytes += self.capacity();
+        }
+    }
+
+    fn shrink(&mut self) {
+        self.shrink_to_fit();
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        if self.capacity() > 0 {
+            usage.allocations += 1;
+        }
+        usage.spare_bytes += (self.capacity() - self.len()) * size_of::<T>();
+        usage.total_bytes += self.capacity() * size_of::<T>();
+        for item in self {
+            item.measure(usage);
+        }
+    }
+
+    fn shrink(&mut self) {
+        self.shrink_to_fit();
+        for item in self {
+            item.shrink();
+        }
+    }
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        if let Some(value) = self {
+            value.measure(usage);
+        }
+    }
+
+    fn shrink(&mut self) {
+        if let Some(value) = self {
+            value.shrink();
+        }
+    }
+}
+
+impl HeapSize for BTreeMap<String, String> {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        for (key, value) in self {
+            usage.allocations += 1;
+            usage.total_bytes += 2 * size_of::<String>();
+            key.measure(usage);
+            value.measure(usage);
+        }
+    }
+
+    fn shrink(&mut self) {}
+}
+
+impl HeapSize for HashMap<String, String> {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        if self.capacity() > 0 {
+            usage.allocations += 1;
+        }
+        usage.spare_bytes += (self.capacity() - self.len()) * 2 * size_of::<String>();
+        usage.total_bytes += self.capacity() * 2 * size_of::<String>();
+        for (key, value) in self {
+            key.measure(usage);
+            value.measure(usage);
+        }
+    }
+
+    fn shrink(&mut self) {
+        self.shrink_to_fit();
+    }
+}
+
+impl HeapSize for Alignment {
+    fn measure(&self, _usage: &mut MemoryUsage) {}
+
+    fn shrink(&mut self) {}
+}
+
+impl HeapSize for Attributes {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        self.id.measure(usage);
+        self.classes.measure(usage);
+        self.pairs.measure(usage);
+    }
+
+    fn shrink(&mut self) {
+        self.id.shrink();
+        self.classes.shrink();
+    }
+}
+
+impl HeapSize for MermaidConfig {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        self.theme.measure(usage);
+        self.font_size.measure(usage);
+        self.font_family.measure(usage);
+        self.theme_variables.measure(usage);
+    }
+
+    fn shrink(&mut self) {
+        self.theme.shrink();
+        self.font_size.shrink();
+        self.font_family.shrink();
+        self.theme_variables.shrink();
+    }
+}
+
+impl HeapSize for ValidationStatus {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        if let ValidationStatus::Invalid { errors } = self {
+            errors.measure(usage);
+        }
+    }
+
+    fn shrink(&mut self) {
+        if let ValidationStatus::Invalid { errors } = self {
+            errors.shrink();
+        }
+    }
+}
+
+impl HeapSize for ListItem {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        self.content.measure(usage);
+        self.children.measure(usage);
+        self.blocks.measure(usage);
+    }
+
+    fn shrink(&mut self) {
+        self.content.shrink();
+        self.children.shrink();
+        self.blocks.shrink();
+    }
+}
+
+impl HeapSize for Inline {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        usage.inlines += 1;
+        match self {
+            Inline::Text { content }
+            | Inline::Code { content }
+            | Inline::Hashtag { tag: content }
+            | Inline::Mention { user: content } => content.measure(usage),
+            Inline::Comment { content } => content.measure(usage),
+            Inline::Bold { content }
+            | Inline::Italic { content }
+            | Inline::Strikethrough { content }
+            | Inline::Highlight { content }
+            | Inline::Spoiler { content } => content.measure(usage),
+            Inline::Link { text, url } => {
+                text.measure(usage);
+                url.measure(usage);
+            }
+            Inline::Image {
+                alt,
+                url,
+                attributes,
+            } => {
+                alt.measure(usage);
+                url.measure(usage);
+                attributes.measure(usage);
+            }
+            Inline::Span {
+                content,
+                attributes,
+            } => {
+                content.measure(usage);
+                attributes.measure(usage);
+            }
+            Inline::Directive {
+                name,
+                content,
+                attributes,
+            } => {
+                name.measure(usage);
+                content.measure(usage);
+                attributes.measure(usage);
+            }
+            Inline::LineBreak => {}
+        }
+    }
+
+    fn shrink(&mut self) {
+        match self {
+            Inline::Text { content }
+            | Inline::Code { content }
+            | Inline::Hashtag { tag: content }
+            | Inline::Mention { user: content } => content.shrink(),
+            Inline::Comment { content } => content.shrink(),
+            Inline::Bold { content }
+            | Inline::Italic { content }
+            | Inline::Strikethrough { content }
+            | Inline::Highlight { content }
+            | Inline::Spoiler { content } => content.shrink(),
+            Inline::Link { text, url } => {
+                text.shrink();
+                url.shrink();
+            }
+            Inline::Image {
+                alt,
+                url,
+                attributes,
+            } => {
+                alt.shrink();
+                url.shrink();
+                attributes.shrink();
+            }
+            Inline::Span {
+                content,
+                attributes,
+            } => {
+                content.shrink();
+                attributes.shrink();
+            }
+            Inline::Directive {
+                name,
+                content,
+                attributes,
+            } => {
+                name.shrink();
+                content.shrink();
+                attributes.shrink();
+            }
+            Inline::LineBreak => {}
+        }
+    }
+}
+
+impl HeapSize for Node {
+    fn measure(&self, usage: &mut MemoryUsage) {
+        usage.blocks += 1;
+        match self {
+            Node::Heading {
+                content,
+                attributes,
+                ..
+            } => {
+                content.measure(usage);
+                attributes.measure(usage);
+            }
+            Node::Paragraph { content } => content.measure(usage),
+            Node::Blockquote {
+                content, blocks, ..
+            } => {
+                content.measure(usage);
+                blocks.measure(usage);
+            }
+            Node::UnorderedList { items } | Node::OrderedList { items } => items.measure(usage),
+            Node::CodeBlock {
+                lang,
+                code,
+                attributes,
+            } => {
+                lang.measure(usage);
+                code.measure(usage);
+                attributes.measure(usage);
+            }
+            Node::MermaidDiagram {
+                diagram,
+                name,
+                config,
+                validation_status,
+                warnings,
+            } => {
+                diagram.measure(usage);
+                name.measure(usage);
+                config.measure(usage);
+                validation_status.measure(usage);
+                warnings.measure(usage);
+            }
+            Node::DiagramRef { name, .. } => name.measure(usage),
+            Node::Table {
+                headers,
+                rows,
+                alignments,
+                caption,
+                ..
+            } => {
+                headers.measure(usage);
+                rows.measure(usage);
+                alignments.measure(usage);
+                caption.measure(usage);
+            }
+            Node::HorizontalRule { .. } => {}
+            Node::Admonition { title, content, .. } => {
+                title.measure(usage);
+                content.measure(usage);
+            }
+            Node::Container {
+                attributes,
+                children,
+            } => {
+                attributes.measure(usage);
+                children.measure(usage);
+            }
+            Node::Details { summary, content } => {
+                summary.measure(usage);
+                content.measure(usage);
+            }
+            Node::Section {
+                heading,
+                attributes,
+                children,
+                ..
+            } => {
+                heading.measure(usage);
+                attributes.measure(usage);
+                children.measure(usage);
+            }
+            Node::Figure {
+                content, caption, ..
+            } => {
+                content.measure(usage);
+                caption.measure(usage);
+            }
+            Node::Comment { content } => content.measure(usage),
+        }
+    }
+
+    fn shrink(&mut self) {
+        match self {
+            Node::Heading {
+                content,
+                attributes,
+                ..
+            } => {
+                content.shrink();
+                attributes.shrink();
+            }
+            Node::Paragraph { content } => content.shrink(),
+            Node::Blockquote {
+                content, blocks, ..
+            } => {
+                content.shrink();
+                blocks.shrink();
+            }
+            Node::UnorderedList { items } | Node::OrderedList { items } => items.shrink(),
+            Node::CodeBlock {
+                lang,
+                code,
+                attributes,
+            } => {
+                lang.shrink();
+                code.shrink();
+                attributes.shrink();
+            }
+            Node::MermaidDiagram {
+                diagram,
+                name,
+                config,
+                validation_status,
+                warnings,
+            } => {
+                diagram.shrink();
+                name.shrink();
+                config.shrink();
+                validation_status.shrink();
+                warnings.shrink();
+            }
+            Node::DiagramRef { name, .. } => name.shrink(),
+            Node::Table {
+                headers,
+                rows,
+                alignments,
+                caption,
+                ..
+            } => {
+                headers.shrink();
+                rows.shrink();
+                alignments.shrink();
+                caption.shrink();
+            }
+            Node::HorizontalRule { .. } => {}
+            Node::Admonition { title, content, .. } => {
+                title.shrink();
+                content.shrink();
+            }
+            Node::Container {
+                attributes,
+                children,
+            } => {
+                attributes.shrink();
+                children.shrink();
+            }
+            Node::Details { summary, content } => {
+                summary.shrink();
+                content.shrink();
+            }
+            Node::Section {
+                heading,
+                attributes,
+                children,
+                ..
+            } => {
+                heading.shrink();
+                attributes.shrink();
+                children.shrink();
+            }
+            Node::Figure {
+                content, caption, ..
+            } => {
+                content.shrink();
+                caption.shrink();
+            }
+            Node::Comment { content } => content.shrink(),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Running the Mermaid CLI (`mmdc`) for validation and build-time rendering.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Distinguishes the temporary files of concurrent runs within one process
+static NEXT_RUN: AtomicUsize = AtomicUsize::new(0);
+
+/// Why an `mmdc` run produced no SVG
+#[derive(Debug)]
+pub(crate) enum MmdcError {
+    /// `mmdc` is not installed
+    Unavailable,
+    /// `mmdc` was killed after running longer than the timeout
+    TimedOut(Duration),
+    /// `mmdc` rejected the diagram; holds the error message from its stderr
+    Failed(String),
+    /// Temporary files could not be written or read, or the process could not be run
+    Io(io::Error),
+}
+
+impl fmt::Display for MmdcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MmdcError::Unavailable => f.write_str("Mermaid CLI (mmdc) is not installed"),
+            MmdcError::TimedOut(timeout) => write!(
+                f,
+                "Mermaid CLI did not finish within {}s",
+                timeout.as_secs_f32()
+            ),
+            MmdcError::Failed(message) => f.write_str(message),
+            MmdcError::Io(e) => write!(f, "Mermaid CLI could not be run: {}", e),
+        }
+    }
+}
+
+/// Input and output files of one run, removed when dropped
+struct TempFiles {
+    input: PathBuf,
+    output: PathBuf,
+}
+
+impl TempFiles {
+    /// Paths for a run writing an output file with the given extension
+    fn new(extension: &str) -> Self {
+        // The process id and a counter keep names unique across processes and threads
+        let stem = format!(
+            "md-parser-mmdc-{}-{}",
+            std::process::id(),
+            NEXT_RUN.fetch_add(1, Ordering::Relaxed)
+        );
+        let dir = std::env::temp_dir();
+        Self {
+            input: dir.join(format!("{}.mmd", stem)),
+            output: dir.join(format!("{}.{}", stem, extension)),
+        }
+    }
+}
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.input);
+        let _ = fs::remove_file(&self.output);
+    }
+}
+
+/// Render `diagram` to SVG with `mmdc`, passing `args` through (e.g. `["-t", "dark"]`)
+///
+/// The process is killed if it runs longer than `timeout`.
+pub(crate) fn render_svg(
+    diagram: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<String, MmdcError> {
+    let svg = render(diagram, "svg", args, timeout)?;
+    String::from_utf8(svg).map_err(|e| MmdcError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+/// Render `diagram` to a PNG image with `mmdc`, like [`render_svg`]
+pub(crate) fn render_png(
+    diagram: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<Vec<u8>, MmdcError> {
+    render(diagram, "png", args, timeout)
+}
+
+/// Run `mmdc`, which picks the output format from the file extension
+fn render(
+    diagram: &str,
+    extension: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<Vec<u8>, MmdcError> {
+    let files = TempFiles::new(extension);
+    fs::write(&files.input, diagram).map_err(MmdcError::Io)?;
+
+    let mut child = Command::new("mmdc")
+        .arg("-i")
+        .arg(&files.input)
+        .arg("-o")
+        .arg(&files.output)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => MmdcError::Unavailable,
+            _ => MmdcError::Io(e),
+        })?;
+
+    // Drain stderr on another thread so a chatty process cannot block on a full pipe
+    let mut stderr_pipe = child.stderr.take();
+    let stderr_reader = thread::spawn(move || {
+        let mut stderr = String::new();
+        if let Some(pipe) = stderr_pipe.as_mut() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        stderr
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait().map_err(MmdcError::Io)? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(MmdcError::TimedOut(timeout));
+            }
+            None => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+
+    let stderr = stderr_reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(MmdcError::Failed(
+            error_message(&stderr).unwrap_or_else(|| format!("Mermaid CLI exited with {}", status)),
+        ));
+    }
+    fs::read(&files.output).map_err(MmdcError::Io)
+}
+
+/// Error message from `mmdc` stderr, without the source excerpt and stack trace
+///
+/// ```text
+/// Error: Parse error on line 2:
+/// graph TD    A-->-->B
+/// ---------------^
+/// Expecting 'AMP', got 'LINK'
+///     at Parser.parseError (...)
+/// ```
+///
+/// becomes `Parse error on line 2: Expecting 'AMP', got 'LINK'`.
+fn error_message(stderr: &str) -> Option<String> {
+    let lines: Vec<&str> = stderr
+        .lines()
+        .map(str::trim)
+        .take_while(|line| !line.starts_with("at "))
+        .collect();
+
+    let mut parts = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        let is_caret =
+            |line: &str| line.contains('^') && line.chars().all(|c| c == '-' || c == '^');
+        let before_caret = lines.get(idx + 1).is_some_and(|next| is_caret(next));
+        if line.is_empty() || is_caret(line) || before_caret || line.contains("parseError (") {
+            continue;
+        }
+        let line = line.strip_prefix("Error:").unwrap_or(line).trim();
+        parts.push(line.trim_end_matches(':'));
+    }
+    (!parts.is_empty()).then(|| parts.join(": "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_message_drops_excerpt_and_stack() {
+        let stderr = "\nError: Parse error on line 2:\ngraph TD    A-->-->B\n---------------^\n\
+                      Expecting 'AMP', 'COLON', got 'LINK'\nParser.parseError (/x/mermaid.js:1:2)\n    \
+                      at Parser.parse (/x/mermaid.js:3:4)\n";
+        assert_eq!(
+            error_message(stderr).as_deref(),
+            Some("Parse error on line 2: Expecting 'AMP', 'COLON', got 'LINK'")
+        );
+        assert_eq!(error_message("\n  \n"), None);
+    }
+}
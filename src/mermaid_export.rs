@@ -0,0 +1,135 @@
+//! Exporting the Mermaid diagrams of a document as separate files.
+
+use crate::ast::{for_each_node, plain_text, MermaidConfig, Node};
+use crate::mermaid_cli;
+use crate::slug::{slugify, SlugGenerator};
+use std::time::Duration;
+
+/// Longest a single `mmdc` render may take
+const RENDER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// File format of an exported diagram
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiagramFormat {
+    /// Mermaid source (`.mmd`), for the Mermaid live editor or other tools
+    #[default]
+    Mmd,
+    /// SVG image rendered with the Mermaid CLI
+    Svg,
+    /// PNG image at twice the natural size, rendered with the Mermaid CLI
+    Png,
+}
+
+impl DiagramFormat {
+    /// File extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            DiagramFormat::Mmd => "mmd",
+            DiagramFormat::Svg => "svg",
+            DiagramFormat::Png => "png",
+        }
+    }
+}
+
+/// A Mermaid diagram with the file name it is exported under
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagramExport {
+    /// File stem, unique within the document: the slug of the diagram's `name=` or of
+    /// the nearest heading above it, `diagram` when there is neither
+    pub name: String,
+    /// Diagram source, preceded by a `%%{init}%%` directive carrying its merged
+    /// configuration if it has any
+    pub source: String,
+}
+
+impl DiagramExport {
+    /// Contents of the exported file: the source itself, or an image rendered by `mmdc`
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if the Mermaid CLI is not installed, fails, or times out
+    pub fn render(&self, format: DiagramFormat) -> Result<Vec<u8>, String> {
+        let rendered = match format {
+            DiagramFormat::Mmd => return Ok(self.source.clone().into_bytes()),
+            DiagramFormat::Svg => {
+                mermaid_cli::render_svg(&self.source, &[], RENDER_TIMEOUT).map(String::into_bytes)
+            }
+            DiagramFormat::Png => {
+                mermaid_cli::render_png(&self.source, &["-s", "2", "-b", "white"], RENDER_TIMEOUT)
+            }
+        };
+        rendered.map_err(|e| e.to_string())
+    }
+}
+
+/// Every Mermaid diagram in `nodes`, in document order, with a unique file name
+///
+/// Diagrams nested in blockquotes, list items, and other blocks are included. Repeated
+/// names get a numeric suffix (`setup`, `setup-1`). `{{diagram:name}}` references are
+/// not exported again.
+pub fn diagram_exports(nodes: &[Node]) -> Vec<DiagramExport> {
+    let mut names = SlugGenerator::new();
+    let mut heading = None;
+    let mut exports = Vec::new();
+    for_each_node(nodes, &mut |node| match node {
+        Node::Heading { content, .. }
+        | Node::Section {
+            heading: content, ..
+        } => heading = Some(content),
+        Node::MermaidDiagram {
+            diagram,
+            name,
+            config,
+            ..
+        } => {
+            let title = match (name, heading) {
+                (Some(name), _) => name.clone(),
+                (None, Some(heading)) => plain_text(heading),
+                (None, None) => String::new(),
+            };
+            let title = match slugify(&title).trim_matches('-') {
+                "" => "diagram".to_string(),
+                slug => slug.to_string(),
+            };
+            exports.push(DiagramExport {
+                name: names.generate(&title),
+                source: with_init_directive(diagram, config.as_ref()),
+            });
+        }
+        _ => {}
+    });
+    exports
+}
+
+/// `diagram` preceded by a `%%{init}%%` directive holding `config`, if it sets anything
+fn with_init_directive(diagram: &str, config: Option<&MermaidConfig>) -> String {
+    let Some(config) = config else {
+        return diagram.to_string();
+    };
+    let mut variables = serde_json::Map::new();
+    for (key, value) in config.theme_variables.iter().flatten() {
+        variables.insert(key.clone(), value.clone().into());
+    }
+    if let Some(font_size) = &config.font_size {
+        variables.insert("fontSize".to_string(), font_size.clone().into());
+    }
+    if let Some(font_family) = &config.font_family {
+        variables.insert("fontFamily".to_string(), font_family.clone().into());
+    }
+
+    let mut init = serde_json::Map::new();
+    if let Some(theme) = &config.theme {
+        init.insert("theme".to_string(), theme.clone().into());
+    }
+    if !variables.is_empty() {
+        init.insert("themeVariables".to_string(), variables.into());
+    }
+    if init.is_empty() {
+        return diagram.to_string();
+    }
+    format!(
+        "%%{{init: {}}}%%\n{}",
+        serde_json::Value::Object(init),
+        diagram
+    )
+}
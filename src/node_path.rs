@@ -0,0 +1,146 @@
+//! Iteration over the nodes of a document with their positions.
+
+use crate::ast::Node;
+use std::fmt;
+use std::iter::Enumerate;
+use std::slice::Iter;
+
+/// Position of a node: its index among the top-level nodes, then its index among the
+/// children of each enclosing admonition, container, details block, or section
+///
+/// Displayed as the indices joined with `.` (`2.0.1`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodePath(Vec<usize>);
+
+impl NodePath {
+    /// Indices from the top level down
+    pub fn indices(&self) -> &[usize] {
+        &self.0
+    }
+
+    /// Number of enclosing blocks (0 for a top-level node)
+    pub fn depth(&self) -> usize {
+        self.0.len().saturating_sub(1)
+    }
+
+    /// Path of the enclosing block, or `None` for a top-level node
+    pub fn parent(&self) -> Option<NodePath> {
+        match self.0.split_last() {
+            Some((_, [])) | None => None,
+            Some((_, parent)) => Some(NodePath(parent.to_vec())),
+        }
+    }
+
+    /// Path of the child at `index` of the node at this path
+    pub fn child(&self, index: usize) -> NodePath {
+        let mut indices = self.0.clone();
+        indices.push(index);
+        NodePath(indices)
+    }
+}
+
+impl From<Vec<usize>> for NodePath {
+    fn from(indices: Vec<usize>) -> Self {
+        NodePath(indices)
+    }
+}
+
+impl fmt::Display for NodePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, index) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator returned by [`iter_nodes`]
+pub struct NodeIter<'a> {
+    stack: Vec<Enumerate<Iter<'a, Node>>>,
+    path: Vec<usize>,
+}
+
+impl<'a> Iterator for NodeIter<'a> {
+    type Item = (NodePath, &'a Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let level = self.stack.len().checked_sub(1)?;
+            let Some((index, node)) = self.stack[level].next() else {
+                self.stack.pop();
+                continue;
+            };
+            self.path.truncate(level);
+            self.path.push(index);
+            let path = NodePath(self.path.clone());
+            if let Some(children) = node.children() {
+                self.stack.push(children.iter().enumerate());
+            }
+            return Some((path, node));
+        }
+    }
+}
+
+/// Every node with its path, in document order (parents before their children)
+///
+/// Paths stay valid as long as the document is not restructured, so they can be
+/// collected first and used with [`node_at_mut`] for targeted edits:
+///
+/// ```
+/// use md_parser::{iter_nodes, node_at_mut, Node, Parser};
+///
+/// let mut ast = Parser::new("```a\n1\n```\n\n> [!NOTE]\n> ```b\n> 2\n> ```\n".to_string())?.parse()?;
+/// let (path, _) = iter_nodes(&ast)
+///     .filter(|(_, node)| matches!(node, Node::CodeBlock { .. }))
+///     .nth(1)
+///     .unwrap();
+/// assert_eq!(path.to_string(), "1.0");
+/// *node_at_mut(&mut ast, &path).unwrap() = Node::HorizontalRule { span: None };
+/// # Ok::<(), md_parser::ParseError>(())
+/// ```
+pub fn iter_nodes(nodes: &[Node]) -> NodeIter<'_> {
+    NodeIter {
+        stack: vec![nodes.iter().enumerate()],
+        path: Vec::new(),
+    }
+}
+
+/// Node at `path`, or `None` if the path leads nowhere
+pub fn node_at<'a>(nodes: &'a [Node], path: &NodePath) -> Option<&'a Node> {
+    let (first, rest) = path.0.split_first()?;
+    rest.iter().try_fold(nodes.get(*first)?, |node, &index| {
+        node.children()?.get(index)
+    })
+}
+
+/// Mutable node at `path`, or `None` if the path leads nowhere
+pub fn node_at_mut<'a>(nodes: &'a mut [Node], path: &NodePath) -> Option<&'a mut Node> {
+    let (first, rest) = path.0.split_first()?;
+    rest.iter()
+        .try_fold(nodes.get_mut(*first)?, |node, &index| {
+            node.children_mut()?.get_mut(index)
+        })
+}
+
+/// Call `f` with every node and its path, in the order of [`iter_nodes`]
+///
+/// The mutable counterpart of [`iter_nodes`]. Children are visited after `f` returns,
+/// so they are those of the node as `f` left it.
+pub fn visit_nodes_mut(nodes: &mut [Node], mut f: impl FnMut(&NodePath, &mut Node)) {
+    visit_mut(nodes, &mut Vec::new(), &mut f);
+}
+
+fn visit_mut(nodes: &mut [Node], path: &mut Vec<usize>, f: &mut impl FnMut(&NodePath, &mut Node)) {
+    for (index, node) in nodes.iter_mut().enumerate() {
+        path.push(index);
+        let node_path = NodePath(path.clone());
+        f(&node_path, node);
+        if let Some(children) = node.children_mut() {
+            visit_mut(children, path, f);
+        }
+        path.pop();
+    }
+}
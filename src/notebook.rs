@@ -0,0 +1,272 @@
+//! Jupyter notebook (`.ipynb`) input.
+
+use crate::ast::{Attributes, Inline, Node, ParseError};
+use crate::config::ParserConfig;
+use crate::parser::Parser;
+use crate::renderer::{render_markdown, MarkdownStyle};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+
+/// Image types of rich outputs, in order of preference
+const IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif"];
+
+/// Class of the code blocks holding cell outputs
+pub const OUTPUT_CLASS: &str = "output";
+
+/// Notebook reading settings (`[notebook]` in the config file)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct NotebookConfig {
+    /// Add the outputs of code cells after their source: text as code blocks with class
+    /// `output`, and PNG, JPEG, and GIF images inline
+    #[serde(default = "default_true")]
+    pub outputs: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for NotebookConfig {
+    fn default() -> Self {
+        Self {
+            outputs: default_true(),
+        }
+    }
+}
+
+/// Errors that can occur while reading a notebook
+#[derive(Debug, Clone)]
+pub enum NotebookError {
+    /// The file is not JSON, or not a version 4 notebook
+    Invalid(String),
+    /// A Markdown cell failed to parse; `cell` counts from 1
+    Parse { cell: usize, error: ParseError },
+}
+
+impl fmt::Display for NotebookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotebookError::Invalid(message) => write!(f, "invalid notebook: {}", message),
+            NotebookError::Parse { cell, error } => write!(f, "cell {}: {}", cell, error),
+        }
+    }
+}
+
+impl Error for NotebookError {}
+
+/// Parse a Jupyter notebook (nbformat 4) into one AST
+///
+/// Markdown cells are parsed with `parser`, so their spans count lines within the cell;
+/// images attached to a cell (`attachment:name.png`) are inlined as data URLs. Code
+/// cells become code blocks in the kernel's language, followed by their outputs when
+/// `config.outputs` is set. Raw cells are left out, as are cells tagged
+/// `remove-cell`, the source of cells tagged `remove-input`, and the outputs of cells
+/// tagged `remove-output`.
+///
+/// # Errors
+///
+/// Returns `NotebookError` if the notebook is malformed or a Markdown cell fails to parse
+pub fn parse_notebook(
+    json: &str,
+    parser: &ParserConfig,
+    config: &NotebookConfig,
+) -> Result<Vec<Node>, NotebookError> {
+    let notebook: Value =
+        serde_json::from_str(json).map_err(|e| NotebookError::Invalid(e.to_string()))?;
+    let major = notebook.get("nbformat").and_then(Value::as_u64);
+    if major != Some(4) {
+        return Err(NotebookError::Invalid(match major {
+            Some(major) => format!("nbformat {} is not supported (only 4 is)", major),
+            None => "missing `nbformat`".to_string(),
+        }));
+    }
+    let cells = notebook
+        .get("cells")
+        .and_then(Value::as_array)
+        .ok_or_else(|| NotebookError::Invalid("missing `cells`".to_string()))?;
+    let metadata = &notebook["metadata"];
+    let language = metadata["kernelspec"]["language"]
+        .as_str()
+        .or_else(|| metadata["language_info"]["name"].as_str())
+        .map(str::to_string);
+
+    let mut nodes = Vec::new();
+    for (idx, cell) in cells.iter().enumerate() {
+        let tags: Vec<&str> = cell["metadata"]["tags"]
+            .as_array()
+            .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        if tags.contains(&"remove-cell") {
+            continue;
+        }
+        let source = multiline(&cell["source"]);
+        match cell["cell_type"].as_str() {
+            Some("markdown") => {
+                let source = inline_attachments(&source, &cell["attachments"]);
+                let cell_nodes = Parser::with_config(source, parser.clone())
+                    .and_then(|mut cell_parser| cell_parser.parse())
+                    .map_err(|error| NotebookError::Parse {
+                        cell: idx + 1,
+                        error,
+                    })?;
+                nodes.extend(cell_nodes);
+            }
+            Some("code") => {
+                if !tags.contains(&"remove-input") && !source.trim().is_empty() {
+                    nodes.push(Node::CodeBlock {
+                        lang: language.clone(),
+                        code: source.trim_end_matches('\n').to_string(),
+                        attributes: Attributes::default(),
+                    });
+                }
+                if config.outputs && !tags.contains(&"remove-output") {
+                    for output in cell["outputs"].as_array().into_iter().flatten() {
+                        nodes.extend(output_node(output));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(nodes)
+}
+
+/// [`parse_notebook`], written back as Markdown
+///
+/// Nested list items are indented by `parser.list_indent`, so the result parses back
+/// with `parser` into the same nodes, apart from source spans. Output blocks keep their
+/// `output` class only when `enable_attributes` is on.
+///
+/// # Errors
+///
+/// Returns `NotebookError` if the notebook is malformed or a Markdown cell fails to parse
+pub fn notebook_to_markdown(
+    json: &str,
+    parser: &ParserConfig,
+    config: &NotebookConfig,
+) -> Result<String, NotebookError> {
+    let mut nodes = parse_notebook(json, parser, config)?;
+    if !parser.enable_attributes {
+        // `{.output}` would be read back as the language of the block
+        for node in &mut nodes {
+            if let Node::CodeBlock { attributes, .. } = node {
+                *attributes = Attributes::default();
+            }
+        }
+    }
+    Ok(render_markdown(
+        &nodes,
+        &MarkdownStyle::plain(parser.list_indent),
+    ))
+}
+
+/// Text of a notebook string field, which may be split into a list of lines
+fn multiline(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// `source` with `attachment:name` URLs replaced by data URLs of the cell's attachments
+fn inline_attachments(source: &str, attachments: &Value) -> String {
+    let mut source = source.to_string();
+    for (name, data) in attachments.as_object().into_iter().flatten() {
+        if let Some(url) = image_data_url(data) {
+            source = source.replace(&format!("(attachment:{})", name), &format!("({})", url));
+        }
+    }
+    source
+}
+
+/// Data URL of the first image in a MIME bundle
+fn image_data_url(bundle: &Value) -> Option<String> {
+    IMAGE_TYPES.iter().find_map(|mime| {
+        let data: String = multiline(bundle.get(*mime)?).split_whitespace().collect();
+        Some(format!("data:{};base64,{}", mime, data))
+    })
+}
+
+/// Node showing one cell output, if it has anything to show
+fn output_node(output: &Value) -> Option<Node> {
+    let text_block = |text: String, class: Option<&str>| {
+        let text = text.trim_end_matches('\n');
+        (!text.is_empty()).then(|| Node::CodeBlock {
+            lang: None,
+            code: text.to_string(),
+            attributes: Attributes {
+                classes: std::iter::once(OUTPUT_CLASS)
+                    .chain(class)
+                    .map(str::to_string)
+                    .collect(),
+                ..Attributes::default()
+            },
+        })
+    };
+    match output["output_type"].as_str()? {
+        "stream" => {
+            let class = (output["name"].as_str() == Some("stderr")).then_some("stderr");
+            text_block(multiline(&output["text"]), class)
+        }
+        "execute_result" | "display_data" => {
+            let data = &output["data"];
+            if let Some(url) = image_data_url(data) {
+                return Some(Node::Paragraph {
+                    content: vec![Inline::Image {
+                        alt: "output".to_string(),
+                        url,
+                        attributes: Attributes::default(),
+                    }],
+                });
+            }
+            text_block(multiline(&data["text/plain"]), None)
+        }
+        "error" => {
+            let traceback: Vec<String> = output["traceback"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(strip_ansi)
+                .collect();
+            text_block(traceback.join("\n"), Some("error"))
+        }
+        _ => None,
+    }
+}
+
+/// `text` without ANSI escape sequences (`\x1b[31m`), which color tracebacks
+fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip `[`, parameters, and the final letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(
+            strip_ansi("\u{1b}[0;31mValueError\u{1b}[0m: bad"),
+            "ValueError: bad"
+        );
+    }
+}
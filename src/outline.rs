@@ -0,0 +1,152 @@
+//! Hierarchical document outline.
+
+use crate::ast::{plain_text, Attributes, Inline, LineRange, Node};
+use crate::folding::section_spans;
+use crate::parse_result::ParseResult;
+use crate::slug::SlugGenerator;
+use crate::transform::nest_sections;
+use serde::{Deserialize, Serialize};
+
+/// A heading together with the nodes and subsections that belong to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutlineSection {
+    /// Heading level (1-6)
+    pub level: u8,
+    /// Heading text without formatting
+    pub title: String,
+    /// Heading anchor: the explicit `{#id}` or a unique slug
+    pub anchor: String,
+    /// Heading inline content
+    pub heading: Vec<Inline>,
+    /// Nodes between this heading and the next heading
+    pub body: Vec<Node>,
+    /// Sections nested under this heading
+    pub children: Vec<OutlineSection>,
+    /// Source lines from the heading to the end of the section; only set by
+    /// [`Outline::from_result`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<LineRange>,
+}
+
+/// A document as a tree of sections
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Outline {
+    /// Nodes before the first heading
+    pub preamble: Vec<Node>,
+    /// Top-level sections
+    pub sections: Vec<OutlineSection>,
+}
+
+impl Outline {
+    /// Build an outline from an AST using heading levels
+    ///
+    /// Sections are formed as by [`nest_sections`]; ASTs that already contain
+    /// `Node::Section` nodes (`enable_sections`) are used as they are.
+    pub fn from_nodes(nodes: &[Node]) -> Self {
+        let mut outline = Outline::default();
+        let mut slugs = SlugGenerator::new();
+
+        for node in nest_sections(nodes) {
+            match node {
+                Node::Section {
+                    level,
+                    heading,
+                    attributes,
+                    children,
+                } => outline.sections.push(outline_section(
+                    level, heading, attributes, children, &mut slugs,
+                )),
+                other => outline.preamble.push(other),
+            }
+        }
+
+        outline
+    }
+
+    /// Build an outline from a parse result, with the source lines of each section
+    ///
+    /// With `enable_sections`, only top-level sections get a span, since nested
+    /// sections have none.
+    pub fn from_result(result: &ParseResult) -> Self {
+        let mut outline = Outline::from_nodes(&result.nodes);
+        let mut headings = section_spans(result).into_iter();
+        let mut spans = Vec::new();
+        for (node, span) in result.nodes.iter().zip(&result.spans) {
+            match node {
+                Node::Heading { .. } => spans.push(headings.next()),
+                Node::Section { children, .. } => {
+                    spans.push(Some(*span));
+                    spans.extend(std::iter::repeat_n(None, count_sections(children)));
+                }
+                _ => {}
+            }
+        }
+        let mut spans = spans.into_iter();
+        for section in &mut outline.sections {
+            assign_spans(section, &mut spans);
+        }
+        outline
+    }
+}
+
+/// Number of `Node::Section` nodes in `nodes`, at any depth
+fn count_sections(nodes: &[Node]) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Section { children, .. } => 1 + count_sections(children),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Give `section` and its subsections the next spans, in pre-order
+fn assign_spans(section: &mut OutlineSection, spans: &mut impl Iterator<Item = Option<LineRange>>) {
+    section.span = spans.next().flatten();
+    for child in &mut section.children {
+        assign_spans(child, spans);
+    }
+}
+
+/// Convert a `Node::Section` into an outline section, splitting body from subsections
+fn outline_section(
+    level: u8,
+    heading: Vec<Inline>,
+    attributes: Attributes,
+    children: Vec<Node>,
+    slugs: &mut SlugGenerator,
+) -> OutlineSection {
+    let title = plain_text(&heading);
+    let anchor = match attributes.id {
+        Some(id) => {
+            slugs.reserve(&id);
+            id
+        }
+        None => slugs.generate(&title),
+    };
+    let mut section = OutlineSection {
+        level,
+        anchor,
+        title,
+        heading,
+        body: Vec::new(),
+        children: Vec::new(),
+        span: None,
+    };
+
+    for child in children {
+        match child {
+            Node::Section {
+                level,
+                heading,
+                attributes,
+                children,
+            } => section
+                .children
+                .push(outline_section(level, heading, attributes, children, slugs)),
+            other => section.body.push(other),
+        }
+    }
+
+    section
+}
@@ -0,0 +1,82 @@
+//! Everything a single parse produces, returned by [`Parser::parse_full`](crate::Parser::parse_full).
+
+use crate::ast::{collect_link_urls, LineRange, Node, ParseError, ParseWarning};
+use crate::definitions::DefinitionMap;
+use serde::{Deserialize, Serialize};
+
+/// AST, warnings, and metadata of a document, from one parse
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseResult {
+    /// Top-level nodes, as returned by [`Parser::parse`](crate::Parser::parse)
+    pub nodes: Vec<Node>,
+    /// Warnings collected while parsing
+    pub warnings: Vec<ParseWarning>,
+    /// Text between the `---` delimiters of a YAML frontmatter block, unparsed
+    pub frontmatter: Option<String>,
+    /// Size of the document and of its AST
+    pub stats: ParseStats,
+    /// Source lines of each node in `nodes`, in the same order
+    ///
+    /// A section covers its heading and everything nested under it. Lines are those of
+    /// the text after include expansion and variable substitution.
+    pub spans: Vec<LineRange>,
+    #[serde(default)]
+    pub(crate) definitions: DefinitionMap,
+}
+
+impl ParseResult {
+    /// Link reference and footnote definitions of the document, those given with
+    /// [`Parser::set_external_definitions`](crate::Parser::set_external_definitions),
+    /// and the references to them
+    pub fn definitions(&self) -> &DefinitionMap {
+        &self.definitions
+    }
+
+    /// Source line of the first link or image to `url`
+    ///
+    /// The link is looked for in the first top-level node that links to `url`, on the
+    /// first line of its span containing `](url`, or else on the span's first line.
+    /// `source` is the text the result was parsed from.
+    pub fn link_line(&self, url: &str, source: &str) -> Option<usize> {
+        let top = self.nodes.iter().position(|node| {
+            collect_link_urls(std::slice::from_ref(node))
+                .iter()
+                .any(|link| link == url)
+        })?;
+        let span = self.spans.get(top)?;
+        let target = format!("]({}", url);
+        let line = (span.start..=span.end).find(|&line| {
+            source
+                .lines()
+                .nth(line - 1)
+                .is_some_and(|text| text.contains(&target))
+        });
+        Some(line.unwrap_or(span.start))
+    }
+
+    /// Serialize the whole result to a JSON string
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::SerializationError` if serialization fails
+    pub fn to_json(&self) -> Result<String, ParseError> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            ParseError::SerializationError(format!("JSON serialization failed: {}", e))
+        })
+    }
+}
+
+/// Counts describing a parsed document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ParseStats {
+    /// Number of source lines
+    pub lines: usize,
+    /// Size of the source in bytes
+    pub bytes: usize,
+    /// Number of block nodes, including nested ones
+    pub blocks: usize,
+    /// Number of inline nodes, including nested ones
+    pub inlines: usize,
+    /// Time the parse took, in microseconds
+    pub duration_us: u128,
+}
@@ -0,0 +1,165 @@
+//! Attribute list parsing (`{#id .class key=val}`).
+
+use crate::ast::{parse_line_ranges, Attributes};
+
+/// Split a trailing attribute list off the end of a line
+///
+/// Returns the text before the attribute list (trimmed) and the parsed attributes.
+/// If the line does not end with a well-formed `{...}` block, the text is returned
+/// unchanged and the attributes are `None`.
+pub(super) fn split_trailing_attributes(text: &str) -> (&str, Option<Attributes>) {
+    let trimmed = text.trim_end();
+    if !trimmed.ends_with('}') {
+        return (text, None);
+    }
+
+    let open = match trimmed.rfind('{') {
+        Some(pos) => pos,
+        None => return (text, None),
+    };
+
+    match parse_attribute_block(&trimmed[open..]) {
+        Some(attributes) => (trimmed[..open].trim_end(), Some(attributes)),
+        None => (text, None),
+    }
+}
+
+/// Split a trailing line range list (`{1,3-5}`) off a code fence info string
+///
+/// Returns the text before it and the ranges without braces or spaces, or the input and
+/// `None` if it does not end with a valid list.
+pub(super) fn split_trailing_line_ranges(text: &str) -> (&str, Option<String>) {
+    let trimmed = text.trim_end();
+    let Some(open) = trimmed.strip_suffix('}').and_then(|rest| rest.rfind('{')) else {
+        return (text, None);
+    };
+    let ranges: String = trimmed[open + 1..trimmed.len() - 1]
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    match parse_line_ranges(&ranges) {
+        Some(_) => (trimmed[..open].trim_end(), Some(ranges)),
+        None => (text, None),
+    }
+}
+
+/// Parse an attribute block of the form `{#id .class key=val key2="quoted val"}`
+///
+/// Returns `None` if the block is malformed or empty.
+pub(super) fn parse_attribute_block(block: &str) -> Option<Attributes> {
+    let inner = block.trim().strip_prefix('{')?.strip_suffix('}')?.trim();
+    if inner.is_empty() {
+        return None;
+    }
+
+    let mut attributes = Attributes::default();
+    for token in tokenize(inner)? {
+        if let Some(id) = token.strip_prefix('#') {
+            if id.is_empty() {
+                return None;
+            }
+            attributes.id = Some(id.to_string());
+        } else if let Some(class) = token.strip_prefix('.') {
+            if class.is_empty() {
+                return None;
+            }
+            attributes.classes.push(class.to_string());
+        } else if let Some((key, value)) = token.split_once('=') {
+            if key.is_empty() {
+                return None;
+            }
+            let value = value.trim_matches('"');
+            attributes.pairs.insert(key.to_string(), value.to_string());
+        } else {
+            return None;
+        }
+    }
+
+    Some(attributes)
+}
+
+/// Split attribute block contents on whitespace, keeping double-quoted values intact
+fn tokenize(inner: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for ch in inner.chars() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(ch);
+            }
+            '{' | '}' if !in_quotes => return None,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if in_quotes {
+        return None;
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Some(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_id_class_and_pairs() {
+        let attrs = parse_attribute_block("{#intro .lead audience=staff}").unwrap();
+        assert_eq!(attrs.id.as_deref(), Some("intro"));
+        assert_eq!(attrs.classes, vec!["lead".to_string()]);
+        assert_eq!(attrs.get("audience"), Some("staff"));
+    }
+
+    #[test]
+    fn test_parse_quoted_value() {
+        let attrs = parse_attribute_block("{title=\"Hello world\"}").unwrap();
+        assert_eq!(attrs.get("title"), Some("Hello world"));
+    }
+
+    #[test]
+    fn test_reject_malformed() {
+        assert!(parse_attribute_block("{}").is_none());
+        assert!(parse_attribute_block("{just words}").is_none());
+        assert!(parse_attribute_block("{key=\"unterminated}").is_none());
+    }
+
+    #[test]
+    fn test_split_trailing_line_ranges() {
+        assert_eq!(
+            split_trailing_line_ranges("rust {1, 3-5}"),
+            ("rust", Some("1,3-5".to_string()))
+        );
+        assert_eq!(
+            split_trailing_line_ranges("{2}"),
+            ("", Some("2".to_string()))
+        );
+        assert_eq!(
+            split_trailing_line_ranges("rust {5-3}"),
+            ("rust {5-3}", None)
+        );
+        assert_eq!(split_trailing_line_ranges("rust {0}"), ("rust {0}", None));
+        assert_eq!(split_trailing_line_ranges("rust {.x}"), ("rust {.x}", None));
+    }
+
+    #[test]
+    fn test_split_trailing() {
+        let (text, attrs) = split_trailing_attributes("Internals {audience=staff}");
+        assert_eq!(text, "Internals");
+        assert_eq!(attrs.unwrap().get("audience"), Some("staff"));
+
+        let (text, attrs) = split_trailing_attributes("Set {a, b}");
+        assert_eq!(text, "Set {a, b}");
+        assert!(attrs.is_none());
+    }
+}
@@ -0,0 +1,64 @@
+//! Custom block syntax registered by library users.
+
+use crate::ast::Node;
+use std::fmt;
+use std::sync::Arc;
+
+/// Function recognizing a custom block: the lines being parsed and the index of the
+/// current line in, the node and the index of the first line after the block out
+type DetectBlock = dyn Fn(&[&str], usize) -> Option<(Node, usize)> + Send + Sync;
+
+/// A custom block-level syntax, tried at the start of every block
+///
+/// Registered with [`Parser::add_block_syntax`](crate::Parser::add_block_syntax).
+/// The function is called with the lines being parsed (the document, or the body of
+/// an admonition, details block, or container) and the index of the line starting
+/// the next block; blank lines are skipped before it is called. It returns `None`
+/// when the line does not start its block, or the node for the block and the index
+/// of the first line after it. Custom syntaxes are tried in registration order
+/// before the built-in ones, so they can claim lines that would otherwise become a
+/// list or a paragraph.
+///
+/// Cloning shares the underlying closure. Two syntaxes compare equal only when they
+/// wrap the same closure instance.
+#[derive(Clone)]
+pub struct BlockSyntax {
+    name: String,
+    detect: Arc<DetectBlock>,
+}
+
+impl BlockSyntax {
+    /// Wrap a closure under a name used in debug output
+    pub fn new(
+        name: impl Into<String>,
+        detect: impl Fn(&[&str], usize) -> Option<(Node, usize)> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            detect: Arc::new(detect),
+        }
+    }
+
+    /// Name given to [`BlockSyntax::new`]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Try the syntax at `idx`; the returned index is at least `idx + 1`
+    pub(super) fn detect(&self, lines: &[&str], idx: usize) -> Option<(Node, usize)> {
+        (self.detect)(lines, idx)
+            .map(|(node, next_idx)| (node, next_idx.clamp(idx + 1, lines.len())))
+    }
+}
+
+impl fmt::Debug for BlockSyntax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BlockSyntax({:?})", self.name)
+    }
+}
+
+impl PartialEq for BlockSyntax {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.detect, &other.detect)
+    }
+}
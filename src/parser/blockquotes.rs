@@ -1,9 +1,11 @@
 //! Blockquote parsing.
 
 use crate::ast::{Node, ParseError, Span};
+use crate::config::ParserConfig;
 
 use super::inline::parse_inline;
 use super::inline::RegexPatterns;
+use super::NestedBlocks;
 
 /// Check if a line is a blockquote and return its nesting level
 ///
@@ -19,16 +21,43 @@ pub(super) fn detect_blockquote_line(line: &str) -> Option<u8> {
     // Count leading `>` characters
     let level = trimmed.chars().take_while(|&c| c == '>').count();
     if level > 0 {
-        Some(level as u8)
+        Some(u8::try_from(level).unwrap_or(u8::MAX))
     } else {
         None
     }
 }
 
+/// Content of a line of a blockquote at nesting `level` without its `>` markers and
+/// the space after them, or `None` if the line does not continue the blockquote
+fn strip_quote_markers(line: &str, level: u8) -> Option<&str> {
+    let mut rest = line.trim_start();
+    for _ in 0..level {
+        rest = rest.strip_prefix('>')?;
+    }
+    Some(rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+/// Whether the line at `idx` of a blockquote at nesting `level` opens its block
+/// content: a fenced code block or a table, with `>`-prefixed rows and separator
+fn opens_quoted_blocks(lines: &[&str], idx: usize, level: u8, config: &ParserConfig) -> bool {
+    if detect_blockquote_line(lines[idx]) != Some(level) {
+        return false;
+    }
+    let Some(content) = strip_quote_markers(lines[idx], level) else {
+        return false;
+    };
+    let next = lines
+        .get(idx + 1)
+        .and_then(|line| strip_quote_markers(line, level));
+    super::blocks::is_code_fence(content.trim(), config)
+        || super::tables::starts_table(content, next)
+}
+
 /// Collect blockquote lines starting at the given index
 ///
 /// Returns the blockquote text (with `>` prefixes stripped) and the new line index after the blockquote.
-/// Stops when encountering an empty line, a different nesting level, or other block elements.
+/// Stops when encountering an empty line, a different nesting level, other block elements,
+/// or the start of the quote's block content.
 pub(super) fn collect_blockquote_lines(
     lines: &[&str],
     start_idx: usize,
@@ -52,13 +81,15 @@ pub(super) fn collect_blockquote_lines(
         }
 
         // Stop at other block elements
-        if current_line.starts_with('#') || current_line.starts_with(&config.code_fence_pattern) {
+        if super::blocks::is_atx_heading(current_line, config)
+            || super::blocks::is_code_fence(current_line, config)
+        {
             break;
         }
 
         // Stop at list lines
-        if super::lists::detect_list_line(lines[i]).is_some()
-            || super::lists::detect_ordered_list_line(lines[i]).is_some()
+        if super::lists::detect_list_line(lines[i], config).is_some()
+            || super::lists::detect_ordered_list_line(lines[i], config).is_some()
         {
             break;
         }
@@ -68,6 +99,10 @@ pub(super) fn collect_blockquote_lines(
             break;
         }
 
+        if opens_quoted_blocks(lines, i, nesting_level, config) {
+            break;
+        }
+
         // Check if it's a blockquote line at the same nesting level
         if let Some(level) = detect_blockquote_line(lines[i]) {
             if level == nesting_level {
@@ -95,9 +130,30 @@ pub(super) fn collect_blockquote_lines(
     (blockquote_text, i)
 }
 
+/// Collect the block content of a blockquote at nesting `level`, starting with the
+/// `>`-prefixed code fence or table at `start_idx`
+///
+/// The content runs over the following lines with the same `>` prefix, including
+/// blank ones; deeper-nested lines keep their extra markers. Returns the lines without
+/// the prefix and the index after them.
+fn collect_quoted_blocks<'a>(
+    lines: &[&'a str],
+    start_idx: usize,
+    level: u8,
+) -> (Vec<&'a str>, usize) {
+    let body: Vec<&str> = lines[start_idx..]
+        .iter()
+        .map_while(|line| strip_quote_markers(line, level))
+        .collect();
+    let end = start_idx + body.len();
+    (body, end)
+}
+
 /// Parse a blockquote starting at the given line index
 ///
-/// Returns the blockquote node and the new line index after the blockquote.
+/// Returns the blockquote node and the new line index after the blockquote. Text up to
+/// the first `>`-prefixed code fence or table is the inline content; that block and the
+/// quoted lines after it are parsed with `parse_blocks`.
 ///
 /// # Errors
 ///
@@ -107,6 +163,7 @@ pub(super) fn parse_blockquote(
     start_idx: usize,
     config: &crate::config::ParserConfig,
     regex_patterns: &RegexPatterns,
+    parse_blocks: &mut NestedBlocks<'_>,
 ) -> Result<(Node, usize), ParseError> {
     // Detect nesting level from first line
     let level = match detect_blockquote_line(lines[start_idx]) {
@@ -122,27 +179,31 @@ pub(super) fn parse_blockquote(
         }
     };
 
+    super::check_nesting(usize::from(level), config, start_idx)?;
+
     // Collect blockquote lines
-    let (blockquote_text, new_idx) = collect_blockquote_lines(lines, start_idx, config);
-
-    if blockquote_text.is_empty() {
-        // Empty blockquote - skip it
-        return Ok((
-            Node::Blockquote {
-                level,
-                content: Vec::new(),
-            },
-            new_idx,
-        ));
+    let (blockquote_text, mut new_idx) = collect_blockquote_lines(lines, start_idx, config);
+
+    // Fenced code blocks, tables, and the quoted lines after them are block content
+    let mut blocks = Vec::new();
+    if new_idx < lines.len() && opens_quoted_blocks(lines, new_idx, level, config) {
+        let (body, end) = collect_quoted_blocks(lines, new_idx, level);
+        blocks = parse_blocks(&body, new_idx)?;
+        new_idx = end;
     }
 
-    // Parse inline content
-    let inline_content = parse_inline(&blockquote_text, regex_patterns)?;
+    // Parse inline content; an empty blockquote has none
+    let content = if blockquote_text.is_empty() {
+        Vec::new()
+    } else {
+        parse_inline(&blockquote_text, regex_patterns)?
+    };
 
     Ok((
         Node::Blockquote {
             level,
-            content: inline_content,
+            content,
+            blocks,
         },
         new_idx,
     ))
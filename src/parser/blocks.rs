@@ -1,29 +1,105 @@
 //! Block-level element parsing (code blocks, headings, paragraphs).
 
-use crate::ast::{Node, ParseError, Span, ValidationStatus};
+use crate::ast::{
+    Attributes, Node, ParseError, ParseWarning, Span, ValidationStatus, WarningCode,
+    HIGHLIGHT_ATTRIBUTE,
+};
+use crate::cancel::CancellationToken;
 use crate::config::ParserConfig;
+use std::time::Duration;
 
+use super::attributes::{split_trailing_attributes, split_trailing_line_ranges};
 use super::inline::parse_inline;
 use super::inline::RegexPatterns;
-use super::mermaid::MermaidValidator;
+use super::mermaid::{error_line, MermaidValidator, CLI_UNAVAILABLE_WARNING};
+
+/// Value of a `name=arch` (or `name="arch"`) entry in a code fence info string
+fn diagram_name(info: &str) -> Option<String> {
+    info.split_whitespace()
+        .find_map(|token| token.strip_prefix("name="))
+        .map(|name| name.trim_matches('"'))
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// Whether `line` (trimmed) opens a fenced code block
+///
+/// As in CommonMark, a backtick fence whose info string contains a backtick is not a
+/// fence, so a paragraph may start with a code span such as ```` ``` a``b ``` ````.
+pub(crate) fn is_code_fence(line: &str, config: &ParserConfig) -> bool {
+    let Some(info) = line.strip_prefix(config.code_fence_pattern.as_str()) else {
+        return false;
+    };
+    !(config.code_fence_pattern.starts_with('`') && info.trim_start_matches('`').contains('`'))
+}
+
+/// Whether `line` (trimmed) opens an ATX heading
+///
+/// With `strict_atx` or `enable_hashtags`, the leading `#`s must be followed by
+/// whitespace or end the line, so `#hashtag` is text.
+pub(super) fn is_atx_heading(line: &str, config: &ParserConfig) -> bool {
+    let Some(rest) = line.strip_prefix('#') else {
+        return false;
+    };
+    !(config.strict_atx || config.enable_hashtags)
+        || rest
+            .trim_start_matches('#')
+            .chars()
+            .next()
+            .is_none_or(char::is_whitespace)
+}
+
+/// Heading text without its closing sequence (`## Title ##`)
+///
+/// The closing `#`s must follow whitespace or make up the whole text, so `# C#` and
+/// `# Title \#` keep theirs.
+fn strip_closing_sequence(content: &str) -> &str {
+    let text = content.trim_end_matches('#');
+    if text.is_empty() || text.ends_with(char::is_whitespace) {
+        text.trim_end()
+    } else {
+        content
+    }
+}
 
 /// Parse a fenced code block starting at the given line index
 ///
 /// Returns the node, the new line index after the code block, and any warnings.
-/// Errors with `UnclosedCodeBlock` if no closing fence is found before EOF.
+/// Errors with `UnclosedCodeBlock` if no closing fence is found before EOF (unless
+/// `error_recovery` is enabled, which closes the block at EOF with a warning), or with
+/// `Cancelled` if `cancel` fires before the Mermaid CLI would be invoked.
 pub(super) fn parse_code_block(
     lines: &[&str],
     start_idx: usize,
     config: &ParserConfig,
-    _regex_patterns: &RegexPatterns,
-) -> Result<(Node, usize, Vec<String>), ParseError> {
+    cancel: Option<&CancellationToken>,
+) -> Result<(Node, usize, Vec<ParseWarning>), ParseError> {
     let line = lines[start_idx].trim();
-    let lang_tag = line[config.code_fence_length..].trim();
+    // A configured fence length past the end of the fence pattern yields no tag
+    let mut lang_tag = line.get(config.code_fence_length..).unwrap_or("").trim();
+    // A trailing attribute list (```` ```rust {#id .class} ````) is not part of the language
+    let mut attributes = Attributes::default();
+    if config.enable_attributes {
+        if let (tag, Some(parsed)) = split_trailing_attributes(lang_tag) {
+            lang_tag = tag;
+            attributes = parsed;
+        } else if let (tag, Some(ranges)) = split_trailing_line_ranges(lang_tag) {
+            lang_tag = tag;
+            attributes
+                .pairs
+                .insert(HIGHLIGHT_ATTRIBUTE.to_string(), ranges);
+        }
+    }
     let lang = if lang_tag.is_empty() {
         None
     } else {
         Some(lang_tag.to_string())
     };
+    // Mermaid info strings may carry `name=...` after the language
+    let (first_word, info) = lang_tag
+        .split_once(char::is_whitespace)
+        .unwrap_or((lang_tag, ""));
+    let is_mermaid = first_word.eq_ignore_ascii_case(&config.mermaid_language);
 
     // Collect code block content until closing fence
     let mut code_lines = Vec::new();
@@ -38,18 +114,28 @@ pub(super) fn parse_code_block(
         i += 1;
     }
 
+    let mut warnings = Vec::new();
     if !is_closed {
         let span = Span {
             line: start_idx + 1,
             column: None,
         };
-        return Err(ParseError::UnclosedCodeBlock { span });
+        if !config.error_recovery {
+            return Err(ParseError::UnclosedCodeBlock { span });
+        }
+        warnings.push(ParseWarning::new(
+            WarningCode::UnclosedCodeBlock,
+            span,
+            "unclosed code block; closed at end of input",
+        ));
     }
+    // Skip the closing fence, if any
+    let next_idx = if is_closed { i + 1 } else { i };
 
     let code = code_lines.join("\n");
 
     // Special handling for Mermaid diagrams
-    if lang.as_ref().map(|s| s.to_lowercase()) == Some(config.mermaid_language.to_lowercase()) {
+    if is_mermaid {
         // Parse frontmatter and extract configuration
         let (inline_config, diagram_content) = MermaidValidator::parse_frontmatter(&code);
 
@@ -57,49 +143,92 @@ pub(super) fn parse_code_block(
         let merged_config = MermaidValidator::merge_config(&config.mermaid, inline_config);
 
         // Validate syntax if enabled
+        if config.mermaid.validate_syntax && config.mermaid.use_cli_validation {
+            super::check_cancelled(cancel, start_idx)?;
+        }
         let (validation_status, validation_warnings) = if config.mermaid.validate_syntax {
-            MermaidValidator::validate_syntax(&diagram_content, config.mermaid.use_cli_validation)
+            let cli_timeout = config
+                .mermaid
+                .use_cli_validation
+                .then(|| Duration::from_secs(config.mermaid.cli_timeout_secs));
+            MermaidValidator::validate_syntax(&diagram_content, cli_timeout)
         } else {
             (ValidationStatus::NotValidated, Vec::new())
         };
 
         // Collect warnings to return
-        let mut warnings = Vec::new();
+        let span = Span {
+            line: start_idx + 1,
+            column: None,
+        };
         for warning in &validation_warnings {
-            warnings.push(format!("Mermaid diagram validation warning: {}", warning));
+            let code = if warning == CLI_UNAVAILABLE_WARNING {
+                WarningCode::MermaidCliUnavailable
+            } else {
+                WarningCode::MermaidWarning
+            };
+            warnings.push(ParseWarning::new(
+                code,
+                span.clone(),
+                format!("Mermaid diagram validation warning: {}", warning),
+            ));
         }
 
         // Add validation errors to warnings (but keep as MermaidDiagram as requested)
         if let ValidationStatus::Invalid { ref errors } = validation_status {
+            // Errors naming a diagram line point at that line of the document
+            let first_line = diagram_content.trim().lines().next().unwrap_or("").trim();
+            let diagram_start = code
+                .lines()
+                .position(|line| line.trim() == first_line)
+                .unwrap_or(0);
             for error in errors {
-                warnings.push(format!("Mermaid diagram validation error: {}", error));
+                let span = match error_line(error) {
+                    Some(line) => Span {
+                        line: start_idx + 1 + diagram_start + line,
+                        column: None,
+                    },
+                    None => span.clone(),
+                };
+                warnings.push(ParseWarning::new(
+                    WarningCode::InvalidMermaid,
+                    span,
+                    format!("Mermaid diagram validation error: {}", error),
+                ));
             }
         }
 
         let node = Node::MermaidDiagram {
             diagram: diagram_content,
+            name: diagram_name(info),
             config: Some(merged_config),
             validation_status,
             warnings: validation_warnings,
         };
 
-        Ok((node, i + 1, warnings))
+        Ok((node, next_idx, warnings))
     } else {
-        Ok((Node::CodeBlock { lang, code }, i + 1, Vec::new()))
+        let node = Node::CodeBlock {
+            lang,
+            code,
+            attributes,
+        };
+        Ok((node, next_idx, warnings))
     }
 }
 
 /// Parse a heading from a line
 ///
 /// Returns `Some(node)` if a valid heading is found, `None` if not a heading.
-/// Errors with `InvalidHeadingLevel` if the line has more than 6 leading `#`.
+/// A closing sequence of `#`s is dropped. Errors with `InvalidHeadingLevel` if the
+/// line has more leading `#` than `max_heading_level`.
 pub(super) fn parse_heading(
     line: &str,
     line_number: usize,
     config: &ParserConfig,
     regex_patterns: &RegexPatterns,
 ) -> Result<Option<Node>, ParseError> {
-    if !line.starts_with('#') {
+    if !is_atx_heading(line, config) {
         return Ok(None);
     }
 
@@ -110,18 +239,28 @@ pub(super) fn parse_heading(
             column: None,
         };
         return Err(ParseError::InvalidHeadingLevel {
-            level: level as u8,
+            level: u8::try_from(level).unwrap_or(u8::MAX),
+            max: config.max_heading_level,
             span,
         });
     }
 
     if level > 0 {
-        let content = line[level..].trim();
+        let mut content = line[level..].trim();
+        let mut attributes = Attributes::default();
+        if config.enable_attributes {
+            if let (text, Some(attrs)) = split_trailing_attributes(content) {
+                content = text;
+                attributes = attrs;
+            }
+        }
+        content = strip_closing_sequence(content);
         if !content.is_empty() {
             let inline_content = parse_inline(content, regex_patterns)?;
             return Ok(Some(Node::Heading {
                 level: level as u8,
                 content: inline_content,
+                attributes,
             }));
         }
     }
@@ -129,42 +268,98 @@ pub(super) fn parse_heading(
     Ok(None)
 }
 
+/// Level of a setext heading underline: 1 for `===`, 2 for `---`
+///
+/// Underlines are a run of one character, optionally followed by spaces.
+fn setext_level(line: &str) -> Option<u8> {
+    let underline = line.trim();
+    if !underline.is_empty() && underline.bytes().all(|b| b == b'=') {
+        Some(1)
+    } else if !underline.is_empty() && underline.bytes().all(|b| b == b'-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Heading from the text of a paragraph followed by a setext underline
+///
+/// Trailing attribute lists are split off as for ATX headings.
+pub(super) fn setext_heading(
+    text: &str,
+    level: u8,
+    config: &ParserConfig,
+    regex_patterns: &RegexPatterns,
+) -> Result<Node, ParseError> {
+    let mut content = text;
+    let mut attributes = Attributes::default();
+    if config.enable_attributes {
+        if let (text, Some(attrs)) = split_trailing_attributes(content) {
+            content = text;
+            attributes = attrs;
+        }
+    }
+    Ok(Node::Heading {
+        level,
+        content: parse_inline(content, regex_patterns)?,
+        attributes,
+    })
+}
+
+/// Whether the raw line `line` starts a block, ending a paragraph before it
+///
+/// The lazy continuation of list items uses the same test.
+pub(super) fn interrupts_paragraph(line: &str, config: &ParserConfig) -> bool {
+    let trimmed = line.trim();
+    is_atx_heading(trimmed, config)
+        || super::horizontal_rules::detect_horizontal_rule(trimmed)
+        || is_code_fence(trimmed, config)
+        || ((config.enable_admonitions || config.enable_directives)
+            && super::containers::fence_info(trimmed).is_some())
+        || (config.enable_csv_tables && super::includes::detect_csv_include(trimmed).is_some())
+        || super::mermaid::detect_diagram_reference(trimmed).is_some()
+        || super::comments::detect_comment_start(trimmed)
+        || super::lists::list_interrupts_paragraph(line, config)
+        || super::tables::detect_table_row(line)
+        || super::blockquotes::detect_blockquote_line(line).is_some()
+}
+
 /// Collect paragraph lines starting at the given index
 ///
-/// Returns the paragraph text and the new line index after the paragraph
+/// Returns the paragraph text, the new line index after the paragraph, and the heading
+/// level when the paragraph ends in a setext underline (`===` or `---`). An underline
+/// is checked before rules, so `---` directly under text makes a heading.
 pub(super) fn collect_paragraph_lines(
     lines: &[&str],
     start_idx: usize,
     config: &ParserConfig,
-) -> (String, usize) {
+) -> (String, usize, Option<u8>) {
     let mut para_lines = Vec::new();
     let mut i = start_idx;
 
     while i < lines.len() {
         let current_line = lines[i].trim();
 
-        // Stop at empty line or block elements
+        // Stop at empty line
         if current_line.is_empty() {
             break;
         }
-        if current_line.starts_with('#') || current_line.starts_with(&config.code_fence_pattern) {
-            break;
-        }
 
-        // Stop at list lines (list parsing happens before paragraph collection)
-        if super::lists::detect_list_line(lines[i]).is_some()
-            || super::lists::detect_ordered_list_line(lines[i]).is_some()
-        {
-            break;
+        // The first line was not recognized as any other block, so it always belongs to
+        // the paragraph (this also guarantees progress, e.g. for a table row without a
+        // separator line)
+        if i == start_idx {
+            para_lines.push(current_line);
+            i += 1;
+            continue;
         }
 
-        // Stop at table rows (table parsing happens before paragraph collection)
-        if super::tables::detect_table_row(lines[i]) {
-            break;
+        if let Some(level) = setext_level(current_line) {
+            if level <= config.max_heading_level {
+                return (para_lines.join(" "), i + 1, Some(level));
+            }
         }
-
-        // Stop at blockquote lines (blockquote parsing happens before paragraph collection)
-        if super::blockquotes::detect_blockquote_line(lines[i]).is_some() {
+        if interrupts_paragraph(lines[i], config) {
             break;
         }
 
@@ -173,5 +368,5 @@ pub(super) fn collect_paragraph_lines(
     }
 
     let para_text = para_lines.join(" ");
-    (para_text, i)
+    (para_text, i, None)
 }
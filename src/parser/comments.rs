@@ -0,0 +1,58 @@
+//! Block-level HTML comments (`<!-- ... -->`) and Obsidian comments (`%% ... %%`).
+
+/// A comment occupying whole lines
+pub(super) struct CommentBlock {
+    /// Text between the markers, trimmed
+    pub(super) content: String,
+    /// Index of the line after the comment
+    pub(super) next_idx: usize,
+    /// Whether the closing `-->` was found
+    pub(super) closed: bool,
+}
+
+/// Opening and closing markers of HTML comments
+pub(super) const HTML_COMMENT: (&str, &str) = ("<!--", "-->");
+
+/// Opening and closing markers of Obsidian comments
+pub(super) const OBSIDIAN_COMMENT: (&str, &str) = ("%%", "%%");
+
+/// Check if a line starts a block comment
+pub(super) fn detect_comment_start(line: &str) -> bool {
+    line.trim_start().starts_with(HTML_COMMENT.0)
+}
+
+/// Collect the comment opened with `markers` at `start_idx`
+///
+/// Returns `None` when text follows the closing marker on its line; such a comment is
+/// inline content of a paragraph. An unclosed comment runs to the end of the input.
+pub(super) fn parse_comment_block(
+    lines: &[&str],
+    start_idx: usize,
+    markers: (&str, &str),
+) -> Option<CommentBlock> {
+    let (open, close) = markers;
+    let first = lines[start_idx].trim_start().strip_prefix(open)?;
+    let mut body = Vec::new();
+
+    for (idx, line) in lines.iter().enumerate().skip(start_idx) {
+        let line = if idx == start_idx { first } else { line };
+        if let Some(end) = line.find(close) {
+            if !line[end + close.len()..].trim().is_empty() {
+                return None;
+            }
+            body.push(&line[..end]);
+            return Some(CommentBlock {
+                content: body.join("\n").trim().to_string(),
+                next_idx: idx + 1,
+                closed: true,
+            });
+        }
+        body.push(line);
+    }
+
+    Some(CommentBlock {
+        content: body.join("\n").trim().to_string(),
+        next_idx: lines.len(),
+        closed: false,
+    })
+}
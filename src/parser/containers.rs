@@ -0,0 +1,105 @@
+//! GitHub-style alerts, `:::` fenced containers, and Pandoc-style fenced divs.
+
+use crate::ast::{AdmonitionKind, Attributes};
+
+use super::attributes::split_trailing_attributes;
+
+/// Check if a line opens a GitHub alert (`> [!NOTE]`) and return its kind
+///
+/// The marker must be alone on a single-level blockquote line.
+pub(super) fn detect_alert(line: &str) -> Option<AdmonitionKind> {
+    let rest = line.trim().strip_prefix('>')?.trim();
+    let name = rest.strip_prefix("[!")?.strip_suffix(']')?;
+    AdmonitionKind::from_name(name)
+}
+
+/// Collect the body of a GitHub alert whose marker is at `start_idx`
+///
+/// Returns the following `>` lines with one `>` (and one space) stripped, so nested
+/// quotes and blank `>` lines keep their meaning, and the index after the alert.
+pub(super) fn collect_alert_lines<'a>(
+    lines: &[&'a str],
+    start_idx: usize,
+) -> (Vec<&'a str>, usize) {
+    let mut body = Vec::new();
+    let mut i = start_idx + 1;
+
+    while i < lines.len() {
+        let Some(content) = lines[i].trim_start().strip_prefix('>') else {
+            break;
+        };
+        body.push(content.strip_prefix(' ').unwrap_or(content));
+        i += 1;
+    }
+
+    (body, i)
+}
+
+/// Text after the colons of a `:::` opening fence (`:::note` gives `note`)
+///
+/// Returns `None` for lines that are not fences or that only close one.
+pub(super) fn fence_info(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    let colons = trimmed.chars().take_while(|&c| c == ':').count();
+    if colons < 3 {
+        return None;
+    }
+    let info = trimmed[colons..].trim();
+    (!info.is_empty()).then_some(info)
+}
+
+/// Check if a line is a closing `:::` fence (three or more colons and nothing else)
+fn is_closing_fence(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() >= 3 && trimmed.chars().all(|c| c == ':')
+}
+
+/// Check if a line opens a fenced admonition (`:::note` or `::: warning`)
+pub(super) fn detect_fenced_admonition(line: &str) -> Option<AdmonitionKind> {
+    fence_info(line).and_then(AdmonitionKind::from_name)
+}
+
+/// Check if a line opens a collapsible block (`:::details Title`) and return its title
+///
+/// The title is empty when none is given.
+pub(super) fn detect_details(line: &str) -> Option<&str> {
+    let info = fence_info(line)?;
+    let (name, title) = info.split_once(char::is_whitespace).unwrap_or((info, ""));
+    name.eq_ignore_ascii_case("details").then(|| title.trim())
+}
+
+/// Check if a line opens a Pandoc-style fenced div and return its attributes
+///
+/// Accepts `::: {.class #id key=val}`, `::: class`, and `::: class {#id}`; a bare
+/// word becomes the first class. Anything else is not a container.
+pub(super) fn detect_container(line: &str) -> Option<Attributes> {
+    let info = fence_info(line)?;
+    let (name, attributes) = split_trailing_attributes(info);
+    let mut attributes = attributes.unwrap_or_default();
+    let name = name.trim();
+    if !name.is_empty() {
+        if name.contains(char::is_whitespace) || name.contains(['{', '}']) {
+            return None;
+        }
+        attributes.classes.insert(0, name.to_string());
+    }
+    Some(attributes)
+}
+
+/// Find the closing fence of the `:::` container opened at `start_idx`
+///
+/// Nested containers are skipped. Returns `None` if the input ends first.
+pub(super) fn find_closing_fence(lines: &[&str], start_idx: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (idx, line) in lines.iter().enumerate().skip(start_idx + 1) {
+        if is_closing_fence(line) {
+            if depth == 0 {
+                return Some(idx);
+            }
+            depth -= 1;
+        } else if fence_info(line).is_some() {
+            depth += 1;
+        }
+    }
+    None
+}
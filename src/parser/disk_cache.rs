@@ -0,0 +1,122 @@
+//! On-disk cache of parse results (`ParserConfig::cache_dir`).
+
+use super::Parser;
+use crate::ast::ParseError;
+use crate::parse_result::ParseResult;
+use crate::stable_hash::fnv1a;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Release of the parser, stored with each result; entries of other releases are misses
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A cache file: the result, the release of the parser that produced it, and the
+/// length and hash of the input it was parsed from
+#[derive(Deserialize)]
+struct CacheEntry {
+    version: String,
+    input_len: usize,
+    input_hash: u64,
+    result: ParseResult,
+}
+
+/// Borrowed form of [`CacheEntry`], written without cloning the result
+#[derive(Serialize)]
+struct StoredEntry<'a> {
+    version: &'a str,
+    input_len: usize,
+    input_hash: u64,
+    result: &'a ParseResult,
+}
+
+/// Where the result for an input is cached, and what identifies that input
+pub(super) struct CacheKey {
+    path: PathBuf,
+    input_len: usize,
+    input_hash: u64,
+}
+
+impl Parser {
+    /// Parse the Markdown file at `path`, reusing the result stored in `cache_dir` by an
+    /// earlier parse of the same content with the same configuration
+    ///
+    /// The file becomes the parser's input, so [`Parser::to_html`](crate::Parser::to_html)
+    /// and the other outputs use it afterwards. Without `cache_dir` this is the same as
+    /// [`Parser::set_input`] followed by [`Parser::parse_full`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::ReadFailed` if the file cannot be read, or `ParseError` if
+    /// parsing fails
+    pub fn parse_cached(&mut self, path: impl AsRef<Path>) -> Result<ParseResult, ParseError> {
+        let path = path.as_ref();
+        let input = fs::read_to_string(path).map_err(|e| ParseError::ReadFailed {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        self.set_input(input);
+        self.parse_full()
+    }
+}
+
+/// Cache file for the parser's input and configuration, or `None` if results are not
+/// cached on disk
+///
+/// The file is named after a hash of the input and a hash of the configuration. Results
+/// that depend on other files (includes, CSV tables), on `mmdc` (Mermaid CLI
+/// validation), on custom block syntaxes, or on external definitions, which the key
+/// cannot cover, are not cached.
+pub(super) fn entry_key(parser: &Parser) -> Option<CacheKey> {
+    let config = &parser.config;
+    let dir = config.cache_dir.as_ref()?;
+    if config.enable_includes
+        || config.enable_csv_tables
+        || config.mermaid.use_cli_validation
+        || !parser.block_syntaxes.is_empty()
+        || !parser.external_definitions.is_empty()
+    {
+        return None;
+    }
+    let mut keyed = config.clone();
+    keyed.cache_dir = None;
+    let config_json = serde_json::to_string(&keyed).ok()?;
+    let input_hash = fnv1a(parser.input.bytes());
+    Some(CacheKey {
+        path: Path::new(dir).join(format!(
+            "{:016x}-{:016x}.json",
+            input_hash,
+            fnv1a(config_json.bytes())
+        )),
+        input_len: parser.input.len(),
+        input_hash,
+    })
+}
+
+/// The cached result for `key`, if it was written by this release of the parser for
+/// the same input
+pub(super) fn load(key: &CacheKey) -> Option<ParseResult> {
+    let entry: CacheEntry = serde_json::from_slice(&fs::read(&key.path).ok()?).ok()?;
+    (entry.version == VERSION
+        && entry.input_len == key.input_len
+        && entry.input_hash == key.input_hash)
+        .then_some(entry.result)
+}
+
+/// Write `result` for `key`, replacing any entry of another release
+pub(super) fn store(key: &CacheKey, result: &ParseResult) {
+    let Ok(json) = serde_json::to_vec(&StoredEntry {
+        version: VERSION,
+        input_len: key.input_len,
+        input_hash: key.input_hash,
+        result,
+    }) else {
+        return;
+    };
+    // A cache that cannot be written only costs a parse next time
+    let _ = key
+        .path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&key.path, json));
+}
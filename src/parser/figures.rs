@@ -0,0 +1,46 @@
+//! Figure captions (`Figure: ...`) after standalone images and Mermaid diagrams.
+
+use crate::ast::{standalone_image, Node};
+
+/// Caption text of a `Figure: ...` line, if `line` is one
+fn detect_caption(line: &str) -> Option<&str> {
+    let caption = line.trim().strip_prefix("Figure:")?.trim();
+    (!caption.is_empty()).then_some(caption)
+}
+
+/// Caption of a block ending before `idx`: a `Figure: ...` line directly at `idx` or
+/// after one blank line
+///
+/// Returns the caption text and the index of the line after it.
+pub(super) fn find_caption<'a>(lines: &[&'a str], idx: usize) -> Option<(&'a str, usize)> {
+    let caption_idx = if lines.get(idx).is_some_and(|line| line.trim().is_empty()) {
+        idx + 1
+    } else {
+        idx
+    };
+    let caption = detect_caption(lines.get(caption_idx)?)?;
+    Some((caption, caption_idx + 1))
+}
+
+/// Whether a node can carry a figure caption
+pub(super) fn is_figure_content(node: &Node) -> bool {
+    match node {
+        Node::MermaidDiagram { .. } => true,
+        Node::Paragraph { content } => standalone_image(content).is_some(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_caption() {
+        let lines = ["```", "", "Figure: Overview", "Figure:", "text"];
+        assert_eq!(find_caption(&lines, 1), Some(("Overview", 3)));
+        assert_eq!(find_caption(&lines, 2), Some(("Overview", 3)));
+        assert_eq!(find_caption(&lines, 3), None);
+        assert_eq!(find_caption(&lines, 5), None);
+    }
+}
@@ -0,0 +1,28 @@
+//! YAML frontmatter at the start of a document.
+
+/// Index of the line after a frontmatter block opening the document
+///
+/// Frontmatter starts with a `---` line, followed directly by a `key: value` line, and
+/// ends at the next `---` or `...` line. Requiring the `key:` line keeps a document
+/// that opens with a rule (`---` followed by text and a setext underline) from being
+/// read as frontmatter.
+pub(crate) fn detect_frontmatter(lines: &[&str]) -> Option<usize> {
+    if lines.first()?.trim_end() != "---" || !is_mapping_line(lines.get(1)?) {
+        return None;
+    }
+    let close = lines[2..]
+        .iter()
+        .position(|line| matches!(line.trim_end(), "---" | "..."))?;
+    Some(close + 3)
+}
+
+/// Whether `line` starts a YAML mapping entry (`title: Guide`, `tags:`)
+fn is_mapping_line(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(key, rest)| {
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-'))
+            && (rest.is_empty() || rest.starts_with(' '))
+    })
+}
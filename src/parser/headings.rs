@@ -0,0 +1,94 @@
+//! Heading structure checks run on the finished AST.
+
+use crate::ast::{plain_text, LineRange, Node, ParseWarning, Span, WarningCode};
+use crate::config::HeadingChecks;
+use crate::node_path::iter_nodes;
+use std::collections::HashSet;
+
+/// Warn about the heading problems enabled in `checks`
+///
+/// Headings nested in containers are reported at the first line of their top-level
+/// block. A line of only `#`s, which is read as a paragraph, counts as an empty heading. Duplicates are headings with the same text (ignoring case and spacing) under
+/// the same parent heading, so `### Linux` may appear under both `## Install` and
+/// `## Remove`.
+pub(super) fn check_headings(
+    nodes: &[Node],
+    spans: &[LineRange],
+    checks: &HeadingChecks,
+    warnings: &mut Vec<ParseWarning>,
+) {
+    let mut previous_level = None;
+    let mut top_level_line = None;
+    // Texts seen at each level since the last heading above it
+    let mut seen: [HashSet<String>; 6] = Default::default();
+    for (path, node) in iter_nodes(nodes) {
+        let line = spans.get(path.indices()[0]).map_or(1, |span| span.start);
+        let Node::Heading { level, content, .. } = node else {
+            if checks.empty && is_bare_marker(node) {
+                warnings.push(ParseWarning::new(
+                    WarningCode::EmptyHeading,
+                    Span { line, column: None },
+                    "heading marker without text".to_string(),
+                ));
+            }
+            continue;
+        };
+        let mut warn = |code, message: String| {
+            warnings.push(ParseWarning::new(
+                code,
+                Span { line, column: None },
+                message,
+            ));
+        };
+        let text = plain_text(content)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if checks.empty && text.is_empty() {
+            warn(WarningCode::EmptyHeading, "heading has no text".to_string());
+        }
+        if let Some(previous) = previous_level.filter(|previous| *level > previous + 1) {
+            if checks.skipped_levels {
+                warn(
+                    WarningCode::SkippedHeadingLevel,
+                    format!(
+                        "level-{} heading follows a level-{} heading",
+                        level, previous
+                    ),
+                );
+            }
+        }
+        if *level == 1 {
+            match top_level_line {
+                Some(first) if checks.multiple_top_level => warn(
+                    WarningCode::MultipleTopLevelHeadings,
+                    format!("second level-1 heading (the first is on line {})", first),
+                ),
+                Some(_) => {}
+                None => top_level_line = Some(line),
+            }
+        }
+        let idx = usize::from((*level).clamp(1, 6) - 1);
+        for deeper in &mut seen[idx + 1..] {
+            deeper.clear();
+        }
+        if !text.is_empty() && !seen[idx].insert(text.to_lowercase()) && checks.duplicates {
+            warn(
+                WarningCode::DuplicateHeading,
+                format!("duplicate heading `{}` in the same section", text),
+            );
+        }
+        previous_level = Some(*level);
+    }
+}
+
+/// Whether `node` is a paragraph of nothing but one to six `#`s
+fn is_bare_marker(node: &Node) -> bool {
+    let Node::Paragraph { content } = node else {
+        return false;
+    };
+    let text = plain_text(content);
+    let text = text.trim();
+    (1..=6).contains(&text.len()) && text.chars().all(|c| c == '#')
+}
@@ -2,43 +2,37 @@
 
 /// Check if a line is a horizontal rule
 ///
-/// A horizontal rule is a line containing at least 3 consecutive `-` or `*` characters,
-/// with optional leading and trailing spaces. The line may contain only spaces and
-/// the rule characters.
+/// A horizontal rule is a line of at least 3 `-`, `*`, or `_` characters of the same
+/// kind, optionally separated by spaces or tabs, with optional leading and trailing
+/// spaces.
 ///
 /// Examples:
-/// - `---` (valid)
-/// - `***` (valid)
+/// - `---`, `***`, `___` (valid)
 /// - `  ---  ` (valid, with spaces)
+/// - `- - -`, `* * *` (valid, spaced)
 /// - `----` (valid, more than 3)
 /// - `--` (invalid, less than 3)
-/// - `**` (invalid, less than 3)
 /// - `---text---` (invalid, contains other characters)
+/// - `---***` (invalid, mixed characters)
 pub(super) fn detect_horizontal_rule(line: &str) -> bool {
     let trimmed = line.trim();
-
-    // Must have at least 3 characters
-    if trimmed.len() < 3 {
+    let Some(marker) = trimmed
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '-' | '*' | '_'))
+    else {
         return false;
-    }
-
-    // Check if all characters are either `-` or `*`
-    // Must have at least 3 consecutive characters of the same type
-    let first_char = trimmed.chars().next().unwrap();
+    };
 
-    // Only `-` and `*` are valid for horizontal rules
-    if first_char != '-' && first_char != '*' {
-        return false;
+    let mut count = 0;
+    for c in trimmed.chars() {
+        if c == marker {
+            count += 1;
+        } else if c != ' ' && c != '\t' {
+            return false;
+        }
     }
-
-    // Check that all characters are the same as the first character
-    // and there are at least 3 of them
-    let count = trimmed.chars().take_while(|&c| c == first_char).count();
-
     count >= 3
-        && trimmed
-            .chars()
-            .all(|c| c == first_char || c.is_whitespace())
 }
 
 #[cfg(test)]
@@ -84,9 +78,18 @@ mod tests {
         assert!(!detect_horizontal_rule("---***"));
     }
 
+    #[test]
+    fn test_detect_underscores_and_spaced_forms() {
+        assert!(detect_horizontal_rule("___"));
+        assert!(detect_horizontal_rule("- - -"));
+        assert!(detect_horizontal_rule(" * * * * "));
+        assert!(detect_horizontal_rule("_\t_ _"));
+        assert!(!detect_horizontal_rule("- - * -"));
+        assert!(!detect_horizontal_rule("- -"));
+    }
+
     #[test]
     fn test_reject_other_characters() {
-        assert!(!detect_horizontal_rule("___"));
         assert!(!detect_horizontal_rule("==="));
         assert!(!detect_horizontal_rule("###"));
     }
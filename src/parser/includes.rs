@@ -0,0 +1,340 @@
+//! File references resolved at parse time (`{{csv:path}}`, `{{include: path}}`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::ast::{Inline, LineRange, Node, ParseError, ParseWarning, Span};
+use crate::config::ParserConfig;
+
+use super::frontmatter::detect_frontmatter;
+use super::obsidian;
+use super::tables::normalize_row;
+
+/// Text of a `{{name:value}}` reference line, if `line` is one
+pub(super) fn detect_reference<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let value = line
+        .trim()
+        .strip_prefix("{{")?
+        .strip_suffix("}}")?
+        .trim()
+        .strip_prefix(name)?
+        .strip_prefix(':')?
+        .trim();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Check if a line is a CSV table reference (`{{csv:data/results.csv}}`) and return its path
+pub(super) fn detect_csv_include(line: &str) -> Option<&str> {
+    detect_reference(line, "csv")
+}
+
+/// Resolve a referenced path against `ParserConfig::base_dir`
+pub(super) fn resolve_path(path: &str, config: &ParserConfig) -> PathBuf {
+    match &config.base_dir {
+        Some(base) => PathBuf::from(base).join(path),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Replace every `{{include: path}}` line of `input` with the referenced file's contents,
+/// and in Obsidian mode every note embed line (`![[Note]]`) with the note
+///
+/// Lines inside fenced code blocks are left alone. Paths are relative to the including
+/// file (`base_dir` for the document itself) and must stay inside `base_dir`.
+///
+/// # Errors
+///
+/// Returns `ParseError::IncludeFailed`, located at the include line of the document,
+/// if a file cannot be read, lies outside `base_dir`, includes itself (directly or
+/// indirectly), or nesting exceeds `max_include_depth`
+pub(super) fn expand_includes(input: &str, config: &ParserConfig) -> Result<String, ParseError> {
+    let base = PathBuf::from(config.base_dir.as_deref().unwrap_or("."));
+    let root = base.canonicalize().map_err(|e| ParseError::IncludeFailed {
+        path: base.display().to_string(),
+        message: format!("cannot open base directory: {}", e),
+        span: Span {
+            line: 1,
+            column: None,
+        },
+    })?;
+
+    let mut expander = IncludeExpander {
+        config,
+        root: &root,
+        stack: Vec::new(),
+        line: 0,
+    };
+    let mut output = String::with_capacity(input.len());
+    expander.expand(input, &root, &mut output)?;
+    Ok(output)
+}
+
+/// What an include line takes from the referenced file
+enum Inclusion<'l> {
+    /// The whole file (`{{include: path}}`)
+    File,
+    /// An Obsidian note without its frontmatter, or only the section under `heading`
+    Note { heading: Option<&'l str> },
+}
+
+/// State of one include expansion
+struct IncludeExpander<'a> {
+    config: &'a ParserConfig,
+    /// Canonical sandbox directory
+    root: &'a Path,
+    /// Canonical paths of the files currently being expanded, outermost first
+    stack: Vec<PathBuf>,
+    /// 0-based line of the document's include currently being expanded
+    line: usize,
+}
+
+impl IncludeExpander<'_> {
+    fn expand(&mut self, text: &str, dir: &Path, output: &mut String) -> Result<(), ParseError> {
+        let mut in_code_block = false;
+        for (idx, line) in text.lines().enumerate() {
+            if super::blocks::is_code_fence(line.trim(), self.config) {
+                in_code_block = !in_code_block;
+            }
+            let (path, inclusion) = match self.reference(line) {
+                Some(reference) if !in_code_block => reference,
+                _ => {
+                    output.push_str(line);
+                    output.push('\n');
+                    continue;
+                }
+            };
+
+            if self.stack.is_empty() {
+                self.line = idx;
+            }
+            let file = self.resolve(&path, dir)?;
+            let contents =
+                fs::read_to_string(&file).map_err(|e| self.error(&path, e.to_string()))?;
+            let file_dir = file.parent().unwrap_or(self.root).to_path_buf();
+            let contents = contents.strip_prefix('\u{feff}').unwrap_or(&contents);
+            let contents = match inclusion {
+                Inclusion::File => contents.to_string(),
+                Inclusion::Note { heading } => self.embedded_part(&path, contents, heading)?,
+            };
+            self.stack.push(file);
+            self.expand(&contents, &file_dir, output)?;
+            self.stack.pop();
+        }
+        Ok(())
+    }
+
+    /// Path referenced by an include line, or by an embed line (`![[Note]]`) in Obsidian
+    /// mode, and what to take from the file
+    fn reference<'l>(&self, line: &'l str) -> Option<(String, Inclusion<'l>)> {
+        if let Some(path) = detect_reference(line, "include") {
+            return Some((path.to_string(), Inclusion::File));
+        }
+        if !self.config.enable_obsidian {
+            return None;
+        }
+        let embed = obsidian::detect_embed_line(line)?;
+        let heading = embed.fragment;
+        Some((embed.note_file()?, Inclusion::Note { heading }))
+    }
+
+    /// The part of an embedded note that is transcluded: the note without its
+    /// frontmatter, or the section under `heading`
+    fn embedded_part(
+        &self,
+        path: &str,
+        contents: &str,
+        heading: Option<&str>,
+    ) -> Result<String, ParseError> {
+        let lines: Vec<&str> = contents.lines().collect();
+        let body = &lines[detect_frontmatter(&lines).unwrap_or(0)..];
+        match heading {
+            Some(heading) => obsidian::heading_section(&body.join("\n"), heading)
+                .map(|section| section.join("\n"))
+                .ok_or_else(|| self.error(path, format!("the note has no heading `{}`", heading))),
+            None => Ok(body.join("\n")),
+        }
+    }
+
+    /// Canonical path of an included file, checked against the sandbox, cycles, and depth
+    fn resolve(&self, path: &str, dir: &Path) -> Result<PathBuf, ParseError> {
+        if self.stack.len() >= self.config.max_include_depth {
+            return Err(self.error(
+                path,
+                format!(
+                    "includes are nested more than {} levels deep",
+                    self.config.max_include_depth
+                ),
+            ));
+        }
+        let file = dir
+            .join(path)
+            .canonicalize()
+            .map_err(|e| self.error(path, e.to_string()))?;
+        if !file.starts_with(self.root) {
+            return Err(self.error(path, "the file is outside the base directory".to_string()));
+        }
+        if self.stack.contains(&file) {
+            let chain = self
+                .stack
+                .iter()
+                .chain(std::iter::once(&file))
+                .map(|entry| {
+                    entry
+                        .strip_prefix(self.root)
+                        .unwrap_or(entry)
+                        .display()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(self.error(path, format!("include cycle: {}", chain)));
+        }
+        Ok(file)
+    }
+
+    fn error(&self, path: &str, message: String) -> ParseError {
+        ParseError::IncludeFailed {
+            path: path.to_string(),
+            message,
+            span: Span {
+                line: self.line + 1,
+                column: None,
+            },
+        }
+    }
+}
+
+/// Load the CSV file referenced on line `line_idx` as a table
+///
+/// The first record is the header row; cells are plain text. Records with a different
+/// number of fields are padded or truncated with a warning, like Markdown table rows.
+///
+/// # Errors
+///
+/// Returns `ParseError::IncludeFailed` if the file cannot be read or has no records
+pub(super) fn load_csv_table(
+    path: &str,
+    line_idx: usize,
+    config: &ParserConfig,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Node, ParseError> {
+    let include_error = |message: String| ParseError::IncludeFailed {
+        path: path.to_string(),
+        message,
+        span: Span {
+            line: line_idx + 1,
+            column: None,
+        },
+    };
+
+    let text =
+        fs::read_to_string(resolve_path(path, config)).map_err(|e| include_error(e.to_string()))?;
+    let mut records = parse_csv(&text).into_iter().map(|record| {
+        record
+            .into_iter()
+            .map(|field| {
+                if field.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![Inline::Text {
+                        content: field.into(),
+                    }]
+                }
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let headers = records
+        .next()
+        .ok_or_else(|| include_error("the CSV file is empty".to_string()))?;
+    let rows = records
+        .map(|mut row| {
+            normalize_row(&mut row, headers.len(), line_idx, warnings);
+            row
+        })
+        .collect();
+
+    Ok(Node::Table {
+        alignments: vec![None; headers.len()],
+        headers,
+        rows,
+        caption: None,
+        number: None,
+        span: Some(LineRange {
+            start: line_idx + 1,
+            end: line_idx + 1,
+        }),
+    })
+}
+
+/// Split CSV text into records (RFC 4180)
+///
+/// Fields may be quoted to contain commas, line breaks, and doubled `""` quotes.
+/// Blank lines are skipped.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|field| !field.is_empty()) || record.len() > 1 {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_quoting() {
+        let records =
+            parse_csv("name,notes\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\n\n,x\n");
+        assert_eq!(
+            records,
+            vec![
+                vec!["name".to_string(), "notes".to_string()],
+                vec!["Smith, J".to_string(), "said \"hi\"\nthen left".to_string()],
+                vec![String::new(), "x".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_csv_include() {
+        assert_eq!(
+            detect_csv_include("{{csv: data/a.csv }}"),
+            Some("data/a.csv")
+        );
+        assert_eq!(detect_csv_include("see {{csv:a.csv}}"), None);
+        assert_eq!(detect_csv_include("{{csv:}}"), None);
+    }
+}
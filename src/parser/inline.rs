@@ -1,461 +1,715 @@
-//! Inline element parsing (bold, italic, links, images, strikethrough).
-
-use crate::ast::{Inline, ParseError};
-use regex::{Regex, RegexSet};
-
-/// Type of inline element match found during parsing
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(super) enum InlineMatchType {
-    Image,
-    Link,
-    Code,
-    Strikethrough,
-    Bold,
-    Italic,
-}
-
-/// Compiled regex patterns for inline element parsing
-pub(super) struct RegexPatterns {
-    /// RegexSet for efficient multi-pattern matching
-    set: RegexSet,
-    /// Individual regexes for getting match positions and captures
-    image: Regex,
-    link: Regex,
-    code: Regex,
-    strikethrough: Regex,
-    bold: Regex,
-    italic: Regex,
+//! Inline element parsing (bold, italic, links, images, strikethrough, highlights, spoilers).
+//!
+//! A single left-to-right scan over the text. Code spans, links, images, directives,
+//! hashtags, mentions, comments, and Obsidian embeds are recognized where they start; `*` and `~~` runs (plus `_` and `~`
+//! runs with `gfm_emphasis`, `==` with `enable_highlight`, and `||` with
+//! `enable_spoilers`) go on a delimiter stack and are paired as soon as a closing run
+//! is seen, in the manner of CommonMark's emphasis algorithm.
+
+use crate::ast::{Attributes, Inline, LimitKind, ParseError};
+use crate::config::ParserConfig;
+use regex::Regex;
+
+use super::attributes::parse_attribute_block;
+use super::obsidian::Embed;
+
+/// Inline syntax settings, the table cell line break pattern, and the reference pattern
+#[derive(Clone)]
+pub(crate) struct RegexPatterns {
+    /// `<br>`, `<br/>`, or `<br />` inside a table cell
+    pub(super) line_break: Regex,
+    /// `[text]` or `[^label]`, then a `[label]` or the `(` of an inline link, if any
+    pub(crate) reference: Regex,
+    /// Whether inline directives (`:name[content]{attrs}`) are recognized
+    enable_directives: bool,
+    /// Whether images take trailing attribute lists and `[text]{attrs}` is a span
+    enable_attributes: bool,
+    /// Whether emphasis follows GFM's flanking rules, including `_` and `~` runs
+    gfm_emphasis: bool,
+    /// Whether `==text==` is recognized as a highlight
+    enable_highlight: bool,
+    /// Whether `||text||` is recognized as a spoiler
+    enable_spoilers: bool,
+    /// Whether `#tag` is recognized as a hashtag
+    enable_hashtags: bool,
+    /// Whether `@user` is recognized as a mention
+    enable_mentions: bool,
+    /// Whether `![[embeds]]` and `%%comments%%` are recognized
+    enable_obsidian: bool,
+    /// Deepest nesting of formatting, links, and directives allowed
+    max_depth: usize,
 }
 
 impl RegexPatterns {
-    /// Compile all regex patterns
-    pub(super) fn new() -> Result<Self, ParseError> {
-        // Pattern strings in order: image, link, code, strikethrough, bold, italic
-        let pattern_strings = [
-            r"!\[([^\]]*)\]\(([^)]+)\)",    // image
-            r"\[([^\]]+)\]\(([^)]+)\)",     // link
-            r"`([^`]+)`",                   // code - backticks with one or more non-backtick chars
-            r"~~([^~]+?)~~",                // strikethrough
-            r"\*\*((?:[^*]|\*[^*])+?)\*\*", // bold - allows * (for italic) but not ** inside
-            r"\*((?:[^*]|\*\*)+)\*", // italic - allows ** (for bold) inside, greedy to match full span
-        ];
-
-        let set = RegexSet::new(pattern_strings).map_err(|e| {
-            ParseError::RegexCompilationError(format!("RegexSet compilation: {}", e))
-        })?;
-
+    pub(crate) fn new(config: &ParserConfig) -> Result<Self, ParseError> {
         Ok(RegexPatterns {
-            set,
-            image: Regex::new(pattern_strings[0])
-                .map_err(|e| ParseError::RegexCompilationError(format!("Image regex: {}", e)))?,
-            link: Regex::new(pattern_strings[1])
-                .map_err(|e| ParseError::RegexCompilationError(format!("Link regex: {}", e)))?,
-            code: Regex::new(pattern_strings[2])
-                .map_err(|e| ParseError::RegexCompilationError(format!("Code regex: {}", e)))?,
-            strikethrough: Regex::new(pattern_strings[3]).map_err(|e| {
-                ParseError::RegexCompilationError(format!("Strikethrough regex: {}", e))
+            line_break: Regex::new(r"(?i)<br\s*/?>").map_err(|e| {
+                ParseError::RegexCompilationError(format!("Line break regex: {}", e))
+            })?,
+            reference: Regex::new(r"\[(\^?)([^\[\]]*)\](?:\[([^\[\]]*)\]|(\())?").map_err(|e| {
+                ParseError::RegexCompilationError(format!("Reference regex: {}", e))
             })?,
-            bold: Regex::new(pattern_strings[4])
-                .map_err(|e| ParseError::RegexCompilationError(format!("Bold regex: {}", e)))?,
-            italic: Regex::new(pattern_strings[5])
-                .map_err(|e| ParseError::RegexCompilationError(format!("Italic regex: {}", e)))?,
+            enable_directives: config.enable_directives,
+            enable_attributes: config.enable_attributes,
+            gfm_emphasis: config.gfm_emphasis,
+            enable_highlight: config.enable_highlight,
+            enable_spoilers: config.enable_spoilers,
+            enable_hashtags: config.enable_hashtags,
+            enable_mentions: config.enable_mentions,
+            enable_obsidian: config.enable_obsidian,
+            max_depth: config.limits.max_nesting_depth,
         })
     }
+}
 
-    /// Find the earliest match among all inline patterns
-    pub(super) fn find_earliest_match(
-        &self,
-        text: &str,
-    ) -> Option<(usize, usize, InlineMatchType)> {
-        // Use RegexSet to quickly identify which patterns match
-        let matches = self.set.matches(text);
+/// Parse inline elements from a text string
+///
+/// Fails with `LimitExceeded` (without a span) when inlines nest deeper than
+/// `limits.max_nesting_depth`.
+pub(super) fn parse_inline(
+    text: &str,
+    regex_patterns: &RegexPatterns,
+) -> Result<Vec<Inline>, ParseError> {
+    Ok(Scanner::new(text, regex_patterns).run()?.0)
+}
 
-        // If no patterns match, return early
-        if !matches.matched_any() {
-            return None;
-        }
+/// A parsed inline, or a run of emphasis delimiters still waiting for a partner
+enum Piece {
+    /// An inline and how many formatting levels it contains (0 for text)
+    Inline(Inline, usize),
+    Delimiter(Delimiter),
+}
 
-        let mut earliest_pos = text.len();
-        let mut match_type = None;
-        let mut match_range = (0, 0);
+/// A run of `*`, `_`, `~`, `=`, or `|` characters
+struct Delimiter {
+    marker: u8,
+    /// Characters not yet used by a match
+    count: usize,
+    /// Length of the run as written
+    length: usize,
+    can_open: bool,
+    can_close: bool,
+}
 
-        // Check patterns in priority order: image (0), link (1), code (2), strikethrough (3), bold (4), italic (5)
-        // Only check patterns that RegexSet identified as matching
+impl Delimiter {
+    /// Whether `self` (an opener) may pair with `closer`
+    ///
+    /// CommonMark's "multiple of 3" rule: when either run can both open and close,
+    /// the two lengths must not add up to a multiple of 3 unless both are, so
+    /// `*a**b*` is one italic rather than an italic and a stray `**`. Strikethrough,
+    /// highlight, and spoiler runs only pair with a run of the same length.
+    fn pairs_with(&self, closer: &Delimiter) -> bool {
+        self.can_open
+            && self.marker == closer.marker
+            && (matches!(self.marker, b'*' | b'_') || self.length == closer.length)
+            && !((self.can_close || closer.can_open)
+                && (self.length + closer.length).is_multiple_of(3)
+                && !(self.length.is_multiple_of(3) && closer.length.is_multiple_of(3)))
+    }
+}
 
-        // Check for images (must check before links since images start with !)
-        if matches.matched(0) {
-            if let Some(m) = self.image.find(text) {
-                if m.start() < earliest_pos {
-                    earliest_pos = m.start();
-                    match_type = Some(InlineMatchType::Image);
-                    match_range = (m.start(), m.end());
-                }
-            }
-        }
+/// Next position of a fixed pattern, remembered so repeated lookups stay linear
+///
+/// Lookups must be made from non-decreasing positions.
+struct NextOccurrence {
+    pattern: &'static str,
+    found: Option<Option<usize>>,
+}
 
-        // Check for links
-        if matches.matched(1) {
-            if let Some(m) = self.link.find(text) {
-                if m.start() < earliest_pos {
-                    earliest_pos = m.start();
-                    match_type = Some(InlineMatchType::Link);
-                    match_range = (m.start(), m.end());
-                }
-            }
+impl NextOccurrence {
+    fn new(pattern: &'static str) -> Self {
+        Self {
+            pattern,
+            found: None,
         }
+    }
 
-        // Check for code (must check before bold/italic to avoid conflicts)
-        if matches.matched(2) {
-            if let Some(m) = self.code.find(text) {
-                if m.start() < earliest_pos {
-                    earliest_pos = m.start();
-                    match_type = Some(InlineMatchType::Code);
-                    match_range = (m.start(), m.end());
-                }
-            }
+    fn find(&mut self, text: &str, from: usize) -> Option<usize> {
+        match self.found {
+            Some(None) => return None,
+            Some(Some(at)) if at >= from => return Some(at),
+            _ => {}
         }
+        let at = text[from..].find(self.pattern).map(|idx| from + idx);
+        self.found = Some(at);
+        at
+    }
+}
 
-        // Check for strikethrough (must check before bold/italic to avoid conflicts)
-        if matches.matched(3) {
-            if let Some(m) = self.strikethrough.find(text) {
-                if m.start() < earliest_pos {
-                    earliest_pos = m.start();
-                    match_type = Some(InlineMatchType::Strikethrough);
-                    match_range = (m.start(), m.end());
-                }
-            }
+struct Scanner<'a> {
+    text: &'a str,
+    patterns: &'a RegexPatterns,
+    pieces: Vec<Piece>,
+    /// Start of the plain text not yet added to `pieces`
+    text_start: usize,
+    /// Per kind of closing run, the stack height below which no opener can match it
+    openers_bottom: [usize; 30],
+    close_bracket: NextOccurrence,
+    close_paren: NextOccurrence,
+    comment_end: NextOccurrence,
+    embed_end: NextOccurrence,
+    percent_comment_end: NextOccurrence,
+    /// Backtick run lengths known to have no closing run further on
+    unclosed_code: Vec<usize>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str, patterns: &'a RegexPatterns) -> Self {
+        Self {
+            text,
+            patterns,
+            pieces: Vec::new(),
+            text_start: 0,
+            openers_bottom: [0; 30],
+            close_bracket: NextOccurrence::new("]"),
+            close_paren: NextOccurrence::new(")"),
+            comment_end: NextOccurrence::new("-->"),
+            embed_end: NextOccurrence::new("]]"),
+            percent_comment_end: NextOccurrence::new("%%"),
+            unclosed_code: Vec::new(),
         }
+    }
 
-        // Check for bold (must check before italic to avoid conflicts)
-        if matches.matched(4) {
-            if let Some(m) = self.bold.find(text) {
-                if m.start() < earliest_pos {
-                    earliest_pos = m.start();
-                    match_type = Some(InlineMatchType::Bold);
-                    match_range = (m.start(), m.end());
+    /// Inlines of the whole text and their nesting depth
+    fn run(mut self) -> Result<(Vec<Inline>, usize), ParseError> {
+        let bytes = self.text.as_bytes();
+        let mut pos = 0;
+        while pos < bytes.len() {
+            // Every construct starts with an ASCII byte, so `pos` is on a char
+            // boundary whenever a handler runs
+            let end = match bytes[pos] {
+                b'`' => Some(self.code_span(pos)),
+                b'!' if self.patterns.enable_obsidian && self.text[pos..].starts_with("![[") => {
+                    self.embed(pos)
                 }
-            }
+                b'!' => self.image(pos),
+                b'[' => self.link(pos)?,
+                b':' if self.patterns.enable_directives => self.directive(pos)?,
+                b'#' if self.patterns.enable_hashtags => self.hashtag(pos),
+                b'@' if self.patterns.enable_mentions => self.mention(pos),
+                b'<' => self.comment(pos),
+                b'%' if self.patterns.enable_obsidian => self.percent_comment(pos),
+                b'\\' => self.escape(pos),
+                b'*' | b'~' => Some(self.delimiter_run(pos)?),
+                b'_' if self.patterns.gfm_emphasis => Some(self.delimiter_run(pos)?),
+                b'=' if self.patterns.enable_highlight => Some(self.delimiter_run(pos)?),
+                b'|' if self.patterns.enable_spoilers => Some(self.delimiter_run(pos)?),
+                _ => None,
+            };
+            pos = end.unwrap_or(pos + 1);
         }
+        self.push_text(self.text.len());
+        Ok(into_inlines(self.pieces))
+    }
 
-        // Check for italic (only if not part of bold - check that it's not **)
-        if matches.matched(5) {
-            if let Some(m) = self.italic.find(text) {
-                let start = m.start();
-                let end = m.end();
-                // Make sure it's not part of bold (check for ** before or after)
-                let is_bold = (start > 0 && text.as_bytes()[start - 1] == b'*')
-                    || (end < text.len() && text.as_bytes()[end] == b'*');
-
-                if !is_bold && start < earliest_pos {
-                    match_type = Some(InlineMatchType::Italic);
-                    match_range = (start, end);
-                }
-            }
+    /// Add the plain text up to `end`
+    fn push_text(&mut self, end: usize) {
+        if self.text_start < end {
+            self.pieces.push(Piece::Inline(
+                Inline::Text {
+                    content: self.text[self.text_start..end].into(),
+                },
+                0,
+            ));
         }
+    }
 
-        match_type.map(|mt| (match_range.0, match_range.1, mt))
+    /// Add an inline spanning `start..end` that contains `depth` formatting levels
+    fn push_inline(&mut self, start: usize, end: usize, inline: Inline, depth: usize) -> usize {
+        self.push_text(start);
+        self.pieces.push(Piece::Inline(inline, depth));
+        self.text_start = end;
+        end
     }
 
-    /// Process an image match and add it to inlines
-    pub(super) fn process_image_match<'a>(
-        &self,
-        remaining: &'a str,
-        match_range: (usize, usize),
-        inlines: &mut Vec<Inline>,
-    ) -> Result<&'a str, ParseError> {
-        // Add text before the image
-        if match_range.0 > 0 {
-            let text_before = &remaining[..match_range.0];
-            if !text_before.is_empty() {
-                inlines.push(Inline::Text {
-                    content: text_before.to_string(),
-                });
-            }
+    /// Depth of an inline wrapping content of depth `inner`
+    fn wrap_depth(&self, inner: usize) -> Result<usize, ParseError> {
+        if inner >= self.patterns.max_depth {
+            return Err(ParseError::LimitExceeded {
+                kind: LimitKind::NestingDepth,
+                limit: self.patterns.max_depth,
+                span: None,
+            });
         }
+        Ok(inner + 1)
+    }
 
-        let match_text = &remaining[match_range.0..match_range.1];
-        let caps = self.image.captures(match_text).ok_or_else(|| {
-            ParseError::InvalidCaptureError("Failed to capture image groups".to_string())
-        })?;
-
-        let alt_text = caps
-            .get(1)
-            .ok_or_else(|| {
-                ParseError::InvalidCaptureError("Failed to capture image alt text".to_string())
-            })?
-            .as_str();
-        let image_url = caps
-            .get(2)
-            .ok_or_else(|| {
-                ParseError::InvalidCaptureError("Failed to capture image URL".to_string())
-            })?
-            .as_str();
-
-        inlines.push(Inline::Image {
-            alt: alt_text.to_string(),
-            url: image_url.to_string(),
-        });
-
-        Ok(&remaining[match_range.1..])
+    /// Parse the text of a link or directive with a scanner of its own
+    fn parse_nested(&self, text: &str) -> Result<(Vec<Inline>, usize), ParseError> {
+        let (inlines, depth) = Scanner::new(text, self.patterns).run()?;
+        Ok((inlines, self.wrap_depth(depth)?))
     }
 
-    /// Process a link match and add it to inlines
-    pub(super) fn process_link_match<'a>(
-        &self,
-        remaining: &'a str,
-        match_range: (usize, usize),
-        inlines: &mut Vec<Inline>,
-        parse_inline_fn: impl Fn(&str) -> Result<Vec<Inline>, ParseError>,
-    ) -> Result<&'a str, ParseError> {
-        // Add text before the link
-        if match_range.0 > 0 {
-            let text_before = &remaining[..match_range.0];
-            if !text_before.is_empty() {
-                inlines.push(Inline::Text {
-                    content: text_before.to_string(),
-                });
+    /// `` `code` ``: a backtick run closed by the next run of the same length
+    ///
+    /// Returns the end of the span, or of the opening run when it is never closed.
+    fn code_span(&mut self, start: usize) -> usize {
+        let run = run_length(self.text, start, b'`');
+        let open_end = start + run;
+        if self.unclosed_code.contains(&run) {
+            return open_end;
+        }
+
+        let mut search = open_end;
+        while let Some(offset) = self.text[search..].find('`') {
+            let close = search + offset;
+            let close_run = run_length(self.text, close, b'`');
+            if close_run == run {
+                let mut content = &self.text[open_end..close];
+                // `` `` a`b `` `` needs the spaces to separate code from delimiters
+                if run > 1
+                    && content.len() > 2
+                    && content.starts_with(' ')
+                    && content.ends_with(' ')
+                {
+                    content = &content[1..content.len() - 1];
+                }
+                let inline = Inline::Code {
+                    content: content.into(),
+                };
+                return self.push_inline(start, close + run, inline, 0);
             }
+            search = close + close_run;
         }
+        self.unclosed_code.push(run);
+        open_end
+    }
 
-        let match_text = &remaining[match_range.0..match_range.1];
-        let caps = self.link.captures(match_text).ok_or_else(|| {
-            ParseError::InvalidCaptureError("Failed to capture link groups".to_string())
-        })?;
-
-        let link_text = caps
-            .get(1)
-            .ok_or_else(|| {
-                ParseError::InvalidCaptureError("Failed to capture link text".to_string())
-            })?
-            .as_str();
-        let link_url = caps
-            .get(2)
-            .ok_or_else(|| {
-                ParseError::InvalidCaptureError("Failed to capture link URL".to_string())
-            })?
-            .as_str();
-
-        let text_inlines = parse_inline_fn(link_text)?;
-        inlines.push(Inline::Link {
-            text: text_inlines,
-            url: link_url.to_string(),
-        });
-
-        Ok(&remaining[match_range.1..])
+    /// `[label]` followed by `(target)`, returning the label, target, and end position
+    fn bracketed(&mut self, open: usize) -> Option<(&'a str, &'a str, usize)> {
+        let text = self.text;
+        let close = self.close_bracket.find(text, open + 1)?;
+        if !text[close + 1..].starts_with('(') {
+            return None;
+        }
+        let paren = self.close_paren.find(text, close + 2)?;
+        let target = &text[close + 2..paren];
+        (!target.is_empty()).then_some((&text[open + 1..close], target, paren + 1))
+    }
+
+    /// Attribute list `{...}` starting at `at`, and the position after it
+    fn attribute_block(&self, at: usize) -> Option<(Attributes, usize)> {
+        let text = self.text;
+        if !text[at..].starts_with('{') {
+            return None;
+        }
+        let block_end = text[at + 1..]
+            .find(['{', '}'])
+            .filter(|&idx| text[at + 1 + idx..].starts_with('}'))
+            .map(|idx| at + idx + 2)?;
+        parse_attribute_block(&text[at..block_end]).map(|attributes| (attributes, block_end))
     }
 
-    /// Process a bold match and add it to inlines
-    pub(super) fn process_bold_match<'a>(
-        &self,
-        remaining: &'a str,
-        match_range: (usize, usize),
-        inlines: &mut Vec<Inline>,
-        parse_inline_fn: impl Fn(&str) -> Result<Vec<Inline>, ParseError>,
-    ) -> Result<&'a str, ParseError> {
-        // Add text before the bold
-        if match_range.0 > 0 {
-            let text_before = &remaining[..match_range.0];
-            if !text_before.is_empty() {
-                inlines.push(Inline::Text {
-                    content: text_before.to_string(),
-                });
+    /// `![alt](url)`, with `{attrs}` following when attribute lists are enabled
+    fn image(&mut self, start: usize) -> Option<usize> {
+        if !self.text[start + 1..].starts_with('[') {
+            return None;
+        }
+        let (alt, url, mut end) = self.bracketed(start + 1)?;
+        let mut attributes = Attributes::default();
+        if self.patterns.enable_attributes {
+            if let Some(parsed) = self.attribute_block(end) {
+                (attributes, end) = parsed;
             }
         }
+        let inline = Inline::Image {
+            alt: alt.to_string(),
+            url: url.to_string(),
+            attributes,
+        };
+        Some(self.push_inline(start, end, inline, 0))
+    }
 
-        let match_text = &remaining[match_range.0..match_range.1];
-        let caps = self.bold.captures(match_text).ok_or_else(|| {
-            ParseError::InvalidCaptureError("Failed to capture bold groups".to_string())
-        })?;
+    /// `[text](url)`, or a `[text]{attrs}` span when attribute lists are enabled
+    fn link(&mut self, start: usize) -> Result<Option<usize>, ParseError> {
+        let Some((text, url, end)) = self.bracketed(start) else {
+            return self.span(start);
+        };
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let (text, depth) = self.parse_nested(text)?;
+        let inline = Inline::Link {
+            text,
+            url: url.to_string(),
+        };
+        Ok(Some(self.push_inline(start, end, inline, depth)))
+    }
 
-        let bold_text = caps
-            .get(1)
-            .ok_or_else(|| {
-                ParseError::InvalidCaptureError("Failed to capture bold text".to_string())
-            })?
-            .as_str();
+    /// `[text]{attrs}`; a malformed attribute list leaves the brackets as text
+    fn span(&mut self, start: usize) -> Result<Option<usize>, ParseError> {
+        if !self.patterns.enable_attributes {
+            return Ok(None);
+        }
+        let Some(close) = self.close_bracket.find(self.text, start + 1) else {
+            return Ok(None);
+        };
+        let Some((attributes, end)) = self.attribute_block(close + 1) else {
+            return Ok(None);
+        };
+        let (content, depth) = self.parse_nested(&self.text[start + 1..close])?;
+        let inline = Inline::Span {
+            content,
+            attributes,
+        };
+        Ok(Some(self.push_inline(start, end, inline, depth)))
+    }
 
-        let bold_inlines = parse_inline_fn(bold_text)?;
-        inlines.push(Inline::Bold {
-            content: bold_inlines,
-        });
+    /// `:name[content]{attrs}`
+    ///
+    /// A `:` preceded by a word character (`note:x[y]`) does not start a directive.
+    /// A malformed attribute block is left in the text after the directive.
+    fn directive(&mut self, start: usize) -> Result<Option<usize>, ParseError> {
+        let text = self.text;
+        if text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == ':')
+        {
+            return Ok(None);
+        }
 
-        Ok(&remaining[match_range.1..])
+        let rest = &text[start + 1..];
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Ok(None);
+        }
+        let name_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        let open = start + 1 + name_len;
+        if !text[open..].starts_with('[') {
+            return Ok(None);
+        }
+        let Some(close) = self.close_bracket.find(text, open + 1) else {
+            return Ok(None);
+        };
+
+        let (attributes, end) = self
+            .attribute_block(close + 1)
+            .unwrap_or((Attributes::default(), close + 1));
+
+        let (content, depth) = self.parse_nested(&text[open + 1..close])?;
+        let inline = Inline::Directive {
+            name: rest[..name_len].to_string(),
+            content,
+            attributes,
+        };
+        Ok(Some(self.push_inline(start, end, inline, depth)))
     }
 
-    /// Process a strikethrough match and add it to inlines
-    pub(super) fn process_strikethrough_match<'a>(
-        &self,
-        remaining: &'a str,
-        match_range: (usize, usize),
-        inlines: &mut Vec<Inline>,
-        parse_inline_fn: impl Fn(&str) -> Result<Vec<Inline>, ParseError>,
-    ) -> Result<&'a str, ParseError> {
-        // Add text before the strikethrough
-        if match_range.0 > 0 {
-            let text_before = &remaining[..match_range.0];
-            if !text_before.is_empty() {
-                inlines.push(Inline::Text {
-                    content: text_before.to_string(),
-                });
-            }
+    /// `#tag`: letters, digits, `_`, `-`, and `/`, with at least one non-digit
+    ///
+    /// A `#` after a word character or `&` (`C#`, `page#id`, `&#39;`) is text, as is
+    /// an all-digit tag (`#42`). Trailing `-` and `/` are not part of the tag.
+    fn hashtag(&mut self, start: usize) -> Option<usize> {
+        let len = token_length(self.text, start, &['_', '-', '/'], &['-', '/'])?;
+        let tag = &self.text[start + 1..start + 1 + len];
+        if tag.chars().all(|c| c.is_ascii_digit()) {
+            return None;
         }
+        let inline = Inline::Hashtag { tag: tag.into() };
+        Some(self.push_inline(start, start + 1 + len, inline, 0))
+    }
 
-        let match_text = &remaining[match_range.0..match_range.1];
-        let caps = self.strikethrough.captures(match_text).ok_or_else(|| {
-            ParseError::InvalidCaptureError("Failed to capture strikethrough groups".to_string())
-        })?;
+    /// `@user`: letters, digits, `_`, `-`, and `.`
+    ///
+    /// An `@` after a word character (`me@example.com`) is text. Trailing `.` and `-`
+    /// are not part of the name, so a mention may end a sentence.
+    fn mention(&mut self, start: usize) -> Option<usize> {
+        let len = token_length(self.text, start, &['_', '-', '.'], &['-', '.'])?;
+        let inline = Inline::Mention {
+            user: self.text[start + 1..start + 1 + len].into(),
+        };
+        Some(self.push_inline(start, start + 1 + len, inline, 0))
+    }
 
-        let strikethrough_text = caps
-            .get(1)
-            .ok_or_else(|| {
-                ParseError::InvalidCaptureError("Failed to capture strikethrough text".to_string())
-            })?
-            .as_str();
+    /// `\#` or `\@`: a literal `#` or `@`, which cannot start a heading, hashtag, or mention
+    fn escape(&mut self, start: usize) -> Option<usize> {
+        matches!(self.text.as_bytes().get(start + 1), Some(b'#' | b'@')).then(|| {
+            self.push_text(start);
+            self.text_start = start + 1;
+            start + 2
+        })
+    }
 
-        let strikethrough_inlines = parse_inline_fn(strikethrough_text)?;
-        inlines.push(Inline::Strikethrough {
-            content: strikethrough_inlines,
-        });
+    /// `<!-- comment -->`; its content is never parsed
+    fn comment(&mut self, start: usize) -> Option<usize> {
+        if !self.text[start..].starts_with("<!--") {
+            return None;
+        }
+        let close = self.comment_end.find(self.text, start + 4)?;
+        let inline = Inline::Comment {
+            content: self.text[start + 4..close].trim().to_string(),
+        };
+        Some(self.push_inline(start, close + 3, inline, 0))
+    }
 
-        Ok(&remaining[match_range.1..])
+    /// `![[target]]`: an Obsidian embed, shown as an image or a link
+    fn embed(&mut self, start: usize) -> Option<usize> {
+        let close = self.embed_end.find(self.text, start + 3)?;
+        let inline = Embed::parse(&self.text[start + 3..close])?.to_inline();
+        Some(self.push_inline(start, close + 2, inline, 0))
     }
 
-    /// Process an italic match and add it to inlines
-    pub(super) fn process_italic_match<'a>(
-        &self,
-        remaining: &'a str,
-        match_range: (usize, usize),
-        inlines: &mut Vec<Inline>,
-        parse_inline_fn: impl Fn(&str) -> Result<Vec<Inline>, ParseError>,
-    ) -> Result<&'a str, ParseError> {
-        // Add text before the italic
-        if match_range.0 > 0 {
-            let text_before = &remaining[..match_range.0];
-            if !text_before.is_empty() {
-                inlines.push(Inline::Text {
-                    content: text_before.to_string(),
-                });
-            }
+    /// `%%comment%%`; like `<!-- -->`, its content is never parsed
+    fn percent_comment(&mut self, start: usize) -> Option<usize> {
+        if !self.text[start..].starts_with("%%") {
+            return None;
         }
+        let close = self.percent_comment_end.find(self.text, start + 2)?;
+        let inline = Inline::Comment {
+            content: self.text[start + 2..close].trim().to_string(),
+        };
+        Some(self.push_inline(start, close + 2, inline, 0))
+    }
 
-        let match_text = &remaining[match_range.0..match_range.1];
-        let caps = self.italic.captures(match_text).ok_or_else(|| {
-            ParseError::InvalidCaptureError("Failed to capture italic groups".to_string())
-        })?;
+    /// A run of `*`, `~`, `=`, or `|`, paired with earlier openers right away when it
+    /// can close
+    ///
+    /// A run can open when followed by a non-space character and close when preceded
+    /// by one, so `**bold **text` does not close at the space. Only `~~`, `==`, and `||`
+    /// runs count for strikethrough, highlights, and spoilers, so `a === b` stays text.
+    /// With `gfm_emphasis`, [`gfm_flanking`] decides instead and `~` runs of length 1
+    /// count too.
+    fn delimiter_run(&mut self, start: usize) -> Result<usize, ParseError> {
+        let marker = self.text.as_bytes()[start];
+        let length = run_length(self.text, start, marker);
+        let end = start + length;
+        let gfm = self.patterns.gfm_emphasis;
+        let counts = match marker {
+            b'~' => length == 2 || (gfm && length == 1),
+            b'=' | b'|' => length == 2,
+            _ => true,
+        };
+        if !counts {
+            return Ok(end);
+        }
 
-        let italic_text = caps
-            .get(1)
-            .ok_or_else(|| {
-                ParseError::InvalidCaptureError("Failed to capture italic text".to_string())
-            })?
-            .as_str();
+        let before = self.text[..start].chars().next_back();
+        let after = self.text[end..].chars().next();
+        let (can_open, can_close) = if gfm {
+            gfm_flanking(marker, before, after)
+        } else {
+            (
+                after.is_some_and(|c| !c.is_whitespace()),
+                before.is_some_and(|c| !c.is_whitespace()),
+            )
+        };
+        let mut run = Delimiter {
+            marker,
+            count: length,
+            length,
+            can_open,
+            can_close,
+        };
+
+        self.push_text(start);
+        self.text_start = end;
+        if run.can_close {
+            self.close(&mut run)?;
+        }
+        if run.count > 0 {
+            self.pieces.push(Piece::Delimiter(run));
+        }
+        Ok(end)
+    }
 
-        let italic_inlines = parse_inline_fn(italic_text)?;
-        inlines.push(Inline::Italic {
-            content: italic_inlines,
-        });
+    /// Pair `closer` with the nearest matching openers, wrapping what lies between
+    fn close(&mut self, closer: &mut Delimiter) -> Result<(), ParseError> {
+        let marker = match closer.marker {
+            b'*' => 0,
+            b'_' => 1,
+            b'~' => 2,
+            b'=' => 3,
+            _ => 4,
+        };
+        let kind = marker * 6 + usize::from(closer.can_open) * 3 + closer.length % 3;
+
+        while closer.count > 0 {
+            let bottom = self.openers_bottom[kind].min(self.pieces.len());
+            let found = (bottom..self.pieces.len()).rev().find(|&idx| {
+                matches!(&self.pieces[idx], Piece::Delimiter(opener) if opener.pairs_with(closer))
+            });
+            let Some(idx) = found else {
+                self.openers_bottom[kind] = self.pieces.len();
+                return Ok(());
+            };
 
-        Ok(&remaining[match_range.1..])
-    }
+            let (content, inner) = into_inlines(self.pieces.drain(idx + 1..).collect());
+            let depth = self.wrap_depth(inner)?;
+            // Pieces above `idx` are gone; anything pushed there later is unexamined
+            for bottom in &mut self.openers_bottom {
+                *bottom = (*bottom).min(idx);
+            }
 
-    /// Process a code match and add it to inlines
-    pub(super) fn process_code_match<'a>(
-        &self,
-        remaining: &'a str,
-        match_range: (usize, usize),
-        inlines: &mut Vec<Inline>,
-    ) -> Result<&'a str, ParseError> {
-        // Add text before the code
-        if match_range.0 > 0 {
-            let text_before = &remaining[..match_range.0];
-            if !text_before.is_empty() {
-                inlines.push(Inline::Text {
-                    content: text_before.to_string(),
-                });
+            let Some(Piece::Delimiter(opener)) = self.pieces.last_mut() else {
+                unreachable!("opener is the top piece after draining");
+            };
+            let used = if opener.count >= 2 && closer.count >= 2 {
+                2
+            } else {
+                1
+            };
+            opener.count -= used;
+            closer.count -= used;
+            if opener.count == 0 {
+                self.pieces.pop();
             }
-        }
 
-        let match_text = &remaining[match_range.0..match_range.1];
-        let caps = self.code.captures(match_text).ok_or_else(|| {
-            ParseError::InvalidCaptureError("Failed to capture code groups".to_string())
-        })?;
+            let inline = match (closer.marker, used) {
+                (b'~', _) => Inline::Strikethrough { content },
+                (b'=', _) => Inline::Highlight { content },
+                (b'|', _) => Inline::Spoiler { content },
+                (_, 2) => Inline::Bold { content },
+                _ => Inline::Italic { content },
+            };
+            self.pieces.push(Piece::Inline(inline, depth));
+        }
+        Ok(())
+    }
+}
 
-        let code_content = caps
-            .get(1)
-            .ok_or_else(|| {
-                ParseError::InvalidCaptureError("Failed to capture code content".to_string())
-            })?
-            .as_str();
+/// Whether a delimiter run between `before` and `after` can open and close, per GFM
+///
+/// A run is left-flanking when it is not followed by whitespace, and not followed by
+/// punctuation unless preceded by whitespace or punctuation; right-flanking is the
+/// mirror image. The ends of the text count as whitespace. Left-flanking runs open
+/// and right-flanking runs close, so `x*(y+z)*2` has no emphasis. A `_` run inside a
+/// word does neither, which keeps `snake_case_names` as text.
+fn gfm_flanking(marker: u8, before: Option<char>, after: Option<char>) -> (bool, bool) {
+    let space = |c: Option<char>| c.is_none_or(char::is_whitespace);
+    let punct = |c: Option<char>| c.is_some_and(is_punctuation);
+    let left = !space(after) && (!punct(after) || space(before) || punct(before));
+    let right = !space(before) && (!punct(before) || space(after) || punct(after));
+    if marker == b'_' {
+        (
+            left && (!right || punct(before)),
+            right && (!left || punct(after)),
+        )
+    } else {
+        (left, right)
+    }
+}
 
-        // Code content is stored as plain text (no recursive parsing)
-        inlines.push(Inline::Code {
-            content: code_content.to_string(),
-        });
+/// ASCII punctuation, and any other character that is not a letter, digit, space, or
+/// control character (Unicode punctuation and symbols)
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || !(c.is_ascii() || c.is_alphanumeric() || c.is_whitespace())
+}
 
-        Ok(&remaining[match_range.1..])
+/// Length of the hashtag or mention name after the marker at `start`
+///
+/// The marker must not follow a word character or `&`. The name is made of
+/// alphanumeric characters and `extra`, must start with an alphanumeric character,
+/// and drops any trailing `trailing` characters. `None` when the name is empty.
+fn token_length(text: &str, start: usize, extra: &[char], trailing: &[char]) -> Option<usize> {
+    if text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '&')
+    {
+        return None;
+    }
+    let rest = &text[start + 1..];
+    if !rest.starts_with(char::is_alphanumeric) {
+        return None;
     }
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || extra.contains(&c)))
+        .unwrap_or(rest.len());
+    Some(rest[..end].trim_end_matches(trailing).len())
 }
 
-/// Parse inline elements from a text string
-pub(super) fn parse_inline(
-    text: &str,
-    regex_patterns: &RegexPatterns,
-) -> Result<Vec<Inline>, ParseError> {
-    let mut inlines = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if let Some((start, end, match_type)) = regex_patterns.find_earliest_match(remaining) {
-            let match_range = (start, end);
-            remaining = match match_type {
-                InlineMatchType::Image => {
-                    regex_patterns.process_image_match(remaining, match_range, &mut inlines)?
-                }
-                InlineMatchType::Link => regex_patterns.process_link_match(
-                    remaining,
-                    match_range,
-                    &mut inlines,
-                    |t| parse_inline(t, regex_patterns),
-                )?,
-                InlineMatchType::Code => {
-                    regex_patterns.process_code_match(remaining, match_range, &mut inlines)?
-                }
-                InlineMatchType::Strikethrough => regex_patterns.process_strikethrough_match(
-                    remaining,
-                    match_range,
-                    &mut inlines,
-                    |t| parse_inline(t, regex_patterns),
-                )?,
-                InlineMatchType::Bold => regex_patterns.process_bold_match(
-                    remaining,
-                    match_range,
-                    &mut inlines,
-                    |t| parse_inline(t, regex_patterns),
-                )?,
-                InlineMatchType::Italic => regex_patterns.process_italic_match(
-                    remaining,
-                    match_range,
-                    &mut inlines,
-                    |t| parse_inline(t, regex_patterns),
-                )?,
-            };
-        } else {
-            // No more matches, add remaining text
-            if !remaining.is_empty() {
-                inlines.push(Inline::Text {
-                    content: remaining.to_string(),
-                });
+/// Number of consecutive `byte`s starting at `start`
+fn run_length(text: &str, start: usize, byte: u8) -> usize {
+    text.as_bytes()[start..]
+        .iter()
+        .take_while(|&&b| b == byte)
+        .count()
+}
+
+/// Final inlines and their deepest nesting
+///
+/// Unmatched delimiters become text and adjacent text is merged.
+fn into_inlines(pieces: Vec<Piece>) -> (Vec<Inline>, usize) {
+    let mut inlines: Vec<Inline> = Vec::with_capacity(pieces.len());
+    let mut max_depth = 0;
+    for piece in pieces {
+        let inline = match piece {
+            Piece::Inline(inline, depth) => {
+                max_depth = max_depth.max(depth);
+                inline
             }
-            break;
+            Piece::Delimiter(run) => Inline::Text {
+                content: char::from(run.marker).to_string().repeat(run.count).into(),
+            },
+        };
+        match (inlines.last_mut(), inline) {
+            (Some(Inline::Text { content: last }), Inline::Text { content }) => {
+                last.push_str(&content)
+            }
+            (_, inline) => inlines.push(inline),
         }
     }
+    (inlines, max_depth)
+}
 
-    // If no inline elements were found, return a single text node
-    if inlines.is_empty() && !text.is_empty() {
-        inlines.push(Inline::Text {
-            content: text.to_string(),
-        });
-    }
+/// First emphasis delimiter left unmatched by [`parse_inline`]
+///
+/// Returns the text fragment holding the delimiter, the delimiter's byte offset in
+/// it, and the delimiter itself. A lone `*` only counts when it sits at a word
+/// boundary, so `2*3` and `a * b` are not reported.
+pub(super) fn find_unclosed_delimiter(inlines: &[Inline]) -> Option<(&str, usize, &'static str)> {
+    inlines.iter().find_map(|inline| match inline {
+        Inline::Text { content } => {
+            find_delimiter_in_text(content).map(|(offset, delim)| (content.as_str(), offset, delim))
+        }
+        Inline::Bold { content }
+        | Inline::Italic { content }
+        | Inline::Strikethrough { content }
+        | Inline::Highlight { content }
+        | Inline::Spoiler { content } => find_unclosed_delimiter(content),
+        Inline::Link { text: content, .. }
+        | Inline::Directive { content, .. }
+        | Inline::Span { content, .. } => find_unclosed_delimiter(content),
+        Inline::Code { .. }
+        | Inline::Image { .. }
+        | Inline::Hashtag { .. }
+        | Inline::Mention { .. }
+        | Inline::LineBreak
+        | Inline::Comment { .. } => None,
+    })
+}
 
-    Ok(inlines)
+fn find_delimiter_in_text(text: &str) -> Option<(usize, &'static str)> {
+    let bytes = text.as_bytes();
+    let is_space = |idx: Option<usize>| {
+        idx.and_then(|i| bytes.get(i))
+            .is_none_or(|b| b.is_ascii_whitespace())
+    };
+    let is_word = |idx: Option<usize>| {
+        idx.and_then(|i| bytes.get(i))
+            .is_some_and(|b| b.is_ascii_alphanumeric())
+    };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"**") {
+            return Some((i, "**"));
+        }
+        if bytes[i..].starts_with(b"~~") {
+            return Some((i, "~~"));
+        }
+        if bytes[i] == b'*' {
+            let before = i.checked_sub(1);
+            let after = Some(i + 1);
+            let opening = !is_space(after) && !is_word(before);
+            let closing = !is_space(before) && !is_word(after);
+            if opening || closing {
+                return Some((i, "*"));
+            }
+        }
+        i += 1;
+    }
+    None
 }
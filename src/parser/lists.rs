@@ -1,16 +1,42 @@
 //! List parsing (unordered, ordered, task lists).
 
 use crate::ast::{Inline, ListItem, Node, ParseError};
+use crate::config::ParserConfig;
 
 use super::inline::parse_inline;
 use super::inline::RegexPatterns;
+use super::NestedBlocks;
+
+/// Width of the leading whitespace of `line`, with each tab advancing to the next
+/// multiple of `tab_width` columns
+pub(super) fn indent_width(line: &str, tab_width: usize) -> usize {
+    let tab_width = tab_width.max(1);
+    let mut width = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width += tab_width - width % tab_width,
+            _ => break,
+        }
+    }
+    width
+}
+
+/// Nesting level of a list marker preceded by `prefix` (whitespace only)
+fn indent_level(prefix: &str, config: &ParserConfig) -> usize {
+    indent_width(prefix, config.tab_width) / config.list_indent.max(1)
+}
 
 /// Check if a raw line (with indentation) matches the ordered list pattern
 ///
 /// Returns Some((indent_level, number, content)) if it's an ordered list line, None otherwise.
-/// Indent level is calculated as number of 2-space increments (0 = no indent, 1 = 2 spaces, etc.)
+/// Indent level is the indentation width (tabs expanded to `tab_width`) divided by
+/// `list_indent`, rounded down.
 /// Pattern: one or more digits followed by `.` and a space
-pub(super) fn detect_ordered_list_line(line: &str) -> Option<(usize, u32, &str)> {
+pub(super) fn detect_ordered_list_line<'a>(
+    line: &'a str,
+    config: &ParserConfig,
+) -> Option<(usize, u32, &'a str)> {
     let trimmed = line.trim();
     if trimmed.is_empty() {
         return None;
@@ -18,6 +44,9 @@ pub(super) fn detect_ordered_list_line(line: &str) -> Option<(usize, u32, &str)>
 
     // Find the position of the first digit
     let digit_start = line.find(|c: char| c.is_ascii_digit())?;
+    if !line[..digit_start].trim().is_empty() {
+        return None;
+    }
 
     // Find where the digits end
     let mut digit_end = digit_start;
@@ -37,12 +66,7 @@ pub(super) fn detect_ordered_list_line(line: &str) -> Option<(usize, u32, &str)>
     let number_str = &line[digit_start..digit_end];
     let number: u32 = number_str.parse().ok()?;
 
-    // Calculate indent: count leading spaces, divide by 2 (round down)
-    let leading_spaces = line[..digit_start]
-        .chars()
-        .take_while(|&c| c == ' ')
-        .count();
-    let indent_level = leading_spaces / 2;
+    let indent_level = indent_level(&line[..digit_start], config);
 
     // Extract content after "number. "
     let content = line[digit_end + 2..].trim();
@@ -52,9 +76,12 @@ pub(super) fn detect_ordered_list_line(line: &str) -> Option<(usize, u32, &str)>
 /// Check if a raw line (with indentation) matches the list pattern
 ///
 /// Returns Some((indent_level, marker, content, checked)) if it's a list line, None otherwise.
-/// Indent level is calculated as number of 2-space increments (0 = no indent, 1 = 2 spaces, etc.)
+/// Indent level is computed as for [`detect_ordered_list_line`].
 /// checked is Some(bool) for task list items, None for regular list items.
-pub(super) fn detect_list_line(line: &str) -> Option<(usize, char, &str, Option<bool>)> {
+pub(super) fn detect_list_line<'a>(
+    line: &'a str,
+    config: &ParserConfig,
+) -> Option<(usize, char, &'a str, Option<bool>)> {
     let trimmed = line.trim();
     if trimmed.is_empty() {
         return None;
@@ -62,6 +89,9 @@ pub(super) fn detect_list_line(line: &str) -> Option<(usize, char, &str, Option<
 
     // Check for list markers: -, *, or +
     let marker_pos = line.find(['-', '*', '+'])?;
+    if !line[..marker_pos].trim().is_empty() {
+        return None;
+    }
     let marker = line.as_bytes()[marker_pos] as char;
 
     // Must be followed by a space
@@ -69,9 +99,7 @@ pub(super) fn detect_list_line(line: &str) -> Option<(usize, char, &str, Option<
         return None;
     }
 
-    // Calculate indent: count leading spaces, divide by 2 (round down)
-    let leading_spaces = line[..marker_pos].chars().take_while(|&c| c == ' ').count();
-    let indent_level = leading_spaces / 2;
+    let indent_level = indent_level(&line[..marker_pos], config);
 
     // Check for task list pattern: - [ ] or - [x] or - [X]
     // Only applies to '-' marker
@@ -105,34 +133,149 @@ pub(super) fn detect_list_line(line: &str) -> Option<(usize, char, &str, Option<
     Some((indent_level, marker, content, None))
 }
 
+/// Whether a list line can interrupt a paragraph
+///
+/// As in CommonMark, an empty item or an ordered item not numbered 1 cannot, so a
+/// paragraph wrapped before `1986. A great year` continues. With
+/// `legacy_list_interrupts`, every list line interrupts.
+pub(super) fn list_interrupts_paragraph(line: &str, config: &ParserConfig) -> bool {
+    if let Some((_, number, content)) = detect_ordered_list_line(line, config) {
+        return config.legacy_list_interrupts || (number == 1 && !content.is_empty());
+    }
+    detect_list_line(line, config).is_some_and(|(_, _, content, checked)| {
+        config.legacy_list_interrupts || !content.is_empty() || checked.is_some()
+    })
+}
+
 /// Check if a line is a continuation line (indented, no marker)
 ///
+/// Unindented text that could continue a paragraph is a lazy continuation of the last
+/// item, as in CommonMark, unless `legacy_list_interrupts` is set.
+///
 /// Returns Some(indent_level) if it's a continuation, None otherwise
-pub(super) fn detect_continuation_line(line: &str) -> Option<usize> {
+pub(super) fn detect_continuation_line(line: &str, config: &ParserConfig) -> Option<usize> {
     if line.trim().is_empty() {
         return None;
     }
 
-    // Must start with spaces (indented)
-    let leading_spaces = line.chars().take_while(|&c| c == ' ').count();
-    if leading_spaces == 0 {
+    // Must NOT match list pattern (no marker)
+    if detect_list_line(line, config).is_some() || detect_ordered_list_line(line, config).is_some()
+    {
         return None;
     }
 
-    // Must NOT match list pattern (no marker)
-    if detect_list_line(line).is_some() || detect_ordered_list_line(line).is_some() {
-        return None;
+    // Must start with spaces or tabs (indented), or be a lazy continuation
+    let width = indent_width(line, config.tab_width);
+    if width == 0 {
+        let lazy =
+            !config.legacy_list_interrupts && !super::blocks::interrupts_paragraph(line, config);
+        return lazy.then_some(0);
     }
 
     // Must not be a block element
-    // Note: This is a static method, so we can't access config here.
-    // We'll check for the default pattern "```" which is the standard.
     let trimmed = line.trim();
-    if trimmed.starts_with('#') || trimmed.starts_with("```") {
+    if super::blocks::is_atx_heading(trimmed, config)
+        || super::blocks::is_code_fence(trimmed, config)
+    {
         return None;
     }
 
-    Some(leading_spaces / 2)
+    Some(width / config.list_indent.max(1))
+}
+
+/// Whether the line at `idx`, indented under a list item, opens block content nested
+/// in the item
+///
+/// Fenced code blocks and tables do; an unindented fence ends the list instead.
+fn opens_item_blocks(lines: &[&str], idx: usize, config: &ParserConfig) -> bool {
+    let line = lines[idx];
+    indent_width(line, config.tab_width) > 0
+        && (super::blocks::is_code_fence(line.trim(), config)
+            || super::tables::starts_table(line, lines.get(idx + 1).copied()))
+}
+
+/// Index after the block content nested in a list item from `start_idx`
+///
+/// The content runs over lines indented at least `indent` columns, and through the
+/// closing fence of each fenced code block, up to a blank line or a list line.
+fn item_blocks_end(
+    lines: &[&str],
+    start_idx: usize,
+    indent: usize,
+    config: &ParserConfig,
+) -> usize {
+    let mut in_fence = false;
+    let mut i = start_idx;
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        if in_fence {
+            in_fence = trimmed != config.code_fence_pattern;
+            i += 1;
+            continue;
+        }
+        if trimmed.is_empty()
+            || indent_width(lines[i], config.tab_width) < indent
+            || detect_list_line(lines[i], config).is_some()
+            || detect_ordered_list_line(lines[i], config).is_some()
+        {
+            break;
+        }
+        in_fence = super::blocks::is_code_fence(trimmed, config);
+        i += 1;
+    }
+    i
+}
+
+/// `line` without up to `indent` columns of leading whitespace
+fn strip_indent(line: &str, indent: usize, tab_width: usize) -> &str {
+    let tab_width = tab_width.max(1);
+    let mut width = 0;
+    for (pos, c) in line.char_indices() {
+        if width >= indent {
+            return &line[pos..];
+        }
+        match c {
+            ' ' => width += 1,
+            '\t' => width += tab_width - width % tab_width,
+            _ => return &line[pos..],
+        }
+    }
+    ""
+}
+
+/// The list item reached by `path`, as tracked for continuation lines
+fn item_at<'a>(items: &'a mut [ListItem], path: &[(usize, usize)]) -> Option<&'a mut ListItem> {
+    let ((_, first), rest) = path.split_first()?;
+    let mut item = items.get_mut(*first)?;
+    for (_, idx) in rest {
+        item = item.children.get_mut(*idx)?;
+    }
+    Some(item)
+}
+
+/// Parse the block content nested in the last item from the line at `start_idx`,
+/// which opens it
+///
+/// Returns the index after the content.
+fn parse_item_blocks(
+    lines: &[&str],
+    start_idx: usize,
+    config: &ParserConfig,
+    items: &mut [ListItem],
+    path: &[(usize, usize)],
+    parse_blocks: &mut NestedBlocks<'_>,
+) -> Result<usize, ParseError> {
+    let indent = indent_width(lines[start_idx], config.tab_width);
+    let end = item_blocks_end(lines, start_idx, indent, config);
+    let body: Vec<&str> = lines[start_idx..end]
+        .iter()
+        .map(|line| strip_indent(line, indent, config.tab_width))
+        .collect();
+    let blocks = parse_blocks(&body, start_idx)?;
+    if let Some(item) = item_at(items, path) {
+        item.blocks.extend(blocks);
+    }
+    Ok(end)
 }
 
 /// Parse an unordered list starting at the given line index
@@ -143,6 +286,7 @@ pub(super) fn parse_unordered_list(
     start_idx: usize,
     config: &crate::config::ParserConfig,
     regex_patterns: &RegexPatterns,
+    parse_blocks: &mut NestedBlocks<'_>,
 ) -> Result<(Node, usize), ParseError> {
     let mut items = Vec::new();
     let mut i = start_idx;
@@ -160,14 +304,27 @@ pub(super) fn parse_unordered_list(
             break;
         }
 
+        // Block content indented under the last item belongs to it
+        if !last_item_path.is_empty() && opens_item_blocks(lines, i, config) {
+            i = parse_item_blocks(lines, i, config, &mut items, &last_item_path, parse_blocks)?;
+            continue;
+        }
+
         // Check for block elements - end of list
         let trimmed = line.trim();
-        if trimmed.starts_with('#') || trimmed.starts_with(&config.code_fence_pattern) {
+        if super::blocks::is_atx_heading(trimmed, config)
+            || super::blocks::is_code_fence(trimmed, config)
+            || super::horizontal_rules::detect_horizontal_rule(trimmed)
+        {
             break;
         }
 
         // Check if it's a list line
-        if let Some((indent_level, _marker, content, checked)) = detect_list_line(line) {
+        if let Some((indent_level, _marker, content, checked)) = detect_list_line(line, config) {
+            // An item indented more than one level past the previous one nests one level
+            let indent_level = indent_level.min(last_items.len());
+            super::check_nesting(indent_level + 1, config, i)?;
+
             // Parse the content as inline elements
             let inline_content = if content.is_empty() {
                 Vec::new()
@@ -178,6 +335,7 @@ pub(super) fn parse_unordered_list(
             let new_item = ListItem {
                 content: inline_content,
                 children: Vec::new(),
+                blocks: Vec::new(),
                 checked,
             };
 
@@ -199,9 +357,9 @@ pub(super) fn parse_unordered_list(
                 // Nested item: add to children of the last item at indent_level - 1
                 let parent_level = indent_level - 1;
                 if parent_level < last_items.len() {
-                    if let Some(parent_idx) = last_items[parent_level] {
-                        // Navigate to the parent item
-                        let mut current = &mut items[parent_idx];
+                    if let (Some(_), Some(root_idx)) = (last_items[parent_level], last_items[0]) {
+                        // Navigate from the top-level item down to the parent
+                        let mut current = &mut items[root_idx];
                         // Navigate through nested children to get to the right depth
                         for level in 1..indent_level {
                             if level < last_items.len() {
@@ -248,7 +406,7 @@ pub(super) fn parse_unordered_list(
             }
 
             i += 1;
-        } else if let Some(_continuation_indent) = detect_continuation_line(line) {
+        } else if let Some(_continuation_indent) = detect_continuation_line(line, config) {
             // Continuation line - append to the most recently added item
             let continuation_text = line.trim();
             if !continuation_text.is_empty() && !last_item_path.is_empty() {
@@ -270,7 +428,7 @@ pub(super) fn parse_unordered_list(
                     // Append continuation to this item
                     if !current.content.is_empty() {
                         current.content.push(Inline::Text {
-                            content: " ".to_string(),
+                            content: " ".into(),
                         });
                     }
                     current.content.extend(continuation_inlines);
@@ -279,7 +437,7 @@ pub(super) fn parse_unordered_list(
                     let item = items.last_mut().unwrap();
                     if !item.content.is_empty() {
                         item.content.push(Inline::Text {
-                            content: " ".to_string(),
+                            content: " ".into(),
                         });
                     }
                     item.content.extend(continuation_inlines);
@@ -303,6 +461,7 @@ pub(super) fn parse_ordered_list(
     start_idx: usize,
     config: &crate::config::ParserConfig,
     regex_patterns: &RegexPatterns,
+    parse_blocks: &mut NestedBlocks<'_>,
 ) -> Result<(Node, usize), ParseError> {
     let mut items = Vec::new();
     let mut i = start_idx;
@@ -320,14 +479,27 @@ pub(super) fn parse_ordered_list(
             break;
         }
 
+        // Block content indented under the last item belongs to it
+        if !last_item_path.is_empty() && opens_item_blocks(lines, i, config) {
+            i = parse_item_blocks(lines, i, config, &mut items, &last_item_path, parse_blocks)?;
+            continue;
+        }
+
         // Check for block elements - end of list
         let trimmed = line.trim();
-        if trimmed.starts_with('#') || trimmed.starts_with(&config.code_fence_pattern) {
+        if super::blocks::is_atx_heading(trimmed, config)
+            || super::blocks::is_code_fence(trimmed, config)
+            || super::horizontal_rules::detect_horizontal_rule(trimmed)
+        {
             break;
         }
 
         // Check if it's an ordered list line
-        if let Some((indent_level, _number, content)) = detect_ordered_list_line(line) {
+        if let Some((indent_level, _number, content)) = detect_ordered_list_line(line, config) {
+            // An item indented more than one level past the previous one nests one level
+            let indent_level = indent_level.min(last_items.len());
+            super::check_nesting(indent_level + 1, config, i)?;
+
             // Parse the content as inline elements
             let inline_content = if content.is_empty() {
                 Vec::new()
@@ -338,6 +510,7 @@ pub(super) fn parse_ordered_list(
             let new_item = ListItem {
                 content: inline_content,
                 children: Vec::new(),
+                blocks: Vec::new(),
                 checked: None, // Ordered lists don't support task lists
             };
 
@@ -359,9 +532,9 @@ pub(super) fn parse_ordered_list(
                 // Nested item: add to children of the last item at indent_level - 1
                 let parent_level = indent_level - 1;
                 if parent_level < last_items.len() {
-                    if let Some(parent_idx) = last_items[parent_level] {
-                        // Navigate to the parent item
-                        let mut current = &mut items[parent_idx];
+                    if let (Some(_), Some(root_idx)) = (last_items[parent_level], last_items[0]) {
+                        // Navigate from the top-level item down to the parent
+                        let mut current = &mut items[root_idx];
                         // Navigate through nested children to get to the right depth
                         for level in 1..indent_level {
                             if level < last_items.len() {
@@ -408,7 +581,7 @@ pub(super) fn parse_ordered_list(
             }
 
             i += 1;
-        } else if let Some(_continuation_indent) = detect_continuation_line(line) {
+        } else if let Some(_continuation_indent) = detect_continuation_line(line, config) {
             // Continuation line - append to the most recently added item
             let continuation_text = line.trim();
             if !continuation_text.is_empty() && !last_item_path.is_empty() {
@@ -430,7 +603,7 @@ pub(super) fn parse_ordered_list(
                     // Append continuation to this item
                     if !current.content.is_empty() {
                         current.content.push(Inline::Text {
-                            content: " ".to_string(),
+                            content: " ".into(),
                         });
                     }
                     current.content.extend(continuation_inlines);
@@ -439,15 +612,18 @@ pub(super) fn parse_ordered_list(
                     let item = items.last_mut().unwrap();
                     if !item.content.is_empty() {
                         item.content.push(Inline::Text {
-                            content: " ".to_string(),
+                            content: " ".into(),
                         });
                     }
                     item.content.extend(continuation_inlines);
                 }
             }
             i += 1;
-        } else if let Some((indent_level, _marker, content, checked)) = detect_list_line(line) {
+        } else if let Some((indent_level, _marker, content, checked)) =
+            detect_list_line(line, config)
+        {
             // Unordered list line - could be nested within ordered list
+            let indent_level = indent_level.min(last_items.len());
             // Parse the content as inline elements
             let inline_content = if content.is_empty() {
                 Vec::new()
@@ -458,6 +634,7 @@ pub(super) fn parse_ordered_list(
             let new_item = ListItem {
                 content: inline_content,
                 children: Vec::new(),
+                blocks: Vec::new(),
                 checked,
             };
 
@@ -472,9 +649,9 @@ pub(super) fn parse_ordered_list(
                 // Nested item: add to children of the last item at indent_level - 1
                 let parent_level = indent_level - 1;
                 if parent_level < last_items.len() {
-                    if let Some(parent_idx) = last_items[parent_level] {
-                        // Navigate to the parent item
-                        let mut current = &mut items[parent_idx];
+                    if let (Some(_), Some(root_idx)) = (last_items[parent_level], last_items[0]) {
+                        // Navigate from the top-level item down to the parent
+                        let mut current = &mut items[root_idx];
                         // Navigate through nested children to get to the right depth
                         for level in 1..indent_level {
                             if level < last_items.len() {
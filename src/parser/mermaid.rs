@@ -1,9 +1,62 @@
 //! Mermaid diagram validator and configuration parser.
 
-use crate::ast::{MermaidConfig, ValidationStatus};
+use crate::ast::{
+    for_each_node, MermaidConfig, Node, ParseWarning, Span, ValidationStatus, WarningCode,
+};
 use crate::config::MermaidParserConfig;
+use crate::mermaid_cli::{self, MmdcError};
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use super::includes::detect_reference;
+
+/// Warning emitted when CLI validation is requested but `mmdc` cannot be run
+pub(super) const CLI_UNAVAILABLE_WARNING: &str =
+    "Mermaid CLI not available, using basic validation only";
+
+/// Check if a line is a named diagram reference (`{{diagram:arch}}`) and return the name
+pub(super) fn detect_diagram_reference(line: &str) -> Option<&str> {
+    detect_reference(line, "diagram")
+}
+
+/// Diagram line (1-based) named by a validation error such as `Parse error on line 2: ...`
+pub(super) fn error_line(error: &str) -> Option<usize> {
+    let rest = &error[error.find("on line ")? + "on line ".len()..];
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..digits].parse().ok()
+}
+
+/// Warn about every `{{diagram:name}}` whose name no diagram in `nodes` defines
+///
+/// Definitions may come after their references, so this runs on the finished AST.
+pub(super) fn check_diagram_references(nodes: &[Node], warnings: &mut Vec<ParseWarning>) {
+    let mut names = HashSet::new();
+    for_each_node(nodes, &mut |node| {
+        if let Node::MermaidDiagram {
+            name: Some(name), ..
+        } = node
+        {
+            names.insert(name.as_str());
+        }
+    });
+    for_each_node(nodes, &mut |node| {
+        if let Node::DiagramRef { name, span } = node {
+            if !names.contains(name.as_str()) {
+                warnings.push(ParseWarning::new(
+                    WarningCode::UndefinedDiagram,
+                    Span {
+                        line: span.map_or(1, |span| span.start),
+                        column: None,
+                    },
+                    format!("undefined diagram `{}`", name),
+                ));
+            }
+        }
+    });
+}
 
 /// Mermaid diagram validator and configuration parser
 pub(super) struct MermaidValidator;
@@ -245,8 +298,12 @@ impl MermaidValidator {
 
     /// Validate Mermaid diagram syntax
     ///
-    /// Returns validation status and warnings
-    pub(super) fn validate_syntax(diagram: &str, use_cli: bool) -> (ValidationStatus, Vec<String>) {
+    /// With `cli_timeout`, the diagram is also rendered with the Mermaid CLI, which may
+    /// take at most that long. Returns validation status and warnings
+    pub(super) fn validate_syntax(
+        diagram: &str,
+        cli_timeout: Option<Duration>,
+    ) -> (ValidationStatus, Vec<String>) {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
 
@@ -355,11 +412,12 @@ impl MermaidValidator {
         }
 
         // Optional CLI validation
-        if use_cli {
-            if let Some(cli_errors) = Self::validate_with_cli(trimmed) {
-                errors.extend(cli_errors);
-            } else {
-                warnings.push("Mermaid CLI not available, using basic validation only".to_string());
+        if let Some(timeout) = cli_timeout {
+            match mermaid_cli::render_svg(trimmed, &[], timeout) {
+                Ok(_) => {}
+                Err(MmdcError::Unavailable) => warnings.push(CLI_UNAVAILABLE_WARNING.to_string()),
+                Err(MmdcError::Failed(message)) => errors.push(format!("Mermaid CLI: {}", message)),
+                Err(e) => warnings.push(format!("{}; only basic validation was done", e)),
             }
         }
 
@@ -369,58 +427,4 @@ impl MermaidValidator {
             (ValidationStatus::Invalid { errors }, warnings)
         }
     }
-
-    /// Attempt to validate using Mermaid CLI (if available)
-    fn validate_with_cli(diagram: &str) -> Option<Vec<String>> {
-        use std::fs;
-        use std::process::Command;
-
-        // Check if mmdc is available
-        if Command::new("mmdc").arg("--version").output().is_err() {
-            return None;
-        }
-
-        // Create a temporary file
-        let temp_dir = std::env::temp_dir();
-        let input_file = temp_dir.join(format!(
-            "mermaid_validate_{}.mmd",
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
-        ));
-        let output_file = temp_dir.join(format!(
-            "mermaid_validate_{}.svg",
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
-        ));
-
-        // Write diagram to temp file
-        if fs::write(&input_file, diagram).is_err() {
-            return None;
-        }
-
-        // Try to render with mmdc
-        let output = Command::new("mmdc")
-            .arg("-i")
-            .arg(&input_file)
-            .arg("-o")
-            .arg(&output_file)
-            .output();
-
-        // Clean up temp files
-        let _ = fs::remove_file(&input_file);
-        let _ = fs::remove_file(&output_file);
-
-        if let Ok(result) = output {
-            if !result.status.success() {
-                let stderr = String::from_utf8_lossy(&result.stderr);
-                return Some(vec![format!("Mermaid CLI validation failed: {}", stderr)]);
-            }
-        }
-
-        None
-    }
 }
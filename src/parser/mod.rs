@@ -1,24 +1,59 @@
 //! Markdown parsing logic.
 
+mod attributes;
+mod block_syntax;
 mod blockquotes;
 mod blocks;
+mod comments;
+mod containers;
+mod disk_cache;
+mod figures;
+mod frontmatter;
+mod headings;
 mod horizontal_rules;
+mod includes;
 mod inline;
 mod lists;
 mod mermaid;
+mod obsidian;
 mod tables;
+mod variables;
 
-use crate::ast::{Node, ParseError};
+use crate::ast::{
+    for_each_inline, for_each_node, Inline, LimitKind, LineRange, Node, ParseError, ParseWarning,
+    Severity, Span, WarningCode,
+};
+use crate::cancel::CancellationToken;
 use crate::config::ParserConfig;
+use crate::definitions::{Definition, DefinitionMap};
+use crate::events::{self, Event, EventHandler};
+use crate::line_index::LineIndex;
+use crate::outline::Outline;
+use crate::parse_result::{ParseResult, ParseStats};
+use crate::schema::VersionedNodes;
+use crate::transform;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::time::{Duration, Instant};
 
-use inline::RegexPatterns;
+pub use block_syntax::BlockSyntax;
+pub(crate) use blocks::is_code_fence;
+pub(crate) use frontmatter::detect_frontmatter;
+pub(crate) use inline::RegexPatterns;
 
 /// Parser for converting Markdown text into an AST
+#[derive(Clone)]
 pub struct Parser {
     input: String,
     regex_patterns: RegexPatterns,
-    warnings: Vec<String>,
-    config: ParserConfig,
+    warnings: Vec<ParseWarning>,
+    pub(crate) config: ParserConfig,
+    /// Custom block syntaxes, tried before the built-in ones
+    block_syntaxes: Vec<BlockSyntax>,
+    /// Result of the last successful parse of `input`, reused until input or config change
+    cache: Option<ParseResult>,
+    /// Definitions from outside the input, such as a shared file of link references
+    external_definitions: Vec<Definition>,
 }
 
 impl Parser {
@@ -37,29 +72,520 @@ impl Parser {
     ///
     /// Returns `ParseError` if regex patterns fail to compile
     pub fn with_config(input: String, config: ParserConfig) -> Result<Self, ParseError> {
-        let regex_patterns = RegexPatterns::new()?;
+        let regex_patterns = RegexPatterns::new(&config)?;
         Ok(Self {
             input,
             regex_patterns,
             warnings: Vec::new(),
             config,
+            block_syntaxes: Vec::new(),
+            cache: None,
+            external_definitions: Vec::new(),
         })
     }
 
+    /// Replace the document to parse, discarding the cached AST
+    pub fn set_input(&mut self, input: String) {
+        self.input = input;
+        self.cache = None;
+    }
+
+    /// Replace the configuration, discarding the cached AST
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if regex patterns fail to compile; the parser is unchanged
+    pub fn set_config(&mut self, config: ParserConfig) -> Result<(), ParseError> {
+        self.regex_patterns = RegexPatterns::new(&config)?;
+        self.config = config;
+        self.cache = None;
+        Ok(())
+    }
+
+    /// Register a custom block syntax, discarding the cached AST
+    ///
+    /// Syntaxes are tried in registration order at the start of every block, before the
+    /// built-in ones; see [`BlockSyntax`].
+    pub fn add_block_syntax(&mut self, syntax: BlockSyntax) {
+        self.block_syntaxes.push(syntax);
+        self.cache = None;
+    }
+
+    /// Set the link reference and footnote definitions that live outside the input,
+    /// discarding the cached AST
+    ///
+    /// References in the input may use them, and they are reported as
+    /// [`DefinitionMap::external`] by [`ParseResult::definitions`]. Definitions in the
+    /// input take precedence. For a shared file, use the `definitions` of
+    /// [`DefinitionMap::from_source`].
+    pub fn set_external_definitions(&mut self, definitions: Vec<Definition>) {
+        self.external_definitions = definitions;
+        self.cache = None;
+    }
+
+    /// Discard the cached AST, so the next call parses the input again
+    ///
+    /// Needed only when something outside the input changed, such as an included
+    /// file or the output of the Mermaid CLI.
+    pub fn clear_cache(&mut self) {
+        self.cache = None;
+    }
+
+    /// Create a parser without an input, for parsing many documents with [`Parser::parse_str`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if regex patterns fail to compile
+    pub fn from_config(config: ParserConfig) -> Result<Self, ParseError> {
+        Self::with_config(String::new(), config)
+    }
+
     /// Parse the input Markdown into a vector of AST nodes
     ///
+    /// The result is cached: later calls, and methods such as [`Parser::to_json`] and
+    /// [`Parser::to_html`](crate::Parser::to_html), reuse it until the input or config
+    /// changes (see [`Parser::set_input`] and [`Parser::clear_cache`]).
+    ///
     /// # Errors
     ///
     /// Returns `ParseError` if parsing fails
     pub fn parse(&mut self) -> Result<Vec<Node>, ParseError> {
+        self.parse_full().map(|result| result.nodes)
+    }
+
+    /// Parse the input Markdown, returning the AST together with its warnings,
+    /// frontmatter, statistics, and the source lines of each top-level node
+    ///
+    /// Warnings are also available from [`Parser::warnings`] afterwards. The result is
+    /// cached, as with [`Parser::parse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails
+    pub fn parse_full(&mut self) -> Result<ParseResult, ParseError> {
+        self.parsed(None).cloned()
+    }
+
+    /// Cached result for the parser's input, parsing it first if there is none
+    ///
+    /// A cache hit restores the warnings of that parse, which [`Parser::parse_str`] may
+    /// have replaced. With `cache_dir`, a result stored on disk is used before parsing.
+    pub(crate) fn parsed(
+        &mut self,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<&ParseResult, ParseError> {
+        let cached = match self.cache.take() {
+            Some(cached) => cached,
+            None => {
+                let entry = disk_cache::entry_key(self);
+                match entry.as_ref().and_then(disk_cache::load) {
+                    Some(stored) => stored,
+                    None => {
+                        let input = std::mem::take(&mut self.input);
+                        let result = self.parse_internal(&input, cancel);
+                        self.input = input;
+                        let result = result?;
+                        if let Some(entry) = &entry {
+                            disk_cache::store(entry, &result);
+                        }
+                        result
+                    }
+                }
+            }
+        };
+        self.warnings.clone_from(&cached.warnings);
+        Ok(self.cache.insert(cached))
+    }
+
+    /// Parse `input` instead of the parser's own input, reusing the compiled patterns
+    ///
+    /// Building a parser compiles its regular expressions; a batch converter can build
+    /// one with [`Parser::from_config`] and call this for every file. Warnings are
+    /// replaced on each call, as with [`Parser::parse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails
+    pub fn parse_str(&mut self, input: &str) -> Result<Vec<Node>, ParseError> {
+        self.parse_internal(input, None).map(|result| result.nodes)
+    }
+
+    /// Parse the input Markdown, aborting when `cancel` is triggered
+    ///
+    /// The token is checked between blocks and before each Mermaid CLI invocation,
+    /// so a host can stop parsing a huge document as soon as it becomes stale.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::Cancelled` if the token was cancelled, or any other
+    /// `ParseError` if parsing fails
+    pub fn parse_with_cancel(
+        &mut self,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<Node>, ParseError> {
+        self.parsed(Some(cancel)).map(|result| result.nodes.clone())
+    }
+
+    /// Parse the input Markdown without blocking the calling task
+    ///
+    /// The parse runs on a helper thread and the returned future completes when it
+    /// finishes, so executors are not blocked by Mermaid CLI validation or include
+    /// reads. Works with any async runtime. Warnings are available from
+    /// [`Parser::warnings`] afterwards, as with [`Parser::parse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails
+    #[cfg(feature = "async")]
+    pub async fn parse_async(&mut self) -> Result<Vec<Node>, ParseError> {
+        let mut worker = self.clone();
+        let (result, warnings, cache) = crate::blocking::spawn(move || {
+            let result = worker.parse();
+            (result, worker.warnings, worker.cache)
+        })
+        .await;
+        self.warnings = warnings;
+        self.cache = cache;
+        result
+    }
+
+    /// Parse the input Markdown, pushing events to `handler` instead of building the AST
+    ///
+    /// Each top-level block is handed to `handler` as [`Event`]s as soon as it is parsed
+    /// and dropped afterwards, so memory use does not grow with the number of blocks.
+    /// With `enable_sections`, `StartSection` and `EndSection` events are emitted
+    /// around headings and the blocks below them. Warnings are available from
+    /// [`Parser::warnings`] afterwards, except for references to undefined diagrams,
+    /// which need the whole document. The result is not cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing fails. Events for the blocks before the failure
+    /// have already been delivered.
+    pub fn parse_events(&mut self, handler: &mut impl EventHandler) -> Result<(), ParseError> {
+        let input = std::mem::take(&mut self.input);
+        let result = self.parse_events_internal(&input, handler);
+        self.input = input;
+        result
+    }
+
+    fn parse_events_internal(
+        &mut self,
+        input: &str,
+        handler: &mut impl EventHandler,
+    ) -> Result<(), ParseError> {
+        self.warnings.clear();
+        let deadline = self.deadline();
+        let source = self
+            .prepare_source(input)
+            .map_err(|e| e.with_column(&LineIndex::new(input)))?;
+        let index = LineIndex::new(&source);
+        let lines: Vec<&str> = source.lines().collect();
+
+        let enable_sections = self.config.enable_sections;
+        let mut open_sections: Vec<u8> = Vec::new();
+        let mut sink = |node: Node| {
+            if let (
+                true,
+                Node::Heading {
+                    level, attributes, ..
+                },
+            ) = (enable_sections, &node)
+            {
+                while open_sections.last().is_some_and(|open| open >= level) {
+                    let level = open_sections.pop().unwrap_or_default();
+                    handler.event(Event::EndSection { level });
+                }
+                open_sections.push(*level);
+                handler.event(Event::StartSection {
+                    level: *level,
+                    attributes,
+                });
+            }
+            events::emit_node(&node, handler);
+        };
+        let mut blocks = BlockParser {
+            config: &self.config,
+            regex_patterns: &self.regex_patterns,
+            block_syntaxes: &self.block_syntaxes,
+            warnings: &mut self.warnings,
+            cancel: None,
+            deadline,
+            depth: 0,
+            line_idx: 0,
+            node_count: 0,
+            counted_nodes: 0,
+            spans: Vec::new(),
+            sink: Some(&mut sink),
+        };
+        let parsed = blocks.parse(&lines).map_err(|e| e.with_column(&index));
+        for warning in &mut self.warnings {
+            index.fill_column(&mut warning.span);
+        }
+        parsed?;
+
+        while let Some(level) = open_sections.pop() {
+            handler.event(Event::EndSection { level });
+        }
+        Ok(())
+    }
+
+    /// When a parse started now runs out of `limits.timeout_ms`
+    fn deadline(&self) -> Option<Instant> {
+        self.config
+            .limits
+            .timeout_ms
+            .map(|ms| Instant::now() + Duration::from_millis(ms))
+    }
+
+    /// `input` with includes expanded, line endings normalized, and variables substituted
+    fn prepare_source<'i>(&mut self, input: &'i str) -> Result<Cow<'i, str>, ParseError> {
+        let mut source = Cow::Borrowed(input.strip_prefix('\u{feff}').unwrap_or(input));
+        check_input_size(&source, &self.config)?;
+        if self.config.enable_includes {
+            source = Cow::Owned(includes::expand_includes(&source, &self.config)?);
+            check_input_size(&source, &self.config)?;
+        }
+        if self.config.normalize_line_endings && source.contains('\r') {
+            source = Cow::Owned(normalize_line_endings(&source));
+        }
+        if self.config.enable_variables {
+            source = Cow::Owned(variables::substitute(
+                &source,
+                &self.config.variables,
+                &mut self.warnings,
+            ));
+            for warning in &mut self.warnings {
+                if self.config.deny.contains(&warning.code) {
+                    warning.severity = Severity::Error;
+                }
+            }
+        }
+        Ok(source)
+    }
+
+    fn parse_internal(
+        &mut self,
+        input: &str,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<ParseResult, ParseError> {
+        let started = Instant::now();
         // Clear warnings at the start of each parse
         self.warnings.clear();
+        let deadline = self.deadline();
+        let source = self
+            .prepare_source(input)
+            .map_err(|e| e.with_column(&LineIndex::new(input)))?;
+        let index = LineIndex::new(&source);
+
+        let lines: Vec<&str> = source.lines().collect();
+        let frontmatter =
+            frontmatter::detect_frontmatter(&lines).map(|end| lines[1..end - 1].join("\n"));
+        let mut blocks = BlockParser {
+            config: &self.config,
+            regex_patterns: &self.regex_patterns,
+            block_syntaxes: &self.block_syntaxes,
+            warnings: &mut self.warnings,
+            cancel,
+            deadline,
+            depth: 0,
+            line_idx: 0,
+            node_count: 0,
+            counted_nodes: 0,
+            spans: Vec::new(),
+            sink: None,
+        };
+        let mut nodes = blocks.parse(&lines).map_err(|e| e.with_column(&index))?;
+        let mut spans = std::mem::take(&mut blocks.spans);
+
+        let mut ast_warnings = Vec::new();
+        mermaid::check_diagram_references(&nodes, &mut ast_warnings);
+        headings::check_headings(
+            &nodes,
+            &spans,
+            &self.config.heading_checks,
+            &mut ast_warnings,
+        );
+        for warning in ast_warnings {
+            blocks.push_warning(warning);
+        }
+        for warning in &mut self.warnings {
+            index.fill_column(&mut warning.span);
+        }
+
+        if let Some(denied) = self
+            .warnings
+            .iter()
+            .find(|warning| warning.severity == Severity::Error)
+        {
+            return Err(ParseError::DeniedWarning(denied.clone()));
+        }
+
+        if self.config.enable_sections {
+            nodes = transform::nest_sections(&nodes);
+            spans = nested_spans(&nodes, &mut spans.iter());
+        }
+
+        let mut stats = ParseStats {
+            lines: lines.len(),
+            bytes: source.len(),
+            ..ParseStats::default()
+        };
+        for_each_node(&nodes, &mut |_| stats.blocks += 1);
+        for_each_inline(&nodes, &mut |_| stats.inlines += 1);
+        stats.duration_us = started.elapsed().as_micros();
+
+        Ok(ParseResult {
+            nodes,
+            warnings: self.warnings.clone(),
+            frontmatter,
+            stats,
+            spans,
+            definitions: DefinitionMap::scan(
+                &source,
+                self.external_definitions.clone(),
+                &self.config,
+                &self.regex_patterns.reference,
+            ),
+        })
+    }
+
+    /// Serialize the AST to JSON string
+    ///
+    /// The output is an [`AstDocument`](crate::AstDocument): the nodes together with the
+    /// `schema_version` of the format, described by [`json_schema`](crate::json_schema).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing or serialization fails
+    pub fn to_json(&mut self) -> Result<String, ParseError> {
+        let ast = &self.parsed(None)?.nodes;
+        serde_json::to_string_pretty(&VersionedNodes::new(ast)).map_err(|e| {
+            ParseError::SerializationError(format!("JSON serialization failed: {}", e))
+        })
+    }
+
+    /// Serialize the document as a nested outline of sections
+    ///
+    /// Unlike [`Parser::to_json`], headings become a tree: each section carries the
+    /// nodes below its heading and its subsections. See [`Outline::from_nodes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing or serialization fails
+    pub fn to_outline_json(&mut self) -> Result<String, ParseError> {
+        let ast = &self.parsed(None)?.nodes;
+        serde_json::to_string_pretty(&Outline::from_nodes(ast)).map_err(|e| {
+            ParseError::SerializationError(format!("JSON serialization failed: {}", e))
+        })
+    }
 
+    /// Serialize the AST to YAML, with the same structure as [`Parser::to_json`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing or serialization fails
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&mut self) -> Result<String, ParseError> {
+        let ast = &self.parsed(None)?.nodes;
+        serde_yaml::to_string(&VersionedNodes::new(ast)).map_err(|e| {
+            ParseError::SerializationError(format!("YAML serialization failed: {}", e))
+        })
+    }
+
+    /// Serialize the AST to MessagePack, with the same structure as [`Parser::to_json`]
+    ///
+    /// Structs are written as maps keyed by field name, so the output can be decoded
+    /// without this crate's type definitions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing or serialization fails
+    #[cfg(feature = "msgpack")]
+    pub fn to_msgpack(&mut self) -> Result<Vec<u8>, ParseError> {
+        let ast = &self.parsed(None)?.nodes;
+        rmp_serde::to_vec_named(&VersionedNodes::new(ast)).map_err(|e| {
+            ParseError::SerializationError(format!("MessagePack serialization failed: {}", e))
+        })
+    }
+
+    /// Serialize the AST to CBOR, with the same structure as [`Parser::to_json`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if parsing or serialization fails
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor(&mut self) -> Result<Vec<u8>, ParseError> {
+        let ast = &self.parsed(None)?.nodes;
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&VersionedNodes::new(ast), &mut bytes).map_err(|e| {
+            ParseError::SerializationError(format!("CBOR serialization failed: {}", e))
+        })?;
+        Ok(bytes)
+    }
+
+    /// Get a reference to the warnings collected during parsing
+    ///
+    /// Warnings are generated for issues like unclosed emphasis or invalid Mermaid
+    /// diagrams. The warnings vector is cleared at the start of each `parse()` call.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+}
+
+/// Parser of block content nested in list items and blockquotes, given its lines and
+/// the index of the first one in the enclosing lines
+type NestedBlocks<'p> = dyn FnMut(&[&str], usize) -> Result<Vec<Node>, ParseError> + 'p;
+
+/// Block-level parser shared by the document and the bodies of container blocks
+struct BlockParser<'a> {
+    config: &'a ParserConfig,
+    regex_patterns: &'a RegexPatterns,
+    block_syntaxes: &'a [BlockSyntax],
+    warnings: &'a mut Vec<ParseWarning>,
+    cancel: Option<&'a CancellationToken>,
+    /// When `limits.timeout_ms` runs out
+    deadline: Option<Instant>,
+    /// Number of container blocks around the lines being parsed
+    depth: usize,
+    /// Index of the line starting the block being parsed, relative to the current lines
+    line_idx: usize,
+    /// Nodes counted against `limits.max_nodes` so far
+    node_count: usize,
+    /// Number of top-level nodes included in `node_count`
+    counted_nodes: usize,
+    /// Source lines of the top-level nodes parsed so far
+    spans: Vec<LineRange>,
+    /// Receiver of top-level nodes as they complete, instead of returning them
+    sink: Option<&'a mut dyn FnMut(Node)>,
+}
+
+impl BlockParser<'_> {
+    /// Parse a sequence of lines into block nodes
+    ///
+    /// Spans are relative to `lines`; see [`BlockParser::parse_nested`]. Limit errors
+    /// raised without a location point at the block being parsed.
+    fn parse(&mut self, lines: &[&str]) -> Result<Vec<Node>, ParseError> {
+        self.parse_blocks(lines)
+            .map_err(|error| error.at_line(self.line_idx + 1))
+    }
+
+    fn parse_blocks(&mut self, lines: &[&str]) -> Result<Vec<Node>, ParseError> {
         let mut nodes = Vec::new();
-        let lines: Vec<&str> = self.input.lines().collect();
-        let mut i = 0;
+        // Frontmatter delimiters are not rules; the block is skipped
+        let mut i = match self.depth {
+            0 => frontmatter::detect_frontmatter(lines).unwrap_or(0),
+            _ => 0,
+        };
 
+        let mut block_start = i;
         while i < lines.len() {
+            self.record_spans(&nodes, lines, block_start..i);
+            block_start = i;
+            check_cancelled(self.cancel, i)?;
+            self.check_limits(&nodes, i)?;
+            self.flush(&mut nodes)?;
+            self.line_idx = i;
             let line = lines[i].trim();
 
             // Skip empty lines
@@ -68,51 +594,247 @@ impl Parser {
                 continue;
             }
 
+            // Check for custom block syntaxes
+            if let Some((node, new_idx)) = self
+                .block_syntaxes
+                .iter()
+                .find_map(|syntax| syntax.detect(lines, i))
+            {
+                nodes.push(node);
+                i = new_idx;
+                continue;
+            }
+
             // Check for fenced code blocks
-            if line.starts_with(&self.config.code_fence_pattern) {
+            if blocks::is_code_fence(line, self.config) {
                 let (node, new_idx, warnings) =
-                    blocks::parse_code_block(&lines, i, &self.config, &self.regex_patterns)?;
-                self.warnings.extend(warnings);
+                    blocks::parse_code_block(lines, i, self.config, self.cancel)?;
+                for warning in warnings {
+                    self.push_warning(warning);
+                }
+                let (node, new_idx) = self.with_caption(node, lines, new_idx)?;
                 nodes.push(node);
                 i = new_idx;
                 continue;
             }
 
+            // Check for comments on lines of their own (`<!-- ... -->`, or `%% ... %%`
+            // in Obsidian mode)
+            let markers = if self.config.enable_obsidian && line.trim_start().starts_with("%%") {
+                Some(comments::OBSIDIAN_COMMENT)
+            } else {
+                comments::detect_comment_start(line).then_some(comments::HTML_COMMENT)
+            };
+            if let Some(markers) = markers {
+                if let Some(comment) = comments::parse_comment_block(lines, i, markers) {
+                    if !comment.closed {
+                        self.push_warning(ParseWarning::new(
+                            WarningCode::UnclosedComment,
+                            Span {
+                                line: i + 1,
+                                column: None,
+                            },
+                            format!("unclosed `{}` comment; closed at end of input", markers.0),
+                        ));
+                    }
+                    nodes.push(Node::Comment {
+                        content: comment.content,
+                    });
+                    i = comment.next_idx;
+                    continue;
+                }
+            }
+
+            // Check for fenced admonitions (`:::note` ... `:::`) and `:::details` blocks
+            if self.config.enable_admonitions {
+                if let Some(kind) = containers::detect_fenced_admonition(line) {
+                    let (content, new_idx) = self.parse_fenced_body(lines, i)?;
+                    nodes.push(Node::Admonition {
+                        kind,
+                        title: None,
+                        content,
+                    });
+                    i = new_idx;
+                    continue;
+                }
+                if let Some(title) = containers::detect_details(line) {
+                    let title = if title.is_empty() { "Details" } else { title };
+                    let summary = inline::parse_inline(title, self.regex_patterns)?;
+                    let (content, new_idx) = self.parse_fenced_body(lines, i)?;
+                    nodes.push(Node::Details { summary, content });
+                    i = new_idx;
+                    continue;
+                }
+            }
+
+            // Check for generic fenced divs (`::: {.class #id}` ... `:::`)
+            if self.config.enable_directives {
+                if let Some(attributes) = containers::detect_container(line) {
+                    let (children, new_idx) = self.parse_fenced_body(lines, i)?;
+                    nodes.push(Node::Container {
+                        attributes,
+                        children,
+                    });
+                    i = new_idx;
+                    continue;
+                }
+            }
+
             // Check for headings (# syntax)
             let line_number = i + 1;
-            if let Some(heading_node) =
-                blocks::parse_heading(line, line_number, &self.config, &self.regex_patterns)?
-            {
+            let heading =
+                match blocks::parse_heading(line, line_number, self.config, self.regex_patterns) {
+                    Err(ParseError::InvalidHeadingLevel { level, max, span })
+                        if self.config.error_recovery =>
+                    {
+                        self.push_warning(ParseWarning::new(
+                            WarningCode::InvalidHeadingLevel,
+                            span,
+                            format!(
+                                "invalid heading level {} (max {}); kept as a paragraph",
+                                level, max
+                            ),
+                        ));
+                        let content = inline::parse_inline(line, self.regex_patterns)?;
+                        nodes.push(Node::Paragraph { content });
+                        i += 1;
+                        continue;
+                    }
+                    result => result?,
+                };
+            if let Some(heading_node) = heading {
+                if let Node::Heading { content, .. } = &heading_node {
+                    self.check_unclosed_emphasis(content, &lines[i..=i], i);
+                }
                 nodes.push(heading_node);
                 i += 1;
                 continue;
             }
 
+            // Check for horizontal rules (before lists, so `- - -` and `* * *` are rules)
+            if horizontal_rules::detect_horizontal_rule(lines[i]) {
+                nodes.push(Node::HorizontalRule {
+                    span: Some(LineRange {
+                        start: i + 1,
+                        end: i + 1,
+                    }),
+                });
+                i += 1;
+                continue;
+            }
+
             // Check for ordered lists (must check before unordered lists, must check raw line, not trimmed, to detect indentation)
-            if lists::detect_ordered_list_line(lines[i]).is_some() {
-                let (list_node, new_idx) =
-                    lists::parse_ordered_list(&lines, i, &self.config, &self.regex_patterns)?;
+            if lists::detect_ordered_list_line(lines[i], self.config).is_some() {
+                let (config, regex_patterns) = (self.config, self.regex_patterns);
+                let (list_node, new_idx) = lists::parse_ordered_list(
+                    lines,
+                    i,
+                    config,
+                    regex_patterns,
+                    &mut |body, offset| self.parse_nested(body, offset),
+                )?;
                 nodes.push(list_node);
                 i = new_idx;
                 continue;
             }
 
             // Check for unordered lists (must check raw line, not trimmed, to detect indentation)
-            if lists::detect_list_line(lines[i]).is_some() {
-                let (list_node, new_idx) =
-                    lists::parse_unordered_list(&lines, i, &self.config, &self.regex_patterns)?;
+            if lists::detect_list_line(lines[i], self.config).is_some() {
+                let (config, regex_patterns) = (self.config, self.regex_patterns);
+                let (list_node, new_idx) = lists::parse_unordered_list(
+                    lines,
+                    i,
+                    config,
+                    regex_patterns,
+                    &mut |body, offset| self.parse_nested(body, offset),
+                )?;
                 nodes.push(list_node);
                 i = new_idx;
                 continue;
             }
 
-            // Check for tables (must check if current line is a table row and next line is separator)
-            if tables::detect_table_row(lines[i]) {
-                // Check if next line is a separator
-                if i + 1 < lines.len() && tables::detect_table_separator(lines[i + 1]) {
-                    let (table_node, new_idx) =
-                        tables::parse_table(&lines, i, &self.config, &self.regex_patterns)?;
-                    nodes.push(table_node);
+            // Check for CSV table references (`{{csv:data.csv}}`)
+            if self.config.enable_csv_tables {
+                if let Some(path) = includes::detect_csv_include(line) {
+                    let mut warnings = Vec::new();
+                    let table = includes::load_csv_table(path, i, self.config, &mut warnings)?;
+                    for warning in warnings {
+                        self.push_warning(warning);
+                    }
+                    nodes.push(table);
+                    i += 1;
+                    continue;
+                }
+            }
+
+            // Check for named diagram references (`{{diagram:arch}}`)
+            if let Some(name) = mermaid::detect_diagram_reference(line) {
+                nodes.push(Node::DiagramRef {
+                    name: name.to_string(),
+                    span: Some(LineRange {
+                        start: i + 1,
+                        end: i + 1,
+                    }),
+                });
+                i += 1;
+                continue;
+            }
+
+            // Check for tables (a table row followed by a separator)
+            if tables::starts_table(lines[i], lines.get(i + 1).copied()) {
+                let (table_node, new_idx, warnings) =
+                    tables::parse_table(lines, i, self.config, self.regex_patterns)?;
+                for warning in warnings {
+                    self.push_warning(warning);
+                }
+                nodes.push(table_node);
+                i = new_idx;
+                continue;
+            }
+
+            // Check for Obsidian callouts, and GitHub alerts (`> [!NOTE]`), before plain
+            // blockquotes
+            if self.config.enable_obsidian {
+                if let Some(callout) = obsidian::detect_callout(lines[i]) {
+                    let title = match (callout.title, callout.default_title()) {
+                        (Some(title), _) => Some(inline::parse_inline(title, self.regex_patterns)?),
+                        (None, Some(title)) => Some(vec![Inline::Text {
+                            content: title.into(),
+                        }]),
+                        (None, None) => None,
+                    };
+                    let (body, new_idx) = containers::collect_alert_lines(lines, i);
+                    let content = self.parse_nested(&body, i + 1)?;
+                    // A foldable callout becomes a collapsible block titled like the callout
+                    nodes.push(if callout.foldable {
+                        Node::Details {
+                            summary: title.unwrap_or_else(|| {
+                                vec![Inline::Text {
+                                    content: callout.kind.title().into(),
+                                }]
+                            }),
+                            content,
+                        }
+                    } else {
+                        Node::Admonition {
+                            kind: callout.kind,
+                            title,
+                            content,
+                        }
+                    });
+                    i = new_idx;
+                    continue;
+                }
+            }
+            if self.config.enable_admonitions {
+                if let Some(kind) = containers::detect_alert(lines[i]) {
+                    let (body, new_idx) = containers::collect_alert_lines(lines, i);
+                    let content = self.parse_nested(&body, i + 1)?;
+                    nodes.push(Node::Admonition {
+                        kind,
+                        title: None,
+                        content,
+                    });
                     i = new_idx;
                     continue;
                 }
@@ -120,24 +842,45 @@ impl Parser {
 
             // Check for blockquotes
             if blockquotes::detect_blockquote_line(lines[i]).is_some() {
-                let (blockquote_node, new_idx) =
-                    blockquotes::parse_blockquote(&lines, i, &self.config, &self.regex_patterns)?;
+                let (config, regex_patterns) = (self.config, self.regex_patterns);
+                let (blockquote_node, new_idx) = blockquotes::parse_blockquote(
+                    lines,
+                    i,
+                    config,
+                    regex_patterns,
+                    &mut |body, offset| self.parse_nested(body, offset),
+                )?;
                 nodes.push(blockquote_node);
                 i = new_idx;
                 continue;
             }
 
-            // Check for horizontal rules
-            if horizontal_rules::detect_horizontal_rule(lines[i]) {
-                nodes.push(Node::HorizontalRule);
-                i += 1;
-                continue;
+            // Check for a standalone image line with a `Figure: ...` caption
+            if figures::find_caption(lines, i + 1).is_some() {
+                let node = Node::Paragraph {
+                    content: inline::parse_inline(line, self.regex_patterns)?,
+                };
+                if figures::is_figure_content(&node) {
+                    let (node, new_idx) = self.with_caption(node, lines, i + 1)?;
+                    nodes.push(node);
+                    i = new_idx;
+                    continue;
+                }
             }
 
             // Collect paragraph lines (until empty line or block element)
-            let (para_text, new_idx) = blocks::collect_paragraph_lines(&lines, i, &self.config);
-            if !para_text.is_empty() {
-                let inline_content = inline::parse_inline(&para_text, &self.regex_patterns)?;
+            let (para_text, new_idx, setext_level) =
+                blocks::collect_paragraph_lines(lines, i, self.config);
+            if let Some(level) = setext_level {
+                let heading =
+                    blocks::setext_heading(&para_text, level, self.config, self.regex_patterns)?;
+                if let Node::Heading { content, .. } = &heading {
+                    self.check_unclosed_emphasis(content, &lines[i..new_idx], i);
+                }
+                nodes.push(heading);
+            } else if !para_text.is_empty() {
+                let inline_content = inline::parse_inline(&para_text, self.regex_patterns)?;
+                self.check_unclosed_emphasis(&inline_content, &lines[i..new_idx], i);
                 nodes.push(Node::Paragraph {
                     content: inline_content,
                 });
@@ -145,26 +888,293 @@ impl Parser {
             i = new_idx;
         }
 
+        self.record_spans(&nodes, lines, block_start..lines.len());
+        self.check_limits(&nodes, lines.len())?;
+        self.flush(&mut nodes)?;
         Ok(nodes)
     }
 
-    /// Serialize the AST to JSON string
+    /// Wrap `node`, which ends before line `idx`, in a figure if a `Figure: ...` caption
+    /// follows it
     ///
-    /// # Errors
+    /// Returns the node and the index of the line after it and its caption.
+    fn with_caption(
+        &self,
+        node: Node,
+        lines: &[&str],
+        idx: usize,
+    ) -> Result<(Node, usize), ParseError> {
+        match figures::find_caption(lines, idx) {
+            Some((caption, new_idx)) if figures::is_figure_content(&node) => Ok((
+                Node::Figure {
+                    content: vec![node],
+                    caption: inline::parse_inline(caption, self.regex_patterns)?,
+                    number: None,
+                },
+                new_idx,
+            )),
+            _ => Ok((node, idx)),
+        }
+    }
+
+    /// Hand the top-level nodes parsed so far to the sink, if there is one
     ///
-    /// Returns `ParseError` if parsing or serialization fails
-    pub fn to_json(&mut self) -> Result<String, ParseError> {
-        let ast = self.parse()?;
-        serde_json::to_string_pretty(&ast).map_err(|e| {
-            ParseError::SerializationError(format!("JSON serialization failed: {}", e))
-        })
+    /// Fails without handing them over when a denied warning has been raised.
+    fn flush(&mut self, nodes: &mut Vec<Node>) -> Result<(), ParseError> {
+        let (0, Some(sink)) = (self.depth, self.sink.as_mut()) else {
+            return Ok(());
+        };
+        if let Some(denied) = self
+            .warnings
+            .iter()
+            .find(|warning| warning.severity == Severity::Error)
+        {
+            return Err(ParseError::DeniedWarning(denied.clone()));
+        }
+        for node in nodes.drain(..) {
+            sink(node);
+        }
+        self.spans.clear();
+        self.counted_nodes = 0;
+        Ok(())
     }
 
-    /// Get a reference to the warnings collected during parsing
+    /// Give top-level nodes without a span yet the non-blank lines of `block`
     ///
-    /// Warnings are generated for issues like unclosed code blocks.
-    /// The warnings vector is cleared at the start of each `parse()` call.
-    pub fn warnings(&self) -> &[String] {
-        &self.warnings
+    /// Called before each block, so the nodes just pushed came from the lines since
+    /// the previous call. Nested bodies are not recorded.
+    fn record_spans(&mut self, nodes: &[Node], lines: &[&str], block: Range<usize>) {
+        if self.depth > 0 || self.spans.len() >= nodes.len() {
+            return;
+        }
+        let last = block
+            .clone()
+            .rev()
+            .find(|&idx| !lines[idx].trim().is_empty())
+            .unwrap_or(block.start);
+        let span = LineRange {
+            start: block.start + 1,
+            end: last + 1,
+        };
+        self.spans.resize(nodes.len(), span);
+    }
+
+    /// Fail once the parse runs past its deadline or the document has too many nodes
+    ///
+    /// Nodes are counted as top-level blocks complete, containers with all their
+    /// content. `nodes` are the nodes parsed before line `line_idx`.
+    fn check_limits(&mut self, nodes: &[Node], line_idx: usize) -> Result<(), ParseError> {
+        let span = Some(Span {
+            line: line_idx + 1,
+            column: None,
+        });
+        if let (Some(deadline), Some(timeout_ms)) = (self.deadline, self.config.limits.timeout_ms) {
+            if Instant::now() >= deadline {
+                return Err(ParseError::LimitExceeded {
+                    kind: LimitKind::Timeout,
+                    limit: usize::try_from(timeout_ms).unwrap_or(usize::MAX),
+                    span,
+                });
+            }
+        }
+
+        let Some(max_nodes) = self.config.limits.max_nodes else {
+            return Ok(());
+        };
+        if self.depth > 0 {
+            return Ok(());
+        }
+        let new = &nodes[self.counted_nodes..];
+        for_each_node(new, &mut |_| self.node_count += 1);
+        for_each_inline(new, &mut |_| self.node_count += 1);
+        self.counted_nodes = nodes.len();
+        if self.node_count > max_nodes {
+            return Err(ParseError::LimitExceeded {
+                kind: LimitKind::Nodes,
+                limit: max_nodes,
+                span,
+            });
+        }
+        Ok(())
+    }
+
+    /// Parse the body of a container block
+    ///
+    /// `line_offset` is the 0-based index of `lines[0]` in the enclosing lines; spans of
+    /// errors and warnings raised inside the body are shifted by it.
+    fn parse_nested(
+        &mut self,
+        lines: &[&str],
+        line_offset: usize,
+    ) -> Result<Vec<Node>, ParseError> {
+        let max_depth = self.config.limits.max_nesting_depth;
+        if self.depth >= max_depth {
+            return Err(ParseError::LimitExceeded {
+                kind: LimitKind::NestingDepth,
+                limit: max_depth,
+                span: None,
+            });
+        }
+
+        let first_warning = self.warnings.len();
+        let (line_idx, depth) = (self.line_idx, self.depth);
+        self.depth += 1;
+        let result = self.parse(lines);
+        (self.line_idx, self.depth) = (line_idx, depth);
+        for warning in &mut self.warnings[first_warning..] {
+            warning.span.line += line_offset;
+        }
+        let mut nodes = result.map_err(|error| error.shift_lines(line_offset))?;
+        for node in &mut nodes {
+            node.shift_lines(line_offset);
+        }
+        Ok(nodes)
+    }
+
+    /// Parse the body of the `:::` container opened at `start_idx`
+    ///
+    /// Returns the body nodes and the index after the closing fence. A container
+    /// that is never closed runs to the end of the input with a warning.
+    fn parse_fenced_body(
+        &mut self,
+        lines: &[&str],
+        start_idx: usize,
+    ) -> Result<(Vec<Node>, usize), ParseError> {
+        let (end, next_idx) = match containers::find_closing_fence(lines, start_idx) {
+            Some(end) => (end, end + 1),
+            None => {
+                self.push_warning(ParseWarning::new(
+                    WarningCode::UnclosedContainer,
+                    Span {
+                        line: start_idx + 1,
+                        column: None,
+                    },
+                    "unclosed `:::` container; closed at end of input",
+                ));
+                (lines.len(), lines.len())
+            }
+        };
+        let content = self.parse_nested(&lines[start_idx + 1..end], start_idx + 1)?;
+        Ok((content, next_idx))
+    }
+
+    /// Record a warning, promoting it to an error if its code is denied
+    fn push_warning(&mut self, mut warning: ParseWarning) {
+        if self.config.deny.contains(&warning.code) {
+            warning.severity = Severity::Error;
+        }
+        self.warnings.push(warning);
+    }
+
+    /// Warn about the first unmatched emphasis delimiter in a block's inline content
+    ///
+    /// `source` holds the block's lines, starting at 0-based line index `first_idx`.
+    fn check_unclosed_emphasis(&mut self, content: &[Inline], source: &[&str], first_idx: usize) {
+        let Some((fragment, offset, delimiter)) = inline::find_unclosed_delimiter(content) else {
+            return;
+        };
+
+        // Locate the delimiter and the word it is attached to in the source lines
+        let rest = &fragment[offset..];
+        let needle = rest.split_whitespace().next().unwrap_or(rest);
+        let span = source
+            .iter()
+            .enumerate()
+            .find_map(|(idx, line)| {
+                line.find(needle).map(|pos| Span {
+                    line: first_idx + idx + 1,
+                    column: Some(line[..pos].chars().count() + 1),
+                })
+            })
+            .unwrap_or(Span {
+                line: first_idx + 1,
+                column: None,
+            });
+
+        self.push_warning(ParseWarning::new(
+            WarningCode::UnclosedEmphasis,
+            span,
+            format!("unclosed emphasis delimiter `{}`", delimiter),
+        ));
+    }
+}
+
+/// Spans of `nodes` built by [`transform::nest_sections`] from nodes spanning `flat`
+///
+/// A section spans its heading and its last descendant.
+fn nested_spans(nodes: &[Node], flat: &mut std::slice::Iter<LineRange>) -> Vec<LineRange> {
+    let mut spans = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let Some(&span) = flat.next() else {
+            break;
+        };
+        let end = match node {
+            Node::Section { children, .. } => nested_spans(children, flat)
+                .last()
+                .map_or(span.end, |last| last.end),
+            _ => span.end,
+        };
+        spans.push(LineRange {
+            start: span.start,
+            end,
+        });
+    }
+    spans
+}
+
+/// `text` with `\r\n` and lone `\r` line endings replaced by `\n`
+fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Fail with `LimitExceeded` if `source` is larger than `limits.max_input_bytes`
+fn check_input_size(source: &str, config: &ParserConfig) -> Result<(), ParseError> {
+    match config.limits.max_input_bytes {
+        Some(limit) if source.len() > limit => Err(ParseError::LimitExceeded {
+            kind: LimitKind::InputSize,
+            limit,
+            span: None,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Fail with `LimitExceeded` if `depth` nesting levels exceed `limits.max_nesting_depth`
+///
+/// `line_idx` is the 0-based index of the line holding the nested construct.
+pub(super) fn check_nesting(
+    depth: usize,
+    config: &ParserConfig,
+    line_idx: usize,
+) -> Result<(), ParseError> {
+    let limit = config.limits.max_nesting_depth;
+    if depth <= limit {
+        return Ok(());
+    }
+    Err(ParseError::LimitExceeded {
+        kind: LimitKind::NestingDepth,
+        limit,
+        span: Some(Span {
+            line: line_idx + 1,
+            column: None,
+        }),
+    })
+}
+
+/// Return `ParseError::Cancelled` if the token has been cancelled
+///
+/// `line_idx` is the 0-based index of the line about to be parsed.
+pub(super) fn check_cancelled(
+    cancel: Option<&CancellationToken>,
+    line_idx: usize,
+) -> Result<(), ParseError> {
+    match cancel {
+        Some(token) if token.is_cancelled() => Err(ParseError::Cancelled {
+            span: Span {
+                line: line_idx + 1,
+                column: None,
+            },
+        }),
+        _ => Ok(()),
     }
 }
@@ -0,0 +1,271 @@
+//! Obsidian syntax (`enable_obsidian`): callouts, `![[embeds]]`, and `%%comments%%`.
+
+use crate::ast::{AdmonitionKind, Attributes, Inline};
+use crate::slug::slugify;
+
+/// File extensions embedded as images rather than linked
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "svg", "webp", "avif"];
+
+/// The marker line of a callout (`> [!tip]- Title`)
+pub(super) struct Callout<'a> {
+    pub(super) kind: AdmonitionKind,
+    /// Title after the marker
+    pub(super) title: Option<&'a str>,
+    /// Type as written
+    pub(super) name: &'a str,
+    /// Whether a `+` or `-` after the marker makes the callout foldable
+    pub(super) foldable: bool,
+}
+
+impl Callout<'_> {
+    /// Title to show when none is written: the type, capitalized, unless it is an
+    /// admonition kind with a title of its own (`> [!faq]` is titled `Faq`)
+    pub(super) fn default_title(&self) -> Option<String> {
+        if AdmonitionKind::from_name(self.name).is_some() {
+            return None;
+        }
+        let mut chars = self.name.chars();
+        let first = chars.next()?;
+        Some(first.to_uppercase().chain(chars).collect())
+    }
+}
+
+/// Check if a line opens a callout (`> [!type]`, optionally followed by `+` or `-` and
+/// a title)
+///
+/// Any type is accepted; types Obsidian does not know look like notes there too.
+pub(super) fn detect_callout(line: &str) -> Option<Callout<'_>> {
+    let rest = line.trim().strip_prefix('>')?.trim_start();
+    let rest = rest.strip_prefix("[!")?;
+    let close = rest.find(']')?;
+    let name = &rest[..close];
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+    let after = &rest[close + 1..];
+    let foldable = after.starts_with(['+', '-']);
+    let title = after[usize::from(foldable)..].trim();
+    Some(Callout {
+        kind: callout_kind(name),
+        title: (!title.is_empty()).then_some(title),
+        name,
+        foldable,
+    })
+}
+
+/// Admonition kind that looks closest to callout type `name`, including Obsidian's
+/// aliases (`hint` is a tip, `bug` a caution)
+fn callout_kind(name: &str) -> AdmonitionKind {
+    match name.to_lowercase().as_str() {
+        "tip" | "hint" | "success" | "check" | "done" => AdmonitionKind::Tip,
+        "important" => AdmonitionKind::Important,
+        "warning" | "attention" | "question" | "help" | "faq" => AdmonitionKind::Warning,
+        "caution" | "failure" | "fail" | "missing" | "danger" | "error" | "bug" => {
+            AdmonitionKind::Caution
+        }
+        _ => AdmonitionKind::Note,
+    }
+}
+
+/// Target of an embed (`![[path#heading|label]]`)
+#[derive(Debug, PartialEq)]
+pub(super) struct Embed<'a> {
+    pub(super) path: &'a str,
+    /// Heading (`#Heading`) or block reference (`#^id`) after the path
+    pub(super) fragment: Option<&'a str>,
+    /// Text after `|`: alt text or a `width` / `widthxheight` size for images
+    pub(super) label: Option<&'a str>,
+}
+
+impl<'a> Embed<'a> {
+    /// Split the text between `![[` and `]]`
+    pub(super) fn parse(target: &'a str) -> Option<Self> {
+        let (target, label) = match target.split_once('|') {
+            Some((target, label)) => (target, Some(label.trim())),
+            None => (target, None),
+        };
+        let (path, fragment) = match target.split_once('#') {
+            Some((path, fragment)) => (path.trim(), Some(fragment.trim())),
+            None => (target.trim(), None),
+        };
+        (!path.is_empty()).then_some(Embed {
+            path,
+            fragment,
+            label,
+        })
+    }
+
+    fn is_image(&self) -> bool {
+        self.path.rsplit_once('.').is_some_and(|(_, extension)| {
+            IMAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+        })
+    }
+
+    /// File of an embedded note, with `.md` added when the name has no extension
+    ///
+    /// `None` for images, other files, and block references (`#^id`), which are not
+    /// transcluded.
+    pub(super) fn note_file(&self) -> Option<String> {
+        if self.is_image() || self.fragment.is_some_and(|f| f.starts_with('^')) {
+            return None;
+        }
+        match self.path.rsplit_once('.') {
+            None => Some(format!("{}.md", self.path)),
+            Some((_, "md")) => Some(self.path.to_string()),
+            Some(_) => None,
+        }
+    }
+
+    /// The embed as inline content: an image, or a link to the note or file
+    pub(super) fn to_inline(&self) -> Inline {
+        let url = self.path.replace(' ', "%20");
+        if self.is_image() {
+            let mut attributes = Attributes::default();
+            let mut alt = String::new();
+            match self.label.map(parse_size) {
+                Some(Some((width, height))) => {
+                    attributes
+                        .pairs
+                        .insert("width".to_string(), width.to_string());
+                    if let Some(height) = height {
+                        attributes
+                            .pairs
+                            .insert("height".to_string(), height.to_string());
+                    }
+                }
+                Some(None) => alt = self.label.unwrap_or_default().to_string(),
+                None => {}
+            }
+            return Inline::Image {
+                alt,
+                url,
+                attributes,
+            };
+        }
+        let url = match (self.note_file(), self.fragment) {
+            (Some(file), Some(heading)) => {
+                format!("{}#{}", file.replace(' ', "%20"), slugify(heading))
+            }
+            (Some(file), None) => file.replace(' ', "%20"),
+            (None, _) => url,
+        };
+        let text = match (self.label, self.fragment) {
+            (Some(label), _) if !label.is_empty() => label.to_string(),
+            (_, Some(fragment)) => format!("{} > {}", self.path, fragment),
+            _ => self.path.to_string(),
+        };
+        Inline::Link {
+            text: vec![Inline::Text {
+                content: text.into(),
+            }],
+            url,
+        }
+    }
+}
+
+/// `300` or `300x200`
+fn parse_size(label: &str) -> Option<(u32, Option<u32>)> {
+    match label.split_once('x') {
+        Some((width, height)) => Some((width.parse().ok()?, Some(height.parse().ok()?))),
+        None => Some((label.parse().ok()?, None)),
+    }
+}
+
+/// Check if a line is nothing but an embed (`![[Note]]`) and return it
+pub(super) fn detect_embed_line(line: &str) -> Option<Embed<'_>> {
+    let target = line.trim().strip_prefix("![[")?.strip_suffix("]]")?;
+    if target.contains("]]") {
+        return None;
+    }
+    Embed::parse(target)
+}
+
+/// Lines of the section under `heading` in `text`: from the heading up to the next
+/// heading of the same or a higher level
+///
+/// Headings are compared ignoring case and surrounding spaces. Lines in fenced code
+/// blocks are never headings.
+pub(super) fn heading_section<'t>(text: &'t str, heading: &str) -> Option<Vec<&'t str>> {
+    let mut section: Option<(usize, Vec<&str>)> = None;
+    let mut fence: Option<&str> = None;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) {
+                fence = None;
+            }
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+        } else if let Some((level, text)) = atx_heading(trimmed) {
+            match &section {
+                Some((open, _)) if level <= *open => break,
+                None if text.eq_ignore_ascii_case(heading.trim()) => {
+                    section = Some((level, Vec::new()))
+                }
+                _ => {}
+            }
+        }
+        if let Some((_, lines)) = &mut section {
+            lines.push(line);
+        }
+    }
+    section.map(|(_, lines)| lines)
+}
+
+/// Level and text of an ATX heading line
+fn atx_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embed_targets() {
+        let embed = Embed::parse("My Note#Set up|see setup").unwrap();
+        assert_eq!(embed.note_file().as_deref(), Some("My Note.md"));
+        assert_eq!(
+            embed.to_inline(),
+            Inline::Link {
+                text: vec![Inline::Text {
+                    content: "see setup".into()
+                }],
+                url: "My%20Note.md#set-up".to_string(),
+            }
+        );
+        assert_eq!(Embed::parse("Note#^block").unwrap().note_file(), None);
+        assert_eq!(Embed::parse("scan.pdf").unwrap().note_file(), None);
+        assert_eq!(Embed::parse(" |x"), None);
+        assert_eq!(parse_size("300x200"), Some((300, Some(200))));
+        assert_eq!(parse_size("a logo"), None);
+    }
+
+    #[test]
+    fn test_heading_section_stops_at_same_level() {
+        let text = "# A\n\n## Setup\n\n```sh\n# not a heading\n```\n\n### Linux\n\n## Usage\n";
+        assert_eq!(
+            heading_section(text, "setup").unwrap(),
+            [
+                "## Setup",
+                "",
+                "```sh",
+                "# not a heading",
+                "```",
+                "",
+                "### Linux",
+                ""
+            ]
+        );
+        assert_eq!(heading_section(text, "Missing"), None);
+    }
+}
@@ -1,6 +1,6 @@
 //! Table parsing.
 
-use crate::ast::{Alignment, Inline, Node, ParseError, Span};
+use crate::ast::{Alignment, Inline, LineRange, Node, ParseError, ParseWarning, Span, WarningCode};
 
 use super::inline::parse_inline;
 use super::inline::RegexPatterns;
@@ -11,6 +11,11 @@ pub(super) fn detect_table_row(line: &str) -> bool {
     trimmed.starts_with('|') && trimmed[1..].contains('|')
 }
 
+/// Whether a table starts at `line`: a row followed by a separator on `next`
+pub(super) fn starts_table(line: &str, next: Option<&str>) -> bool {
+    detect_table_row(line) && next.is_some_and(detect_table_separator)
+}
+
 /// Check if a line is a table separator (matches pattern like |:---|, |---:|, |:---:|, or |---|)
 pub(super) fn detect_table_separator(line: &str) -> bool {
     let trimmed = line.trim();
@@ -87,6 +92,50 @@ pub(super) fn parse_table_separator(line: &str) -> Vec<Option<Alignment>> {
     alignments
 }
 
+/// Split a table row into raw cell texts on unescaped pipes
+///
+/// `\|` becomes a literal `|` inside the cell. The empty parts before a leading and
+/// after a trailing pipe are dropped.
+fn split_cells(line: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.trim().chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.peek() == Some(&'|') => {
+                chars.next();
+                current.push('|');
+            }
+            '|' => parts.push(std::mem::take(&mut current)),
+            _ => current.push(ch),
+        }
+    }
+    parts.push(current);
+
+    if parts.first().is_some_and(|part| part.trim().is_empty()) {
+        parts.remove(0);
+    }
+    if parts.last().is_some_and(|part| part.trim().is_empty()) {
+        parts.pop();
+    }
+    parts
+}
+
+/// Parse a cell's inline content, turning `<br>` (`<br/>`, `<br />`) into line breaks
+fn parse_cell(text: &str, regex_patterns: &RegexPatterns) -> Result<Vec<Inline>, ParseError> {
+    let mut inlines = Vec::new();
+    for (idx, segment) in regex_patterns.line_break.split(text).enumerate() {
+        if idx > 0 {
+            inlines.push(Inline::LineBreak);
+        }
+        let segment = segment.trim();
+        if !segment.is_empty() {
+            inlines.extend(parse_inline(segment, regex_patterns)?);
+        }
+    }
+    Ok(inlines)
+}
+
 /// Parse a table row into cells, parsing inline content for each cell
 ///
 /// # Errors
@@ -96,40 +145,80 @@ pub(super) fn parse_table_row(
     line: &str,
     regex_patterns: &RegexPatterns,
 ) -> Result<Vec<Vec<Inline>>, ParseError> {
-    let trimmed = line.trim();
-    let parts: Vec<&str> = trimmed.split('|').collect();
-    let mut cells = Vec::new();
+    split_cells(line)
+        .iter()
+        .map(|cell| parse_cell(cell, regex_patterns))
+        .collect()
+}
 
-    // When splitting by '|', if line starts with '|', first part is empty
-    // If line ends with '|', last part is empty
-    // We want to process all parts between the pipes
-    let start_idx = if !parts.is_empty() && parts[0].trim().is_empty() {
-        1
-    } else {
-        0
-    };
-    let end_idx = if !parts.is_empty() && parts[parts.len() - 1].trim().is_empty() {
-        parts.len() - 1
-    } else {
-        parts.len()
-    };
+/// Strip the trailing `\` that continues a multi-line table row onto the next line
+///
+/// The marker must follow the row's closing pipe (`| a | b | \`), so an escaped pipe
+/// at the end of a row is not mistaken for it.
+fn strip_row_continuation(line: &str) -> Option<&str> {
+    let row = line.trim_end().strip_suffix('\\')?.trim_end();
+    (row.ends_with('|') && !row.ends_with("\\|")).then_some(row)
+}
 
-    for part in &parts[start_idx..end_idx] {
-        let cell_content = part.trim();
-        let cell_inlines = if cell_content.is_empty() {
-            Vec::new()
-        } else {
-            parse_inline(cell_content, regex_patterns)?
-        };
-        cells.push(cell_inlines);
+/// Append each cell of `continuation` to the matching cell of `row` after a line break
+fn merge_row(row: &mut Vec<Vec<Inline>>, continuation: Vec<Vec<Inline>>) {
+    for (idx, cell) in continuation.into_iter().enumerate() {
+        match row.get_mut(idx) {
+            Some(existing) => {
+                if !cell.is_empty() {
+                    if !existing.is_empty() {
+                        existing.push(Inline::LineBreak);
+                    }
+                    existing.extend(cell);
+                }
+            }
+            None => row.push(cell),
+        }
     }
+}
 
-    Ok(cells)
+/// Caption text of a `Table: ...` line, if `line` is one
+fn detect_caption(line: &str) -> Option<&str> {
+    let caption = line.trim().strip_prefix("Table:")?.trim();
+    (!caption.is_empty()).then_some(caption)
+}
+
+/// Pad or truncate `row` to `columns` cells, warning with the row's line if it differs
+pub(super) fn normalize_row(
+    row: &mut Vec<Vec<Inline>>,
+    columns: usize,
+    line_idx: usize,
+    warnings: &mut Vec<ParseWarning>,
+) {
+    if row.len() == columns {
+        return;
+    }
+    let action = if row.len() < columns {
+        "padded with empty cells"
+    } else {
+        "extra cells dropped"
+    };
+    warnings.push(ParseWarning::new(
+        WarningCode::TableColumnMismatch,
+        Span {
+            line: line_idx + 1,
+            column: None,
+        },
+        format!(
+            "table row has {} cells, expected {}; {}",
+            row.len(),
+            columns,
+            action
+        ),
+    ));
+    row.resize_with(columns, Vec::new);
 }
 
 /// Parse a table starting at the given line index
 ///
-/// Returns the node and the new line index after the table.
+/// Returns the node, the new line index after the table (and its caption line, if
+/// any), and a warning for each row whose cell count differs from the header's. Such rows are padded with empty cells
+/// or truncated so every row has one cell per header.
 /// A table must have:
 /// 1. A header row (starts with |)
 /// 2. A separator row (matches separator pattern)
@@ -143,7 +232,7 @@ pub(super) fn parse_table(
     start_idx: usize,
     config: &crate::config::ParserConfig,
     regex_patterns: &RegexPatterns,
-) -> Result<(Node, usize), ParseError> {
+) -> Result<(Node, usize, Vec<ParseWarning>), ParseError> {
     let mut i = start_idx;
 
     // Parse header row
@@ -176,7 +265,10 @@ pub(super) fn parse_table(
     i += 1;
 
     // Parse data rows until a non-table line is encountered
-    let mut rows = Vec::new();
+    let mut rows: Vec<Vec<Vec<Inline>>> = Vec::new();
+    let mut warnings = Vec::new();
+    // Whether the previous data row ended with a continuation marker
+    let mut continues_previous = false;
     while i < lines.len() {
         let line = lines[i].trim();
 
@@ -184,21 +276,32 @@ pub(super) fn parse_table(
         if line.is_empty() {
             break;
         }
-        if line.starts_with('#') || line.starts_with(&config.code_fence_pattern) {
+        if super::blocks::is_atx_heading(line, config) || super::blocks::is_code_fence(line, config)
+        {
             break;
         }
 
         // Stop at list lines
-        if super::lists::detect_list_line(lines[i]).is_some()
-            || super::lists::detect_ordered_list_line(lines[i]).is_some()
+        if super::lists::detect_list_line(lines[i], config).is_some()
+            || super::lists::detect_ordered_list_line(lines[i], config).is_some()
         {
             break;
         }
 
         // Check if it's a table row
         if detect_table_row(lines[i]) {
-            let row = parse_table_row(lines[i], regex_patterns)?;
-            rows.push(row);
+            // With multi-line rows, `| a | b | \` continues the row on the next line
+            let continued = config
+                .enable_multiline_tables
+                .then(|| strip_row_continuation(lines[i]))
+                .flatten();
+            let mut row = parse_table_row(continued.unwrap_or(lines[i]), regex_patterns)?;
+            normalize_row(&mut row, headers.len(), i, &mut warnings);
+            match rows.last_mut() {
+                Some(previous) if continues_previous => merge_row(previous, row),
+                _ => rows.push(row),
+            }
+            continues_previous = continued.is_some();
             i += 1;
         } else {
             // Not a table row, end of table
@@ -206,12 +309,35 @@ pub(super) fn parse_table(
         }
     }
 
+    let span = LineRange {
+        start: start_idx + 1,
+        end: i,
+    };
+
+    // A `Table: ...` caption may follow directly or after one blank line
+    let caption_idx = if lines.get(i).is_some_and(|line| line.trim().is_empty()) {
+        i + 1
+    } else {
+        i
+    };
+    let caption = match lines.get(caption_idx).and_then(|line| detect_caption(line)) {
+        Some(text) => {
+            i = caption_idx + 1;
+            Some(parse_inline(text, regex_patterns)?)
+        }
+        None => None,
+    };
+
     Ok((
         Node::Table {
             headers,
             rows,
             alignments,
+            caption,
+            number: None,
+            span: Some(span),
         },
         i,
+        warnings,
     ))
 }
@@ -0,0 +1,69 @@
+//! `{{name}}` variable substitution.
+
+use std::collections::BTreeMap;
+
+use crate::ast::{ParseWarning, Span, WarningCode};
+
+/// Replace `{{name}}` placeholders with values from `variables`
+///
+/// Runs on the raw text before block parsing, so placeholders work everywhere,
+/// including code blocks and link URLs. `\{{name}}` produces a literal `{{name}}`.
+/// Placeholders naming an unknown variable are left as written, with a warning.
+pub(super) fn substitute(
+    input: &str,
+    variables: &BTreeMap<String, String>,
+    warnings: &mut Vec<ParseWarning>,
+) -> String {
+    let mut output = String::with_capacity(input.len());
+    for (idx, line) in input.split_inclusive('\n').enumerate() {
+        let mut rest = line;
+        while let Some(start) = rest.find("{{") {
+            let Some((name, len)) = placeholder(&rest[start..]) else {
+                output.push_str(&rest[..start + 2]);
+                rest = &rest[start + 2..];
+                continue;
+            };
+
+            if rest[..start].ends_with('\\') {
+                output.push_str(&rest[..start - 1]);
+                output.push_str(&rest[start..start + len]);
+            } else {
+                output.push_str(&rest[..start]);
+                match variables.get(name) {
+                    Some(value) => output.push_str(value),
+                    None => {
+                        warnings.push(ParseWarning::new(
+                            WarningCode::UndefinedVariable,
+                            Span {
+                                line: idx + 1,
+                                column: Some(
+                                    line[..line.len() - rest.len() + start].chars().count() + 1,
+                                ),
+                            },
+                            format!("undefined variable `{}`", name),
+                        ));
+                        output.push_str(&rest[start..start + len]);
+                    }
+                }
+            }
+            rest = &rest[start + len..];
+        }
+        output.push_str(rest);
+    }
+    output
+}
+
+/// Variable name and length of a `{{ name }}` placeholder at the start of `text`
+///
+/// Names start with a letter or `_` and continue with letters, digits, `_`, `-`, or
+/// `.`, so references such as `{{include: file.md}}` are not placeholders.
+fn placeholder(text: &str) -> Option<(&str, usize)> {
+    let end = text.find("}}")?;
+    let name = text[2..end].trim();
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    valid.then_some((name, end + 2))
+}
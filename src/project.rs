@@ -0,0 +1,438 @@
+//! Multi-file projects with cross-document link validation.
+
+use crate::ast::{collect_link_urls, plain_text, Node, ParseError, ParseWarning};
+use crate::config::ParserConfig;
+use crate::parser::Parser;
+use crate::renderer::is_absolute_url;
+use crate::slug::{slugify, SlugGenerator};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Errors that can occur while loading a project
+#[derive(Debug)]
+pub enum ProjectError {
+    /// A file or directory could not be read
+    Io { path: PathBuf, message: String },
+    /// A document failed to parse
+    Parse { path: PathBuf, error: ParseError },
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::Io { path, message } => {
+                write!(f, "{}: {}", path.display(), message)
+            }
+            ProjectError::Parse { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl Error for ProjectError {}
+
+/// A parsed document belonging to a project
+#[derive(Debug, Clone)]
+pub struct ProjectDocument {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    /// Parsed AST
+    pub nodes: Vec<Node>,
+    /// Warnings collected while parsing
+    pub warnings: Vec<ParseWarning>,
+    /// Heading anchors defined by the document, in document order
+    pub anchors: Vec<String>,
+}
+
+impl ProjectDocument {
+    /// A document at `path` (relative to the project root), with the anchors of its
+    /// headings
+    pub fn new(path: PathBuf, nodes: Vec<Node>, warnings: Vec<ParseWarning>) -> Self {
+        Self {
+            anchors: heading_anchors(&nodes),
+            path,
+            nodes,
+            warnings,
+        }
+    }
+
+    /// Read and parse the document at `path`, which lies below `root`
+    fn load(root: &Path, path: &Path, config: &ParserConfig) -> Result<Self, ProjectError> {
+        let markdown = fs::read_to_string(path).map_err(|e| ProjectError::Io {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+        let parse_error = |error| ProjectError::Parse {
+            path: relative.clone(),
+            error,
+        };
+        let mut parser =
+            Parser::with_config(markdown, config.for_document(path)).map_err(parse_error)?;
+        let nodes = parser.parse().map_err(parse_error)?;
+        Ok(Self::new(relative, nodes, parser.warnings().to_vec()))
+    }
+}
+
+/// Why a cross-reference could not be resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrokenLinkReason {
+    /// The link points to a Markdown file that is not part of the project
+    MissingDocument,
+    /// The target document exists but does not define the anchor
+    MissingAnchor,
+    /// The link points to a non-Markdown file that does not exist on disk
+    MissingFile,
+}
+
+/// A relative link that does not resolve within the project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// Document containing the link (relative to the project root)
+    pub source: PathBuf,
+    /// The link URL as written
+    pub url: String,
+    /// Why the link is broken
+    pub reason: BrokenLinkReason,
+}
+
+/// How the heading anchors of a project's documents stay distinct when the documents
+/// are published together, such as on one page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlugStrategy {
+    /// Each anchor is prefixed with a slug of its document's path, so `#install` in
+    /// `guide/setup.md` becomes `guide-setup--install`
+    #[default]
+    PerFilePrefix,
+    /// Anchors are kept as they are and must be unique across the project
+    Global,
+}
+
+impl SlugStrategy {
+    /// Project-wide anchor for `anchor` of the document at `path`
+    pub fn anchor(self, path: &Path, anchor: &str) -> String {
+        match self {
+            SlugStrategy::Global => anchor.to_string(),
+            SlugStrategy::PerFilePrefix => {
+                let stem = path.with_extension("");
+                let words: Vec<_> = stem
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect();
+                format!("{}--{}", slugify(&words.join(" ")), anchor)
+            }
+        }
+    }
+}
+
+/// A project-wide anchor that more than one heading would get
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorCollision {
+    /// The anchor, as given by the [`SlugStrategy`]
+    pub anchor: String,
+    /// Documents of the headings with the anchor each defines, in path order
+    pub documents: Vec<(PathBuf, String)>,
+}
+
+/// A directory of Markdown documents parsed together
+///
+/// Builds a global anchor index so relative links between documents
+/// (`../guide/setup.md#install`) can be resolved and validated.
+#[derive(Debug, Clone)]
+pub struct Project {
+    root: PathBuf,
+    documents: Vec<ProjectDocument>,
+    anchors: BTreeMap<PathBuf, Vec<String>>,
+}
+
+impl Project {
+    /// Parse every `.md` file below `root`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProjectError` if a directory or file cannot be read, or a document fails to parse
+    pub fn load(root: impl AsRef<Path>, config: &ParserConfig) -> Result<Self, ProjectError> {
+        let root = root.as_ref().to_path_buf();
+        let paths = collect_markdown_files(&root)?;
+
+        let documents = paths
+            .iter()
+            .map(|path| ProjectDocument::load(&root, path, config))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_documents(root, documents))
+    }
+
+    /// A project of `document` and the Markdown files below `root` that it links to
+    ///
+    /// Only the linked files are read, which is all [`broken_links`](Project::broken_links)
+    /// needs to check the links of a single document. Linked files that do not exist are
+    /// left out, so links to them are reported as missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProjectError` if a linked file cannot be read or fails to parse
+    pub fn with_linked_documents(
+        root: impl AsRef<Path>,
+        document: ProjectDocument,
+        config: &ParserConfig,
+    ) -> Result<Self, ProjectError> {
+        let root = root.as_ref();
+        let single = Self::from_documents(root, vec![document]);
+        let document = &single.documents[0];
+        let linked: BTreeSet<PathBuf> = collect_link_urls(&document.nodes)
+            .iter()
+            .filter_map(|url| single.resolve_link(&document.path, url))
+            .map(|(target, _)| target)
+            .filter(|target| {
+                *target != document.path
+                    && target.extension().is_some_and(|ext| ext == "md")
+                    && root.join(target).is_file()
+            })
+            .collect();
+
+        let mut documents = single.documents;
+        for target in linked {
+            documents.push(ProjectDocument::load(root, &root.join(target), config)?);
+        }
+        documents.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Self::from_documents(root, documents))
+    }
+
+    /// Build a project from already parsed documents (paths relative to `root`)
+    pub fn from_documents(root: impl AsRef<Path>, documents: Vec<ProjectDocument>) -> Self {
+        let anchors = documents
+            .iter()
+            .map(|doc| (doc.path.clone(), doc.anchors.clone()))
+            .collect();
+        Self {
+            root: root.as_ref().to_path_buf(),
+            documents,
+            anchors,
+        }
+    }
+
+    /// Project root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// All documents, sorted by path
+    pub fn documents(&self) -> &[ProjectDocument] {
+        &self.documents
+    }
+
+    /// Look up a document by its path relative to the project root
+    pub fn document(&self, path: impl AsRef<Path>) -> Option<&ProjectDocument> {
+        let path = path.as_ref();
+        self.documents.iter().find(|doc| doc.path == path)
+    }
+
+    /// Global anchor index: document path to the heading anchors it defines
+    pub fn anchor_index(&self) -> &BTreeMap<PathBuf, Vec<String>> {
+        &self.anchors
+    }
+
+    /// Resolve a link written in document `from` to a project path and optional anchor
+    ///
+    /// Returns `None` for absolute URLs (`https://…`, `mailto:…`). Fragment-only links
+    /// (`#install`) resolve to `from` itself. The target path is not checked for existence.
+    pub fn resolve_link(&self, from: &Path, url: &str) -> Option<(PathBuf, Option<String>)> {
+        if let Some(anchor) = url.strip_prefix('#') {
+            return Some((from.to_path_buf(), Some(anchor.to_string())));
+        }
+        if is_absolute_url(url) {
+            return None;
+        }
+
+        let (path_part, anchor) = match url.split_once('#') {
+            Some((path, anchor)) => (path, Some(anchor.to_string())),
+            None => (url, None),
+        };
+        let path_part = path_part.split('?').next().unwrap_or_default();
+        let base = if path_part.starts_with('/') {
+            PathBuf::new()
+        } else {
+            from.parent().map(Path::to_path_buf).unwrap_or_default()
+        };
+        let target = normalize_path(&base.join(path_part.trim_start_matches('/')));
+        Some((target, anchor))
+    }
+
+    /// Find every relative link that does not resolve to a document, anchor, or file
+    pub fn broken_links(&self) -> Vec<BrokenLink> {
+        let mut broken = Vec::new();
+        for doc in &self.documents {
+            for url in collect_link_urls(&doc.nodes) {
+                let Some((target, anchor)) = self.resolve_link(&doc.path, &url) else {
+                    continue;
+                };
+
+                let reason = if target.extension().is_some_and(|ext| ext == "md") {
+                    match self.anchors.get(&target) {
+                        None => Some(BrokenLinkReason::MissingDocument),
+                        Some(anchors) => match &anchor {
+                            Some(a) if !a.is_empty() && !anchors.contains(a) => {
+                                Some(BrokenLinkReason::MissingAnchor)
+                            }
+                            _ => None,
+                        },
+                    }
+                } else if !self.root.join(&target).exists() {
+                    Some(BrokenLinkReason::MissingFile)
+                } else {
+                    None
+                };
+
+                if let Some(reason) = reason {
+                    broken.push(BrokenLink {
+                        source: doc.path.clone(),
+                        url,
+                        reason,
+                    });
+                }
+            }
+        }
+        broken
+    }
+
+    /// Find the anchors of documents that other documents link to which collide once
+    /// made project-wide by `strategy`
+    ///
+    /// Only documents targeted by a link from another document are checked, since
+    /// cross-links are what a colliding anchor would send to the wrong heading.
+    pub fn anchor_collisions(&self, strategy: SlugStrategy) -> Vec<AnchorCollision> {
+        let mut targeted = BTreeSet::new();
+        for doc in &self.documents {
+            for url in collect_link_urls(&doc.nodes) {
+                if let Some((target, _)) = self.resolve_link(&doc.path, &url) {
+                    if target != doc.path && self.anchors.contains_key(&target) {
+                        targeted.insert(target);
+                    }
+                }
+            }
+        }
+
+        let mut qualified: BTreeMap<String, Vec<(PathBuf, String)>> = BTreeMap::new();
+        for path in targeted {
+            for anchor in &self.anchors[&path] {
+                qualified
+                    .entry(strategy.anchor(&path, anchor))
+                    .or_default()
+                    .push((path.clone(), anchor.clone()));
+            }
+        }
+        qualified
+            .into_iter()
+            .filter(|(_, documents)| documents.len() > 1)
+            .map(|(anchor, documents)| AnchorCollision { anchor, documents })
+            .collect()
+    }
+}
+
+/// Compute heading anchors for a document: explicit `{#id}` or a unique slug
+pub(crate) fn heading_anchors(nodes: &[Node]) -> Vec<String> {
+    heading_targets(nodes)
+        .into_iter()
+        .map(|heading| heading.anchor)
+        .collect()
+}
+
+/// A heading that links can point to
+pub(crate) struct HeadingTarget {
+    pub(crate) anchor: String,
+    /// Heading text without formatting
+    pub(crate) title: String,
+    /// Index of the top-level node holding the heading
+    pub(crate) top: usize,
+}
+
+/// Headings of a document with their anchors, in document order
+pub(crate) fn heading_targets(nodes: &[Node]) -> Vec<HeadingTarget> {
+    let mut headings = Vec::new();
+    let mut slugs = SlugGenerator::new();
+    for (top, node) in nodes.iter().enumerate() {
+        collect_headings(node, top, &mut slugs, &mut headings);
+    }
+    headings
+}
+
+fn collect_headings(
+    node: &Node,
+    top: usize,
+    slugs: &mut SlugGenerator,
+    headings: &mut Vec<HeadingTarget>,
+) {
+    let (content, attributes) = match node {
+        Node::Heading {
+            content,
+            attributes,
+            ..
+        } => (content, attributes),
+        Node::Section {
+            heading,
+            attributes,
+            ..
+        } => (heading, attributes),
+        _ => return,
+    };
+    let title = plain_text(content);
+    let anchor = match &attributes.id {
+        Some(id) => {
+            slugs.reserve(id);
+            id.clone()
+        }
+        None => slugs.generate(&title),
+    };
+    headings.push(HeadingTarget { anchor, title, top });
+    if let Node::Section { children, .. } = node {
+        for child in children {
+            collect_headings(child, top, slugs, headings);
+        }
+    }
+}
+
+/// Resolve `.` and `..` components without touching the filesystem
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Every `.md` file below `root`, sorted by path
+///
+/// # Errors
+///
+/// Returns `ProjectError::Io` if a directory cannot be read
+pub fn collect_markdown_files(root: &Path) -> Result<Vec<PathBuf>, ProjectError> {
+    let mut files = Vec::new();
+    walk_markdown_files(root, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn walk_markdown_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), ProjectError> {
+    let io_error = |e: std::io::Error| ProjectError::Io {
+        path: dir.to_path_buf(),
+        message: e.to_string(),
+    };
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        if path.is_dir() {
+            walk_markdown_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
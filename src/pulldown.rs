@@ -0,0 +1,751 @@
+//! Conversion between the AST and [`pulldown_cmark`] event streams (`pulldown` feature).
+//!
+//! Tooling written against pulldown-cmark (mdBook preprocessors, syntax highlighters,
+//! `pulldown_cmark::html::push_html`) can consume a parsed document through
+//! [`to_pulldown_events`], and events from any pulldown source can be turned back
+//! into nodes with [`from_pulldown_events`].
+
+use crate::ast::{
+    for_each_node, plain_text, AdmonitionKind, Alignment, Attributes, Inline, ListItem, Node,
+    ValidationStatus,
+};
+use crate::renderer::{escape_html, render_attributes, SPOILER_OPEN};
+use pulldown_cmark::{
+    Alignment as PulldownAlignment, BlockQuoteKind, CodeBlockKind, CowStr, Event, HeadingLevel,
+    LinkType, Tag, TagEnd,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::iter::Peekable;
+
+/// Convert nodes into the pulldown-cmark events of the same document
+///
+/// Text is borrowed from `nodes`. Constructs pulldown-cmark has no event for are
+/// mapped to the closest equivalent:
+///
+/// - Mermaid diagrams become ```` ```mermaid ```` code blocks, keeping `name=` in the info string
+/// - `{{diagram:name}}` references are replaced by the named diagram
+/// - A table caption becomes a paragraph after the table
+/// - A blockquote of level `n` becomes `n` nested block quotes around a paragraph
+/// - Admonitions become GitHub-style alert block quotes
+/// - Containers, details blocks, comments, and inline directives become raw HTML
+/// - Sections are flattened into their heading followed by their children
+pub fn to_pulldown_events(nodes: &[Node]) -> std::vec::IntoIter<Event<'_>> {
+    let mut diagrams = HashMap::new();
+    for_each_node(nodes, &mut |node| {
+        if let Node::MermaidDiagram {
+            name: Some(name), ..
+        } = node
+        {
+            diagrams.entry(name.as_str()).or_insert(node);
+        }
+    });
+
+    let mut writer = EventWriter {
+        events: Vec::new(),
+        diagrams,
+    };
+    writer.blocks(nodes);
+    writer.events.into_iter()
+}
+
+/// Build nodes from a pulldown-cmark event stream
+///
+/// The conversion is lossy where the AST is less expressive than CommonMark:
+/// soft breaks become spaces, items of nested lists become nested list items
+/// regardless of their list type, block quotes keep only their paragraphs (other
+/// blocks are moved out of the quote), and blocks inside list items keep only their
+/// text. ```` ```mermaid ```` code blocks become unvalidated Mermaid diagrams, and
+/// HTML comments become comment nodes; other raw HTML is kept as text.
+pub fn from_pulldown_events<'a>(events: impl IntoIterator<Item = Event<'a>>) -> Vec<Node> {
+    let mut reader = EventReader {
+        events: events.into_iter().peekable(),
+    };
+    reader.blocks(None)
+}
+
+struct EventWriter<'a> {
+    events: Vec<Event<'a>>,
+    /// Named Mermaid diagrams, for resolving `{{diagram:name}}`
+    diagrams: HashMap<&'a str, &'a Node>,
+}
+
+impl<'a> EventWriter<'a> {
+    fn blocks(&mut self, nodes: &'a [Node]) {
+        for node in nodes {
+            self.block(node);
+        }
+    }
+
+    fn block(&mut self, node: &'a Node) {
+        match node {
+            Node::Heading {
+                level,
+                content,
+                attributes,
+            } => self.heading(*level, content, attributes),
+            Node::Section {
+                level,
+                heading,
+                attributes,
+                children,
+            } => {
+                self.heading(*level, heading, attributes);
+                self.blocks(children);
+            }
+            Node::Paragraph { content } => self.paragraph(content),
+            Node::UnorderedList { items } => self.list(items, false),
+            Node::OrderedList { items } => self.list(items, true),
+            // Info strings cannot carry attributes
+            Node::CodeBlock { lang, code, .. } => {
+                self.code_block(CowStr::from(lang.as_deref().unwrap_or("")), code)
+            }
+            Node::MermaidDiagram { diagram, name, .. } => {
+                let info = match name {
+                    Some(name) => CowStr::from(format!("mermaid name={}", name)),
+                    None => CowStr::from("mermaid"),
+                };
+                self.code_block(info, diagram);
+            }
+            Node::DiagramRef { name, .. } => match self.diagrams.get(name.as_str()) {
+                Some(diagram) => self.block(diagram),
+                None => {
+                    self.events.push(Event::Start(Tag::Paragraph));
+                    self.events
+                        .push(Event::Text(format!("{{{{diagram:{}}}}}", name).into()));
+                    self.events.push(Event::End(TagEnd::Paragraph));
+                }
+            },
+            Node::Table {
+                headers,
+                rows,
+                alignments,
+                caption,
+                ..
+            } => {
+                let alignments = alignments
+                    .iter()
+                    .map(|alignment| match alignment {
+                        None => PulldownAlignment::None,
+                        Some(Alignment::Left) => PulldownAlignment::Left,
+                        Some(Alignment::Center) => PulldownAlignment::Center,
+                        Some(Alignment::Right) => PulldownAlignment::Right,
+                    })
+                    .collect();
+                self.events.push(Event::Start(Tag::Table(alignments)));
+                self.events.push(Event::Start(Tag::TableHead));
+                self.cells(headers);
+                self.events.push(Event::End(TagEnd::TableHead));
+                for row in rows {
+                    self.events.push(Event::Start(Tag::TableRow));
+                    self.cells(row);
+                    self.events.push(Event::End(TagEnd::TableRow));
+                }
+                self.events.push(Event::End(TagEnd::Table));
+                if let Some(caption) = caption {
+                    self.paragraph(caption);
+                }
+            }
+            Node::Blockquote {
+                level,
+                content,
+                blocks,
+            } => {
+                let level = usize::from((*level).max(1));
+                for _ in 0..level {
+                    self.events.push(Event::Start(Tag::BlockQuote(None)));
+                }
+                if !content.is_empty() || blocks.is_empty() {
+                    self.paragraph(content);
+                }
+                self.blocks(blocks);
+                for _ in 0..level {
+                    self.events.push(Event::End(TagEnd::BlockQuote(None)));
+                }
+            }
+            Node::HorizontalRule { .. } => self.events.push(Event::Rule),
+            // GitHub alerts have no title; a callout title is dropped
+            Node::Admonition { kind, content, .. } => {
+                let kind = Some(match kind {
+                    AdmonitionKind::Note => BlockQuoteKind::Note,
+                    AdmonitionKind::Tip => BlockQuoteKind::Tip,
+                    AdmonitionKind::Important => BlockQuoteKind::Important,
+                    AdmonitionKind::Warning => BlockQuoteKind::Warning,
+                    AdmonitionKind::Caution => BlockQuoteKind::Caution,
+                });
+                self.events.push(Event::Start(Tag::BlockQuote(kind)));
+                self.blocks(content);
+                self.events.push(Event::End(TagEnd::BlockQuote(kind)));
+            }
+            Node::Container {
+                attributes,
+                children,
+            } => {
+                self.html_block(format!("<div{}>\n", render_attributes(None, attributes)));
+                self.blocks(children);
+                self.html_block("</div>\n".to_string());
+            }
+            Node::Details { summary, content } => {
+                self.html_block(format!(
+                    "<details>\n<summary>{}</summary>\n",
+                    escape_html(&plain_text(summary))
+                ));
+                self.blocks(content);
+                self.html_block("</details>\n".to_string());
+            }
+            Node::Figure {
+                content, caption, ..
+            } => {
+                // CommonMark has no figures; the caption stays a `Figure:` paragraph
+                self.blocks(content);
+                self.events.push(Event::Start(Tag::Paragraph));
+                self.events.push(Event::Text("Figure: ".into()));
+                self.inlines(caption);
+                self.events.push(Event::End(TagEnd::Paragraph));
+            }
+            Node::Comment { content } => self.html_block(comment_html(content)),
+        }
+    }
+
+    fn heading(&mut self, level: u8, content: &'a [Inline], attributes: &'a Attributes) {
+        let level = HeadingLevel::try_from(usize::from(level)).unwrap_or(HeadingLevel::H6);
+        self.events.push(Event::Start(Tag::Heading {
+            level,
+            id: attributes.id.as_deref().map(CowStr::from),
+            classes: attributes
+                .classes
+                .iter()
+                .map(|class| CowStr::from(class.as_str()))
+                .collect(),
+            attrs: attributes
+                .pairs
+                .iter()
+                .map(|(key, value)| (CowStr::from(key.as_str()), Some(value.as_str().into())))
+                .collect(),
+        }));
+        self.inlines(content);
+        self.events.push(Event::End(TagEnd::Heading(level)));
+    }
+
+    fn paragraph(&mut self, content: &'a [Inline]) {
+        self.events.push(Event::Start(Tag::Paragraph));
+        self.inlines(content);
+        self.events.push(Event::End(TagEnd::Paragraph));
+    }
+
+    /// Emit a tight list; nested items always form an unordered list, as in HTML output
+    fn list(&mut self, items: &'a [ListItem], ordered: bool) {
+        let start = ordered.then_some(1);
+        self.events.push(Event::Start(Tag::List(start)));
+        for item in items {
+            self.events.push(Event::Start(Tag::Item));
+            if let Some(checked) = item.checked {
+                self.events.push(Event::TaskListMarker(checked));
+            }
+            self.inlines(&item.content);
+            self.blocks(&item.blocks);
+            if !item.children.is_empty() {
+                self.list(&item.children, false);
+            }
+            self.events.push(Event::End(TagEnd::Item));
+        }
+        self.events.push(Event::End(TagEnd::List(ordered)));
+    }
+
+    fn code_block(&mut self, info: CowStr<'a>, code: &'a str) {
+        self.events
+            .push(Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))));
+        // pulldown-cmark ends code block text with a newline
+        if !code.is_empty() {
+            self.events.push(Event::Text(format!("{}\n", code).into()));
+        }
+        self.events.push(Event::End(TagEnd::CodeBlock));
+    }
+
+    fn cells(&mut self, cells: &'a [Vec<Inline>]) {
+        for cell in cells {
+            self.events.push(Event::Start(Tag::TableCell));
+            self.inlines(cell);
+            self.events.push(Event::End(TagEnd::TableCell));
+        }
+    }
+
+    fn html_block(&mut self, html: String) {
+        self.events.push(Event::Start(Tag::HtmlBlock));
+        self.events.push(Event::Html(html.into()));
+        self.events.push(Event::End(TagEnd::HtmlBlock));
+    }
+
+    fn inlines(&mut self, inlines: &'a [Inline]) {
+        for inline in inlines {
+            self.inline(inline);
+        }
+    }
+
+    fn inline(&mut self, inline: &'a Inline) {
+        match inline {
+            Inline::Text { content } => self.events.push(Event::Text(content.as_str().into())),
+            Inline::Bold { content } => self.wrap(Tag::Strong, content),
+            Inline::Italic { content } => self.wrap(Tag::Emphasis, content),
+            Inline::Strikethrough { content } => self.wrap(Tag::Strikethrough, content),
+            Inline::Highlight { content } => self.wrap_html("<mark>", content, "</mark>"),
+            Inline::Spoiler { content } => self.wrap_html(SPOILER_OPEN, content, "</span>"),
+            Inline::Link { text, url } => self.wrap(
+                Tag::Link {
+                    link_type: LinkType::Inline,
+                    dest_url: url.as_str().into(),
+                    title: CowStr::from(""),
+                    id: CowStr::from(""),
+                },
+                text,
+            ),
+            // Image attributes have no pulldown equivalent
+            Inline::Image { alt, url, .. } => {
+                self.events.push(Event::Start(Tag::Image {
+                    link_type: LinkType::Inline,
+                    dest_url: url.as_str().into(),
+                    title: CowStr::from(""),
+                    id: CowStr::from(""),
+                }));
+                if !alt.is_empty() {
+                    self.events.push(Event::Text(alt.as_str().into()));
+                }
+                self.events.push(Event::End(TagEnd::Image));
+            }
+            Inline::Span {
+                content,
+                attributes,
+            } => {
+                let open = format!("<span{}>", render_attributes(None, attributes));
+                self.wrap_html(open, content, "</span>");
+            }
+            Inline::Code { content } => self.events.push(Event::Code(content.as_str().into())),
+            Inline::Hashtag { tag } => self.events.push(Event::Text(format!("#{}", tag).into())),
+            Inline::Mention { user } => self.events.push(Event::Text(format!("@{}", user).into())),
+            Inline::LineBreak => self.events.push(Event::HardBreak),
+            Inline::Comment { content } => self
+                .events
+                .push(Event::InlineHtml(comment_html(content).into())),
+            Inline::Directive {
+                name,
+                content,
+                attributes,
+            } => {
+                let open = format!("<span{}>", render_attributes(Some(name), attributes));
+                self.wrap_html(open, content, "</span>");
+            }
+        }
+    }
+
+    /// Content between raw HTML opening and closing tags
+    fn wrap_html(
+        &mut self,
+        open: impl Into<CowStr<'a>>,
+        content: &'a [Inline],
+        close: &'static str,
+    ) {
+        self.events.push(Event::InlineHtml(open.into()));
+        self.inlines(content);
+        self.events.push(Event::InlineHtml(CowStr::from(close)));
+    }
+
+    fn wrap(&mut self, tag: Tag<'a>, content: &'a [Inline]) {
+        let end = tag.to_end();
+        self.events.push(Event::Start(tag));
+        self.inlines(content);
+        self.events.push(Event::End(end));
+    }
+}
+
+fn comment_html(content: &str) -> String {
+    // `--` may not appear inside an HTML comment
+    format!("<!-- {} -->", content.replace("--", "- -"))
+}
+
+struct EventReader<'a, I: Iterator<Item = Event<'a>>> {
+    events: Peekable<I>,
+}
+
+impl<'a, I: Iterator<Item = Event<'a>>> EventReader<'a, I> {
+    /// Read blocks up to and including `until`, or to the end of the stream
+    fn blocks(&mut self, until: Option<TagEnd>) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        loop {
+            if self.events.peek().is_some_and(is_inline_event) {
+                let mut content = Vec::new();
+                while let Some(event) = self.events.next_if(is_inline_event) {
+                    self.inline(event, &mut content);
+                }
+                push_paragraph(&mut nodes, content);
+                continue;
+            }
+            let Some(event) = self.events.next() else {
+                break;
+            };
+            match event {
+                Event::End(end) if Some(end) == until => break,
+                Event::Start(tag) => self.block(tag, &mut nodes),
+                Event::Rule => nodes.push(Node::HorizontalRule { span: None }),
+                // Stray ends and markers outside list items
+                _ => {}
+            }
+        }
+        nodes
+    }
+
+    fn block(&mut self, tag: Tag<'a>, nodes: &mut Vec<Node>) {
+        match tag {
+            Tag::Paragraph => {
+                let content = self.inlines(TagEnd::Paragraph);
+                push_paragraph(nodes, content);
+            }
+            Tag::Heading {
+                level,
+                id,
+                classes,
+                attrs,
+            } => nodes.push(Node::Heading {
+                level: level as u8,
+                content: self.inlines(TagEnd::Heading(level)),
+                attributes: Attributes {
+                    id: id.map(|id| id.to_string()),
+                    classes: classes.iter().map(|class| class.to_string()).collect(),
+                    pairs: attrs
+                        .into_iter()
+                        .map(|(key, value)| {
+                            (
+                                key.to_string(),
+                                value.map(|v| v.to_string()).unwrap_or_default(),
+                            )
+                        })
+                        .collect::<BTreeMap<_, _>>(),
+                },
+            }),
+            Tag::BlockQuote(Some(kind)) => nodes.push(Node::Admonition {
+                kind: match kind {
+                    BlockQuoteKind::Note => AdmonitionKind::Note,
+                    BlockQuoteKind::Tip => AdmonitionKind::Tip,
+                    BlockQuoteKind::Important => AdmonitionKind::Important,
+                    BlockQuoteKind::Warning => AdmonitionKind::Warning,
+                    BlockQuoteKind::Caution => AdmonitionKind::Caution,
+                },
+                title: None,
+                content: self.blocks(Some(TagEnd::BlockQuote(Some(kind)))),
+            }),
+            Tag::BlockQuote(None) => {
+                let first = nodes.len();
+                for node in self.blocks(Some(TagEnd::BlockQuote(None))) {
+                    let node = match node {
+                        Node::Paragraph { content } => Node::Blockquote {
+                            level: 1,
+                            content,
+                            blocks: Vec::new(),
+                        },
+                        Node::Blockquote {
+                            level,
+                            content,
+                            blocks,
+                        } => Node::Blockquote {
+                            level: level.saturating_add(1),
+                            content,
+                            blocks,
+                        },
+                        // Code blocks join the quote before them, or start one
+                        code @ (Node::CodeBlock { .. } | Node::MermaidDiagram { .. }) => {
+                            match nodes[first..].last_mut() {
+                                Some(Node::Blockquote {
+                                    level: 1, blocks, ..
+                                }) => {
+                                    blocks.push(code);
+                                    continue;
+                                }
+                                _ => Node::Blockquote {
+                                    level: 1,
+                                    content: Vec::new(),
+                                    blocks: vec![code],
+                                },
+                            }
+                        }
+                        other => other,
+                    };
+                    nodes.push(node);
+                }
+            }
+            Tag::CodeBlock(kind) => {
+                let mut code = self.text(TagEnd::CodeBlock);
+                if code.ends_with('\n') {
+                    code.pop();
+                }
+                let info = match kind {
+                    CodeBlockKind::Fenced(info) => info.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                let mut words = info.split_whitespace();
+                match words.next() {
+                    Some("mermaid") => nodes.push(Node::MermaidDiagram {
+                        diagram: code,
+                        name: words
+                            .find_map(|word| word.strip_prefix("name="))
+                            .map(str::to_string),
+                        config: None,
+                        validation_status: ValidationStatus::NotValidated,
+                        warnings: Vec::new(),
+                    }),
+                    lang => nodes.push(Node::CodeBlock {
+                        lang: lang.map(str::to_string),
+                        code,
+                        attributes: Attributes::default(),
+                    }),
+                }
+            }
+            Tag::HtmlBlock => {
+                let html = self.text(TagEnd::HtmlBlock);
+                let html = html.trim();
+                match parse_comment(html) {
+                    Some(content) => nodes.push(Node::Comment { content }),
+                    None => push_paragraph(
+                        nodes,
+                        vec![Inline::Text {
+                            content: html.into(),
+                        }],
+                    ),
+                }
+            }
+            Tag::List(start) => {
+                let items = self.list_items(TagEnd::List(start.is_some()));
+                nodes.push(match start {
+                    Some(_) => Node::OrderedList { items },
+                    None => Node::UnorderedList { items },
+                });
+            }
+            Tag::Table(alignments) => nodes.push(self.table(alignments)),
+            // Frontmatter is not part of the AST
+            Tag::MetadataBlock(kind) => {
+                self.text(TagEnd::MetadataBlock(kind));
+            }
+            // Footnote definitions, definition lists, and anything newer keep their content
+            other => {
+                let inner = self.blocks(Some(other.to_end()));
+                nodes.extend(inner);
+            }
+        }
+    }
+
+    fn list_items(&mut self, end: TagEnd) -> Vec<ListItem> {
+        let mut items = Vec::new();
+        while let Some(event) = self.events.next() {
+            match event {
+                Event::End(e) if e == end => break,
+                Event::Start(Tag::Item) => items.push(self.list_item()),
+                _ => {}
+            }
+        }
+        items
+    }
+
+    fn list_item(&mut self) -> ListItem {
+        let mut item = ListItem {
+            content: Vec::new(),
+            children: Vec::new(),
+            blocks: Vec::new(),
+            checked: None,
+        };
+        while let Some(event) = self.events.next() {
+            match event {
+                Event::End(TagEnd::Item) => break,
+                Event::TaskListMarker(checked) => item.checked = Some(checked),
+                Event::Start(Tag::List(start)) => {
+                    let children = self.list_items(TagEnd::List(start.is_some()));
+                    item.children.extend(children);
+                }
+                Event::Start(tag @ Tag::CodeBlock(_)) => self.block(tag, &mut item.blocks),
+                // Paragraphs of loose lists, and any other block, contribute their text
+                Event::Start(tag) if !is_inline_tag(&tag) => {
+                    if !item.content.is_empty() {
+                        push_text(&mut item.content, " ");
+                    }
+                    let content = self.inlines(tag.to_end());
+                    for inline in content {
+                        push_inline(&mut item.content, inline);
+                    }
+                }
+                event => self.inline(event, &mut item.content),
+            }
+        }
+        item
+    }
+
+    fn table(&mut self, alignments: Vec<PulldownAlignment>) -> Node {
+        let mut headers = Vec::new();
+        let mut rows = Vec::new();
+        while let Some(event) = self.events.next() {
+            match event {
+                Event::End(TagEnd::Table) => break,
+                Event::Start(Tag::TableHead) => headers = self.cells(TagEnd::TableHead),
+                Event::Start(Tag::TableRow) => rows.push(self.cells(TagEnd::TableRow)),
+                _ => {}
+            }
+        }
+        Node::Table {
+            headers,
+            rows,
+            alignments: alignments
+                .into_iter()
+                .map(|alignment| match alignment {
+                    PulldownAlignment::None => None,
+                    PulldownAlignment::Left => Some(Alignment::Left),
+                    PulldownAlignment::Center => Some(Alignment::Center),
+                    PulldownAlignment::Right => Some(Alignment::Right),
+                })
+                .collect(),
+            caption: None,
+            number: None,
+            span: None,
+        }
+    }
+
+    fn cells(&mut self, end: TagEnd) -> Vec<Vec<Inline>> {
+        let mut cells = Vec::new();
+        while let Some(event) = self.events.next() {
+            match event {
+                Event::End(e) if e == end => break,
+                Event::Start(Tag::TableCell) => cells.push(self.inlines(TagEnd::TableCell)),
+                _ => {}
+            }
+        }
+        cells
+    }
+
+    /// Read inline content up to and including `end`
+    fn inlines(&mut self, end: TagEnd) -> Vec<Inline> {
+        let mut content = Vec::new();
+        while let Some(event) = self.events.next() {
+            match event {
+                Event::End(e) if e == end => break,
+                event => self.inline(event, &mut content),
+            }
+        }
+        content
+    }
+
+    fn inline(&mut self, event: Event<'a>, out: &mut Vec<Inline>) {
+        match event {
+            Event::Text(text) => push_text(out, &text),
+            Event::Code(code) | Event::InlineMath(code) | Event::DisplayMath(code) => {
+                out.push(Inline::Code {
+                    content: code.as_ref().into(),
+                })
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                let trimmed = html.trim();
+                if let Some(content) = parse_comment(trimmed) {
+                    out.push(Inline::Comment { content });
+                } else if matches!(trimmed, "<br>" | "<br/>" | "<br />") {
+                    out.push(Inline::LineBreak);
+                } else {
+                    push_text(out, &html);
+                }
+            }
+            Event::FootnoteReference(label) => push_text(out, &format!("[^{}]", label)),
+            // The parser joins the lines of a paragraph with spaces
+            Event::SoftBreak => push_text(out, " "),
+            Event::HardBreak => out.push(Inline::LineBreak),
+            Event::Start(Tag::Strong) => out.push(Inline::Bold {
+                content: self.inlines(TagEnd::Strong),
+            }),
+            Event::Start(Tag::Emphasis) => out.push(Inline::Italic {
+                content: self.inlines(TagEnd::Emphasis),
+            }),
+            Event::Start(Tag::Strikethrough) => out.push(Inline::Strikethrough {
+                content: self.inlines(TagEnd::Strikethrough),
+            }),
+            Event::Start(Tag::Link { dest_url, .. }) => out.push(Inline::Link {
+                text: self.inlines(TagEnd::Link),
+                url: dest_url.to_string(),
+            }),
+            Event::Start(Tag::Image { dest_url, .. }) => out.push(Inline::Image {
+                alt: plain_text(&self.inlines(TagEnd::Image)),
+                url: dest_url.to_string(),
+                attributes: Attributes::default(),
+            }),
+            // Superscript, subscript, and blocks nested in inline content are flattened
+            Event::Start(tag) => {
+                for inline in self.inlines(tag.to_end()) {
+                    push_inline(out, inline);
+                }
+            }
+            Event::End(_) | Event::Rule | Event::TaskListMarker(_) => {}
+        }
+    }
+
+    /// Concatenate the text of events up to and including `end`
+    fn text(&mut self, end: TagEnd) -> String {
+        let mut text = String::new();
+        for event in self.events.by_ref() {
+            match event {
+                Event::End(e) if e == end => break,
+                Event::Text(t) | Event::Html(t) | Event::InlineHtml(t) => text.push_str(&t),
+                _ => {}
+            }
+        }
+        text
+    }
+}
+
+fn is_inline_tag(tag: &Tag<'_>) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Link { .. }
+            | Tag::Image { .. }
+    )
+}
+
+/// Inline content appearing directly in a block context, as in tight list items
+fn is_inline_event(event: &Event<'_>) -> bool {
+    match event {
+        Event::Start(tag) => is_inline_tag(tag),
+        Event::Text(_)
+        | Event::Code(_)
+        | Event::InlineMath(_)
+        | Event::DisplayMath(_)
+        | Event::InlineHtml(_)
+        | Event::FootnoteReference(_)
+        | Event::SoftBreak
+        | Event::HardBreak => true,
+        _ => false,
+    }
+}
+
+/// Text between `<!--` and `-->`, trimmed, if `html` is a single comment
+fn parse_comment(html: &str) -> Option<String> {
+    let inner = html.strip_prefix("<!--")?.strip_suffix("-->")?;
+    (!inner.contains("-->")).then(|| inner.trim().to_string())
+}
+
+fn push_paragraph(nodes: &mut Vec<Node>, content: Vec<Inline>) {
+    if !content.is_empty() {
+        nodes.push(Node::Paragraph { content });
+    }
+}
+
+fn push_text(out: &mut Vec<Inline>, text: &str) {
+    if let Some(Inline::Text { content }) = out.last_mut() {
+        content.push_str(text);
+    } else {
+        out.push(Inline::Text {
+            content: text.into(),
+        });
+    }
+}
+
+/// Push an inline, merging text into a preceding text node
+fn push_inline(out: &mut Vec<Inline>, inline: Inline) {
+    match inline {
+        Inline::Text { content } => push_text(out, &content),
+        inline => out.push(inline),
+    }
+}
@@ -0,0 +1,298 @@
+//! AsciiDoc export.
+
+use crate::ast::{
+    figure_image, Alignment, Attributes, Inline, ListItem, Node, ValidationStatus,
+    HIGHLIGHT_ATTRIBUTE, LINE_NUMBERS_CLASS,
+};
+use crate::transform;
+
+/// Escape characters that AsciiDoc treats as inline markup
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '*' | '_' | '`' | '#' | '~' | '^' | '+') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+fn render_inlines(inlines: &[Inline]) -> String {
+    inlines.iter().map(render_inline).collect()
+}
+
+fn render_inline(inline: &Inline) -> String {
+    match inline {
+        Inline::Text { content } => escape_text(content),
+        Inline::Bold { content } => format!("*{}*", render_inlines(content)),
+        Inline::Italic { content } => format!("_{}_", render_inlines(content)),
+        Inline::Strikethrough { content } => {
+            format!("[.line-through]#{}#", render_inlines(content))
+        }
+        Inline::Highlight { content } => format!("#{}#", render_inlines(content)),
+        Inline::Spoiler { content } => format!("[.spoiler]#{}#", render_inlines(content)),
+        Inline::Code { content } => format!("`+{}+`", content),
+        Inline::Link { text, url } => format!("link:{}[{}]", url, render_inlines(text)),
+        Inline::Image { alt, url, .. } => format!("image:{}[{}]", url, alt.replace(']', "\\]")),
+        Inline::Span {
+            content,
+            attributes,
+        } => {
+            let anchor = block_anchor(attributes);
+            // An inline anchor is the block anchor without its trailing newline
+            format!("{}#{}#", anchor.trim_end(), render_inlines(content))
+        }
+        Inline::Hashtag { tag } => escape_text(&format!("#{}", tag)),
+        Inline::Mention { user } => escape_text(&format!("@{}", user)),
+        Inline::LineBreak => " +\n".to_string(),
+        // AsciiDoc has no inline comments
+        Inline::Comment { .. } => String::new(),
+        Inline::Directive { name, content, .. } => {
+            format!("[.{}]#{}#", name, render_inlines(content))
+        }
+    }
+}
+
+/// Block attribute line (`[#id.role]`) for a container or code block, empty without id or
+/// classes
+fn block_anchor(attributes: &Attributes) -> String {
+    let mut out = String::new();
+    if let Some(id) = &attributes.id {
+        out.push('#');
+        out.push_str(id);
+    }
+    for class in &attributes.classes {
+        out.push('.');
+        out.push_str(class);
+    }
+    if out.is_empty() {
+        out
+    } else {
+        format!("[{}]\n", out)
+    }
+}
+
+fn render_list_items(items: &[ListItem], marker: char, depth: usize, out: &mut String) {
+    for item in items {
+        out.push_str(&marker.to_string().repeat(depth));
+        out.push(' ');
+        match item.checked {
+            Some(true) => out.push_str("[x] "),
+            Some(false) => out.push_str("[ ] "),
+            None => {}
+        }
+        out.push_str(&render_inlines(&item.content));
+        out.push('\n');
+        // A `+` line attaches each block to the item
+        for block in &item.blocks {
+            out.push_str("+\n");
+            out.push_str(&render_node(block));
+        }
+        render_list_items(&item.children, marker, depth + 1, out);
+    }
+}
+
+fn render_node(node: &Node) -> String {
+    match node {
+        Node::Heading { level, content, .. } => render_heading(*level, content),
+        Node::Paragraph { content } => format!("{}\n", render_inlines(content)),
+        Node::UnorderedList { items } => {
+            let mut out = String::new();
+            render_list_items(items, '*', 1, &mut out);
+            out
+        }
+        Node::OrderedList { items } => {
+            let mut out = String::new();
+            render_list_items(items, '.', 1, &mut out);
+            out
+        }
+        Node::CodeBlock {
+            lang,
+            code,
+            attributes,
+        } => {
+            let mut options = Vec::new();
+            if attributes
+                .classes
+                .iter()
+                .any(|class| class == LINE_NUMBERS_CLASS)
+            {
+                options.push("linenums".to_string());
+                if let Some(start) = attributes.get("start") {
+                    options.push(format!("start={}", start));
+                }
+            }
+            if let Some(lines) = attributes.get(HIGHLIGHT_ATTRIBUTE) {
+                options.push(format!("highlight={}", lines.replace(',', ";")));
+            }
+            // The language is positional, so numbered or highlighted blocks without one get `text`
+            let header = match (lang, options.is_empty()) {
+                (None, true) => String::new(),
+                (lang, _) => {
+                    options.insert(0, lang.as_deref().unwrap_or("text").to_string());
+                    format!("[source,{}]\n", options.join(","))
+                }
+            };
+            format!(
+                "{}{}----\n{}\n----\n",
+                block_anchor(attributes),
+                header,
+                code
+            )
+        }
+        Node::MermaidDiagram {
+            diagram,
+            validation_status,
+            ..
+        } => {
+            // asciidoctor-diagram block; invalid diagrams are kept as literal text
+            let style = match validation_status {
+                ValidationStatus::Invalid { .. } => "[literal]",
+                _ => "[mermaid]",
+            };
+            format!("{}\n....\n{}\n....\n", style, diagram)
+        }
+        // Replaced by `resolve_diagram_references` in `render_asciidoc`
+        Node::DiagramRef { .. } => String::new(),
+        Node::Table {
+            headers,
+            rows,
+            alignments,
+            caption,
+            ..
+        } => {
+            let title = caption
+                .as_ref()
+                .map(|caption| format!(".{}\n", render_inlines(caption)))
+                .unwrap_or_default();
+            let cols: Vec<&str> = (0..headers.len())
+                .map(|i| match alignments.get(i).copied().flatten() {
+                    Some(Alignment::Center) => "^",
+                    Some(Alignment::Right) => ">",
+                    _ => "<",
+                })
+                .collect();
+            let mut out = format!("{}[%header,cols=\"{}\"]\n|===\n", title, cols.join(","));
+            let render_row = |cells: &[Vec<Inline>]| -> String {
+                cells
+                    .iter()
+                    .map(|cell| format!("|{}", render_inlines(cell).replace('|', "\\|")))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            out.push_str(&render_row(headers));
+            out.push_str("\n\n");
+            for row in rows {
+                out.push_str(&render_row(row));
+                out.push('\n');
+            }
+            out.push_str("|===\n");
+            out
+        }
+        Node::Blockquote {
+            level,
+            content,
+            blocks,
+        } => {
+            // Nested quote blocks need longer delimiters than their parents
+            let mut out = String::new();
+            for depth in 0..usize::from(*level) {
+                out.push_str(&"_".repeat(4 + depth));
+                out.push('\n');
+            }
+            out.push_str(&render_inlines(content));
+            out.push('\n');
+            for block in blocks {
+                out.push('\n');
+                out.push_str(&render_node(block));
+            }
+            for depth in (0..usize::from(*level)).rev() {
+                out.push_str(&"_".repeat(4 + depth));
+                out.push('\n');
+            }
+            out
+        }
+        Node::HorizontalRule { .. } => "'''\n".to_string(),
+        Node::Admonition {
+            kind,
+            title,
+            content,
+        } => {
+            let body = content
+                .iter()
+                .map(render_node)
+                .collect::<Vec<_>>()
+                .join("\n");
+            let title = match title {
+                Some(title) => format!(".{}\n", render_inlines(title)),
+                None => String::new(),
+            };
+            format!(
+                "{}[{}]\n====\n{}====\n",
+                title,
+                kind.as_str().to_ascii_uppercase(),
+                body
+            )
+        }
+        Node::Details { summary, content } => {
+            let body = content
+                .iter()
+                .map(render_node)
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!(
+                ".{}\n[%collapsible]\n====\n{}====\n",
+                render_inlines(summary),
+                body
+            )
+        }
+        Node::Figure {
+            content, caption, ..
+        } => {
+            // A block title; Asciidoctor numbers titled images itself
+            let body = match figure_image(content) {
+                Some(Inline::Image { alt, url, .. }) => {
+                    format!("image::{}[{}]\n", url, alt.replace(']', "\\]"))
+                }
+                _ => content.iter().map(render_node).collect(),
+            };
+            format!(".{}\n{}", render_inlines(caption), body)
+        }
+        Node::Comment { content } => format!("////\n{}\n////\n", content),
+        Node::Container {
+            attributes,
+            children,
+        } => {
+            let body = children
+                .iter()
+                .map(render_node)
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("{}--\n{}--\n", block_anchor(attributes), body)
+        }
+        Node::Section {
+            level,
+            heading,
+            children,
+            ..
+        } => std::iter::once(render_heading(*level, heading))
+            .chain(children.iter().map(render_node))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn render_heading(level: u8, content: &[Inline]) -> String {
+    // `=` is the document title, so sections start at `==` and run out at
+    // `======`; deeper headings become discrete headings so h5 and h6 differ
+    let markers = "=".repeat((usize::from(level) + 1).min(6));
+    let style = if level >= 6 { "[discrete]\n" } else { "" };
+    format!("{}{} {}\n", style, markers, render_inlines(content))
+}
+
+/// Render the AST as an AsciiDoc document
+pub(crate) fn render_asciidoc(ast: &[Node]) -> String {
+    let ast = transform::resolve_diagram_references(ast);
+    ast.iter().map(render_node).collect::<Vec<_>>().join("\n")
+}
@@ -0,0 +1,69 @@
+//! HTML rendering of document diffs.
+
+use super::{escape_html, render_node};
+use crate::ast::Node;
+use crate::config::RendererConfig;
+use crate::diff::{align, text_diff, DiffOp, TextChange};
+
+/// Render the new document with changes since `old` highlighted
+///
+/// Removed and inserted blocks are wrapped in `diff-removed` / `diff-inserted`
+/// containers. Modified headings, paragraphs, and blockquotes show word-level
+/// changes with `<del>` and `<ins>`; other modified blocks are shown as removed
+/// followed by inserted.
+pub(crate) fn render_diff_fragment(old: &[Node], new: &[Node], config: &RendererConfig) -> String {
+    let mut html = String::new();
+    for op in align(old, new) {
+        let block = match op {
+            DiffOp::Equal { new: n, .. } => render_node(&new[n], config),
+            DiffOp::Removed { old: o } => wrap("diff-removed", &render_node(&old[o], config)),
+            DiffOp::Inserted { new: n } => wrap("diff-inserted", &render_node(&new[n], config)),
+            DiffOp::Modified { old: o, new: n } => {
+                let changes = text_diff(&old[o], &new[n]);
+                match block_tags(&new[n]) {
+                    Some((open, close)) if !changes.is_empty() => wrap(
+                        "diff-modified",
+                        &format!("{}{}{}", open, render_text_changes(&changes), close),
+                    ),
+                    _ => format!(
+                        "{}\n{}",
+                        wrap("diff-removed", &render_node(&old[o], config)),
+                        wrap("diff-inserted", &render_node(&new[n], config))
+                    ),
+                }
+            }
+        };
+        html.push_str(&block);
+        html.push('\n');
+    }
+    html
+}
+
+fn wrap(class: &str, inner: &str) -> String {
+    format!("<div class=\"{}\">\n{}\n</div>", class, inner)
+}
+
+/// Opening and closing tags for blocks that support word-level diffs
+fn block_tags(node: &Node) -> Option<(String, String)> {
+    match node {
+        Node::Heading { level, .. } => Some((format!("<h{}>", level), format!("</h{}>", level))),
+        Node::Paragraph { .. } => Some(("<p>".to_string(), "</p>".to_string())),
+        Node::Blockquote { level, .. } => Some((
+            "<blockquote>".repeat(usize::from(*level)),
+            "</blockquote>".repeat(usize::from(*level)),
+        )),
+        _ => None,
+    }
+}
+
+fn render_text_changes(changes: &[TextChange]) -> String {
+    changes
+        .iter()
+        .map(|change| match change {
+            TextChange::Unchanged(text) => escape_html(text),
+            TextChange::Inserted(text) => format!("<ins>{}</ins>", escape_html(text)),
+            TextChange::Removed(text) => format!("<del>{}</del>", escape_html(text)),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
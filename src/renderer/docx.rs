@@ -0,0 +1,755 @@
+//! Word document (OOXML `.docx`) generation (`docx` feature).
+//!
+//! Documents use the built-in Word style names (`Heading1`, `Quote`, `ListParagraph`,
+//! ...) so they pick up the look of whatever template they are pasted into.
+
+use super::zip::ZipWriter;
+use super::{apply_transforms, escape_html, is_absolute_url, mermaid_svg, resolve_url};
+use crate::ast::{Alignment, Inline, ListItem, Node};
+use crate::config::RendererConfig;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Document properties and rendering options for a Word document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocxOptions {
+    /// Document title (core properties)
+    pub title: String,
+    /// Author (core properties), if any
+    pub author: Option<String>,
+    /// Embed Mermaid diagrams as PNG images rendered with the Mermaid CLI (`mmdc`)
+    ///
+    /// Diagrams are shown as code when disabled or when `mmdc` is not installed.
+    /// Rendered images are cached in the renderer's `mermaid_cache_dir`.
+    pub render_mermaid: bool,
+}
+
+impl Default for DocxOptions {
+    fn default() -> Self {
+        Self {
+            title: "Untitled".to_string(),
+            author: None,
+            render_mermaid: true,
+        }
+    }
+}
+
+/// Width of the text area of an A4 page with 1" margins, in EMU (914400 per inch)
+const MAX_IMAGE_WIDTH_EMU: u64 = 5_731_510;
+/// EMU per pixel at 96 DPI
+const EMU_PER_PIXEL: u64 = 9_525;
+
+const NS_W: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const NS_R: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const NS_WP: &str = "http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing";
+const NS_A: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
+const NS_PIC: &str = "http://schemas.openxmlformats.org/drawingml/2006/picture";
+const REL_BASE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// Render the AST as a `.docx` archive
+///
+/// Local images are embedded from `resource_dir`; remote images become links.
+///
+/// # Errors
+///
+/// Returns an error if an existing image file cannot be read
+pub(crate) fn render_docx(
+    ast: &[Node],
+    options: &DocxOptions,
+    resource_dir: &Path,
+    config: &RendererConfig,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let nodes = apply_transforms(ast, config);
+    let mut writer = DocxWriter {
+        config,
+        options,
+        resource_dir,
+        body: String::new(),
+        relationships: Vec::new(),
+        images: Vec::new(),
+        embedded: HashMap::new(),
+        ordered_lists: 0,
+        drawings: 0,
+    };
+    for node in nodes.iter() {
+        writer.block(node)?;
+    }
+
+    let mut zip = ZipWriter::new();
+    zip.add_file("[Content_Types].xml", CONTENT_TYPES.as_bytes());
+    zip.add_file("_rels/.rels", PACKAGE_RELS.as_bytes());
+    zip.add_file("docProps/core.xml", core_properties(options).as_bytes());
+    zip.add_file("word/styles.xml", STYLES.as_bytes());
+    zip.add_file(
+        "word/numbering.xml",
+        numbering(writer.ordered_lists).as_bytes(),
+    );
+    zip.add_file(
+        "word/_rels/document.xml.rels",
+        writer.document_relationships().as_bytes(),
+    );
+    zip.add_file("word/document.xml", writer.document().as_bytes());
+    for image in &writer.images {
+        zip.add_file(&format!("word/media/{}", image.name), &image.bytes);
+    }
+    Ok(zip.finish())
+}
+
+/// Formatting applied to a run of text
+#[derive(Debug, Clone, Copy, Default)]
+struct RunStyle {
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    highlight: bool,
+    code: bool,
+    link: bool,
+}
+
+impl RunStyle {
+    fn properties(self) -> String {
+        let mut props = String::new();
+        if self.code {
+            props.push_str("<w:rStyle w:val=\"CodeChar\"/>");
+        } else if self.link {
+            props.push_str("<w:rStyle w:val=\"Hyperlink\"/>");
+        }
+        if self.bold {
+            props.push_str("<w:b/>");
+        }
+        if self.italic {
+            props.push_str("<w:i/>");
+        }
+        if self.strike {
+            props.push_str("<w:strike/>");
+        }
+        if self.highlight {
+            props.push_str("<w:highlight w:val=\"yellow\"/>");
+        }
+        if props.is_empty() {
+            props
+        } else {
+            format!("<w:rPr>{}</w:rPr>", props)
+        }
+    }
+}
+
+/// An image file stored under `word/media`
+struct EmbeddedImage {
+    name: String,
+    bytes: Vec<u8>,
+    relationship: String,
+    /// Size in pixels
+    width: u32,
+    height: u32,
+}
+
+struct DocxWriter<'a> {
+    config: &'a RendererConfig,
+    options: &'a DocxOptions,
+    resource_dir: &'a Path,
+    body: String,
+    /// `(target, type, external)` of each relationship after styles and numbering
+    relationships: Vec<(String, &'static str, bool)>,
+    images: Vec<EmbeddedImage>,
+    /// Index in `images` of each embedded file, by source path
+    embedded: HashMap<PathBuf, usize>,
+    /// Ordered lists so far; each one restarts its numbering
+    ordered_lists: usize,
+    drawings: usize,
+}
+
+impl DocxWriter<'_> {
+    fn block(&mut self, node: &Node) -> Result<(), Box<dyn Error>> {
+        match node {
+            Node::Heading { level, content, .. } => {
+                self.paragraph(&format!("Heading{}", level), "", content)?
+            }
+            Node::Section {
+                level,
+                heading,
+                children,
+                ..
+            } => {
+                self.paragraph(&format!("Heading{}", level), "", heading)?;
+                for child in children {
+                    self.block(child)?;
+                }
+            }
+            Node::Paragraph { content } => self.paragraph("", "", content)?,
+            Node::UnorderedList { items } => self.list_items(items, 1, 0)?,
+            Node::OrderedList { items } => {
+                self.ordered_lists += 1;
+                // Numbering instance 1 is the shared bullet list
+                self.list_items(items, self.ordered_lists + 1, 0)?;
+            }
+            Node::CodeBlock { code, .. } => self.code(code),
+            Node::MermaidDiagram {
+                diagram,
+                config: mermaid_config,
+                ..
+            } => {
+                let theme = mermaid_config
+                    .as_ref()
+                    .and_then(|cfg| cfg.theme.as_deref())
+                    .unwrap_or("default");
+                let cache_dir = self.config.mermaid_cache_dir.as_deref().map(Path::new);
+                let png = self
+                    .options
+                    .render_mermaid
+                    .then(|| mermaid_svg::prerender_png(diagram, theme, cache_dir))
+                    .flatten();
+                match png.and_then(|png| self.embed_bytes(png, None)) {
+                    Some(index) => {
+                        // Rendered at twice the natural size for sharpness
+                        let drawing = self.drawing(index, "Mermaid diagram", 2);
+                        self.body.push_str(&format!(
+                            "<w:p><w:pPr><w:jc w:val=\"center\"/></w:pPr>{}</w:p>",
+                            drawing
+                        ));
+                    }
+                    None => self.code(diagram),
+                }
+            }
+            // References are resolved by the render-time transforms
+            Node::DiagramRef { .. } | Node::Comment { .. } => {}
+            Node::Table {
+                headers,
+                rows,
+                alignments,
+                caption,
+                number,
+                ..
+            } => {
+                self.body.push_str(
+                    "<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/>\
+                     <w:tblW w:w=\"5000\" w:type=\"pct\"/></w:tblPr>",
+                );
+                self.table_row(headers, alignments, true)?;
+                for row in rows {
+                    self.table_row(row, alignments, false)?;
+                }
+                self.body.push_str("</w:tbl>");
+                if let Some(caption) = caption {
+                    let mut label = Vec::new();
+                    if let Some(number) = number {
+                        label.push(Inline::Text {
+                            content: format!("{} ", self.config.strings.table_label(*number))
+                                .into(),
+                        });
+                    }
+                    label.extend(caption.iter().cloned());
+                    self.paragraph("Caption", "", &label)?;
+                }
+            }
+            Node::Blockquote {
+                level,
+                content,
+                blocks,
+            } => {
+                let indent = format!("<w:ind w:left=\"{}\"/>", 720 * u32::from(*level));
+                self.paragraph("Quote", &indent, content)?;
+                for block in blocks {
+                    self.block(block)?;
+                }
+            }
+            Node::HorizontalRule { .. } => self.body.push_str(
+                "<w:p><w:pPr><w:pBdr><w:bottom w:val=\"single\" w:sz=\"6\" w:space=\"1\" \
+                 w:color=\"auto\"/></w:pBdr></w:pPr></w:p>",
+            ),
+            Node::Admonition {
+                kind,
+                title,
+                content,
+            } => {
+                let title = vec![Inline::Bold {
+                    content: title.clone().unwrap_or_else(|| {
+                        vec![Inline::Text {
+                            content: self.config.strings.admonition_title(*kind).into(),
+                        }]
+                    }),
+                }];
+                self.boxed(&title, content)?;
+            }
+            Node::Details { summary, content } => {
+                let summary = vec![Inline::Bold {
+                    content: summary.clone(),
+                }];
+                self.boxed(&summary, content)?;
+            }
+            Node::Container { children, .. } => {
+                for child in children {
+                    self.block(child)?;
+                }
+            }
+            Node::Figure {
+                content,
+                caption,
+                number,
+            } => {
+                for child in content {
+                    self.block(child)?;
+                }
+                let mut label = Vec::new();
+                if let Some(number) = number {
+                    label.push(Inline::Text {
+                        content: format!("{} ", self.config.strings.figure_label(*number)).into(),
+                    });
+                }
+                label.extend(caption.iter().cloned());
+                self.paragraph("Caption", "", &label)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a paragraph with an optional style and extra paragraph properties
+    fn paragraph(
+        &mut self,
+        style: &str,
+        properties: &str,
+        content: &[Inline],
+    ) -> Result<(), Box<dyn Error>> {
+        let runs = self.runs(content, RunStyle::default())?;
+        self.body.push_str("<w:p>");
+        if !style.is_empty() || !properties.is_empty() {
+            self.body.push_str("<w:pPr>");
+            if !style.is_empty() {
+                self.body
+                    .push_str(&format!("<w:pStyle w:val=\"{}\"/>", style));
+            }
+            self.body.push_str(properties);
+            self.body.push_str("</w:pPr>");
+        }
+        self.body.push_str(&runs);
+        self.body.push_str("</w:p>");
+        Ok(())
+    }
+
+    fn list_items(
+        &mut self,
+        items: &[ListItem],
+        num_id: usize,
+        level: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        // Word supports nine list levels
+        let ilvl = level.min(8);
+        for item in items {
+            let mut content = Vec::new();
+            if let Some(checked) = item.checked {
+                content.push(Inline::Text {
+                    content: if checked { "☒ " } else { "☐ " }.into(),
+                });
+            }
+            content.extend(item.content.iter().cloned());
+            let numbering = format!(
+                "<w:numPr><w:ilvl w:val=\"{}\"/><w:numId w:val=\"{}\"/></w:numPr>",
+                ilvl, num_id
+            );
+            self.paragraph("ListParagraph", &numbering, &content)?;
+            for block in &item.blocks {
+                self.block(block)?;
+            }
+            // Nested items are bulleted, as in HTML output
+            self.list_items(&item.children, 1, level + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Code as one paragraph in the `Code` style, with a line break per source line
+    fn code(&mut self, code: &str) {
+        let runs: Vec<String> = code
+            .lines()
+            .map(|line| format!("<w:t xml:space=\"preserve\">{}</w:t>", escape_html(line)))
+            .collect();
+        self.body.push_str(&format!(
+            "<w:p><w:pPr><w:pStyle w:val=\"Code\"/></w:pPr><w:r>{}</w:r></w:p>",
+            runs.join("<w:br/>")
+        ));
+    }
+
+    fn table_row(
+        &mut self,
+        cells: &[Vec<Inline>],
+        alignments: &[Option<Alignment>],
+        header: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        self.body.push_str("<w:tr>");
+        if header {
+            self.body.push_str("<w:trPr><w:tblHeader/></w:trPr>");
+        }
+        for (column, cell) in cells.iter().enumerate() {
+            let justification = match alignments.get(column).copied().flatten() {
+                Some(Alignment::Center) => "<w:jc w:val=\"center\"/>",
+                Some(Alignment::Right) => "<w:jc w:val=\"right\"/>",
+                Some(Alignment::Left) | None => "",
+            };
+            let style = RunStyle {
+                bold: header,
+                ..RunStyle::default()
+            };
+            let runs = self.runs(cell, style)?;
+            self.body.push_str(&format!(
+                "<w:tc><w:p><w:pPr>{}</w:pPr>{}</w:p></w:tc>",
+                justification, runs
+            ));
+        }
+        self.body.push_str("</w:tr>");
+        Ok(())
+    }
+
+    /// A shaded single-cell table with a title paragraph, for admonitions and details
+    fn boxed(&mut self, title: &[Inline], content: &[Node]) -> Result<(), Box<dyn Error>> {
+        self.body.push_str(
+            "<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/>\
+             <w:tblW w:w=\"5000\" w:type=\"pct\"/></w:tblPr>\
+             <w:tr><w:tc><w:tcPr><w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"F2F2F2\"/></w:tcPr>",
+        );
+        self.paragraph("", "", title)?;
+        for node in content {
+            self.block(node)?;
+        }
+        self.body.push_str("</w:tc></w:tr></w:tbl>");
+        // Word needs a paragraph between adjacent tables
+        self.body.push_str("<w:p/>");
+        Ok(())
+    }
+
+    fn runs(&mut self, inlines: &[Inline], style: RunStyle) -> Result<String, Box<dyn Error>> {
+        let mut xml = String::new();
+        for inline in inlines {
+            match inline {
+                Inline::Text { content } => xml.push_str(&text_run(content, style)),
+                Inline::Bold { content } => xml.push_str(&self.runs(
+                    content,
+                    RunStyle {
+                        bold: true,
+                        ..style
+                    },
+                )?),
+                Inline::Italic { content } => xml.push_str(&self.runs(
+                    content,
+                    RunStyle {
+                        italic: true,
+                        ..style
+                    },
+                )?),
+                Inline::Strikethrough { content } => xml.push_str(&self.runs(
+                    content,
+                    RunStyle {
+                        strike: true,
+                        ..style
+                    },
+                )?),
+                Inline::Highlight { content } => xml.push_str(&self.runs(
+                    content,
+                    RunStyle {
+                        highlight: true,
+                        ..style
+                    },
+                )?),
+                // Word has no way to hide text until clicked
+                Inline::Spoiler { content } => xml.push_str(&self.runs(content, style)?),
+                Inline::Code { content } => xml.push_str(&text_run(
+                    content,
+                    RunStyle {
+                        code: true,
+                        ..style
+                    },
+                )),
+                Inline::Link { text, url } => {
+                    let runs = self.runs(
+                        text,
+                        RunStyle {
+                            link: true,
+                            ..style
+                        },
+                    )?;
+                    xml.push_str(&self.hyperlink(url, &runs));
+                }
+                Inline::Image { alt, url, .. } => xml.push_str(&self.image(alt, url, style)?),
+                Inline::Span { content, .. } => xml.push_str(&self.runs(content, style)?),
+                Inline::Hashtag { tag } => xml.push_str(&text_run(&format!("#{}", tag), style)),
+                Inline::Mention { user } => xml.push_str(&text_run(&format!("@{}", user), style)),
+                Inline::LineBreak => xml.push_str("<w:r><w:br/></w:r>"),
+                Inline::Comment { .. } => {}
+                Inline::Directive { content, .. } => xml.push_str(&self.runs(content, style)?),
+            }
+        }
+        Ok(xml)
+    }
+
+    fn hyperlink(&mut self, url: &str, runs: &str) -> String {
+        if let Some(anchor) = url.strip_prefix('#') {
+            return format!(
+                "<w:hyperlink w:anchor=\"{}\">{}</w:hyperlink>",
+                escape_html(anchor),
+                runs
+            );
+        }
+        let relationship = self.relationship(resolve_url(url, self.config), "hyperlink", true);
+        format!(
+            "<w:hyperlink r:id=\"{}\">{}</w:hyperlink>",
+            relationship, runs
+        )
+    }
+
+    /// Embed a local image, or link to a remote or unreadable one by its alt text
+    fn image(&mut self, alt: &str, url: &str, style: RunStyle) -> Result<String, Box<dyn Error>> {
+        let label = if alt.is_empty() { url } else { alt };
+        if is_absolute_url(url) {
+            let run = text_run(
+                label,
+                RunStyle {
+                    link: true,
+                    ..style
+                },
+            );
+            return Ok(self.hyperlink(url, &run));
+        }
+        let source = self.resource_dir.join(url);
+        let index = match self.embedded.get(&source) {
+            Some(&index) => Some(index),
+            None if source.is_file() => {
+                let bytes = fs::read(&source)
+                    .map_err(|e| format!("Error reading image '{}': {}", source.display(), e))?;
+                self.embed_bytes(bytes, Some(source))
+            }
+            None => None,
+        };
+        Ok(match index {
+            Some(index) => self.drawing(index, alt, 1),
+            None => text_run(
+                label,
+                RunStyle {
+                    italic: true,
+                    ..style
+                },
+            ),
+        })
+    }
+
+    /// Store an image under `word/media`, returning its index, if its format is supported
+    fn embed_bytes(&mut self, bytes: Vec<u8>, source: Option<PathBuf>) -> Option<usize> {
+        let (extension, width, height) = image_info(&bytes)?;
+        let name = format!("image{}.{}", self.images.len() + 1, extension);
+        let relationship = self.relationship(format!("media/{}", name), "image", false);
+        self.images.push(EmbeddedImage {
+            name,
+            bytes,
+            relationship,
+            width,
+            height,
+        });
+        let index = self.images.len() - 1;
+        if let Some(source) = source {
+            self.embedded.insert(source, index);
+        }
+        Some(index)
+    }
+
+    /// Inline drawing of an embedded image, `scale` pixels per displayed pixel,
+    /// shrunk to the page width
+    fn drawing(&mut self, index: usize, description: &str, scale: u32) -> String {
+        let image = &self.images[index];
+        let mut width = u64::from(image.width / scale.max(1)) * EMU_PER_PIXEL;
+        let mut height = u64::from(image.height / scale.max(1)) * EMU_PER_PIXEL;
+        if width > MAX_IMAGE_WIDTH_EMU {
+            height = height * MAX_IMAGE_WIDTH_EMU / width;
+            width = MAX_IMAGE_WIDTH_EMU;
+        }
+        self.drawings += 1;
+        let id = self.drawings;
+        format!(
+            "<w:r><w:drawing><wp:inline distT=\"0\" distB=\"0\" distL=\"0\" distR=\"0\">\
+             <wp:extent cx=\"{width}\" cy=\"{height}\"/>\
+             <wp:docPr id=\"{id}\" name=\"Picture {id}\" descr=\"{description}\"/>\
+             <wp:cNvGraphicFramePr><a:graphicFrameLocks noChangeAspect=\"1\"/></wp:cNvGraphicFramePr>\
+             <a:graphic><a:graphicData uri=\"{NS_PIC}\"><pic:pic>\
+             <pic:nvPicPr><pic:cNvPr id=\"{id}\" name=\"{name}\"/><pic:cNvPicPr/></pic:nvPicPr>\
+             <pic:blipFill><a:blip r:embed=\"{relationship}\"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>\
+             <pic:spPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"{width}\" cy=\"{height}\"/></a:xfrm>\
+             <a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom></pic:spPr>\
+             </pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>",
+            description = escape_html(description),
+            name = image.name,
+            relationship = image.relationship,
+        )
+    }
+
+    /// Add a relationship from the document part, returning its id
+    fn relationship(&mut self, target: String, kind: &'static str, external: bool) -> String {
+        self.relationships.push((target, kind, external));
+        // rId1 and rId2 are the styles and numbering parts
+        format!("rId{}", self.relationships.len() + 2)
+    }
+
+    fn document_relationships(&self) -> String {
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"rId1\" Type=\"{REL_BASE}/styles\" Target=\"styles.xml\"/>\
+             <Relationship Id=\"rId2\" Type=\"{REL_BASE}/numbering\" Target=\"numbering.xml\"/>"
+        );
+        for (index, (target, kind, external)) in self.relationships.iter().enumerate() {
+            xml.push_str(&format!(
+                "<Relationship Id=\"rId{}\" Type=\"{}/{}\" Target=\"{}\"{}/>",
+                index + 3,
+                REL_BASE,
+                kind,
+                escape_html(target),
+                if *external {
+                    " TargetMode=\"External\""
+                } else {
+                    ""
+                }
+            ));
+        }
+        xml.push_str("</Relationships>");
+        xml
+    }
+
+    fn document(&self) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <w:document xmlns:w=\"{NS_W}\" xmlns:r=\"{NS_R}\" xmlns:wp=\"{NS_WP}\" \
+             xmlns:a=\"{NS_A}\" xmlns:pic=\"{NS_PIC}\"><w:body>{}\
+             <w:sectPr><w:pgSz w:w=\"11906\" w:h=\"16838\"/>\
+             <w:pgMar w:top=\"1440\" w:right=\"1440\" w:bottom=\"1440\" w:left=\"1440\" \
+             w:header=\"708\" w:footer=\"708\" w:gutter=\"0\"/></w:sectPr></w:body></w:document>",
+            self.body
+        )
+    }
+}
+
+fn text_run(text: &str, style: RunStyle) -> String {
+    format!(
+        "<w:r>{}<w:t xml:space=\"preserve\">{}</w:t></w:r>",
+        style.properties(),
+        escape_html(text)
+    )
+}
+
+/// File extension and pixel size of a PNG, JPEG, or GIF image
+fn image_info(bytes: &[u8]) -> Option<(&'static str, u32, u32)> {
+    let be16 = |at: usize| {
+        Some(u32::from(u16::from_be_bytes([
+            *bytes.get(at)?,
+            *bytes.get(at + 1)?,
+        ])))
+    };
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        let be32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+        return Some(("png", be32(16)?, be32(20)?));
+    }
+    if bytes.starts_with(b"GIF8") {
+        let le16 = |at: usize| {
+            Some(u32::from(u16::from_le_bytes([
+                *bytes.get(at)?,
+                *bytes.get(at + 1)?,
+            ])))
+        };
+        return Some(("gif", le16(6)?, le16(8)?));
+    }
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        // Walk the marker segments up to the start-of-frame header
+        let mut at = 2;
+        while *bytes.get(at)? == 0xFF {
+            let marker = *bytes.get(at + 1)?;
+            let is_frame = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_frame {
+                return Some(("jpeg", be16(at + 7)?, be16(at + 5)?));
+            }
+            at += 2 + usize::try_from(be16(at + 2)?).ok()?;
+        }
+    }
+    None
+}
+
+fn core_properties(options: &DocxOptions) -> String {
+    let creator = options
+        .author
+        .as_ref()
+        .map(|author| format!("<dc:creator>{}</dc:creator>", escape_html(author)))
+        .unwrap_or_default();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\"><dc:title>{}</dc:title>{}</cp:coreProperties>",
+        escape_html(&options.title),
+        creator
+    )
+}
+
+/// Numbering definitions: instance 1 is the bullet list, 2 onwards one per ordered list
+fn numbering(ordered_lists: usize) -> String {
+    let levels = |ordered: bool| -> String {
+        (0..9)
+            .map(|level| {
+                let (format, text) = if ordered {
+                    ("decimal", format!("%{}.", level + 1))
+                } else {
+                    ("bullet", ["•", "◦", "▪"][level % 3].to_string())
+                };
+                format!(
+                    "<w:lvl w:ilvl=\"{level}\"><w:start w:val=\"1\"/><w:numFmt w:val=\"{format}\"/>\
+                     <w:lvlText w:val=\"{text}\"/><w:lvlJc w:val=\"left\"/>\
+                     <w:pPr><w:ind w:left=\"{}\" w:hanging=\"360\"/></w:pPr></w:lvl>",
+                    720 * (level + 1)
+                )
+            })
+            .collect()
+    };
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <w:numbering xmlns:w=\"{NS_W}\">\
+         <w:abstractNum w:abstractNumId=\"0\"><w:multiLevelType w:val=\"hybridMultilevel\"/>{}</w:abstractNum>\
+         <w:abstractNum w:abstractNumId=\"1\"><w:multiLevelType w:val=\"hybridMultilevel\"/>{}</w:abstractNum>\
+         <w:num w:numId=\"1\"><w:abstractNumId w:val=\"0\"/></w:num>",
+        levels(false),
+        levels(true)
+    );
+    for list in 0..ordered_lists {
+        xml.push_str(&format!(
+            "<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"1\"/>\
+             <w:lvlOverride w:ilvl=\"0\"><w:startOverride w:val=\"1\"/></w:lvlOverride></w:num>",
+            list + 2
+        ));
+    }
+    xml.push_str("</w:numbering>");
+    xml
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Default Extension="png" ContentType="image/png"/><Default Extension="jpeg" ContentType="image/jpeg"/><Default Extension="gif" ContentType="image/gif"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/><Override PartName="/word/numbering.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/></Types>"#;
+
+const PACKAGE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/></Relationships>"#;
+
+const STYLES: &str = include_str!("../../assets/docx_styles.xml");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_info_reads_headers() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 1, 44, 0, 0, 0, 200]);
+        assert_eq!(image_info(&png), Some(("png", 300, 200)));
+
+        let gif = b"GIF89a\x40\x01\xf0\x00";
+        assert_eq!(image_info(gif), Some(("gif", 320, 240)));
+
+        // SOI, an APP0 segment of length 4, then a baseline frame header
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0, 4, 0, 0, 0xFF, 0xC0, 0, 17, 8, 0, 120, 0, 160,
+        ];
+        assert_eq!(image_info(&jpeg), Some(("jpeg", 160, 120)));
+
+        assert_eq!(image_info(b"<svg/>"), None);
+        assert_eq!(image_info(&[0xFF, 0xD8, 0xFF]), None);
+    }
+}
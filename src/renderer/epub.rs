@@ -0,0 +1,390 @@
+//! EPUB 3 packaging built on the HTML fragment renderer.
+
+use super::zip::ZipWriter;
+use super::{escape_html, is_absolute_url, render_fragment};
+use crate::ast::{for_each_inline, plain_text, Inline, Node};
+use crate::config::{RendererConfig, UrlRewriter};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Metadata and packaging options for an EPUB book
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpubOptions {
+    /// Book title
+    pub title: String,
+    /// Author (`dc:creator`), if any
+    pub author: Option<String>,
+    /// BCP 47 language tag (e.g. "en")
+    pub language: String,
+    /// Unique identifier; derived from the title when `None`
+    pub identifier: Option<String>,
+    /// Last-modified timestamp (`YYYY-MM-DDThh:mm:ssZ`); the current time when `None`
+    pub modified: Option<String>,
+}
+
+impl Default for EpubOptions {
+    fn default() -> Self {
+        Self {
+            title: "Untitled".to_string(),
+            author: None,
+            language: "en".to_string(),
+            identifier: None,
+            modified: None,
+        }
+    }
+}
+
+/// One chapter (spine item) of an EPUB book
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpubChapter {
+    /// Title shown in the navigation document
+    pub title: String,
+    /// Chapter content
+    pub nodes: Vec<Node>,
+    /// Directory that relative image paths in this chapter are resolved against
+    pub resource_dir: PathBuf,
+}
+
+impl EpubChapter {
+    /// Split a document into chapters at each level-1 heading
+    ///
+    /// Content before the first `#` heading becomes its own chapter titled `fallback_title`.
+    pub fn split_document(
+        nodes: &[Node],
+        fallback_title: &str,
+        resource_dir: impl AsRef<Path>,
+    ) -> Vec<EpubChapter> {
+        let resource_dir = resource_dir.as_ref().to_path_buf();
+        let mut chapters: Vec<EpubChapter> = Vec::new();
+
+        for node in nodes {
+            let chapter_heading = match node {
+                Node::Heading {
+                    level: 1, content, ..
+                }
+                | Node::Section {
+                    level: 1,
+                    heading: content,
+                    ..
+                } => Some(content),
+                _ => None,
+            };
+            if chapter_heading.is_some() || chapters.is_empty() {
+                let title = chapter_heading
+                    .map(|content| plain_text(content))
+                    .unwrap_or_else(|| fallback_title.to_string());
+                chapters.push(EpubChapter {
+                    title,
+                    nodes: Vec::new(),
+                    resource_dir: resource_dir.clone(),
+                });
+            }
+            if let Some(chapter) = chapters.last_mut() {
+                chapter.nodes.push(node.clone());
+            }
+        }
+
+        chapters
+    }
+}
+
+/// Package chapters into an EPUB 3 archive
+///
+/// Each chapter is rendered with the HTML fragment renderer and converted to XHTML.
+/// Relative images that exist on disk are embedded under `images/`.
+///
+/// # Errors
+///
+/// Returns an error if an existing image file cannot be read
+pub(crate) fn render_epub(
+    chapters: &[EpubChapter],
+    options: &EpubOptions,
+    renderer_config: &RendererConfig,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut zip = ZipWriter::new();
+    // The mimetype entry must come first and be stored uncompressed
+    zip.add_file("mimetype", b"application/epub+zip");
+    zip.add_file("META-INF/container.xml", CONTAINER_XML.as_bytes());
+    zip.add_file(
+        "OEBPS/style.css",
+        include_str!("../../assets/styles.css").as_bytes(),
+    );
+
+    let mut manifest = Vec::new();
+    let mut images: Vec<(String, &'static str)> = Vec::new();
+    let mut image_ids: HashMap<PathBuf, String> = HashMap::new();
+
+    for (index, chapter) in chapters.iter().enumerate() {
+        // Embed local images and map their URLs to archive paths
+        let mut url_map = HashMap::new();
+        for url in chapter_image_urls(&chapter.nodes) {
+            if is_absolute_url(&url) {
+                continue;
+            }
+            let source = chapter.resource_dir.join(&url);
+            if let Some(href) = image_ids.get(&source) {
+                url_map.insert(url, href.clone());
+                continue;
+            }
+            let Some(media_type) = image_media_type(&source) else {
+                continue;
+            };
+            if !source.is_file() {
+                continue;
+            }
+            let bytes = fs::read(&source)
+                .map_err(|e| format!("Error reading image '{}': {}", source.display(), e))?;
+            let extension = source
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let href = format!("images/image-{}.{}", images.len() + 1, extension);
+            zip.add_file(&format!("OEBPS/{}", href), &bytes);
+            images.push((href.clone(), media_type));
+            image_ids.insert(source, href.clone());
+            url_map.insert(url, href);
+        }
+
+        let chapter_config = RendererConfig {
+            url_rewriter: Some(chain_rewriter(
+                renderer_config.url_rewriter.clone(),
+                url_map,
+            )),
+            ..renderer_config.clone()
+        };
+        let body = to_xhtml(&render_fragment(&chapter.nodes, &chapter_config));
+        let filename = format!("chapter-{}.xhtml", index + 1);
+        zip.add_file(
+            &format!("OEBPS/{}", filename),
+            xhtml_document(&chapter.title, &options.language, &body).as_bytes(),
+        );
+        manifest.push((format!("chapter-{}", index + 1), filename));
+    }
+
+    zip.add_file(
+        "OEBPS/nav.xhtml",
+        nav_document(chapters, options).as_bytes(),
+    );
+    zip.add_file(
+        "OEBPS/content.opf",
+        package_document(options, &manifest, &images).as_bytes(),
+    );
+
+    Ok(zip.finish())
+}
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+/// Wrap a user rewriter so chapter-local image paths are replaced afterwards
+fn chain_rewriter(user: Option<UrlRewriter>, url_map: HashMap<String, String>) -> UrlRewriter {
+    UrlRewriter::new(move |url| {
+        if let Some(href) = url_map.get(url) {
+            return href.clone();
+        }
+        match &user {
+            Some(rewriter) => rewriter.rewrite(url),
+            None => url.to_string(),
+        }
+    })
+}
+
+/// Convert the HTML void elements emitted by the renderer into XHTML form
+fn to_xhtml(html: &str) -> String {
+    html.replace("<hr>", "<hr />")
+        .replace(
+            "<input type=\"checkbox\" disabled checked>",
+            "<input type=\"checkbox\" disabled=\"disabled\" checked=\"checked\" />",
+        )
+        .replace(
+            "<input type=\"checkbox\" disabled>",
+            "<input type=\"checkbox\" disabled=\"disabled\" />",
+        )
+        .replace(
+            "<input type=\"checkbox\" checked>",
+            "<input type=\"checkbox\" checked=\"checked\" />",
+        )
+        .replace("<input type=\"checkbox\">", "<input type=\"checkbox\" />")
+}
+
+fn xhtml_document(title: &str, language: &str, body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="{lang}" lang="{lang}">
+<head>
+  <meta charset="UTF-8" />
+  <title>{title}</title>
+  <link rel="stylesheet" type="text/css" href="style.css" />
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        lang = escape_html(language),
+        title = escape_html(title),
+        body = body
+    )
+}
+
+fn nav_document(chapters: &[EpubChapter], options: &EpubOptions) -> String {
+    let mut items = String::new();
+    for (index, chapter) in chapters.iter().enumerate() {
+        items.push_str(&format!(
+            "      <li><a href=\"chapter-{}.xhtml\">{}</a></li>\n",
+            index + 1,
+            escape_html(&chapter.title)
+        ));
+    }
+    let body = format!(
+        "  <nav epub:type=\"toc\" id=\"toc\">\n    <h1>{}</h1>\n    <ol>\n{}    </ol>\n  </nav>\n",
+        escape_html(&options.title),
+        items
+    );
+    xhtml_document(&options.title, &options.language, &body)
+}
+
+fn package_document(
+    options: &EpubOptions,
+    chapters: &[(String, String)],
+    images: &[(String, &'static str)],
+) -> String {
+    let identifier = options
+        .identifier
+        .clone()
+        .unwrap_or_else(|| format!("urn:md-parser:{}", crate::slug::slugify(&options.title)));
+    let modified = options.modified.clone().unwrap_or_else(|| {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        format_utc_timestamp(secs)
+    });
+
+    let mut metadata = format!(
+        "    <dc:identifier id=\"book-id\">{}</dc:identifier>\n    <dc:title>{}</dc:title>\n    <dc:language>{}</dc:language>\n",
+        escape_html(&identifier),
+        escape_html(&options.title),
+        escape_html(&options.language)
+    );
+    if let Some(author) = &options.author {
+        metadata.push_str(&format!(
+            "    <dc:creator>{}</dc:creator>\n",
+            escape_html(author)
+        ));
+    }
+    metadata.push_str(&format!(
+        "    <meta property=\"dcterms:modified\">{}</meta>\n",
+        escape_html(&modified)
+    ));
+
+    let mut manifest = String::from(
+        "    <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n    <item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>\n",
+    );
+    let mut spine = String::new();
+    for (id, href) in chapters {
+        manifest.push_str(&format!(
+            "    <item id=\"{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n",
+            id, href
+        ));
+        spine.push_str(&format!("    <itemref idref=\"{}\"/>\n", id));
+    }
+    for (index, (href, media_type)) in images.iter().enumerate() {
+        manifest.push_str(&format!(
+            "    <item id=\"image-{}\" href=\"{}\" media-type=\"{}\"/>\n",
+            index + 1,
+            href,
+            media_type
+        ));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+{}  </metadata>
+  <manifest>
+{}  </manifest>
+  <spine>
+{}  </spine>
+</package>
+"#,
+        metadata, manifest, spine
+    )
+}
+
+/// Media type for embeddable image formats, `None` for anything else
+fn image_media_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "svg" => Some("image/svg+xml"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Format seconds since the UNIX epoch as `YYYY-MM-DDThh:mm:ssZ`
+fn format_utc_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        (rem % 3_600) / 60,
+        rem % 60
+    )
+}
+
+/// Collect image URLs from a chapter
+fn chapter_image_urls(nodes: &[Node]) -> Vec<String> {
+    let mut urls = Vec::new();
+    for_each_inline(nodes, &mut |inline| {
+        if let Inline::Image { url, .. } = inline {
+            urls.push(url.clone());
+        }
+    });
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_utc_timestamp() {
+        assert_eq!(format_utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn test_to_xhtml_closes_void_elements() {
+        assert_eq!(to_xhtml("<hr>"), "<hr />");
+        assert!(to_xhtml("<li><input type=\"checkbox\" disabled> a</li>").contains("/>"));
+    }
+}
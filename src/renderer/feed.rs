@@ -0,0 +1,289 @@
+//! Feed item rendering (RSS `<content:encoded>`, Atom `<content type="html">`).
+//!
+//! Feed readers show item HTML without the page templates, styles, or scripts, so
+//! Mermaid diagrams are turned into code blocks or image links, and comments and
+//! interactive elements are left out.
+
+use super::render_fragment;
+use crate::ast::{for_each_node, plain_text, Attributes, Inline, Node};
+use crate::config::{CommentMode, RendererConfig};
+use crate::transform;
+
+/// How Mermaid diagrams appear in feed items, which cannot run Mermaid's script
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum FeedMermaid {
+    /// A `mermaid` code block showing the diagram source
+    #[default]
+    Code,
+    /// An image served by a Mermaid rendering service
+    ///
+    /// The image URL is the prefix followed by the URL-safe Base64 encoding of the
+    /// diagram source, as expected by `https://mermaid.ink/svg/`.
+    Image {
+        /// Service URL the encoded diagram is appended to
+        url_prefix: String,
+    },
+}
+
+impl FeedMermaid {
+    /// Images rendered by the public mermaid.ink service
+    pub fn mermaid_ink() -> Self {
+        FeedMermaid::Image {
+            url_prefix: "https://mermaid.ink/svg/".to_string(),
+        }
+    }
+}
+
+/// Options for [`FeedItem::from_nodes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedOptions {
+    /// Only include the document up to the second heading of the first heading's level
+    pub first_section_only: bool,
+    /// How Mermaid diagrams are shown
+    pub mermaid: FeedMermaid,
+    /// Maximum length of the summary in characters, cut at a word boundary
+    pub summary_length: usize,
+}
+
+impl Default for FeedOptions {
+    fn default() -> Self {
+        Self {
+            first_section_only: false,
+            mermaid: FeedMermaid::Code,
+            summary_length: 280,
+        }
+    }
+}
+
+/// Title, summary, and HTML content of a document as a feed entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedItem {
+    /// Plain text of the first heading
+    pub title: Option<String>,
+    /// Plain text of the first paragraph, shortened to `summary_length`
+    pub summary: Option<String>,
+    /// Feed-safe HTML of the document, without the title heading
+    pub content_html: String,
+}
+
+impl FeedItem {
+    /// Render a document as a feed entry
+    ///
+    /// Relative link and image URLs are resolved against `renderer_config.base_url`,
+    /// which feeds need since readers show items away from the site. Comments are
+    /// stripped, task checkboxes are disabled, and Mermaid diagrams are never
+    /// pre-rendered to inline SVG. When the document starts with a heading, that
+    /// heading becomes the title and is left out of the content.
+    pub fn from_nodes(
+        nodes: &[Node],
+        options: &FeedOptions,
+        renderer_config: &RendererConfig,
+    ) -> FeedItem {
+        // Resolve references first, so definitions outside the first section still count
+        let mut nodes = transform::resolve_diagram_references(nodes);
+        if options.first_section_only {
+            nodes = first_section(nodes);
+        }
+
+        let title = first_heading(&nodes);
+        if let Some(first) = nodes.first() {
+            match first {
+                Node::Heading { .. } => {
+                    nodes.remove(0);
+                }
+                Node::Section { children, .. } => {
+                    let children = children.clone();
+                    nodes.splice(0..1, children);
+                }
+                _ => {}
+            }
+        }
+        let summary = first_paragraph(&nodes).map(|text| truncate(&text, options.summary_length));
+        let nodes = replace_diagrams(nodes, &options.mermaid);
+
+        let config = RendererConfig {
+            comments: CommentMode::Strip,
+            interactive_tasks: false,
+            prerender_mermaid: false,
+            ..renderer_config.clone()
+        };
+        let content_html = config.line_ending.apply(render_fragment(&nodes, &config));
+
+        FeedItem {
+            title,
+            summary,
+            content_html,
+        }
+    }
+}
+
+/// Nodes up to the next heading of the same or a higher level than the first one
+fn first_section(nodes: Vec<Node>) -> Vec<Node> {
+    let mut section_level = None;
+    let mut end = nodes.len();
+    for (index, node) in nodes.iter().enumerate() {
+        let level = match node {
+            Node::Heading { level, .. } | Node::Section { level, .. } => *level,
+            _ => continue,
+        };
+        match section_level {
+            None => section_level = Some(level),
+            Some(first) if level <= first => {
+                end = index;
+                break;
+            }
+            Some(_) => {}
+        }
+    }
+    nodes.into_iter().take(end).collect()
+}
+
+fn first_heading(nodes: &[Node]) -> Option<String> {
+    let mut title = None;
+    for_each_node(nodes, &mut |node| {
+        if let Node::Heading { content, .. }
+        | Node::Section {
+            heading: content, ..
+        } = node
+        {
+            title.get_or_insert_with(|| plain_text(content));
+        }
+    });
+    title
+}
+
+fn first_paragraph(nodes: &[Node]) -> Option<String> {
+    let mut summary = None;
+    for_each_node(nodes, &mut |node| {
+        if let Node::Paragraph { content } = node {
+            let text = plain_text(content);
+            if summary.is_none() && !text.trim().is_empty() {
+                summary = Some(text);
+            }
+        }
+    });
+    summary
+}
+
+/// Shorten `text` to at most `max_chars` characters, ending at a word boundary with `…`
+fn truncate(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    // Leave room for the ellipsis
+    let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    let cut = match cut.rfind(char::is_whitespace) {
+        Some(space) if space > 0 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end())
+}
+
+/// Replace Mermaid diagrams, at any depth, with their feed representation
+fn replace_diagrams(nodes: Vec<Node>, mode: &FeedMermaid) -> Vec<Node> {
+    nodes
+        .into_iter()
+        .map(|node| match node {
+            Node::MermaidDiagram { diagram, name, .. } => match mode {
+                FeedMermaid::Code => Node::CodeBlock {
+                    lang: Some("mermaid".to_string()),
+                    code: diagram,
+                    attributes: Attributes::default(),
+                },
+                FeedMermaid::Image { url_prefix } => Node::Paragraph {
+                    content: vec![Inline::Image {
+                        alt: match name {
+                            Some(name) => format!("Diagram: {}", name),
+                            None => "Diagram".to_string(),
+                        },
+                        url: format!("{}{}", url_prefix, base64_url(diagram.as_bytes())),
+                        attributes: Attributes::default(),
+                    }],
+                },
+            },
+            Node::Admonition {
+                kind,
+                title,
+                content,
+            } => Node::Admonition {
+                kind,
+                title,
+                content: replace_diagrams(content, mode),
+            },
+            Node::Details { summary, content } => Node::Details {
+                summary,
+                content: replace_diagrams(content, mode),
+            },
+            Node::Container {
+                attributes,
+                children,
+            } => Node::Container {
+                attributes,
+                children: replace_diagrams(children, mode),
+            },
+            Node::Section {
+                level,
+                heading,
+                attributes,
+                children,
+            } => Node::Section {
+                level,
+                heading,
+                attributes,
+                children: replace_diagrams(children, mode),
+            },
+            Node::Figure {
+                content,
+                caption,
+                number,
+            } => Node::Figure {
+                content: replace_diagrams(content, mode),
+                caption,
+                number,
+            },
+            other => other,
+        })
+        .collect()
+}
+
+/// URL-safe Base64 without padding (RFC 4648 §5)
+fn base64_url(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+        for i in 0..=chunk.len() {
+            let index = (group >> (18 - 6 * i)) & 0x3F;
+            out.push(char::from(ALPHABET[index as usize]));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_url() {
+        assert_eq!(base64_url(b""), "");
+        assert_eq!(base64_url(b"f"), "Zg");
+        assert_eq!(base64_url(b"fo"), "Zm8");
+        assert_eq!(base64_url(b"foo"), "Zm9v");
+        assert_eq!(base64_url(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64_url(&[0xfb, 0xff]), "-_8");
+    }
+
+    #[test]
+    fn test_truncate_at_word_boundary() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("one two three", 10), "one two…");
+        assert_eq!(truncate("abcdefghijkl", 5), "abcd…");
+    }
+}
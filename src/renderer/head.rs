@@ -0,0 +1,134 @@
+//! Page `<title>` and `<meta>` tags of HTML documents.
+
+use super::escape_html;
+use crate::ast::{for_each_node, plain_text, Node};
+use crate::config::RendererConfig;
+
+/// Longest description taken from the first paragraph, in characters
+const DESCRIPTION_LENGTH: usize = 160;
+
+/// `header` with its `<title>` set and the configured `<meta>` tags added after it
+///
+/// The title and description come from the config, or else from the first level-1
+/// heading and the first paragraph of the transformed `nodes`, so sections filtered out
+/// for the audience are never quoted. With a `heading_offset`, the title is the first
+/// heading at the level a level-1 heading is shifted to. Without a title the template's
+/// own is kept. Templates without a `<title>` get the tags before `</head>`.
+pub(super) fn fill_head(header: &str, nodes: &[Node], config: &RendererConfig) -> String {
+    let top = (1 + i16::from(config.heading_offset)).clamp(1, 6) as u8;
+    let title = config.title.clone().or_else(|| first_title(nodes, top));
+    let description = config
+        .description
+        .clone()
+        .or_else(|| first_paragraph(nodes).map(|text| truncate(&text, DESCRIPTION_LENGTH)));
+
+    let mut tags = Vec::new();
+    if let Some(description) = &description {
+        tags.push(meta("name", "description", description));
+    }
+    if let Some(author) = &config.author {
+        tags.push(meta("name", "author", author));
+    }
+    for (name, content) in &config.meta {
+        tags.push(meta("name", name, content));
+    }
+    if config.open_graph {
+        if let Some(title) = &title {
+            tags.push(meta("property", "og:title", title));
+        }
+        if let Some(description) = &description {
+            tags.push(meta("property", "og:description", description));
+        }
+        tags.push(meta("property", "og:type", "article"));
+    }
+    let tags: String = tags.iter().map(|tag| format!("\n    {}", tag)).collect();
+
+    match title_element(header) {
+        Some((start, end)) => {
+            let element = match &title {
+                Some(title) => format!("<title>{}</title>", escape_html(title)),
+                None => header[start..end].to_string(),
+            };
+            format!("{}{}{}{}", &header[..start], element, tags, &header[end..])
+        }
+        None => match header.find("</head>") {
+            Some(at) => format!("{}{}\n{}", header[..at].trim_end(), tags, &header[at..]),
+            None => header.to_string(),
+        },
+    }
+}
+
+fn meta(attribute: &str, name: &str, content: &str) -> String {
+    format!(
+        "<meta {}=\"{}\" content=\"{}\">",
+        attribute,
+        escape_html(name),
+        escape_html(content)
+    )
+}
+
+/// Byte range of the `<title>...</title>` element in `header`
+fn title_element(header: &str) -> Option<(usize, usize)> {
+    let start = header.find("<title>")?;
+    let end = start + header[start..].find("</title>")? + "</title>".len();
+    Some((start, end))
+}
+
+/// Text of the first heading at `top` level
+fn first_title(nodes: &[Node], top: u8) -> Option<String> {
+    let mut title = None;
+    for_each_node(nodes, &mut |node| match node {
+        Node::Heading { level, content, .. }
+        | Node::Section {
+            level,
+            heading: content,
+            ..
+        } if *level == top && title.is_none() => title = Some(plain_text(content)),
+        _ => {}
+    });
+    title.filter(|title| !title.trim().is_empty())
+}
+
+/// Text of the first paragraph, with runs of whitespace collapsed
+fn first_paragraph(nodes: &[Node]) -> Option<String> {
+    let mut text = None;
+    for_each_node(nodes, &mut |node| {
+        if let (Node::Paragraph { content }, None) = (node, &text) {
+            text = Some(
+                plain_text(content)
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+    });
+    text.filter(|text| !text.is_empty())
+}
+
+/// `text` cut at a word boundary to at most `max` characters, with an ellipsis if cut
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max - 1).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > 0 => &cut[..space],
+        _ => &cut,
+    };
+    format!(
+        "{}…",
+        cut.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_cuts_at_word_boundaries() {
+        assert_eq!(truncate("short text", 20), "short text");
+        assert_eq!(truncate("one two, three four", 14), "one two…");
+        assert_eq!(truncate("abcdefghij", 5), "abcd…");
+    }
+}
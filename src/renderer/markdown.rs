@@ -0,0 +1,544 @@
+//! Markdown serialization, for writing a modified AST back to a file.
+
+use crate::ast::{Alignment, Attributes, Inline, ListItem, Node, HIGHLIGHT_ATTRIBUTE};
+use crate::format::FormatConfig;
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// Choices between equivalent Markdown forms
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MarkdownStyle {
+    /// Spaces of indentation per list nesting level
+    list_indent: usize,
+    list_marker: char,
+    fence: &'static str,
+    /// Level-1 and level-2 headings as underlined (setext) headings
+    setext: bool,
+    pad_tables: bool,
+    /// Column at which running text is wrapped; 0 for none
+    wrap_width: usize,
+}
+
+impl MarkdownStyle {
+    /// The style of [`to_markdown`](crate::to_markdown): `-` markers, backtick fences,
+    /// `#` headings, unpadded tables, and one line per block
+    pub(crate) fn plain(list_indent: usize) -> Self {
+        Self {
+            list_indent,
+            list_marker: '-',
+            fence: "```",
+            setext: false,
+            pad_tables: false,
+            wrap_width: 0,
+        }
+    }
+
+    /// The style configured for `md-parser fmt`
+    pub(crate) fn formatted(list_indent: usize, config: &FormatConfig) -> Self {
+        Self {
+            list_indent,
+            list_marker: config.list_marker.as_char(),
+            fence: config.fence.as_str(),
+            setext: config.headings == crate::format::HeadingMarkup::Setext,
+            pad_tables: config.pad_tables,
+            wrap_width: config.wrap_width,
+        }
+    }
+
+    /// Copy for content written behind a prefix of `columns` columns, such as `> `
+    fn narrowed(self, columns: usize) -> Self {
+        Self {
+            wrap_width: wrap_width_after(self.wrap_width, columns),
+            ..self
+        }
+    }
+}
+
+/// Wrap width left after a prefix of `columns` columns; never below 20 columns, so deep
+/// nesting does not put every word on its own line
+fn wrap_width_after(wrap_width: usize, columns: usize) -> usize {
+    if wrap_width == 0 {
+        0
+    } else {
+        wrap_width.saturating_sub(columns).max(20)
+    }
+}
+
+/// `text` broken at single spaces into lines of at most `width` columns; unbroken when
+/// `width` is 0
+///
+/// Spaces inside the `unbreakable` byte ranges are kept. A line never starts with a word
+/// that could be read as block markup (`-`, `1.`, `#`, `>`, `Figure:`), and never ends
+/// in a `\`, which would make a hard line break. A word longer than `width` gets a line
+/// of its own.
+fn wrap(text: &str, width: usize, unbreakable: &[Range<usize>]) -> Vec<String> {
+    if width == 0 || text.width() <= width {
+        return vec![text.to_string()];
+    }
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut offset: usize = 0;
+    for (idx, word) in text.split(' ').enumerate() {
+        let space = offset.saturating_sub(1);
+        offset += word.len() + 1;
+        let breakable = idx > 0
+            && !unbreakable.iter().any(|range| range.contains(&space))
+            && !line.is_empty()
+            && !line.ends_with([' ', '\\'])
+            && can_start_line(word);
+        if breakable && line.width() + 1 + word.width() > width {
+            lines.push(std::mem::take(&mut line));
+        } else if idx > 0 {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    lines
+}
+
+/// Whether a wrapped line may start with `word` without being read as something other
+/// than a paragraph continuation
+fn can_start_line(word: &str) -> bool {
+    if matches!(word, "Figure:" | "Table:") || word.contains("]:") {
+        return false;
+    }
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_digit() => !word
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .starts_with(['.', ')']),
+        Some(c) if c.is_alphabetic() => true,
+        Some('(' | '"' | '\'' | '*' | '_' | '[' | '`') => {
+            chars.next().is_some_and(char::is_alphanumeric)
+        }
+        _ => false,
+    }
+}
+
+fn render_inlines(inlines: &[Inline]) -> String {
+    inlines.iter().map(render_inline).collect()
+}
+
+fn render_inline(inline: &Inline) -> String {
+    match inline {
+        Inline::Text { content } => content.to_string(),
+        Inline::Bold { content } => format!("**{}**", render_inlines(content)),
+        Inline::Italic { content } => format!("*{}*", render_inlines(content)),
+        Inline::Strikethrough { content } => format!("~~{}~~", render_inlines(content)),
+        Inline::Highlight { content } => format!("=={}==", render_inlines(content)),
+        Inline::Spoiler { content } => format!("||{}||", render_inlines(content)),
+        Inline::Code { content } => {
+            // Backticks inside the code need a longer delimiter than their longest run
+            let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            if longest_run > 0 {
+                let fence = "`".repeat(longest_run + 1);
+                format!("{} {} {}", fence, content, fence)
+            } else {
+                format!("`{}`", content)
+            }
+        }
+        Inline::Link { text, url } => format!("[{}]({})", render_inlines(text), url),
+        Inline::Image {
+            alt,
+            url,
+            attributes,
+        } => format!("![{}]({}){}", alt, url, attribute_block(attributes)),
+        Inline::Span {
+            content,
+            attributes,
+        } => format!(
+            "[{}]{}",
+            render_inlines(content),
+            attribute_block(attributes)
+        ),
+        Inline::Hashtag { tag } => format!("#{}", tag),
+        Inline::Mention { user } => format!("@{}", user),
+        Inline::LineBreak => "<br>".to_string(),
+        Inline::Comment { content } => format!("<!-- {} -->", content),
+        Inline::Directive {
+            name,
+            content,
+            attributes,
+        } => format!(
+            ":{}[{}]{}",
+            name,
+            render_inlines(content),
+            attribute_block(attributes)
+        ),
+    }
+}
+
+/// Attribute list (`{#id .class key=val}`), empty when there are no attributes
+fn attribute_block(attributes: &Attributes) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let mut tokens = Vec::new();
+    if let Some(id) = &attributes.id {
+        tokens.push(format!("#{}", id));
+    }
+    for class in &attributes.classes {
+        tokens.push(format!(".{}", class));
+    }
+    for (key, value) in &attributes.pairs {
+        if value.is_empty() || value.contains(char::is_whitespace) {
+            tokens.push(format!("{}=\"{}\"", key, value));
+        } else {
+            tokens.push(format!("{}={}", key, value));
+        }
+    }
+    format!("{{{}}}", tokens.join(" "))
+}
+
+/// List items at nesting `depth`, indented by `list_indent` spaces per level
+///
+/// Wrapped lines of an item are indented to line up with the text after its marker.
+fn render_list_items(
+    items: &[ListItem],
+    ordered: bool,
+    depth: usize,
+    style: &MarkdownStyle,
+    out: &mut String,
+) {
+    let pad = " ".repeat(depth * style.list_indent);
+    for (idx, item) in items.iter().enumerate() {
+        let mut marker = if ordered {
+            format!("{}. ", idx + 1)
+        } else {
+            format!("{} ", style.list_marker)
+        };
+        let continuation = format!("{}{}", pad, " ".repeat(marker.len()));
+        match item.checked {
+            Some(true) => marker.push_str("[x] "),
+            Some(false) => marker.push_str("[ ] "),
+            None => {}
+        }
+        // Each line of an item is parsed on its own, so only plain text may be broken
+        let mut text = String::new();
+        let mut unbreakable = Vec::new();
+        for inline in &item.content {
+            let start = text.len();
+            text.push_str(&render_inline(inline));
+            if !matches!(inline, Inline::Text { .. }) {
+                unbreakable.push(start..text.len());
+            }
+        }
+        let width = wrap_width_after(style.wrap_width, pad.len() + marker.len());
+        for (line_idx, line) in wrap(&text, width, &unbreakable).iter().enumerate() {
+            let mut line = if line_idx == 0 {
+                format!("{}{}{}", pad, marker, line)
+            } else {
+                format!("{}{}", continuation, line)
+            };
+            // `- [ ]` with no text would otherwise end in a space
+            line.truncate(line.trim_end_matches(' ').len());
+            out.push_str(&line);
+            out.push('\n');
+        }
+        render_nested_blocks(&item.blocks, &continuation, style, out);
+        render_list_items(&item.children, ordered, depth + 1, style, out);
+    }
+}
+
+/// Block content of a list item or blockquote, each line after `prefix`
+///
+/// Blocks follow each other without blank lines, which would end the item or quote.
+fn render_nested_blocks(blocks: &[Node], prefix: &str, style: &MarkdownStyle, out: &mut String) {
+    let style = style.narrowed(prefix.len());
+    for block in blocks {
+        for line in render_node(block, &style).lines() {
+            if line.is_empty() {
+                out.push_str(prefix.trim_end());
+            } else {
+                out.push_str(prefix);
+                out.push_str(line);
+            }
+            out.push('\n');
+        }
+    }
+}
+
+/// Table cell text, with pipes escaped so they do not split the cell
+fn render_cell(cell: &[Inline]) -> String {
+    render_inlines(cell).replace('|', "\\|")
+}
+
+/// Table rows, the delimiter row second; with `pad`, cells are padded to their column's
+/// widest cell and aligned like the column
+fn render_table(
+    headers: &[Vec<Inline>],
+    rows: &[Vec<Vec<Inline>>],
+    alignments: &[Option<Alignment>],
+    pad: bool,
+) -> String {
+    let render_cells = |cells: &[Vec<Inline>]| -> Vec<String> {
+        cells.iter().map(|cell| render_cell(cell)).collect()
+    };
+    let headers = render_cells(headers);
+    let rows: Vec<Vec<String>> = rows.iter().map(|row| render_cells(row)).collect();
+    let alignment = |column: usize| alignments.get(column).copied().flatten();
+
+    let mut widths: Vec<usize> = headers.iter().map(|cell| cell.width().max(3)).collect();
+    if pad {
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.width());
+            }
+        }
+    }
+    let separators: Vec<String> = (0..headers.len())
+        .map(|column| {
+            let dashes = if pad { widths[column] } else { 3 };
+            match alignment(column) {
+                Some(Alignment::Left) => format!(":{}", "-".repeat(dashes.max(4) - 1)),
+                Some(Alignment::Center) => format!(":{}:", "-".repeat(dashes.max(5) - 2)),
+                Some(Alignment::Right) => format!("{}:", "-".repeat(dashes.max(4) - 1)),
+                None => "-".repeat(dashes),
+            }
+        })
+        .collect();
+    if pad {
+        for (width, separator) in widths.iter_mut().zip(&separators) {
+            *width = (*width).max(separator.len());
+        }
+    }
+
+    let render_row = |cells: &[String]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(column, cell)| match widths.get(column).filter(|_| pad) {
+                Some(&width) => align_cell(cell, width, alignment(column)),
+                None => cell.clone(),
+            })
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let mut out = render_row(&headers);
+    out.push_str(&format!("| {} |\n", separators.join(" | ")));
+    for row in &rows {
+        out.push_str(&render_row(row));
+    }
+    out
+}
+
+/// `cell` padded with spaces to `width` columns on the side(s) its alignment calls for
+fn align_cell(cell: &str, width: usize, alignment: Option<Alignment>) -> String {
+    let fill = width.saturating_sub(cell.width());
+    match alignment {
+        Some(Alignment::Right) => format!("{}{}", " ".repeat(fill), cell),
+        Some(Alignment::Center) => format!(
+            "{}{}{}",
+            " ".repeat(fill / 2),
+            cell,
+            " ".repeat(fill - fill / 2)
+        ),
+        _ => format!("{}{}", cell, " ".repeat(fill)),
+    }
+}
+
+/// Body of a `:::` block: the rendered nodes followed by the closing fence
+fn fenced_block(opening: String, content: &[Node], style: &MarkdownStyle) -> String {
+    let opening = opening.trim_end();
+    let body = render_markdown(content, style);
+    if body.is_empty() {
+        format!("{}\n:::\n", opening)
+    } else {
+        format!("{}\n{}:::\n", opening, body)
+    }
+}
+
+fn render_heading(
+    level: u8,
+    content: &[Inline],
+    attributes: &Attributes,
+    style: &MarkdownStyle,
+) -> String {
+    let mut text = render_inlines(content);
+    if style.setext && level <= 2 && attributes.is_empty() && !text.trim().is_empty() {
+        let underline = if level == 1 { "=" } else { "-" };
+        return format!("{}\n{}\n", text, underline.repeat(text.width().max(3)));
+    }
+    // Trailing `#`s after a space would be read back as a closing sequence
+    let kept = text.trim_end_matches('#').len();
+    if kept < text.len() && (kept == 0 || text[..kept].ends_with(char::is_whitespace)) {
+        text.insert(kept, '\\');
+    }
+    let mut out = format!("{} {}", "#".repeat(level as usize), text);
+    if !attributes.is_empty() {
+        out.push(' ');
+        out.push_str(&attribute_block(attributes));
+    }
+    out.push('\n');
+    out
+}
+
+fn render_node(node: &Node, style: &MarkdownStyle) -> String {
+    match node {
+        Node::Heading {
+            level,
+            content,
+            attributes,
+        } => render_heading(*level, content, attributes, style),
+        Node::Paragraph { content } => {
+            let text = render_inlines(content);
+            // A paragraph starting with `#` would be read back as a heading, unless it
+            // starts with a hashtag, which can only be read back with hashtags enabled
+            let hashtag = matches!(content.first(), Some(Inline::Hashtag { .. }));
+            let escape = if text.starts_with('#') && !hashtag {
+                "\\"
+            } else {
+                ""
+            };
+            let mut out = String::new();
+            for line in wrap(&format!("{}{}", escape, text), style.wrap_width, &[]) {
+                out.push_str(&line);
+                out.push('\n');
+            }
+            out
+        }
+        Node::UnorderedList { items } => {
+            let mut out = String::new();
+            render_list_items(items, false, 0, style, &mut out);
+            out
+        }
+        Node::OrderedList { items } => {
+            let mut out = String::new();
+            render_list_items(items, true, 0, style, &mut out);
+            out
+        }
+        Node::CodeBlock {
+            lang,
+            code,
+            attributes,
+        } => {
+            let mut info = lang.clone().unwrap_or_default();
+            if !attributes.is_empty() {
+                if !info.is_empty() {
+                    info.push(' ');
+                }
+                // Written in the short `{1,3-5}` form when it is the only attribute
+                match attributes.get(HIGHLIGHT_ATTRIBUTE) {
+                    Some(lines)
+                        if attributes.pairs.len() == 1
+                            && attributes.id.is_none()
+                            && attributes.classes.is_empty() =>
+                    {
+                        info.push_str(&format!("{{{}}}", lines));
+                    }
+                    _ => info.push_str(&attribute_block(attributes)),
+                }
+            }
+            format!("{fence}{}\n{}\n{fence}\n", info, code, fence = style.fence)
+        }
+        // Inline `%%{init}%%` settings were merged into the config and are not written back
+        Node::MermaidDiagram { diagram, name, .. } => match name {
+            Some(name) => format!(
+                "{fence}mermaid name={}\n{}\n{fence}\n",
+                name,
+                diagram,
+                fence = style.fence
+            ),
+            None => format!(
+                "{fence}mermaid\n{}\n{fence}\n",
+                diagram,
+                fence = style.fence
+            ),
+        },
+        Node::DiagramRef { name, .. } => format!("{{{{diagram:{}}}}}\n", name),
+        Node::Table {
+            headers,
+            rows,
+            alignments,
+            caption,
+            ..
+        } => {
+            let mut out = render_table(headers, rows, alignments, style.pad_tables);
+            if let Some(caption) = caption {
+                out.push_str(&format!("\nTable: {}\n", render_inlines(caption)));
+            }
+            out
+        }
+        Node::Blockquote {
+            level,
+            content,
+            blocks,
+        } => {
+            let prefix = format!("{} ", ">".repeat(*level as usize));
+            let width = wrap_width_after(style.wrap_width, prefix.len());
+            let mut out = String::new();
+            if !content.is_empty() || blocks.is_empty() {
+                for line in wrap(&render_inlines(content), width, &[]) {
+                    out.push_str(&format!("{}{}\n", prefix, line));
+                }
+            }
+            render_nested_blocks(blocks, &prefix, style, &mut out);
+            out
+        }
+        Node::HorizontalRule { .. } => "---\n".to_string(),
+        Node::Admonition {
+            kind,
+            title,
+            content,
+        } => {
+            let mut out = format!("> [!{}]", kind.as_str().to_uppercase());
+            if let Some(title) = title {
+                out.push(' ');
+                out.push_str(&render_inlines(title));
+            }
+            out.push('\n');
+            for line in render_markdown(content, &style.narrowed(2)).lines() {
+                if line.is_empty() {
+                    out.push_str(">\n");
+                } else {
+                    out.push_str(&format!("> {}\n", line));
+                }
+            }
+            out
+        }
+        Node::Details { summary, content } => fenced_block(
+            format!(":::details {}", render_inlines(summary)),
+            content,
+            style,
+        ),
+        Node::Container {
+            attributes,
+            children,
+        } => fenced_block(
+            format!("::: {}", attribute_block(attributes)),
+            children,
+            style,
+        ),
+        Node::Section {
+            level,
+            heading,
+            attributes,
+            children,
+        } => {
+            let mut out = render_heading(*level, heading, attributes, style);
+            let body = render_markdown(children, style);
+            if !body.is_empty() {
+                out.push('\n');
+                out.push_str(&body);
+            }
+            out
+        }
+        Node::Figure {
+            content, caption, ..
+        } => format!(
+            "{}\nFigure: {}\n",
+            render_markdown(content, style),
+            render_inlines(caption)
+        ),
+        Node::Comment { content } => format!("<!-- {} -->\n", content),
+    }
+}
+
+/// Serialize AST nodes back to Markdown in `style`, one blank line between blocks
+pub(crate) fn render_markdown(ast: &[Node], style: &MarkdownStyle) -> String {
+    ast.iter()
+        .map(|node| render_node(node, style))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,99 @@
+//! Build-time Mermaid rendering with an image cache.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::mermaid_cli;
+use crate::stable_hash::fnv1a;
+
+/// Longest a single `mmdc` render may take before the diagram falls back to the client
+const RENDER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Render a diagram to SVG with `mmdc`, reusing a cached SVG when one exists
+///
+/// Cached files are named after a hash of the theme and the diagram source, so an
+/// edited diagram or a new theme renders again while unchanged ones are read from
+/// `cache_dir`. Returns `None` if `mmdc` is not installed or fails; callers fall back
+/// to client-side rendering.
+pub(super) fn prerender(diagram: &str, theme: &str, cache_dir: Option<&Path>) -> Option<String> {
+    let svg = cached(diagram, theme, cache_dir, "svg", || {
+        mermaid_cli::render_svg(diagram, &["-t", theme], RENDER_TIMEOUT)
+            .ok()
+            .map(String::into_bytes)
+    })?;
+    String::from_utf8(svg).ok()
+}
+
+/// Render a diagram to a PNG image at twice its natural size, sharing the SVG cache directory
+#[cfg(feature = "docx")]
+pub(super) fn prerender_png(
+    diagram: &str,
+    theme: &str,
+    cache_dir: Option<&Path>,
+) -> Option<Vec<u8>> {
+    cached(diagram, theme, cache_dir, "png", || {
+        let args = ["-t", theme, "-s", "2", "-b", "white"];
+        mermaid_cli::render_png(diagram, &args, RENDER_TIMEOUT).ok()
+    })
+}
+
+/// Read `<cache_dir>/<key>.<extension>`, or render and store it
+fn cached(
+    diagram: &str,
+    theme: &str,
+    cache_dir: Option<&Path>,
+    extension: &str,
+    render: impl FnOnce() -> Option<Vec<u8>>,
+) -> Option<Vec<u8>> {
+    let cached =
+        cache_dir.map(|dir| dir.join(format!("{}.{}", cache_key(diagram, theme), extension)));
+    if let Some(bytes) = cached.as_ref().and_then(|path| fs::read(path).ok()) {
+        return Some(bytes);
+    }
+
+    let bytes = render()?;
+    if let (Some(dir), Some(path)) = (cache_dir, &cached) {
+        // A cache that cannot be written only costs a re-render next time
+        let _ = fs::create_dir_all(dir).and_then(|()| fs::write(path, &bytes));
+    }
+    Some(bytes)
+}
+
+/// Cache file stem: 64-bit FNV-1a of the theme and diagram, in hex
+fn cache_key(diagram: &str, theme: &str) -> String {
+    format!(
+        "{:016x}",
+        fnv1a(theme.bytes().chain([0]).chain(diagram.bytes()))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_depends_on_theme_and_diagram() {
+        let key = cache_key("graph TD\n    A-->B", "default");
+        assert_eq!(key.len(), 16);
+        assert_eq!(key, cache_key("graph TD\n    A-->B", "default"));
+        assert_ne!(key, cache_key("graph TD\n    A-->B", "dark"));
+        assert_ne!(key, cache_key("graph TD\n    A-->C", "default"));
+    }
+
+    #[test]
+    fn test_prerender_reads_cached_svg() {
+        let dir = std::env::temp_dir().join(format!("md-parser-svg-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let diagram = "graph LR\n    X-->Y";
+        fs::write(
+            dir.join(format!("{}.svg", cache_key(diagram, "forest"))),
+            "<svg>cached</svg>",
+        )
+        .unwrap();
+
+        let svg = prerender(diagram, "forest", Some(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(svg.as_deref(), Some("<svg>cached</svg>"));
+    }
+}
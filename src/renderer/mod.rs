@@ -0,0 +1,1246 @@
+//! HTML rendering logic.
+
+mod asciidoc;
+mod diff;
+#[cfg(feature = "docx")]
+mod docx;
+mod epub;
+mod feed;
+mod head;
+mod markdown;
+mod mermaid_svg;
+mod output_style;
+#[cfg(feature = "http")]
+mod remote_images;
+mod rst;
+mod slides;
+mod zip;
+
+pub(crate) use asciidoc::render_asciidoc;
+pub(crate) use diff::render_diff_fragment;
+#[cfg(feature = "docx")]
+pub(crate) use docx::render_docx;
+#[cfg(feature = "docx")]
+pub use docx::DocxOptions;
+pub(crate) use epub::render_epub;
+pub use epub::{EpubChapter, EpubOptions};
+pub use feed::{FeedItem, FeedMermaid, FeedOptions};
+pub(crate) use markdown::{render_markdown, MarkdownStyle};
+pub(crate) use rst::render_rst;
+pub(crate) use slides::render_slides;
+pub use slides::SlideOptions;
+
+use crate::annotations::Annotations;
+use crate::ast::{
+    for_each_node, parse_line_ranges, plain_text, standalone_image, Alignment, Attributes, Inline,
+    ListItem, Node, ValidationStatus, HIGHLIGHT_ATTRIBUTE, LINE_NUMBERS_CLASS,
+};
+use crate::config::{CommentMode, CopyButton, MermaidScript, RendererConfig};
+use crate::node_path::NodePath;
+use crate::transform;
+use output_style::restyle_html;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{create_dir_all, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// Escape HTML special characters
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Check whether a URL should be left alone by base URL resolution
+///
+/// Absolute URLs (`https://…`, `mailto:…`, `//host/…`) and fragment-only links (`#id`)
+/// already point somewhere specific.
+pub(crate) fn is_absolute_url(url: &str) -> bool {
+    if url.starts_with("//") || url.starts_with('#') {
+        return true;
+    }
+    match url.find(':') {
+        Some(colon) => {
+            let scheme = &url[..colon];
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+/// Apply the configured URL rewriter and base URL to a link or image target
+fn resolve_url(url: &str, config: &RendererConfig) -> String {
+    let rewritten = match &config.url_rewriter {
+        Some(rewriter) => rewriter.rewrite(url),
+        None => url.to_string(),
+    };
+
+    match &config.base_url {
+        Some(base) if !is_absolute_url(&rewritten) => format!(
+            "{}/{}",
+            base.trim_end_matches('/'),
+            rewritten.trim_start_matches('/')
+        ),
+        _ => rewritten,
+    }
+}
+
+/// Host of an `http(s)://` or protocol-relative URL, lowercased and without port
+fn url_host(url: &str) -> Option<String> {
+    let lower = url.to_ascii_lowercase();
+    let rest = ["https://", "http://", "//"]
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+/// `target`, `rel`, and `class` attributes for a link to `href` that leaves the site
+fn external_link_attributes(href: &str, config: &RendererConfig) -> String {
+    let links = &config.external_links;
+    if !links.new_tab && links.rel.is_none() && links.class.is_none() {
+        return String::new();
+    }
+    let Some(host) = url_host(href) else {
+        return String::new();
+    };
+    let site = links.site.as_deref().or(config.base_url.as_deref());
+    // A bare host name is read as a protocol-relative URL
+    let site_host =
+        site.and_then(|site| url_host(site).or_else(|| url_host(&format!("//{}", site))));
+    if site_host.as_deref() == Some(host.as_str()) {
+        return String::new();
+    }
+    let mut attributes = String::new();
+    if let Some(class) = &links.class {
+        attributes.push_str(&format!(" class=\"{}\"", escape_html(class)));
+    }
+    if links.new_tab {
+        attributes.push_str(" target=\"_blank\"");
+    }
+    if let Some(rel) = &links.rel {
+        attributes.push_str(&format!(" rel=\"{}\"", escape_html(rel)));
+    }
+    attributes
+}
+
+/// Opening tag of a spoiler, which the page styles hide until it is clicked or focused
+pub(crate) const SPOILER_OPEN: &str = "<span class=\"spoiler\" tabindex=\"0\">";
+
+/// Render inline elements to HTML
+fn render_inline(inline: &Inline, config: &RendererConfig) -> String {
+    if let Some(html) = config
+        .custom_renderer
+        .as_ref()
+        .and_then(|custom| custom.render_inline(inline, config))
+    {
+        return html;
+    }
+    match inline {
+        Inline::Text { content } => escape_html(content),
+        Inline::Bold { content } => {
+            let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+            format!("<strong>{}</strong>", inner)
+        }
+        Inline::Italic { content } => {
+            let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+            format!("<em>{}</em>", inner)
+        }
+        Inline::Strikethrough { content } => {
+            let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+            format!("<del>{}</del>", inner)
+        }
+        Inline::Highlight { content } => {
+            let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+            format!("<mark>{}</mark>", inner)
+        }
+        Inline::Spoiler { content } => {
+            let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+            format!("{}{}</span>", SPOILER_OPEN, inner)
+        }
+        Inline::Link { text, url } => {
+            let link_text: String = text.iter().map(|i| render_inline(i, config)).collect();
+            let href = resolve_url(url, config);
+            format!(
+                "<a href=\"{}\"{}>{}</a>",
+                escape_html(&href),
+                external_link_attributes(&href, config),
+                link_text
+            )
+        }
+        Inline::Image {
+            alt,
+            url,
+            attributes,
+        } => {
+            let src = resolve_url(url, config);
+            format!(
+                "<img src=\"{}\" alt=\"{}\"{} />",
+                escape_html(&src),
+                escape_html(alt),
+                render_image_attributes(attributes)
+            )
+        }
+        Inline::Span {
+            content,
+            attributes,
+        } => {
+            let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+            format!(
+                "<span{}>{}</span>",
+                render_attributes(None, attributes),
+                inner
+            )
+        }
+        Inline::Code { content } => {
+            format!("<code>{}</code>", escape_html(content))
+        }
+        Inline::Hashtag { tag } => render_tag(
+            "hashtag",
+            '#',
+            tag,
+            config.hashtag_url.as_deref(),
+            "{tag}",
+            config,
+        ),
+        Inline::Mention { user } => render_tag(
+            "mention",
+            '@',
+            user,
+            config.mention_url.as_deref(),
+            "{user}",
+            config,
+        ),
+        Inline::LineBreak => String::from("<br>"),
+        Inline::Comment { content } => render_comment(content, "span", config),
+        Inline::Directive {
+            name,
+            content,
+            attributes,
+        } => {
+            let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+            format!(
+                "<span{}>{}</span>",
+                render_attributes(Some(name), attributes),
+                inner
+            )
+        }
+    }
+}
+
+/// A hashtag or mention, linked when a URL template is configured
+fn render_tag(
+    class: &str,
+    marker: char,
+    name: &str,
+    url_template: Option<&str>,
+    placeholder: &str,
+    config: &RendererConfig,
+) -> String {
+    let text = escape_html(&format!("{}{}", marker, name));
+    match url_template {
+        Some(template) => {
+            let href = resolve_url(&template.replace(placeholder, name), config);
+            format!(
+                "<a class=\"{}\" href=\"{}\">{}</a>",
+                class,
+                escape_html(&href),
+                text
+            )
+        }
+        None => format!("<span class=\"{}\">{}</span>", class, text),
+    }
+}
+
+/// Render `id`, `class`, and `data-*` HTML attributes, each with a leading space
+///
+/// `extra_class` is emitted before the classes from `attributes`.
+pub(crate) fn render_attributes(extra_class: Option<&str>, attributes: &Attributes) -> String {
+    let mut html = String::new();
+    if let Some(id) = &attributes.id {
+        html.push_str(&format!(" id=\"{}\"", escape_html(id)));
+    }
+    let classes: Vec<&str> = extra_class
+        .into_iter()
+        .chain(attributes.classes.iter().map(String::as_str))
+        .collect();
+    if !classes.is_empty() {
+        html.push_str(&format!(" class=\"{}\"", escape_html(&classes.join(" "))));
+    }
+    for (key, value) in &attributes.pairs {
+        html.push_str(&format!(
+            " data-{}=\"{}\"",
+            escape_html(key),
+            escape_html(value)
+        ));
+    }
+    html
+}
+
+/// Code with each line in a `<span class="line">`, numbered from `start` if given
+///
+/// Lines inside `highlighted` (1-based, counted from the first line of the block)
+/// also get the `highlighted` class.
+fn render_code_lines(code: &str, start: Option<usize>, highlighted: &[(usize, usize)]) -> String {
+    code.split('\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            let class = if highlighted
+                .iter()
+                .any(|&(first, last)| (first..=last).contains(&(idx + 1)))
+            {
+                "line highlighted"
+            } else {
+                "line"
+            };
+            let number = start
+                .map(|start| format!(" data-line=\"{}\"", start + idx))
+                .unwrap_or_default();
+            format!(
+                "<span class=\"{}\"{}>{}</span>",
+                class,
+                number,
+                escape_html(line)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Image attributes: `width` and `height` are emitted as such, other pairs as `data-*`
+fn render_image_attributes(attributes: &Attributes) -> String {
+    let mut size = String::new();
+    let mut rest = attributes.clone();
+    for key in ["width", "height"] {
+        if let Some(value) = rest.pairs.remove(key) {
+            size.push_str(&format!(" {}=\"{}\"", key, escape_html(&value)));
+        }
+    }
+    format!("{}{}", render_attributes(None, &rest), size)
+}
+
+/// Render a list item and its nested children recursively
+fn render_list_item(item: &ListItem, config: &RendererConfig) -> String {
+    let content: String = item
+        .content
+        .iter()
+        .map(|i| render_inline(i, config))
+        .collect();
+
+    // Render checkbox for task list items
+    let checkbox = match (item.checked, config.interactive_tasks) {
+        (Some(true), false) => "<input type=\"checkbox\" disabled checked",
+        (Some(false), false) => "<input type=\"checkbox\" disabled",
+        (Some(true), true) => "<input type=\"checkbox\" checked",
+        (Some(false), true) => "<input type=\"checkbox\"",
+        (None, _) => "",
+    };
+    // In accessible mode the item text labels the checkbox
+    let label = plain_text(&item.content);
+    let checkbox = match item.checked {
+        Some(_) if config.accessible && !label.trim().is_empty() => format!(
+            "{} aria-label=\"{}\"> ",
+            checkbox,
+            escape_html(label.trim())
+        ),
+        Some(_) => format!("{}> ", checkbox),
+        None => String::new(),
+    };
+
+    let mut html = format!("<li>{}{}", checkbox, content);
+    for block in &item.blocks {
+        html.push('\n');
+        html.push_str(&render_node(block, config));
+    }
+
+    // Render nested children if any
+    if !item.children.is_empty() {
+        html.push_str("<ul>");
+        for child in &item.children {
+            html.push_str(&render_list_item(child, config));
+        }
+        html.push_str("</ul>");
+    }
+
+    html.push_str("</li>");
+    html
+}
+
+/// Render a comment according to `RendererConfig::comments`
+///
+/// `element` wraps visible comments: `span` inline, `div` for block comments.
+fn render_comment(content: &str, element: &str, config: &RendererConfig) -> String {
+    match config.comments {
+        CommentMode::Strip => String::new(),
+        // `--` may not appear inside an HTML comment
+        CommentMode::Keep => format!("<!-- {} -->", content.replace("--", "- -")),
+        CommentMode::Visible => format!(
+            "<{} class=\"comment\">{}</{}>",
+            element,
+            escape_html(content),
+            element
+        ),
+    }
+}
+
+/// `style` attribute for a table cell in column `column`, empty without an alignment
+fn alignment_style(alignments: &[Option<Alignment>], column: usize) -> &'static str {
+    match alignments.get(column).copied().flatten() {
+        Some(Alignment::Left) => " style=\"text-align: left;\"",
+        Some(Alignment::Center) => " style=\"text-align: center;\"",
+        Some(Alignment::Right) => " style=\"text-align: right;\"",
+        None => "",
+    }
+}
+
+/// Render a single node to HTML
+fn render_node(node: &Node, config: &RendererConfig) -> String {
+    render_node_at(node, config, None)
+}
+
+/// Annotations to emit while rendering, with the path of the node being rendered
+type AnnotationScope<'a> = Option<(&'a Annotations, &'a NodePath)>;
+
+/// Render a node, adding its annotations (if `at` is given) to its element
+fn render_node_at(node: &Node, config: &RendererConfig, at: AnnotationScope) -> String {
+    let html = render_block(node, config, at);
+    match at.and_then(|(annotations, path)| annotations.get(path)) {
+        Some(metadata) => add_data_attributes(html, metadata),
+        None => html,
+    }
+}
+
+/// Render the `index`th child of the node at `at`
+fn render_child(
+    child: &Node,
+    index: usize,
+    config: &RendererConfig,
+    at: AnnotationScope,
+) -> String {
+    match at {
+        Some((annotations, path)) => {
+            render_node_at(child, config, Some((annotations, &path.child(index))))
+        }
+        None => render_node_at(child, config, None),
+    }
+}
+
+/// `html` with `data-*` attributes for `metadata` on its first element, or wrapped in a
+/// `<div>` carrying them if it does not start with one
+fn add_data_attributes(html: String, metadata: &HashMap<String, String>) -> String {
+    if html.is_empty() {
+        return html;
+    }
+    let mut attributes: Vec<(String, &str)> = metadata
+        .iter()
+        .map(|(key, value)| {
+            let name = key
+                .to_lowercase()
+                .chars()
+                .map(|c| match c {
+                    'a'..='z' | '0'..='9' | '-' | '_' | '.' => c,
+                    _ => '-',
+                })
+                .collect();
+            (name, value.as_str())
+        })
+        .collect();
+    attributes.sort();
+    let attributes: String = attributes
+        .iter()
+        .map(|(name, value)| format!(" data-{}=\"{}\"", name, escape_html(value)))
+        .collect();
+    let tag_name_end = html
+        .strip_prefix('<')
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_alphabetic()))
+        .and_then(|rest| rest.find([' ', '>', '/', '\n']))
+        .map(|end| end + 1);
+    match tag_name_end {
+        Some(end) => format!("{}{}{}", &html[..end], attributes, &html[end..]),
+        None => format!("<div{}>{}</div>", attributes, html),
+    }
+}
+
+/// Render a node's own markup, its children with their annotations
+fn render_block(node: &Node, config: &RendererConfig, at: AnnotationScope) -> String {
+    if let Some(html) = config
+        .custom_renderer
+        .as_ref()
+        .and_then(|custom| custom.render_node(node, config))
+    {
+        return html;
+    }
+    match node {
+        Node::Heading {
+            level,
+            content,
+            attributes,
+        } => render_heading(*level, content, attributes, config),
+        Node::Paragraph { content } => {
+            let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+            format!("<p>{}</p>", inner)
+        }
+        Node::UnorderedList { items } => {
+            let mut html = String::from("<ul>");
+            for item in items {
+                html.push_str(&render_list_item(item, config));
+            }
+            html.push_str("</ul>");
+            html
+        }
+        Node::OrderedList { items } => {
+            let mut html = String::from("<ol>");
+            for item in items {
+                html.push_str(&render_list_item(item, config));
+            }
+            html.push_str("</ol>");
+            html
+        }
+        Node::CodeBlock {
+            lang,
+            code,
+            attributes,
+        } => {
+            let lang_class = lang
+                .as_ref()
+                .map(|l| format!(" class=\"language-{}\"", escape_html(l)))
+                .unwrap_or_default();
+            let has_numbers_class = attributes
+                .classes
+                .iter()
+                .any(|class| class == LINE_NUMBERS_CLASS);
+            let numbered = config.code_line_numbers || has_numbers_class;
+            let highlighted = attributes
+                .get(HIGHLIGHT_ATTRIBUTE)
+                .and_then(parse_line_ranges)
+                .unwrap_or_default();
+            let body = if numbered || !highlighted.is_empty() {
+                let start = attributes
+                    .get("start")
+                    .and_then(|start| start.parse().ok())
+                    .unwrap_or(1);
+                render_code_lines(code, numbered.then_some(start), &highlighted)
+            } else {
+                escape_html(code)
+            };
+            let extra_class = (numbered && !has_numbers_class).then_some(LINE_NUMBERS_CLASS);
+            let pre = format!(
+                "<pre{}><code{}>{}</code></pre>",
+                render_attributes(extra_class, attributes),
+                lang_class,
+                body
+            );
+            match config.copy_button {
+                CopyButton::Off => pre,
+                CopyButton::Inline | CopyButton::Markup => format!(
+                    "<div class=\"code-block\"><button type=\"button\" class=\"copy-button\" \
+                     data-copied=\"{}\">{}</button>{}</div>",
+                    escape_html(&config.strings.copied),
+                    escape_html(&config.strings.copy),
+                    pre
+                ),
+            }
+        }
+        Node::MermaidDiagram {
+            diagram,
+            name,
+            config: mermaid_config,
+            validation_status,
+            warnings,
+        } => {
+            let escaped_diagram = escape_html(diagram);
+
+            // Named diagrams are anchored so references can link to the definition
+            let mut data_attrs = match name {
+                Some(name) => format!(" id=\"diagram-{}\"", escape_html(name)),
+                None => String::new(),
+            };
+
+            if config.prerender_mermaid
+                && !matches!(validation_status, ValidationStatus::Invalid { .. })
+            {
+                let theme = mermaid_config
+                    .as_ref()
+                    .and_then(|cfg| cfg.theme.as_deref())
+                    .unwrap_or("default");
+                let cache_dir = config
+                    .mermaid_cache_dir
+                    .as_deref()
+                    .map(std::path::Path::new);
+                if let Some(svg) = mermaid_svg::prerender(diagram, theme, cache_dir) {
+                    return format!("<div class=\"mermaid-svg\"{}>{}</div>", data_attrs, svg);
+                }
+            }
+
+            // Build data attributes for configuration
+            if let Some(cfg) = mermaid_config {
+                // Serialize config to JSON for data attribute
+                if let Ok(config_json) = serde_json::to_string(cfg) {
+                    data_attrs.push_str(&format!(
+                        " data-mermaid-config=\"{}\"",
+                        escape_html(&config_json)
+                    ));
+                }
+
+                // Also add individual attributes for easier access
+                if let Some(ref theme) = cfg.theme {
+                    data_attrs.push_str(&format!(" data-mermaid-theme=\"{}\"", escape_html(theme)));
+                }
+                if let Some(ref font_size) = cfg.font_size {
+                    data_attrs.push_str(&format!(
+                        " data-mermaid-font-size=\"{}\"",
+                        escape_html(font_size)
+                    ));
+                }
+                if let Some(ref font_family) = cfg.font_family {
+                    data_attrs.push_str(&format!(
+                        " data-mermaid-font-family=\"{}\"",
+                        escape_html(font_family)
+                    ));
+                }
+            }
+
+            // Add validation status as data attribute
+            let validation_attr = match validation_status {
+                ValidationStatus::Valid => " data-mermaid-valid=\"true\"",
+                ValidationStatus::Invalid { .. } => " data-mermaid-valid=\"false\"",
+                ValidationStatus::NotValidated => "",
+            };
+
+            // Build HTML with validation warnings as comments
+            let mut html = String::new();
+
+            // Add validation warning comments if present
+            if let ValidationStatus::Invalid { ref errors } = validation_status {
+                html.push_str("<!-- Mermaid validation errors:\n");
+                for error in errors {
+                    html.push_str(&format!("  - {}\n", escape_html(error)));
+                }
+                html.push_str("-->\n");
+            }
+
+            if !warnings.is_empty() {
+                html.push_str("<!-- Mermaid validation warnings:\n");
+                for warning in warnings {
+                    html.push_str(&format!("  - {}\n", escape_html(warning)));
+                }
+                html.push_str("-->\n");
+            }
+
+            html.push_str(&format!(
+                "<div class=\"mermaid\"{}{}>{}</div>",
+                data_attrs, validation_attr, escaped_diagram
+            ));
+
+            html
+        }
+        // References are replaced by `apply_transforms`; this shows them as written
+        Node::DiagramRef { name, .. } => format!("<p>{{{{diagram:{}}}}}</p>", escape_html(name)),
+        Node::Table {
+            headers,
+            rows,
+            alignments,
+            caption,
+            number,
+            ..
+        } => {
+            let mut html = match number {
+                Some(number) => format!("<table id=\"table-{}\">\n", number),
+                None => String::from("<table>\n"),
+            };
+            if let Some(caption) = caption {
+                let caption: String = caption.iter().map(|i| render_inline(i, config)).collect();
+                let label = match number {
+                    Some(number) => format!(
+                        "<span class=\"table-number\">{}</span> ",
+                        escape_html(&config.strings.table_label(*number))
+                    ),
+                    None => String::new(),
+                };
+                html.push_str(&format!("<caption>{}{}</caption>\n", label, caption));
+            }
+            html.push_str("<thead>\n<tr>");
+            for (i, header_cell) in headers.iter().enumerate() {
+                let alignment = alignment_style(alignments, i);
+                let cell_content: String = header_cell
+                    .iter()
+                    .map(|i| render_inline(i, config))
+                    .collect();
+                let scope = if config.accessible {
+                    " scope=\"col\""
+                } else {
+                    ""
+                };
+                html.push_str(&format!("<th{}{}>{}</th>", scope, alignment, cell_content));
+            }
+            html.push_str("</tr>\n</thead>\n<tbody>");
+            for row in rows {
+                html.push_str("<tr>");
+                for (i, cell) in row.iter().enumerate() {
+                    let alignment = alignment_style(alignments, i);
+                    let cell_content: String =
+                        cell.iter().map(|i| render_inline(i, config)).collect();
+                    html.push_str(&format!("<td{}>{}</td>", alignment, cell_content));
+                }
+                html.push_str("</tr>");
+            }
+            html.push_str("</tbody>\n</table>");
+            html
+        }
+        Node::Blockquote {
+            level,
+            content,
+            blocks,
+        } => {
+            let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+            // For nested blockquotes, nest multiple <blockquote> elements
+            let mut html = String::new();
+            for _ in 0..*level {
+                html.push_str("<blockquote>");
+            }
+            html.push_str(&inner);
+            for (index, child) in blocks.iter().enumerate() {
+                html.push('\n');
+                html.push_str(&render_child(child, index, config, at));
+            }
+            for _ in 0..*level {
+                html.push_str("</blockquote>");
+            }
+            html
+        }
+        Node::HorizontalRule { .. } => String::from("<hr>"),
+        Node::Admonition {
+            kind,
+            title,
+            content,
+        } => {
+            let title = match title {
+                Some(title) => title.iter().map(|i| render_inline(i, config)).collect(),
+                None => escape_html(config.strings.admonition_title(*kind)),
+            };
+            let mut html = format!(
+                "<div class=\"admonition admonition-{}\">\n<p class=\"admonition-title\">{}</p>",
+                kind.as_str(),
+                title
+            );
+            for (index, child) in content.iter().enumerate() {
+                html.push('\n');
+                html.push_str(&render_child(child, index, config, at));
+            }
+            html.push_str("\n</div>");
+            html
+        }
+        Node::Details { summary, content } => {
+            let summary: String = summary.iter().map(|i| render_inline(i, config)).collect();
+            let mut html = format!("<details>\n<summary>{}</summary>", summary);
+            for (index, child) in content.iter().enumerate() {
+                html.push('\n');
+                html.push_str(&render_child(child, index, config, at));
+            }
+            html.push_str("\n</details>");
+            html
+        }
+        Node::Comment { content } => render_comment(content, "div", config),
+        Node::Container {
+            attributes,
+            children,
+        } => {
+            let mut html = format!("<div{}>", render_attributes(None, attributes));
+            for (index, child) in children.iter().enumerate() {
+                html.push('\n');
+                html.push_str(&render_child(child, index, config, at));
+            }
+            html.push_str("\n</div>");
+            html
+        }
+        Node::Figure {
+            content,
+            caption,
+            number,
+        } => {
+            let mut html = match number {
+                Some(number) => format!("<figure id=\"figure-{}\">", number),
+                None => String::from("<figure>"),
+            };
+            for (index, child) in content.iter().enumerate() {
+                html.push('\n');
+                // The image is the figure's content, not a paragraph of its own
+                match child {
+                    Node::Paragraph { content } if standalone_image(content).is_some() => {
+                        html.extend(content.iter().map(|i| render_inline(i, config)))
+                    }
+                    child => html.push_str(&render_child(child, index, config, at)),
+                }
+            }
+            let caption: String = caption.iter().map(|i| render_inline(i, config)).collect();
+            let label = match number {
+                Some(number) => format!(
+                    "<span class=\"figure-number\">{}</span> ",
+                    escape_html(&config.strings.figure_label(*number))
+                ),
+                None => String::new(),
+            };
+            html.push_str(&format!(
+                "\n<figcaption>{}{}</figcaption>\n</figure>",
+                label, caption
+            ));
+            html
+        }
+        Node::Section {
+            level,
+            heading,
+            attributes,
+            children,
+        } => {
+            let mut html = String::from("<section>\n");
+            html.push_str(&render_heading(*level, heading, attributes, config));
+            for (index, child) in children.iter().enumerate() {
+                html.push('\n');
+                html.push_str(&render_child(child, index, config, at));
+            }
+            html.push_str("\n</section>");
+            html
+        }
+    }
+}
+
+fn render_heading(
+    level: u8,
+    content: &[Inline],
+    attributes: &Attributes,
+    config: &RendererConfig,
+) -> String {
+    let inner: String = content.iter().map(|i| render_inline(i, config)).collect();
+    format!(
+        "<h{}{}>{}</h{}>",
+        level,
+        render_attributes(None, attributes),
+        inner,
+        level
+    )
+}
+
+/// Apply the render-time transforms selected in the renderer config
+///
+/// Borrows the AST unchanged when no transform is enabled.
+fn apply_transforms<'a>(ast: &'a [Node], config: &RendererConfig) -> Cow<'a, [Node]> {
+    let mut nodes = Cow::Borrowed(ast);
+    // Resolve references first, so definitions in filtered-out sections still count
+    let mut has_references = false;
+    for_each_node(ast, &mut |node| {
+        has_references |= matches!(node, Node::DiagramRef { .. });
+    });
+    if has_references {
+        nodes = Cow::Owned(transform::resolve_diagram_references(&nodes));
+    }
+    if let Some(audience) = &config.audience {
+        nodes = Cow::Owned(transform::filter_audience(&nodes, audience));
+    }
+    if config.normalize_headings {
+        nodes = Cow::Owned(transform::normalize_heading_levels(&nodes));
+    }
+    if config.heading_offset != 0 {
+        nodes = Cow::Owned(transform::shift_heading_levels(
+            &nodes,
+            config.heading_offset,
+        ));
+    }
+    if config.number_headings {
+        nodes = Cow::Owned(transform::number_headings(
+            &nodes,
+            config.number_headings_from,
+        ));
+    }
+    if config.number_figures {
+        nodes = Cow::Owned(transform::number_figures(&nodes));
+    }
+    if config.number_tables {
+        nodes = Cow::Owned(transform::number_tables(&nodes));
+    }
+    #[cfg(feature = "http")]
+    if config.localize_images {
+        nodes = Cow::Owned(remote_images::localize_images(&nodes, config));
+    }
+    nodes
+}
+
+/// Serialize an AST back to Markdown, e.g. after [`set_task_state`](crate::set_task_state)
+///
+/// Re-parsing the output with the parser features the AST was built with gives the
+/// same nodes, apart from source spans, list markers and numbering, and inline
+/// Mermaid `%%{init}%%` settings. Nested list items are indented by two spaces;
+/// [`Parser::to_markdown`](crate::Parser::to_markdown) uses the parser's `list_indent`.
+pub fn to_markdown(nodes: &[Node]) -> String {
+    render_markdown(nodes, &MarkdownStyle::plain(2))
+}
+
+/// Render the AST to an HTML fragment (no header, styles, or footer)
+///
+/// Applies the same render-time transforms as the full document renderer.
+pub(crate) fn render_fragment(ast: &[Node], config: &RendererConfig) -> String {
+    render_nodes(&apply_transforms(ast, config), config)
+}
+
+/// Render nodes one per line, without applying transforms
+fn render_nodes(nodes: &[Node], config: &RendererConfig) -> String {
+    render_annotated_nodes(nodes, config, None)
+}
+
+/// Render nodes one per line with the metadata of `annotations` as `data-*` attributes
+fn render_annotated_nodes(
+    nodes: &[Node],
+    config: &RendererConfig,
+    annotations: Option<&Annotations>,
+) -> String {
+    let mut html = String::new();
+    for (index, node) in nodes.iter().enumerate() {
+        let path = NodePath::from(vec![index]);
+        // Stripped comments render to nothing
+        let rendered = render_node_at(node, config, annotations.map(|a| (a, &path)));
+        if !rendered.is_empty() {
+            html.push_str(&rendered);
+            html.push('\n');
+        }
+    }
+    html
+}
+
+/// Generate a complete HTML document from the AST.
+///
+/// Loads header, styles, body start, and footer from configured paths, then renders each node.
+/// When `config.audience` is set, sections restricted to other audiences are left out;
+/// heading levels are normalized and shifted according to `config.heading_offset`.
+///
+/// # Errors
+///
+/// Returns an error if template files cannot be read
+pub(crate) fn render_to_html(
+    ast: &[Node],
+    config: &RendererConfig,
+) -> Result<String, Box<dyn Error>> {
+    let nodes = apply_transforms(ast, config);
+    let html = Templates::load(config)?.wrap(&render_nodes(&nodes, config), &nodes, config);
+    Ok(config
+        .line_ending
+        .apply(restyle_html(html, config.output_style)))
+}
+
+/// Wrap an HTML fragment in the configured header, styles, body start, and footer
+///
+/// The page title and description come from the config alone.
+///
+/// # Errors
+///
+/// Returns an error if template files cannot be read
+pub(crate) fn wrap_document(body: &str, config: &RendererConfig) -> Result<String, Box<dyn Error>> {
+    Ok(Templates::load(config)?.wrap(body, &[], config))
+}
+
+/// Page templates surrounding the rendered document
+#[derive(Debug, Clone)]
+struct Templates {
+    header: String,
+    styles: String,
+    body_start: String,
+    footer: String,
+    /// Script elements loading and starting Mermaid, added to pages with diagrams
+    mermaid: String,
+}
+
+impl Templates {
+    /// Read the configured template files, falling back to the built-in assets for
+    /// files that don't exist
+    fn load(config: &RendererConfig) -> io::Result<Self> {
+        let load = |path: &str, builtin: &str| {
+            if std::path::Path::new(path).exists() {
+                std::fs::read_to_string(path)
+            } else {
+                Ok(builtin.to_string())
+            }
+        };
+        Ok(Self {
+            header: load(
+                &config.html_header_path,
+                include_str!("../../assets/html_header.html"),
+            )?,
+            styles: load(
+                &config.styles_css_path,
+                include_str!("../../assets/styles.css"),
+            )?,
+            body_start: load(
+                &config.html_body_start_path,
+                include_str!("../../assets/html_body_start.html"),
+            )?,
+            footer: load(
+                &config.html_footer_path,
+                include_str!("../../assets/html_footer.html"),
+            )?,
+            mermaid: mermaid_loader(config)?,
+        })
+    }
+
+    /// The page around `body`, with the head filled in from `config` and the transformed
+    /// `nodes` it was rendered from
+    fn wrap(&self, body: &str, nodes: &[Node], config: &RendererConfig) -> String {
+        let mut html = String::new();
+        html.push_str(&head::fill_head(&self.header, nodes, config));
+        html.push_str(&format!("<style>\n{}\n</style>", self.styles));
+        html.push_str(&self.body_start);
+        if config.accessible {
+            html.push_str(&format!(
+                "<a class=\"skip-link\" href=\"#main-content\">{}</a>\n",
+                escape_html(&config.strings.skip_link)
+            ));
+            html.push_str("<main id=\"main-content\">\n");
+            html.push_str(body);
+            html.push_str("</main>\n");
+        } else {
+            html.push_str(body);
+        }
+        // Prerendered SVGs (`mermaid-svg`) need no script
+        if body.contains("class=\"mermaid\"") {
+            html.push_str(&self.mermaid);
+        }
+        if config.copy_button == CopyButton::Inline && body.contains("class=\"copy-button\"") {
+            html.push_str(COPY_BUTTON_SCRIPT);
+        }
+        if body.contains("class=\"spoiler\"") {
+            html.push_str(SPOILER_SCRIPT);
+        }
+        html.push_str(&self.footer);
+        html
+    }
+}
+
+/// Styles and script of code block copy buttons, added to pages that have them
+const COPY_BUTTON_SCRIPT: &str = r#"<style>
+.code-block {
+    position: relative;
+}
+.copy-button {
+    position: absolute;
+    top: 6px;
+    right: 6px;
+    padding: 2px 8px;
+    font-size: 12px;
+    cursor: pointer;
+    opacity: 0.8;
+}
+.copy-button:hover, .copy-button:focus-visible {
+    opacity: 1;
+}
+</style>
+<script>
+    document.addEventListener('click', (event) => {
+        const button = event.target.closest('.copy-button');
+        if (!button) {
+            return;
+        }
+        const code = button.parentElement.querySelector('code');
+        navigator.clipboard.writeText(code.innerText).then(() => {
+            const label = button.textContent;
+            button.textContent = button.dataset.copied;
+            setTimeout(() => { button.textContent = label; }, 1500);
+        });
+    });
+</script>
+"#;
+
+/// Script revealing a spoiler when it is clicked, added to pages that have spoilers
+const SPOILER_SCRIPT: &str = r#"<script>
+    document.addEventListener('click', (event) => {
+        const spoiler = event.target.closest('.spoiler');
+        if (spoiler) {
+            spoiler.classList.toggle('revealed');
+        }
+    });
+</script>
+"#;
+
+/// URL of a Mermaid release on jsDelivr
+pub(crate) fn mermaid_cdn_url(version: &str) -> String {
+    format!(
+        "https://cdn.jsdelivr.net/npm/mermaid@{}/dist/mermaid.min.js",
+        version
+    )
+}
+
+/// File name of the local Mermaid copy, relative to the page
+fn local_mermaid_file(config: &RendererConfig) -> String {
+    format!("mermaid-{}.min.js", config.mermaid_version)
+}
+
+/// `<script>` elements loading Mermaid as configured by `mermaid_script` and starting it
+///
+/// # Errors
+///
+/// Returns an error if the script must be inlined and `mermaid_script_path` cannot be read
+fn mermaid_loader(config: &RendererConfig) -> io::Result<String> {
+    let script = match config.mermaid_script {
+        MermaidScript::None => return Ok(String::new()),
+        MermaidScript::Cdn => format!(
+            "<script src=\"{}\"></script>",
+            escape_html(&mermaid_cdn_url(&config.mermaid_version))
+        ),
+        MermaidScript::Local => format!(
+            "<script src=\"{}\"></script>",
+            escape_html(&local_mermaid_file(config))
+        ),
+        MermaidScript::Inline => {
+            let source = std::fs::read_to_string(&config.mermaid_script_path).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!(
+                        "cannot read Mermaid script {}: {}",
+                        config.mermaid_script_path, e
+                    ),
+                )
+            })?;
+            // A `</script` in the bundle would end the element early
+            format!(
+                "<script>\n{}\n</script>",
+                source.replace("</script", "<\\/script")
+            )
+        }
+    };
+    Ok(format!(
+        "{}\n<script>\n    mermaid.initialize({{ startOnLoad: true, theme: 'default' }});\n</script>\n",
+        script
+    ))
+}
+
+/// Copy `mermaid_script_path` into the output directory for pages that load it locally
+///
+/// An existing copy is kept, since its name carries the pinned version.
+fn copy_local_mermaid(
+    html: &str,
+    output_dir: &std::path::Path,
+    config: &RendererConfig,
+) -> io::Result<()> {
+    let name = local_mermaid_file(config);
+    let target = output_dir.join(&name);
+    if config.mermaid_script != MermaidScript::Local
+        || target.exists()
+        || !html.contains(&format!("<script src=\"{}\">", escape_html(&name)))
+    {
+        return Ok(());
+    }
+    std::fs::copy(&config.mermaid_script_path, &target)
+        .map(|_| ())
+        .map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!(
+                    "cannot copy Mermaid script {}: {}",
+                    config.mermaid_script_path, e
+                ),
+            )
+        })
+}
+
+/// HTML renderer with its configuration and page templates loaded once
+///
+/// `Renderer` is `Send + Sync` and rendering takes `&self`, so a server can build one
+/// at startup and share it (e.g. in an `Arc`) across requests instead of reading the
+/// templates for every document.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    config: RendererConfig,
+    templates: Templates,
+}
+
+impl Renderer {
+    /// Build a renderer, reading the template files named in `config`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a configured template file exists but cannot be read
+    pub fn new(config: RendererConfig) -> io::Result<Self> {
+        let templates = Templates::load(&config)?;
+        Ok(Self { config, templates })
+    }
+
+    /// The configuration the renderer was built with
+    pub fn config(&self) -> &RendererConfig {
+        &self.config
+    }
+
+    /// Render the AST as a complete HTML document
+    pub fn render_html(&self, ast: &[Node]) -> String {
+        let nodes = apply_transforms(ast, &self.config);
+        let html = self
+            .templates
+            .wrap(&render_nodes(&nodes, &self.config), &nodes, &self.config);
+        self.config
+            .line_ending
+            .apply(restyle_html(html, self.config.output_style))
+    }
+
+    /// Render the AST as an HTML fragment, without the page templates
+    pub fn render_fragment(&self, ast: &[Node]) -> String {
+        let html = render_fragment(ast, &self.config);
+        self.config
+            .line_ending
+            .apply(restyle_html(html, self.config.output_style))
+    }
+
+    /// Render the AST as a complete HTML document, writing the metadata of each
+    /// annotated node as `data-*` attributes of its element
+    ///
+    /// Keys are lowercased, with characters other than letters, digits, `-`, `_`, and `.`
+    /// replaced by `-`. A node whose markup does not start with an element (a custom
+    /// renderer's plain text) is wrapped in a `<div>` for them. Paths are positions in
+    /// the document as rendered: with `audience` set, annotate the output of
+    /// [`filter_audience`](crate::filter_audience), as the other transforms leave nodes
+    /// where they are.
+    pub fn render_html_annotated(&self, ast: &[Node], annotations: &Annotations) -> String {
+        let nodes = apply_transforms(ast, &self.config);
+        let body = render_annotated_nodes(&nodes, &self.config, Some(annotations));
+        let html = self.templates.wrap(&body, &nodes, &self.config);
+        self.config
+            .line_ending
+            .apply(restyle_html(html, self.config.output_style))
+    }
+
+    /// Render the AST as an HTML fragment with annotations; see
+    /// [`Renderer::render_html_annotated`]
+    pub fn render_fragment_annotated(&self, ast: &[Node], annotations: &Annotations) -> String {
+        let nodes = apply_transforms(ast, &self.config);
+        let html = render_annotated_nodes(&nodes, &self.config, Some(annotations));
+        self.config
+            .line_ending
+            .apply(restyle_html(html, self.config.output_style))
+    }
+}
+
+/// Write the AST as a full HTML document to the configured output directory.
+///
+/// Creates the output directory if it does not exist.
+///
+/// # Errors
+///
+/// Returns `Box<dyn Error>` if directory creation, template loading, or file writing fails.
+pub(crate) fn render_to_html_file(
+    ast: &[Node],
+    filename: &str,
+    config: &RendererConfig,
+) -> Result<(), Box<dyn Error>> {
+    let output_dir = PathBuf::from(&config.output_directory);
+    create_dir_all(&output_dir)?;
+
+    let file_path = output_dir.join(filename);
+    let html = render_to_html(ast, config)?;
+    copy_local_mermaid(&html, &output_dir, config)?;
+    let mut file = File::create(&file_path)?;
+    file.write_all(html.as_bytes())?;
+    Ok(())
+}
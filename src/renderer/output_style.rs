@@ -0,0 +1,353 @@
+//! Reformatting generated HTML: minified or pretty-printed.
+//!
+//! Whitespace is only changed where HTML ignores it: between block-level elements and
+//! within runs of text. Preformatted content (`<pre>`, `<code>`, `<textarea>`,
+//! `<script>`, `<style>`, inline SVG, and Mermaid diagram sources) is copied verbatim.
+
+use crate::config::OutputStyle;
+
+/// Elements that start a line of their own when pretty-printing
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "base",
+    "blockquote",
+    "body",
+    "caption",
+    "col",
+    "colgroup",
+    "dd",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "link",
+    "main",
+    "meta",
+    "nav",
+    "noscript",
+    "ol",
+    "p",
+    "pre",
+    "script",
+    "section",
+    "style",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Elements without a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is copied verbatim, along with Mermaid diagram `<div>`s
+const VERBATIM_ELEMENTS: &[&str] = &["code", "pre", "script", "style", "svg", "textarea"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    /// Opening tag of an element with content
+    Open,
+    /// Opening tag of a void or self-closed element
+    Void,
+    Close,
+    /// Text between tags
+    Text,
+    /// Comment, doctype, or a verbatim element with its content
+    Other,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: Kind,
+    /// Element name; empty for text, comments, and doctypes
+    name: &'a str,
+    raw: &'a str,
+    /// Whether the token starts or ends a line when pretty-printing
+    block: bool,
+}
+
+/// `html` reformatted in `style`
+pub(crate) fn restyle_html(html: String, style: OutputStyle) -> String {
+    match style {
+        OutputStyle::Compact => html,
+        OutputStyle::Minified => minify(&tokenize(&html)),
+        OutputStyle::Pretty { indent } => pretty(&tokenize(&html), indent),
+    }
+}
+
+fn minify(tokens: &[Token]) -> String {
+    let mut html = String::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.kind == Kind::Text {
+            html.push_str(&trimmed_text(tokens, idx));
+        } else {
+            html.push_str(token.raw);
+        }
+    }
+    html
+}
+
+/// Text with whitespace runs collapsed, trimmed where it touches a block-level token
+fn trimmed_text(tokens: &[Token], idx: usize) -> String {
+    let mut text = collapse_whitespace(tokens[idx].raw);
+    if idx == 0 || tokens[idx - 1].block {
+        text = text.trim_start().to_string();
+    }
+    if tokens.get(idx + 1).is_none_or(|next| next.block) {
+        text = text.trim_end().to_string();
+    }
+    text
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_space = false;
+    for ch in text.chars() {
+        if ch.is_ascii_whitespace() {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(ch);
+            in_space = false;
+        }
+    }
+    collapsed
+}
+
+fn pretty(tokens: &[Token], indent: usize) -> String {
+    let mut html = String::new();
+    let mut depth = 0;
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let token = tokens[idx];
+        let pad = " ".repeat(indent * depth);
+        if !token.block {
+            // A run of text and inline elements shares one line
+            let end = tokens[idx..]
+                .iter()
+                .position(|token| token.block)
+                .map_or(tokens.len(), |offset| idx + offset);
+            let line = minify(&tokens[idx..end]);
+            if !line.is_empty() {
+                html.push_str(&format!("{}{}\n", pad, line));
+            }
+            idx = end;
+            continue;
+        }
+        match token.kind {
+            Kind::Open => match inline_content_end(tokens, idx) {
+                // Blocks holding only inline content stay on one line
+                Some(end) => {
+                    html.push_str(&format!("{}{}\n", pad, minify(&tokens[idx..=end])));
+                    idx = end;
+                }
+                None => {
+                    html.push_str(&format!("{}{}\n", pad, token.raw));
+                    depth += 1;
+                }
+            },
+            Kind::Close => {
+                depth = depth.saturating_sub(1);
+                html.push_str(&format!("{}{}\n", " ".repeat(indent * depth), token.raw));
+            }
+            Kind::Void | Kind::Other | Kind::Text => {
+                html.push_str(&format!("{}{}\n", pad, token.raw));
+            }
+        }
+        idx += 1;
+    }
+    html
+}
+
+/// Index of the closing tag of the element opened at `start`, if nothing between them
+/// is block-level
+fn inline_content_end(tokens: &[Token], start: usize) -> Option<usize> {
+    let name = tokens[start].name;
+    for (idx, token) in tokens.iter().enumerate().skip(start + 1) {
+        if token.kind == Kind::Close && token.name == name {
+            return Some(idx);
+        }
+        if token.block {
+            return None;
+        }
+    }
+    None
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < html.len() {
+        let rest = &html[pos..];
+        let len = if !rest.starts_with('<') {
+            let len = rest.find('<').unwrap_or(rest.len());
+            tokens.push(token(Kind::Text, "", &rest[..len]));
+            len
+        } else if rest.starts_with("<!--") {
+            let len = rest.find("-->").map_or(rest.len(), |end| end + 3);
+            tokens.push(token(Kind::Other, "", &rest[..len]));
+            len
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            // A doctype sits on a line of its own
+            let len = rest.find('>').map_or(rest.len(), |end| end + 1);
+            let mut doctype = token(Kind::Other, "", &rest[..len]);
+            doctype.block = true;
+            tokens.push(doctype);
+            len
+        } else if let Some(name) = rest.strip_prefix("</").and_then(tag_name) {
+            let len = rest.find('>').map_or(rest.len(), |end| end + 1);
+            tokens.push(token(Kind::Close, name, &rest[..len]));
+            len
+        } else if let Some(name) = tag_name(&rest[1..]) {
+            let tag_len = tag_end(rest);
+            let tag = &rest[..tag_len];
+            let verbatim = VERBATIM_ELEMENTS.contains(&name) || is_mermaid(tag);
+            let kind = if VOID_ELEMENTS.contains(&name) || tag.ends_with("/>") {
+                Kind::Void
+            } else if verbatim {
+                Kind::Other
+            } else {
+                Kind::Open
+            };
+            let len = match kind {
+                Kind::Other => tag_len + closing_offset(&rest[tag_len..], name),
+                _ => tag_len,
+            };
+            tokens.push(token(kind, name, &rest[..len]));
+            len
+        } else {
+            // A stray `<`
+            let len = rest[1..].find('<').map_or(rest.len(), |offset| offset + 1);
+            tokens.push(token(Kind::Text, "", &rest[..len]));
+            len
+        };
+        pos += len;
+    }
+    tokens
+}
+
+fn token<'a>(kind: Kind, name: &'a str, raw: &'a str) -> Token<'a> {
+    Token {
+        kind,
+        name,
+        raw,
+        block: !name.is_empty() && BLOCK_ELEMENTS.contains(&name),
+    }
+}
+
+/// Element name at the start of `text`, if it is one
+///
+/// Names are matched as written; the renderer and the built-in templates use lowercase.
+fn tag_name(text: &str) -> Option<&str> {
+    let len = text
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+        .unwrap_or(text.len());
+    (len > 0 && text.starts_with(|c: char| c.is_ascii_alphabetic())).then(|| &text[..len])
+}
+
+/// Length of the opening tag at the start of `text`, skipping `>` inside quoted values
+fn tag_end(text: &str) -> usize {
+    let mut quote = None;
+    for (idx, ch) in text.char_indices() {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(open), _) if ch == open => quote = None,
+            (None, '>') => return idx + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Length of `content` up to and including the tag closing a `name` element, counting
+/// nested elements of the same name
+fn closing_offset(content: &str, name: &str) -> usize {
+    let open = format!("<{}", name);
+    let close = format!("</{}", name);
+    let mut depth = 1;
+    let mut pos = 0;
+    while let Some(offset) = content[pos..].find('<') {
+        let at = pos + offset;
+        let rest = &content[at..];
+        if rest.starts_with(&close) && tag_name(&rest[2..]) == Some(name) {
+            depth -= 1;
+            if depth == 0 {
+                return at + tag_end(rest);
+            }
+        } else if rest.starts_with(&open) && tag_name(&rest[1..]) == Some(name) {
+            depth += 1;
+        }
+        pos = at + 1;
+    }
+    content.len()
+}
+
+/// Whether an opening tag has the `mermaid` class, whose text is diagram source
+fn is_mermaid(tag: &str) -> bool {
+    tag.split("class=\"")
+        .nth(1)
+        .and_then(|classes| classes.split('"').next())
+        .is_some_and(|classes| classes.split_whitespace().any(|class| class == "mermaid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_keeps_verbatim_elements_whole() {
+        let html = "<div class=\"mermaid\">graph TD\n  A--&gt;B</div><p>a <!-- c --> b</p>";
+        let tokens = tokenize(html);
+        let kinds: Vec<_> = tokens.iter().map(|token| token.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                Kind::Other,
+                Kind::Open,
+                Kind::Text,
+                Kind::Other,
+                Kind::Text,
+                Kind::Close
+            ]
+        );
+        assert_eq!(
+            tokens[0].raw,
+            "<div class=\"mermaid\">graph TD\n  A--&gt;B</div>"
+        );
+        assert!(tokens[0].block);
+        assert_eq!(
+            closing_offset("<pre>x</pre></pre>rest", "pre"),
+            "<pre>x</pre></pre>".len()
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Downloading remote images so rendered documents do not hot-link external hosts.
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::ast::{Inline, ListItem, Node};
+use crate::config::RendererConfig;
+use crate::node_path::visit_nodes_mut;
+
+/// Largest image that is downloaded; bigger ones stay remote
+const MAX_IMAGE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Point every `http://` and `https://` image at a local copy in the image assets directory
+///
+/// Images are saved to `<output_directory>/<image_assets_dir>/` under a name derived
+/// from their URL, and linked as `<image_assets_dir>/<name>`. A file that is already
+/// there is reused without downloading it again. Images that cannot be downloaded
+/// within the timeout keep their remote URL.
+pub(super) fn localize_images(nodes: &[Node], config: &RendererConfig) -> Vec<Node> {
+    let dir = Path::new(&config.output_directory).join(&config.image_assets_dir);
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(config.image_download_timeout_secs))
+        .build();
+    let mut nodes = nodes.to_vec();
+    visit_nodes_mut(&mut nodes, |_, node| {
+        for inlines in node_inlines_mut(node) {
+            localize_inlines(inlines, &mut |url| {
+                let name = local_name(url);
+                let path = dir.join(&name);
+                if !path.exists() {
+                    let bytes = download(&agent, url)?;
+                    fs::create_dir_all(&dir).ok()?;
+                    fs::write(&path, bytes).ok()?;
+                }
+                Some(format!(
+                    "{}/{}",
+                    config.image_assets_dir.trim_end_matches('/'),
+                    name
+                ))
+            });
+        }
+    });
+    nodes
+}
+
+/// Body of a successful response, if it is no larger than [`MAX_IMAGE_BYTES`]
+fn download(agent: &ureq::Agent, url: &str) -> Option<Vec<u8>> {
+    let response = agent.get(url).call().ok()?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_IMAGE_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    (bytes.len() as u64 <= MAX_IMAGE_BYTES).then_some(bytes)
+}
+
+/// Inline content of a node itself, not of its block children
+fn node_inlines_mut(node: &mut Node) -> Vec<&mut Vec<Inline>> {
+    match node {
+        Node::Heading { content, .. }
+        | Node::Paragraph { content }
+        | Node::Blockquote { content, .. }
+        | Node::Details {
+            summary: content, ..
+        }
+        | Node::Section {
+            heading: content, ..
+        }
+        | Node::Figure {
+            caption: content, ..
+        } => vec![content],
+        Node::UnorderedList { items } | Node::OrderedList { items } => {
+            let mut inlines = Vec::new();
+            item_inlines_mut(items, &mut inlines);
+            inlines
+        }
+        Node::Table {
+            headers,
+            rows,
+            caption,
+            ..
+        } => headers
+            .iter_mut()
+            .chain(rows.iter_mut().flatten())
+            .chain(caption)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn item_inlines_mut<'a>(items: &'a mut [ListItem], inlines: &mut Vec<&'a mut Vec<Inline>>) {
+    for item in items {
+        inlines.push(&mut item.content);
+        item_inlines_mut(&mut item.children, inlines);
+    }
+}
+
+/// Replace the URL of every remote image, including images nested in links and emphasis
+fn localize_inlines(inlines: &mut [Inline], localize: &mut impl FnMut(&str) -> Option<String>) {
+    for inline in inlines {
+        match inline {
+            Inline::Image { url, .. } if is_remote(url) => {
+                if let Some(local) = localize(url) {
+                    *url = local;
+                }
+            }
+            Inline::Link { text: content, .. }
+            | Inline::Bold { content }
+            | Inline::Italic { content }
+            | Inline::Strikethrough { content }
+            | Inline::Highlight { content }
+            | Inline::Spoiler { content }
+            | Inline::Span { content, .. }
+            | Inline::Directive { content, .. } => localize_inlines(content, localize),
+            _ => {}
+        }
+    }
+}
+
+fn is_remote(url: &str) -> bool {
+    let scheme = url.split_once("://").map(|(scheme, _)| scheme);
+    scheme.is_some_and(|scheme| {
+        scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+    })
+}
+
+/// File name of a downloaded image: a 64-bit FNV-1a hash of its URL in hex, with the
+/// extension of the URL's path when it has a short alphanumeric one
+fn local_name(url: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in url.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let extension = path
+        .rsplit('/')
+        .next()
+        .and_then(|file| file.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .filter(|extension| {
+            (1..=5).contains(&extension.len())
+                && extension.bytes().all(|b| b.is_ascii_alphanumeric())
+        });
+    match extension {
+        Some(extension) => format!("{:016x}.{}", hash, extension),
+        None => format!("{:016x}", hash),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_name_keeps_short_extensions() {
+        let name = local_name("https://example.com/img/logo.PNG?v=2");
+        assert_eq!(name.len(), 20);
+        assert!(name.ends_with(".png"));
+        assert_ne!(name, local_name("https://example.com/img/logo.PNG?v=3"));
+        assert!(!local_name("https://example.com/image").contains('.'));
+        assert!(!local_name("https://example.com/a.b/c").contains('.'));
+        assert!(is_remote("HTTPS://example.com/a.png"));
+        assert!(!is_remote("images/a.png"));
+    }
+}
@@ -0,0 +1,401 @@
+//! reStructuredText export.
+
+use crate::ast::{
+    figure_image, plain_text, Attributes, Inline, ListItem, Node, ValidationStatus,
+    HIGHLIGHT_ATTRIBUTE, LINE_NUMBERS_CLASS,
+};
+use crate::transform;
+
+/// Section underline characters, indexed by heading level - 1
+const UNDERLINES: [char; 6] = ['=', '-', '~', '^', '"', '\''];
+
+/// Escape characters that reST treats as inline markup
+///
+/// A trailing `_` only starts a reference when it ends a word, so underscores inside
+/// identifiers such as `snake_case` are left alone.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let needs_escape = match ch {
+            '\\' | '*' | '`' | '|' => true,
+            '_' => !chars.peek().is_some_and(|next| next.is_alphanumeric()),
+            _ => false,
+        };
+        if needs_escape {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Inline renderer that collects image substitution definitions
+///
+/// reST has no inline image syntax, so inline images become `|image-N|` references
+/// whose definitions are emitted after the enclosing block.
+#[derive(Default)]
+struct RstWriter {
+    substitutions: Vec<String>,
+}
+
+impl RstWriter {
+    fn render_inlines(&mut self, inlines: &[Inline]) -> String {
+        inlines
+            .iter()
+            .map(|inline| self.render_inline(inline))
+            .collect()
+    }
+
+    fn render_inline(&mut self, inline: &Inline) -> String {
+        match inline {
+            Inline::Text { content } => escape_text(content),
+            // reST inline markup cannot nest, so nested formatting is flattened
+            Inline::Bold { content } => format!("**{}**", escape_text(&plain_text(content))),
+            Inline::Italic { content } => format!("*{}*", escape_text(&plain_text(content))),
+            // No strikethrough in reST; keep the text
+            Inline::Strikethrough { content } => self.render_inlines(content),
+            // Nor highlights or spoilers
+            Inline::Highlight { content } | Inline::Spoiler { content } => {
+                self.render_inlines(content)
+            }
+            Inline::Code { content } => format!("``{}``", content),
+            Inline::Link { text, url } => {
+                let text = plain_text(text).replace('<', "\\<").replace('`', "\\`");
+                format!("`{} <{}>`__", text, url)
+            }
+            Inline::Image {
+                alt,
+                url,
+                attributes,
+            } => {
+                let name = format!("image-{}", self.substitutions.len() + 1);
+                let mut definition = format!(".. |{}| image:: {}", name, url);
+                if !alt.is_empty() {
+                    definition.push_str(&format!("\n   :alt: {}", alt));
+                }
+                definition.push_str(&directive_options(attributes));
+                self.substitutions.push(definition);
+                format!("|{}|", name)
+            }
+            Inline::Hashtag { tag } => escape_text(&format!("#{}", tag)),
+            Inline::Mention { user } => escape_text(&format!("@{}", user)),
+            // list-table cells cannot break lines inside a paragraph
+            Inline::LineBreak => " ".to_string(),
+            // reST has no inline comments
+            Inline::Comment { .. } => String::new(),
+            // Roles would need a `.. role::` definition per name; keep the content
+            Inline::Directive { content, .. } | Inline::Span { content, .. } => {
+                self.render_inlines(content)
+            }
+        }
+    }
+
+    /// Render a block of inline content followed by any pending image definitions
+    fn render_block(&mut self, block: String) -> String {
+        if self.substitutions.is_empty() {
+            return block;
+        }
+        let definitions = std::mem::take(&mut self.substitutions).join("\n");
+        format!("{}\n{}\n", block, definitions)
+    }
+
+    fn render_list_items(
+        &mut self,
+        items: &[ListItem],
+        ordered: bool,
+        indent: usize,
+        out: &mut String,
+    ) {
+        let pad = " ".repeat(indent);
+        for (idx, item) in items.iter().enumerate() {
+            let marker = if ordered {
+                format!("{}.", idx + 1)
+            } else {
+                "-".to_string()
+            };
+            let checkbox = match item.checked {
+                Some(true) => "[x] ",
+                Some(false) => "[ ] ",
+                None => "",
+            };
+            out.push_str(&format!(
+                "{}{} {}{}\n",
+                pad,
+                marker,
+                checkbox,
+                self.render_inlines(&item.content)
+            ));
+            for block in &item.blocks {
+                // Body blocks are separated by blank lines and indented to the item text
+                let body = self.render_node(block);
+                out.push('\n');
+                out.push_str(&indent_lines(&body, indent + marker.len() + 1));
+                out.push('\n');
+            }
+            if !item.children.is_empty() {
+                // Nested lists must be separated by blank lines and indented to the item text
+                out.push('\n');
+                self.render_list_items(&item.children, ordered, indent + marker.len() + 1, out);
+                out.push('\n');
+            }
+        }
+    }
+
+    fn render_node(&mut self, node: &Node) -> String {
+        match node {
+            Node::Heading { level, content, .. } => self.render_heading(*level, content),
+            Node::Paragraph { content } => {
+                let text = self.render_inlines(content);
+                self.render_block(format!("{}\n", text))
+            }
+            Node::UnorderedList { items } => {
+                let mut out = String::new();
+                self.render_list_items(items, false, 0, &mut out);
+                self.render_block(out)
+            }
+            Node::OrderedList { items } => {
+                let mut out = String::new();
+                self.render_list_items(items, true, 0, &mut out);
+                self.render_block(out)
+            }
+            Node::CodeBlock {
+                lang,
+                code,
+                attributes,
+            } => {
+                let options = directive_options(attributes) + &line_options(attributes);
+                let header = match lang {
+                    Some(lang) => format!(".. code-block:: {}{}", lang, options),
+                    None if !options.is_empty() => format!(".. code-block::{}", options),
+                    None => "::".to_string(),
+                };
+                format!("{}\n\n{}\n", header, indent_lines(code, 3))
+            }
+            Node::MermaidDiagram {
+                diagram,
+                validation_status,
+                ..
+            } => {
+                // sphinxcontrib-mermaid directive; invalid diagrams are kept as literal text
+                let header = match validation_status {
+                    ValidationStatus::Invalid { .. } => "::",
+                    _ => ".. mermaid::",
+                };
+                format!("{}\n\n{}\n", header, indent_lines(diagram, 3))
+            }
+            // Replaced by `resolve_diagram_references` in `render_rst`
+            Node::DiagramRef { .. } => String::new(),
+            Node::Table {
+                headers,
+                rows,
+                caption,
+                ..
+            } => {
+                let mut out = String::from(".. list-table::");
+                if let Some(caption) = caption {
+                    out.push(' ');
+                    out.push_str(&self.render_inlines(caption));
+                }
+                out.push_str("\n   :header-rows: 1\n\n");
+                for row in std::iter::once(headers).chain(rows) {
+                    for (idx, cell) in row.iter().enumerate() {
+                        let prefix = if idx == 0 { "   * - " } else { "     - " };
+                        out.push_str(prefix);
+                        out.push_str(&self.render_inlines(cell));
+                        out.push('\n');
+                    }
+                }
+                self.render_block(out)
+            }
+            Node::Blockquote {
+                level,
+                content,
+                blocks,
+            } => {
+                let mut parts = vec![self.render_inlines(content)];
+                parts.extend(blocks.iter().map(|block| self.render_node(block)));
+                let text = parts.join("\n");
+                let quoted = indent_lines(&text, 3 * usize::from(*level));
+                self.render_block(format!("{}\n", quoted))
+            }
+            Node::HorizontalRule { .. } => "----\n".to_string(),
+            Node::Admonition {
+                kind,
+                title,
+                content,
+            } => {
+                let body = content
+                    .iter()
+                    .map(|child| self.render_node(child))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let directive = match title {
+                    // A titled admonition is the generic directive, styled by its kind
+                    Some(title) => format!(
+                        "admonition:: {}\n   :class: {}",
+                        self.render_inlines(title),
+                        kind.as_str()
+                    ),
+                    None => format!("{}::", kind.as_str()),
+                };
+                format!(".. {}\n\n{}\n", directive, indent_lines(&body, 3))
+            }
+            Node::Details { summary, content } => {
+                // reST has no collapsible block; a topic keeps the title and body together
+                let title = self.render_inlines(summary);
+                let body = content
+                    .iter()
+                    .map(|child| self.render_node(child))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.render_block(format!(
+                    ".. topic:: {}\n\n{}\n",
+                    title,
+                    indent_lines(&body, 3)
+                ))
+            }
+            Node::Figure {
+                content, caption, ..
+            } => {
+                let caption = self.render_inlines(caption);
+                let figure = match (figure_image(content), content.as_slice()) {
+                    (
+                        Some(Inline::Image {
+                            alt,
+                            url,
+                            attributes,
+                        }),
+                        _,
+                    ) => {
+                        let mut header = format!(".. figure:: {}", url);
+                        if !alt.is_empty() {
+                            header.push_str(&format!("\n   :alt: {}", alt));
+                        }
+                        header.push_str(&directive_options(attributes));
+                        format!("{}\n\n{}\n", header, indent_lines(&caption, 3))
+                    }
+                    (
+                        _,
+                        [Node::MermaidDiagram {
+                            diagram,
+                            validation_status,
+                            ..
+                        }],
+                    ) if !matches!(validation_status, ValidationStatus::Invalid { .. }) => {
+                        format!(
+                            ".. mermaid::\n   :caption: {}\n\n{}\n",
+                            caption,
+                            indent_lines(diagram, 3)
+                        )
+                    }
+                    // Invalid diagrams are literal blocks, which take no caption
+                    _ => {
+                        let body = content
+                            .iter()
+                            .map(|child| self.render_node(child))
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        format!("{}\n{}\n", body, caption)
+                    }
+                };
+                self.render_block(figure)
+            }
+            Node::Comment { content } => format!("..\n{}\n", indent_lines(content, 3)),
+            Node::Container {
+                attributes,
+                children,
+            } => {
+                let body = children
+                    .iter()
+                    .map(|child| self.render_node(child))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let mut header = String::from(".. container::");
+                if !attributes.classes.is_empty() {
+                    header.push(' ');
+                    header.push_str(&attributes.classes.join(" "));
+                }
+                if let Some(id) = &attributes.id {
+                    header.push_str(&format!("\n   :name: {}", id));
+                }
+                format!("{}\n\n{}\n", header, indent_lines(&body, 3))
+            }
+            Node::Section {
+                level,
+                heading,
+                children,
+                ..
+            } => {
+                let mut parts = vec![self.render_heading(*level, heading)];
+                parts.extend(children.iter().map(|child| self.render_node(child)));
+                parts.join("\n")
+            }
+        }
+    }
+
+    fn render_heading(&mut self, level: u8, content: &[Inline]) -> String {
+        let title = self.render_inlines(content);
+        let underline_char = UNDERLINES[usize::from(level.clamp(1, 6) - 1)];
+        let underline = underline_char
+            .to_string()
+            .repeat(title.chars().count().max(1));
+        self.render_block(format!("{}\n{}\n", title, underline))
+    }
+}
+
+/// `:name:` and `:class:` directive options (each on its own line) for an attribute list
+fn directive_options(attributes: &Attributes) -> String {
+    let mut options = String::new();
+    if let Some(id) = &attributes.id {
+        options.push_str(&format!("\n   :name: {}", id));
+    }
+    if !attributes.classes.is_empty() {
+        options.push_str(&format!("\n   :class: {}", attributes.classes.join(" ")));
+    }
+    options
+}
+
+/// `:linenos:`, `:lineno-start:`, and `:emphasize-lines:` options of a code block
+fn line_options(attributes: &Attributes) -> String {
+    let mut options = String::new();
+    if attributes
+        .classes
+        .iter()
+        .any(|class| class == LINE_NUMBERS_CLASS)
+    {
+        options.push_str("\n   :linenos:");
+        if let Some(start) = attributes.get("start") {
+            options.push_str(&format!("\n   :lineno-start: {}", start));
+        }
+    }
+    if let Some(lines) = attributes.get(HIGHLIGHT_ATTRIBUTE) {
+        options.push_str(&format!("\n   :emphasize-lines: {}", lines));
+    }
+    options
+}
+
+/// Indent every non-empty line of `text` by `width` spaces
+fn indent_lines(text: &str, width: usize) -> String {
+    let pad = " ".repeat(width);
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", pad, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render the AST as a reStructuredText document
+pub(crate) fn render_rst(ast: &[Node]) -> String {
+    let ast = transform::resolve_diagram_references(ast);
+    let mut writer = RstWriter::default();
+    ast.iter()
+        .map(|node| writer.render_node(node))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,159 @@
+//! Slide deck output as a Reveal.js presentation.
+
+use super::{apply_transforms, escape_html, mermaid_cdn_url, render_nodes};
+use crate::ast::Node;
+use crate::config::RendererConfig;
+
+/// Options for [`Parser::to_slides`](crate::Parser::to_slides)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlideOptions {
+    /// Presentation title (`<title>`)
+    pub title: String,
+    /// Start a new slide at each `---` horizontal rule, which is dropped
+    pub split_on_rules: bool,
+    /// Start a new slide at each heading of this level or higher (`None` disables)
+    pub split_heading_level: Option<u8>,
+    /// Reveal.js theme name (`white`, `black`, `simple`, ...)
+    pub theme: String,
+    /// Base URL of the Reveal.js distribution the page loads
+    pub reveal_url: String,
+}
+
+impl Default for SlideOptions {
+    fn default() -> Self {
+        Self {
+            title: "Slides".to_string(),
+            split_on_rules: true,
+            split_heading_level: Some(2),
+            theme: "white".to_string(),
+            reveal_url: "https://cdn.jsdelivr.net/npm/reveal.js@5".to_string(),
+        }
+    }
+}
+
+/// Render the AST as a standalone Reveal.js slide deck
+///
+/// Render-time transforms run before splitting, so headings split at their shifted
+/// levels. Comments on a slide become its speaker notes. Mermaid diagrams are drawn
+/// when their slide is first shown, since Mermaid cannot lay out hidden slides.
+pub(crate) fn render_slides(
+    ast: &[Node],
+    options: &SlideOptions,
+    config: &RendererConfig,
+) -> String {
+    let nodes = apply_transforms(ast, config);
+    let mut flat = Vec::new();
+    flatten_sections(&nodes, &mut flat);
+
+    let mut html = String::new();
+    html.push_str(&format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{title}</title>
+    <link rel="stylesheet" href="{reveal}/dist/reveal.css">
+    <link rel="stylesheet" href="{reveal}/dist/theme/{theme}.css">
+    <script src="{mermaid}"></script>
+</head>
+<body>
+<div class="reveal">
+<div class="slides">
+"#,
+        title = escape_html(&options.title),
+        reveal = escape_html(options.reveal_url.trim_end_matches('/')),
+        theme = escape_html(&options.theme),
+        mermaid = escape_html(&mermaid_cdn_url(&config.mermaid_version)),
+    ));
+
+    for slide in split_slides(flat, options) {
+        let (notes, body): (Vec<Node>, Vec<Node>) = slide
+            .into_iter()
+            .partition(|node| matches!(node, Node::Comment { .. }));
+        html.push_str("<section>\n");
+        html.push_str(&render_nodes(&body, config));
+        if !notes.is_empty() {
+            html.push_str("<aside class=\"notes\">\n");
+            for note in &notes {
+                if let Node::Comment { content } = note {
+                    html.push_str(&format!("<p>{}</p>\n", escape_html(content)));
+                }
+            }
+            html.push_str("</aside>\n");
+        }
+        html.push_str("</section>\n");
+    }
+
+    html.push_str(&format!(
+        r#"</div>
+</div>
+<script src="{reveal}/dist/reveal.js"></script>
+<script>
+    mermaid.initialize({{ startOnLoad: false, theme: 'default' }});
+    function renderDiagrams(slide) {{
+        const pending = slide.querySelectorAll('.mermaid:not([data-processed])');
+        if (pending.length > 0) {{
+            mermaid.run({{ nodes: pending }});
+        }}
+    }}
+    Reveal.initialize({{ hash: true }}).then(() => renderDiagrams(Reveal.getCurrentSlide()));
+    Reveal.on('slidechanged', (event) => renderDiagrams(event.currentSlide));
+</script>
+</body>
+</html>
+"#,
+        reveal = escape_html(options.reveal_url.trim_end_matches('/')),
+    ));
+    config.line_ending.apply(html)
+}
+
+/// Replace sections by their heading followed by their children
+fn flatten_sections(nodes: &[Node], out: &mut Vec<Node>) {
+    for node in nodes {
+        match node {
+            Node::Section {
+                level,
+                heading,
+                attributes,
+                children,
+            } => {
+                out.push(Node::Heading {
+                    level: *level,
+                    content: heading.clone(),
+                    attributes: attributes.clone(),
+                });
+                flatten_sections(children, out);
+            }
+            other => out.push(other.clone()),
+        }
+    }
+}
+
+/// Group top-level nodes into slides, dropping slides left empty
+fn split_slides(nodes: Vec<Node>, options: &SlideOptions) -> Vec<Vec<Node>> {
+    let mut slides: Vec<Vec<Node>> = vec![Vec::new()];
+    for node in nodes {
+        let starts_slide = match &node {
+            Node::HorizontalRule { .. } => {
+                if options.split_on_rules {
+                    slides.push(Vec::new());
+                    continue;
+                }
+                false
+            }
+            Node::Heading { level, .. } => {
+                options.split_heading_level.is_some_and(|max| *level <= max)
+            }
+            _ => false,
+        };
+        if starts_slide {
+            slides.push(Vec::new());
+        }
+        if let Some(slide) = slides.last_mut() {
+            slide.push(node);
+        }
+    }
+    slides.retain(|slide| !slide.is_empty());
+    slides
+}
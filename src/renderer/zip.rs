@@ -0,0 +1,123 @@
+//! Minimal ZIP archive writer (stored entries only).
+//!
+//! Container formats such as EPUB only need uncompressed entries, so this avoids
+//! pulling in a compression dependency.
+
+/// CRC-32 (IEEE) checksum as required by the ZIP format
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// In-memory ZIP archive builder
+///
+/// Entries are written in insertion order with a fixed timestamp so the output is
+/// byte-for-byte reproducible.
+#[derive(Debug, Default)]
+pub(crate) struct ZipWriter {
+    data: Vec<u8>,
+    central_directory: Vec<u8>,
+    entries: u16,
+}
+
+/// DOS date for 1980-01-01 (the earliest representable date)
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+impl ZipWriter {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a stored (uncompressed) file entry
+    pub(crate) fn add_file(&mut self, name: &str, contents: &[u8]) {
+        let offset = self.data.len() as u32;
+        let crc = crc32(contents);
+        let size = contents.len() as u32;
+        let name_bytes = name.as_bytes();
+
+        // Local file header
+        self.data.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        self.data.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // flags
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // method: stored
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // mod time
+        self.data.extend_from_slice(&DOS_DATE.to_le_bytes());
+        self.data.extend_from_slice(&crc.to_le_bytes());
+        self.data.extend_from_slice(&size.to_le_bytes()); // compressed size
+        self.data.extend_from_slice(&size.to_le_bytes()); // uncompressed size
+        self.data
+            .extend_from_slice(&(name_bytes.len() as u16).to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+        self.data.extend_from_slice(name_bytes);
+        self.data.extend_from_slice(contents);
+
+        // Central directory record
+        let cd = &mut self.central_directory;
+        cd.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        cd.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        cd.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        cd.extend_from_slice(&0u16.to_le_bytes()); // flags
+        cd.extend_from_slice(&0u16.to_le_bytes()); // method
+        cd.extend_from_slice(&0u16.to_le_bytes()); // mod time
+        cd.extend_from_slice(&DOS_DATE.to_le_bytes());
+        cd.extend_from_slice(&crc.to_le_bytes());
+        cd.extend_from_slice(&size.to_le_bytes());
+        cd.extend_from_slice(&size.to_le_bytes());
+        cd.extend_from_slice(&(name_bytes.len() as u16).to_le_bytes());
+        cd.extend_from_slice(&0u16.to_le_bytes()); // extra length
+        cd.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        cd.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        cd.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        cd.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        cd.extend_from_slice(&offset.to_le_bytes());
+        cd.extend_from_slice(name_bytes);
+
+        self.entries += 1;
+    }
+
+    /// Finish the archive and return its bytes
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        let cd_offset = self.data.len() as u32;
+        let cd_size = self.central_directory.len() as u32;
+        self.data.extend_from_slice(&self.central_directory);
+
+        // End of central directory record
+        self.data.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // disk with central directory
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&cd_size.to_le_bytes());
+        self.data.extend_from_slice(&cd_offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_archive_layout() {
+        let mut zip = ZipWriter::new();
+        zip.add_file("mimetype", b"application/epub+zip");
+        let bytes = zip.finish();
+
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+        assert_eq!(&bytes[30..38], b"mimetype");
+        assert_eq!(&bytes[bytes.len() - 22..bytes.len() - 18], b"PK\x05\x06");
+    }
+}
@@ -0,0 +1,110 @@
+//! Build reports summarizing the documents processed in a batch.
+
+use crate::ast::{Node, ParseWarning};
+use crate::lint::LintDiagnostic;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Summary of a single processed Markdown file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileReport {
+    /// Path of the source file, relative to the build root
+    pub path: String,
+    /// Number of top-level nodes per node type (`heading`, `paragraph`, …)
+    pub node_counts: BTreeMap<String, usize>,
+    /// Total number of top-level nodes
+    pub node_total: usize,
+    /// Warnings collected while parsing
+    pub warnings: Vec<ParseWarning>,
+    /// Relative link and image targets that do not resolve to a document, heading, or
+    /// file; empty unless set by the caller from [`Project::broken_links`](crate::Project::broken_links)
+    pub broken_links: Vec<String>,
+    /// Style problems found by [`lint`](crate::lint) in the source; empty unless set by
+    /// the caller, since the report is built from the AST
+    #[serde(default)]
+    pub lint: Vec<LintDiagnostic>,
+    /// Parse error that stopped processing of this file, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Time spent parsing and rendering this file, in milliseconds
+    pub duration_ms: u128,
+}
+
+impl FileReport {
+    /// Build the report for a successfully parsed file
+    pub fn new(path: &str, ast: &[Node], warnings: &[ParseWarning], duration: Duration) -> Self {
+        let mut node_counts = BTreeMap::new();
+        for node in ast {
+            *node_counts.entry(node.kind().to_string()).or_insert(0) += 1;
+        }
+
+        Self {
+            path: path.to_string(),
+            node_counts,
+            node_total: ast.len(),
+            warnings: warnings.to_vec(),
+            broken_links: Vec::new(),
+            lint: Vec::new(),
+            error: None,
+            duration_ms: duration.as_millis(),
+        }
+    }
+
+    /// Build the report for a file that failed to parse or render
+    pub fn failed(path: &str, error: String, duration: Duration) -> Self {
+        Self {
+            path: path.to_string(),
+            node_counts: BTreeMap::new(),
+            node_total: 0,
+            warnings: Vec::new(),
+            broken_links: Vec::new(),
+            lint: Vec::new(),
+            error: Some(error),
+            duration_ms: duration.as_millis(),
+        }
+    }
+}
+
+/// Summary of a whole directory build, written as `build-report.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BuildReport {
+    /// Number of files processed (including failed ones)
+    pub total_files: usize,
+    /// Number of files that failed to parse or render
+    pub failed_files: usize,
+    /// Sum of warnings across all files
+    pub total_warnings: usize,
+    /// Sum of broken links across all files
+    pub total_broken_links: usize,
+    /// Sum of lint diagnostics across all files
+    #[serde(default)]
+    pub total_lint: usize,
+    /// Wall-clock time for the whole build, in milliseconds
+    pub duration_ms: u128,
+    /// Per-file details
+    pub files: Vec<FileReport>,
+}
+
+impl BuildReport {
+    /// Add a file report and update the totals
+    pub fn push(&mut self, file: FileReport) {
+        self.total_files += 1;
+        if file.error.is_some() {
+            self.failed_files += 1;
+        }
+        self.total_warnings += file.warnings.len();
+        self.total_broken_links += file.broken_links.len();
+        self.total_lint += file.lint.len();
+        self.files.push(file);
+    }
+
+    /// Serialize the report as pretty-printed JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
@@ -0,0 +1,48 @@
+//! Versioned AST documents and their JSON Schema.
+
+use crate::ast::Node;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Version of the serialized AST format, written as `schema_version`
+///
+/// The version is incremented whenever a change could break a consumer written
+/// against the previous one: a node or inline type, field, or enum value is removed or
+/// renamed, a field changes type, or an optional field becomes required. Adding a
+/// node or inline type, or an optional field that is left out when empty, keeps the
+/// version; consumers should ignore `type` tags and fields they do not know.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// AST as written by [`Parser::to_json`](crate::Parser::to_json) and the other
+/// serializers, tagged with the format version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AstDocument {
+    /// Format version, [`SCHEMA_VERSION`] for documents written by this release
+    pub schema_version: u32,
+    /// Top-level nodes
+    pub nodes: Vec<Node>,
+}
+
+/// Borrowed form of [`AstDocument`], serialized without cloning the nodes
+#[derive(Serialize)]
+pub(crate) struct VersionedNodes<'a> {
+    schema_version: u32,
+    nodes: &'a [Node],
+}
+
+impl<'a> VersionedNodes<'a> {
+    pub(crate) fn new(nodes: &'a [Node]) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            nodes,
+        }
+    }
+}
+
+/// JSON Schema (draft 2020-12) describing the output of [`Parser::to_json`](crate::Parser::to_json)
+///
+/// Code generators can turn it into types for other languages, such as TypeScript
+/// interfaces. The schema of each release is also in `schema/ast.schema.json`.
+pub fn json_schema() -> serde_json::Value {
+    schemars::schema_for!(AstDocument).to_value()
+}
@@ -0,0 +1,163 @@
+//! Search index of document sections, for static sites.
+
+use crate::ast::{for_each_node, plain_text, ListItem, Node};
+use crate::outline::{Outline, OutlineSection};
+use crate::tags::extract_hashtags;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One searchable section of a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchRecord {
+    /// `path#anchor`, or `path` for the text before the first heading
+    pub id: String,
+    /// Page the section is on, as given to [`SearchIndex::add_document`]
+    pub path: String,
+    /// Heading anchor; `None` for the text before the first heading
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+    /// Heading text without formatting, or the page title for the text before the first
+    /// heading
+    pub title: String,
+    /// Title of the page
+    pub page: String,
+    /// Text of the section without its subsections, one block per line
+    pub body: String,
+    /// Hashtags in the heading and body, for documents parsed with `enable_hashtags`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Search records for the sections of any number of documents, with an inverted index
+///
+/// `records` load into Lunr or elasticlunr as documents with `id` as the ref and
+/// `title`, `body`, and `tags` as fields. `index` maps every lowercased word of those
+/// fields (two characters or longer) to the positions of the records containing it, for
+/// sites that search without a library.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchIndex {
+    pub records: Vec<SearchRecord>,
+    pub index: BTreeMap<String, Vec<usize>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a record for each section of a document, and one for its text before the
+    /// first heading, if any
+    ///
+    /// `path` is where the page is published (`guide/setup.html`) and `title` its title.
+    pub fn add_document(&mut self, path: &str, title: &str, nodes: &[Node]) {
+        let outline = Outline::from_nodes(nodes);
+        let body = block_text(&outline.preamble);
+        if !body.is_empty() {
+            self.push(SearchRecord {
+                id: path.to_string(),
+                path: path.to_string(),
+                anchor: None,
+                title: title.to_string(),
+                page: title.to_string(),
+                body,
+                tags: extract_hashtags(&outline.preamble),
+            });
+        }
+        for section in &outline.sections {
+            self.add_section(path, title, section);
+        }
+    }
+
+    fn add_section(&mut self, path: &str, page: &str, section: &OutlineSection) {
+        let heading = [Node::Paragraph {
+            content: section.heading.clone(),
+        }];
+        let mut tags = extract_hashtags(&heading);
+        for tag in extract_hashtags(&section.body) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        self.push(SearchRecord {
+            id: format!("{}#{}", path, section.anchor),
+            path: path.to_string(),
+            anchor: Some(section.anchor.clone()),
+            title: section.title.clone(),
+            page: page.to_string(),
+            body: block_text(&section.body),
+            tags,
+        });
+        for child in &section.children {
+            self.add_section(path, page, child);
+        }
+    }
+
+    fn push(&mut self, record: SearchRecord) {
+        let position = self.records.len();
+        let fields = [&record.title, &record.body, &record.tags.join(" ")];
+        for term in fields.into_iter().flat_map(|field| terms(field)) {
+            let positions = self.index.entry(term).or_default();
+            if positions.last() != Some(&position) {
+                positions.push(position);
+            }
+        }
+        self.records.push(record);
+    }
+
+    /// Serialize as compact JSON, to keep the file small for browsers
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+/// Lowercased words of `text` that are two characters or longer
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().nth(1).is_some())
+        .map(str::to_lowercase)
+}
+
+/// Text of every block in `nodes`, one per line; Mermaid diagrams are left out
+fn block_text(nodes: &[Node]) -> String {
+    let mut lines = Vec::new();
+    for_each_node(nodes, &mut |node| match node {
+        Node::Heading { content, .. }
+        | Node::Paragraph { content }
+        | Node::Blockquote { content, .. } => lines.push(plain_text(content)),
+        Node::Section { heading, .. } => lines.push(plain_text(heading)),
+        Node::UnorderedList { items } | Node::OrderedList { items } => item_text(items, &mut lines),
+        Node::Table {
+            headers,
+            rows,
+            caption,
+            ..
+        } => {
+            for row in std::iter::once(headers).chain(rows) {
+                let cells: Vec<_> = row.iter().map(|cell| plain_text(cell)).collect();
+                lines.push(cells.join(" "));
+            }
+            lines.extend(caption.as_deref().map(plain_text));
+        }
+        Node::Admonition {
+            title: Some(title), ..
+        } => lines.push(plain_text(title)),
+        Node::Details { summary, .. } => lines.push(plain_text(summary)),
+        Node::Figure { caption, .. } => lines.push(plain_text(caption)),
+        Node::CodeBlock { code, .. } => lines.push(code.clone()),
+        _ => {}
+    });
+    lines.retain(|line| !line.trim().is_empty());
+    lines.join("\n")
+}
+
+/// Push the text of every item; blocks nested in items are visited as nodes
+fn item_text(items: &[ListItem], lines: &mut Vec<String>) {
+    for item in items {
+        lines.push(plain_text(&item.content));
+        item_text(&item.children, lines);
+    }
+}
@@ -0,0 +1,183 @@
+//! Typed node selectors.
+
+use crate::ast::{plain_text, Node};
+use crate::node_path::{iter_nodes, NodePath};
+
+/// Filter selecting nodes of a document, anywhere in it
+///
+/// Start from a node type and narrow it down; every condition must hold:
+///
+/// ```
+/// use md_parser::{Parser, Select};
+///
+/// let ast = Parser::new("## Setup\n\n```rust\nfn main() {}\n```\n".to_string())?.parse()?;
+/// let rust = Select::code_blocks().lang("rust").find(&ast);
+/// assert_eq!(rust.len(), 1);
+/// assert_eq!(Select::headings().level(2).first(&ast).unwrap().0.to_string(), "0");
+/// # Ok::<(), md_parser::ParseError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Select {
+    kind: Option<String>,
+    level: Option<u8>,
+    lang: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    text: Option<String>,
+}
+
+impl Select {
+    /// Every node
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Nodes of the given type, named as in JSON output (`"heading"`, `"code_block"`, ...)
+    pub fn kind(kind: impl Into<String>) -> Self {
+        Self {
+            kind: Some(kind.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Headings
+    pub fn headings() -> Self {
+        Self::kind("heading")
+    }
+
+    /// Paragraphs
+    pub fn paragraphs() -> Self {
+        Self::kind("paragraph")
+    }
+
+    /// Fenced code blocks, excluding Mermaid diagrams
+    pub fn code_blocks() -> Self {
+        Self::kind("code_block")
+    }
+
+    /// Mermaid diagrams
+    pub fn mermaid_diagrams() -> Self {
+        Self::kind("mermaid_diagram")
+    }
+
+    /// Tables
+    pub fn tables() -> Self {
+        Self::kind("table")
+    }
+
+    /// Admonitions
+    pub fn admonitions() -> Self {
+        Self::kind("admonition")
+    }
+
+    /// Figures
+    pub fn figures() -> Self {
+        Self::kind("figure")
+    }
+
+    /// Sections (`enable_sections`)
+    pub fn sections() -> Self {
+        Self::kind("section")
+    }
+
+    /// Only headings and sections of this level
+    pub fn level(mut self, level: u8) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Only code blocks in this language (exact match)
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    /// Only nodes whose attribute list has this id
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Only nodes whose attribute list has this class; may be given several times
+    pub fn class(mut self, class: impl Into<String>) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+
+    /// Only nodes whose own text contains `text`
+    ///
+    /// The text of headings, paragraphs, and blockquotes is their plain inline
+    /// text; a section's is its heading; a figure's is its caption; a code block's or
+    /// diagram's is its source.
+    /// Other nodes never match.
+    pub fn containing(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Whether `node` satisfies every condition
+    pub fn matches(&self, node: &Node) -> bool {
+        if self.kind.as_deref().is_some_and(|kind| kind != node.kind()) {
+            return false;
+        }
+        if let Some(level) = self.level {
+            match node {
+                Node::Heading { level: actual, .. } | Node::Section { level: actual, .. }
+                    if *actual == level => {}
+                _ => return false,
+            }
+        }
+        if let Some(lang) = &self.lang {
+            if !matches!(node, Node::CodeBlock { lang: Some(actual), .. } if actual == lang) {
+                return false;
+            }
+        }
+        if self.id.is_some() || !self.classes.is_empty() {
+            let Some(attributes) = node.attributes() else {
+                return false;
+            };
+            if self.id.is_some() && attributes.id != self.id {
+                return false;
+            }
+            if !self
+                .classes
+                .iter()
+                .all(|class| attributes.classes.contains(class))
+            {
+                return false;
+            }
+        }
+        if let Some(text) = &self.text {
+            let own_text = match node {
+                Node::Heading { content, .. }
+                | Node::Paragraph { content }
+                | Node::Blockquote { content, .. }
+                | Node::Section {
+                    heading: content, ..
+                }
+                | Node::Figure {
+                    caption: content, ..
+                } => plain_text(content),
+                Node::CodeBlock { code, .. } => code.clone(),
+                Node::MermaidDiagram { diagram, .. } => diagram.clone(),
+                _ => return false,
+            };
+            if !own_text.contains(text.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Every matching node with its path, in document order
+    pub fn find<'a>(&self, nodes: &'a [Node]) -> Vec<(NodePath, &'a Node)> {
+        iter_nodes(nodes)
+            .filter(|(_, node)| self.matches(node))
+            .collect()
+    }
+
+    /// The first matching node with its path
+    pub fn first<'a>(&self, nodes: &'a [Node]) -> Option<(NodePath, &'a Node)> {
+        iter_nodes(nodes).find(|(_, node)| self.matches(node))
+    }
+}
@@ -0,0 +1,168 @@
+//! Navigation tree and sitemap of the pages built from a directory.
+
+use crate::ast::{plain_text, Node};
+use crate::parse_result::ParseResult;
+use crate::renderer::escape_html;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A page of a site, with what its navigation needs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SitePage {
+    /// Path of the page from the site root, with `/` separators (`guide/setup.html`)
+    pub path: String,
+    /// The frontmatter `title`, else the first heading, else the file name
+    pub title: String,
+    /// The frontmatter `nav_order`; pages with one come first, lowest first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<i64>,
+    /// The frontmatter `nav_exclude: true`; the page is in the sitemap but not the navigation
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+impl SitePage {
+    /// Page at `path` for a parsed document
+    pub fn from_result(path: &str, result: &ParseResult) -> Self {
+        let frontmatter = result.frontmatter.as_deref().unwrap_or_default();
+        let title = frontmatter_field(frontmatter, "title")
+            .map(str::to_string)
+            .or_else(|| first_heading(&result.nodes))
+            .unwrap_or_else(|| {
+                let file = path.rsplit('/').next().unwrap_or(path);
+                file.split('.').next().unwrap_or(file).to_string()
+            });
+        Self {
+            path: path.to_string(),
+            title,
+            order: frontmatter_field(frontmatter, "nav_order").and_then(|v| v.parse().ok()),
+            hidden: frontmatter_field(frontmatter, "nav_exclude") == Some("true"),
+        }
+    }
+}
+
+/// An entry of the navigation tree: a page, or a directory of pages
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NavEntry {
+    /// Title of the page; for a directory, that of its `index` page or else its name
+    pub title: String,
+    /// Path of the page; for a directory, that of its `index` page, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Pages and directories in the directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<NavEntry>,
+}
+
+/// Navigation tree of `pages`, following their directories
+///
+/// The `index` page of a directory stands for the directory, with its title and
+/// `nav_order`. Entries with a `nav_order` come first, lowest first; the rest follow by
+/// file or directory name. Hidden pages, and directories left empty by them, are left
+/// out.
+pub fn navigation(pages: &[SitePage]) -> Vec<NavEntry> {
+    let mut root = NavDir::default();
+    for page in pages.iter().filter(|page| !page.hidden) {
+        let mut parts: Vec<&str> = page.path.split('/').collect();
+        let file = parts.pop().unwrap_or_default();
+        let mut dir = &mut root;
+        for part in parts {
+            dir = dir.dirs.entry(part.to_string()).or_default();
+        }
+        if file.split('.').next() == Some("index") && dir.index.is_none() {
+            dir.index = Some(page);
+        } else {
+            dir.pages.push((file, page));
+        }
+    }
+    // The site's own index page is its first entry
+    if let Some(index) = root.index.take() {
+        root.pages.push(("", index));
+    }
+    root.entries()
+}
+
+/// Pages and subdirectories of one directory of a site
+#[derive(Default)]
+struct NavDir<'a> {
+    index: Option<&'a SitePage>,
+    pages: Vec<(&'a str, &'a SitePage)>,
+    dirs: BTreeMap<String, NavDir<'a>>,
+}
+
+impl NavDir<'_> {
+    fn entries(&self) -> Vec<NavEntry> {
+        let mut entries: Vec<(Option<i64>, &str, NavEntry)> = self
+            .pages
+            .iter()
+            .map(|(file, page)| {
+                let entry = NavEntry {
+                    title: page.title.clone(),
+                    path: Some(page.path.clone()),
+                    children: Vec::new(),
+                };
+                (page.order, *file, entry)
+            })
+            .collect();
+        for (name, dir) in &self.dirs {
+            let children = dir.entries();
+            if children.is_empty() && dir.index.is_none() {
+                continue;
+            }
+            let entry = NavEntry {
+                title: dir
+                    .index
+                    .map_or_else(|| name.clone(), |index| index.title.clone()),
+                path: dir.index.map(|index| index.path.clone()),
+                children,
+            };
+            entries.push((dir.index.and_then(|index| index.order), name, entry));
+        }
+        entries.sort_by(|(a_order, a_name, _), (b_order, b_name, _)| {
+            (a_order.is_none(), a_order, a_name).cmp(&(b_order.is_none(), b_order, b_name))
+        });
+        entries.into_iter().map(|(_, _, entry)| entry).collect()
+    }
+}
+
+/// `sitemap.xml` listing every page, hidden ones included, under `base_url`
+pub fn sitemap_xml(pages: &[SitePage], base_url: &str) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for page in pages {
+        let url = format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            page.path.trim_start_matches('/')
+        );
+        xml.push_str(&format!("  <url><loc>{}</loc></url>\n", escape_html(&url)));
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// Value of a top-level `key: value` line of YAML frontmatter, without quotes
+fn frontmatter_field<'a>(frontmatter: &'a str, key: &str) -> Option<&'a str> {
+    frontmatter.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix(':')?.trim();
+        let unquoted = ['"', '\''].iter().find_map(|quote| {
+            value
+                .strip_prefix(*quote)
+                .and_then(|rest| rest.strip_suffix(*quote))
+        });
+        Some(unquoted.unwrap_or(value)).filter(|value| !value.is_empty())
+    })
+}
+
+/// Text of the first heading of a document
+fn first_heading(nodes: &[Node]) -> Option<String> {
+    nodes.iter().find_map(|node| match node {
+        Node::Heading { content, .. }
+        | Node::Section {
+            heading: content, ..
+        } => Some(plain_text(content)),
+        _ => None,
+    })
+}
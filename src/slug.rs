@@ -0,0 +1,65 @@
+//! Heading anchor slug generation.
+
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthChar;
+
+/// Convert heading text into a URL fragment (GitHub style)
+///
+/// Lowercases the text, keeps alphanumerics (in any script), combining marks, `-` and
+/// `_`, turns spaces into `-`, and drops every other character: `"Getting Started!"`
+/// becomes `getting-started` and `"Café ☕"` becomes `café-`.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for ch in text.trim().chars() {
+        if ch.is_alphanumeric() || is_combining_mark(ch) || ch == '-' || ch == '_' {
+            slug.extend(ch.to_lowercase());
+        } else if ch.is_whitespace() {
+            slug.push('-');
+        }
+    }
+    slug
+}
+
+/// Whether `ch` is a combining mark, such as a separately encoded accent or an Indic
+/// vowel sign or virama, rather than an invisible formatting character
+fn is_combining_mark(ch: char) -> bool {
+    let formatting = matches!(
+        ch,
+        '\u{200b}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2060}'..='\u{206f}' | '\u{feff}'
+    );
+    !ch.is_alphanumeric() && !ch.is_control() && !formatting && ch.width() == Some(0)
+}
+
+/// Generates unique slugs within one document
+///
+/// Repeated headings get a numeric suffix (`intro`, `intro-1`, `intro-2`), matching
+/// the anchors GitHub generates for duplicate headings.
+#[derive(Debug, Default)]
+pub struct SlugGenerator {
+    seen: HashMap<String, usize>,
+}
+
+impl SlugGenerator {
+    /// Create a generator with no slugs issued yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a unique slug for `text`
+    pub fn generate(&mut self, text: &str) -> String {
+        let base = slugify(text);
+        let mut slug = base.clone();
+        while self.seen.contains_key(&slug) {
+            let count = self.seen.entry(base.clone()).or_insert(0);
+            *count += 1;
+            slug = format!("{}-{}", base, count);
+        }
+        self.seen.insert(slug.clone(), 0);
+        slug
+    }
+
+    /// Mark `slug` as taken, such as an explicit `{#id}`, so later generated slugs avoid it
+    pub fn reserve(&mut self, slug: &str) {
+        self.seen.entry(slug.to_string()).or_insert(0);
+    }
+}
@@ -0,0 +1,37 @@
+//! Hashtag and mention extraction.
+
+use crate::ast::{for_each_inline, Inline, Node};
+
+/// Every distinct hashtag (without `#`) in order of first appearance
+///
+/// Tags are found anywhere inline content can be, including emphasis, links, list
+/// items, table cells, and nested blocks. The document must be parsed with
+/// `enable_hashtags`. Tags differing only in case are listed separately.
+pub fn extract_hashtags(nodes: &[Node]) -> Vec<String> {
+    collect(nodes, |inline| match inline {
+        Inline::Hashtag { tag } => Some(tag.as_str()),
+        _ => None,
+    })
+}
+
+/// Every distinct mentioned user (without `@`) in order of first appearance
+///
+/// The document must be parsed with `enable_mentions`.
+pub fn extract_mentions(nodes: &[Node]) -> Vec<String> {
+    collect(nodes, |inline| match inline {
+        Inline::Mention { user } => Some(user.as_str()),
+        _ => None,
+    })
+}
+
+fn collect(nodes: &[Node], name: impl Fn(&Inline) -> Option<&str>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for_each_inline(nodes, &mut |inline| {
+        if let Some(name) = name(inline) {
+            if !names.iter().any(|known| known == name) {
+                names.push(name.to_string());
+            }
+        }
+    });
+    names
+}
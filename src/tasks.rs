@@ -0,0 +1,163 @@
+//! Task list extraction and editing.
+
+use crate::ast::{plain_text, Inline, ListItem, Node};
+use serde::{Deserialize, Serialize};
+
+/// A task list item found by [`extract_tasks`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    /// Item text without formatting
+    pub text: String,
+    /// Item inline content
+    pub content: Vec<Inline>,
+    /// Whether the box is ticked
+    pub checked: bool,
+    /// Nesting depth within its list (0 for top-level items)
+    pub depth: usize,
+    /// Text of the closest heading before the task, if any
+    pub heading: Option<String>,
+    /// Location of the item, as accepted by [`set_task_state`]
+    pub path: Vec<usize>,
+}
+
+/// Completion counts for a set of tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TaskStats {
+    /// Number of tasks
+    pub total: usize,
+    /// Number of ticked tasks
+    pub completed: usize,
+}
+
+impl TaskStats {
+    /// Count the ticked tasks in `tasks`
+    pub fn from_tasks(tasks: &[Task]) -> Self {
+        Self {
+            total: tasks.len(),
+            completed: tasks.iter().filter(|task| task.checked).count(),
+        }
+    }
+
+    /// Completed share of the tasks in percent, or `None` when there are no tasks
+    pub fn percent_complete(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.completed as f64 * 100.0 / self.total as f64)
+    }
+}
+
+/// Collect every task list item in document order
+///
+/// Tasks are found in lists at any depth, including lists inside
+/// admonitions, details blocks, containers, and sections. Regular list items are skipped
+/// but their nested tasks are not.
+pub fn extract_tasks(nodes: &[Node]) -> Vec<Task> {
+    let mut collector = TaskCollector {
+        tasks: Vec::new(),
+        heading: None,
+        path: Vec::new(),
+    };
+    collector.visit_nodes(nodes);
+    collector.tasks
+}
+
+struct TaskCollector {
+    tasks: Vec<Task>,
+    /// Text of the last heading seen
+    heading: Option<String>,
+    /// Path of the node or item being visited
+    path: Vec<usize>,
+}
+
+impl TaskCollector {
+    fn visit_nodes(&mut self, nodes: &[Node]) {
+        for (idx, node) in nodes.iter().enumerate() {
+            self.path.push(idx);
+            match node {
+                Node::Heading { content, .. } => self.heading = Some(plain_text(content)),
+                Node::Section {
+                    heading, children, ..
+                } => {
+                    self.heading = Some(plain_text(heading));
+                    self.visit_nodes(children);
+                }
+                Node::UnorderedList { items } | Node::OrderedList { items } => {
+                    self.visit_items(items, 0)
+                }
+                Node::Admonition { content, .. }
+                | Node::Details { content, .. }
+                | Node::Container {
+                    children: content, ..
+                } => self.visit_nodes(content),
+                _ => {}
+            }
+            self.path.pop();
+        }
+    }
+
+    fn visit_items(&mut self, items: &[ListItem], depth: usize) {
+        for (idx, item) in items.iter().enumerate() {
+            self.path.push(idx);
+            if let Some(checked) = item.checked {
+                self.tasks.push(Task {
+                    text: plain_text(&item.content),
+                    content: item.content.clone(),
+                    checked,
+                    depth,
+                    heading: self.heading.clone(),
+                    path: self.path.clone(),
+                });
+            }
+            self.visit_items(&item.children, depth + 1);
+            self.path.pop();
+        }
+    }
+}
+
+/// Check or uncheck the task list item at `path`
+///
+/// `path` starts with the index of a node in `nodes`. Each following index selects
+/// a child: a node of an admonition, details block, container, or section, an item
+/// of a list, or a nested item of a list item. `[2, 0, 1]` is the second nested item
+/// of the first item of the list at `nodes[2]`.
+///
+/// Returns `false`, leaving the AST unchanged, if the path does not lead to a task
+/// list item. Write the result back with [`to_markdown`](crate::to_markdown).
+pub fn set_task_state(nodes: &mut [Node], path: &[usize], checked: bool) -> bool {
+    let Some((&first, rest)) = path.split_first() else {
+        return false;
+    };
+    let Some(node) = nodes.get_mut(first) else {
+        return false;
+    };
+
+    match node {
+        Node::UnorderedList { items } | Node::OrderedList { items } => {
+            match find_item(items, rest) {
+                Some(item) if item.checked.is_some() => {
+                    item.checked = Some(checked);
+                    true
+                }
+                _ => false,
+            }
+        }
+        Node::Admonition { content, .. }
+        | Node::Details { content, .. }
+        | Node::Container {
+            children: content, ..
+        }
+        | Node::Section {
+            children: content, ..
+        } => set_task_state(content, rest, checked),
+        _ => false,
+    }
+}
+
+/// List item at `path`, where each index selects an item among the previous one's children
+fn find_item<'a>(items: &'a mut [ListItem], path: &[usize]) -> Option<&'a mut ListItem> {
+    let (&first, rest) = path.split_first()?;
+    let item = items.get_mut(first)?;
+    if rest.is_empty() {
+        Some(item)
+    } else {
+        find_item(&mut item.children, rest)
+    }
+}
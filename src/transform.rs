@@ -0,0 +1,408 @@
+//! Post-parse AST transforms.
+
+use crate::ast::{for_each_node, plain_text, Attributes, Inline, Node};
+use crate::node_path::visit_nodes_mut;
+use crate::slug::SlugGenerator;
+use std::collections::HashMap;
+
+/// Heading attribute key used to restrict a section to specific audiences
+pub const AUDIENCE_ATTRIBUTE: &str = "audience";
+
+/// Remove sections whose heading `audience` attribute does not include `audience`
+///
+/// A section is a heading plus every following node up to the next heading of the
+/// same or higher level. Headings without an `audience` attribute are visible to
+/// everyone. The attribute value may list several audiences separated by commas or
+/// whitespace (`{audience="staff, partners"}`). Removing a section also removes all
+/// of its subsections. Works on both flat and [`nest_sections`] output.
+pub fn filter_audience(nodes: &[Node], audience: &str) -> Vec<Node> {
+    let mut result = Vec::with_capacity(nodes.len());
+    // Level of the heading whose section is currently being skipped
+    let mut skipping_level: Option<u8> = None;
+
+    for node in nodes {
+        if let Node::Section {
+            level,
+            heading,
+            attributes,
+            children,
+        } = node
+        {
+            if skipping_level.is_some_and(|skip| *level > skip) {
+                continue;
+            }
+            skipping_level = None;
+
+            let visible = attributes
+                .get(AUDIENCE_ATTRIBUTE)
+                .is_none_or(|allowed| audience_matches(allowed, audience));
+            if visible {
+                result.push(Node::Section {
+                    level: *level,
+                    heading: heading.clone(),
+                    attributes: attributes.clone(),
+                    children: filter_audience(children, audience),
+                });
+            }
+            continue;
+        } else if let Node::Heading {
+            level, attributes, ..
+        } = node
+        {
+            if let Some(skip) = skipping_level {
+                if *level > skip {
+                    continue;
+                }
+                skipping_level = None;
+            }
+
+            if let Some(allowed) = attributes.get(AUDIENCE_ATTRIBUTE) {
+                if !audience_matches(allowed, audience) {
+                    skipping_level = Some(*level);
+                    continue;
+                }
+            }
+        } else if skipping_level.is_some() {
+            continue;
+        }
+
+        result.push(node.clone());
+    }
+
+    result
+}
+
+/// Check whether a comma/whitespace separated audience list contains `audience`
+fn audience_matches(allowed: &str, audience: &str) -> bool {
+    allowed
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .any(|candidate| candidate.eq_ignore_ascii_case(audience))
+}
+
+/// Shift every heading level by `offset`, clamping the result to 1-6
+///
+/// Useful when embedding a document in a larger page: an offset of 1 turns every
+/// `<h1>` into an `<h2>`.
+pub fn shift_heading_levels(nodes: &[Node], offset: i8) -> Vec<Node> {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Heading {
+                level,
+                content,
+                attributes,
+            } => Node::Heading {
+                level: shift_level(*level, offset),
+                content: content.clone(),
+                attributes: attributes.clone(),
+            },
+            Node::Section {
+                level,
+                heading,
+                attributes,
+                children,
+            } => Node::Section {
+                level: shift_level(*level, offset),
+                heading: heading.clone(),
+                attributes: attributes.clone(),
+                children: shift_heading_levels(children, offset),
+            },
+            other => other.clone(),
+        })
+        .collect()
+}
+
+fn shift_level(level: u8, offset: i8) -> u8 {
+    (i16::from(level) + i16::from(offset)).clamp(1, 6) as u8
+}
+
+/// Replace every `{{diagram:name}}` reference with a copy of the named Mermaid diagram
+///
+/// The copy has no name, so only the definition carries the diagram's anchor. When
+/// several diagrams share a name the first one wins. References to unknown names become
+/// paragraphs showing the reference as written.
+pub fn resolve_diagram_references(nodes: &[Node]) -> Vec<Node> {
+    let mut diagrams = HashMap::new();
+    for_each_node(nodes, &mut |node| {
+        if let Node::MermaidDiagram {
+            name: Some(name), ..
+        } = node
+        {
+            diagrams.entry(name.as_str()).or_insert(node);
+        }
+    });
+    replace_references(nodes, &diagrams)
+}
+
+fn replace_references(nodes: &[Node], diagrams: &HashMap<&str, &Node>) -> Vec<Node> {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::DiagramRef { name, .. } => match diagrams.get(name.as_str()) {
+                Some(Node::MermaidDiagram {
+                    diagram,
+                    config,
+                    validation_status,
+                    warnings,
+                    ..
+                }) => Node::MermaidDiagram {
+                    diagram: diagram.clone(),
+                    name: None,
+                    config: config.clone(),
+                    validation_status: validation_status.clone(),
+                    warnings: warnings.clone(),
+                },
+                _ => Node::Paragraph {
+                    content: vec![Inline::Text {
+                        content: format!("{{{{diagram:{}}}}}", name).into(),
+                    }],
+                },
+            },
+            Node::Admonition {
+                kind,
+                title,
+                content,
+            } => Node::Admonition {
+                kind: *kind,
+                title: title.clone(),
+                content: replace_references(content, diagrams),
+            },
+            Node::Details { summary, content } => Node::Details {
+                summary: summary.clone(),
+                content: replace_references(content, diagrams),
+            },
+            Node::Container {
+                attributes,
+                children,
+            } => Node::Container {
+                attributes: attributes.clone(),
+                children: replace_references(children, diagrams),
+            },
+            Node::Section {
+                level,
+                heading,
+                attributes,
+                children,
+            } => Node::Section {
+                level: *level,
+                heading: heading.clone(),
+                attributes: attributes.clone(),
+                children: replace_references(children, diagrams),
+            },
+            other => other.clone(),
+        })
+        .collect()
+}
+
+/// Number every figure from 1 in document order, replacing any existing numbers
+///
+/// Figures nested in admonitions, containers, details blocks, and sections count too.
+pub fn number_figures(nodes: &[Node]) -> Vec<Node> {
+    let mut nodes = nodes.to_vec();
+    let mut next = 1;
+    visit_nodes_mut(&mut nodes, |_, node| {
+        if let Node::Figure { number, .. } = node {
+            *number = Some(next);
+            next += 1;
+        }
+    });
+    nodes
+}
+
+/// Number every table that has a caption from 1 in document order, replacing any
+/// existing numbers
+///
+/// Tables without a caption have nowhere to show a number and are skipped.
+pub fn number_tables(nodes: &[Node]) -> Vec<Node> {
+    let mut nodes = nodes.to_vec();
+    let mut next = 1;
+    visit_nodes_mut(&mut nodes, |_, node| {
+        if let Node::Table {
+            caption, number, ..
+        } = node
+        {
+            *number = caption.is_some().then_some(next);
+            next += usize::from(caption.is_some());
+        }
+    });
+    nodes
+}
+
+/// Prefix headings with hierarchical section numbers (`1`, `1.1`, `1.2.3`)
+///
+/// Numbering starts at `from_level`: with 2, a level-1 document title stays unnumbered
+/// and its level-2 headings become `1`, `2`, and so on. A skipped level counts as 0
+/// (`1.0.1`). Headings without an `{#id}` get the id their unnumbered text would have,
+/// so anchors and the [`Outline`](crate::Outline) built from the result do not change
+/// with the numbers. Headings inside admonitions and containers are not part of the
+/// outline and are left alone.
+pub fn number_headings(nodes: &[Node], from_level: u8) -> Vec<Node> {
+    HeadingNumbers {
+        counters: [0; 6],
+        from_level: usize::from(from_level.clamp(1, 6)),
+        slugs: SlugGenerator::new(),
+    }
+    .apply(nodes)
+}
+
+struct HeadingNumbers {
+    /// Count of headings at each level since the last heading above it
+    counters: [usize; 6],
+    from_level: usize,
+    slugs: SlugGenerator,
+}
+
+impl HeadingNumbers {
+    fn apply(&mut self, nodes: &[Node]) -> Vec<Node> {
+        nodes
+            .iter()
+            .map(|node| match node {
+                Node::Heading {
+                    level,
+                    content,
+                    attributes,
+                } => {
+                    let (content, attributes) = self.number(*level, content, attributes);
+                    Node::Heading {
+                        level: *level,
+                        content,
+                        attributes,
+                    }
+                }
+                Node::Section {
+                    level,
+                    heading,
+                    attributes,
+                    children,
+                } => {
+                    let (heading, attributes) = self.number(*level, heading, attributes);
+                    Node::Section {
+                        level: *level,
+                        heading,
+                        attributes,
+                        children: self.apply(children),
+                    }
+                }
+                other => other.clone(),
+            })
+            .collect()
+    }
+
+    fn number(
+        &mut self,
+        level: u8,
+        content: &[Inline],
+        attributes: &Attributes,
+    ) -> (Vec<Inline>, Attributes) {
+        let mut attributes = attributes.clone();
+        match &attributes.id {
+            Some(id) => self.slugs.reserve(id),
+            None => {
+                let slug = self.slugs.generate(&plain_text(content));
+                attributes.id = (!slug.is_empty()).then_some(slug);
+            }
+        }
+        let level = usize::from(level.clamp(1, 6));
+        if level < self.from_level {
+            return (content.to_vec(), attributes);
+        }
+        self.counters[level - 1] += 1;
+        self.counters[level..].fill(0);
+        let number: Vec<String> = self.counters[self.from_level - 1..level]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let mut numbered = vec![Inline::Text {
+            content: format!("{} ", number.join(".")).into(),
+        }];
+        numbered.extend_from_slice(content);
+        (numbered, attributes)
+    }
+}
+
+/// Shift headings so the shallowest heading level in the document becomes level 1
+///
+/// A document whose top-level headings are `###` is rendered as if they were `#`,
+/// preserving the relative structure below them.
+pub fn normalize_heading_levels(nodes: &[Node]) -> Vec<Node> {
+    match min_heading_level(nodes) {
+        Some(min) if min > 1 => shift_heading_levels(nodes, 1 - min as i8),
+        _ => nodes.to_vec(),
+    }
+}
+
+/// Shallowest heading level in the nodes, including headings of nested sections
+fn min_heading_level(nodes: &[Node]) -> Option<u8> {
+    nodes
+        .iter()
+        .filter_map(|node| match node {
+            Node::Heading { level, .. } => Some(*level),
+            Node::Section { level, .. } => Some(*level),
+            _ => None,
+        })
+        .min()
+}
+
+/// Nest nodes under `Node::Section` based on heading levels
+///
+/// Each heading becomes a section holding the nodes that follow it, up to the next
+/// heading of the same or a higher level; deeper headings become nested sections.
+/// Nodes before the first heading stay at the top level. Skipped levels (`#`
+/// followed by `###`) nest under the nearest shallower heading.
+pub fn nest_sections(nodes: &[Node]) -> Vec<Node> {
+    let mut result = Vec::new();
+    // Open sections, shallowest first
+    let mut stack: Vec<OpenSection> = Vec::new();
+
+    for node in nodes {
+        match node {
+            Node::Heading {
+                level,
+                content,
+                attributes,
+            } => {
+                close_sections(&mut stack, &mut result, *level);
+                stack.push(OpenSection {
+                    level: *level,
+                    heading: content.clone(),
+                    attributes: attributes.clone(),
+                    children: Vec::new(),
+                });
+            }
+            other => match stack.last_mut() {
+                Some(section) => section.children.push(other.clone()),
+                None => result.push(other.clone()),
+            },
+        }
+    }
+    close_sections(&mut stack, &mut result, 0);
+
+    result
+}
+
+/// A section whose closing heading has not been reached yet
+struct OpenSection {
+    level: u8,
+    heading: Vec<Inline>,
+    attributes: Attributes,
+    children: Vec<Node>,
+}
+
+/// Close every open section at `level` or deeper, attaching each to its parent
+fn close_sections(stack: &mut Vec<OpenSection>, result: &mut Vec<Node>, level: u8) {
+    while stack.last().is_some_and(|section| section.level >= level) {
+        let Some(section) = stack.pop() else {
+            break;
+        };
+        let node = Node::Section {
+            level: section.level,
+            heading: section.heading,
+            attributes: section.attributes,
+            children: section.children,
+        };
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => result.push(node),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use md_parser::{audit_accessibility, AccessibilityRule, Parser, Renderer, RendererConfig};
+
+const INPUT: &str = "# Guide\n\n### Too deep\n\n![](diagram.png) and ![Logo](logo.png)\n\n\
+                     See [ ](https://example.com).\n\n- [x] Done\n- [ ] \n\n\
+                     | Name | Role |\n|------|------|\n| Ann | Dev |\n";
+
+#[test]
+fn test_audit_lists_violations_with_spans() {
+    let result = Parser::new(INPUT.to_string())
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    let issues = audit_accessibility(&result);
+    let rules: Vec<_> = issues.iter().map(|issue| issue.rule).collect();
+    assert_eq!(
+        rules,
+        [
+            AccessibilityRule::SkippedHeadingLevel,
+            AccessibilityRule::MissingAltText,
+            AccessibilityRule::EmptyLinkText,
+            AccessibilityRule::UnlabeledCheckbox,
+        ]
+    );
+    assert_eq!(issues[1].span.unwrap().start, 5);
+    assert_eq!(issues[1].path.to_string(), "2");
+    assert_eq!(
+        issues[1].to_string(),
+        "line 5: accessibility: image `diagram.png` has no alt text [missing-alt-text]"
+    );
+    assert_eq!(issues[3].span.unwrap().start, 9);
+
+    let clean = Parser::new("# A\n\n## B\n\n![Chart](c.png)\n\n# C\n".to_string())
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    assert!(audit_accessibility(&clean).is_empty());
+}
+
+#[test]
+fn test_accessible_mode_adds_scopes_labels_and_landmarks() {
+    let ast = Parser::new(INPUT.to_string()).unwrap().parse().unwrap();
+    let renderer = Renderer::new(RendererConfig {
+        accessible: true,
+        ..RendererConfig::default()
+    })
+    .unwrap();
+
+    let html = renderer.render_html(&ast);
+    assert!(html.contains("<th scope=\"col\">Name</th>"), "{}", html);
+    assert!(html.contains("<input type=\"checkbox\" disabled checked aria-label=\"Done\"> Done"));
+    assert!(html.contains("<input type=\"checkbox\" disabled> </li>"));
+    let skip = html
+        .find("<a class=\"skip-link\" href=\"#main-content\">")
+        .unwrap();
+    let main = html
+        .find("<main id=\"main-content\">\n<h1>Guide</h1>")
+        .unwrap();
+    assert!(skip < main);
+    assert!(html.find("</main>").unwrap() > html.find("</table>").unwrap());
+
+    // Fragments have no page to hold landmarks
+    assert!(!renderer.render_fragment(&ast).contains("<main"));
+
+    let plain = Renderer::new(RendererConfig::default())
+        .unwrap()
+        .render_html(&ast);
+    assert!(plain.contains("<th>Name</th>"));
+    assert!(!plain.contains("<main") && !plain.contains("aria-label"));
+}
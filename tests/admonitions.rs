@@ -0,0 +1,105 @@
+mod common;
+
+use common::parse;
+use md_parser::{AdmonitionKind, Inline, Node, Parser, ParserConfig, WarningCode};
+
+fn text(content: &str) -> Node {
+    Node::Paragraph {
+        content: vec![Inline::Text {
+            content: content.into(),
+        }],
+    }
+}
+
+#[test]
+fn test_github_alert_becomes_admonition() {
+    let ast = parse("> [!WARNING]\n> Back up first.\n>\n> - one\n> - two\n\nAfter.");
+
+    assert_eq!(ast.len(), 2);
+    match &ast[0] {
+        Node::Admonition {
+            kind,
+            title: None,
+            content,
+        } => {
+            assert_eq!(*kind, AdmonitionKind::Warning);
+            assert_eq!(content[0], text("Back up first."));
+            assert!(matches!(content[1], Node::UnorderedList { .. }));
+        }
+        other => panic!("expected admonition, got {:?}", other),
+    }
+    assert_eq!(ast[1], text("After."));
+}
+
+#[test]
+fn test_unknown_alert_type_stays_blockquote() {
+    let ast = parse("> [!RANDOM]\n> Text");
+    assert!(matches!(ast[0], Node::Blockquote { .. }));
+}
+
+#[test]
+fn test_fenced_admonition_with_nested_blocks() {
+    let ast = parse("Intro\n:::tip\nUse `--stdin`.\n\n```sh\nmd-parser -\n```\n:::\nOutro");
+
+    assert_eq!(ast.len(), 3);
+    assert_eq!(ast[0], text("Intro"));
+    match &ast[1] {
+        Node::Admonition {
+            kind,
+            title: None,
+            content,
+        } => {
+            assert_eq!(*kind, AdmonitionKind::Tip);
+            assert_eq!(content.len(), 2);
+            assert!(matches!(content[1], Node::CodeBlock { .. }));
+        }
+        other => panic!("expected admonition, got {:?}", other),
+    }
+    assert_eq!(ast[2], text("Outro"));
+}
+
+#[test]
+fn test_unclosed_fenced_admonition_warns_with_line() {
+    let mut parser = Parser::new("# Title\n\n:::note\nBody **open".to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert!(matches!(ast[1], Node::Admonition { .. }));
+    let codes: Vec<_> = parser
+        .warnings()
+        .iter()
+        .map(|w| (w.code, w.span.line))
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            (WarningCode::UnclosedContainer, 3),
+            (WarningCode::UnclosedEmphasis, 4)
+        ]
+    );
+}
+
+#[test]
+fn test_admonition_html() {
+    let html = Parser::new("> [!NOTE]\n> Read **this**.".to_string())
+        .unwrap()
+        .to_html()
+        .unwrap();
+
+    assert!(html.contains(
+        "<div class=\"admonition admonition-note\">\n<p class=\"admonition-title\">Note</p>\n<p>Read <strong>this</strong>.</p>\n</div>"
+    ));
+}
+
+#[test]
+fn test_admonitions_can_be_disabled() {
+    let config = ParserConfig {
+        enable_admonitions: false,
+        ..ParserConfig::default()
+    };
+    let ast = Parser::with_config("> [!NOTE]\n> Text".to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    assert!(matches!(ast[0], Node::Blockquote { .. }));
+}
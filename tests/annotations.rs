@@ -0,0 +1,60 @@
+mod common;
+
+use common::parse;
+use md_parser::{annotate, Annotations, Node, NodePath, Renderer, RendererConfig};
+use std::collections::HashMap;
+
+#[test]
+fn test_annotation_pass_collects_metadata() {
+    let ast = parse("# Title\n\nFirst\n\n> [!NOTE]\n> Nested\n");
+    let annotations = annotate(&ast, |path, node| match node {
+        Node::Paragraph { .. } => HashMap::from([("path".to_string(), path.to_string())]),
+        _ => HashMap::new(),
+    });
+
+    let paths: Vec<String> = annotations
+        .iter()
+        .map(|(path, _)| path.to_string())
+        .collect();
+    assert_eq!(paths, ["1", "2.0"]);
+    assert_eq!(
+        annotations.get(&NodePath::from(vec![2, 0])).unwrap()["path"],
+        "2.0"
+    );
+    assert!(annotations.get(&NodePath::from(vec![0])).is_none());
+}
+
+#[test]
+fn test_annotations_render_as_data_attributes() {
+    let ast = parse("# Title\n\nText\n\n---\n\n> [!NOTE]\n> Nested\n");
+    let mut annotations = Annotations::new();
+    annotations.insert(NodePath::from(vec![0]), "author", "Ada <ada@example.com>");
+    annotations.insert(NodePath::from(vec![0]), "Review Status", "approved");
+    annotations.insert(NodePath::from(vec![2]), "author", "Grace");
+    annotations.insert(NodePath::from(vec![3, 0]), "author", "Linus");
+
+    let renderer = Renderer::new(RendererConfig::default()).unwrap();
+    let html = renderer.render_fragment_annotated(&ast, &annotations);
+    assert!(
+        html.contains(
+            "<h1 data-author=\"Ada &lt;ada@example.com&gt;\" data-review-status=\"approved\""
+        ),
+        "{}",
+        html
+    );
+    assert!(html.contains("<p>Text</p>"), "{}", html);
+    assert!(html.contains("<hr data-author=\"Grace\""), "{}", html);
+    assert!(
+        html.contains("<p data-author=\"Linus\">Nested</p>"),
+        "{}",
+        html
+    );
+
+    // Without annotations the output is unchanged
+    assert_eq!(
+        renderer.render_fragment_annotated(&ast, &Annotations::new()),
+        renderer.render_fragment(&ast)
+    );
+    let page = renderer.render_html_annotated(&ast, &annotations);
+    assert!(page.contains("data-author=\"Linus\""));
+}
@@ -0,0 +1,35 @@
+use md_parser::{from_arena_ast, to_arena_ast, ArenaInline, ArenaNode, Bump, Parser, ParserConfig};
+
+#[test]
+fn test_arena_ast_round_trip() {
+    let source = "# Guide {#top .intro}\n\n- [x] Done with **bold**\n  - nested\n\n\
+                  | a | b |\n|:--|--:|\n| `x` | [y](z) |\n\n\
+                  ```mermaid\ngraph TD\n    A-->B\n```\n\n\
+                  > [!NOTE]\n> Mind the ![gap](gap.png)\n";
+    let config = ParserConfig {
+        enable_attributes: true,
+        enable_sections: true,
+        ..ParserConfig::default()
+    };
+    let ast = Parser::with_config(source.to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let arena = Bump::new();
+    let nodes = to_arena_ast(&arena, &ast);
+    let ArenaNode::Section {
+        heading,
+        attributes,
+        ..
+    } = nodes[0]
+    else {
+        panic!("expected a section, got {:?}", nodes[0]);
+    };
+    assert_eq!(heading, [ArenaInline::Text { content: "Guide" }]);
+    assert_eq!(attributes.id, Some("top"));
+    assert_eq!(attributes.classes, ["intro"]);
+    assert!(arena.allocated_bytes() > 0);
+
+    assert_eq!(from_arena_ast(nodes), ast);
+}
@@ -0,0 +1,85 @@
+use md_parser::Parser;
+
+fn asciidoc(input: &str) -> String {
+    Parser::new(input.to_string())
+        .unwrap()
+        .to_asciidoc()
+        .unwrap()
+}
+
+fn rst(input: &str) -> String {
+    Parser::new(input.to_string()).unwrap().to_rst().unwrap()
+}
+
+#[test]
+fn test_asciidoc_headings_and_inline_formatting() {
+    let output = asciidoc("# Title\n\n## Setup\n\nSome **bold**, *italic* and `code`.");
+
+    assert!(output.contains("== Title\n"));
+    assert!(output.contains("=== Setup\n"));
+    assert!(output.contains("Some *bold*, _italic_ and `+code+`."));
+}
+
+#[test]
+fn test_asciidoc_keeps_deep_headings_distinct() {
+    let output = asciidoc("##### Five\n\n###### Six");
+
+    assert!(output.contains("====== Five\n"));
+    assert!(!output.contains("[discrete]\n====== Five"));
+    assert!(output.contains("[discrete]\n====== Six\n"));
+}
+
+#[test]
+fn test_asciidoc_blocks() {
+    let input =
+        "- one\n  - nested\n\n```rust\nfn main() {}\n```\n\n| A | B |\n|:---:|---:|\n| 1 | 2 |";
+    let output = asciidoc(input);
+
+    assert!(output.contains("* one\n** nested\n"));
+    assert!(output.contains("[source,rust]\n----\nfn main() {}\n----\n"));
+    assert!(output.contains("[%header,cols=\"^,>\"]\n|===\n|A |B\n\n|1 |2\n|===\n"));
+}
+
+#[test]
+fn test_asciidoc_links_and_images() {
+    let output = asciidoc("[Docs](https://example.com) ![Logo](logo.png)");
+
+    assert!(output.contains("link:https://example.com[Docs]"));
+    assert!(output.contains("image:logo.png[Logo]"));
+}
+
+#[test]
+fn test_rst_headings_use_underlines_matching_title_length() {
+    let output = rst("# Title\n\n## Getting started");
+
+    assert!(output.contains("Title\n=====\n"));
+    assert!(output.contains("Getting started\n---------------\n"));
+}
+
+#[test]
+fn test_rst_inline_markup_and_images() {
+    let output = rst("Use **bold**, `code` and [docs](https://example.com). ![Logo](logo.png)");
+
+    assert!(output.contains("Use **bold**, ``code`` and `docs <https://example.com>`__."));
+    assert!(output.contains("|image-1|"));
+    assert!(output.contains(".. |image-1| image:: logo.png\n   :alt: Logo"));
+}
+
+#[test]
+fn test_rst_code_blocks_and_tables() {
+    let input = "```python\nprint(1)\n```\n\n| A | B |\n|---|---|\n| 1 | 2 |";
+    let output = rst(input);
+
+    assert!(output.contains(".. code-block:: python\n\n   print(1)\n"));
+    assert!(output.contains(
+        ".. list-table::\n   :header-rows: 1\n\n   * - A\n     - B\n   * - 1\n     - 2\n"
+    ));
+}
+
+#[test]
+fn test_admonitions_use_native_blocks() {
+    let input = "> [!CAUTION]\n> Mind the gap.";
+
+    assert!(asciidoc(input).contains("[CAUTION]\n====\nMind the gap.\n====\n"));
+    assert!(rst(input).contains(".. caution::\n\n   Mind the gap.\n"));
+}
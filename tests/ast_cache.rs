@@ -0,0 +1,68 @@
+mod common;
+
+use md_parser::{Inline, Node, Parser, ParserConfig};
+use std::fs;
+
+fn paragraph(text: &str) -> Node {
+    Node::Paragraph {
+        content: vec![Inline::Text {
+            content: text.into(),
+        }],
+    }
+}
+
+#[test]
+fn test_outputs_reuse_the_cached_ast() {
+    // An included file changing on disk shows whether the document was parsed again
+    let dir = common::temp_path("ast_cache");
+    fs::create_dir_all(&dir).unwrap();
+    let part = dir.join("part.md");
+    fs::write(&part, "First").unwrap();
+    let config = ParserConfig {
+        enable_includes: true,
+        base_dir: Some(dir.to_string_lossy().into_owned()),
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config("{{include: part.md}}".to_string(), config).unwrap();
+
+    assert_eq!(parser.parse().unwrap(), vec![paragraph("First")]);
+    fs::write(&part, "Second").unwrap();
+    assert!(parser.to_json().unwrap().contains("First"));
+    assert!(parser.to_markdown().unwrap().contains("First"));
+    assert_eq!(parser.parse().unwrap(), vec![paragraph("First")]);
+
+    parser.clear_cache();
+    let second = parser.parse().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(second, vec![paragraph("Second")]);
+}
+
+#[test]
+fn test_cache_invalidated_by_new_input_or_config() {
+    let mut parser = Parser::new("#hashtag".to_string()).unwrap();
+    assert!(matches!(parser.parse().unwrap()[0], Node::Heading { .. }));
+
+    parser
+        .set_config(ParserConfig {
+            strict_atx: true,
+            ..ParserConfig::default()
+        })
+        .unwrap();
+    assert_eq!(parser.parse().unwrap(), vec![paragraph("#hashtag")]);
+
+    parser.set_input("Plain".to_string());
+    assert_eq!(parser.parse().unwrap(), vec![paragraph("Plain")]);
+}
+
+#[test]
+fn test_cache_hit_restores_warnings() {
+    let mut parser = Parser::new("Some **bold".to_string()).unwrap();
+    parser.parse().unwrap();
+    assert_eq!(parser.warnings().len(), 1);
+
+    parser.parse_str("Clean text").unwrap();
+    assert!(parser.warnings().is_empty());
+
+    parser.to_json().unwrap();
+    assert_eq!(parser.warnings().len(), 1);
+}
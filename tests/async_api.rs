@@ -0,0 +1,55 @@
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+use md_parser::{Parser, RendererConfig, WarningCode};
+
+/// Minimal executor: poll on the current thread, parking until woken
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn test_parse_async_matches_parse() {
+    let input = "# Title\n\nSome **bold text\n\n- [x] done";
+    let mut sync_parser = Parser::new(input.to_string()).unwrap();
+    let expected = sync_parser.parse().unwrap();
+
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let ast = block_on(parser.parse_async()).unwrap();
+
+    assert_eq!(ast, expected);
+    assert_eq!(parser.warnings(), sync_parser.warnings());
+    assert_eq!(parser.warnings()[0].code, WarningCode::UnclosedEmphasis);
+}
+
+#[test]
+fn test_parse_async_reports_errors() {
+    let mut parser = Parser::new("```rust\nfn main() {}".to_string()).unwrap();
+    assert!(block_on(parser.parse_async()).is_err());
+}
+
+#[test]
+fn test_to_html_async() {
+    let mut parser = Parser::new("# Hello\n\nWorld".to_string()).unwrap();
+    let html = block_on(parser.to_html_async(&RendererConfig::default())).unwrap();
+    assert!(html.contains("<h1"));
+    assert!(html.contains("<p>World</p>"));
+}
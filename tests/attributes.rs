@@ -0,0 +1,121 @@
+mod common;
+
+use common::text;
+use md_parser::{Attributes, Inline, Node, Parser, ParserConfig, RendererConfig};
+use std::collections::BTreeMap;
+
+fn attributes(id: Option<&str>, classes: &[&str], pairs: &[(&str, &str)]) -> Attributes {
+    Attributes {
+        id: id.map(str::to_string),
+        classes: classes.iter().map(|c| c.to_string()).collect(),
+        pairs: pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+#[test]
+fn test_code_block_attributes() {
+    let config = ParserConfig {
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    let ast = common::parse_with(
+        "```rust {#main .numbered start=10}\nfn main() {}\n```",
+        config.clone(),
+    );
+    assert_eq!(
+        ast,
+        vec![Node::CodeBlock {
+            lang: Some("rust".to_string()),
+            code: "fn main() {}".to_string(),
+            attributes: attributes(Some("main"), &["numbered"], &[("start", "10")]),
+        }]
+    );
+
+    let ast = common::parse_with("``` {.shell}\nls\n```", config);
+    assert!(matches!(
+        &ast[0],
+        Node::CodeBlock { lang: None, attributes, .. } if attributes.classes == ["shell"]
+    ));
+}
+
+#[test]
+fn test_image_and_span_attributes() {
+    let ast = common::parse_with(
+        "![Logo](logo.png){#logo .small width=64} and [**key** term]{.term lang=de} [x]{}",
+        ParserConfig {
+            enable_attributes: true,
+            ..ParserConfig::default()
+        },
+    );
+    assert_eq!(
+        ast,
+        vec![Node::Paragraph {
+            content: vec![
+                Inline::Image {
+                    alt: "Logo".to_string(),
+                    url: "logo.png".to_string(),
+                    attributes: attributes(Some("logo"), &["small"], &[("width", "64")]),
+                },
+                text(" and "),
+                Inline::Span {
+                    content: vec![
+                        Inline::Bold {
+                            content: vec![text("key")]
+                        },
+                        text(" term"),
+                    ],
+                    attributes: attributes(None, &["term"], &[("lang", "de")]),
+                },
+                // An empty attribute list is not an attribute list
+                text(" [x]{}"),
+            ]
+        }]
+    );
+}
+
+#[test]
+fn test_attributes_disabled_by_default() {
+    let ast = common::parse("```rust {.x}\ncode\n```\n\n![a](b.png){.c} [d]{.e}");
+    assert!(matches!(
+        &ast[0],
+        Node::CodeBlock { lang: Some(lang), attributes, .. }
+            if lang == "rust {.x}" && attributes.is_empty()
+    ));
+    let Node::Paragraph { content } = &ast[1] else {
+        panic!("expected a paragraph, got {:?}", ast[1]);
+    };
+    assert!(matches!(&content[0], Inline::Image { attributes, .. } if attributes.is_empty()));
+    assert_eq!(content[1], text("{.c} [d]{.e}"));
+}
+
+#[test]
+fn test_attributes_in_html_and_markdown() {
+    let input = "## Title {#top}\n\n```rust {#main .numbered}\nfn main() {}\n```\n\n![Logo](logo.png){.small width=64 alt-text=x} [term]{.term}\n";
+    let config = ParserConfig {
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config(input.to_string(), config).unwrap();
+    let html = parser
+        .to_html_with_config(&RendererConfig::default())
+        .unwrap();
+    assert!(html.contains("<h2 id=\"top\">Title</h2>"), "{}", html);
+    assert!(html.contains(
+        "<pre id=\"main\" class=\"numbered\"><code class=\"language-rust\">fn main() {}</code></pre>"
+    ));
+    assert!(html.contains(
+        "<img src=\"logo.png\" alt=\"Logo\" class=\"small\" data-alt-text=\"x\" width=\"64\" />"
+    ));
+    assert!(html.contains("<span class=\"term\">term</span>"));
+
+    let markdown = parser.to_markdown().unwrap();
+    assert!(
+        markdown.contains("```rust {#main .numbered}\n"),
+        "{}",
+        markdown
+    );
+    assert!(markdown.contains("![Logo](logo.png){.small alt-text=x width=64} [term]{.term}"));
+}
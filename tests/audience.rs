@@ -0,0 +1,80 @@
+use md_parser::{filter_audience, Node, Parser, ParserConfig, RendererConfig};
+
+fn parse_with_attributes(input: &str) -> Vec<Node> {
+    let config = ParserConfig {
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config(input.to_string(), config).unwrap();
+    parser.parse().unwrap()
+}
+
+#[test]
+fn test_heading_attributes_parsed_when_enabled() {
+    let result = parse_with_attributes("## Internals {#internals .secret audience=staff}");
+
+    assert_eq!(result.len(), 1);
+    match &result[0] {
+        Node::Heading {
+            level, attributes, ..
+        } => {
+            assert_eq!(*level, 2);
+            assert_eq!(attributes.id.as_deref(), Some("internals"));
+            assert_eq!(attributes.classes, vec!["secret".to_string()]);
+            assert_eq!(attributes.get("audience"), Some("staff"));
+        }
+        _ => panic!("Expected Heading"),
+    }
+}
+
+#[test]
+fn test_heading_attributes_ignored_by_default() {
+    let mut parser = Parser::new("## Internals {audience=staff}".to_string()).unwrap();
+    let result = parser.parse().unwrap();
+
+    match &result[0] {
+        Node::Heading { attributes, .. } => assert!(attributes.is_empty()),
+        _ => panic!("Expected Heading"),
+    }
+}
+
+#[test]
+fn test_filter_removes_section_and_subsections() {
+    let input = "# Guide\n\nIntro.\n\n## Internals {audience=staff}\n\nSecret.\n\n### Deep\n\nMore secret.\n\n## Usage\n\nPublic.";
+    let ast = parse_with_attributes(input);
+
+    let public = filter_audience(&ast, "public");
+    assert_eq!(public.len(), 4, "Guide, Intro, Usage, Public remain");
+    assert!(public.iter().all(|n| !format!("{:?}", n).contains("ecret")));
+
+    let staff = filter_audience(&ast, "staff");
+    assert_eq!(staff.len(), ast.len());
+}
+
+#[test]
+fn test_filter_supports_multiple_audiences() {
+    let ast = parse_with_attributes("## Roadmap {audience=\"staff, partners\"}\n\nPlans.");
+
+    assert_eq!(filter_audience(&ast, "partners").len(), 2);
+    assert_eq!(filter_audience(&ast, "public").len(), 0);
+}
+
+#[test]
+fn test_renderer_applies_audience() {
+    let config = ParserConfig {
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    let input = "# Doc\n\n## Internals {audience=staff}\n\nStaff only text.".to_string();
+    let mut parser = Parser::with_config(input, config).unwrap();
+
+    let public_config = RendererConfig {
+        audience: Some("public".to_string()),
+        ..RendererConfig::default()
+    };
+    let html = parser.to_html_with_config(&public_config).unwrap();
+    assert!(!html.contains("Staff only text"));
+
+    let html = parser.to_html().unwrap();
+    assert!(html.contains("Staff only text"));
+}
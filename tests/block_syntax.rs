@@ -0,0 +1,111 @@
+use md_parser::{
+    AdmonitionKind, Attributes, BlockSyntax, Inline, Node, Parser, Renderer, RendererConfig,
+};
+
+/// `@endpoint GET /users` followed by indented description lines
+fn endpoint_syntax() -> BlockSyntax {
+    BlockSyntax::new("endpoint", |lines, idx| {
+        let rest = lines[idx].trim().strip_prefix("@endpoint ")?;
+        let (method, path) = rest.split_once(' ')?;
+        let end = lines[idx + 1..]
+            .iter()
+            .position(|line| !line.starts_with("    "))
+            .map_or(lines.len(), |offset| idx + 1 + offset);
+        let description = lines[idx + 1..end]
+            .iter()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut attributes = Attributes {
+            classes: vec!["api-endpoint".to_string()],
+            ..Attributes::default()
+        };
+        attributes
+            .pairs
+            .insert("method".to_string(), method.to_string());
+        attributes
+            .pairs
+            .insert("path".to_string(), path.to_string());
+        let node = Node::Container {
+            attributes,
+            children: vec![Node::Paragraph {
+                content: vec![Inline::Text {
+                    content: description.into(),
+                }],
+            }],
+        };
+        Some((node, end))
+    })
+}
+
+fn parse(input: &str) -> Vec<Node> {
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    parser.add_block_syntax(endpoint_syntax());
+    parser.parse().unwrap()
+}
+
+#[test]
+fn test_custom_block_syntax() {
+    let ast = parse(
+        "# API\n\n@endpoint GET /users\n    Lists all users.\n    Paginated.\nAfter the block.\n",
+    );
+    assert_eq!(ast.len(), 3, "{:?}", ast);
+    let Node::Container {
+        attributes,
+        children,
+    } = &ast[1]
+    else {
+        panic!("expected the endpoint container, got {:?}", ast[1]);
+    };
+    assert_eq!(attributes.get("method"), Some("GET"));
+    assert_eq!(attributes.get("path"), Some("/users"));
+    assert_eq!(
+        children,
+        &[Node::Paragraph {
+            content: vec![Inline::Text {
+                content: "Lists all users. Paginated.".into()
+            }]
+        }]
+    );
+    assert!(matches!(&ast[2], Node::Paragraph { .. }));
+
+    let html = Renderer::new(RendererConfig::default())
+        .unwrap()
+        .render_html(&ast);
+    assert!(html.contains("<div class=\"api-endpoint\" data-method=\"GET\" data-path=\"/users\">"));
+}
+
+#[test]
+fn test_custom_block_syntax_in_nested_blocks_and_fallthrough() {
+    let ast = parse("> [!NOTE]\n> @endpoint DELETE /users/1\n\n@endpoint\n\n- @endpoint POST /x\n");
+    let Node::Admonition { kind, content, .. } = &ast[0] else {
+        panic!("expected an admonition, got {:?}", ast[0]);
+    };
+    assert_eq!(*kind, AdmonitionKind::Note);
+    assert!(
+        matches!(&content[0], Node::Container { attributes, .. } if attributes.get("method") == Some("DELETE"))
+    );
+    // Not the custom syntax: parsed as usual
+    assert!(matches!(&ast[1], Node::Paragraph { .. }));
+    // Custom syntaxes only start blocks, not list items
+    assert!(matches!(&ast[2], Node::UnorderedList { .. }));
+}
+
+#[test]
+fn test_custom_block_syntax_takes_precedence_and_always_advances() {
+    let mut parser = Parser::new("- not a list\n- either\n".to_string()).unwrap();
+    parser.add_block_syntax(BlockSyntax::new("rule", |lines, idx| {
+        lines[idx]
+            .starts_with("- ")
+            .then_some((Node::HorizontalRule { span: None }, idx))
+    }));
+    let ast = parser.parse().unwrap();
+    assert_eq!(
+        ast,
+        vec![
+            Node::HorizontalRule { span: None },
+            Node::HorizontalRule { span: None }
+        ]
+    );
+}
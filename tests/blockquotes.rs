@@ -8,13 +8,13 @@ fn test_simple_blockquote() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 1);
             assert_eq!(content.len(), 1);
             assert_eq!(
                 content[0],
                 Inline::Text {
-                    content: "This is a simple blockquote.".to_string()
+                    content: "This is a simple blockquote.".into()
                 }
             );
         }
@@ -31,7 +31,7 @@ fn test_multiline_blockquote() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 1);
             // Content should be joined with spaces
             assert!(!content.is_empty());
@@ -39,7 +39,7 @@ fn test_multiline_blockquote() {
                 .iter()
                 .map(|inline| match inline {
                     Inline::Text { content } => content.clone(),
-                    _ => String::new(),
+                    _ => String::new().into(),
                 })
                 .collect();
             assert!(text_content.contains("First line"));
@@ -58,7 +58,7 @@ fn test_blockquote_with_inline_formatting() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 1);
             // Should have bold and italic elements
             let has_bold = content
@@ -82,7 +82,7 @@ fn test_blockquote_with_link() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 1);
             let has_link = content
                 .iter()
@@ -101,13 +101,13 @@ fn test_nested_blockquote_level_2() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 2);
             assert_eq!(content.len(), 1);
             assert_eq!(
                 content[0],
                 Inline::Text {
-                    content: "This is a nested blockquote.".to_string()
+                    content: "This is a nested blockquote.".into()
                 }
             );
         }
@@ -123,13 +123,13 @@ fn test_nested_blockquote_level_3() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 3);
             assert_eq!(content.len(), 1);
             assert_eq!(
                 content[0],
                 Inline::Text {
-                    content: "This is a deeply nested blockquote.".to_string()
+                    content: "This is a deeply nested blockquote.".into()
                 }
             );
         }
@@ -274,7 +274,7 @@ fn test_blockquote_with_space_after_gt() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 1);
             assert!(!content.is_empty());
         }
@@ -290,7 +290,7 @@ fn test_blockquote_without_space_after_gt() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 1);
             assert!(!content.is_empty());
         }
@@ -306,7 +306,7 @@ fn test_empty_blockquote() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 1);
             assert!(content.is_empty());
         }
@@ -322,7 +322,7 @@ fn test_blockquote_with_only_whitespace() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Blockquote { level, content } => {
+        Node::Blockquote { level, content, .. } => {
             assert_eq!(*level, 1);
             // Whitespace-only content should result in empty or minimal content
             assert!(
@@ -356,3 +356,60 @@ fn test_blockquote_mixed_with_other_elements() {
         _ => panic!("Expected Paragraph"),
     }
 }
+
+#[test]
+fn test_fenced_code_block_nested_in_blockquote() {
+    let input =
+        "> Example:\n> ```rust\n> fn main() {\n>\n>     run();\n> }\n> ```\n> Done.\n\nAfter.";
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+
+    assert_eq!(ast.len(), 2, "{:?}", ast);
+    let Node::Blockquote {
+        level,
+        content,
+        blocks,
+    } = &ast[0]
+    else {
+        panic!("expected blockquote, got {:?}", ast[0]);
+    };
+    assert_eq!(*level, 1);
+    assert_eq!(
+        content,
+        &vec![Inline::Text {
+            content: "Example:".into()
+        }]
+    );
+    assert_eq!(
+        blocks[0],
+        Node::CodeBlock {
+            lang: Some("rust".to_string()),
+            code: "fn main() {\n\n    run();\n}".to_string(),
+            attributes: Default::default(),
+        }
+    );
+    assert!(matches!(&blocks[1], Node::Paragraph { .. }));
+
+    // Markdown output keeps the fence inside the quote
+    let markdown = Parser::new(input.to_string())
+        .unwrap()
+        .to_markdown()
+        .unwrap();
+    assert!(
+        markdown.starts_with("> Example:\n> ```rust\n> fn main() {\n>\n>     run();\n> }\n> ```\n"),
+        "{}",
+        markdown
+    );
+}
+
+#[test]
+fn test_unclosed_fence_in_blockquote_reports_its_line() {
+    let err = Parser::new("Intro.\n\n> ```rust\n> code\n".to_string())
+        .unwrap()
+        .parse()
+        .unwrap_err();
+    assert!(
+        matches!(&err, md_parser::ParseError::UnclosedCodeBlock { span } if span.line == 3),
+        "{:?}",
+        err
+    );
+}
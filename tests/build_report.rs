@@ -0,0 +1,62 @@
+use md_parser::{
+    lint, BuildReport, FileReport, LintConfig, LintRule, ParseWarning, Parser, Severity, Span,
+    WarningCode,
+};
+use std::time::Duration;
+
+#[test]
+fn test_file_report_counts_nodes() {
+    let mut parser = Parser::new("# Title\n\nOne.\n\nTwo.\n\n---".to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+
+    let report = FileReport::new("doc.md", &ast, &[], Duration::ZERO);
+    assert_eq!(report.node_total, 4);
+    assert_eq!(report.node_counts.get("heading"), Some(&1));
+    assert_eq!(report.node_counts.get("paragraph"), Some(&2));
+    assert_eq!(report.node_counts.get("horizontal_rule"), Some(&1));
+}
+
+#[test]
+fn test_build_report_totals_and_json() {
+    let mut report = BuildReport::default();
+    report.push(FileReport::new(
+        "a.md",
+        &[],
+        &[ParseWarning {
+            code: WarningCode::UnclosedEmphasis,
+            severity: Severity::Warning,
+            span: Span {
+                line: 2,
+                column: Some(5),
+            },
+            message: "unclosed emphasis delimiter `**`".to_string(),
+        }],
+        Duration::from_millis(3),
+    ));
+    let mut linted = FileReport::new("c.md", &[], &[], Duration::ZERO);
+    linted.lint = lint(
+        "# Title \n\nSee https://example.com\n",
+        &LintConfig::default(),
+    )
+    .unwrap();
+    report.push(linted);
+    report.push(FileReport::failed(
+        "b.md",
+        "line 1: unclosed code block".to_string(),
+        Duration::ZERO,
+    ));
+
+    assert_eq!(report.total_files, 3);
+    assert_eq!(report.failed_files, 1);
+    assert_eq!(report.total_warnings, 1);
+    let rules: Vec<_> = report.files[1].lint.iter().map(|d| d.rule).collect();
+    assert_eq!(rules, [LintRule::TrailingWhitespace, LintRule::BareUrl]);
+    assert_eq!(report.total_lint, 2);
+
+    let json = report.to_json().unwrap();
+    assert!(json.contains("\"path\": \"a.md\""));
+    assert!(json.contains("\"code\": \"unclosed-emphasis\""));
+    assert!(json.contains("unclosed code block"));
+    assert!(json.contains("\"rule\": \"bare-url\""));
+    assert!(json.contains("\"total_lint\": 2"));
+}
@@ -0,0 +1,46 @@
+use md_parser::{CancellationToken, ParseError, Parser};
+
+#[test]
+fn test_parse_with_uncancelled_token() {
+    let mut parser = Parser::new("# Title\n\nParagraph".to_string()).unwrap();
+    let token = CancellationToken::new();
+
+    let result = parser.parse_with_cancel(&token).unwrap();
+    assert_eq!(result.len(), 2);
+}
+
+#[test]
+fn test_parse_with_cancelled_token() {
+    let mut parser = Parser::new("# Title\n\nParagraph".to_string()).unwrap();
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let err = parser.parse_with_cancel(&token).unwrap_err();
+    match &err {
+        ParseError::Cancelled { span } => assert_eq!(span.line, 1),
+        _ => panic!("expected Cancelled, got {:?}", err),
+    }
+    assert!(format!("{}", err).contains("parsing cancelled"));
+}
+
+#[test]
+fn test_token_clones_share_state() {
+    let token = CancellationToken::new();
+    let handle = token.clone();
+    assert!(!token.is_cancelled());
+
+    let worker = std::thread::spawn(move || handle.cancel());
+    worker.join().unwrap();
+    assert!(token.is_cancelled());
+}
+
+#[test]
+fn test_parse_ignores_token_after_completion() {
+    let mut parser = Parser::new("text".to_string()).unwrap();
+    let token = CancellationToken::new();
+    parser.parse_with_cancel(&token).unwrap();
+    token.cancel();
+
+    // Plain parse() never observes a token
+    assert!(parser.parse().is_ok());
+}
@@ -0,0 +1,127 @@
+use md_parser::{ChangeCategory, Changelog, Parser};
+
+const CHANGELOG: &str = "\
+# Changelog
+
+All notable changes to this project are documented here.
+
+## [Unreleased]
+
+### Added
+
+- Dark mode
+
+## [1.1.0] - 2024-03-01
+
+A maintenance release.
+
+### Fixed
+
+- Crash on empty input
+  - Also on whitespace-only input
+- Wrong **exit code**
+
+### Security
+
+- Escape titles
+
+## [1.0.1] - 2024-02-10 [YANKED]
+
+### fixed
+
+- Typo
+
+## 1.0.0 (2024-01-15)
+
+### Added
+
+- First release
+
+[Unreleased]: https://example.com/compare/v1.1.0...HEAD
+[1.1.0]: https://example.com/compare/v1.0.1...v1.1.0
+";
+
+fn changelog(input: &str) -> Changelog {
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    Changelog::from_nodes(&parser.parse().unwrap())
+}
+
+#[test]
+fn test_versions_are_read_from_headings() {
+    let changelog = changelog(CHANGELOG);
+    let versions: Vec<(&str, Option<&str>, bool)> = changelog
+        .versions()
+        .iter()
+        .map(|v| (v.version.as_str(), v.date.as_deref(), v.yanked))
+        .collect();
+    assert_eq!(
+        versions,
+        [
+            ("Unreleased", None, false),
+            ("1.1.0", Some("2024-03-01"), false),
+            ("1.0.1", Some("2024-02-10"), true),
+            ("1.0.0", Some("2024-01-15"), false),
+        ]
+    );
+
+    let unreleased = changelog.unreleased().unwrap();
+    assert_eq!(
+        unreleased.url.as_deref(),
+        Some("https://example.com/compare/v1.1.0...HEAD")
+    );
+    assert_eq!(
+        unreleased.entries(&ChangeCategory::Added)[0].text,
+        "Dark mode"
+    );
+
+    let latest = changelog.latest().unwrap();
+    assert_eq!(latest.version, "1.1.0");
+    assert_eq!(latest.anchor, "110---2024-03-01");
+    assert_eq!(latest.notes.len(), 1);
+    assert_eq!(changelog.version("v1.0.0").unwrap().url, None);
+    assert!(changelog.version("2.0.0").is_none());
+}
+
+#[test]
+fn test_entries_by_category() {
+    let changelog = changelog(CHANGELOG);
+    let version = changelog.version("1.1.0").unwrap();
+    let fixed: Vec<&str> = version
+        .entries(&ChangeCategory::Fixed)
+        .iter()
+        .map(|entry| entry.text.as_str())
+        .collect();
+    assert_eq!(fixed, ["Crash on empty input", "Wrong exit code"]);
+    assert_eq!(version.entries(&ChangeCategory::Fixed)[0].children.len(), 1);
+    assert!(version.entries(&ChangeCategory::Removed).is_empty());
+
+    // Category headings are matched ignoring case, across versions
+    let fixes: Vec<(&str, &str)> = changelog
+        .entries(&ChangeCategory::Fixed)
+        .map(|(version, entry)| (version.version.as_str(), entry.text.as_str()))
+        .collect();
+    assert_eq!(
+        fixes,
+        [
+            ("1.1.0", "Crash on empty input"),
+            ("1.1.0", "Wrong exit code"),
+            ("1.0.1", "Typo"),
+        ]
+    );
+}
+
+#[test]
+fn test_other_headings_and_inline_links() {
+    let changelog = changelog(
+        "## [2.0.0](https://example.com/v2) - 2024-05-01\n\n### Migration notes\n\n- Rename the config\n\n\
+         ## Roadmap\n\n- Plugins\n",
+    );
+    assert_eq!(changelog.versions().len(), 1);
+    let version = &changelog.versions()[0];
+    assert_eq!(version.url.as_deref(), Some("https://example.com/v2"));
+    assert_eq!(version.date.as_deref(), Some("2024-05-01"));
+    assert_eq!(
+        version.categories[0].category,
+        ChangeCategory::Other("Migration notes".to_string())
+    );
+}
@@ -0,0 +1,430 @@
+mod common;
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// Run the binary in a fresh working directory (so no `config.toml` is picked up)
+fn run(name: &str, args: &[&str], stdin: Option<&str>) -> (Output, PathBuf) {
+    let dir = common::temp_path(&format!("cli_{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "# Title\n\n## Part\n\n```mermaid\ngraph TD\n    A-->B\n```\n",
+    )
+    .unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_md-parser"))
+        .args(args)
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    if let Some(input) = stdin {
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+    }
+    drop(child.stdin.take());
+    (child.wait_with_output().unwrap(), dir)
+}
+
+#[test]
+fn test_html_subcommand_to_stdout_with_flag_override() {
+    let (output, _) = run(
+        "html_stdout",
+        &["html", "doc.md", "--stdout", "--heading-offset", "1"],
+        None,
+    );
+
+    assert!(output.status.success());
+    let html = String::from_utf8(output.stdout).unwrap();
+    assert!(html.contains("<h2>Title</h2>"));
+    assert!(html.contains("<h3>Part</h3>"));
+}
+
+#[test]
+fn test_json_subcommand_reads_stdin() {
+    let (output, _) = run("json_stdin", &["json", "--stdin"], Some("# From stdin"));
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["nodes"][0]["type"], "heading");
+}
+
+#[test]
+fn test_output_flag_and_output_dir() {
+    let (output, dir) = run(
+        "output_file",
+        &["html", "doc.md", "-o", "site/doc.html"],
+        None,
+    );
+    assert!(output.status.success());
+    assert!(dir.join("site/doc.html").exists());
+
+    let (output, dir) = run(
+        "output_dir",
+        &["toc", "doc.md", "--output-dir", "out"],
+        None,
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(dir.join("out/doc.toc.md")).unwrap(),
+        "- [Title](#title)\n  - [Part](#part)\n"
+    );
+}
+
+#[test]
+fn test_mermaid_export_writes_diagram_files() {
+    let (output, dir) = run(
+        "mermaid_export",
+        &["mermaid", "export", "doc.md", "--output", "diagrams"],
+        None,
+    );
+
+    assert!(output.status.success());
+    // Named after the nearest heading
+    let diagram = fs::read_to_string(dir.join("diagrams/doc-part.mmd")).unwrap();
+    assert!(diagram.contains("A-->B"));
+}
+
+#[test]
+fn test_mermaid_export_out_dir_and_missing_renderer() {
+    let (output, dir) = run(
+        "mermaid_export_out_dir",
+        &["mermaid", "export", "doc.md", "--out-dir", "img"],
+        None,
+    );
+    assert!(output.status.success());
+    assert!(dir.join("img/doc-part.mmd").exists());
+
+    // Rendering images needs `mmdc`; without it the export reports the diagram and fails
+    let (output, dir) = run(
+        "mermaid_export_svg",
+        &[
+            "mermaid",
+            "export",
+            "doc.md",
+            "--out-dir",
+            "img",
+            "--format",
+            "svg",
+        ],
+        None,
+    );
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("diagram `part`"), "{}", stderr);
+        assert!(!dir.join("img/doc-part.svg").exists());
+    }
+}
+
+#[test]
+fn test_check_fails_on_warnings() {
+    let (clean, _) = run("check_clean", &["check", "doc.md"], None);
+    assert!(clean.status.success());
+
+    let (output, _) = run(
+        "check_warning",
+        &["check", "--stdin"],
+        Some("```rust\nfn main() {}\n"),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 problem(s)"));
+}
+
+#[test]
+fn test_check_runs_links_and_lint_with_json_output() {
+    let (output, dir) = run(
+        "check_json",
+        &["check", "--stdin", "--format", "json"],
+        Some("# Guide\n\nSee [setup](setup.md) and [the doc](doc.md).\n\n## Next Steps\n"),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let found: Vec<(&str, &str)> = diagnostics
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| (d["source"].as_str().unwrap(), d["rule"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        found,
+        [("links", "broken-link"), ("lint", "heading-capitalization")]
+    );
+    assert_eq!(diagnostics[0]["line"], 3);
+    assert_eq!(diagnostics[1]["line"], 5);
+    assert_eq!(diagnostics[1]["severity"], "warning");
+    // Nothing is written next to the input
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    let (output, _) = run(
+        "check_skip",
+        &["check", "--stdin", "--no-lint", "--no-links"],
+        Some("# Guide\n\nSee [setup](setup.md).\n\n## Next Steps\n"),
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_check_reports_missing_anchors() {
+    let (output, _) = run(
+        "check_anchors",
+        &["check", "--stdin", "--format", "json", "--no-lint"],
+        Some("# Guide\n\n[ok](#guide) [gone](#missing)\n\n[part](doc.md#part) [not there](doc.md#nothere)\n"),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let found: Vec<(&str, u64)> = diagnostics
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| (d["message"].as_str().unwrap(), d["line"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("broken link `#missing`: the target has no such heading", 3),
+            (
+                "broken link `doc.md#nothere`: the target has no such heading",
+                5
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_output_requires_single_input() {
+    let (output, _) = run(
+        "multi_output",
+        &["html", "doc.md", "doc.md", "-o", "x.html"],
+        None,
+    );
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("single input"));
+}
+
+#[test]
+fn test_format_flag_streams_stdin_to_stdout() {
+    let (output, dir) = run(
+        "format_stdin",
+        &["--format", "toc"],
+        Some("# One\n\n## Two"),
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "- [One](#one)\n  - [Two](#two)\n"
+    );
+    // Nothing but the fixture was written
+    assert!(!dir.join("output").exists());
+}
+
+#[test]
+fn test_dash_input_renders_html_to_stdout() {
+    let (output, dir) = run("dash_stdin", &["-"], Some("# Piped"));
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("<h1>Piped</h1>"));
+    assert!(!dir.join("output").exists());
+}
+
+#[test]
+fn test_subcommand_with_dash_input_writes_stdout() {
+    let (output, _) = run("subcommand_dash", &["json", "-"], Some("Text"));
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["nodes"][0]["type"], "paragraph");
+}
+
+#[test]
+fn test_var_flag_sets_variables() {
+    let (output, _) = run(
+        "var_flag",
+        &[
+            "html",
+            "-",
+            "--stdout",
+            "--var",
+            "version=2.1",
+            "--var",
+            "name=md",
+        ],
+        Some("# {{name}} {{version}}"),
+    );
+
+    assert!(output.status.success());
+    let html = String::from_utf8(output.stdout).unwrap();
+    assert!(html.contains("<h1>md 2.1</h1>"));
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_format_msgpack_streams_binary() {
+    let (output, _) = run("format_msgpack", &["--format", "msgpack", "doc.md"], None);
+
+    assert!(output.status.success(), "{:?}", output);
+    // Binary output gets no trailing newline
+    let ast: serde_json::Value = rmp_serde::from_slice(&output.stdout).unwrap();
+    assert_eq!(ast["nodes"][0]["type"], "heading");
+}
+
+#[test]
+fn test_slides_subcommand_writes_reveal_deck() {
+    let (output, _dir) = run(
+        "slides",
+        &["slides", "-"],
+        Some("# Talk\n\n## First\n\n---\n\nSecond"),
+    );
+    assert!(output.status.success());
+    let html = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(html.matches("<section>").count(), 3);
+    assert!(html.contains("<title>Talk</title>"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_test_subcommand_reports_each_code_block() {
+    let (output, _dir) = run(
+        "doctest",
+        &["test", "--stdin", "--lang", "sh"],
+        Some("```sh\necho ok\n```\n\n```sh\nexit 2\n```\n\n```python\nraise\n```\n"),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("PASS <stdin>:1 (sh)"), "{}", stdout);
+    assert!(stdout.contains("FAIL <stdin>:5 (sh)"));
+    assert!(!stdout.contains("python"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 passed, 1 failed, 0 skipped"));
+}
+
+#[test]
+fn test_check_reports_accessibility_issues_in_accessible_mode() {
+    let config = common::temp_path("cli_a11y").with_extension("toml");
+    fs::write(&config, "[renderer]\naccessible = true\n").unwrap();
+    let (output, _) = run(
+        "check_a11y",
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "check",
+            "--stdin",
+            "--no-lint",
+            "--no-links",
+        ],
+        Some("# Title\n\n![](chart.png)\n"),
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("image `chart.png` has no alt text [missing-alt-text]"));
+    assert!(stderr.contains("1 problem(s)"));
+    fs::remove_file(&config).unwrap();
+}
+
+#[test]
+fn test_lint_fixes_stdin_and_fails_on_remaining_problems() {
+    let (output, _) = run(
+        "lint_fix",
+        &["lint", "--stdin", "--fix"],
+        Some("# Getting Started\n\n* one\n- two  \n"),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "# Getting started\n\n* one\n* two\n"
+    );
+
+    let (output, _) = run("lint_check", &["lint", "--stdin"], Some("![](chart.png)\n"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("image has no alt text [missing-alt-text]"));
+    assert!(stderr.contains("1 problem(s)"));
+}
+
+#[test]
+fn test_fmt_check_lists_unformatted_files() {
+    let dir = common::temp_path("cli_fmt");
+    fs::create_dir_all(&dir).unwrap();
+    let messy = dir.join("messy.md");
+    fs::write(&messy, "Title\n=====\n\n* item\n").unwrap();
+    fs::write(dir.join("tidy.md"), "# Title\n\n- item\n").unwrap();
+
+    let (output, _) = run(
+        "fmt_check",
+        &["fmt", "--check", dir.to_str().unwrap()],
+        None,
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("messy.md: not formatted"));
+    assert!(!stderr.contains("tidy.md"));
+    assert_eq!(
+        fs::read_to_string(&messy).unwrap(),
+        "Title\n=====\n\n* item\n"
+    );
+
+    let (output, _) = run("fmt_write", &["fmt", dir.to_str().unwrap()], None);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(fs::read_to_string(&messy).unwrap(), "# Title\n\n- item\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_toc_lists_numbered_headings() {
+    let config = common::temp_path("cli_numbering").with_extension("toml");
+    fs::write(&config, "[renderer]\nnumber_headings = true\n").unwrap();
+    let (output, _) = run(
+        "toc_numbered",
+        &["--config", config.to_str().unwrap(), "toc", "--stdin"],
+        Some("# Intro\n\n## Setup\n\n## Usage\n\n# Reference\n"),
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "- [1 Intro](#intro)\n  - [1.1 Setup](#setup)\n  - [1.2 Usage](#usage)\n\
+         - [2 Reference](#reference)\n"
+    );
+    fs::remove_file(&config).unwrap();
+}
+
+#[test]
+fn test_notebook_input_renders_html() {
+    let dir = common::temp_path("cli_notebook_input");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let notebook = dir.join("analysis.ipynb");
+    fs::write(
+        &notebook,
+        r##"{"nbformat": 4, "nbformat_minor": 5,
+            "metadata": {"language_info": {"name": "python"}},
+            "cells": [
+              {"cell_type": "markdown", "metadata": {}, "source": ["# Analysis"]},
+              {"cell_type": "code", "metadata": {}, "source": ["print(1 + 1)"],
+               "outputs": [{"output_type": "stream", "name": "stdout", "text": ["2\n"]}]}
+            ]}"##,
+    )
+    .unwrap();
+    let (output, _) = run(
+        "notebook",
+        &["--format", "html", notebook.to_str().unwrap()],
+        None,
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let html = String::from_utf8(output.stdout).unwrap();
+    assert!(html.contains("<h1"), "{}", html);
+    assert!(html.contains("print(1 + 1)"), "{}", html);
+    assert!(html.contains("<pre><code>2</code></pre>"), "{}", html);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,4 +1,4 @@
-use md_parser::{Node, Parser};
+use md_parser::{Node, Parser, ParserConfig, RendererConfig, HIGHLIGHT_ATTRIBUTE};
 
 #[test]
 fn test_standard_code_block() {
@@ -8,7 +8,7 @@ fn test_standard_code_block() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::CodeBlock { lang, code } => {
+        Node::CodeBlock { lang, code, .. } => {
             assert_eq!(lang.as_ref(), Some(&"rust".to_string()));
             assert_eq!(code, "fn main() {\n    println!(\"Hello\");\n}");
         }
@@ -24,7 +24,7 @@ fn test_code_block_without_language() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::CodeBlock { lang, code } => {
+        Node::CodeBlock { lang, code, .. } => {
             assert_eq!(lang, &None);
             assert_eq!(code, "Some code here");
         }
@@ -45,6 +45,7 @@ fn test_mermaid_diagram() {
             config,
             validation_status,
             warnings,
+            ..
         } => {
             assert_eq!(diagram, "graph TD\n    A-->B");
             assert!(config.is_some(), "Config should be present");
@@ -91,7 +92,7 @@ fn test_python_code_block() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::CodeBlock { lang, code } => {
+        Node::CodeBlock { lang, code, .. } => {
             assert_eq!(lang.as_ref(), Some(&"python".to_string()));
             assert_eq!(code, "def hello():\n    print(\"Hello, World!\")");
         }
@@ -107,7 +108,7 @@ fn test_javascript_code_block() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::CodeBlock { lang, code } => {
+        Node::CodeBlock { lang, code, .. } => {
             assert_eq!(lang.as_ref(), Some(&"javascript".to_string()));
             assert_eq!(code, "function greet() {\n    console.log('Hello');\n}");
         }
@@ -124,7 +125,7 @@ fn test_typescript_code_block() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::CodeBlock { lang, code } => {
+        Node::CodeBlock { lang, code, .. } => {
             assert_eq!(lang.as_ref(), Some(&"typescript".to_string()));
             assert_eq!(
                 code,
@@ -145,7 +146,7 @@ fn test_multiple_language_code_blocks() {
 
     // First block: Rust
     match &result[0] {
-        Node::CodeBlock { lang, code } => {
+        Node::CodeBlock { lang, code, .. } => {
             assert_eq!(lang.as_ref(), Some(&"rust".to_string()));
             assert_eq!(code, "fn main() {}");
         }
@@ -154,7 +155,7 @@ fn test_multiple_language_code_blocks() {
 
     // Second block: Python
     match &result[1] {
-        Node::CodeBlock { lang, code } => {
+        Node::CodeBlock { lang, code, .. } => {
             assert_eq!(lang.as_ref(), Some(&"python".to_string()));
             assert_eq!(code, "def main():\n    pass");
         }
@@ -166,7 +167,7 @@ fn test_multiple_language_code_blocks() {
 
     // Third block: JavaScript
     match &result[2] {
-        Node::CodeBlock { lang, code } => {
+        Node::CodeBlock { lang, code, .. } => {
             assert_eq!(lang.as_ref(), Some(&"javascript".to_string()));
             assert_eq!(code, "function main() {}");
         }
@@ -176,3 +177,74 @@ fn test_multiple_language_code_blocks() {
         ),
     }
 }
+
+fn parse_with_attributes(input: &str) -> Parser {
+    let config = ParserConfig {
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    Parser::with_config(input.to_string(), config).unwrap()
+}
+
+#[test]
+fn test_highlighted_lines() {
+    let mut parser = parse_with_attributes("```rust {1, 3-4}\na\nb\nc\nd\n```");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(
+        &ast[0],
+        Node::CodeBlock { lang: Some(lang), attributes, .. }
+            if lang == "rust" && attributes.get(HIGHLIGHT_ATTRIBUTE) == Some("1,3-4")
+    ));
+
+    let html = parser
+        .to_html_with_config(&RendererConfig::default())
+        .unwrap();
+    assert!(html.contains(
+        "<pre data-highlight=\"1,3-4\"><code class=\"language-rust\"><span class=\"line highlighted\">a</span>\n<span class=\"line\">b</span>\n<span class=\"line highlighted\">c</span>\n<span class=\"line highlighted\">d</span></code></pre>"
+    ), "{}", html);
+    assert!(parser
+        .to_markdown()
+        .unwrap()
+        .starts_with("```rust {1,3-4}\n"));
+    assert!(parser
+        .to_rst()
+        .unwrap()
+        .contains(".. code-block:: rust\n   :emphasize-lines: 1,3-4\n"));
+    assert!(parser
+        .to_asciidoc()
+        .unwrap()
+        .contains("[source,rust,highlight=1;3-4]\n"));
+
+    // Not a range list: kept in the info string
+    let ast = parse_with_attributes("```rust {4-2}\nx\n```")
+        .parse()
+        .unwrap();
+    assert!(matches!(&ast[0], Node::CodeBlock { lang: Some(lang), .. } if lang == "rust {4-2}"));
+}
+
+#[test]
+fn test_line_numbers() {
+    let mut parser = parse_with_attributes(
+        "```py {.line-numbers start=9 highlight=2}\nx = 1\ny = 2\n```\n\n```\nplain\n```",
+    );
+    let html = parser
+        .to_html_with_config(&RendererConfig::default())
+        .unwrap();
+    assert!(html.contains("<span class=\"line\" data-line=\"9\">x = 1</span>\n<span class=\"line highlighted\" data-line=\"10\">y = 2</span>"), "{}", html);
+    assert!(html.contains("<pre><code>plain</code></pre>"));
+    assert!(html.contains("pre.line-numbers code .line::before"));
+    assert!(parser
+        .to_rst()
+        .unwrap()
+        .contains(":linenos:\n   :lineno-start: 9\n   :emphasize-lines: 2\n"));
+
+    let numbered_everywhere = parser
+        .to_html_with_config(&RendererConfig {
+            code_line_numbers: true,
+            ..RendererConfig::default()
+        })
+        .unwrap();
+    assert!(numbered_everywhere.contains(
+        "<pre class=\"line-numbers\"><code><span class=\"line\" data-line=\"1\">plain</span></code></pre>"
+    ));
+}
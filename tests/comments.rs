@@ -0,0 +1,78 @@
+use md_parser::{CommentMode, Inline, Node, Parser, RendererConfig, WarningCode};
+
+fn html(input: &str, comments: CommentMode) -> String {
+    let config = RendererConfig {
+        comments,
+        ..RendererConfig::default()
+    };
+    Parser::new(input.to_string())
+        .unwrap()
+        .to_html_with_config(&config)
+        .unwrap()
+}
+
+#[test]
+fn test_block_comment_is_not_absorbed_into_paragraph() {
+    let input = "Intro text\n<!-- TODO: expand\n   this section -->\nMore text";
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+
+    let kinds: Vec<&str> = ast.iter().map(Node::kind).collect();
+    assert_eq!(kinds, ["paragraph", "comment", "paragraph"]);
+    assert_eq!(
+        ast[1],
+        Node::Comment {
+            content: "TODO: expand\n   this section".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_inline_comment() {
+    let ast = Parser::new("Ship it <!-- after *review* --> today".to_string())
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        ast[0],
+        Node::Paragraph {
+            content: vec![
+                Inline::Text {
+                    content: "Ship it ".into()
+                },
+                Inline::Comment {
+                    content: "after *review*".to_string()
+                },
+                Inline::Text {
+                    content: " today".into()
+                },
+            ]
+        }
+    );
+}
+
+#[test]
+fn test_comment_modes() {
+    let input = "<!-- draft -->\n\nText <!-- a -- b --> end";
+
+    let stripped = html(input, CommentMode::Strip);
+    assert!(!stripped.contains("draft"));
+    assert!(stripped.contains("<p>Text  end</p>"));
+
+    let kept = html(input, CommentMode::Keep);
+    assert!(kept.contains("<!-- draft -->\n<p>Text <!-- a - - b --> end</p>"));
+
+    let visible = html(input, CommentMode::Visible);
+    assert!(visible.contains("<div class=\"comment\">draft</div>"));
+    assert!(visible.contains("<p>Text <span class=\"comment\">a -- b</span> end</p>"));
+}
+
+#[test]
+fn test_unclosed_comment_warns() {
+    let mut parser = Parser::new("Text\n\n<!-- never closed\n\n# Hidden".to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert_eq!(ast.len(), 2);
+    assert_eq!(parser.warnings()[0].code, WarningCode::UnclosedComment);
+    assert_eq!(parser.warnings()[0].span.line, 3);
+}
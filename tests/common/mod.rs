@@ -0,0 +1,30 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use md_parser::{Inline, Node, Parser, ParserConfig};
+use std::path::PathBuf;
+
+/// Parses `input` with the default configuration.
+pub fn parse(input: &str) -> Vec<Node> {
+    parse_with(input, ParserConfig::default())
+}
+
+/// Parses `input` with `config`.
+pub fn parse_with(input: &str, config: ParserConfig) -> Vec<Node> {
+    Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+/// A plain text inline.
+pub fn text(content: &str) -> Inline {
+    Inline::Text {
+        content: content.into(),
+    }
+}
+
+/// A path under the system temp dir that is unique to this test process.
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("md_parser_{}_{}", name, std::process::id()))
+}
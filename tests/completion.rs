@@ -0,0 +1,73 @@
+use md_parser::{
+    completion_items, CompletionKind, LineRange, Parser, ParserConfig, Project, ProjectDocument,
+};
+use std::path::Path;
+
+#[test]
+fn test_document_completion_items() {
+    let source = "# Guide\n\n![Chart](img/chart.png)\n\n## Set up {#setup}\n\n\
+                  See [the spec][spec][^1] and ![Again](img/chart.png).\n\n\
+                  ```md\n[fake]: https://example.com\n```\n\n\
+                  [spec]: https://example.com/spec\n[^1]: A footnote.\n";
+    let config = ParserConfig {
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    let result = Parser::with_config(source.to_string(), config)
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    let items = completion_items(&result);
+
+    let found: Vec<(CompletionKind, &str, &str)> = items
+        .iter()
+        .map(|item| (item.kind, item.label.as_str(), item.detail.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (CompletionKind::Heading, "#guide", "Guide"),
+            (CompletionKind::Heading, "#setup", "Set up"),
+            (
+                CompletionKind::LinkReference,
+                "spec",
+                "https://example.com/spec"
+            ),
+            (CompletionKind::Footnote, "1", "A footnote."),
+            (CompletionKind::Image, "img/chart.png", "Chart"),
+        ]
+    );
+    assert_eq!(items[1].span, Some(LineRange { start: 5, end: 5 }));
+    assert_eq!(items[3].span, Some(LineRange { start: 14, end: 14 }));
+    assert_eq!(items[4].span, Some(LineRange { start: 3, end: 3 }));
+}
+
+#[test]
+fn test_project_link_completions() {
+    let document = |path: &str, source: &str| {
+        let nodes = Parser::new(source.to_string()).unwrap().parse().unwrap();
+        ProjectDocument {
+            path: path.into(),
+            nodes,
+            warnings: Vec::new(),
+            anchors: Vec::new(),
+        }
+    };
+    let project = Project::from_documents(
+        "docs",
+        vec![
+            document("index.md", "# Home\n"),
+            document("guide/setup.md", "# Install\n\n## Linux\n"),
+        ],
+    );
+
+    let labels: Vec<String> = project
+        .link_completions(Path::new("guide/setup.md"))
+        .into_iter()
+        .map(|item| item.label)
+        .collect();
+    assert_eq!(
+        labels,
+        ["../index.md", "../index.md#home", "#install", "#linux"]
+    );
+}
@@ -0,0 +1,254 @@
+mod common;
+
+use md_parser::Config;
+use std::fs;
+use std::path::PathBuf;
+
+/// Write the shipped `config.toml` with `replace` substitutions applied and `extra` appended
+fn config_file(name: &str, replace: &[(&str, &str)], extra: &str) -> PathBuf {
+    let shipped = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("config.toml");
+    let mut contents = fs::read_to_string(shipped).unwrap();
+    for (from, to) in replace {
+        assert!(contents.contains(from), "{} not in config.toml", from);
+        contents = contents.replacen(from, to, 1);
+    }
+    contents.push_str(extra);
+
+    let path = common::temp_path(&format!("config_{}", name)).with_extension("toml");
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+/// 1-based line of the first line containing `needle`
+fn line_of(path: &PathBuf, needle: &str) -> usize {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .position(|line| line.contains(needle))
+        .unwrap()
+        + 1
+}
+
+#[test]
+fn test_lenient_mode_ignores_unknown_keys() {
+    let path = config_file(
+        "lenient",
+        &[(
+            "enable_html = true",
+            "enable_htm = true\nenable_html = true",
+        )],
+        "",
+    );
+
+    assert!(Config::load_from_path(&path).is_ok());
+}
+
+#[test]
+fn test_strict_mode_reports_unknown_keys_with_line_numbers() {
+    let path = config_file(
+        "unknown",
+        &[(
+            "enable_html = true",
+            "enable_htm = true\nenable_html = true",
+        )],
+        "",
+    );
+
+    let err = Config::load_from_path_strict(&path).unwrap_err();
+    let expected = format!(
+        "{}:{}: unknown key 'enable_htm' in [output] (did you mean 'enable_html'?)",
+        path.display(),
+        line_of(&path, "enable_htm ")
+    );
+    assert!(err.contains(&expected), "{}", err);
+}
+
+#[test]
+fn test_strict_mode_reports_typo_of_required_key() {
+    let path = config_file("required", &[("html_filename =", "html_filenme =")], "");
+
+    let err = Config::load_from_path_strict(&path).unwrap_err();
+    assert!(err.contains("unknown key 'html_filenme' in [output] (did you mean 'html_filename'?)"));
+    // Partial files are allowed, so the misspelled key is the only problem
+    assert_eq!(err.lines().count(), 1, "{}", err);
+}
+
+#[test]
+fn test_strict_mode_checks_nested_tables() {
+    let path = config_file("nested", &[("default_theme =", "default_theem =")], "");
+
+    let err = Config::load_from_path_strict(&path).unwrap_err();
+    let expected = format!(
+        ":{}: unknown key 'default_theem' in [parser.mermaid]",
+        line_of(&path, "default_theem")
+    );
+    assert!(err.contains(&expected), "{}", err);
+}
+
+#[test]
+fn test_strict_mode_reports_value_ranges_with_line_numbers() {
+    let path = config_file(
+        "ranges",
+        &[
+            ("max_heading_level = 6", "max_heading_level = 9"),
+            ("heading_offset = 0", "heading_offset = 7"),
+            ("list_indent = 2", "list_indent = 8"),
+            ("number_headings_from = 1", "number_headings_from = 0"),
+        ],
+        "",
+    );
+
+    let err = Config::load_from_path_strict(&path).unwrap_err();
+    assert!(err.contains(&format!(
+        ":{}: Invalid max_heading_level: 9",
+        line_of(&path, "max_heading_level = 9")
+    )));
+    assert!(err.contains(&format!(
+        ":{}: Invalid heading_offset: 7",
+        line_of(&path, "heading_offset = 7")
+    )));
+    assert!(err.contains(&format!(
+        ":{}: Invalid list_indent: 8",
+        line_of(&path, "list_indent = 8")
+    )));
+    assert!(err.contains(&format!(
+        ":{}: Invalid number_headings_from: 0",
+        line_of(&path, "number_headings_from = 0")
+    )));
+}
+
+#[test]
+fn test_strict_mode_accepts_the_shipped_config() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("config.toml");
+
+    assert!(Config::load_from_path_strict(path).is_ok());
+}
+
+#[test]
+fn test_strict_mode_accepts_every_optional_key() {
+    // Keys of `Option` fields, commented out in the shipped config
+    let optional = [
+        "base_dir = ",
+        "cache_dir = ",
+        "max_input_bytes = ",
+        "max_nodes = ",
+        "timeout_ms = ",
+        "audience = ",
+        "base_url = ",
+        "mermaid_cache_dir = ",
+        "title = ",
+        "description = ",
+        "author = ",
+        "hashtag_url = ",
+        "mention_url = ",
+        "site = ",
+        "rel = ",
+        "class = ",
+        "site_url = ",
+    ];
+    let replace: Vec<(String, String)> = optional
+        .iter()
+        .map(|key| (format!("\n# {}", key), format!("\n{}", key)))
+        .collect();
+    let replace: Vec<(&str, &str)> = replace
+        .iter()
+        .map(|(from, to)| (from.as_str(), to.as_str()))
+        .collect();
+    let path = config_file("optional", &replace, "");
+
+    let config = Config::load_from_path_strict(&path).unwrap();
+    assert_eq!(config.parser.limits.max_input_bytes, Some(10485760));
+    assert_eq!(config.parser.base_dir.as_deref(), Some("docs"));
+    assert_eq!(
+        config.output.site_url.as_deref(),
+        Some("https://docs.example.com")
+    );
+}
+
+#[test]
+fn test_strict_mode_accepts_free_form_meta_tags() {
+    let path = config_file(
+        "meta",
+        &[(
+            "# meta = { robots = \"noindex\" }",
+            "meta = { robots = \"noindex\" }",
+        )],
+        "",
+    );
+
+    let config = Config::load_from_path_strict(&path).unwrap();
+    assert_eq!(config.renderer.meta["robots"], "noindex");
+}
+
+#[test]
+fn test_env_override_output_dir() {
+    let path = config_file("env", &[], "");
+    std::env::set_var("MD_PARSER_OUTPUT_DIR", "from-env");
+    let config = Config::load_from_path(&path);
+    std::env::remove_var("MD_PARSER_OUTPUT_DIR");
+
+    let config = config.unwrap();
+    assert_eq!(config.output.directory, "from-env");
+    assert_eq!(config.renderer.output_directory, "from-env");
+}
+
+/// Write a standalone config layer
+fn layer_file(name: &str, contents: &str) -> PathBuf {
+    let path = common::temp_path(&format!("layer_{}", name)).with_extension("toml");
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_partial_config_keeps_defaults_for_missing_keys() {
+    let path = layer_file(
+        "partial",
+        "[renderer]\nbase_url = \"https://docs.example.com\"\n",
+    );
+
+    let config = Config::load_from_path(&path).unwrap();
+    let defaults = Config::default();
+    assert_eq!(
+        config.renderer.base_url.as_deref(),
+        Some("https://docs.example.com")
+    );
+    assert_eq!(config.output.html_filename, defaults.output.html_filename);
+    assert_eq!(
+        config.parser.max_heading_level,
+        defaults.parser.max_heading_level
+    );
+}
+
+#[test]
+fn test_later_layers_override_earlier_layers_key_by_key() {
+    let user = layer_file(
+        "user",
+        "[parser]\nmax_heading_level = 4\nenable_attributes = true\n\n[output]\ndirectory = \"user-out\"\n",
+    );
+    let project = layer_file("project", "[parser]\nmax_heading_level = 3\n");
+
+    let config = Config::from_layers([&user, &project]).unwrap();
+    assert_eq!(config.parser.max_heading_level, 3);
+    assert!(config.parser.enable_attributes);
+    assert_eq!(config.output.directory, "user-out");
+}
+
+#[test]
+fn test_strict_layers_report_problems_in_the_file_that_sets_them() {
+    let user = layer_file("strict_user", "[parser]\nmax_heading_level = 4\n");
+    let project = layer_file(
+        "strict_project",
+        "[output]\nenable_htm = true\n\n[parser]\nmax_heading_level = 9\n",
+    );
+
+    let err = Config::from_layers_strict([&user, &project]).unwrap_err();
+    assert!(err.contains(&format!(
+        "{}:2: unknown key 'enable_htm' in [output]",
+        project.display()
+    )));
+    assert!(
+        err.contains(&format!("{}:5: ", project.display())),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use md_parser::{Attributes, Inline, Node, Parser, ParserConfig};
+
+fn directives_config() -> ParserConfig {
+    ParserConfig {
+        enable_directives: true,
+        ..ParserConfig::default()
+    }
+}
+
+fn parse(input: &str) -> Vec<Node> {
+    common::parse_with(input, directives_config())
+}
+
+#[test]
+fn test_fenced_div_with_attributes() {
+    let ast = parse("::: {.sidebar #aside role=note}\nInside.\n\n- item\n:::\n\nAfter.");
+
+    assert_eq!(ast.len(), 2);
+    match &ast[0] {
+        Node::Container {
+            attributes,
+            children,
+        } => {
+            assert_eq!(attributes.id.as_deref(), Some("aside"));
+            assert_eq!(attributes.classes, vec!["sidebar".to_string()]);
+            assert_eq!(attributes.get("role"), Some("note"));
+            assert_eq!(children.len(), 2);
+        }
+        other => panic!("expected container, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_fenced_div_bare_word_and_nesting() {
+    let ast = parse("::: outer\n:::: {.inner}\nDeep.\n::::\n:::");
+
+    let Node::Container {
+        attributes,
+        children,
+    } = &ast[0]
+    else {
+        panic!("expected container, got {:?}", ast[0]);
+    };
+    assert_eq!(attributes.classes, vec!["outer".to_string()]);
+    assert!(matches!(
+        &children[0],
+        Node::Container { attributes, .. } if attributes.classes == vec!["inner".to_string()]
+    ));
+}
+
+#[test]
+fn test_directives_disabled_by_default() {
+    let input = "::: {.sidebar}\nText :abbr[HTML]{title=markup}\n:::";
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+
+    assert!(!ast
+        .iter()
+        .any(|node| matches!(node, Node::Container { .. })));
+    let html = Parser::new(input.to_string()).unwrap().to_html().unwrap();
+    assert!(!html.contains("<span"));
+}
+
+#[test]
+fn test_inline_directive() {
+    let ast = parse("Use :abbr[*HTML*]{title=\"HyperText Markup\"} here, not a:b[c].");
+
+    let Node::Paragraph { content } = &ast[0] else {
+        panic!("expected paragraph, got {:?}", ast[0]);
+    };
+    let mut attributes = Attributes::default();
+    attributes
+        .pairs
+        .insert("title".to_string(), "HyperText Markup".to_string());
+    assert_eq!(
+        content[1],
+        Inline::Directive {
+            name: "abbr".to_string(),
+            content: vec![Inline::Italic {
+                content: vec![Inline::Text {
+                    content: "HTML".into()
+                }]
+            }],
+            attributes,
+        }
+    );
+    assert_eq!(
+        content[2],
+        Inline::Text {
+            content: " here, not a:b[c].".into()
+        }
+    );
+}
+
+#[test]
+fn test_containers_and_directives_html() {
+    let input = "::: {.warning #w1}\nPress :kbd[Ctrl]{.key}.\n:::";
+    let html = Parser::with_config(input.to_string(), directives_config())
+        .unwrap()
+        .to_html()
+        .unwrap();
+
+    assert!(html.contains(
+        "<div id=\"w1\" class=\"warning\">\n<p>Press <span class=\"kbd key\">Ctrl</span>.</p>\n</div>"
+    ));
+}
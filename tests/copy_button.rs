@@ -0,0 +1,65 @@
+use md_parser::{CopyButton, Parser, Renderer, RendererConfig, RendererStrings};
+
+const INPUT: &str = "# Setup\n\n```sh\necho \"hi\"\n```\n";
+
+fn page(input: &str, copy_button: CopyButton) -> String {
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+    Renderer::new(RendererConfig {
+        copy_button,
+        ..RendererConfig::default()
+    })
+    .unwrap()
+    .render_html(&ast)
+}
+
+#[test]
+fn test_inline_buttons_bring_their_script() {
+    let html = page(INPUT, CopyButton::Inline);
+    assert!(
+        html.contains(
+            "<div class=\"code-block\"><button type=\"button\" class=\"copy-button\" \
+             data-copied=\"Copied\">Copy</button><pre><code class=\"language-sh\">\
+             echo &quot;hi&quot;</code></pre></div>"
+        ),
+        "{}",
+        html
+    );
+    assert_eq!(html.matches("navigator.clipboard.writeText").count(), 1);
+
+    // Pages without code blocks get no script
+    let html = page("# Setup\n\nNo code.\n", CopyButton::Inline);
+    assert!(!html.contains("navigator.clipboard"));
+}
+
+#[test]
+fn test_markup_and_off_modes() {
+    let html = page(INPUT, CopyButton::Markup);
+    assert!(html.contains("<button type=\"button\" class=\"copy-button\""));
+    assert!(!html.contains("navigator.clipboard"));
+
+    let html = page(INPUT, CopyButton::Off);
+    assert!(!html.contains("copy-button"));
+    assert!(html.contains("<pre><code class=\"language-sh\">"));
+
+    // Mermaid diagrams are not code to copy
+    assert!(
+        !page("```mermaid\ngraph TD\n    A-->B\n```\n", CopyButton::Markup).contains("copy-button")
+    );
+}
+
+#[test]
+fn test_button_labels_are_localized() {
+    let ast = Parser::new(INPUT.to_string()).unwrap().parse().unwrap();
+    let html = Renderer::new(RendererConfig {
+        copy_button: CopyButton::Markup,
+        strings: RendererStrings {
+            copy: "Kopieren".to_string(),
+            copied: "Kopiert".to_string(),
+            ..RendererStrings::default()
+        },
+        ..RendererConfig::default()
+    })
+    .unwrap()
+    .render_fragment(&ast);
+    assert!(html.contains("data-copied=\"Kopiert\">Kopieren</button>"));
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use md_parser::{Inline, Node, ParseError, Parser, ParserConfig, Project, WarningCode};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Create a fresh directory under the system temp dir holding `files`
+fn scratch_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = common::temp_path(&format!("csv_{}", name));
+    let _ = fs::remove_dir_all(&root);
+    for (path, content) in files {
+        let full = root.join(path);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::write(full, content).unwrap();
+    }
+    root
+}
+
+fn csv_config(base_dir: &Path) -> ParserConfig {
+    ParserConfig {
+        enable_csv_tables: true,
+        base_dir: Some(base_dir.to_string_lossy().into_owned()),
+        ..ParserConfig::default()
+    }
+}
+
+fn text(content: &str) -> Vec<Inline> {
+    vec![Inline::Text {
+        content: content.into(),
+    }]
+}
+
+#[test]
+fn test_csv_reference_becomes_table() {
+    let dir = scratch_dir(
+        "basic",
+        &[(
+            "data/results.csv",
+            "Bench,Time\n\"parse, small\",1.2ms\nrender,\n",
+        )],
+    );
+    let input = "Results:\n{{csv:data/results.csv}}\n\nDone.";
+    let mut parser = Parser::with_config(input.to_string(), csv_config(&dir)).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert_eq!(ast.len(), 3);
+    match &ast[1] {
+        Node::Table { headers, rows, .. } => {
+            assert_eq!(headers, &vec![text("Bench"), text("Time")]);
+            assert_eq!(rows[0], vec![text("parse, small"), text("1.2ms")]);
+            assert_eq!(rows[1], vec![text("render"), Vec::new()]);
+        }
+        other => panic!("expected table, got {:?}", other),
+    }
+    assert!(parser.warnings().is_empty());
+}
+
+#[test]
+fn test_csv_ragged_records_warn() {
+    let dir = scratch_dir("ragged", &[("t.csv", "a,b\n1\n1,2,3\n")]);
+    let mut parser = Parser::with_config("{{csv:t.csv}}".to_string(), csv_config(&dir)).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert!(matches!(&ast[0], Node::Table { rows, .. } if rows.iter().all(|r| r.len() == 2)));
+    let warnings = parser.warnings();
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings
+        .iter()
+        .all(|w| w.code == WarningCode::TableColumnMismatch && w.span.line == 1));
+}
+
+#[test]
+fn test_missing_csv_is_an_error() {
+    let dir = scratch_dir("missing", &[]);
+    let result = Parser::with_config("Intro\n\n{{csv:nope.csv}}".to_string(), csv_config(&dir))
+        .unwrap()
+        .parse();
+
+    match result {
+        Err(ParseError::IncludeFailed { path, span, .. }) => {
+            assert_eq!(path, "nope.csv");
+            assert_eq!(span.line, 3);
+        }
+        other => panic!("expected include error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_csv_disabled_by_default_and_resolved_per_document() {
+    let ast = Parser::new("{{csv:t.csv}}".to_string())
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(matches!(&ast[0], Node::Paragraph { .. }));
+
+    // Project documents resolve references next to themselves
+    let root = scratch_dir(
+        "project",
+        &[
+            ("guide/index.md", "{{csv:t.csv}}"),
+            ("guide/t.csv", "x\n1\n"),
+        ],
+    );
+    let config = ParserConfig {
+        enable_csv_tables: true,
+        ..ParserConfig::default()
+    };
+    let project = Project::load(&root, &config).unwrap();
+    assert!(matches!(
+        &project.documents()[0].nodes[0],
+        Node::Table { rows, .. } if rows.len() == 1
+    ));
+}
@@ -0,0 +1,124 @@
+use md_parser::{DefinitionKind, DefinitionMap, LineRange, Parser, ParserConfig};
+
+fn labels<'a>(definitions: impl IntoIterator<Item = &'a md_parser::Definition>) -> Vec<&'a str> {
+    definitions
+        .into_iter()
+        .map(|definition| definition.label.as_str())
+        .collect()
+}
+
+#[test]
+fn test_definitions_and_references_are_audited() {
+    let input = "\
+See the [guide][Rust Book], [Rust  book][], and [home].[^note]
+
+Code `[guide][missing]` and an [inline](https://example.com) link.
+
+```
+[fenced]: https://example.com
+```
+
+[rust book]: https://doc.rust-lang.org/book/ \"The Book\"
+[unused]: https://example.com/unused
+[RUST BOOK]: https://example.com/duplicate
+[^note]: A footnote.
+";
+    let result = Parser::new(input.to_string())
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    let map = result.definitions();
+
+    assert_eq!(
+        labels(&map.definitions),
+        ["rust book", "unused", "RUST BOOK", "note"]
+    );
+    assert_eq!(
+        map.definitions[0].target,
+        "https://doc.rust-lang.org/book/ \"The Book\""
+    );
+    assert_eq!(
+        map.definitions[3].span,
+        Some(LineRange { start: 12, end: 12 })
+    );
+
+    // `[home]` is a shortcut without a definition, so it is not a reference
+    let references: Vec<_> = map
+        .references
+        .iter()
+        .map(|reference| (reference.kind, reference.label.as_str()))
+        .collect();
+    assert_eq!(
+        references,
+        [
+            (DefinitionKind::LinkReference, "Rust Book"),
+            (DefinitionKind::LinkReference, "Rust  book"),
+            (DefinitionKind::Footnote, "note"),
+        ]
+    );
+
+    assert_eq!(
+        map.get(DefinitionKind::LinkReference, "rust\tBOOK")
+            .map(|definition| definition.span),
+        Some(Some(LineRange { start: 9, end: 9 }))
+    );
+    assert_eq!(labels(map.duplicates()), ["RUST BOOK"]);
+    assert_eq!(labels(map.unused()), ["unused"]);
+    assert!(map.undefined().is_empty());
+}
+
+#[test]
+fn test_external_definitions_resolve_references() {
+    let shared = "[rust]: https://www.rust-lang.org\n[^license]: MIT.\n";
+    let external = DefinitionMap::from_source(shared).unwrap().definitions;
+
+    let input = "Use [Rust][rust], see [^license] and [^missing].\n\n[rust]: https://local\n";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let before = parser.parse_full().unwrap();
+    assert_eq!(before.definitions().undefined().len(), 2);
+
+    parser.set_external_definitions(external);
+    let result = parser.parse_full().unwrap();
+    let map = result.definitions();
+
+    assert_eq!(labels(&map.external), ["rust", "license"]);
+    assert_eq!(map.external[1].span, Some(LineRange { start: 2, end: 2 }));
+    let undefined: Vec<_> = map
+        .undefined()
+        .iter()
+        .map(|reference| reference.label.as_str())
+        .collect();
+    assert_eq!(undefined, ["missing"]);
+
+    // The document's own definition takes precedence and hides the shared one
+    assert_eq!(
+        map.get(DefinitionKind::LinkReference, "rust")
+            .map(|definition| definition.target.as_str()),
+        Some("https://local")
+    );
+    assert_eq!(labels(map.duplicates()), ["rust"]);
+}
+
+#[test]
+fn test_definitions_follow_the_parsers_code_fences() {
+    // A backtick fence whose info string has a backtick is a paragraph, not a fence
+    let input = "``` a`b ```\n\n[open]: https://example.com/open\n\n```\n[fenced]: https://example.com/fenced\n```\n";
+    let result = Parser::new(input.to_string())
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    assert_eq!(labels(&result.definitions().definitions), ["open"]);
+
+    // With a `~~~` fence pattern, backtick lines are text
+    let config = ParserConfig {
+        code_fence_pattern: "~~~".to_string(),
+        ..ParserConfig::default()
+    };
+    let input =
+        "```\n[shown]: https://example.com\n```\n\n~~~\n[hidden]: https://example.com\n~~~\n";
+    let result = Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    assert_eq!(labels(&result.definitions().definitions), ["shown"]);
+}
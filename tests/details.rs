@@ -0,0 +1,55 @@
+use md_parser::{Inline, Node, Parser};
+
+#[test]
+fn test_details_block_with_title_and_body() {
+    let input =
+        ":::details How do I *reset* it?\nHold the button.\n\n- for 5 seconds\n:::\n\nNext.";
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+
+    assert_eq!(ast.len(), 2);
+    match &ast[0] {
+        Node::Details { summary, content } => {
+            assert_eq!(
+                summary,
+                &vec![
+                    Inline::Text {
+                        content: "How do I ".into()
+                    },
+                    Inline::Italic {
+                        content: vec![Inline::Text {
+                            content: "reset".into()
+                        }]
+                    },
+                    Inline::Text {
+                        content: " it?".into()
+                    },
+                ]
+            );
+            assert_eq!(content.len(), 2);
+        }
+        other => panic!("expected details, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_details_without_title_uses_default_summary() {
+    let ast = Parser::new("::: details\nBody\n:::".to_string())
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    assert!(matches!(
+        &ast[0],
+        Node::Details { summary, .. } if summary == &vec![Inline::Text { content: "Details".into() }]
+    ));
+}
+
+#[test]
+fn test_details_html_and_nesting() {
+    let input = ":::details FAQ\n:::note\nInside.\n:::\n:::";
+    let html = Parser::new(input.to_string()).unwrap().to_html().unwrap();
+
+    assert!(html.contains(
+        "<details>\n<summary>FAQ</summary>\n<div class=\"admonition admonition-note\">\n<p class=\"admonition-title\">Note</p>\n<p>Inside.</p>\n</div>\n</details>"
+    ));
+}
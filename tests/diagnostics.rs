@@ -0,0 +1,155 @@
+use md_parser::{
+    lint, BrokenLink, BrokenLinkReason, Diagnostic, DiagnosticSource, Diagnostics, LintConfig,
+    Parser, ParserConfig, Severity,
+};
+use std::path::PathBuf;
+
+fn diagnostics() -> Diagnostics {
+    let source = "# Getting Started\n\nSome **bold text\n";
+    let result = Parser::new(source.to_string())
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.add_parse_warnings("docs\\guide.md", &result.warnings);
+    diagnostics.add_lint(
+        "docs\\guide.md",
+        &lint(source, &LintConfig::default()).unwrap(),
+    );
+    diagnostics.add_broken_links(
+        "docs\\guide.md",
+        &[BrokenLink {
+            source: PathBuf::from("guide.md"),
+            url: "setup.md".to_string(),
+            reason: BrokenLinkReason::MissingDocument,
+        }],
+    );
+    diagnostics
+}
+
+#[test]
+fn test_diagnostics_from_each_check() {
+    let diagnostics = diagnostics();
+    let found: Vec<(DiagnosticSource, &str, Severity, Option<usize>)> = diagnostics
+        .iter()
+        .map(|d| (d.source, d.rule.as_str(), d.severity, d.line))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (
+                DiagnosticSource::Parse,
+                "unclosed-emphasis",
+                Severity::Warning,
+                Some(3)
+            ),
+            (
+                DiagnosticSource::Lint,
+                "heading-capitalization",
+                Severity::Warning,
+                Some(1)
+            ),
+            (
+                DiagnosticSource::Links,
+                "broken-link",
+                Severity::Error,
+                None
+            ),
+        ]
+    );
+    assert!(diagnostics.has_failures());
+
+    let rendered = diagnostics.diagnostics[1].display_with_source("# Getting Started\n");
+    assert!(
+        rendered.starts_with("warning: heading is not in sentence case [heading-capitalization]"),
+        "{}",
+        rendered
+    );
+    assert!(
+        rendered.contains("= note: fixable: `# Getting started`"),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+fn test_json_format() {
+    let diagnostics = diagnostics();
+    let json: serde_json::Value = serde_json::from_str(&diagnostics.to_json().unwrap()).unwrap();
+    assert_eq!(json[0]["file"], "docs\\guide.md");
+    assert_eq!(json[0]["line"], 3);
+    assert_eq!(json[0]["severity"], "warning");
+    assert_eq!(json[0]["source"], "parse");
+    assert_eq!(json[0]["rule"], "unclosed-emphasis");
+    assert!(json[2].get("line").is_none());
+
+    let parsed: Diagnostics = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, diagnostics);
+}
+
+#[test]
+fn test_sarif_log() {
+    let mut diagnostics = diagnostics();
+    let error = Parser::with_config(
+        "```rust\nfn main() {}\n".to_string(),
+        ParserConfig::default(),
+    )
+    .unwrap()
+    .parse()
+    .unwrap_err();
+    diagnostics.add_parse_error("notes.md", &error);
+
+    let sarif: serde_json::Value = serde_json::from_str(&diagnostics.to_sarif().unwrap()).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "md-parser");
+    let rules: Vec<&str> = run["tool"]["driver"]["rules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|rule| rule["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        rules,
+        [
+            "broken-link",
+            "heading-capitalization",
+            "parse-error",
+            "unclosed-emphasis"
+        ]
+    );
+
+    let first = &run["results"][0];
+    assert_eq!(first["ruleId"], "unclosed-emphasis");
+    assert_eq!(first["level"], "warning");
+    let location = &first["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "docs/guide.md");
+    assert_eq!(location["region"]["startLine"], 3);
+    assert!(run["results"][2]["locations"][0]["physicalLocation"]
+        .get("region")
+        .is_none());
+
+    let last = &run["results"][3];
+    assert_eq!(last["level"], "error");
+    assert!(last["message"]["text"]
+        .as_str()
+        .unwrap()
+        .contains("add a closing fence"));
+}
+
+#[test]
+fn test_notes_do_not_fail() {
+    let mut diagnostics = Diagnostics::new();
+    assert!(!diagnostics.has_failures());
+    diagnostics.push(Diagnostic {
+        file: "a.md".to_string(),
+        line: Some(1),
+        column: None,
+        severity: Severity::Note,
+        source: DiagnosticSource::Parse,
+        rule: "mermaid-cli-unavailable".to_string(),
+        message: "mmdc not found".to_string(),
+        note: None,
+    });
+    assert!(!diagnostics.has_failures());
+}
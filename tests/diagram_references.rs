@@ -0,0 +1,103 @@
+use md_parser::{resolve_diagram_references, to_markdown, Node, Parser, WarningCode};
+
+const DOCUMENT: &str = "\
+# Overview
+
+See the architecture:
+
+{{diagram:arch}}
+
+# Details
+
+```mermaid name=arch
+graph TD
+    A-->B
+```
+";
+
+#[test]
+fn test_named_diagram_and_reference() {
+    let ast = Parser::new(DOCUMENT.to_string()).unwrap().parse().unwrap();
+
+    let kinds: Vec<&str> = ast.iter().map(Node::kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            "heading",
+            "paragraph",
+            "diagram_ref",
+            "heading",
+            "mermaid_diagram"
+        ]
+    );
+    match &ast[2] {
+        Node::DiagramRef { name, span } => {
+            assert_eq!(name, "arch");
+            assert_eq!(span.unwrap().start, 5);
+        }
+        other => panic!("Expected DiagramRef, got {:?}", other),
+    }
+    match &ast[4] {
+        Node::MermaidDiagram { name, diagram, .. } => {
+            assert_eq!(name.as_deref(), Some("arch"));
+            assert_eq!(diagram, "graph TD\n    A-->B");
+        }
+        other => panic!("Expected MermaidDiagram, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_reference_renders_copy_of_diagram() {
+    let html = Parser::new(DOCUMENT.to_string())
+        .unwrap()
+        .to_html()
+        .unwrap();
+
+    assert_eq!(html.matches("graph TD").count(), 2);
+    assert_eq!(html.matches("id=\"diagram-arch\"").count(), 1);
+    assert!(!html.contains("{{diagram:arch}}"));
+
+    let asciidoc = Parser::new(DOCUMENT.to_string())
+        .unwrap()
+        .to_asciidoc()
+        .unwrap();
+    assert_eq!(asciidoc.matches("[mermaid]").count(), 2);
+}
+
+#[test]
+fn test_undefined_diagram_reference_warns() {
+    let mut parser = Parser::new("Intro\n{{diagram:missing}}".to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert_eq!(ast.len(), 2);
+    let warnings = parser.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, WarningCode::UndefinedDiagram);
+    assert_eq!(warnings[0].span.line, 2);
+
+    match &resolve_diagram_references(&ast)[1] {
+        Node::Paragraph { content } => {
+            assert_eq!(md_parser::plain_text(content), "{{diagram:missing}}")
+        }
+        other => panic!("Expected Paragraph, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_reference_inside_admonition_and_markdown_output() {
+    let input = "> [!NOTE]\n> {{diagram:flow}}\n\n```mermaid name=flow\ngraph LR\n    X-->Y\n```\n";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert!(parser.warnings().is_empty());
+    assert_eq!(to_markdown(&ast), input);
+    match &resolve_diagram_references(&ast)[0] {
+        Node::Admonition { content, .. } => {
+            assert!(matches!(
+                &content[0],
+                Node::MermaidDiagram { name: None, .. }
+            ))
+        }
+        other => panic!("Expected Admonition, got {:?}", other),
+    }
+}
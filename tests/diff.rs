@@ -0,0 +1,136 @@
+mod common;
+
+use common::parse;
+use md_parser::{ast_diff, diff_to_html, DocChange, Node, RendererConfig, TextChange};
+
+#[test]
+fn test_identical_documents_have_no_changes() {
+    let doc = parse("# Title\n\nSame text");
+
+    assert!(ast_diff(&doc, &doc).is_empty());
+}
+
+#[test]
+fn test_inserted_and_removed_blocks() {
+    let old = parse("# Title\n\n```\ncode\n```\n\nKept");
+    let new = parse("# Title\n\nKept\n\n---");
+
+    let changes = ast_diff(&old, &new);
+    assert_eq!(changes.len(), 2);
+    assert!(matches!(
+        &changes[0],
+        DocChange::Removed {
+            old_index: 1,
+            node: Node::CodeBlock { .. }
+        }
+    ));
+    assert!(matches!(
+        &changes[1],
+        DocChange::Inserted {
+            new_index: 2,
+            node: Node::HorizontalRule { .. }
+        }
+    ));
+}
+
+#[test]
+fn test_modified_paragraph_has_word_changes() {
+    let old = parse("# Title\n\nThe quick brown fox");
+    let new = parse("# Title\n\nThe slow brown fox jumps");
+
+    let changes = ast_diff(&old, &new);
+    assert_eq!(changes.len(), 1);
+    match &changes[0] {
+        DocChange::Modified {
+            old_index,
+            new_index,
+            text_changes,
+            ..
+        } => {
+            assert_eq!((*old_index, *new_index), (1, 1));
+            assert_eq!(
+                text_changes,
+                &[
+                    TextChange::Unchanged("The".to_string()),
+                    TextChange::Removed("quick".to_string()),
+                    TextChange::Inserted("slow".to_string()),
+                    TextChange::Unchanged("brown fox".to_string()),
+                    TextChange::Inserted("jumps".to_string()),
+                ]
+            );
+        }
+        other => panic!("expected modification, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_diff_html_highlights_changes() {
+    let old = parse("# Title\n\nOld words here\n\nGone");
+    let new = parse("# Title\n\nNew words here");
+
+    let html = diff_to_html(&old, &new, &RendererConfig::default()).unwrap();
+    assert!(html.contains("<h1>Title</h1>"));
+    assert!(html.contains(
+        "<div class=\"diff-modified\">\n<p><del>Old</del> <ins>New</ins> words here</p>\n</div>"
+    ));
+    assert!(html.contains("<div class=\"diff-removed\">\n<p>Gone</p>\n</div>"));
+}
+
+#[test]
+fn test_word_diff_aligns_interleaved_changes() {
+    let old = parse("a b c d e f");
+    let new = parse("x b y d z f w");
+
+    let changes = ast_diff(&old, &new);
+    let DocChange::Modified { text_changes, .. } = &changes[0] else {
+        panic!("expected a modified paragraph, got {:?}", changes);
+    };
+    assert_eq!(
+        text_changes,
+        &[
+            TextChange::Removed("a".to_string()),
+            TextChange::Inserted("x".to_string()),
+            TextChange::Unchanged("b".to_string()),
+            TextChange::Removed("c".to_string()),
+            TextChange::Inserted("y".to_string()),
+            TextChange::Unchanged("d".to_string()),
+            TextChange::Removed("e".to_string()),
+            TextChange::Inserted("z".to_string()),
+            TextChange::Unchanged("f".to_string()),
+            TextChange::Inserted("w".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_large_documents_diff_edits_in_the_middle() {
+    let paragraphs: Vec<String> = (0..5000).map(|i| format!("Paragraph {}", i)).collect();
+    let old = parse(&paragraphs.join("\n\n"));
+    let mut edited = paragraphs.clone();
+    edited[2500] = "Paragraph changed".to_string();
+    edited.remove(4000);
+    edited.insert(10, "# Added".to_string());
+    let new = parse(&edited.join("\n\n"));
+
+    let changes = ast_diff(&old, &new);
+    assert_eq!(changes.len(), 3, "{:?}", changes);
+    assert!(matches!(
+        changes[0],
+        DocChange::Inserted { new_index: 10, .. }
+    ));
+    assert!(matches!(
+        changes[1],
+        DocChange::Modified {
+            old_index: 2500,
+            new_index: 2501,
+            ..
+        }
+    ));
+    assert!(matches!(
+        changes[2],
+        DocChange::Removed {
+            old_index: 4000,
+            ..
+        }
+    ));
+}
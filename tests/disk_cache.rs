@@ -0,0 +1,130 @@
+mod common;
+
+use md_parser::{Node, ParseError, Parser, ParserConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = common::temp_path(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn cached_config(cache: &Path) -> ParserConfig {
+    ParserConfig {
+        cache_dir: Some(cache.to_string_lossy().into_owned()),
+        ..ParserConfig::default()
+    }
+}
+
+fn entries(cache: &Path) -> Vec<PathBuf> {
+    fs::read_dir(cache)
+        .map(|dir| dir.map(|entry| entry.unwrap().path()).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_parse_cached_reuses_stored_result() {
+    let dir = temp_dir("disk_cache");
+    let cache = dir.join("cache");
+    let doc = dir.join("doc.md");
+    fs::write(&doc, "# Hello\n\nSome **bold\n").unwrap();
+
+    let mut parser = Parser::with_config(String::new(), cached_config(&cache)).unwrap();
+    let first = parser.parse_cached(&doc).unwrap();
+    assert_eq!(first.warnings.len(), 1);
+    let stored = entries(&cache);
+    assert_eq!(stored.len(), 1);
+
+    // Edit the stored result to show that the next parse reads it instead of the file
+    let json = fs::read_to_string(&stored[0]).unwrap();
+    fs::write(&stored[0], json.replace("Hello", "Cached")).unwrap();
+    let mut parser = Parser::with_config(String::new(), cached_config(&cache)).unwrap();
+    let second = parser.parse_cached(&doc).unwrap();
+    assert!(matches!(&second.nodes[0], Node::Heading { .. }));
+    assert!(parser.to_html().unwrap().contains("Cached"));
+    assert_eq!(parser.warnings().len(), 1);
+
+    // Another configuration or other content is a miss with its own entry
+    let mut strict = Parser::with_config(
+        String::new(),
+        ParserConfig {
+            strict_atx: true,
+            ..cached_config(&cache)
+        },
+    )
+    .unwrap();
+    assert!(!strict.parse_cached(&doc).unwrap().nodes.is_empty());
+    fs::write(&doc, "# Hello again\n").unwrap();
+    parser.parse_cached(&doc).unwrap();
+    let count = entries(&cache).len();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn test_results_reading_other_files_are_not_cached() {
+    let dir = temp_dir("disk_cache_includes");
+    let cache = dir.join("cache");
+    fs::write(dir.join("part.md"), "Included").unwrap();
+    let config = ParserConfig {
+        enable_includes: true,
+        base_dir: Some(dir.to_string_lossy().into_owned()),
+        ..cached_config(&cache)
+    };
+    let mut parser = Parser::with_config("{{include: part.md}}".to_string(), config).unwrap();
+    parser.parse().unwrap();
+    let count = entries(&cache).len();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_entry_for_other_input_is_a_miss() {
+    let dir = temp_dir("disk_cache_other_input");
+    let cache = dir.join("cache");
+    let doc = dir.join("doc.md");
+    fs::write(&doc, "# First\n").unwrap();
+    let mut parser = Parser::with_config(String::new(), cached_config(&cache)).unwrap();
+    parser.parse_cached(&doc).unwrap();
+
+    // Put the entry where another input's result would be, as a hash collision would
+    let stored = entries(&cache).remove(0);
+    fs::write(&doc, "# Second\n").unwrap();
+    parser.parse_cached(&doc).unwrap();
+    let other = entries(&cache)
+        .into_iter()
+        .find(|path| *path != stored)
+        .unwrap();
+    fs::copy(&stored, &other).unwrap();
+
+    let mut parser = Parser::with_config(String::new(), cached_config(&cache)).unwrap();
+    parser.parse_cached(&doc).unwrap();
+    let html = parser.to_html().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(html.contains("Second"));
+}
+
+#[test]
+fn test_results_of_mermaid_cli_validation_are_not_cached() {
+    let dir = temp_dir("disk_cache_mermaid_cli");
+    let cache = dir.join("cache");
+    let mut config = cached_config(&cache);
+    config.mermaid.use_cli_validation = true;
+    let mut parser = Parser::with_config("# Title".to_string(), config).unwrap();
+    parser.parse().unwrap();
+    let count = entries(&cache).len();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_parse_cached_reports_unreadable_file() {
+    let mut parser = Parser::new(String::new()).unwrap();
+    let error = parser.parse_cached("does/not/exist.md").unwrap_err();
+    assert!(matches!(error, ParseError::ReadFailed { .. }));
+    assert!(error
+        .to_string()
+        .starts_with("cannot read `does/not/exist.md`"));
+}
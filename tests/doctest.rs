@@ -0,0 +1,108 @@
+mod common;
+
+use md_parser::{
+    extract_code_blocks, run_doc_tests, write_code_blocks, DocTestConfig, DocTestOutcome, Parser,
+    ParserConfig,
+};
+use std::collections::BTreeMap;
+
+const INPUT: &str = "# Tutorial\n\n```sh\necho ok\n```\n\n> [!TIP]\n> ```sh\n> exit 3\n> ```\n\n```rust,ignore\nnot rust\n```\n\n```text\nplain\n```\n\n```\nno language\n```\n";
+
+fn snippets(langs: &[&str]) -> Vec<md_parser::CodeSnippet> {
+    let config = ParserConfig {
+        enable_admonitions: true,
+        ..ParserConfig::default()
+    };
+    let result = Parser::with_config(INPUT.to_string(), config)
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    let langs: Vec<String> = langs.iter().map(|lang| lang.to_string()).collect();
+    extract_code_blocks(&result, &langs)
+}
+
+#[test]
+fn test_extract_code_blocks() {
+    let all = snippets(&[]);
+    let found: Vec<(&str, usize, String)> = all
+        .iter()
+        .map(|snippet| {
+            (
+                snippet.lang.as_str(),
+                snippet.line,
+                snippet.path.to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("sh", 3, "1".to_string()),
+            // Nested blocks point at their top-level block
+            ("sh", 7, "2.0".to_string()),
+            ("rust", 12, "3".to_string()),
+            ("text", 16, "4".to_string()),
+        ]
+    );
+    assert!(all[2].is_ignored());
+    assert_eq!(all[2].flags, ["ignore"]);
+    assert_eq!(all[2].source(), "fn main() {\nnot rust\n}\n");
+
+    assert_eq!(snippets(&["rust", "text"]).len(), 2);
+}
+
+#[test]
+fn test_write_code_blocks() {
+    let dir = common::temp_path("doctest_write");
+    let paths = write_code_blocks(&snippets(&["sh", "rust"]), &dir, "tutorial").unwrap();
+    let names: Vec<String> = paths
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["tutorial-1.sh", "tutorial-2.sh", "tutorial-3.rs"]);
+    assert_eq!(std::fs::read_to_string(&paths[0]).unwrap(), "echo ok\n");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_doc_tests() {
+    let config = DocTestConfig {
+        commands: BTreeMap::from([("sh".to_string(), "sh {file}".to_string())]),
+        ..DocTestConfig::default()
+    };
+    let outcomes: Vec<DocTestOutcome> = run_doc_tests(&snippets(&[]), &config)
+        .into_iter()
+        .map(|result| result.outcome)
+        .collect();
+    assert_eq!(outcomes[0], DocTestOutcome::Passed);
+    assert!(
+        matches!(&outcomes[1], DocTestOutcome::Failed { output } if output.contains("exit status: 3")),
+        "{:?}",
+        outcomes[1]
+    );
+    assert_eq!(
+        outcomes[2],
+        DocTestOutcome::Skipped {
+            reason: "ignored".to_string()
+        }
+    );
+    assert_eq!(
+        outcomes[3],
+        DocTestOutcome::Skipped {
+            reason: "no command for `text`".to_string()
+        }
+    );
+
+    let slow = DocTestConfig {
+        commands: BTreeMap::from([("sh".to_string(), "sleep 5".to_string())]),
+        timeout_ms: 50,
+    };
+    let results = run_doc_tests(&snippets(&["sh"])[..1], &slow);
+    assert_eq!(
+        results[0].outcome,
+        DocTestOutcome::Failed {
+            output: "timed out after 50 ms".to_string()
+        }
+    );
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use md_parser::{DocxOptions, Parser, RendererConfig};
+use std::fs;
+
+/// Find `needle` in the (uncompressed) archive bytes
+fn contains(haystack: &[u8], needle: &str) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle.as_bytes())
+}
+
+fn options() -> DocxOptions {
+    DocxOptions {
+        title: "Compliance report".to_string(),
+        author: Some("Audit".to_string()),
+        render_mermaid: false,
+    }
+}
+
+fn docx_for(input: &str, resource_dir: &std::path::Path) -> Vec<u8> {
+    Parser::new(input.to_string())
+        .unwrap()
+        .to_docx(&options(), resource_dir, &RendererConfig::default())
+        .unwrap()
+}
+
+#[test]
+fn test_docx_package_parts() {
+    let bytes = docx_for("# Title\n\nText", std::path::Path::new("."));
+
+    assert_eq!(&bytes[..4], b"PK\x03\x04");
+    for part in [
+        "[Content_Types].xml",
+        "_rels/.rels",
+        "docProps/core.xml",
+        "word/document.xml",
+        "word/styles.xml",
+        "word/numbering.xml",
+        "word/_rels/document.xml.rels",
+    ] {
+        assert!(contains(&bytes, part), "missing {}", part);
+    }
+    assert!(contains(&bytes, "<dc:title>Compliance report</dc:title>"));
+    assert!(contains(&bytes, "<dc:creator>Audit</dc:creator>"));
+}
+
+#[test]
+fn test_docx_blocks_use_word_styles() {
+    let input = "# Scope\n\nSome **bold** and `code`.\n\n- one\n  - nested\n\n1. first\n\n1. again\n\n| A | B |\n|---|---:|\n| 1 | 2 |\n\n```rust\nfn main() {\n    run();\n}\n```\n\n> Quoted\n\n```mermaid\ngraph TD\n  A --> B\n```\n";
+    let bytes = docx_for(input, std::path::Path::new("."));
+
+    assert!(contains(
+        &bytes,
+        "<w:pStyle w:val=\"Heading1\"/></w:pPr><w:r><w:t xml:space=\"preserve\">Scope</w:t></w:r>"
+    ));
+    assert!(contains(
+        &bytes,
+        "<w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">bold</w:t>"
+    ));
+    assert!(contains(&bytes, "<w:rStyle w:val=\"CodeChar\"/>"));
+    assert!(contains(
+        &bytes,
+        "<w:ilvl w:val=\"1\"/><w:numId w:val=\"1\"/>"
+    ));
+    // Each ordered list restarts its numbering
+    assert!(contains(&bytes, "<w:numId w:val=\"2\"/>"));
+    assert!(contains(&bytes, "<w:numId w:val=\"3\"/>"));
+    assert!(contains(
+        &bytes,
+        "<w:num w:numId=\"3\"><w:abstractNumId w:val=\"1\"/>"
+    ));
+    assert!(contains(&bytes, "<w:tblHeader/>"));
+    assert!(contains(&bytes, "<w:jc w:val=\"right\"/>"));
+    assert!(contains(
+        &bytes,
+        "<w:t xml:space=\"preserve\">fn main() {</w:t><w:br/><w:t xml:space=\"preserve\">    run();</w:t>"
+    ));
+    assert!(contains(&bytes, "<w:pStyle w:val=\"Quote\"/>"));
+    // Without rendering, diagrams are shown as code
+    assert!(contains(
+        &bytes,
+        "<w:t xml:space=\"preserve\">  A --&gt; B</w:t>"
+    ));
+}
+
+#[test]
+fn test_docx_embeds_local_images_and_links_remote_ones() {
+    let dir = common::temp_path("docx_images");
+    fs::create_dir_all(&dir).unwrap();
+    // PNG signature and IHDR chunk headers for 320x240 and 1280x960 images
+    let png = |width: [u8; 2], height: [u8; 2]| {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&[
+            0, 0, width[0], width[1], 0, 0, height[0], height[1], 8, 2, 0, 0, 0,
+        ]);
+        png
+    };
+    fs::write(dir.join("chart.png"), png([1, 64], [0, 240])).unwrap();
+    fs::write(dir.join("wide.png"), png([5, 0], [3, 192])).unwrap();
+
+    let bytes = docx_for(
+        "![Chart](chart.png) and ![Chart](chart.png)\n\n![Wide](wide.png)\n\n![Logo](https://example.com/logo.png) [site](https://example.com)\n\n![Missing](missing.png)",
+        &dir,
+    );
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(contains(&bytes, "word/media/image1.png"));
+    assert!(contains(&bytes, "word/media/image2.png"));
+    assert!(!contains(&bytes, "word/media/image3.png"));
+    assert!(contains(&bytes, "Target=\"media/image1.png\""));
+    // 320x240 pixels at 96 DPI
+    assert!(contains(
+        &bytes,
+        "<wp:extent cx=\"3048000\" cy=\"2286000\"/>"
+    ));
+    // Shrunk to the width of the page
+    assert!(contains(
+        &bytes,
+        "<wp:extent cx=\"5731510\" cy=\"4298632\"/>"
+    ));
+    assert!(contains(&bytes, "descr=\"Chart\""));
+    assert!(contains(
+        &bytes,
+        "Target=\"https://example.com/logo.png\" TargetMode=\"External\""
+    ));
+    assert!(contains(
+        &bytes,
+        "<w:rPr><w:i/></w:rPr><w:t xml:space=\"preserve\">Missing</w:t>"
+    ));
+}
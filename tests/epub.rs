@@ -0,0 +1,77 @@
+mod common;
+
+use md_parser::{EpubChapter, EpubOptions, Parser, RendererConfig};
+
+/// Find `needle` in the (uncompressed) archive bytes
+fn contains(haystack: &[u8], needle: &str) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle.as_bytes())
+}
+
+fn epub_for(input: &str, options: &EpubOptions) -> Vec<u8> {
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    parser
+        .to_epub(
+            options,
+            env!("CARGO_MANIFEST_DIR"),
+            &RendererConfig::default(),
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_epub_starts_with_uncompressed_mimetype() {
+    let bytes = epub_for("# Title\n\nText", &EpubOptions::default());
+
+    assert_eq!(&bytes[..4], b"PK\x03\x04");
+    assert_eq!(&bytes[30..38], b"mimetype");
+    assert_eq!(&bytes[38..58], b"application/epub+zip");
+}
+
+#[test]
+fn test_epub_contains_package_nav_and_chapters() {
+    let options = EpubOptions {
+        title: "Handbook".to_string(),
+        author: Some("Docs Team".to_string()),
+        modified: Some("2024-01-01T00:00:00Z".to_string()),
+        ..EpubOptions::default()
+    };
+    let bytes = epub_for("# One\n\nFirst.\n\n---\n\n# Two\n\nSecond.", &options);
+
+    assert!(contains(&bytes, "META-INF/container.xml"));
+    assert!(contains(&bytes, "<dc:title>Handbook</dc:title>"));
+    assert!(contains(&bytes, "<dc:creator>Docs Team</dc:creator>"));
+    assert!(contains(&bytes, "<itemref idref=\"chapter-1\"/>"));
+    assert!(contains(&bytes, "<itemref idref=\"chapter-2\"/>"));
+    assert!(contains(&bytes, "<a href=\"chapter-2.xhtml\">Two</a>"));
+    // XHTML requires self-closed void elements
+    assert!(contains(&bytes, "<hr />"));
+}
+
+#[test]
+fn test_split_document_into_chapters() {
+    let mut parser = Parser::new("Preface.\n\n# One\n\nA\n\n## Sub\n\n# Two".to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+    let chapters = EpubChapter::split_document(&ast, "Book", ".");
+
+    let titles: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+    assert_eq!(titles, vec!["Book", "One", "Two"]);
+    assert_eq!(chapters[1].nodes.len(), 3);
+}
+
+#[test]
+fn test_epub_embeds_local_images() {
+    let dir = common::temp_path("epub_images");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("logo.png"), b"\x89PNG fake").unwrap();
+
+    let mut parser = Parser::new("# Pics\n\n![Logo](logo.png)".to_string()).unwrap();
+    let bytes = parser
+        .to_epub(&EpubOptions::default(), &dir, &RendererConfig::default())
+        .unwrap();
+
+    assert!(contains(&bytes, "OEBPS/images/image-1.png"));
+    assert!(contains(&bytes, "src=\"images/image-1.png\""));
+    assert!(contains(&bytes, "media-type=\"image/png\""));
+}
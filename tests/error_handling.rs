@@ -1,4 +1,12 @@
-use md_parser::{ParseError, Parser};
+use md_parser::{Attributes, Inline, Node, ParseError, Parser, ParserConfig, WarningCode};
+
+fn recovering_parser(input: &str) -> Parser {
+    let config = ParserConfig {
+        error_recovery: true,
+        ..ParserConfig::default()
+    };
+    Parser::with_config(input.to_string(), config).unwrap()
+}
 
 #[test]
 fn test_invalid_heading_level() {
@@ -7,10 +15,11 @@ fn test_invalid_heading_level() {
     let err = parser.parse().unwrap_err();
 
     match &err {
-        ParseError::InvalidHeadingLevel { level, span } => {
+        ParseError::InvalidHeadingLevel { level, max, span } => {
             assert!(*level > 6, "expected level > 6, got {}", level);
+            assert_eq!(*max, 6);
             assert_eq!(span.line, 1);
-            assert_eq!(span.column, None);
+            assert_eq!(span.column, Some(1));
         }
         _ => panic!("expected InvalidHeadingLevel, got {:?}", err),
     }
@@ -35,7 +44,7 @@ fn test_invalid_heading_level_line_number() {
     let err = parser.parse().unwrap_err();
 
     match &err {
-        ParseError::InvalidHeadingLevel { level, span } => {
+        ParseError::InvalidHeadingLevel { level, span, .. } => {
             assert!(*level > 6);
             assert_eq!(span.line, 3, "heading is on line 3");
         }
@@ -43,6 +52,30 @@ fn test_invalid_heading_level_line_number() {
     }
 }
 
+#[test]
+fn test_invalid_heading_level_reports_configured_max() {
+    let config = ParserConfig {
+        max_heading_level: 3,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config("#### deep".to_string(), config).unwrap();
+    let err = parser.parse().unwrap_err();
+
+    assert!(matches!(
+        err,
+        ParseError::InvalidHeadingLevel {
+            level: 4,
+            max: 3,
+            ..
+        }
+    ));
+    assert!(
+        err.to_string().contains("invalid heading level 4 (max 3)"),
+        "{}",
+        err
+    );
+}
+
 #[test]
 fn test_unclosed_code_block() {
     let input = "```\ncode\n".to_string();
@@ -52,7 +85,7 @@ fn test_unclosed_code_block() {
     match &err {
         ParseError::UnclosedCodeBlock { span } => {
             assert_eq!(span.line, 1);
-            assert_eq!(span.column, None);
+            assert_eq!(span.column, Some(1));
         }
         _ => panic!("expected UnclosedCodeBlock, got {:?}", err),
     }
@@ -83,3 +116,77 @@ fn test_unclosed_code_block_line_number() {
         _ => panic!("expected UnclosedCodeBlock, got {:?}", err),
     }
 }
+
+#[test]
+fn test_error_recovery_keeps_invalid_heading_as_paragraph() {
+    let mut parser = recovering_parser("# Title\n\n####### too deep\n\nAfter.");
+    let ast = parser.parse().unwrap();
+
+    assert_eq!(ast.len(), 3);
+    assert_eq!(
+        ast[1],
+        Node::Paragraph {
+            content: vec![Inline::Text {
+                content: "####### too deep".into()
+            }]
+        }
+    );
+    let warnings = parser.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, WarningCode::InvalidHeadingLevel);
+    assert_eq!(warnings[0].span.line, 3);
+}
+
+#[test]
+fn test_error_recovery_closes_unclosed_code_block_at_eof() {
+    let mut parser = recovering_parser("Intro.\n\n```rust\nfn main() {}\n");
+    let ast = parser.parse().unwrap();
+
+    assert_eq!(
+        ast[1],
+        Node::CodeBlock {
+            lang: Some("rust".to_string()),
+            code: "fn main() {}".to_string(),
+            attributes: Attributes::default(),
+        }
+    );
+    assert_eq!(parser.warnings()[0].code, WarningCode::UnclosedCodeBlock);
+    assert_eq!(parser.warnings()[0].span.line, 3);
+}
+
+#[test]
+fn test_error_recovery_respects_deny() {
+    let config = ParserConfig {
+        error_recovery: true,
+        deny: vec![WarningCode::UnclosedCodeBlock],
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config("```\ncode".to_string(), config).unwrap();
+
+    assert!(matches!(
+        parser.parse(),
+        Err(ParseError::DeniedWarning(warning)) if warning.code == WarningCode::UnclosedCodeBlock
+    ));
+}
+
+#[test]
+fn test_display_with_source_shows_line_and_caret() {
+    let input = "Intro.\n\n####### Deep";
+    let err = Parser::new(input.to_string()).unwrap().parse().unwrap_err();
+
+    let rendered = err.display_with_source(input);
+    let expected = "error: invalid heading level 7 (max 6)\n --> line 3, column 1\n  |\n3 | ####### Deep\n  | ^\n  = note: ";
+    assert!(rendered.starts_with(expected), "{}", rendered);
+}
+
+#[test]
+fn test_warning_display_with_source_points_at_column() {
+    let input = "Some **bold text";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    parser.parse().unwrap();
+
+    let rendered = parser.warnings()[0].display_with_source(input);
+    assert!(rendered.starts_with(
+        "warning: unclosed emphasis delimiter `**` [unclosed-emphasis]\n --> line 1, column 6\n  |\n1 | Some **bold text\n  |      ^\n"
+    ), "{}", rendered);
+}
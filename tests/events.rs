@@ -0,0 +1,116 @@
+use md_parser::{emit_events, Event, EventHandler, Parser, ParserConfig};
+
+/// Debug form of every event, since events borrow from nodes dropped during the parse
+fn streamed(input: &str, config: ParserConfig) -> Vec<String> {
+    let mut events = Vec::new();
+    Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse_events(&mut |event: Event<'_>| events.push(format!("{:?}", event)))
+        .unwrap();
+    events
+}
+
+fn from_ast(input: &str, config: ParserConfig) -> Vec<String> {
+    let ast = Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    let mut events = Vec::new();
+    emit_events(&ast, &mut |event: Event<'_>| {
+        events.push(format!("{:?}", event))
+    });
+    events
+}
+
+#[test]
+fn test_event_sequence() {
+    let events = streamed(
+        "# Title\n\nSome **bold** text.\n\n- one\n- [x] two\n",
+        ParserConfig::default(),
+    );
+    assert_eq!(
+        events,
+        [
+            "StartHeading { level: 1, attributes: Attributes { id: None, classes: [], pairs: {} } }",
+            "Text(\"Title\")",
+            "EndHeading { level: 1 }",
+            "StartParagraph",
+            "Text(\"Some \")",
+            "StartBold",
+            "Text(\"bold\")",
+            "EndBold",
+            "Text(\" text.\")",
+            "EndParagraph",
+            "StartList { ordered: false }",
+            "StartListItem { checked: None }",
+            "Text(\"one\")",
+            "EndListItem",
+            "StartListItem { checked: Some(true) }",
+            "Text(\"two\")",
+            "EndListItem",
+            "EndList { ordered: false }",
+        ]
+    );
+}
+
+#[test]
+fn test_streamed_events_match_the_ast() {
+    let input = "---\ntitle: Doc\n---\n# A\n\nIntro with [a link](x.md) and `code`.\n\n## B\n\n> [!NOTE]\n> Nested *text*\n\n| H1 | H2 |\n|----|----|\n| a  | b  |\n\n```rust\nfn main() {}\n```\n\n# C\n\n1. first\n    1. nested\n";
+    assert_eq!(
+        streamed(input, ParserConfig::default()),
+        from_ast(input, ParserConfig::default())
+    );
+
+    let config = ParserConfig {
+        enable_sections: true,
+        ..ParserConfig::default()
+    };
+    let events = streamed(input, config.clone());
+    assert_eq!(events, from_ast(input, config));
+    let sections: Vec<&String> = events
+        .iter()
+        .filter(|event| event.contains("Section"))
+        .collect();
+    assert_eq!(sections.len(), 6, "{:?}", sections);
+    assert!(sections[2].starts_with("EndSection { level: 2 }"));
+    assert!(sections[3].starts_with("EndSection { level: 1 }"));
+}
+
+#[derive(Default)]
+struct WordCounter {
+    words: usize,
+    code_blocks: usize,
+}
+
+impl EventHandler for WordCounter {
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::Text(text) => self.words += text.split_whitespace().count(),
+            Event::CodeBlock { .. } => self.code_blocks += 1,
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn test_handler_trait_and_errors() {
+    let input = "one two\n\n```\ncode\n```\n\nthree *four\n".repeat(1000);
+    let mut counter = WordCounter::default();
+    let mut parser = Parser::new(input).unwrap();
+    parser.parse_events(&mut counter).unwrap();
+    assert_eq!((counter.words, counter.code_blocks), (4000, 1000));
+    // Warnings are collected as usual
+    assert_eq!(parser.warnings().len(), 1000);
+
+    // A denied warning stops the stream before the block raising it is delivered
+    let config = ParserConfig {
+        deny: vec![md_parser::WarningCode::UnclosedEmphasis],
+        ..ParserConfig::default()
+    };
+    let mut counter = WordCounter::default();
+    let result = Parser::with_config("one\n\ntwo *three\n\nfour\n".to_string(), config)
+        .unwrap()
+        .parse_events(&mut counter);
+    assert!(result.is_err());
+    assert_eq!(counter.words, 1);
+}
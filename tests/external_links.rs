@@ -0,0 +1,67 @@
+use md_parser::{ExternalLinks, Parser, Renderer, RendererConfig};
+
+fn render(input: &str, config: RendererConfig) -> String {
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+    Renderer::new(config).unwrap().render_fragment(&ast)
+}
+
+fn marked(site: Option<&str>) -> ExternalLinks {
+    ExternalLinks {
+        site: site.map(str::to_string),
+        new_tab: true,
+        rel: Some("noopener noreferrer".to_string()),
+        class: Some("external".to_string()),
+    }
+}
+
+#[test]
+fn test_external_links_get_configured_attributes() {
+    let html = render(
+        "[a](https://Other.org/x) [b](https://docs.example.com:443/y) [c](guide.html) \
+         [d](#top) [e](mailto:me@other.org) [f](//cdn.other.org/z)",
+        RendererConfig {
+            external_links: marked(Some("docs.example.com")),
+            ..RendererConfig::default()
+        },
+    );
+
+    assert!(html.contains(
+        "<a href=\"https://Other.org/x\" class=\"external\" target=\"_blank\" \
+         rel=\"noopener noreferrer\">a</a>"
+    ));
+    assert!(html.contains("<a href=\"//cdn.other.org/z\" class=\"external\""));
+    assert!(html.contains("<a href=\"https://docs.example.com:443/y\">b</a>"));
+    assert!(html.contains("<a href=\"guide.html\">c</a>"));
+    assert!(html.contains("<a href=\"#top\">d</a>"));
+    assert!(html.contains("<a href=\"mailto:me@other.org\">e</a>"));
+}
+
+#[test]
+fn test_site_defaults_to_base_url() {
+    let html = render(
+        "[home](/index.html) [same](https://blog.example.com/a) [away](https://other.org)",
+        RendererConfig {
+            base_url: Some("https://blog.example.com/posts".to_string()),
+            external_links: ExternalLinks {
+                new_tab: true,
+                ..ExternalLinks::default()
+            },
+            ..RendererConfig::default()
+        },
+    );
+
+    assert!(html.contains("<a href=\"https://blog.example.com/posts/index.html\">home</a>"));
+    assert!(html.contains("<a href=\"https://blog.example.com/a\">same</a>"));
+    assert!(html.contains("<a href=\"https://other.org\" target=\"_blank\">away</a>"));
+}
+
+#[test]
+fn test_links_are_unchanged_by_default() {
+    let html = render("[a](https://other.org)", RendererConfig::default());
+    assert!(html.contains("<a href=\"https://other.org\">a</a>"));
+
+    let links: ExternalLinks =
+        toml::from_str("site = \"https://example.com\"\nclass = \"ext\"").unwrap();
+    assert_eq!(links.class.as_deref(), Some("ext"));
+    assert!(!links.new_tab);
+}
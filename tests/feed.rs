@@ -0,0 +1,87 @@
+use md_parser::{CommentMode, FeedMermaid, FeedOptions, Parser, RendererConfig};
+
+const POST: &str = "# Release 2.0\n\nThis release adds **streaming** and a new [changelog](changelog.md).\n\n<!-- draft note -->\n\n![Screenshot](img/shot.png)\n\n```mermaid\ngraph LR\n  A --> B\n```\n\n- [x] shipped\n\n# Older news\n\nOld text\n";
+
+fn config() -> RendererConfig {
+    RendererConfig {
+        base_url: Some("https://blog.example.com/posts".to_string()),
+        comments: CommentMode::Keep,
+        interactive_tasks: true,
+        ..RendererConfig::default()
+    }
+}
+
+#[test]
+fn test_feed_item_title_summary_and_safe_content() {
+    let item = Parser::new(POST.to_string())
+        .unwrap()
+        .to_feed_item(&FeedOptions::default(), &config())
+        .unwrap();
+
+    assert_eq!(item.title.as_deref(), Some("Release 2.0"));
+    assert_eq!(
+        item.summary.as_deref(),
+        Some("This release adds streaming and a new changelog.")
+    );
+
+    let html = &item.content_html;
+    assert!(!html.contains("<h1>Release 2.0</h1>"), "{}", html);
+    assert!(html.contains("href=\"https://blog.example.com/posts/changelog.md\""));
+    assert!(html.contains("src=\"https://blog.example.com/posts/img/shot.png\""));
+    assert!(
+        html.contains("<pre><code class=\"language-mermaid\">graph LR\n  A --&gt; B</code></pre>")
+    );
+    assert!(!html.contains("class=\"mermaid\""), "{}", html);
+    assert!(!html.contains("<script"));
+    assert!(!html.contains("draft note"));
+    assert!(html.contains("<input type=\"checkbox\" disabled checked>"));
+    assert!(html.contains("Older news"));
+}
+
+#[test]
+fn test_feed_item_first_section_only() {
+    let options = FeedOptions {
+        first_section_only: true,
+        ..FeedOptions::default()
+    };
+    let item = Parser::new(POST.to_string())
+        .unwrap()
+        .to_feed_item(&options, &config())
+        .unwrap();
+    assert!(!item.content_html.contains("Older news"));
+    assert!(!item.content_html.contains("Old text"));
+}
+
+#[test]
+fn test_feed_item_mermaid_as_image() {
+    let options = FeedOptions {
+        mermaid: FeedMermaid::mermaid_ink(),
+        ..FeedOptions::default()
+    };
+    let item = Parser::new("```mermaid name=flow\ngraph TD\n```\n".to_string())
+        .unwrap()
+        .to_feed_item(&options, &config())
+        .unwrap();
+    // "graph TD" in URL-safe Base64
+    assert!(
+        item.content_html
+            .contains("<img src=\"https://mermaid.ink/svg/Z3JhcGggVEQ\" alt=\"Diagram: flow\" />"),
+        "{}",
+        item.content_html
+    );
+    assert_eq!(item.title, None);
+    assert_eq!(item.summary, None);
+}
+
+#[test]
+fn test_feed_summary_is_shortened() {
+    let options = FeedOptions {
+        summary_length: 20,
+        ..FeedOptions::default()
+    };
+    let item = Parser::new("A fairly long opening paragraph for the feed.".to_string())
+        .unwrap()
+        .to_feed_item(&options, &config())
+        .unwrap();
+    assert_eq!(item.summary.as_deref(), Some("A fairly long…"));
+}
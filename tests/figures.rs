@@ -0,0 +1,99 @@
+mod common;
+
+use common::parse;
+use md_parser::{
+    number_figures, to_markdown, Inline, Node, Parser, Renderer, RendererConfig, Select,
+};
+
+fn html(ast: &[Node], number_figures: bool) -> String {
+    Renderer::new(RendererConfig {
+        number_figures,
+        ..RendererConfig::default()
+    })
+    .unwrap()
+    .render_fragment(ast)
+}
+
+#[test]
+fn test_image_and_diagram_figures() {
+    let ast = parse(
+        "![Overview](overview.png)\nFigure: System **overview**\n\n\
+         ```mermaid\ngraph TD\n    A-->B\n```\n\nFigure: Data flow\n",
+    );
+    assert_eq!(ast.len(), 2, "{:?}", ast);
+
+    let Node::Figure {
+        content,
+        caption,
+        number,
+    } = &ast[0]
+    else {
+        panic!("expected a figure, got {:?}", ast[0]);
+    };
+    assert!(
+        matches!(&content[..], [Node::Paragraph { content }] if matches!(content[..], [Inline::Image { .. }]))
+    );
+    assert_eq!(md_parser::plain_text(caption), "System overview");
+    assert_eq!(*number, None);
+
+    assert!(matches!(
+        &ast[1],
+        Node::Figure { content, .. } if matches!(content[..], [Node::MermaidDiagram { .. }])
+    ));
+}
+
+#[test]
+fn test_caption_needs_a_standalone_image_or_diagram() {
+    let ast = parse(
+        "Text ![icon](i.png)\nFigure: not a figure\n\n```rust\nfn main() {}\n```\nFigure: nor this\n",
+    );
+    assert!(Select::figures().find(&ast).is_empty(), "{:?}", ast);
+    assert!(matches!(&ast[0], Node::Paragraph { .. }));
+    assert!(matches!(&ast[2], Node::Paragraph { .. }));
+}
+
+#[test]
+fn test_figure_html_and_numbering() {
+    let ast = parse("![A](a.png)\nFigure: First\n\n> [!NOTE]\n> ![B](b.png)\n> Figure: Second\n");
+
+    let plain = html(&ast, false);
+    assert!(plain.contains(
+        "<figure>\n<img src=\"a.png\" alt=\"A\" />\n<figcaption>First</figcaption>\n</figure>"
+    ));
+
+    let numbered = html(&ast, true);
+    assert!(numbered.contains("<figure id=\"figure-1\">"));
+    assert!(numbered.contains(
+        "<figcaption><span class=\"figure-number\">Figure 2:</span> Second</figcaption>"
+    ));
+
+    // The transform is also available on its own
+    let numbers: Vec<_> = Select::figures()
+        .find(&number_figures(&ast))
+        .into_iter()
+        .map(|(_, node)| match node {
+            Node::Figure { number, .. } => *number,
+            _ => None,
+        })
+        .collect();
+    assert_eq!(numbers, [Some(1), Some(2)]);
+}
+
+#[test]
+fn test_figure_markdown_roundtrip_and_exports() {
+    let input = "![A](a.png)\nFigure: Caption\n";
+    let ast = parse(input);
+    let markdown = to_markdown(&ast);
+    assert_eq!(markdown, "![A](a.png)\n\nFigure: Caption\n");
+    assert_eq!(parse(&markdown), ast);
+
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    assert!(parser
+        .to_asciidoc()
+        .unwrap()
+        .contains(".Caption\nimage::a.png[A]\n"));
+    assert!(parser
+        .to_rst()
+        .unwrap()
+        .contains(".. figure:: a.png\n   :alt: A\n\n   Caption\n"));
+}
@@ -0,0 +1,69 @@
+use md_parser::{folding_ranges, FoldingKind, LineRange, Outline, Parser, ParserConfig};
+
+const SOURCE: &str = "---\ntitle: Guide\n---\n\
+                      # Guide\n\nIntro.\n\n\
+                      ## Install\n\n- one\n- two\n  - nested\n\n\
+                      ```sh\ncargo install md-parser\n```\n\n\
+                      ## Use\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n\
+                      # Appendix\n";
+
+fn parse(config: ParserConfig) -> md_parser::ParseResult {
+    Parser::with_config(SOURCE.to_string(), config)
+        .unwrap()
+        .parse_full()
+        .unwrap()
+}
+
+#[test]
+fn test_folding_ranges() {
+    let ranges = folding_ranges(&parse(ParserConfig::default()));
+    let found: Vec<(FoldingKind, usize, usize)> = ranges
+        .iter()
+        .map(|range| (range.kind, range.start, range.end))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (FoldingKind::Frontmatter, 1, 3),
+            (FoldingKind::Section, 4, 22),
+            (FoldingKind::Section, 8, 16),
+            (FoldingKind::List, 10, 12),
+            (FoldingKind::CodeBlock, 14, 16),
+            (FoldingKind::Section, 18, 22),
+            (FoldingKind::Table, 20, 22),
+        ]
+    );
+}
+
+#[test]
+fn test_outline_spans() {
+    let outline = Outline::from_result(&parse(ParserConfig::default()));
+    let guide = &outline.sections[0];
+    assert_eq!(guide.span, Some(LineRange { start: 4, end: 22 }));
+    assert_eq!(
+        guide.children[0].span,
+        Some(LineRange { start: 8, end: 16 })
+    );
+    assert_eq!(
+        guide.children[1].span,
+        Some(LineRange { start: 18, end: 22 })
+    );
+    assert_eq!(
+        outline.sections[1].span,
+        Some(LineRange { start: 24, end: 24 })
+    );
+
+    let sectioned = parse(ParserConfig {
+        enable_sections: true,
+        ..ParserConfig::default()
+    });
+    let outline = Outline::from_result(&sectioned);
+    assert_eq!(
+        outline.sections[0].span,
+        Some(LineRange { start: 4, end: 22 })
+    );
+    assert_eq!(outline.sections[0].children[0].span, None);
+    assert!(folding_ranges(&sectioned)
+        .iter()
+        .any(|range| range.kind == FoldingKind::Section && range.start == 4 && range.end == 22));
+}
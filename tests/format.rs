@@ -0,0 +1,103 @@
+use md_parser::{
+    format_markdown, FenceStyle, FormatConfig, FormatError, HeadingMarkup, ListMarker, ParserConfig,
+};
+
+fn format(source: &str, config: &FormatConfig) -> String {
+    format_markdown(source, &ParserConfig::default(), config).unwrap()
+}
+
+#[test]
+fn test_format_normalizes_markup() {
+    let input = "---\ntitle: Guide\n---\nGuide\n=====\n\n* one\n+ two\n    * nested\n\n\
+                 | Name | Role |\n|---|---:|\n| Ann | Developer |\n";
+    assert_eq!(
+        format(input, &FormatConfig::default()),
+        "---\ntitle: Guide\n---\n\n# Guide\n\n- one\n- two\n  - nested\n\n\
+         | Name |      Role |\n| ---- | --------: |\n| Ann  | Developer |\n"
+    );
+}
+
+#[test]
+fn test_format_is_idempotent() {
+    let input =
+        "Title\n=====\n\nSome text that runs on well past the wrap width of forty columns.\n\n\
+                 1. An item long enough to wrap onto a second line here\n\n\
+                 > A quote long enough to wrap onto a second line too\n\n```sh\necho hi\n```\n";
+    let config = FormatConfig {
+        wrap_width: 40,
+        ..FormatConfig::default()
+    };
+    let formatted = format(input, &config);
+    assert_eq!(
+        formatted,
+        "# Title\n\nSome text that runs on well past the\nwrap width of forty columns.\n\n\
+         1. An item long enough to wrap onto a\n   second line here\n\n\
+         > A quote long enough to wrap onto a\n> second line too\n\n```sh\necho hi\n```\n"
+    );
+    assert_eq!(format(&formatted, &config), formatted);
+}
+
+#[test]
+fn test_format_style_options() {
+    let config = FormatConfig {
+        list_marker: ListMarker::Asterisk,
+        headings: HeadingMarkup::Setext,
+        pad_tables: false,
+        wrap_width: 0,
+        ..FormatConfig::default()
+    };
+    let input = "# Top\n\n## Sub\n\n### Deep\n\n- a\n\n| a | b |\n|---|---|\n| long | x |\n";
+    assert_eq!(
+        format(input, &config),
+        "Top\n===\n\nSub\n---\n\n### Deep\n\n* a\n\n| a | b |\n| --- | --- |\n| long | x |\n"
+    );
+
+    let tilde = FormatConfig {
+        fence: FenceStyle::Tilde,
+        ..FormatConfig::default()
+    };
+    let parser = ParserConfig {
+        code_fence_pattern: "~~~".to_string(),
+        ..ParserConfig::default()
+    };
+    assert_eq!(
+        format_markdown("~~~rust\nfn main() {}\n~~~\n", &parser, &tilde).unwrap(),
+        "~~~rust\nfn main() {}\n~~~\n"
+    );
+}
+
+#[test]
+fn test_wrapping_never_starts_a_line_with_block_markup() {
+    let config = FormatConfig {
+        wrap_width: 20,
+        ..FormatConfig::default()
+    };
+    let input = "Counting down from ten: 3. 2. 1. - and then # done\n";
+    let formatted = format(input, &config);
+    for line in formatted.lines().skip(1) {
+        assert!(line.starts_with(char::is_alphabetic), "{:?}", formatted);
+    }
+    // Emphasis in list items is kept on one line, since each line is parsed on its own
+    let input = "- words before **a bold phrase that is long** and after\n";
+    assert!(format(input, &config).contains("**a bold phrase that is long**"));
+}
+
+#[test]
+fn test_format_keeps_placeholders_and_refuses_lossy_output() {
+    let parser = ParserConfig {
+        enable_variables: true,
+        ..ParserConfig::default()
+    };
+    let input = "Version {{version}}\n\n{{include: other.md}}\n";
+    assert_eq!(
+        format_markdown(input, &parser, &FormatConfig::default()).unwrap(),
+        input
+    );
+
+    // Inline Mermaid settings are not written back
+    let input = "Intro\n\n```mermaid\n%%{init: {'theme':'dark'}}%%\ngraph TD\n  A-->B\n```\n";
+    match format_markdown(input, &ParserConfig::default(), &FormatConfig::default()) {
+        Err(FormatError::Unstable { line }) => assert_eq!(line, 3),
+        other => panic!("expected an unstable format, got {:?}", other),
+    }
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::text;
+use md_parser::{Inline, Node, ParserConfig};
+
+/// Inlines of the only paragraph in `ast`
+fn paragraph(ast: Vec<Node>) -> Vec<Inline> {
+    match ast.as_slice() {
+        [Node::Paragraph { content }] => content.clone(),
+        other => panic!("expected one paragraph, got {:?}", other),
+    }
+}
+
+fn italic(content: &str) -> Inline {
+    Inline::Italic {
+        content: vec![text(content)],
+    }
+}
+
+#[test]
+fn test_punctuation_blocks_intraword_emphasis() {
+    let gfm = ParserConfig {
+        gfm_emphasis: true,
+        ..ParserConfig::default()
+    };
+    // Without the flag, `*` before `(` opens and `)*` closes
+    assert!(matches!(
+        paragraph(common::parse("x*(y+z)*2")).as_slice(),
+        [_, Inline::Italic { .. }, _]
+    ));
+    assert_eq!(
+        paragraph(common::parse_with("x*(y+z)*2", gfm.clone())),
+        vec![text("x*(y+z)*2")]
+    );
+    assert_eq!(
+        paragraph(common::parse_with("a*\"quoted\"*", gfm.clone())),
+        vec![text("a*\"quoted\"*")]
+    );
+    // Punctuation on the outer side is fine
+    assert_eq!(
+        paragraph(common::parse_with("(*note*)", gfm.clone())),
+        vec![text("("), italic("note"), text(")")]
+    );
+}
+
+#[test]
+fn test_intraword_star_pairs_as_in_gfm() {
+    let gfm = ParserConfig {
+        gfm_emphasis: true,
+        ..ParserConfig::default()
+    };
+    assert_eq!(
+        paragraph(common::parse_with("a*b*c", gfm.clone())),
+        vec![text("a"), italic("b"), text("c")]
+    );
+    assert_eq!(
+        paragraph(common::parse_with("2*3*4", gfm.clone())),
+        vec![text("2"), italic("3"), text("4")]
+    );
+    assert_eq!(
+        paragraph(common::parse_with("2 * 3 * 4", gfm.clone())),
+        vec![text("2 * 3 * 4")]
+    );
+}
+
+#[test]
+fn test_underscore_emphasis() {
+    let gfm = ParserConfig {
+        gfm_emphasis: true,
+        ..ParserConfig::default()
+    };
+    assert_eq!(
+        paragraph(common::parse_with(
+            "an _italic_ and __bold__ word",
+            gfm.clone()
+        )),
+        vec![
+            text("an "),
+            italic("italic"),
+            text(" and "),
+            Inline::Bold {
+                content: vec![text("bold")]
+            },
+            text(" word")
+        ]
+    );
+    assert_eq!(
+        paragraph(common::parse_with(
+            "call snake_case_names() or _private_ ones",
+            gfm.clone()
+        )),
+        vec![
+            text("call snake_case_names() or "),
+            italic("private"),
+            text(" ones")
+        ]
+    );
+    // `_` is plain text without the flag
+    assert_eq!(
+        paragraph(common::parse("_not italic_")),
+        vec![text("_not italic_")]
+    );
+}
+
+#[test]
+fn test_single_tilde_strikethrough() {
+    let gfm = ParserConfig {
+        gfm_emphasis: true,
+        ..ParserConfig::default()
+    };
+    let struck = |content: &str| Inline::Strikethrough {
+        content: vec![text(content)],
+    };
+    assert_eq!(
+        paragraph(common::parse_with("~one~ and ~~two~~", gfm.clone())),
+        vec![struck("one"), text(" and "), struck("two")]
+    );
+    // Runs of different lengths do not pair
+    assert_eq!(
+        paragraph(common::parse_with("~a~~", gfm.clone())),
+        vec![text("~a~~")]
+    );
+    assert_eq!(paragraph(common::parse("~one~")), vec![text("~one~")]);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::text;
+use md_parser::{
+    extract_hashtags, extract_mentions, Inline, Node, Parser, ParserConfig, RendererConfig,
+};
+
+fn config() -> ParserConfig {
+    ParserConfig {
+        enable_hashtags: true,
+        enable_mentions: true,
+        ..ParserConfig::default()
+    }
+}
+
+fn parse(input: &str) -> Vec<Node> {
+    common::parse_with(input, config())
+}
+
+fn hashtag(tag: &str) -> Inline {
+    Inline::Hashtag { tag: tag.into() }
+}
+
+#[test]
+fn test_hashtags_and_mentions_in_text() {
+    let ast = parse("#idea for @alice: see #project/alpha-2, thanks @bob.");
+    assert_eq!(
+        ast,
+        vec![Node::Paragraph {
+            content: vec![
+                hashtag("idea"),
+                text(" for "),
+                Inline::Mention {
+                    user: "alice".into()
+                },
+                text(": see "),
+                hashtag("project/alpha-2"),
+                text(", thanks "),
+                Inline::Mention { user: "bob".into() },
+                text("."),
+            ]
+        }]
+    );
+}
+
+#[test]
+fn test_lookalikes_stay_text() {
+    for input in [
+        "mail me@example.com",
+        "C# and page#anchor",
+        "issue #42",
+        "\\#literal and \\@literal",
+        "`#code`",
+        "# ",
+    ] {
+        let ast = parse(input);
+        let found = extract_hashtags(&ast).len() + extract_mentions(&ast).len();
+        assert_eq!(found, 0, "{}: {:?}", input, ast);
+    }
+    // Still a heading when followed by a space
+    assert!(matches!(parse("# Title #tag")[0], Node::Heading { .. }));
+}
+
+#[test]
+fn test_extraction_finds_nested_tags() {
+    let input = "# Notes #meeting\n\n**bold #urgent** and [link #meeting](x.md)\n\n- item @carol\n\n| A |\n|---|\n| #table @dave |\n\n> [!NOTE]\n> #inside\n";
+    let ast = parse(input);
+    assert_eq!(
+        extract_hashtags(&ast),
+        vec!["meeting", "urgent", "table", "inside"]
+    );
+    assert_eq!(extract_mentions(&ast), vec!["carol", "dave"]);
+}
+
+#[test]
+fn test_disabled_by_default() {
+    let ast = Parser::new("#tag @user".to_string())
+        .unwrap()
+        .parse()
+        .unwrap();
+    // Without hashtags, `#tag` at the start of a line is a heading
+    assert!(matches!(ast[0], Node::Heading { .. }));
+    assert!(extract_hashtags(&ast).is_empty());
+    assert!(extract_mentions(&ast).is_empty());
+}
+
+#[test]
+fn test_hashtag_and_mention_rendering() {
+    let mut parser = Parser::with_config("#rust by @ferris".to_string(), config()).unwrap();
+    let plain = parser
+        .to_html_with_config(&RendererConfig::default())
+        .unwrap();
+    assert!(plain.contains(
+        "<p><span class=\"hashtag\">#rust</span> by <span class=\"mention\">@ferris</span></p>"
+    ));
+
+    let linked = parser
+        .to_html_with_config(&RendererConfig {
+            hashtag_url: Some("/tags/{tag}.html".to_string()),
+            mention_url: Some("https://github.com/{user}".to_string()),
+            base_url: Some("https://notes.example.com".to_string()),
+            ..RendererConfig::default()
+        })
+        .unwrap();
+    assert!(linked.contains(
+        "<a class=\"hashtag\" href=\"https://notes.example.com/tags/rust.html\">#rust</a>"
+    ));
+    assert!(linked.contains("<a class=\"mention\" href=\"https://github.com/ferris\">@ferris</a>"));
+    assert_eq!(parser.to_markdown().unwrap().trim_end(), "#rust by @ferris");
+}
@@ -0,0 +1,69 @@
+use md_parser::{normalize_heading_levels, shift_heading_levels, Node, Parser, RendererConfig};
+
+fn heading_levels(nodes: &[Node]) -> Vec<u8> {
+    nodes
+        .iter()
+        .filter_map(|n| match n {
+            Node::Heading { level, .. } => Some(*level),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_shift_heading_levels_demotes() {
+    let mut parser = Parser::new("# One\n\n## Two\n\nText".to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+
+    let shifted = shift_heading_levels(&ast, 1);
+    assert_eq!(heading_levels(&shifted), vec![2, 3]);
+    assert_eq!(shifted.len(), ast.len());
+}
+
+#[test]
+fn test_shift_heading_levels_clamps() {
+    let mut parser = Parser::new("# One\n\n###### Six".to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert_eq!(heading_levels(&shift_heading_levels(&ast, 2)), vec![3, 6]);
+    assert_eq!(heading_levels(&shift_heading_levels(&ast, -3)), vec![1, 3]);
+}
+
+#[test]
+fn test_normalize_heading_levels() {
+    let mut parser = Parser::new("### Top\n\n#### Sub\n\n### Other".to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert_eq!(
+        heading_levels(&normalize_heading_levels(&ast)),
+        vec![1, 2, 1]
+    );
+}
+
+#[test]
+fn test_renderer_heading_offset() {
+    let mut parser = Parser::new("# Title\n\n## Section".to_string()).unwrap();
+    let config = RendererConfig {
+        heading_offset: 1,
+        ..RendererConfig::default()
+    };
+    let html = parser.to_html_with_config(&config).unwrap();
+
+    assert!(html.contains("<h2>Title</h2>"));
+    assert!(html.contains("<h3>Section</h3>"));
+    assert!(!html.contains("<h1>"));
+}
+
+#[test]
+fn test_renderer_normalize_then_offset() {
+    let mut parser = Parser::new("### Title\n\n#### Section".to_string()).unwrap();
+    let config = RendererConfig {
+        normalize_headings: true,
+        heading_offset: 1,
+        ..RendererConfig::default()
+    };
+    let html = parser.to_html_with_config(&config).unwrap();
+
+    assert!(html.contains("<h2>Title</h2>"));
+    assert!(html.contains("<h3>Section</h3>"));
+}
@@ -1,4 +1,8 @@
-use md_parser::{Inline, Node, Parser};
+mod common;
+
+use md_parser::{
+    to_markdown, HeadingChecks, Inline, Node, ParseError, Parser, ParserConfig, WarningCode,
+};
 
 #[test]
 fn test_heading_h1() {
@@ -8,13 +12,13 @@ fn test_heading_h1() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Heading { level, content } => {
+        Node::Heading { level, content, .. } => {
             assert_eq!(*level, 1);
             assert_eq!(content.len(), 1);
             assert_eq!(
                 content[0],
                 Inline::Text {
-                    content: "Heading 1".to_string()
+                    content: "Heading 1".into()
                 }
             );
         }
@@ -30,13 +34,13 @@ fn test_heading_h2() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Heading { level, content } => {
+        Node::Heading { level, content, .. } => {
             assert_eq!(*level, 2);
             assert_eq!(content.len(), 1);
             assert_eq!(
                 content[0],
                 Inline::Text {
-                    content: "Heading 2".to_string()
+                    content: "Heading 2".into()
                 }
             );
         }
@@ -52,13 +56,13 @@ fn test_heading_h6() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Heading { level, content } => {
+        Node::Heading { level, content, .. } => {
             assert_eq!(*level, 6);
             assert_eq!(content.len(), 1);
             assert_eq!(
                 content[0],
                 Inline::Text {
-                    content: "Heading 6".to_string()
+                    content: "Heading 6".into()
                 }
             );
         }
@@ -85,7 +89,7 @@ fn test_mixed_content() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "Some paragraph.".to_string()
+                    content: "Some paragraph.".into()
                 }
             );
         }
@@ -102,3 +106,176 @@ fn test_mixed_content() {
         _ => panic!("Expected MermaidDiagram"),
     }
 }
+
+fn parse_with(input: &str, strict_atx: bool) -> Vec<Node> {
+    let config = ParserConfig {
+        strict_atx,
+        ..ParserConfig::default()
+    };
+    common::parse_with(input, config)
+}
+
+fn heading(level: u8, text: &str) -> Node {
+    Node::Heading {
+        level,
+        content: vec![Inline::Text {
+            content: text.into(),
+        }],
+        attributes: Default::default(),
+    }
+}
+
+fn paragraph(text: &str) -> Node {
+    Node::Paragraph {
+        content: vec![Inline::Text {
+            content: text.into(),
+        }],
+    }
+}
+
+#[test]
+fn test_closing_hash_sequence_is_dropped() {
+    assert_eq!(parse_with("## Title ##", false), vec![heading(2, "Title")]);
+    assert_eq!(
+        parse_with("# Title #####  ", false),
+        vec![heading(1, "Title")]
+    );
+    // Without a space before them the hashes are part of the text
+    assert_eq!(parse_with("# C#", false), vec![heading(1, "C#")]);
+}
+
+#[test]
+fn test_escaped_hashes() {
+    assert_eq!(
+        parse_with("### foo \\###", false),
+        vec![heading(3, "foo ###")]
+    );
+    assert_eq!(
+        parse_with("# Title \\#", false),
+        vec![heading(1, "Title #")]
+    );
+    assert_eq!(
+        parse_with("\\# not a heading", false),
+        vec![paragraph("# not a heading")]
+    );
+}
+
+#[test]
+fn test_strict_atx_keeps_hashtags_as_text() {
+    let input = "#hashtag and #another\n# Real heading";
+    assert_eq!(
+        parse_with(input, true),
+        vec![
+            paragraph("#hashtag and #another"),
+            heading(1, "Real heading")
+        ]
+    );
+    // A paragraph continues over a hashtag line
+    assert_eq!(
+        parse_with("Tags:\n#rust #markdown", true),
+        vec![paragraph("Tags: #rust #markdown")]
+    );
+    assert_eq!(parse_with("#hashtag", false), vec![heading(1, "hashtag")]);
+}
+
+#[test]
+fn test_markdown_output_escapes_hashes() {
+    for (input, strict_atx) in [("#hashtag", true), ("# Issue \\#", false), ("## #", false)] {
+        let ast = parse_with(input, strict_atx);
+        assert_eq!(parse_with(&to_markdown(&ast), strict_atx), ast, "{}", input);
+    }
+}
+
+#[test]
+fn test_setext_headings() {
+    assert_eq!(
+        parse_with("Title\n=====\n\nSub title\n---", false),
+        vec![heading(1, "Title"), heading(2, "Sub title")]
+    );
+    // Every paragraph line before the underline is part of the heading
+    assert_eq!(
+        parse_with("A long\nheading\n---\nText", false),
+        vec![heading(2, "A long heading"), paragraph("Text")]
+    );
+    // After a blank line `---` is a rule
+    assert!(matches!(
+        parse_with("Text\n\n---", false)[..],
+        [Node::Paragraph { .. }, Node::HorizontalRule { .. }]
+    ));
+}
+
+fn heading_warnings(input: &str, heading_checks: HeadingChecks) -> Vec<(WarningCode, usize)> {
+    let config = ParserConfig {
+        heading_checks,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config(input.to_string(), config).unwrap();
+    parser.parse().unwrap();
+    parser
+        .warnings()
+        .iter()
+        .map(|warning| (warning.code, warning.span.line))
+        .collect()
+}
+
+const STRUCTURE: &str = "# Guide\n\n### Deep\n\n## Install\n\n### Linux\n\n### linux\n\n\
+                         ## Remove\n\n### Linux\n\n# Appendix\n\n##\n";
+
+#[test]
+fn test_heading_checks_report_structure_problems() {
+    let all = HeadingChecks {
+        skipped_levels: true,
+        multiple_top_level: true,
+        duplicates: true,
+        empty: true,
+    };
+    assert_eq!(
+        heading_warnings(STRUCTURE, all),
+        [
+            (WarningCode::SkippedHeadingLevel, 3),
+            (WarningCode::DuplicateHeading, 9),
+            (WarningCode::MultipleTopLevelHeadings, 15),
+            (WarningCode::EmptyHeading, 17),
+        ]
+    );
+    assert!(heading_warnings(STRUCTURE, HeadingChecks::default()).is_empty());
+
+    let only_duplicates = HeadingChecks {
+        duplicates: true,
+        ..HeadingChecks::default()
+    };
+    let mut parser = Parser::with_config(
+        STRUCTURE.to_string(),
+        ParserConfig {
+            heading_checks: only_duplicates.clone(),
+            ..ParserConfig::default()
+        },
+    )
+    .unwrap();
+    parser.parse().unwrap();
+    assert_eq!(
+        parser.warnings()[0].message,
+        "duplicate heading `linux` in the same section"
+    );
+    assert_eq!(
+        heading_warnings(STRUCTURE, only_duplicates),
+        [(WarningCode::DuplicateHeading, 9)]
+    );
+}
+
+#[test]
+fn test_denied_heading_check_fails_the_parse() {
+    let config = ParserConfig {
+        heading_checks: HeadingChecks {
+            multiple_top_level: true,
+            ..HeadingChecks::default()
+        },
+        deny: vec![WarningCode::MultipleTopLevelHeadings],
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config("# One\n\n# Two\n".to_string(), config).unwrap();
+    assert!(matches!(
+        parser.parse(),
+        Err(ParseError::DeniedWarning(warning)) if warning.code == WarningCode::MultipleTopLevelHeadings
+    ));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::text;
+use md_parser::{Inline, Node, Parser, ParserConfig, RendererConfig};
+
+fn config() -> ParserConfig {
+    ParserConfig {
+        enable_highlight: true,
+        enable_spoilers: true,
+        ..ParserConfig::default()
+    }
+}
+
+fn parse(input: &str, config: ParserConfig) -> Vec<Inline> {
+    let ast = common::parse_with(input, config);
+    match ast.as_slice() {
+        [Node::Paragraph { content }] => content.clone(),
+        other => panic!("expected one paragraph, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_highlight_and_spoiler_inlines() {
+    assert_eq!(
+        parse("a ==marked **bold**== and ||the twist|| here", config()),
+        vec![
+            text("a "),
+            Inline::Highlight {
+                content: vec![
+                    text("marked "),
+                    Inline::Bold {
+                        content: vec![text("bold")]
+                    }
+                ]
+            },
+            text(" and "),
+            Inline::Spoiler {
+                content: vec![text("the twist")]
+            },
+            text(" here"),
+        ]
+    );
+}
+
+#[test]
+fn test_markers_need_exact_runs_and_flanking() {
+    assert_eq!(parse("a == b == c", config()), vec![text("a == b == c")]);
+    assert_eq!(parse("===x===", config()), vec![text("===x===")]);
+    assert_eq!(parse("x || y", config()), vec![text("x || y")]);
+    assert_eq!(parse("==unclosed", config()), vec![text("==unclosed")]);
+}
+
+#[test]
+fn test_disabled_by_default() {
+    assert_eq!(
+        parse("==a== ||b||", ParserConfig::default()),
+        vec![text("==a== ||b||")]
+    );
+}
+
+#[test]
+fn test_highlight_and_spoiler_rendering() {
+    let input = "==note== and ||secret <b>||";
+    let mut parser = Parser::with_config(input.to_string(), config()).unwrap();
+    let html = parser
+        .to_html_with_config(&RendererConfig::default())
+        .unwrap();
+    assert!(html.contains(
+        "<p><mark>note</mark> and <span class=\"spoiler\" tabindex=\"0\">secret &lt;b&gt;</span></p>"
+    ));
+    assert!(html.contains(".spoiler.revealed"));
+    assert_eq!(html.matches("classList.toggle('revealed')").count(), 1);
+    assert_eq!(parser.to_markdown().unwrap().trim_end(), input);
+
+    // Pages without spoilers get no script
+    let mut parser = Parser::with_config("==note==".to_string(), config()).unwrap();
+    let html = parser
+        .to_html_with_config(&RendererConfig::default())
+        .unwrap();
+    assert!(!html.contains("closest('.spoiler')"));
+}
@@ -1,4 +1,7 @@
-use md_parser::{Node, Parser};
+mod common;
+
+use common::parse;
+use md_parser::{LineRange, Node, Parser};
 
 #[test]
 fn test_simple_horizontal_rule_dashes() {
@@ -8,7 +11,7 @@ fn test_simple_horizontal_rule_dashes() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule"),
     }
 }
@@ -21,7 +24,7 @@ fn test_simple_horizontal_rule_asterisks() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule"),
     }
 }
@@ -34,7 +37,7 @@ fn test_horizontal_rule_more_than_three() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule"),
     }
 }
@@ -47,7 +50,7 @@ fn test_horizontal_rule_many_asterisks() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule"),
     }
 }
@@ -60,7 +63,7 @@ fn test_horizontal_rule_with_spaces() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule"),
     }
 }
@@ -73,7 +76,7 @@ fn test_horizontal_rule_asterisks_with_spaces() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule"),
     }
 }
@@ -90,7 +93,7 @@ fn test_horizontal_rule_between_paragraphs() {
         _ => panic!("Expected Paragraph as first element"),
     }
     match &result[1] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule as second element"),
     }
     match &result[2] {
@@ -108,7 +111,7 @@ fn test_multiple_horizontal_rules() {
     assert_eq!(result.len(), 3);
     for node in &result {
         match node {
-            Node::HorizontalRule => {}
+            Node::HorizontalRule { .. } => {}
             _ => panic!("Expected all HorizontalRule elements"),
         }
     }
@@ -126,7 +129,7 @@ fn test_horizontal_rule_after_heading() {
         _ => panic!("Expected Heading as first element"),
     }
     match &result[1] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule as second element"),
     }
 }
@@ -139,7 +142,7 @@ fn test_horizontal_rule_before_list() {
 
     assert_eq!(result.len(), 2);
     match &result[0] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule as first element"),
     }
     match &result[1] {
@@ -160,7 +163,7 @@ fn test_horizontal_rule_after_list() {
         _ => panic!("Expected UnorderedList as first element"),
     }
     match &result[1] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule as second element"),
     }
 }
@@ -177,7 +180,7 @@ fn test_horizontal_rule_with_paragraph_before_and_after() {
         _ => panic!("Expected Paragraph as first element"),
     }
     match &result[1] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule as second element"),
     }
     match &result[2] {
@@ -249,13 +252,13 @@ fn test_horizontal_rule_in_complex_document() {
 
     // Check first horizontal rule
     match &result[2] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule at position 2"),
     }
 
     // Check second horizontal rule
     match &result[5] {
-        Node::HorizontalRule => {}
+        Node::HorizontalRule { .. } => {}
         _ => panic!("Expected HorizontalRule at position 5"),
     }
 }
@@ -277,3 +280,69 @@ fn test_horizontal_rule_rendering_asterisks() {
 
     assert!(html.contains("<hr>"));
 }
+
+#[test]
+fn test_underscore_and_spaced_rules() {
+    for input in ["___", "- - -", "* * *", "_ _ _ _"] {
+        assert!(
+            matches!(parse(input)[..], [Node::HorizontalRule { .. }]),
+            "{:?} parsed as {:?}",
+            input,
+            parse(input)
+        );
+    }
+    // A spaced rule ends a list instead of becoming an item
+    let result = parse("- one\n- two\n* * *");
+    assert!(matches!(
+        result[..],
+        [Node::UnorderedList { .. }, Node::HorizontalRule { .. }]
+    ));
+}
+
+#[test]
+fn test_rule_records_its_line() {
+    let result = parse("Intro\n\n***\n\nOutro");
+    assert_eq!(
+        result[1],
+        Node::HorizontalRule {
+            span: Some(LineRange { start: 3, end: 3 })
+        }
+    );
+}
+
+#[test]
+fn test_rule_interrupts_paragraph() {
+    let result = parse("Some text\n***\nMore text");
+    assert!(matches!(
+        result[..],
+        [
+            Node::Paragraph { .. },
+            Node::HorizontalRule { .. },
+            Node::Paragraph { .. }
+        ]
+    ));
+}
+
+#[test]
+fn test_frontmatter_is_not_a_rule() {
+    let input = "---\ntitle: Guide\ntags: [a, b]\n---\n\n# Guide\n\n---\n\nText";
+    let result = parse(input);
+    assert!(matches!(result[0], Node::Heading { .. }), "{:?}", result);
+    assert_eq!(
+        result[1],
+        Node::HorizontalRule {
+            span: Some(LineRange { start: 8, end: 8 })
+        }
+    );
+
+    // Without a `key:` line after it, an opening `---` is a rule
+    let result = parse("---\n\nText\n\n---");
+    assert!(matches!(
+        result[..],
+        [
+            Node::HorizontalRule { .. },
+            Node::Paragraph { .. },
+            Node::HorizontalRule { .. }
+        ]
+    ));
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use md_parser::{Node, ParseError, Parser, ParserConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Create a fresh directory under the system temp dir holding `files`
+fn scratch_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = common::temp_path(&format!("include_{}", name));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    for (path, content) in files {
+        let full = root.join(path);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::write(full, content).unwrap();
+    }
+    root
+}
+
+fn parse_in(dir: &Path, input: &str) -> Result<Vec<Node>, ParseError> {
+    let config = ParserConfig {
+        enable_includes: true,
+        base_dir: Some(dir.to_string_lossy().into_owned()),
+        ..ParserConfig::default()
+    };
+    Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+}
+
+fn include_message(result: Result<Vec<Node>, ParseError>) -> (String, usize) {
+    match result {
+        Err(ParseError::IncludeFailed { message, span, .. }) => (message, span.line),
+        other => panic!("expected include error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_nested_includes_are_spliced_in() {
+    let dir = scratch_dir(
+        "nested",
+        &[
+            ("shared/license.md", "## License\n\n{{include: notice.md}}"),
+            ("shared/notice.md", "MIT licensed."),
+        ],
+    );
+    let ast = parse_in(&dir, "# Guide\n\n{{include: shared/license.md}}\n\nEnd.").unwrap();
+
+    let kinds: Vec<&str> = ast.iter().map(Node::kind).collect();
+    assert_eq!(kinds, ["heading", "heading", "paragraph", "paragraph"]);
+}
+
+#[test]
+fn test_include_in_code_block_and_disabled_by_default() {
+    let dir = scratch_dir("code", &[("a.md", "Included.")]);
+    let ast = parse_in(&dir, "```\n{{include: a.md}}\n```").unwrap();
+    assert!(matches!(&ast[0], Node::CodeBlock { code, .. } if code == "{{include: a.md}}"));
+
+    let ast = Parser::new("{{include: a.md}}".to_string())
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(matches!(&ast[0], Node::Paragraph { .. }));
+}
+
+#[test]
+fn test_include_cycle_is_reported() {
+    let dir = scratch_dir(
+        "cycle",
+        &[("a.md", "{{include: b.md}}"), ("b.md", "{{include: a.md}}")],
+    );
+    let (message, line) = include_message(parse_in(&dir, "Intro\n{{include: a.md}}"));
+
+    assert_eq!(message, "include cycle: a.md -> b.md -> a.md");
+    assert_eq!(line, 2);
+}
+
+#[test]
+fn test_include_outside_base_dir_is_rejected() {
+    let dir = scratch_dir("sandbox", &[("docs/page.md", "x")]);
+    fs::write(dir.join("secret.md"), "secret").unwrap();
+
+    let (message, _) = include_message(parse_in(&dir.join("docs"), "{{include: ../secret.md}}"));
+    assert_eq!(message, "the file is outside the base directory");
+}
+
+#[test]
+fn test_include_depth_limit() {
+    let dir = scratch_dir(
+        "depth",
+        &[
+            ("a.md", "{{include: b.md}}"),
+            ("b.md", "{{include: c.md}}"),
+            ("c.md", "Deep."),
+        ],
+    );
+    let config = ParserConfig {
+        enable_includes: true,
+        max_include_depth: 2,
+        base_dir: Some(dir.to_string_lossy().into_owned()),
+        ..ParserConfig::default()
+    };
+    let result = Parser::with_config("{{include: a.md}}".to_string(), config)
+        .unwrap()
+        .parse();
+
+    let (message, _) = include_message(result);
+    assert_eq!(message, "includes are nested more than 2 levels deep");
+}
@@ -1,3 +1,6 @@
+mod common;
+
+use common::text;
 use md_parser::{Inline, Node, Parser};
 
 #[test]
@@ -13,7 +16,7 @@ fn test_bold_text() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "This is ".to_string()
+                    content: "This is ".into()
                 }
             );
             match &inlines[1] {
@@ -24,7 +27,7 @@ fn test_bold_text() {
                     assert_eq!(
                         bold_inlines[0],
                         Inline::Text {
-                            content: "bold".to_string()
+                            content: "bold".into()
                         }
                     );
                 }
@@ -33,7 +36,7 @@ fn test_bold_text() {
             assert_eq!(
                 inlines[2],
                 Inline::Text {
-                    content: " text.".to_string()
+                    content: " text.".into()
                 }
             );
         }
@@ -54,7 +57,7 @@ fn test_italic_text() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "This is ".to_string()
+                    content: "This is ".into()
                 }
             );
             match &inlines[1] {
@@ -65,7 +68,7 @@ fn test_italic_text() {
                     assert_eq!(
                         italic_inlines[0],
                         Inline::Text {
-                            content: "italic".to_string()
+                            content: "italic".into()
                         }
                     );
                 }
@@ -74,7 +77,7 @@ fn test_italic_text() {
             assert_eq!(
                 inlines[2],
                 Inline::Text {
-                    content: " text.".to_string()
+                    content: " text.".into()
                 }
             );
         }
@@ -95,7 +98,7 @@ fn test_link() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "Visit ".to_string()
+                    content: "Visit ".into()
                 }
             );
             match &inlines[1] {
@@ -104,7 +107,7 @@ fn test_link() {
                     assert_eq!(
                         text[0],
                         Inline::Text {
-                            content: "Rust".to_string()
+                            content: "Rust".into()
                         }
                     );
                     assert_eq!(url, "https://rust-lang.org");
@@ -114,7 +117,7 @@ fn test_link() {
             assert_eq!(
                 inlines[2],
                 Inline::Text {
-                    content: " today!".to_string()
+                    content: " today!".into()
                 }
             );
         }
@@ -136,7 +139,7 @@ fn test_nested_bold_italic() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "This is ".to_string()
+                    content: "This is ".into()
                 }
             );
             match &inlines[1] {
@@ -148,7 +151,7 @@ fn test_nested_bold_italic() {
                     assert_eq!(
                         bold_inlines[0],
                         Inline::Text {
-                            content: "bold with ".to_string()
+                            content: "bold with ".into()
                         }
                     );
                     // Verify italic is nested inside bold
@@ -160,7 +163,7 @@ fn test_nested_bold_italic() {
                             assert_eq!(
                                 italic_inlines[0],
                                 Inline::Text {
-                                    content: "italic".to_string()
+                                    content: "italic".into()
                                 }
                             );
                         }
@@ -169,7 +172,7 @@ fn test_nested_bold_italic() {
                     assert_eq!(
                         bold_inlines[2],
                         Inline::Text {
-                            content: " inside".to_string()
+                            content: " inside".into()
                         }
                     );
                 }
@@ -178,7 +181,7 @@ fn test_nested_bold_italic() {
             assert_eq!(
                 inlines[2],
                 Inline::Text {
-                    content: ".".to_string()
+                    content: ".".into()
                 }
             );
         }
@@ -194,7 +197,7 @@ fn test_heading_with_inline() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Heading { level, content } => {
+        Node::Heading { level, content, .. } => {
             assert_eq!(*level, 1);
             assert!(content.len() >= 2);
         }
@@ -231,11 +234,11 @@ fn test_image() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "Here's an image ".to_string()
+                    content: "Here's an image ".into()
                 }
             );
             match &inlines[1] {
-                Inline::Image { alt, url } => {
+                Inline::Image { alt, url, .. } => {
                     assert_eq!(alt, "alt text");
                     assert_eq!(url, "https://example.com/image.png");
                 }
@@ -244,7 +247,7 @@ fn test_image() {
             assert_eq!(
                 inlines[2],
                 Inline::Text {
-                    content: ".".to_string()
+                    content: ".".into()
                 }
             );
         }
@@ -263,7 +266,7 @@ fn test_image_empty_alt() {
         Node::Paragraph { content: inlines } => {
             assert_eq!(inlines.len(), 1);
             match &inlines[0] {
-                Inline::Image { alt, url } => {
+                Inline::Image { alt, url, .. } => {
                     assert_eq!(alt, " ");
                     assert_eq!(url, "https://example.com/image.png");
                 }
@@ -286,7 +289,7 @@ fn test_image_vs_link() {
             assert_eq!(inlines.len(), 3);
             // First should be image
             match &inlines[0] {
-                Inline::Image { alt, url } => {
+                Inline::Image { alt, url, .. } => {
                     assert_eq!(alt, "image");
                     assert_eq!(url, "url.png");
                 }
@@ -296,7 +299,7 @@ fn test_image_vs_link() {
             assert_eq!(
                 inlines[1],
                 Inline::Text {
-                    content: " and ".to_string()
+                    content: " and ".into()
                 }
             );
             // Third should be link
@@ -306,7 +309,7 @@ fn test_image_vs_link() {
                     assert_eq!(
                         text[0],
                         Inline::Text {
-                            content: "link".to_string()
+                            content: "link".into()
                         }
                     );
                     assert_eq!(url, "url.html");
@@ -354,7 +357,7 @@ fn test_image_in_heading() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Heading { level, content } => {
+        Node::Heading { level, content, .. } => {
             assert_eq!(*level, 1);
             let has_image = content
                 .iter()
@@ -383,7 +386,7 @@ fn test_bold_with_italic_inside() {
                     assert_eq!(
                         bold_inlines[0],
                         Inline::Text {
-                            content: "bold ".to_string()
+                            content: "bold ".into()
                         }
                     );
                     match &bold_inlines[1] {
@@ -394,7 +397,7 @@ fn test_bold_with_italic_inside() {
                             assert_eq!(
                                 italic_inlines[0],
                                 Inline::Text {
-                                    content: "italic".to_string()
+                                    content: "italic".into()
                                 }
                             );
                         }
@@ -403,7 +406,7 @@ fn test_bold_with_italic_inside() {
                     assert_eq!(
                         bold_inlines[2],
                         Inline::Text {
-                            content: " text".to_string()
+                            content: " text".into()
                         }
                     );
                 }
@@ -432,7 +435,7 @@ fn test_italic_with_bold_inside() {
                     assert_eq!(
                         italic_inlines[0],
                         Inline::Text {
-                            content: "italic ".to_string()
+                            content: "italic ".into()
                         }
                     );
                     match &italic_inlines[1] {
@@ -443,7 +446,7 @@ fn test_italic_with_bold_inside() {
                             assert_eq!(
                                 bold_inlines[0],
                                 Inline::Text {
-                                    content: "bold".to_string()
+                                    content: "bold".into()
                                 }
                             );
                         }
@@ -452,7 +455,7 @@ fn test_italic_with_bold_inside() {
                     assert_eq!(
                         italic_inlines[2],
                         Inline::Text {
-                            content: " text".to_string()
+                            content: " text".into()
                         }
                     );
                 }
@@ -482,7 +485,7 @@ fn test_bold_with_multiple_italic_inside() {
                     assert_eq!(
                         bold_inlines[0],
                         Inline::Text {
-                            content: "bold ".to_string()
+                            content: "bold ".into()
                         }
                     );
                     // First italic
@@ -494,7 +497,7 @@ fn test_bold_with_multiple_italic_inside() {
                             assert_eq!(
                                 italic_inlines[0],
                                 Inline::Text {
-                                    content: "italic".to_string()
+                                    content: "italic".into()
                                 }
                             );
                         }
@@ -503,7 +506,7 @@ fn test_bold_with_multiple_italic_inside() {
                     assert_eq!(
                         bold_inlines[2],
                         Inline::Text {
-                            content: " and ".to_string()
+                            content: " and ".into()
                         }
                     );
                     // Second italic
@@ -515,7 +518,7 @@ fn test_bold_with_multiple_italic_inside() {
                             assert_eq!(
                                 italic_inlines[0],
                                 Inline::Text {
-                                    content: "more italic".to_string()
+                                    content: "more italic".into()
                                 }
                             );
                         }
@@ -524,7 +527,7 @@ fn test_bold_with_multiple_italic_inside() {
                     assert_eq!(
                         bold_inlines[4],
                         Inline::Text {
-                            content: " text".to_string()
+                            content: " text".into()
                         }
                     );
                 }
@@ -554,7 +557,7 @@ fn test_italic_with_bold_inside_complex() {
                     assert_eq!(
                         italic_inlines[0],
                         Inline::Text {
-                            content: "italic ".to_string()
+                            content: "italic ".into()
                         }
                     );
                     // First bold
@@ -566,7 +569,7 @@ fn test_italic_with_bold_inside_complex() {
                             assert_eq!(
                                 bold_inlines[0],
                                 Inline::Text {
-                                    content: "bold".to_string()
+                                    content: "bold".into()
                                 }
                             );
                         }
@@ -575,7 +578,7 @@ fn test_italic_with_bold_inside_complex() {
                     assert_eq!(
                         italic_inlines[2],
                         Inline::Text {
-                            content: " and ".to_string()
+                            content: " and ".into()
                         }
                     );
                     // Second bold
@@ -587,7 +590,7 @@ fn test_italic_with_bold_inside_complex() {
                             assert_eq!(
                                 bold_inlines[0],
                                 Inline::Text {
-                                    content: "more bold".to_string()
+                                    content: "more bold".into()
                                 }
                             );
                         }
@@ -596,7 +599,7 @@ fn test_italic_with_bold_inside_complex() {
                     assert_eq!(
                         italic_inlines[4],
                         Inline::Text {
-                            content: " text".to_string()
+                            content: " text".into()
                         }
                     );
                 }
@@ -621,7 +624,7 @@ fn test_multiple_nested_formats() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "Start ".to_string()
+                    content: "Start ".into()
                 }
             );
             // First bold with italic
@@ -639,7 +642,7 @@ fn test_multiple_nested_formats() {
             assert_eq!(
                 inlines[2],
                 Inline::Text {
-                    content: " and ".to_string()
+                    content: " and ".into()
                 }
             );
             // Italic with bold
@@ -657,7 +660,7 @@ fn test_multiple_nested_formats() {
             assert_eq!(
                 inlines[4],
                 Inline::Text {
-                    content: " end.".to_string()
+                    content: " end.".into()
                 }
             );
         }
@@ -683,7 +686,7 @@ fn test_simple_bold_no_nesting() {
                     assert_eq!(
                         bold_inlines[0],
                         Inline::Text {
-                            content: "bold".to_string()
+                            content: "bold".into()
                         }
                     );
                 }
@@ -712,7 +715,7 @@ fn test_simple_italic_no_nesting() {
                     assert_eq!(
                         italic_inlines[0],
                         Inline::Text {
-                            content: "italic".to_string()
+                            content: "italic".into()
                         }
                     );
                 }
@@ -738,7 +741,7 @@ fn test_inline_code_simple() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "This is ".to_string()
+                    content: "This is ".into()
                 }
             );
             match &inlines[1] {
@@ -750,7 +753,7 @@ fn test_inline_code_simple() {
             assert_eq!(
                 inlines[2],
                 Inline::Text {
-                    content: " text.".to_string()
+                    content: " text.".into()
                 }
             );
         }
@@ -945,7 +948,7 @@ fn test_inline_code_in_heading() {
 
     assert_eq!(result.len(), 1);
     match &result[0] {
-        Node::Heading { level, content } => {
+        Node::Heading { level, content, .. } => {
             assert_eq!(*level, 1);
             let has_code = content
                 .iter()
@@ -1001,3 +1004,71 @@ fn test_inline_code_in_list() {
         _ => panic!("Expected UnorderedList"),
     }
 }
+
+fn paragraph(input: &str) -> Vec<Inline> {
+    match Parser::new(input.to_string())
+        .unwrap()
+        .parse()
+        .unwrap()
+        .remove(0)
+    {
+        Node::Paragraph { content } => content,
+        other => panic!("Expected Paragraph, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_closing_delimiter_after_space_does_not_close() {
+    assert_eq!(paragraph("**bold **text"), vec![text("**bold **text")]);
+    assert_eq!(
+        paragraph("**bold **text**"),
+        vec![
+            text("**bold "),
+            Inline::Bold {
+                content: vec![text("text")]
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_triple_delimiters_nest_italic_and_bold() {
+    assert_eq!(
+        paragraph("***both***"),
+        vec![Inline::Italic {
+            content: vec![Inline::Bold {
+                content: vec![text("both")]
+            }]
+        }]
+    );
+}
+
+#[test]
+fn test_code_span_takes_precedence_over_emphasis() {
+    assert_eq!(
+        paragraph("*a `b* c`"),
+        vec![
+            text("*a "),
+            Inline::Code {
+                content: "b* c".into()
+            }
+        ]
+    );
+    assert_eq!(
+        paragraph("Use `` a`b `` here"),
+        vec![
+            text("Use "),
+            Inline::Code {
+                content: "a`b".into()
+            },
+            text(" here")
+        ]
+    );
+}
+
+#[test]
+fn test_long_paragraph_with_unmatched_markers() {
+    let input = format!("`{}", "*a [b c <!-- ![ ".repeat(20_000));
+    let inlines = paragraph(&input);
+    assert_eq!(inlines, vec![text(input.trim_end())]);
+}
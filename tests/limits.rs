@@ -0,0 +1,97 @@
+use md_parser::{LimitKind, ParseError, Parser, ParserConfig};
+
+fn parse_with(input: &str, configure: impl FnOnce(&mut ParserConfig)) -> Result<(), ParseError> {
+    let mut config = ParserConfig::default();
+    configure(&mut config);
+    Parser::with_config(input.to_string(), config)?
+        .parse()
+        .map(|_| ())
+}
+
+fn limit_kind(result: Result<(), ParseError>) -> (LimitKind, usize, Option<usize>) {
+    match result {
+        Err(ParseError::LimitExceeded { kind, limit, span }) => {
+            (kind, limit, span.map(|span| span.line))
+        }
+        other => panic!("Expected LimitExceeded, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_input_size_limit() {
+    let result = parse_with("# Title\n\nSome text", |c| {
+        c.limits.max_input_bytes = Some(8)
+    });
+    assert_eq!(limit_kind(result), (LimitKind::InputSize, 8, None));
+    assert!(parse_with("# Title", |c| c.limits.max_input_bytes = Some(8)).is_ok());
+}
+
+#[test]
+fn test_deep_inline_nesting_fails_instead_of_overflowing() {
+    let input = format!(
+        "Intro\n\n{}deep{}",
+        "*a **b ".repeat(5_000),
+        "** c*".repeat(5_000)
+    );
+    let (kind, limit, line) = limit_kind(parse_with(&input, |_| {}));
+    assert_eq!((kind, limit, line), (LimitKind::NestingDepth, 128, Some(3)));
+
+    let nested_links = format!("{}x{}", "[".repeat(3), "](u)".repeat(3));
+    assert!(parse_with(&nested_links, |c| c.limits.max_nesting_depth = 1).is_ok());
+    assert!(parse_with("**bold *italic***", |c| c.limits.max_nesting_depth = 2).is_ok());
+    assert!(parse_with("**bold *italic***", |c| c.limits.max_nesting_depth = 1).is_err());
+}
+
+#[test]
+fn test_list_blockquote_and_container_nesting_limits() {
+    let list = "- a\n  - b\n    - c\n      - d";
+    assert_eq!(
+        limit_kind(parse_with(list, |c| c.limits.max_nesting_depth = 3)),
+        (LimitKind::NestingDepth, 3, Some(4))
+    );
+
+    let quote = "Text\n\n>>>> deep";
+    assert_eq!(
+        limit_kind(parse_with(quote, |c| c.limits.max_nesting_depth = 3)),
+        (LimitKind::NestingDepth, 3, Some(3))
+    );
+
+    let containers = ":::: note\n::: warning\ninner\n:::\n::::";
+    assert_eq!(
+        limit_kind(parse_with(containers, |c| c.limits.max_nesting_depth = 1)),
+        (LimitKind::NestingDepth, 1, Some(2))
+    );
+    assert!(parse_with(containers, |c| c.limits.max_nesting_depth = 2).is_ok());
+}
+
+#[test]
+fn test_node_limit() {
+    let input = "One *two*\n\nThree\n\nFour";
+    // Paragraph, Text, Italic, Text, then a second Paragraph and Text
+    assert_eq!(
+        limit_kind(parse_with(input, |c| c.limits.max_nodes = Some(5))),
+        (LimitKind::Nodes, 5, Some(4))
+    );
+    assert!(parse_with(input, |c| c.limits.max_nodes = Some(8)).is_ok());
+}
+
+#[test]
+fn test_timeout() {
+    let input = "Paragraph\n\n".repeat(1_000);
+    assert_eq!(
+        limit_kind(parse_with(&input, |c| c.limits.timeout_ms = Some(0))).0,
+        LimitKind::Timeout
+    );
+}
+
+#[test]
+fn test_limits_from_toml() {
+    let config: ParserConfig = toml::from_str(
+        "max_heading_level = 6\ncode_fence_length = 3\ncode_fence_pattern = \"```\"\n\
+         mermaid_language = \"mermaid\"\n[limits]\nmax_nodes = 10\n",
+    )
+    .unwrap();
+    assert_eq!(config.limits.max_nodes, Some(10));
+    assert_eq!(config.limits.max_nesting_depth, 128);
+    assert_eq!(config.limits.max_input_bytes, None);
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use md_parser::{
+    Attributes, Config, Inline, LineEnding, Node, Parser, ParserConfig, Renderer, RendererConfig,
+};
+use std::fs;
+
+const DOCUMENT: &str = "# Title\n\n```rust\nfn main() {}\n```\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n- one\n  - two\n\nText\nmore text\n";
+
+fn parse_with(input: &str, normalize_line_endings: bool) -> Vec<Node> {
+    let config = ParserConfig {
+        normalize_line_endings,
+        ..ParserConfig::default()
+    };
+    common::parse_with(input, config)
+}
+
+fn parse(input: &str) -> Vec<Node> {
+    parse_with(input, true)
+}
+
+#[test]
+fn test_byte_order_mark_is_dropped() {
+    let ast = parse("\u{feff}# Title");
+    assert!(
+        matches!(&ast[0], Node::Heading { level: 1, content, .. }
+            if content == &vec![Inline::Text { content: "Title".into() }]),
+        "{:?}",
+        ast
+    );
+}
+
+#[test]
+fn test_crlf_and_cr_parse_like_lf() {
+    let expected = parse(DOCUMENT);
+    assert_eq!(parse(&DOCUMENT.replace('\n', "\r\n")), expected);
+    assert_eq!(parse(&DOCUMENT.replace('\n', "\r")), expected);
+    assert_eq!(parse_with(&DOCUMENT.replace('\n', "\r\n"), false), expected);
+
+    let Node::CodeBlock { code, .. } = &parse("```\r\na\r\r\nb\r\n```")[0] else {
+        panic!("expected code block");
+    };
+    assert_eq!(code, "a\n\nb");
+}
+
+#[test]
+fn test_lone_cr_kept_without_normalization() {
+    let ast = parse_with("line\rmore", false);
+    assert_eq!(
+        ast,
+        vec![Node::Paragraph {
+            content: vec![Inline::Text {
+                content: "line\rmore".into()
+            }]
+        }]
+    );
+}
+
+#[test]
+fn test_included_file_with_bom_and_crlf() {
+    let dir = common::temp_path("line_endings_include");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("part.md"),
+        "\u{feff}## Part\r\n\r\n```\r\ncode\r\n```\r\n",
+    )
+    .unwrap();
+    let config = ParserConfig {
+        enable_includes: true,
+        base_dir: Some(dir.to_string_lossy().into_owned()),
+        ..ParserConfig::default()
+    };
+    let ast = Parser::with_config("{{include: part.md}}".to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(
+        matches!(&ast[0], Node::Heading { level: 2, .. }),
+        "{:?}",
+        ast
+    );
+    assert_eq!(
+        ast[1],
+        Node::CodeBlock {
+            lang: None,
+            code: "code".to_string(),
+            attributes: Attributes::default(),
+        }
+    );
+}
+
+#[test]
+fn test_renderer_line_ending() {
+    let ast = parse(DOCUMENT);
+    let lf = Renderer::new(RendererConfig::default()).unwrap();
+    assert!(!lf.render_html(&ast).contains('\r'));
+
+    let crlf = Renderer::new(RendererConfig {
+        line_ending: LineEnding::Crlf,
+        ..RendererConfig::default()
+    })
+    .unwrap();
+    for html in [crlf.render_fragment(&ast), crlf.render_html(&ast)] {
+        assert!(html.contains("\r\n"));
+        assert_eq!(html.matches('\n').count(), html.matches("\r\n").count());
+    }
+}
+
+#[test]
+fn test_line_ending_from_config_file() {
+    let path = common::temp_path("line_endings_layer").with_extension("toml");
+    fs::write(
+        &path,
+        "[parser]\nnormalize_line_endings = false\n\n[renderer]\nline_ending = \"crlf\"\n",
+    )
+    .unwrap();
+    let config = Config::from_layers([&path]).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(!config.parser.normalize_line_endings);
+    assert_eq!(config.renderer.line_ending, LineEnding::Crlf);
+}
@@ -0,0 +1,58 @@
+use md_parser::{LineIndex, ParseError, Parser, Span};
+
+#[test]
+fn test_offsets_round_trip_with_crlf_and_multibyte_text() {
+    let text = "# Café\r\n\r\n日本語 *text*\nlast";
+    let index = LineIndex::new(text);
+    assert_eq!(index.line_count(), 4);
+    assert_eq!(index.line(1), Some("# Café"));
+    assert_eq!(index.line(2), Some(""));
+    assert_eq!(index.line(5), None);
+
+    let star = text.find('*').unwrap();
+    assert_eq!(
+        index.span(star),
+        Span {
+            line: 3,
+            column: Some(5)
+        }
+    );
+    for (offset, _) in text
+        .char_indices()
+        .filter(|(_, c)| *c != '\r' && *c != '\n')
+    {
+        assert_eq!(index.offset(&index.span(offset)), Some(offset));
+    }
+    // Past the end of a line and the start of a line without a column
+    assert_eq!(
+        index.offset(&Span {
+            line: 1,
+            column: Some(8)
+        }),
+        None
+    );
+    assert_eq!(
+        index.offset(&Span {
+            line: 4,
+            column: None
+        }),
+        text.find("last")
+    );
+}
+
+#[test]
+fn test_parse_diagnostics_have_columns() {
+    let err = Parser::new("Intro.\n\n  ```rust\nfn main() {}\n".to_string())
+        .unwrap()
+        .parse()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::UnclosedCodeBlock { span } if span == Span { line: 3, column: Some(3) }
+    ));
+
+    let mut parser = Parser::new("| a | b |\n|---|---|\n| 1 |\n".to_string()).unwrap();
+    parser.parse().unwrap();
+    assert!(!parser.warnings().is_empty());
+    assert!(parser.warnings().iter().all(|w| w.span.column.is_some()));
+}
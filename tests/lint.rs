@@ -0,0 +1,157 @@
+use md_parser::{apply_fixes, lint, HeadingStyle, LintConfig, LintRule};
+
+const INPUT: &str = "---\ntitle: Notes   \n---\n# getting Started With The API\n\n\
+                     See https://example.com/docs. and `https://code.example`.\n\n\
+                     - one\n* two\n\n![](chart.png)\n\n## Setup\n\n## Setup \n\n\
+                     ```sh\necho \"Long Line\"   \n```\n";
+
+fn rules(source: &str, config: &LintConfig) -> Vec<LintRule> {
+    lint(source, config)
+        .unwrap()
+        .iter()
+        .map(|diagnostic| diagnostic.rule)
+        .collect()
+}
+
+#[test]
+fn test_lint_reports_each_rule_in_order() {
+    let diagnostics = lint(INPUT, &LintConfig::default()).unwrap();
+    let rules: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.rule)
+        .collect();
+    assert_eq!(
+        rules,
+        [
+            LintRule::HeadingCapitalization,
+            LintRule::BareUrl,
+            LintRule::ListMarker,
+            LintRule::MissingAltText,
+            LintRule::DuplicateHeading,
+            LintRule::TrailingWhitespace,
+        ]
+    );
+    assert_eq!(diagnostics[0].span.line, 4);
+    assert_eq!(
+        diagnostics[0].fix.as_ref().unwrap().replacement,
+        "# Getting started with the API"
+    );
+    assert_eq!(
+        diagnostics[1].to_string(),
+        "line 6, column 5: bare URL `https://example.com/docs`; use a link [bare-url]"
+    );
+    assert!(diagnostics[3].fix.is_none());
+    assert!(diagnostics[4].message.contains("first on line 13"));
+}
+
+#[test]
+fn test_apply_fixes_rewrites_fixable_lines() {
+    let diagnostics = lint(INPUT, &LintConfig::default()).unwrap();
+    let fixed = apply_fixes(INPUT, &diagnostics);
+    assert!(fixed.contains("# Getting started with the API\n"));
+    assert!(fixed.contains("See [https://example.com/docs](https://example.com/docs). and"));
+    assert!(fixed.contains("- one\n- two\n"));
+    assert!(fixed.contains("## Setup\n\n```"));
+    // Frontmatter and code are left alone
+    assert!(fixed.contains("title: Notes   \n"));
+    assert!(fixed.contains("echo \"Long Line\"   \n"));
+    assert_eq!(
+        rules(&fixed, &LintConfig::default()),
+        [LintRule::MissingAltText, LintRule::DuplicateHeading]
+    );
+}
+
+#[test]
+fn test_heading_style_and_proper_nouns() {
+    let title = LintConfig {
+        heading_style: HeadingStyle::Title,
+        ..LintConfig::default()
+    };
+    let diagnostics = lint("# Working with the rust API\n", &title).unwrap();
+    assert_eq!(
+        diagnostics[0].fix.as_ref().unwrap().replacement,
+        "# Working with the Rust API"
+    );
+
+    let nouns = LintConfig {
+        proper_nouns: vec!["Rust".to_string()],
+        ..LintConfig::default()
+    };
+    assert!(lint("# Using Rust with GitHub\n", &nouns)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        rules("# Using Rust with GitHub\n", &LintConfig::default()),
+        [LintRule::HeadingCapitalization]
+    );
+}
+
+#[test]
+fn test_heading_fix_handles_case_mappings_that_change_length() {
+    let title = LintConfig {
+        heading_style: HeadingStyle::Title,
+        ..LintConfig::default()
+    };
+    let fix = |source: &str, config: &LintConfig| {
+        lint(source, config)
+            .unwrap()
+            .iter()
+            .find_map(|diagnostic| diagnostic.fix.as_ref())
+            .map(|fix| fix.replacement.clone())
+    };
+    assert_eq!(
+        fix("# ŉab beta gamma\n", &title).as_deref(),
+        Some("# ʼNab Beta Gamma")
+    );
+    assert_eq!(
+        fix("# ﬁsh and chips\n", &title).as_deref(),
+        Some("# FIsh and Chips")
+    );
+    assert_eq!(
+        fix("# Intro ẞeta Gamma\n", &LintConfig::default()).as_deref(),
+        Some("# Intro ßeta gamma")
+    );
+    // Used to panic on a char boundary or an out-of-range index
+    for source in ["# ŉ \u{feff}p\n", "# ﬁ[ ] |@user\n"] {
+        lint(source, &title).unwrap();
+        lint(source, &LintConfig::default()).unwrap();
+    }
+}
+
+#[test]
+fn test_line_length_and_disabled_rules() {
+    let long = format!(
+        "{}\n\n| {} |\n",
+        "word ".repeat(25).trim_end(),
+        "cell ".repeat(30)
+    );
+    assert_eq!(rules(&long, &LintConfig::default()), [LintRule::LineLength]);
+
+    let config = LintConfig {
+        disable: vec![LintRule::LineLength],
+        max_line_length: 10,
+        ..LintConfig::default()
+    };
+    assert!(lint(&long, &config).unwrap().is_empty());
+    let url = format!("See https://example.com/{}\n", "a".repeat(120));
+    assert_eq!(
+        rules(&url, &LintConfig::default()),
+        [LintRule::BareUrl],
+        "a long URL is exempt from the length limit"
+    );
+}
+
+#[test]
+fn test_lint_config_from_toml() {
+    let config: LintConfig = toml::from_str(
+        "disable = [\"bare-url\", \"line-length\"]\nheading_style = \"title\"\nmax_line_length = 80\n",
+    )
+    .unwrap();
+    assert_eq!(config.disable, [LintRule::BareUrl, LintRule::LineLength]);
+    assert_eq!(config.heading_style, HeadingStyle::Title);
+    assert_eq!(config.max_line_length, 80);
+    assert_eq!(
+        LintRule::from_name("duplicate-heading"),
+        Some(LintRule::DuplicateHeading)
+    );
+}
@@ -1,4 +1,4 @@
-use md_parser::{Inline, Node, Parser};
+use md_parser::{Inline, ListItem, Node, Parser, ParserConfig};
 
 #[test]
 fn test_unordered_list_simple() {
@@ -14,14 +14,14 @@ fn test_unordered_list_simple() {
             assert_eq!(
                 items[0].content[0],
                 Inline::Text {
-                    content: "one".to_string()
+                    content: "one".into()
                 }
             );
             assert_eq!(items[1].content.len(), 1);
             assert_eq!(
                 items[1].content[0],
                 Inline::Text {
-                    content: "two".to_string()
+                    content: "two".into()
                 }
             );
             assert!(items[0].children.is_empty());
@@ -74,7 +74,7 @@ fn test_nested_list_two_levels() {
             assert_eq!(
                 items[0].content[0],
                 Inline::Text {
-                    content: "a".to_string()
+                    content: "a".into()
                 }
             );
             assert_eq!(items[0].children.len(), 2);
@@ -82,14 +82,14 @@ fn test_nested_list_two_levels() {
             assert_eq!(
                 items[0].children[0].content[0],
                 Inline::Text {
-                    content: "b".to_string()
+                    content: "b".into()
                 }
             );
             assert_eq!(items[0].children[1].content.len(), 1);
             assert_eq!(
                 items[0].children[1].content[0],
                 Inline::Text {
-                    content: "c".to_string()
+                    content: "c".into()
                 }
             );
         }
@@ -112,7 +112,7 @@ fn test_nested_list_three_levels() {
             assert_eq!(
                 items[0].children[0].children[0].content[0],
                 Inline::Text {
-                    content: "c".to_string()
+                    content: "c".into()
                 }
             );
         }
@@ -139,7 +139,7 @@ fn test_list_then_paragraph() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "Some paragraph.".to_string()
+                    content: "Some paragraph.".into()
                 }
             );
         }
@@ -269,21 +269,21 @@ fn test_ordered_list_simple() {
             assert_eq!(
                 items[0].content[0],
                 Inline::Text {
-                    content: "one".to_string()
+                    content: "one".into()
                 }
             );
             assert_eq!(items[1].content.len(), 1);
             assert_eq!(
                 items[1].content[0],
                 Inline::Text {
-                    content: "two".to_string()
+                    content: "two".into()
                 }
             );
             assert_eq!(items[2].content.len(), 1);
             assert_eq!(
                 items[2].content[0],
                 Inline::Text {
-                    content: "three".to_string()
+                    content: "three".into()
                 }
             );
             assert!(items[0].children.is_empty());
@@ -353,7 +353,7 @@ fn test_ordered_list_nested_two_levels() {
             assert_eq!(
                 items[0].content[0],
                 Inline::Text {
-                    content: "a".to_string()
+                    content: "a".into()
                 }
             );
             assert_eq!(items[0].children.len(), 2);
@@ -361,14 +361,14 @@ fn test_ordered_list_nested_two_levels() {
             assert_eq!(
                 items[0].children[0].content[0],
                 Inline::Text {
-                    content: "b".to_string()
+                    content: "b".into()
                 }
             );
             assert_eq!(items[0].children[1].content.len(), 1);
             assert_eq!(
                 items[0].children[1].content[0],
                 Inline::Text {
-                    content: "c".to_string()
+                    content: "c".into()
                 }
             );
         }
@@ -391,7 +391,7 @@ fn test_ordered_list_nested_three_levels() {
             assert_eq!(
                 items[0].children[0].children[0].content[0],
                 Inline::Text {
-                    content: "c".to_string()
+                    content: "c".into()
                 }
             );
         }
@@ -489,7 +489,7 @@ fn test_ordered_list_then_paragraph() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "Some paragraph.".to_string()
+                    content: "Some paragraph.".into()
                 }
             );
         }
@@ -568,3 +568,145 @@ fn test_ordered_list_then_blockquote() {
         _ => panic!("Expected Blockquote second"),
     }
 }
+
+#[test]
+fn test_marker_after_text_is_not_a_list() {
+    for input in ["a - b", "Total * 2 + 1", "Step 1. then 2."] {
+        let result = Parser::new(input.to_string()).unwrap().parse().unwrap();
+        assert!(
+            matches!(result[..], [Node::Paragraph { .. }]),
+            "{:?} parsed as {:?}",
+            input,
+            result
+        );
+    }
+}
+
+#[test]
+fn test_nested_item_under_later_top_level_item() {
+    let input = "- a\n- b\n  - c\n    - d".to_string();
+    let result = Parser::new(input).unwrap().parse().unwrap();
+    let Node::UnorderedList { items } = &result[0] else {
+        panic!("Expected UnorderedList, got {:?}", result[0]);
+    };
+    assert!(items[0].children.is_empty());
+    assert_eq!(items[1].children.len(), 1);
+    assert_eq!(items[1].children[0].children.len(), 1);
+}
+
+/// Shape of a list: each item's text and its children
+fn outline(items: &[ListItem]) -> Vec<(String, usize)> {
+    fn walk(items: &[ListItem], depth: usize, out: &mut Vec<(String, usize)>) {
+        for item in items {
+            out.push((md_parser::plain_text(&item.content), depth));
+            walk(&item.children, depth + 1, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(items, 0, &mut out);
+    out
+}
+
+fn list_outline(input: &str, config: ParserConfig) -> Vec<(String, usize)> {
+    let result = Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    match &result[..] {
+        [Node::UnorderedList { items }] | [Node::OrderedList { items }] => outline(items),
+        _ => panic!("Expected one list, got {:?}", result),
+    }
+}
+
+fn expected(items: &[(&str, usize)]) -> Vec<(String, usize)> {
+    items
+        .iter()
+        .map(|&(text, depth)| (text.to_string(), depth))
+        .collect()
+}
+
+#[test]
+fn test_tab_indented_list_nests() {
+    let input = "- a\n\t- b\n\t\t- c\n\t\t  more\n- d";
+    assert_eq!(
+        list_outline(input, ParserConfig::default()),
+        expected(&[("a", 0), ("b", 1), ("c more", 2), ("d", 0)])
+    );
+    // A tab after spaces advances to the next tab stop
+    let config = ParserConfig {
+        list_indent: 4,
+        ..ParserConfig::default()
+    };
+    assert_eq!(
+        list_outline("- a\n  \t- b\n- c", config),
+        expected(&[("a", 0), ("b", 1), ("c", 0)])
+    );
+}
+
+#[test]
+fn test_list_indent_width() {
+    let input = "1. a\n   1. b\n      - c\n   2. d\n2. e";
+    let config = ParserConfig {
+        list_indent: 3,
+        ..ParserConfig::default()
+    };
+    assert_eq!(
+        list_outline(input, config.clone()),
+        expected(&[("a", 0), ("b", 1), ("c", 2), ("d", 1), ("e", 0)])
+    );
+
+    // The Markdown written back uses the same width
+    let mut parser = Parser::with_config(input.to_string(), config.clone()).unwrap();
+    let markdown = parser.to_markdown().unwrap();
+    assert!(markdown.contains("\n   1. b\n"), "{}", markdown);
+    assert_eq!(
+        list_outline(&markdown, config),
+        expected(&[("a", 0), ("b", 1), ("c", 2), ("d", 1), ("e", 0)])
+    );
+}
+
+#[test]
+fn test_over_indented_item_nests_one_level() {
+    assert_eq!(
+        list_outline("- a\n      - b\n  - c", ParserConfig::default()),
+        expected(&[("a", 0), ("b", 1), ("c", 1)])
+    );
+}
+
+#[test]
+fn test_fenced_code_block_nested_in_list_item() {
+    let input = "1. Install:\n   ```bash\n   cargo add md-parser\n\n     --offline\n   ```\n2. Run it\n   - sub\n\nAfter.";
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+
+    assert_eq!(ast.len(), 2, "{:?}", ast);
+    let Node::OrderedList { items } = &ast[0] else {
+        panic!("expected ordered list, got {:?}", ast[0]);
+    };
+    assert_eq!(items.len(), 2);
+    assert_eq!(
+        items[0].blocks,
+        vec![Node::CodeBlock {
+            lang: Some("bash".to_string()),
+            code: "cargo add md-parser\n\n  --offline".to_string(),
+            attributes: Default::default(),
+        }]
+    );
+    assert!(items[1].blocks.is_empty());
+    assert_eq!(items[1].children.len(), 1);
+
+    let html = Parser::new(input.to_string()).unwrap().to_html().unwrap();
+    assert!(html.contains(
+        "<li>Install:\n<pre><code class=\"language-bash\">cargo add md-parser\n\n  --offline</code></pre></li>"
+    ), "{}", html);
+}
+
+#[test]
+fn test_unindented_fence_still_ends_list() {
+    let ast = Parser::new("- item\n```\ncode\n```".to_string())
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(ast.len(), 2);
+    assert!(matches!(&ast[0], Node::UnorderedList { items } if items[0].blocks.is_empty()));
+    assert!(matches!(ast[1], Node::CodeBlock { .. }));
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use md_parser::{run_language_server, LanguageServer, ParserConfig};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Cursor;
+
+const GUIDE: &str = "\
+# Guide
+
+See [setup](setup.md#install), [missing](missing.md), and [usage](#usage).
+
+## Usage
+
+Run it.
+
+```mermaid
+graph TD
+    A-->B)
+```
+";
+
+fn did_open(uri: &str, text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {"textDocument": {"uri": uri, "languageId": "markdown", "version": 1, "text": text}},
+    })
+}
+
+fn request(id: u64, method: &str, uri: &str, position: Option<(u64, u64)>) -> Value {
+    let mut params = json!({"textDocument": {"uri": uri}});
+    if let Some((line, character)) = position {
+        params["position"] = json!({"line": line, "character": character});
+    }
+    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+}
+
+fn hover_text(server: &mut LanguageServer, uri: &str, line: u64, character: u64) -> Value {
+    let replies = server.handle(&request(
+        9,
+        "textDocument/hover",
+        uri,
+        Some((line, character)),
+    ));
+    replies[0]["result"]["contents"]["value"].clone()
+}
+
+#[test]
+fn test_language_server_features() {
+    let root = common::temp_path("lsp");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    fs::write(
+        root.join("setup.md"),
+        "# Setup\n\n## Install\n\nRun `cargo install`.\n\n## Other\n",
+    )
+    .unwrap();
+    let uri = format!("file://{}", root.join("guide.md").display());
+    let mut server = LanguageServer::new(ParserConfig::default());
+
+    let replies = server.handle(&request(1, "initialize", "", None));
+    assert_eq!(replies[0]["result"]["capabilities"]["hoverProvider"], true);
+
+    // Opening a document publishes its diagnostics
+    let replies = server.handle(&did_open(&uri, GUIDE));
+    assert_eq!(replies[0]["method"], "textDocument/publishDiagnostics");
+    let diagnostics = replies[0]["params"]["diagnostics"].as_array().unwrap();
+    let codes: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic["code"].as_str().unwrap())
+        .collect();
+    assert!(codes.contains(&"invalid-mermaid"), "{:?}", codes);
+    let broken = diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic["code"] == "broken-link")
+        .unwrap();
+    assert_eq!(broken["severity"], 1);
+    assert_eq!(broken["range"]["start"]["line"], 2);
+
+    // Headings nest as document symbols
+    let replies = server.handle(&request(2, "textDocument/documentSymbol", &uri, None));
+    let symbols = &replies[0]["result"];
+    assert_eq!(symbols[0]["name"], "Guide");
+    assert_eq!(symbols[0]["range"]["start"]["line"], 0);
+    assert_eq!(symbols[0]["children"][0]["name"], "Usage");
+    assert_eq!(
+        symbols[0]["children"][0]["selectionRange"]["start"]["line"],
+        4
+    );
+
+    let replies = server.handle(&request(3, "textDocument/foldingRange", &uri, None));
+    let ranges = replies[0]["result"].as_array().unwrap();
+    assert!(ranges
+        .iter()
+        .any(|range| range["startLine"] == 4 && range["kind"] == "region"));
+
+    // Hovering a link previews its target section
+    assert_eq!(
+        hover_text(&mut server, &uri, 2, 8),
+        "## Install\n\nRun `cargo install`."
+    );
+    assert_eq!(
+        hover_text(&mut server, &uri, 2, 62)
+            .as_str()
+            .unwrap()
+            .lines()
+            .next(),
+        Some("## Usage")
+    );
+    assert_eq!(hover_text(&mut server, &uri, 2, 1), Value::Null);
+
+    // Changes replace the text and republish
+    let change = json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": {"uri": uri, "version": 2},
+            "contentChanges": [{"text": "# Fixed\n"}],
+        },
+    });
+    let replies = server.handle(&change);
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+
+    let replies = server.handle(&request(4, "workspace/symbol", &uri, None));
+    assert_eq!(replies[0]["error"]["code"], -32601);
+}
+
+#[test]
+fn test_run_language_server_frames_messages() {
+    let mut input = Vec::new();
+    for message in [
+        request(1, "initialize", "", None),
+        did_open("untitled:notes", "# Notes\n"),
+        request(2, "shutdown", "", None),
+        json!({"jsonrpc": "2.0", "method": "exit"}),
+        request(3, "initialize", "", None),
+    ] {
+        let body = message.to_string();
+        input.extend(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes());
+    }
+    let mut output = Vec::new();
+    run_language_server(ParserConfig::default(), Cursor::new(input), &mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    let bodies: Vec<Value> = output
+        .split("Content-Length: ")
+        .skip(1)
+        .map(|frame| {
+            let (length, body) = frame.split_once("\r\n\r\n").unwrap();
+            assert_eq!(length.parse::<usize>().unwrap(), body.len());
+            serde_json::from_str(body).unwrap()
+        })
+        .collect();
+    // Nothing is read after `exit`
+    assert_eq!(bodies.len(), 3);
+    assert_eq!(bodies[0]["id"], 1);
+    assert_eq!(bodies[1]["params"]["uri"], "untitled:notes");
+    assert_eq!(
+        bodies[2],
+        json!({"jsonrpc": "2.0", "id": 2, "result": null})
+    );
+}
@@ -0,0 +1,40 @@
+use md_parser::{memory_usage, shrink_ast, Parser};
+
+#[test]
+fn test_memory_usage_counts_nodes_and_text() {
+    let ast = Parser::new("# Title\n\nSome *text* and `code`\n\n- one\n- two\n".to_string())
+        .unwrap()
+        .parse()
+        .unwrap();
+    let usage = memory_usage(&ast);
+    assert_eq!(usage.blocks, 3);
+    // Title, Some, italic and its text, and, code, one, two
+    assert_eq!(usage.inlines, 8);
+    assert_eq!(
+        usage.text_bytes,
+        ["Title", "Some ", "text", " and ", "code", "one", "two"]
+            .iter()
+            .map(|s| s.len())
+            .sum::<usize>()
+    );
+    // Short text is stored inline: only the vectors of the nodes and items allocate
+    assert!(usage.allocations < usage.inlines);
+    assert!(usage.total_bytes > usage.text_bytes);
+}
+
+#[test]
+fn test_shrink_ast_releases_spare_capacity() {
+    let source = (0..50)
+        .map(|i| format!("Paragraph {} with **bold** text", i))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let mut ast = Parser::new(source).unwrap().parse().unwrap();
+    let before = memory_usage(&ast);
+    let original = ast.clone();
+
+    shrink_ast(&mut ast);
+    let after = memory_usage(&ast);
+    assert_eq!(ast, original);
+    assert_eq!(after.spare_bytes, 0);
+    assert_eq!(after.total_bytes, before.total_bytes - before.spare_bytes);
+}
@@ -1,4 +1,6 @@
-use md_parser::{Config, MermaidParserConfig, Node, Parser, ValidationStatus};
+mod common;
+
+use md_parser::{Config, MermaidParserConfig, Node, Parser, RendererConfig, ValidationStatus};
 
 #[test]
 fn test_mermaid_validation_valid() {
@@ -13,6 +15,7 @@ fn test_mermaid_validation_valid() {
             config,
             validation_status,
             warnings,
+            ..
         } => {
             assert_eq!(diagram, "graph TD\n    A-->B\n    B-->C");
             assert!(config.is_some());
@@ -260,3 +263,69 @@ fn test_mermaid_diagram_kept_on_validation_error() {
         _ => panic!("Expected MermaidDiagram, not CodeBlock"),
     }
 }
+
+#[test]
+fn test_prerender_falls_back_without_mermaid_cli() {
+    if std::process::Command::new("mmdc")
+        .arg("--version")
+        .output()
+        .is_ok()
+    {
+        return;
+    }
+    let cache_dir = common::temp_path("prerender");
+    let config = RendererConfig {
+        prerender_mermaid: true,
+        mermaid_cache_dir: Some(cache_dir.to_string_lossy().into_owned()),
+        ..RendererConfig::default()
+    };
+    let html = Parser::new("```mermaid\ngraph TD\n    A-->B\n```".to_string())
+        .unwrap()
+        .to_html_with_config(&config)
+        .unwrap();
+
+    assert!(html.contains("<div class=\"mermaid\""));
+    assert!(!cache_dir.exists());
+}
+
+#[test]
+fn test_mermaid_in_blockquote_and_list_item() {
+    let input = "> Architecture:\n> ```mermaid\n> graph TD\n>     A-->B\n> ```\n\n- Flow:\n  ```mermaid\n  invalid syntax here\n  ```\n";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.len(), 2);
+    let Node::Blockquote { blocks, .. } = &result[0] else {
+        panic!("expected blockquote, got {:?}", result[0]);
+    };
+    assert!(matches!(
+        &blocks[0],
+        Node::MermaidDiagram { diagram, .. } if diagram == "graph TD\n    A-->B"
+    ));
+    let Node::UnorderedList { items } = &result[1] else {
+        panic!("expected list, got {:?}", result[1]);
+    };
+    assert!(matches!(
+        &items[0].blocks[0],
+        Node::MermaidDiagram {
+            diagram,
+            validation_status: ValidationStatus::Invalid { .. },
+            ..
+        } if diagram == "invalid syntax here"
+    ));
+    // Validation errors point at the nested fence in the document
+    let invalid = parser
+        .warnings()
+        .iter()
+        .find(|w| w.code == md_parser::WarningCode::InvalidMermaid)
+        .expect("invalid diagram warning");
+    assert_eq!(invalid.span.line, 8);
+
+    let html = Parser::new(input.to_string()).unwrap().to_html().unwrap();
+    assert_eq!(
+        html.matches("<div class=\"mermaid\"").count(),
+        2,
+        "{}",
+        html
+    );
+}
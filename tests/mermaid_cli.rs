@@ -0,0 +1,71 @@
+//! Mermaid CLI validation against a stand-in `mmdc` script placed first on `PATH`.
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use md_parser::{MermaidParserConfig, Parser, ParserConfig, WarningCode};
+
+fn install_fake_mmdc(dir: &Path, script: &str) {
+    let path = dir.join("mmdc");
+    fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn cli_config(timeout_secs: u64) -> ParserConfig {
+    ParserConfig {
+        mermaid: MermaidParserConfig {
+            use_cli_validation: true,
+            cli_timeout_secs: timeout_secs,
+            ..MermaidParserConfig::default()
+        },
+        ..ParserConfig::default()
+    }
+}
+
+// One test, so the PATH change cannot race with other tests in this binary
+#[test]
+fn test_cli_errors_and_timeouts_become_diagnostics() {
+    let dir = common::temp_path("fake_mmdc");
+    fs::create_dir_all(&dir).unwrap();
+    let path = std::env::var("PATH").unwrap_or_default();
+    std::env::set_var("PATH", format!("{}:{}", dir.display(), path));
+
+    install_fake_mmdc(
+        &dir,
+        "printf 'Error: Parse error on line 2:\\nA-->-->B\\n----^\\nExpecting NODE, got LINK\\n    at parse (mermaid.js:1:1)\\n' >&2\nexit 1",
+    );
+    let input = "Intro\n\n```mermaid\ngraph TD\n    A-->-->B\n```";
+    let mut parser = Parser::with_config(input.to_string(), cli_config(30)).unwrap();
+    parser.parse().unwrap();
+    let error = parser
+        .warnings()
+        .iter()
+        .find(|warning| warning.code == WarningCode::InvalidMermaid)
+        .expect("expected an invalid-mermaid warning");
+    assert_eq!(error.span.line, 5);
+    assert!(error
+        .message
+        .ends_with("Mermaid CLI: Parse error on line 2: Expecting NODE, got LINK"));
+
+    install_fake_mmdc(&dir, "sleep 5");
+    let mut parser = Parser::with_config(
+        "```mermaid\ngraph TD\n    A-->B\n```".to_string(),
+        cli_config(1),
+    )
+    .unwrap();
+    parser.parse().unwrap();
+    let warnings = parser.warnings();
+    assert!(warnings
+        .iter()
+        .all(|warning| warning.code != WarningCode::InvalidMermaid));
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.message.contains("did not finish within 1s")));
+
+    std::env::set_var("PATH", path);
+    fs::remove_dir_all(&dir).unwrap();
+}
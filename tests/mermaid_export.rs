@@ -0,0 +1,54 @@
+mod common;
+
+use common::parse;
+use md_parser::{diagram_exports, DiagramFormat};
+
+#[test]
+fn test_diagram_export_names_follow_headings() {
+    let ast = parse(
+        "```mermaid\ngraph TD\n    A-->B\n```\n\n\
+         ## Setup Steps\n\n```mermaid\ngraph TD\n    C-->D\n```\n\n\
+         ```mermaid\ngraph TD\n    E-->F\n```\n\n\
+         ```mermaid name=flow\ngraph TD\n    G-->H\n```\n",
+    );
+    let names: Vec<_> = diagram_exports(&ast)
+        .into_iter()
+        .map(|export| export.name)
+        .collect();
+    assert_eq!(names, ["diagram", "setup-steps", "setup-steps-1", "flow"]);
+}
+
+#[test]
+fn test_diagram_export_source_carries_init_directive() {
+    let ast =
+        parse("# Doc\n\n```mermaid\n%%{init: {'theme':'dark'}}%%\ngraph TD\n    A-->B\n```\n");
+    let exports = diagram_exports(&ast);
+    assert_eq!(exports.len(), 1);
+    let source = &exports[0].source;
+    assert!(
+        source.starts_with("%%{init: {\"theme\":\"dark\""),
+        "{}",
+        source
+    );
+    assert!(source.contains("\"themeVariables\":{"), "{}", source);
+    assert_eq!(source.matches("%%{init").count(), 1, "{}", source);
+    assert!(source.ends_with("graph TD\n    A-->B"), "{}", source);
+    assert_eq!(
+        exports[0].render(DiagramFormat::Mmd).unwrap(),
+        exports[0].source.as_bytes()
+    );
+    assert_eq!(DiagramFormat::Png.extension(), "png");
+}
+
+#[test]
+fn test_diagram_exports_include_nested_diagrams() {
+    let ast = parse(
+        "## Overview\n\n> ```mermaid\n> graph TD\n>     A-->B\n> ```\n\n\
+         - Step:\n  ```mermaid name=steps\n  graph TD\n      C-->D\n  ```\n",
+    );
+    let names: Vec<_> = diagram_exports(&ast)
+        .into_iter()
+        .map(|export| export.name)
+        .collect();
+    assert_eq!(names, ["overview", "steps"]);
+}
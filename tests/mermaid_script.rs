@@ -0,0 +1,89 @@
+use md_parser::{MermaidScript, Parser, Renderer, RendererConfig};
+use std::fs;
+
+const DIAGRAM: &str = "# Flow\n\n```mermaid\ngraph TD\n    A-->B\n```\n";
+
+fn page(input: &str, config: RendererConfig) -> String {
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+    Renderer::new(config).unwrap().render_html(&ast)
+}
+
+#[test]
+fn test_cdn_script_is_pinned_and_only_added_for_diagrams() {
+    let html = page(DIAGRAM, RendererConfig::default());
+    assert!(html.contains(
+        "<script src=\"https://cdn.jsdelivr.net/npm/mermaid@10.9.1/dist/mermaid.min.js\"></script>"
+    ));
+    assert_eq!(html.matches("mermaid.initialize").count(), 1);
+
+    let html = page("# Plain\n\nNo diagrams here.\n", RendererConfig::default());
+    assert!(!html.contains("mermaid.min.js"));
+    assert!(!html.contains("mermaid.initialize"));
+}
+
+#[test]
+fn test_inline_and_disabled_scripts() {
+    let dir = std::env::temp_dir().join(format!("md_parser_mermaid_script_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("mermaid.min.js");
+    fs::write(&script, "window.mermaid = {}; // \"</script>\"").unwrap();
+
+    let html = page(
+        DIAGRAM,
+        RendererConfig {
+            mermaid_script: MermaidScript::Inline,
+            mermaid_script_path: script.to_string_lossy().into_owned(),
+            ..RendererConfig::default()
+        },
+    );
+    assert!(html.contains("<script>\nwindow.mermaid = {}; // \"<\\/script>\"\n</script>"));
+    assert!(!html.contains("cdn.jsdelivr.net/npm/mermaid"));
+
+    let html = page(
+        DIAGRAM,
+        RendererConfig {
+            mermaid_script: MermaidScript::None,
+            ..RendererConfig::default()
+        },
+    );
+    assert!(!html.contains("mermaid.initialize"));
+    assert!(html.contains("<div class=\"mermaid\""));
+
+    let missing = Renderer::new(RendererConfig {
+        mermaid_script: MermaidScript::Inline,
+        mermaid_script_path: dir.join("missing.js").to_string_lossy().into_owned(),
+        ..RendererConfig::default()
+    });
+    assert!(missing.unwrap_err().to_string().contains("missing.js"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_local_script_is_copied_next_to_the_page() {
+    let dir = std::env::temp_dir().join(format!("md_parser_mermaid_local_{}", std::process::id()));
+    let out = dir.join("out");
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("mermaid.min.js");
+    fs::write(&script, "window.mermaid = {};").unwrap();
+
+    let config = RendererConfig {
+        output_directory: out.to_string_lossy().into_owned(),
+        mermaid_script: MermaidScript::Local,
+        mermaid_version: "11.0.0".to_string(),
+        mermaid_script_path: script.to_string_lossy().into_owned(),
+        ..RendererConfig::default()
+    };
+    let mut parser = Parser::new(DIAGRAM.to_string()).unwrap();
+    parser.parse().unwrap();
+    parser
+        .to_html_file_with_config("page.html", &config)
+        .unwrap();
+
+    let html = fs::read_to_string(out.join("page.html")).unwrap();
+    assert!(html.contains("<script src=\"mermaid-11.0.0.min.js\"></script>"));
+    assert_eq!(
+        fs::read_to_string(out.join("mermaid-11.0.0.min.js")).unwrap(),
+        "window.mermaid = {};"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
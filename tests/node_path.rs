@@ -0,0 +1,91 @@
+mod common;
+
+use md_parser::{iter_nodes, node_at, node_at_mut, visit_nodes_mut, Node, NodePath, ParserConfig};
+
+const INPUT: &str = "Intro\n\n# One\n\n```a\n1\n```\n\n> [!NOTE]\n> ```b\n> 2\n> ```\n\n## Two\n\n:::details More\n```c\n3\n```\n:::\n";
+
+#[test]
+fn test_iter_nodes_paths() {
+    let ast = common::parse_with(
+        INPUT,
+        ParserConfig {
+            enable_sections: true,
+            ..ParserConfig::default()
+        },
+    );
+    let paths: Vec<(String, &str)> = iter_nodes(&ast)
+        .map(|(path, node)| (path.to_string(), node.kind()))
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            ("0", "paragraph"),
+            ("1", "section"),
+            ("1.0", "code_block"),
+            ("1.1", "admonition"),
+            ("1.1.0", "code_block"),
+            ("1.2", "section"),
+            ("1.2.0", "details"),
+            ("1.2.0.0", "code_block"),
+        ]
+        .map(|(path, kind)| (path.to_string(), kind))
+    );
+
+    for (path, node) in iter_nodes(&ast) {
+        assert_eq!(node_at(&ast, &path), Some(node));
+    }
+    assert_eq!(node_at(&ast, &NodePath::from(vec![0, 0])), None);
+    assert_eq!(node_at(&ast, &NodePath::default()), None);
+}
+
+#[test]
+fn test_replace_nth_code_block() {
+    let mut ast = common::parse_with(
+        INPUT,
+        ParserConfig {
+            enable_sections: true,
+            ..ParserConfig::default()
+        },
+    );
+    let (path, _) = iter_nodes(&ast)
+        .filter(|(_, node)| matches!(node, Node::CodeBlock { .. }))
+        .nth(2)
+        .unwrap();
+    assert_eq!(path.indices(), [1, 2, 0, 0]);
+    assert_eq!(path.depth(), 3);
+    assert_eq!(path.parent(), Some(NodePath::from(vec![1, 2, 0])));
+    assert_eq!(NodePath::from(vec![1, 2, 0]).child(0), path);
+
+    *node_at_mut(&mut ast, &path).unwrap() = Node::HorizontalRule { span: None };
+    let kinds: Vec<&str> = iter_nodes(&ast).map(|(_, node)| node.kind()).collect();
+    assert_eq!(kinds.last(), Some(&"horizontal_rule"));
+}
+
+#[test]
+fn test_visit_nodes_mut() {
+    let mut ast = common::parse_with(
+        INPUT,
+        ParserConfig {
+            enable_sections: true,
+            ..ParserConfig::default()
+        },
+    );
+    let mut visited = Vec::new();
+    visit_nodes_mut(&mut ast, |path, node| {
+        visited.push(path.clone());
+        if let Node::CodeBlock { lang, .. } = node {
+            *lang = Some(format!("{}-{}", lang.as_deref().unwrap_or(""), path));
+        }
+    });
+    assert_eq!(
+        visited,
+        iter_nodes(&ast).map(|(path, _)| path).collect::<Vec<_>>()
+    );
+    let langs: Vec<String> = iter_nodes(&ast)
+        .filter_map(|(_, node)| match node {
+            Node::CodeBlock { lang, .. } => lang.clone(),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(langs, ["a-1.0", "b-1.1.0", "c-1.2.0.0"]);
+}
@@ -0,0 +1,74 @@
+use md_parser::{
+    escape_html, plain_text, CustomRenderer, Inline, Node, NodeRenderer, Parser, RendererConfig,
+};
+
+fn render(input: &str, custom_renderer: CustomRenderer) -> String {
+    Parser::new(input.to_string())
+        .unwrap()
+        .to_html_with_config(&RendererConfig {
+            custom_renderer: Some(custom_renderer),
+            ..RendererConfig::default()
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_closure_overrides_code_blocks() {
+    let charts = CustomRenderer::new(|node: &Node, _: &RendererConfig| match node {
+        Node::CodeBlock {
+            lang: Some(lang),
+            code,
+            ..
+        } if lang == "chart" => Some(format!(
+            "<div class=\"chart\" data-spec=\"{}\"></div>",
+            escape_html(code)
+        )),
+        _ => None,
+    });
+    let html = render(
+        "```chart\n{\"type\": \"bar\"}\n```\n\n```rust\nfn main() {}\n```\n",
+        charts,
+    );
+    assert!(html
+        .contains("<div class=\"chart\" data-spec=\"{&quot;type&quot;: &quot;bar&quot;}\"></div>"));
+    // Other code blocks keep the default rendering
+    assert!(html.contains("<code class=\"language-rust\">fn main() {}</code>"));
+}
+
+struct ExternalLinks;
+
+impl NodeRenderer for ExternalLinks {
+    fn render_inline(&self, inline: &Inline, _: &RendererConfig) -> Option<String> {
+        match inline {
+            Inline::Link { text, url } if url.starts_with("https://") => Some(format!(
+                "<a href=\"{}\" rel=\"external\">{}</a>",
+                escape_html(url),
+                escape_html(&plain_text(text))
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_trait_overrides_nested_inlines() {
+    let html = render(
+        "> [!NOTE]\n> See **[the docs](https://example.com)** or [home](/).\n",
+        CustomRenderer::new(ExternalLinks),
+    );
+    assert!(
+        html.contains(
+            "<strong><a href=\"https://example.com\" rel=\"external\">the docs</a></strong>"
+        ),
+        "{}",
+        html
+    );
+    assert!(html.contains("<a href=\"/\">home</a>"));
+}
+
+#[test]
+fn test_custom_renderer_equality() {
+    let custom = CustomRenderer::new(ExternalLinks);
+    assert_eq!(custom, custom.clone());
+    assert_ne!(custom, CustomRenderer::new(ExternalLinks));
+}
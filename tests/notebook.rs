@@ -0,0 +1,150 @@
+use md_parser::{
+    notebook_to_markdown, parse_notebook, Inline, Node, NotebookConfig, NotebookError, Parser,
+    ParserConfig,
+};
+use serde_json::json;
+
+fn notebook(cells: serde_json::Value) -> String {
+    json!({
+        "nbformat": 4,
+        "nbformat_minor": 5,
+        "metadata": {"kernelspec": {"name": "python3", "language": "python"}},
+        "cells": cells,
+    })
+    .to_string()
+}
+
+fn parse(json: &str) -> Vec<Node> {
+    parse_notebook(json, &ParserConfig::default(), &NotebookConfig::default()).unwrap()
+}
+
+#[test]
+fn test_cells_become_one_document() {
+    let json = notebook(json!([
+        {"cell_type": "markdown", "metadata": {}, "source": ["# Analysis\n", "\n", "Some *notes*."]},
+        {"cell_type": "code", "metadata": {}, "execution_count": 1,
+         "source": ["import math\n", "math.pi"], "outputs": []},
+        {"cell_type": "raw", "metadata": {}, "source": "left out"},
+        {"cell_type": "markdown", "metadata": {}, "source": "## Results"},
+    ]));
+    let ast = parse(&json);
+
+    assert_eq!(ast.len(), 4, "{:?}", ast);
+    assert!(matches!(&ast[0], Node::Heading { level: 1, .. }));
+    assert!(matches!(&ast[1], Node::Paragraph { .. }));
+    assert!(matches!(
+        &ast[2],
+        Node::CodeBlock { lang: Some(lang), code, .. }
+            if lang == "python" && code == "import math\nmath.pi"
+    ));
+    assert!(matches!(&ast[3], Node::Heading { level: 2, .. }));
+}
+
+#[test]
+fn test_outputs_follow_their_cell() {
+    let json = notebook(json!([
+        {"cell_type": "code", "metadata": {}, "execution_count": 1, "source": "run()",
+         "outputs": [
+            {"output_type": "stream", "name": "stdout", "text": ["step 1\n", "step 2\n"]},
+            {"output_type": "stream", "name": "stderr", "text": "careful\n"},
+            {"output_type": "display_data", "metadata": {},
+             "data": {"image/png": "iVBORw0KGgo=\n", "text/plain": "<Figure>"}},
+            {"output_type": "execute_result", "execution_count": 1, "metadata": {},
+             "data": {"text/plain": ["42"]}},
+            {"output_type": "error", "ename": "ValueError", "evalue": "bad",
+             "traceback": ["\u{1b}[0;31mValueError\u{1b}[0m: bad"]},
+         ]},
+    ]));
+    let ast = parse(&json);
+
+    let outputs: Vec<(&str, Vec<String>)> = ast[1..]
+        .iter()
+        .filter_map(|node| match node {
+            Node::CodeBlock {
+                code, attributes, ..
+            } => Some((code.as_str(), attributes.classes.clone())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        outputs,
+        vec![
+            ("step 1\nstep 2", vec!["output".to_string()]),
+            ("careful", vec!["output".to_string(), "stderr".to_string()]),
+            ("42", vec!["output".to_string()]),
+            (
+                "ValueError: bad",
+                vec!["output".to_string(), "error".to_string()]
+            ),
+        ]
+    );
+    assert!(ast.iter().any(|node| matches!(
+        node,
+        Node::Paragraph { content } if matches!(
+            &content[0],
+            Inline::Image { url, .. } if url == "data:image/png;base64,iVBORw0KGgo="
+        )
+    )));
+
+    let config = NotebookConfig { outputs: false };
+    let ast = parse_notebook(&json, &ParserConfig::default(), &config).unwrap();
+    assert_eq!(ast.len(), 1);
+}
+
+#[test]
+fn test_cell_tags_remove_parts() {
+    let output = json!([{"output_type": "stream", "name": "stdout", "text": "out"}]);
+    let json = notebook(json!([
+        {"cell_type": "code", "metadata": {"tags": ["remove-cell"]}, "source": "a", "outputs": output},
+        {"cell_type": "code", "metadata": {"tags": ["remove-input"]}, "source": "b", "outputs": output},
+        {"cell_type": "code", "metadata": {"tags": ["remove-output"]}, "source": "c", "outputs": output},
+    ]));
+    let codes: Vec<String> = parse(&json)
+        .into_iter()
+        .filter_map(|node| match node {
+            Node::CodeBlock { code, .. } => Some(code),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(codes, ["out", "c"]);
+}
+
+#[test]
+fn test_attachments_and_markdown_round_trip() {
+    let json = notebook(json!([
+        {"cell_type": "markdown", "metadata": {},
+         "source": "- Plot:\n  - ![chart](attachment:chart.png)",
+         "attachments": {"chart.png": {"image/png": "AAAA"}}},
+        {"cell_type": "code", "metadata": {}, "source": "plot()", "outputs": []},
+    ]));
+    let config = ParserConfig {
+        list_indent: 2,
+        ..ParserConfig::default()
+    };
+    let nodes = parse_notebook(&json, &config, &NotebookConfig::default()).unwrap();
+    assert!(format!("{:?}", nodes).contains("data:image/png;base64,AAAA"));
+
+    let markdown = notebook_to_markdown(&json, &config, &NotebookConfig::default()).unwrap();
+    assert!(markdown.contains("```python\nplot()\n```"), "{}", markdown);
+    let reparsed = Parser::with_config(markdown, config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(reparsed.len(), nodes.len());
+}
+
+#[test]
+fn test_invalid_notebooks_are_rejected() {
+    let error = |json: &str| {
+        parse_notebook(json, &ParserConfig::default(), &NotebookConfig::default()).unwrap_err()
+    };
+    assert!(matches!(error("not json"), NotebookError::Invalid(_)));
+    assert_eq!(
+        error(r#"{"nbformat": 3, "worksheets": []}"#).to_string(),
+        "invalid notebook: nbformat 3 is not supported (only 4 is)"
+    );
+    assert_eq!(
+        error(r#"{"nbformat": 4}"#).to_string(),
+        "invalid notebook: missing `cells`"
+    );
+}
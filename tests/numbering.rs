@@ -0,0 +1,110 @@
+mod common;
+
+use md_parser::{
+    number_headings, number_tables, plain_text, Node, ParserConfig, Renderer, RendererConfig,
+};
+
+fn heading_texts(nodes: &[Node]) -> Vec<(String, Option<String>)> {
+    nodes
+        .iter()
+        .filter_map(|node| match node {
+            Node::Heading {
+                content,
+                attributes,
+                ..
+            } => Some((plain_text(content), attributes.id.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_number_headings_hierarchically() {
+    let ast = common::parse_with(
+        "# Intro\n\n## Goals\n\n## Goals\n\n#### Deep\n\n# Usage {#use}\n\n## Setup\n",
+        ParserConfig {
+            enable_attributes: true,
+            ..ParserConfig::default()
+        },
+    );
+    let pair = |text: &str, id: &str| (text.to_string(), Some(id.to_string()));
+    assert_eq!(
+        heading_texts(&number_headings(&ast, 1)),
+        vec![
+            pair("1 Intro", "intro"),
+            pair("1.1 Goals", "goals"),
+            pair("1.2 Goals", "goals-1"),
+            pair("1.2.0.1 Deep", "deep"),
+            pair("2 Usage", "use"),
+            pair("2.1 Setup", "setup"),
+        ]
+    );
+
+    // A level-1 title stays unnumbered when numbering starts at level 2
+    let ast = common::parse("# Guide\n\n## Install\n\n### Linux\n\n## Remove\n");
+    let texts: Vec<String> = heading_texts(&number_headings(&ast, 2))
+        .into_iter()
+        .map(|(text, _)| text)
+        .collect();
+    assert_eq!(texts, ["Guide", "1 Install", "1.1 Linux", "2 Remove"]);
+}
+
+#[test]
+fn test_number_headings_in_sections() {
+    let ast = common::parse_with(
+        "# One\n\n## Two\n\nText\n\n# Three\n",
+        ParserConfig {
+            enable_sections: true,
+            ..ParserConfig::default()
+        },
+    );
+    let html = Renderer::new(RendererConfig {
+        number_headings: true,
+        ..RendererConfig::default()
+    })
+    .unwrap()
+    .render_fragment(&ast);
+    assert!(html.contains("<h1 id=\"one\">1 One</h1>"), "{}", html);
+    assert!(html.contains("<h2 id=\"two\">1.1 Two</h2>"), "{}", html);
+    assert!(html.contains("<h1 id=\"three\">2 Three</h1>"), "{}", html);
+}
+
+#[test]
+fn test_number_captioned_tables() {
+    let ast = common::parse(
+        "| a |\n|---|\n| 1 |\n\nTable: First\n\n| b |\n|---|\n| 2 |\n\n\
+         | c |\n|---|\n| 3 |\nTable: Second\n",
+    );
+    let numbers: Vec<Option<usize>> = number_tables(&ast)
+        .iter()
+        .filter_map(|node| match node {
+            Node::Table { number, .. } => Some(*number),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(numbers, [Some(1), None, Some(2)]);
+
+    let html = Renderer::new(RendererConfig {
+        number_tables: true,
+        ..RendererConfig::default()
+    })
+    .unwrap()
+    .render_fragment(&ast);
+    assert!(
+        html.contains(
+            "<table id=\"table-2\">\n<caption><span class=\"table-number\">Table 2:</span> Second</caption>"
+        ),
+        "{}",
+        html
+    );
+    // Numbers are render-time only and do not change the parsed AST
+    assert!(!common::parse("| a |\n|---|\n| 1 |\nTable: T\n")
+        .iter()
+        .any(|node| matches!(
+            node,
+            Node::Table {
+                number: Some(_),
+                ..
+            }
+        )));
+}
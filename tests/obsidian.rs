@@ -0,0 +1,170 @@
+mod common;
+
+use common::text;
+use md_parser::{AdmonitionKind, Inline, Node, ParseError, Parser, ParserConfig};
+use std::fs;
+
+fn obsidian() -> ParserConfig {
+    ParserConfig {
+        enable_obsidian: true,
+        ..ParserConfig::default()
+    }
+}
+
+fn parse(input: &str, config: ParserConfig) -> Result<Vec<Node>, ParseError> {
+    Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+}
+
+#[test]
+fn test_callouts_become_admonitions_and_details() {
+    let ast = parse(
+        "> [!tip] Read **this**\n> Body\n\n> [!bug]\n> Crash\n\n> [!NOTE]\n> Plain\n\n\
+         > [!faq]- Why?\n> Because.\n",
+        obsidian(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        ast[0],
+        Node::Admonition {
+            kind: AdmonitionKind::Tip,
+            title: Some(vec![
+                text("Read "),
+                Inline::Bold {
+                    content: vec![text("this")]
+                }
+            ]),
+            content: vec![Node::Paragraph {
+                content: vec![text("Body")]
+            }],
+        }
+    );
+    // Obsidian's other types map to the nearest kind and keep their name as the title
+    assert!(matches!(
+        &ast[1],
+        Node::Admonition { kind: AdmonitionKind::Caution, title: Some(title), .. }
+            if title == &[text("Bug")]
+    ));
+    assert!(matches!(
+        &ast[2],
+        Node::Admonition {
+            kind: AdmonitionKind::Note,
+            title: None,
+            ..
+        }
+    ));
+    assert!(matches!(
+        &ast[3],
+        Node::Details { summary, content } if summary == &[text("Why?")] && content.len() == 1
+    ));
+
+    // Without the profile, only GitHub's own alert types are admonitions
+    let ast = parse("> [!tip] Title\n> Body\n", ParserConfig::default()).unwrap();
+    assert!(matches!(ast[0], Node::Blockquote { .. }));
+}
+
+#[test]
+fn test_embeds_become_images_and_links() {
+    let ast = parse(
+        "![[logo.png|200]] ![[chart.svg|Sales chart]] ![[My Note#Set up]] ![[scan.pdf]]",
+        obsidian(),
+    )
+    .unwrap();
+    let Node::Paragraph { content } = &ast[0] else {
+        panic!("expected a paragraph, got {:?}", ast[0]);
+    };
+    assert!(matches!(
+        &content[0],
+        Inline::Image { url, attributes, .. }
+            if url == "logo.png" && attributes.get("width") == Some("200")
+    ));
+    assert!(matches!(
+        &content[2],
+        Inline::Image { url, alt, .. } if url == "chart.svg" && alt == "Sales chart"
+    ));
+    assert_eq!(
+        content[4],
+        Inline::Link {
+            text: vec![text("My Note > Set up")],
+            url: "My%20Note.md#set-up".to_string(),
+        }
+    );
+    assert!(matches!(&content[6], Inline::Link { url, .. } if url == "scan.pdf"));
+}
+
+#[test]
+fn test_percent_comments() {
+    let ast = parse(
+        "%%\nDraft notes\n%%\n\nShown %%hidden%% text\n\n%% unclosed\n",
+        obsidian(),
+    )
+    .unwrap();
+    assert_eq!(
+        ast[0],
+        Node::Comment {
+            content: "Draft notes".to_string()
+        }
+    );
+    assert_eq!(
+        ast[1],
+        Node::Paragraph {
+            content: vec![
+                text("Shown "),
+                Inline::Comment {
+                    content: "hidden".to_string()
+                },
+                text(" text"),
+            ]
+        }
+    );
+    assert!(matches!(ast[2], Node::Comment { .. }));
+
+    let mut parser = Parser::with_config("%% unclosed\n".to_string(), obsidian()).unwrap();
+    let result = parser.parse_full().unwrap();
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].message.contains("unclosed `%%` comment"));
+}
+
+#[test]
+fn test_note_embeds_are_transcluded_with_includes() {
+    let vault = common::temp_path("obsidian_vault");
+    let _ = fs::remove_dir_all(&vault);
+    fs::create_dir_all(&vault).unwrap();
+    fs::write(
+        vault.join("Setup Guide.md"),
+        "---\ntags: guide\n---\n# Install\n\nRun the installer.\n\n## Linux\n\nUse apt.\n\n\
+         # Remove\n\nDelete it.\n",
+    )
+    .unwrap();
+    let config = ParserConfig {
+        enable_includes: true,
+        base_dir: Some(vault.to_string_lossy().into_owned()),
+        ..obsidian()
+    };
+
+    let ast = parse("![[Setup Guide#install]]\n", config.clone()).unwrap();
+    let headings: Vec<_> = ast
+        .iter()
+        .filter(|node| matches!(node, Node::Heading { .. }))
+        .collect();
+    assert_eq!(headings.len(), 2, "{:?}", ast);
+    assert!(!format!("{:?}", ast).contains("Delete it"));
+
+    // The whole note, without its frontmatter
+    let ast = parse("![[Setup Guide]]\n", config.clone()).unwrap();
+    assert!(
+        matches!(&ast[0], Node::Heading { level: 1, .. }),
+        "{:?}",
+        ast
+    );
+
+    match parse("![[Setup Guide#Upgrade]]\n", config) {
+        Err(ParseError::IncludeFailed { message, .. }) => {
+            assert!(message.contains("no heading `Upgrade`"), "{}", message)
+        }
+        other => panic!("expected an include error, got {:?}", other),
+    }
+    fs::remove_dir_all(&vault).unwrap();
+}
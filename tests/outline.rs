@@ -0,0 +1,60 @@
+use md_parser::{Outline, Parser, ParserConfig};
+
+fn outline(input: &str) -> Outline {
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    Outline::from_nodes(&parser.parse().unwrap())
+}
+
+#[test]
+fn test_outline_nests_sections_by_heading_level() {
+    let outline = outline("Intro\n\n# Guide\n\nText\n\n## Install\n\n## Usage\n\n# Reference");
+
+    assert_eq!(outline.preamble.len(), 1);
+    assert_eq!(outline.sections.len(), 2);
+
+    let guide = &outline.sections[0];
+    assert_eq!(guide.title, "Guide");
+    assert_eq!(guide.anchor, "guide");
+    assert_eq!(guide.body.len(), 1);
+    let children: Vec<&str> = guide.children.iter().map(|c| c.title.as_str()).collect();
+    assert_eq!(children, ["Install", "Usage"]);
+
+    assert_eq!(outline.sections[1].title, "Reference");
+    assert!(outline.sections[1].children.is_empty());
+}
+
+#[test]
+fn test_outline_skipped_levels_nest_under_nearest_parent() {
+    let outline = outline("# Top\n\n### Deep\n\n## Middle");
+
+    let top = &outline.sections[0];
+    let levels: Vec<u8> = top.children.iter().map(|c| c.level).collect();
+    assert_eq!(levels, [3, 2]);
+}
+
+#[test]
+fn test_outline_anchors_are_unique_and_respect_explicit_ids() {
+    let config = ParserConfig {
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    let mut parser =
+        Parser::with_config("# Notes\n\n# Notes\n\n# Custom {#mine}".to_string(), config).unwrap();
+    let outline = Outline::from_nodes(&parser.parse().unwrap());
+
+    let anchors: Vec<&str> = outline.sections.iter().map(|s| s.anchor.as_str()).collect();
+    assert_eq!(anchors, ["notes", "notes-1", "mine"]);
+}
+
+#[test]
+fn test_to_outline_json() {
+    let mut parser = Parser::new("# A\n\n## B\n\nText".to_string()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&parser.to_outline_json().unwrap()).unwrap();
+
+    assert_eq!(json["sections"][0]["title"], "A");
+    assert_eq!(json["sections"][0]["children"][0]["title"], "B");
+    assert_eq!(
+        json["sections"][0]["children"][0]["body"][0]["type"],
+        "paragraph"
+    );
+}
@@ -0,0 +1,68 @@
+use md_parser::{OutputStyle, Parser, Renderer, RendererConfig};
+
+const INPUT: &str = "# Title\n\nSome *text*   with  spaces and `a  b`.\n\n\
+                     - one\n- two\n\n```rust\nfn main() {\n    let x = 1;\n}\n```\n\n\
+                     ```mermaid\ngraph TD\n    A-->B\n```\n";
+
+fn renderer(output_style: OutputStyle) -> Renderer {
+    Renderer::new(RendererConfig {
+        output_style,
+        ..RendererConfig::default()
+    })
+    .unwrap()
+}
+
+fn fragment(output_style: OutputStyle) -> String {
+    let ast = Parser::new(INPUT.to_string()).unwrap().parse().unwrap();
+    renderer(output_style).render_fragment(&ast)
+}
+
+#[test]
+fn test_minified_output_drops_whitespace_between_blocks() {
+    let html = fragment(OutputStyle::Minified);
+    assert!(
+        html.starts_with("<h1>Title</h1><p>Some <em>text</em> with spaces and <code>a  b</code>.</p><ul><li>one</li><li>two</li></ul>"),
+        "{}",
+        html
+    );
+    // Preformatted code and diagram sources are untouched
+    assert!(html.contains("fn main() {\n    let x = 1;\n}</code></pre>"));
+    assert!(html.contains("graph TD\n    A--&gt;B</div>"));
+    assert!(!html.ends_with('\n'));
+}
+
+#[test]
+fn test_pretty_output_indents_nested_blocks() {
+    let html = fragment(OutputStyle::Pretty { indent: 4 });
+    assert!(
+        html.starts_with(
+            "<h1>Title</h1>\n<p>Some <em>text</em> with spaces and <code>a  b</code>.</p>\n<ul>\n    <li>one</li>\n    <li>two</li>\n</ul>\n"
+        ),
+        "{}",
+        html
+    );
+    assert!(html.contains("fn main() {\n    let x = 1;\n}</code></pre>\n"));
+}
+
+#[test]
+fn test_pretty_full_document_and_default() {
+    let ast = Parser::new("Hello".to_string()).unwrap().parse().unwrap();
+    let html = renderer(OutputStyle::Pretty { indent: 2 }).render_html(&ast);
+    assert!(
+        html.starts_with(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n  <head>\n    <meta charset=\"UTF-8\">\n"
+        ),
+        "{}",
+        html
+    );
+    assert!(html.contains("\n    <title>Markdown Parser Output</title>\n"));
+    assert!(html.contains("\n  </head>\n  <body>\n    <p>Hello</p>\n    <script>\n"));
+    assert!(html.ends_with("    </script>\n  </body>\n</html>\n"));
+
+    // The default leaves the output as rendered
+    assert_eq!(fragment(OutputStyle::default()), {
+        let ast = Parser::new(INPUT.to_string()).unwrap().parse().unwrap();
+        renderer(OutputStyle::Compact).render_fragment(&ast)
+    });
+    assert!(fragment(OutputStyle::Compact).contains("</h1>\n<p>"));
+}
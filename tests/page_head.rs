@@ -0,0 +1,96 @@
+use md_parser::{Parser, ParserConfig, Renderer, RendererConfig};
+use std::collections::BTreeMap;
+
+fn page(input: &str, config: RendererConfig) -> String {
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+    Renderer::new(config).unwrap().render_html(&ast)
+}
+
+#[test]
+fn test_title_and_description_come_from_the_document() {
+    let html = page(
+        "Intro line.\n\n# Release *Notes* & more\n\nFirst   paragraph\nwith \"quotes\".\n",
+        RendererConfig::default(),
+    );
+    assert!(
+        html.contains("<title>Release Notes &amp; more</title>"),
+        "{}",
+        html
+    );
+    assert!(!html.contains("Markdown Parser Output"));
+    assert!(html.contains("<meta name=\"description\" content=\"Intro line.\">"));
+    assert!(!html.contains("og:title"));
+
+    // Without a level-1 heading the template's title stays
+    let html = page("## Only a subheading\n", RendererConfig::default());
+    assert!(html.contains("<title>Markdown Parser Output</title>"));
+    assert!(!html.contains("name=\"description\""));
+}
+
+#[test]
+fn test_configured_meta_tags_override_the_document() {
+    let long = "word ".repeat(60);
+    let html = page(
+        &format!("# Derived\n\n{}\n", long),
+        RendererConfig {
+            title: Some("Handbook".to_string()),
+            author: Some("Docs Team".to_string()),
+            open_graph: true,
+            meta: BTreeMap::from([("robots".to_string(), "noindex".to_string())]),
+            ..RendererConfig::default()
+        },
+    );
+    assert!(html.contains(
+        "    <title>Handbook</title>\n    <meta name=\"description\" content=\"word word"
+    ));
+    let description = html
+        .split("<meta name=\"description\" content=\"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap();
+    assert!(description.ends_with("word…"));
+    assert!(description.chars().count() <= 160);
+    assert!(html.contains("<meta name=\"author\" content=\"Docs Team\">"));
+    assert!(html.contains("<meta name=\"robots\" content=\"noindex\">"));
+    assert!(html.contains("<meta property=\"og:title\" content=\"Handbook\">"));
+    assert!(html.contains(&format!(
+        "<meta property=\"og:description\" content=\"{}\">",
+        description
+    )));
+    assert!(html.contains("<meta property=\"og:type\" content=\"article\">"));
+
+    let html = page(
+        "# Derived\n\nBody.\n",
+        RendererConfig {
+            description: Some("Set by hand".to_string()),
+            ..RendererConfig::default()
+        },
+    );
+    assert!(html.contains("<meta name=\"description\" content=\"Set by hand\">"));
+}
+
+#[test]
+fn test_head_follows_render_transforms() {
+    let input =
+        "# Doc\n\n## Internals {audience=staff}\n\nStaff only text.\n\n## Public\n\nOpen text.\n";
+    let ast = Parser::with_config(
+        input.to_string(),
+        ParserConfig {
+            enable_attributes: true,
+            ..ParserConfig::default()
+        },
+    )
+    .unwrap()
+    .parse()
+    .unwrap();
+    let html = Renderer::new(RendererConfig {
+        audience: Some("public".to_string()),
+        heading_offset: 1,
+        ..RendererConfig::default()
+    })
+    .unwrap()
+    .render_html(&ast);
+    assert!(html.contains("<title>Doc</title>"), "{}", html);
+    assert!(html.contains("<meta name=\"description\" content=\"Open text.\">"));
+    assert!(!html.contains("Staff only"));
+}
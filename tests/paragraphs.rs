@@ -1,4 +1,4 @@
-use md_parser::{Inline, Node, Parser};
+use md_parser::{plain_text, Inline, Node, Parser, ParserConfig};
 
 #[test]
 fn test_simple_paragraph() {
@@ -13,7 +13,7 @@ fn test_simple_paragraph() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "This is a simple paragraph.".to_string()
+                    content: "This is a simple paragraph.".into()
                 }
             );
         }
@@ -34,7 +34,7 @@ fn test_multiple_paragraphs() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "First paragraph.".to_string()
+                    content: "First paragraph.".into()
                 }
             );
         }
@@ -46,7 +46,7 @@ fn test_multiple_paragraphs() {
             assert_eq!(
                 inlines[0],
                 Inline::Text {
-                    content: "Second paragraph.".to_string()
+                    content: "Second paragraph.".into()
                 }
             );
         }
@@ -71,3 +71,90 @@ fn test_whitespace_only() {
 
     assert_eq!(result.len(), 0);
 }
+
+/// Block kinds and text of each top-level node
+fn blocks(input: &str, legacy_list_interrupts: bool) -> Vec<(&'static str, String)> {
+    let config = ParserConfig {
+        legacy_list_interrupts,
+        ..ParserConfig::default()
+    };
+    let result = Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    result
+        .iter()
+        .map(|node| match node {
+            Node::Paragraph { content } => ("paragraph", plain_text(content)),
+            Node::UnorderedList { items } | Node::OrderedList { items } => (
+                "list",
+                items
+                    .iter()
+                    .map(|item| plain_text(&item.content))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            ),
+            other => panic!("unexpected node {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn test_list_interrupting_paragraph() {
+    assert_eq!(
+        blocks("Shopping:\n- milk\n- eggs", false),
+        vec![
+            ("paragraph", "Shopping:".to_string()),
+            ("list", "milk | eggs".to_string())
+        ]
+    );
+    assert_eq!(
+        blocks("Steps:\n1. mix\n2. bake", false),
+        vec![
+            ("paragraph", "Steps:".to_string()),
+            ("list", "mix | bake".to_string())
+        ]
+    );
+    // An ordered item not numbered 1 or an empty item continues the paragraph
+    assert_eq!(
+        blocks("The year was\n1986. A great year", false),
+        vec![("paragraph", "The year was 1986. A great year".to_string())]
+    );
+    assert_eq!(
+        blocks("Star\n* ", false),
+        vec![("paragraph", "Star *".to_string())]
+    );
+}
+
+#[test]
+fn test_text_after_list_item_is_lazy_continuation() {
+    assert_eq!(
+        blocks("- first item\nwrapped here\n- second", false),
+        vec![("list", "first item wrapped here | second".to_string())]
+    );
+    assert_eq!(
+        blocks("- item\n\nNew paragraph", false),
+        vec![
+            ("list", "item".to_string()),
+            ("paragraph", "New paragraph".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_legacy_list_interrupts() {
+    assert_eq!(
+        blocks("The year was\n1986. A great year", true),
+        vec![
+            ("paragraph", "The year was".to_string()),
+            ("list", "A great year".to_string())
+        ]
+    );
+    assert_eq!(
+        blocks("- first item\nafter", true),
+        vec![
+            ("list", "first item".to_string()),
+            ("paragraph", "after".to_string())
+        ]
+    );
+}
@@ -0,0 +1,63 @@
+use md_parser::{LineRange, Node, Parser, ParserConfig, WarningCode};
+
+fn range(start: usize, end: usize) -> LineRange {
+    LineRange { start, end }
+}
+
+#[test]
+fn test_parse_full_collects_everything() {
+    let input = "---\ntitle: Guide\ntags: [a, b]\n---\n\n# Intro\n\nSome **bold\ntext.\n\n```rust\nfn main() {}\n```\n\n- one\n- two\n";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let result = parser.parse_full().unwrap();
+
+    assert_eq!(
+        result.frontmatter.as_deref(),
+        Some("title: Guide\ntags: [a, b]")
+    );
+    assert_eq!(result.nodes.len(), 4);
+    assert_eq!(
+        result.spans,
+        vec![range(6, 6), range(8, 9), range(11, 13), range(15, 16)]
+    );
+    assert_eq!(result.warnings, parser.warnings());
+    assert_eq!(result.warnings[0].code, WarningCode::UnclosedEmphasis);
+
+    assert_eq!(result.stats.lines, 16);
+    assert_eq!(result.stats.bytes, input.len());
+    // Heading, paragraph, code block, list
+    assert_eq!(result.stats.blocks, 4);
+    assert!(result.stats.inlines >= 4, "{:?}", result.stats);
+
+    assert_eq!(result.nodes, parser.parse().unwrap());
+}
+
+#[test]
+fn test_spans_of_sections_cover_their_children() {
+    let config = ParserConfig {
+        enable_sections: true,
+        ..ParserConfig::default()
+    };
+    let input = "Preamble\n\n# One\n\nText\n\n## Two\n\n- item\n\n# Three\n";
+    let result = Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse_full()
+        .unwrap();
+
+    assert!(matches!(result.nodes[1], Node::Section { level: 1, .. }));
+    assert_eq!(result.spans, vec![range(1, 1), range(3, 9), range(11, 11)]);
+}
+
+#[test]
+fn test_parse_result_without_frontmatter_serializes() {
+    let result = Parser::new("Text\n\n---\n\nMore".to_string())
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    assert_eq!(result.frontmatter, None);
+    assert_eq!(result.spans, vec![range(1, 1), range(3, 3), range(5, 5)]);
+
+    let json = result.to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["stats"]["lines"], 5);
+    assert_eq!(value["spans"][2]["start"], 5);
+}
@@ -0,0 +1,39 @@
+use md_parser::{Node, Parser, ParserConfig, WarningCode};
+
+#[test]
+fn test_parse_str_reuses_parser() {
+    let mut parser = Parser::from_config(ParserConfig::default()).unwrap();
+
+    let first = parser.parse_str("# One\n\nSome **bold").unwrap();
+    assert_eq!(first.len(), 2);
+    assert_eq!(parser.warnings().len(), 1);
+    assert_eq!(parser.warnings()[0].code, WarningCode::UnclosedEmphasis);
+
+    let second = parser.parse_str("- a\n- b").unwrap();
+    assert!(matches!(&second[0], Node::UnorderedList { items } if items.len() == 2));
+    assert!(parser.warnings().is_empty());
+
+    assert_eq!(
+        parser.parse_str("# One\n\nSome **bold").unwrap(),
+        Parser::new("# One\n\nSome **bold".to_string())
+            .unwrap()
+            .parse()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_parse_str_keeps_own_input() {
+    let mut parser = Parser::new("# Stored".to_string()).unwrap();
+    parser.parse_str("Other text").unwrap();
+
+    match &parser.parse().unwrap()[0] {
+        Node::Heading { level, .. } => assert_eq!(*level, 1),
+        other => panic!("Expected Heading, got {:?}", other),
+    }
+    assert!(Parser::from_config(ParserConfig::default())
+        .unwrap()
+        .parse()
+        .unwrap()
+        .is_empty());
+}
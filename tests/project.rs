@@ -0,0 +1,214 @@
+mod common;
+
+use md_parser::{
+    slugify, BrokenLinkReason, DiagnosticSource, Diagnostics, Parser, ParserConfig, Project,
+    ProjectDocument, SlugStrategy,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Create a fresh project directory under the system temp dir
+fn project_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = common::temp_path(&format!("project_{}", name));
+    let _ = fs::remove_dir_all(&root);
+    for (path, content) in files {
+        let full = root.join(path);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::write(full, content).unwrap();
+    }
+    root
+}
+
+#[test]
+fn test_slugify() {
+    assert_eq!(slugify("Getting Started!"), "getting-started");
+    assert_eq!(slugify("API v2 `config`"), "api-v2-config");
+}
+
+#[test]
+fn test_project_loads_documents_and_anchors() {
+    let root = project_dir(
+        "anchors",
+        &[
+            ("index.md", "# Welcome\n\n## Install\n\n## Install"),
+            ("guide/setup.md", "# Setup Guide"),
+        ],
+    );
+    let project = Project::load(&root, &ParserConfig::default()).unwrap();
+
+    assert_eq!(project.documents().len(), 2);
+    let index = project.anchor_index();
+    assert_eq!(
+        index.get(Path::new("index.md")).unwrap(),
+        &vec![
+            "welcome".to_string(),
+            "install".to_string(),
+            "install-1".to_string()
+        ]
+    );
+    assert!(project.document("guide/setup.md").is_some());
+}
+
+#[test]
+fn test_generated_anchors_avoid_explicit_ids() {
+    let root = project_dir(
+        "explicit_ids",
+        &[(
+            "index.md",
+            "## Setup {#install}\n\n## Steps {#install-1}\n\n## Install\n\n## Install",
+        )],
+    );
+    let config = ParserConfig {
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    let project = Project::load(&root, &config).unwrap();
+
+    assert_eq!(
+        project.anchor_index().get(Path::new("index.md")).unwrap(),
+        &["install", "install-1", "install-2", "install-3"]
+    );
+}
+
+#[test]
+fn test_project_resolves_relative_links() {
+    let root = project_dir("resolve", &[("a.md", "# A")]);
+    let project = Project::load(&root, &ParserConfig::default()).unwrap();
+
+    let (path, anchor) = project
+        .resolve_link(Path::new("guide/setup.md"), "../reference/api.md#errors")
+        .unwrap();
+    assert_eq!(path, PathBuf::from("reference/api.md"));
+    assert_eq!(anchor.as_deref(), Some("errors"));
+    assert!(project
+        .resolve_link(Path::new("a.md"), "https://example.com")
+        .is_none());
+}
+
+#[test]
+fn test_project_reports_broken_cross_references() {
+    let root = project_dir(
+        "broken",
+        &[
+            (
+                "index.md",
+                "# Home\n\n[ok](guide/setup.md#setup-guide) [bad anchor](guide/setup.md#nope) [gone](missing.md) [self](#home) [img](logo.png)",
+            ),
+            ("guide/setup.md", "# Setup Guide\n\n[back](../index.md#home)"),
+        ],
+    );
+    let project = Project::load(&root, &ParserConfig::default()).unwrap();
+    let broken = project.broken_links();
+
+    let summary: Vec<(&str, BrokenLinkReason)> = broken
+        .iter()
+        .map(|b| (b.url.as_str(), b.reason.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("guide/setup.md#nope", BrokenLinkReason::MissingAnchor),
+            ("missing.md", BrokenLinkReason::MissingDocument),
+            ("logo.png", BrokenLinkReason::MissingFile),
+        ]
+    );
+}
+
+#[test]
+fn test_linked_documents_check_one_document() {
+    let root = project_dir(
+        "linked",
+        &[
+            ("guide/setup.md", "# Setup Guide"),
+            ("other.md", "```\nunclosed"),
+        ],
+    );
+    let source = "# Home\n\n[ok](guide/setup.md#setup-guide) [bad](guide/setup.md#nope) \
+                  [self](#home) [missing](#gone) [file](Cargo.toml)";
+    let nodes = Parser::new(source.to_string()).unwrap().parse().unwrap();
+    let document = ProjectDocument::new(PathBuf::from("index.md"), nodes, Vec::new());
+    let project =
+        Project::with_linked_documents(&root, document, &ParserConfig::default()).unwrap();
+
+    // Only linked documents are read, so `other.md` does not fail the load
+    let paths: Vec<&Path> = project
+        .documents()
+        .iter()
+        .map(|doc| doc.path.as_path())
+        .collect();
+    assert_eq!(paths, [Path::new("guide/setup.md"), Path::new("index.md")]);
+    let summary: Vec<(String, BrokenLinkReason)> = project
+        .broken_links()
+        .into_iter()
+        .map(|link| (link.url, link.reason))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (
+                "guide/setup.md#nope".to_string(),
+                BrokenLinkReason::MissingAnchor
+            ),
+            ("#gone".to_string(), BrokenLinkReason::MissingAnchor),
+            ("Cargo.toml".to_string(), BrokenLinkReason::MissingFile),
+        ]
+    );
+}
+
+#[test]
+fn test_project_parse_error_names_file() {
+    let root = project_dir("error", &[("bad.md", "```\nunclosed")]);
+    let err = Project::load(&root, &ParserConfig::default()).unwrap_err();
+    assert!(err.to_string().contains("bad.md"));
+}
+
+#[test]
+fn test_anchor_collisions_across_linked_documents() {
+    let root = project_dir(
+        "collisions",
+        &[
+            (
+                "index.md",
+                "# Home\n\n## Install\n\n[setup](guide/setup.md) [api](guide-setup.md#install)",
+            ),
+            (
+                "guide/setup.md",
+                "# Setup\n\n## Install\n\n[home](../index.md)",
+            ),
+            ("guide-setup.md", "# Install"),
+            ("unlinked.md", "# Install"),
+        ],
+    );
+    let project = Project::load(&root, &ParserConfig::default()).unwrap();
+
+    // Documents nothing links to are not checked
+    let global = project.anchor_collisions(SlugStrategy::Global);
+    assert_eq!(global.len(), 1);
+    assert_eq!(global[0].anchor, "install");
+    let paths: Vec<_> = global[0]
+        .documents
+        .iter()
+        .map(|(path, _)| path.clone())
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            PathBuf::from("guide/setup.md"),
+            PathBuf::from("guide-setup.md"),
+            PathBuf::from("index.md")
+        ]
+    );
+
+    // Prefixes keep `index.md` apart, but two paths can share one
+    let prefixed = project.anchor_collisions(SlugStrategy::PerFilePrefix);
+    let anchors: Vec<_> = prefixed.iter().map(|c| c.anchor.as_str()).collect();
+    assert_eq!(anchors, ["guide-setup--install"]);
+
+    let mut diagnostics = Diagnostics::new();
+    diagnostics.add_anchor_collisions(&prefixed);
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = diagnostics.iter().next().unwrap();
+    assert_eq!(diagnostic.rule, "anchor-collision");
+    assert_eq!(diagnostic.source, DiagnosticSource::Anchors);
+    assert_eq!(Path::new(&diagnostic.file), Path::new("guide-setup.md"));
+}
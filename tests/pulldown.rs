@@ -0,0 +1,128 @@
+#![cfg(feature = "pulldown")]
+
+mod common;
+
+use common::parse;
+use md_parser::pulldown_cmark::{html, Event, Options, Parser as CmarkParser, Tag};
+use md_parser::{
+    from_pulldown_events, to_pulldown_events, AdmonitionKind, Alignment, Inline, Node, Parser,
+    ParserConfig,
+};
+
+fn cmark(input: &str) -> Vec<Node> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_GFM;
+    from_pulldown_events(CmarkParser::new_ext(input, options))
+}
+
+#[test]
+fn test_round_trip_through_events() {
+    let input = "# Title\n\nSome **bold**, *italic*, ~~gone~~, `code` and [a link](https://example.com).\n\n- [x] done\n- todo\n  - nested\n\n1. first\n2. second\n\n```rust\nfn main() {}\n```\n\n| Name | Size |\n|:-----|-----:|\n| a    | 1    |\n\n> quoted\n\n---\n\n> [!WARNING]\n> Careful\n";
+    let mut nodes = parse(input);
+    // Source lines are not part of the event stream
+    for node in &mut nodes {
+        if let Node::Table { span, .. } | Node::HorizontalRule { span } = node {
+            *span = None;
+        }
+    }
+    assert_eq!(from_pulldown_events(to_pulldown_events(&nodes)), nodes);
+}
+
+#[test]
+fn test_events_render_with_pulldown_html() {
+    let config = ParserConfig {
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    let input = "## Intro {#start}\n\nText with ![logo](logo.png)\n\n```mermaid\ngraph TD\n  A --> B\n```\n";
+    let nodes = Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    let mut out = String::new();
+    html::push_html(&mut out, to_pulldown_events(&nodes));
+
+    assert!(out.contains("<h2 id=\"start\">Intro</h2>"), "{}", out);
+    assert!(
+        out.contains("<img src=\"logo.png\" alt=\"logo\" />"),
+        "{}",
+        out
+    );
+    assert!(
+        out.contains("<pre><code class=\"language-mermaid\">graph TD\n  A --&gt; B\n</code></pre>"),
+        "{}",
+        out
+    );
+}
+
+#[test]
+fn test_diagram_references_resolved_in_events() {
+    let nodes = parse("```mermaid name=flow\ngraph LR\n  A --> B\n```\n\n{{diagram:flow}}\n\n{{diagram:missing}}\n");
+    let events: Vec<Event> = to_pulldown_events(&nodes).collect();
+    let mermaid_blocks = events
+        .iter()
+        .filter(|event| matches!(event, Event::Start(Tag::CodeBlock(_))))
+        .count();
+    assert_eq!(mermaid_blocks, 2);
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, Event::Text(text) if &**text == "{{diagram:missing}}")));
+}
+
+#[test]
+fn test_commonmark_from_pulldown_parser() {
+    let nodes = cmark("Line one\nline two  \nafter break\n\n> [!TIP]\n> Use it\n\n| a | b |\n|:-:|---|\n| 1 | 2 |\n\n<!-- note -->\n\n```mermaid name=arch\ngraph TD\n```\n");
+
+    assert_eq!(
+        nodes[0],
+        Node::Paragraph {
+            content: vec![
+                Inline::Text {
+                    content: "Line one line two".into()
+                },
+                Inline::LineBreak,
+                Inline::Text {
+                    content: "after break".into()
+                },
+            ]
+        }
+    );
+    assert!(matches!(
+        nodes[1],
+        Node::Admonition {
+            kind: AdmonitionKind::Tip,
+            ..
+        }
+    ));
+    assert!(matches!(
+        &nodes[2],
+        Node::Table { alignments, .. } if alignments == &[Some(Alignment::Center), None]
+    ));
+    assert_eq!(
+        nodes[3],
+        Node::Comment {
+            content: "note".to_string()
+        }
+    );
+    assert!(matches!(
+        &nodes[4],
+        Node::MermaidDiagram { name: Some(name), diagram, .. } if name == "arch" && diagram == "graph TD"
+    ));
+}
+
+#[test]
+fn test_loose_and_nested_lists_from_pulldown() {
+    let nodes = cmark("- one\n\n  more\n- two\n  1. inner\n");
+    let Node::UnorderedList { items } = &nodes[0] else {
+        panic!("{:?}", nodes);
+    };
+    assert_eq!(
+        items[0].content,
+        vec![Inline::Text {
+            content: "one more".into()
+        }]
+    );
+    assert_eq!(items[1].children.len(), 1);
+}
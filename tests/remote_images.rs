@@ -0,0 +1,94 @@
+use md_parser::{Parser, Renderer, RendererConfig};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\nnot really";
+
+/// Serve `PNG` for every request on a local port; returns the base URL and request counter
+fn serve() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&requests);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            counter.fetch_add(1, Ordering::SeqCst);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                PNG.len()
+            )
+            .unwrap();
+            stream.write_all(PNG).unwrap();
+        }
+    });
+    (base, requests)
+}
+
+fn render(input: &str, output_directory: &Path) -> String {
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+    Renderer::new(RendererConfig {
+        output_directory: output_directory.to_string_lossy().into_owned(),
+        localize_images: true,
+        image_download_timeout_secs: 5,
+        ..RendererConfig::default()
+    })
+    .unwrap()
+    .render_fragment(&ast)
+}
+
+#[test]
+fn test_remote_images_are_downloaded_once_and_relinked() {
+    let (base, requests) = serve();
+    let out = std::env::temp_dir().join(format!("md_parser_remote_images_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&out);
+    let input = format!(
+        "![Logo]({base}/logo.png) and ![local](local.png)\n\n- **![badge]({base}/badge.svg?x=1)**\n"
+    );
+
+    let html = render(&input, &out);
+    assert!(!html.contains(&base), "{}", html);
+    assert!(html.contains("src=\"local.png\""));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    let files: Vec<_> = fs::read_dir(out.join("images"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(files.len(), 2);
+    let logo = files.iter().find(|name| name.ends_with(".png")).unwrap();
+    assert!(html.contains(&format!("src=\"images/{}\"", logo)));
+    assert_eq!(fs::read(out.join("images").join(logo)).unwrap(), PNG);
+
+    // A second build reuses the downloaded files
+    assert_eq!(render(&input, &out), html);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    fs::remove_dir_all(&out).unwrap();
+}
+
+#[test]
+fn test_unreachable_images_keep_their_url() {
+    // Nothing listens on a port that was just released
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let out = std::env::temp_dir().join(format!("md_parser_remote_missing_{}", std::process::id()));
+    let url = format!("http://127.0.0.1:{}/gone.png", port);
+
+    let html = render(&format!("![Gone]({})", url), &out);
+    assert!(html.contains(&format!("src=\"{}\"", url)), "{}", html);
+    assert!(!out.join("images").exists());
+}
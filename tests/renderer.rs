@@ -0,0 +1,61 @@
+mod common;
+
+use std::fs;
+use std::sync::Arc;
+use std::thread;
+
+use common::parse;
+use md_parser::{Parser, Renderer, RendererConfig};
+
+#[test]
+fn test_renderer_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Renderer>();
+}
+
+#[test]
+fn test_renderer_matches_parser_output() {
+    let input = "# Title\n\n- [x] done\n\n> quote";
+    let config = RendererConfig {
+        heading_offset: 1,
+        ..RendererConfig::default()
+    };
+    let renderer = Renderer::new(config.clone()).unwrap();
+
+    let expected = Parser::new(input.to_string())
+        .unwrap()
+        .to_html_with_config(&config)
+        .unwrap();
+    assert_eq!(renderer.render_html(&parse(input)), expected);
+    assert!(renderer.render_fragment(&parse(input)).starts_with("<h2"));
+}
+
+#[test]
+fn test_renderer_reads_templates_once() {
+    let dir = common::temp_path("renderer");
+    fs::create_dir_all(&dir).unwrap();
+    let footer = dir.join("footer.html");
+    fs::write(&footer, "<footer>v1</footer>").unwrap();
+
+    let renderer = Arc::new(
+        Renderer::new(RendererConfig {
+            html_footer_path: footer.to_string_lossy().into_owned(),
+            ..RendererConfig::default()
+        })
+        .unwrap(),
+    );
+    fs::write(&footer, "<footer>v2</footer>").unwrap();
+
+    let handles: Vec<_> = (0..3)
+        .map(|n| {
+            let renderer = Arc::clone(&renderer);
+            thread::spawn(move || renderer.render_html(&parse(&format!("Page {}", n))))
+        })
+        .collect();
+    for (n, handle) in handles.into_iter().enumerate() {
+        let html = handle.join().unwrap();
+        assert!(html.contains(&format!("<p>Page {}</p>", n)));
+        assert!(html.ends_with("<footer>v1</footer>"));
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,47 @@
+use md_parser::{Parser, Renderer, RendererConfig, RendererStrings};
+
+const INPUT: &str = "> [!WARNING]\n> Hot surface.\n\n![Oven](oven.png)\nFigure: The oven\n";
+
+#[test]
+fn test_inserted_strings_come_from_the_config() {
+    let ast = Parser::new(INPUT.to_string()).unwrap().parse().unwrap();
+    let html = Renderer::new(RendererConfig {
+        number_figures: true,
+        accessible: true,
+        strings: RendererStrings {
+            warning: "Warnung".to_string(),
+            figure: "Abbildung {number} –".to_string(),
+            skip_link: "Zum Inhalt <springen>".to_string(),
+            ..RendererStrings::default()
+        },
+        ..RendererConfig::default()
+    })
+    .unwrap()
+    .render_html(&ast);
+
+    assert!(
+        html.contains("<p class=\"admonition-title\">Warnung</p>"),
+        "{}",
+        html
+    );
+    assert!(html.contains("<span class=\"figure-number\">Abbildung 1 –</span> The oven"));
+    assert!(html.contains("href=\"#main-content\">Zum Inhalt &lt;springen&gt;</a>"));
+}
+
+#[test]
+fn test_strings_default_to_english_and_load_from_toml() {
+    let strings = RendererStrings::default();
+    assert_eq!(strings.figure_label(2), "Figure 2:");
+    assert_eq!(strings.skip_link, "Skip to main content");
+
+    let strings: RendererStrings = toml::from_str(
+        r#"
+        note = "Remarque"
+        figure = "Figure {number} :"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(strings.note, "Remarque");
+    assert_eq!(strings.tip, "Tip");
+    assert_eq!(strings.figure_label(4), "Figure 4 :");
+}
@@ -0,0 +1,145 @@
+//! Arbitrary input must never make the parser or the renderers panic.
+//!
+//! The `fuzz/` targets search for new crashes; inputs they find belong in
+//! `test_known_crash_inputs`.
+
+use md_parser::{to_markdown, Parser, ParserConfig, Renderer, RendererConfig};
+
+fn all_syntax() -> ParserConfig {
+    ParserConfig {
+        enable_attributes: true,
+        enable_sections: true,
+        enable_directives: true,
+        gfm_emphasis: true,
+        enable_multiline_tables: true,
+        enable_variables: true,
+        error_recovery: true,
+        ..ParserConfig::default()
+    }
+}
+
+fn parse_and_render(input: &str, config: ParserConfig) {
+    let renderer = Renderer::new(RendererConfig::default()).unwrap();
+    let mut parser = Parser::with_config(input.to_string(), config).unwrap();
+    if let Ok(ast) = parser.parse() {
+        renderer.render_html(&ast);
+        to_markdown(&ast);
+    }
+    let _ = parser.to_asciidoc();
+    let _ = parser.to_rst();
+}
+
+#[test]
+fn test_known_crash_inputs() {
+    let inputs = [
+        // Unclosed-emphasis check sliced text inside a multi-byte character
+        "`é---\n",
+        "**é*",
+        "~~🦀~",
+        "# *日本*\n\n- [ ]🦀\n- [x]é",
+        "1.é\n1. ー\n\u{feff}> [!NOTE]",
+        "| é | 🦀 |\n|---|:-:|\n| ー |",
+        "```mermaid\n%%{init: {'themeVariables': é}}%%\ngraph TD\n    🦀-->é\n```",
+        ":é[x]{#🦀} :x[é]{é=🦀}",
+        "{{é}} {{ 🦀 }} \\{{x}}",
+        "<!-- é",
+        // Multi-byte characters right after block and inline markers
+        "#🦀",
+        "#🦀 #",
+        "e\u{301}* _\u{301}_ ~~\u{5e9}~~",
+    ];
+    for input in inputs {
+        parse_and_render(input, all_syntax());
+        parse_and_render(input, ParserConfig::default());
+    }
+
+    let heading = format!("{} Title", "#".repeat(300));
+    assert!(Parser::new(heading).unwrap().parse().is_err());
+}
+
+#[test]
+fn test_custom_fence_length_longer_than_pattern() {
+    let config = ParserConfig {
+        code_fence_length: 10,
+        ..ParserConfig::default()
+    };
+    parse_and_render("```rust\nfn main() {}\n```", config);
+}
+
+#[test]
+fn test_generated_inputs_do_not_panic() {
+    const TOKENS: &[&str] = &[
+        "#",
+        "###",
+        "\n",
+        "\n\n",
+        " ",
+        "    ",
+        "*",
+        "**",
+        "~~",
+        "~",
+        "_",
+        "__",
+        "`",
+        "```",
+        "```mermaid\n",
+        "[",
+        "](",
+        ")",
+        "![",
+        "<!--",
+        "-->",
+        ">",
+        "> [!NOTE]",
+        ":::",
+        ":::details ",
+        "::: {.a}",
+        ":x[",
+        "{#id .c}",
+        "|",
+        "| a | b |\n|---|---|\n",
+        "- ",
+        "- [ ] ",
+        "1. ",
+        "---",
+        "é",
+        "🦀",
+        "ー",
+        "שלום",
+        "مرحبا",
+        "\u{301}",
+        "👩\u{200d}💻",
+        "🇯🇵",
+        "\u{200b}",
+        "\r\n",
+        "\\",
+        "<br>",
+        "{{x}}",
+        "{{diagram:d}}",
+        "%%{init: {'theme':'dark'}}%%\n",
+        "graph TD\n",
+        "A-->B\n",
+        "word",
+    ];
+
+    // xorshift, so failures reproduce
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for round in 0..2_000 {
+        let input: String = (0..next() % 30)
+            .map(|_| TOKENS[(next() % TOKENS.len() as u64) as usize])
+            .collect();
+        let config = if round % 2 == 0 {
+            all_syntax()
+        } else {
+            ParserConfig::default()
+        };
+        parse_and_render(&input, config);
+    }
+}
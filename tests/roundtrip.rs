@@ -0,0 +1,169 @@
+//! Property tests: generated ASTs survive a Markdown round trip, and arbitrary inline
+//! text parses to a stable result.
+
+use md_parser::{to_markdown, Attributes, Inline, ListItem, Node, Parser, ParserConfig};
+use proptest::prelude::*;
+
+/// Parse `markdown`, dropping the source lines that generated nodes do not have
+fn parse(markdown: &str) -> Vec<Node> {
+    let mut nodes = Parser::new(markdown.to_string()).unwrap().parse().unwrap();
+    for node in &mut nodes {
+        if let Node::HorizontalRule { span } = node {
+            *span = None;
+        }
+    }
+    nodes
+}
+
+fn words() -> impl Strategy<Value = String> {
+    prop::collection::vec("[a-z]{1,8}", 1..4).prop_map(|words| words.join(" "))
+}
+
+/// Inlines that start and end with a word, so emphasis around them can open and close
+fn inlines(depth: u32) -> BoxedStrategy<Vec<Inline>> {
+    let atom = if depth == 0 {
+        "[a-z]{1,8}"
+            .prop_map(|content| Inline::Code {
+                content: content.into(),
+            })
+            .boxed()
+    } else {
+        prop_oneof![
+            "[a-z]{1,8}".prop_map(|content| Inline::Code {
+                content: content.into()
+            }),
+            inlines(depth - 1).prop_map(|content| Inline::Bold { content }),
+            inlines(depth - 1).prop_map(|content| Inline::Italic { content }),
+            inlines(depth - 1).prop_map(|content| Inline::Strikethrough { content }),
+            (inlines(0), "[a-z]{1,8}\\.html").prop_map(|(text, url)| Inline::Link { text, url }),
+        ]
+        .boxed()
+    };
+
+    (words(), prop::collection::vec((atom, words()), 0..3))
+        .prop_map(|(first, rest)| {
+            let count = rest.len();
+            let mut inlines = Vec::new();
+            let mut text = first;
+            for (idx, (atom, words)) in rest.into_iter().enumerate() {
+                text.push(' ');
+                inlines.push(Inline::Text {
+                    content: text.into(),
+                });
+                inlines.push(atom);
+                text = format!(" {}", words);
+                if idx + 1 == count {
+                    break;
+                }
+            }
+            inlines.push(Inline::Text {
+                content: text.into(),
+            });
+            inlines
+        })
+        .boxed()
+}
+
+fn list_items(depth: u32, tasks: bool) -> BoxedStrategy<Vec<ListItem>> {
+    let checked = if tasks {
+        prop::option::of(any::<bool>()).boxed()
+    } else {
+        Just(None).boxed()
+    };
+    let children = if depth == 0 {
+        Just(Vec::new()).boxed()
+    } else {
+        prop_oneof![Just(Vec::new()), list_items(depth - 1, tasks)].boxed()
+    };
+    prop::collection::vec(
+        (inlines(1), children, code_blocks(), checked).prop_map(
+            |(content, children, blocks, checked)| ListItem {
+                content,
+                children,
+                blocks,
+                checked,
+            },
+        ),
+        1..4,
+    )
+    .boxed()
+}
+
+fn code_block() -> impl Strategy<Value = Node> {
+    (
+        prop::option::of("[a-z]{1,5}"),
+        prop::collection::vec(words(), 1..4),
+    )
+        .prop_map(|(lang, lines)| Node::CodeBlock {
+            lang,
+            code: lines.join("\n"),
+            attributes: Attributes::default(),
+        })
+}
+
+/// Fenced code blocks nested in a list item or blockquote
+fn code_blocks() -> impl Strategy<Value = Vec<Node>> {
+    prop::collection::vec(code_block(), 0..2)
+}
+
+fn block() -> impl Strategy<Value = Node> {
+    prop_oneof![
+        (1..=6u8, inlines(2)).prop_map(|(level, content)| Node::Heading {
+            level,
+            content,
+            attributes: Attributes::default(),
+        }),
+        inlines(2).prop_map(|content| Node::Paragraph { content }),
+        list_items(2, true).prop_map(|items| Node::UnorderedList { items }),
+        list_items(2, false).prop_map(|items| Node::OrderedList { items }),
+        code_block(),
+        (1..=3u8, inlines(1), code_blocks()).prop_map(|(level, content, blocks)| {
+            Node::Blockquote {
+                level,
+                content,
+                blocks,
+            }
+        }),
+        Just(Node::HorizontalRule { span: None }),
+    ]
+}
+
+/// Text made of inline markers, for checking parser invariants on malformed input
+///
+/// With `unescaped`, the text also holds backticks and line breaks. The Markdown writer
+/// does not escape those: a literal backtick can pair with a code span's delimiter when
+/// the output is re-parsed, and a joined line can start with a list marker.
+fn inline_soup(unescaped: bool) -> impl Strategy<Value = String> {
+    let mut pieces = vec![
+        "*", "**", "~~", "[", "]", "(", ")", "!", "<!--", "-->", " ", "a", "é",
+    ];
+    if unescaped {
+        pieces.extend(["`", "```", "\n"]);
+    }
+    prop::collection::vec(prop::sample::select(pieces), 1..40).prop_map(|pieces| pieces.concat())
+}
+
+proptest! {
+    #[test]
+    fn prop_markdown_round_trip(ast in prop::collection::vec(block(), 1..6)) {
+        let markdown = to_markdown(&ast);
+        prop_assert_eq!(parse(&markdown), ast, "markdown:\n{}", markdown);
+    }
+
+    #[test]
+    fn prop_parse_never_fails_with_recovery(text in inline_soup(true)) {
+        let config = ParserConfig {
+            error_recovery: true,
+            ..ParserConfig::default()
+        };
+        let ast = Parser::with_config(text.clone(), config).unwrap().parse();
+        prop_assert!(ast.is_ok(), "input: {:?}, error: {:?}", text, ast);
+    }
+
+    #[test]
+    fn prop_parse_is_stable(text in inline_soup(false)) {
+        let markdown = to_markdown(&parse(&text));
+        // Re-parsing the serialized AST gives the same AST back
+        prop_assert_eq!(to_markdown(&parse(&markdown)), markdown.clone(), "input: {:?}", text);
+    }
+}
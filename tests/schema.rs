@@ -0,0 +1,57 @@
+use md_parser::{json_schema, AstDocument, Parser, SCHEMA_VERSION};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_committed_schema_is_current() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("schema/ast.schema.json");
+    let schema = serde_json::to_string_pretty(&json_schema()).unwrap() + "\n";
+    if std::env::var_os("UPDATE_SCHEMA").is_some() {
+        fs::write(&path, &schema).unwrap();
+    }
+    let committed = fs::read_to_string(&path).unwrap();
+    assert!(
+        committed == schema,
+        "schema/ast.schema.json is out of date; review the change against the evolution \
+         policy of SCHEMA_VERSION, then run `UPDATE_SCHEMA=1 cargo test --test schema`"
+    );
+}
+
+#[test]
+fn test_json_output_carries_schema_version() {
+    let input = "# Title\n\n- [x] task\n\n```mermaid\ngraph TD\n    A-->B\n```\n\n---\n";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let json = parser.to_json().unwrap();
+
+    let document: AstDocument = serde_json::from_str(&json).unwrap();
+    assert_eq!(document.schema_version, SCHEMA_VERSION);
+    assert_eq!(document.nodes, parser.parse().unwrap());
+}
+
+#[test]
+fn test_schema_lists_every_node_type() {
+    let schema = json_schema().to_string();
+    for kind in [
+        "heading",
+        "paragraph",
+        "unordered_list",
+        "ordered_list",
+        "code_block",
+        "mermaid_diagram",
+        "diagram_ref",
+        "table",
+        "blockquote",
+        "horizontal_rule",
+        "admonition",
+        "container",
+        "details",
+        "section",
+        "comment",
+    ] {
+        assert!(
+            schema.contains(&format!("\"const\":\"{}\"", kind)),
+            "{} missing from the schema",
+            kind
+        );
+    }
+}
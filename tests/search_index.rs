@@ -0,0 +1,66 @@
+mod common;
+
+use md_parser::{ParserConfig, SearchIndex};
+
+#[test]
+fn test_records_per_section() {
+    let config = ParserConfig {
+        enable_hashtags: true,
+        ..ParserConfig::default()
+    };
+    let nodes = common::parse_with(
+        "Intro text.\n\n# Setup Guide\n\nRead **this** first. #docs\n\n## Install\n\n- Run the installer\n  - then restart\n\n```sh\ncargo install md-parser\n```\n\n| Key | Value |\n|-----|-------|\n| os | linux |\n",
+        config,
+    );
+    let mut index = SearchIndex::new();
+    index.add_document("guide/setup.html", "Setup Guide", &nodes);
+
+    let ids: Vec<_> = index.records.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [
+            "guide/setup.html",
+            "guide/setup.html#setup-guide",
+            "guide/setup.html#install"
+        ]
+    );
+
+    let preamble = &index.records[0];
+    assert_eq!(preamble.anchor, None);
+    assert_eq!(preamble.title, "Setup Guide");
+    assert_eq!(preamble.body, "Intro text.");
+
+    // A section's body leaves out its subsections
+    let guide = &index.records[1];
+    assert_eq!(guide.anchor.as_deref(), Some("setup-guide"));
+    assert_eq!(guide.body, "Read this first. #docs");
+    assert_eq!(guide.tags, ["docs"]);
+    assert_eq!(guide.page, "Setup Guide");
+
+    let install = &index.records[2];
+    assert_eq!(
+        install.body,
+        "Run the installer\nthen restart\ncargo install md-parser\nKey Value\nos linux"
+    );
+}
+
+#[test]
+fn test_inverted_index_spans_documents() {
+    let mut index = SearchIndex::new();
+    index.add_document("a.html", "A", &common::parse("# Install\n\nRun CARGO."));
+    index.add_document(
+        "b.html",
+        "B",
+        &common::parse("# Usage\n\nCargo run, cargo build."),
+    );
+
+    assert_eq!(index.index["cargo"], [0, 1]);
+    assert_eq!(index.index["install"], [0]);
+    assert_eq!(index.index["usage"], [1]);
+    // Single characters are not indexed
+    assert!(!index.index.contains_key("a"));
+
+    let json: serde_json::Value = serde_json::from_str(&index.to_json().unwrap()).unwrap();
+    assert_eq!(json["records"][1]["id"], "b.html#usage");
+    assert_eq!(json["index"]["run"], serde_json::json!([0, 1]));
+}
@@ -0,0 +1,86 @@
+use md_parser::{filter_audience, nest_sections, Node, Parser, ParserConfig, RendererConfig};
+
+fn parse_sections(input: &str) -> Vec<Node> {
+    let config = ParserConfig {
+        enable_sections: true,
+        enable_attributes: true,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config(input.to_string(), config).unwrap();
+    parser.parse().unwrap()
+}
+
+/// Section levels and child counts, depth first
+fn shape(nodes: &[Node]) -> Vec<(u8, usize)> {
+    let mut out = Vec::new();
+    for node in nodes {
+        if let Node::Section {
+            level, children, ..
+        } = node
+        {
+            out.push((*level, children.len()));
+            out.extend(shape(children));
+        }
+    }
+    out
+}
+
+#[test]
+fn test_sections_are_disabled_by_default() {
+    let mut parser = Parser::new("# A\n\nText".to_string()).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert!(matches!(ast[0], Node::Heading { .. }));
+}
+
+#[test]
+fn test_sections_nest_by_heading_level() {
+    let ast = parse_sections("Intro\n\n# A\n\nText\n\n## B\n\nMore\n\n### C\n\n## D\n\n# E");
+
+    assert!(matches!(ast[0], Node::Paragraph { .. }));
+    // A holds its paragraph plus B and D; B holds its paragraph plus C
+    assert_eq!(shape(&ast), [(1, 3), (2, 2), (3, 0), (2, 0), (1, 0)]);
+}
+
+#[test]
+fn test_nest_sections_keeps_heading_attributes() {
+    let ast = parse_sections("# Setup {#install}\n\nText");
+
+    match &ast[0] {
+        Node::Section { attributes, .. } => assert_eq!(attributes.id.as_deref(), Some("install")),
+        other => panic!("expected section, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_audience_filter_drops_nested_sections() {
+    let ast = parse_sections("# Public\n\n## Internal {audience=staff}\n\nSecret\n\n## Open");
+
+    let filtered = filter_audience(&ast, "public");
+    assert_eq!(shape(&filtered), [(1, 1), (2, 0)]);
+}
+
+#[test]
+fn test_nest_sections_matches_parser_pass() {
+    let input = "# A\n\nText\n\n## B";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let flat = parser.parse().unwrap();
+
+    assert_eq!(nest_sections(&flat), parse_sections(input));
+}
+
+#[test]
+fn test_sections_render_as_html_section_elements() {
+    let config = ParserConfig {
+        enable_sections: true,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config("# A\n\nText\n\n## B".to_string(), config).unwrap();
+    let html = parser
+        .to_html_with_config(&RendererConfig::default())
+        .unwrap();
+
+    assert!(html.contains(
+        "<section>\n<h1>A</h1>\n<p>Text</p>\n<section>\n<h2>B</h2>\n</section>\n</section>"
+    ));
+}
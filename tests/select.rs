@@ -0,0 +1,63 @@
+mod common;
+
+use md_parser::{Node, ParserConfig, Select};
+
+const INPUT: &str = "# Guide\n\n## Install {#install .step}\n\n```sh\ncargo add md-parser\n```\n\n## Usage {.step}\n\n::: {.box}\n```rust\nlet ast = parse();\n```\n:::\n\n```rust {.no-run}\nfn main() {}\n```\n";
+
+fn paths(select: &Select, ast: &[Node]) -> Vec<String> {
+    select
+        .find(ast)
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect()
+}
+
+#[test]
+fn test_select_by_type_and_fields() {
+    let ast = common::parse_with(
+        INPUT,
+        ParserConfig {
+            enable_attributes: true,
+            enable_directives: true,
+            ..ParserConfig::default()
+        },
+    );
+    assert_eq!(paths(&Select::headings(), &ast), ["0", "1", "3"]);
+    assert_eq!(paths(&Select::headings().level(2), &ast), ["1", "3"]);
+    // Nested code blocks are found too
+    assert_eq!(
+        paths(&Select::code_blocks().lang("rust"), &ast),
+        ["4.0", "5"]
+    );
+    assert_eq!(
+        paths(&Select::code_blocks().lang("rus"), &ast),
+        Vec::<String>::new()
+    );
+    assert_eq!(paths(&Select::kind("container"), &ast), ["4"]);
+    assert_eq!(Select::all().find(&ast).len(), 7);
+}
+
+#[test]
+fn test_select_by_attributes_and_text() {
+    let ast = common::parse_with(
+        INPUT,
+        ParserConfig {
+            enable_attributes: true,
+            enable_directives: true,
+            ..ParserConfig::default()
+        },
+    );
+    assert_eq!(paths(&Select::all().class("step"), &ast), ["1", "3"]);
+    assert_eq!(paths(&Select::headings().id("install"), &ast), ["1"]);
+    assert_eq!(
+        paths(&Select::all().class("step").class("x"), &ast),
+        Vec::<String>::new()
+    );
+    assert_eq!(paths(&Select::code_blocks().class("no-run"), &ast), ["5"]);
+    assert_eq!(paths(&Select::all().containing("parse()"), &ast), ["4.0"]);
+
+    let (path, node) = Select::headings().containing("Usage").first(&ast).unwrap();
+    assert_eq!(path.indices(), [3]);
+    assert!(Select::headings().level(2).matches(node));
+    assert_eq!(Select::tables().first(&ast), None);
+}
@@ -0,0 +1,47 @@
+#![cfg(any(feature = "yaml", feature = "msgpack", feature = "cbor"))]
+
+use md_parser::{AstDocument, Parser, SCHEMA_VERSION};
+
+const DOCUMENT: &str = "# Title\n\nSome **bold** text.\n\n- [x] done\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n---\n\n```mermaid\ngraph TD\n    A-->B\n```\n";
+
+fn parser() -> Parser {
+    Parser::new(DOCUMENT.to_string()).unwrap()
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_matches_json_structure() {
+    let mut parser = parser();
+    let yaml = parser.to_yaml().unwrap();
+    let from_yaml: serde_json::Value = serde_yaml::from_str(&yaml).unwrap();
+    let from_json: serde_json::Value = serde_json::from_str(&parser.to_json().unwrap()).unwrap();
+    assert_eq!(from_yaml, from_json);
+
+    let document: AstDocument = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(document.schema_version, SCHEMA_VERSION);
+    assert_eq!(document.nodes, parser.parse().unwrap());
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_msgpack_roundtrip_and_size() {
+    let mut parser = parser();
+    let bytes = parser.to_msgpack().unwrap();
+    let document: AstDocument = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(document.nodes, parser.parse().unwrap());
+
+    // Field names are kept, so generic decoders see the same structure as in JSON
+    let value: serde_json::Value = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(value["schema_version"], SCHEMA_VERSION);
+    assert_eq!(value["nodes"][0]["type"], "heading");
+    assert!(bytes.len() < parser.to_json().unwrap().len() / 2);
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_cbor_roundtrip() {
+    let mut parser = parser();
+    let bytes = parser.to_cbor().unwrap();
+    let document: AstDocument = ciborium::from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(document.nodes, parser.parse().unwrap());
+}
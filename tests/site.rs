@@ -0,0 +1,70 @@
+use md_parser::{navigation, sitemap_xml, NavEntry, Parser, SitePage};
+
+fn page(path: &str, markdown: &str) -> SitePage {
+    let result = Parser::new(markdown.to_string())
+        .unwrap()
+        .parse_full()
+        .unwrap();
+    SitePage::from_result(path, &result)
+}
+
+fn titles(entries: &[NavEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.title.as_str()).collect()
+}
+
+#[test]
+fn test_page_titles_and_frontmatter_keys() {
+    let titled = page(
+        "guide/setup.html",
+        "---\ntitle: \"Getting set up\"\nnav_order: 2\n---\n\n# Setup\n",
+    );
+    assert_eq!(titled.title, "Getting set up");
+    assert_eq!(titled.order, Some(2));
+    assert!(!titled.hidden);
+
+    let heading = page("faq.html", "Intro.\n\n## Frequently *asked*\n");
+    assert_eq!(heading.title, "Frequently asked");
+    assert_eq!(heading.order, None);
+
+    let hidden = page("notes/draft.html", "---\nnav_exclude: true\n---\n\nText.\n");
+    assert_eq!(hidden.title, "draft");
+    assert!(hidden.hidden);
+}
+
+#[test]
+fn test_navigation_follows_directories_and_order() {
+    let pages = [
+        page("index.html", "# Home\n"),
+        page("faq.html", "# FAQ\n"),
+        page("about.html", "# About\n"),
+        page("guide/index.html", "---\nnav_order: 1\n---\n\n# Guide\n"),
+        page(
+            "guide/install.html",
+            "---\nnav_order: 2\n---\n\n# Install\n",
+        ),
+        page("guide/advanced.html", "# Advanced\n"),
+        page("guide/basics.html", "---\nnav_order: 1\n---\n\n# Basics\n"),
+        page("reference/api.html", "# API\n"),
+        page("drafts/wip.html", "---\nnav_exclude: true\n---\n\n# WIP\n"),
+    ];
+    let nav = navigation(&pages);
+
+    assert_eq!(titles(&nav), ["Guide", "Home", "About", "FAQ", "reference"]);
+    assert_eq!(nav[0].path.as_deref(), Some("guide/index.html"));
+    assert_eq!(titles(&nav[0].children), ["Basics", "Install", "Advanced"]);
+    assert_eq!(nav[4].path, None);
+    assert_eq!(titles(&nav[4].children), ["API"]);
+
+    let json = serde_json::to_value(&nav).unwrap();
+    assert_eq!(
+        json[1],
+        serde_json::json!({"title": "Home", "path": "index.html"})
+    );
+
+    let xml = sitemap_xml(&pages, "https://docs.example.com/");
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset"));
+    assert!(xml.contains("  <url><loc>https://docs.example.com/guide/install.html</loc></url>\n"));
+    // Pages left out of the navigation stay in the sitemap
+    assert!(xml.contains("drafts/wip.html"));
+    assert_eq!(xml.matches("<url>").count(), pages.len());
+}
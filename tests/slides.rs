@@ -0,0 +1,87 @@
+use md_parser::{Parser, RendererConfig, SlideOptions};
+
+const DECK: &str = "# Quarterly review\n\nWelcome\n\n## Numbers\n\nRevenue is up.\n\n<!-- Mention the new region -->\n\n---\n\n```mermaid\ngraph LR\n  A --> B\n```\n\n---\n\n## Questions\n";
+
+fn slides(input: &str, options: &SlideOptions) -> String {
+    Parser::new(input.to_string())
+        .unwrap()
+        .to_slides(options, &RendererConfig::default())
+        .unwrap()
+}
+
+fn sections(html: &str) -> Vec<&str> {
+    html.split("<section>\n")
+        .skip(1)
+        .map(|slide| slide.split("</section>").next().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_split_on_rules_and_level_two_headings() {
+    let html = slides(DECK, &SlideOptions::default());
+    let slides = sections(&html);
+
+    assert_eq!(slides.len(), 4, "{}", html);
+    assert!(slides[0].contains("<h1>Quarterly review</h1>"));
+    assert!(slides[1].starts_with("<h2>Numbers</h2>"));
+    assert!(slides[2].contains("<div class=\"mermaid\""));
+    assert!(slides[3].contains("<h2>Questions</h2>"));
+    assert!(!html.contains("<hr"));
+}
+
+#[test]
+fn test_comments_become_speaker_notes() {
+    let html = slides(DECK, &SlideOptions::default());
+    let slides = sections(&html);
+    assert!(slides[1].contains("<aside class=\"notes\">\n<p>Mention the new region</p>\n</aside>"));
+    assert!(!html.contains("<!--"));
+}
+
+#[test]
+fn test_reveal_page_with_per_slide_mermaid() {
+    let options = SlideOptions {
+        title: "Q3 <draft>".to_string(),
+        theme: "black".to_string(),
+        ..SlideOptions::default()
+    };
+    let html = slides(DECK, &options);
+    assert!(html.contains("<title>Q3 &lt;draft&gt;</title>"));
+    assert!(html.contains("reveal.js@5/dist/theme/black.css"));
+    assert!(html.contains("<div class=\"reveal\">\n<div class=\"slides\">"));
+    assert!(html.contains("mermaid.initialize({ startOnLoad: false"));
+    assert!(html.contains("Reveal.on('slidechanged'"));
+}
+
+#[test]
+fn test_split_options() {
+    let options = SlideOptions {
+        split_on_rules: false,
+        split_heading_level: Some(1),
+        ..SlideOptions::default()
+    };
+    let html = slides(DECK, &options);
+    assert_eq!(sections(&html).len(), 1);
+    assert!(html.contains("<hr"));
+
+    let options = SlideOptions {
+        split_heading_level: None,
+        ..SlideOptions::default()
+    };
+    assert_eq!(sections(&slides(DECK, &options)).len(), 3);
+}
+
+#[test]
+fn test_sections_are_split_like_headings() {
+    let config = md_parser::ParserConfig {
+        enable_sections: true,
+        ..md_parser::ParserConfig::default()
+    };
+    let html = Parser::with_config(
+        "# Title\n\n## One\n\nA\n\n## Two\n\nB\n".to_string(),
+        config,
+    )
+    .unwrap()
+    .to_slides(&SlideOptions::default(), &RendererConfig::default())
+    .unwrap();
+    assert_eq!(sections(&html).len(), 3);
+}
@@ -1,4 +1,7 @@
-use md_parser::{Alignment, Inline, Node, Parser};
+mod common;
+
+use common::text;
+use md_parser::{Alignment, Inline, LineRange, Node, Parser, ParserConfig, WarningCode};
 
 #[test]
 fn test_simple_table() {
@@ -13,6 +16,7 @@ fn test_simple_table() {
             headers,
             rows,
             alignments,
+            ..
         } => {
             assert_eq!(headers.len(), 2);
             assert_eq!(rows.len(), 1);
@@ -22,14 +26,14 @@ fn test_simple_table() {
             assert_eq!(
                 headers[0][0],
                 Inline::Text {
-                    content: "Header 1".to_string()
+                    content: "Header 1".into()
                 }
             );
             assert_eq!(headers[1].len(), 1);
             assert_eq!(
                 headers[1][0],
                 Inline::Text {
-                    content: "Header 2".to_string()
+                    content: "Header 2".into()
                 }
             );
             // Check rows
@@ -37,13 +41,13 @@ fn test_simple_table() {
             assert_eq!(
                 rows[0][0][0],
                 Inline::Text {
-                    content: "Cell 1".to_string()
+                    content: "Cell 1".into()
                 }
             );
             assert_eq!(
                 rows[0][1][0],
                 Inline::Text {
-                    content: "Cell 2".to_string()
+                    content: "Cell 2".into()
                 }
             );
         }
@@ -64,6 +68,7 @@ fn test_table_with_alignment() {
             headers,
             rows,
             alignments,
+            ..
         } => {
             assert_eq!(headers.len(), 3);
             assert_eq!(rows.len(), 1);
@@ -89,6 +94,7 @@ fn test_table_with_inline_formatting() {
             headers,
             rows: _,
             alignments: _,
+            ..
         } => {
             assert_eq!(headers.len(), 3);
             // First header should have bold
@@ -123,6 +129,7 @@ fn test_table_with_empty_cells() {
             headers,
             rows,
             alignments: _,
+            ..
         } => {
             assert_eq!(headers.len(), 3);
             assert_eq!(rows.len(), 2);
@@ -158,7 +165,7 @@ fn test_table_followed_by_paragraph() {
             assert_eq!(
                 content[0],
                 Inline::Text {
-                    content: "Some paragraph text.".to_string()
+                    content: "Some paragraph text.".into()
                 }
             );
         }
@@ -198,6 +205,7 @@ fn test_table_multiple_rows() {
             headers,
             rows,
             alignments: _,
+            ..
         } => {
             assert_eq!(headers.len(), 2);
             assert_eq!(rows.len(), 3);
@@ -269,3 +277,231 @@ fn test_table_without_trailing_pipe() {
         _ => panic!("Expected Table, got {:?}", result[0]),
     }
 }
+
+#[test]
+fn test_table_escaped_pipe_stays_in_cell() {
+    let input = "| Op | Meaning |\n|----|---------|\n| `a \\| b` | either \\| or |".to_string();
+    let result = Parser::new(input).unwrap().parse().unwrap();
+
+    match &result[0] {
+        Node::Table { rows, .. } => {
+            assert_eq!(rows[0].len(), 2);
+            assert_eq!(
+                rows[0][0],
+                vec![Inline::Code {
+                    content: "a | b".into()
+                }]
+            );
+            assert_eq!(rows[0][1], vec![text("either | or")]);
+        }
+        other => panic!("expected table, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_table_br_becomes_line_break() {
+    let input = "| Step |\n|------|\n| one<br>two<BR />three |".to_string();
+    let mut parser = Parser::new(input).unwrap();
+    let result = parser.parse().unwrap();
+
+    match &result[0] {
+        Node::Table { rows, .. } => assert_eq!(
+            rows[0][0],
+            vec![
+                text("one"),
+                Inline::LineBreak,
+                text("two"),
+                Inline::LineBreak,
+                text("three")
+            ]
+        ),
+        other => panic!("expected table, got {:?}", other),
+    }
+    assert!(parser
+        .to_html()
+        .unwrap()
+        .contains("<td>one<br>two<br>three</td>"));
+}
+
+#[test]
+fn test_multiline_table_rows() {
+    let input =
+        "| Name | Notes |\n|------|-------|\n| a | first | \\\n|   | second |\n| b | single |";
+    let config = ParserConfig {
+        enable_multiline_tables: true,
+        ..ParserConfig::default()
+    };
+    let result = Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    match &result[0] {
+        Node::Table { rows, .. } => {
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[0][0], vec![text("a")]);
+            assert_eq!(
+                rows[0][1],
+                vec![text("first"), Inline::LineBreak, text("second")]
+            );
+            assert_eq!(rows[1][1], vec![text("single")]);
+        }
+        other => panic!("expected table, got {:?}", other),
+    }
+
+    // Without the option the marker is ordinary text and rows stay separate
+    let result = Parser::new(input.to_string()).unwrap().parse().unwrap();
+    assert!(matches!(&result[0], Node::Table { rows, .. } if rows.len() == 3));
+}
+
+#[test]
+fn test_ragged_rows_are_normalized_with_warnings() {
+    let input =
+        "Intro\n\n| A | B | C |\n|---|---|---|\n| 1 | 2 |\n| 1 | 2 | 3 | 4 |\n| 1 | 2 | 3 |";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let result = parser.parse().unwrap();
+
+    match &result[1] {
+        Node::Table { rows, span, .. } => {
+            assert!(rows.iter().all(|row| row.len() == 3));
+            assert!(rows[0][2].is_empty());
+            assert_eq!(rows[1][2], vec![text("3")]);
+            assert_eq!(*span, Some(LineRange { start: 3, end: 7 }));
+        }
+        other => panic!("expected table, got {:?}", other),
+    }
+
+    let warnings = parser.warnings();
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings
+        .iter()
+        .all(|w| w.code == WarningCode::TableColumnMismatch));
+    assert_eq!(warnings[0].span.line, 5);
+    assert_eq!(
+        warnings[0].message,
+        "table row has 2 cells, expected 3; padded with empty cells"
+    );
+    assert_eq!(warnings[1].span.line, 6);
+    assert_eq!(
+        warnings[1].message,
+        "table row has 4 cells, expected 3; extra cells dropped"
+    );
+}
+
+#[test]
+fn test_table_span_inside_container_is_absolute() {
+    let input = "Text\n\n:::note\n| A |\n|---|\n| 1 | 2 |\n:::";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let result = parser.parse().unwrap();
+
+    match &result[1] {
+        Node::Admonition { content, .. } => assert!(matches!(
+            &content[0],
+            Node::Table { span: Some(span), .. } if *span == LineRange { start: 4, end: 6 }
+        )),
+        other => panic!("expected admonition, got {:?}", other),
+    }
+    assert_eq!(parser.warnings()[0].span.line, 6);
+}
+
+#[test]
+fn test_table_caption_html() {
+    let input =
+        "| Name | Score |\n|:-----|------:|\n| Ada | 10 |\n\nTable: Final *scores*\n\nAfter.";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.len(), 2);
+    assert!(matches!(
+        &result[0],
+        Node::Table { caption: Some(caption), span: Some(span), .. }
+            if caption.len() == 2 && span.end == 3
+    ));
+
+    let html = parser.to_html().unwrap();
+    assert!(html.contains(
+        "<table>\n<caption>Final <em>scores</em></caption>\n<thead>\n<tr><th style=\"text-align: left;\">Name</th><th style=\"text-align: right;\">Score</th></tr>\n</thead>\n<tbody><tr><td style=\"text-align: left;\">Ada</td><td style=\"text-align: right;\">10</td></tr>"
+    ));
+}
+
+#[test]
+fn test_table_caption_other_formats() {
+    let input = "| A |\n|---|\n| 1 |\nTable: Results";
+
+    let asciidoc = Parser::new(input.to_string())
+        .unwrap()
+        .to_asciidoc()
+        .unwrap();
+    assert!(asciidoc.starts_with(".Results\n[%header,cols=\"<\"]\n|===\n"));
+
+    let rst = Parser::new(input.to_string()).unwrap().to_rst().unwrap();
+    assert!(rst.starts_with(".. list-table:: Results\n   :header-rows: 1\n"));
+}
+
+#[test]
+fn test_table_nested_in_list_item() {
+    let input =
+        "- Which formats?\n  | Format | Flag |\n  |--------|------|\n  | JSON   | `--format json` |\n- Next question".to_string();
+    let ast = Parser::new(input.clone()).unwrap().parse().unwrap();
+
+    assert_eq!(ast.len(), 1, "{:?}", ast);
+    let Node::UnorderedList { items } = &ast[0] else {
+        panic!("expected list, got {:?}", ast[0]);
+    };
+    assert_eq!(items.len(), 2);
+    let [Node::Table {
+        headers,
+        rows,
+        span,
+        ..
+    }] = items[0].blocks.as_slice()
+    else {
+        panic!("expected a nested table, got {:?}", items[0].blocks);
+    };
+    assert_eq!(headers.len(), 2);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(*span, Some(LineRange { start: 2, end: 4 }));
+
+    // Written back as Markdown, the table stays under its item
+    let markdown = Parser::new(input).unwrap().to_markdown().unwrap();
+    let reparsed = Parser::new(markdown.clone()).unwrap().parse().unwrap();
+    assert!(
+        matches!(&reparsed[0], Node::UnorderedList { items } if matches!(items[0].blocks.as_slice(), [Node::Table { .. }])),
+        "{}",
+        markdown
+    );
+}
+
+#[test]
+fn test_table_nested_in_blockquote() {
+    let input = "> Answer:\n> | a | b |\n> |:---|---:|\n> | 1 | 2 |\n\nAfter.";
+    let ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+
+    assert_eq!(ast.len(), 2, "{:?}", ast);
+    let Node::Blockquote {
+        content, blocks, ..
+    } = &ast[0]
+    else {
+        panic!("expected blockquote, got {:?}", ast[0]);
+    };
+    assert_eq!(
+        content,
+        &vec![Inline::Text {
+            content: "Answer:".into()
+        }]
+    );
+    let [Node::Table {
+        alignments, rows, ..
+    }] = blocks.as_slice()
+    else {
+        panic!("expected a nested table, got {:?}", blocks);
+    };
+    assert_eq!(
+        alignments,
+        &vec![Some(Alignment::Left), Some(Alignment::Right)]
+    );
+    assert_eq!(rows.len(), 1);
+
+    let html = Parser::new(input.to_string()).unwrap().to_html().unwrap();
+    assert!(html.contains("<blockquote>Answer:\n<table>"), "{}", html);
+}
@@ -1,4 +1,7 @@
-use md_parser::{Inline, Node, Parser};
+use md_parser::{
+    extract_tasks, set_task_state, to_markdown, Inline, Node, Parser, ParserConfig, RendererConfig,
+    TaskStats,
+};
 
 #[test]
 fn test_task_list_unchecked() {
@@ -15,13 +18,13 @@ fn test_task_list_unchecked() {
             assert_eq!(
                 items[0].content[0],
                 Inline::Text {
-                    content: "task 1".to_string()
+                    content: "task 1".into()
                 }
             );
             assert_eq!(
                 items[1].content[0],
                 Inline::Text {
-                    content: "task 2".to_string()
+                    content: "task 2".into()
                 }
             );
         }
@@ -43,7 +46,7 @@ fn test_task_list_checked() {
             assert_eq!(
                 items[0].content[0],
                 Inline::Text {
-                    content: "completed task".to_string()
+                    content: "completed task".into()
                 }
             );
         }
@@ -65,7 +68,7 @@ fn test_task_list_case_insensitive() {
             assert_eq!(
                 items[0].content[0],
                 Inline::Text {
-                    content: "uppercase task".to_string()
+                    content: "uppercase task".into()
                 }
             );
         }
@@ -145,7 +148,7 @@ fn test_task_list_continuation() {
                 .iter()
                 .map(|i| match i {
                     Inline::Text { content } => content.clone(),
-                    _ => String::new(),
+                    _ => String::new().into(),
                 })
                 .collect();
             assert!(content_text.contains("task with"));
@@ -192,3 +195,188 @@ fn test_task_list_nested_tasks() {
         _ => panic!("Expected UnorderedList, got {:?}", result[0]),
     }
 }
+
+#[test]
+fn test_task_checkboxes_are_disabled_by_default() {
+    let html = Parser::new("- [x] done\n- [ ] todo".to_string())
+        .unwrap()
+        .to_html()
+        .unwrap();
+    assert!(html.contains("<input type=\"checkbox\" disabled checked> done"));
+    assert!(html.contains("<input type=\"checkbox\" disabled> todo"));
+}
+
+#[test]
+fn test_interactive_task_checkboxes() {
+    let config = RendererConfig {
+        interactive_tasks: true,
+        ..RendererConfig::default()
+    };
+    let html = Parser::new("- [x] done\n- [ ] todo".to_string())
+        .unwrap()
+        .to_html_with_config(&config)
+        .unwrap();
+    assert!(html.contains("<input type=\"checkbox\" checked> done"));
+    assert!(html.contains("<input type=\"checkbox\"> todo"));
+    assert!(!html.contains("disabled"));
+}
+
+#[test]
+fn test_set_task_state_round_trip() {
+    let input = "# Plan\n\n- [ ] parent\n  - [ ] child\n- plain item\n";
+    let mut ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+
+    assert!(set_task_state(&mut ast, &[1, 0, 0], true));
+    assert!(set_task_state(&mut ast, &[1, 0], true));
+    let markdown = to_markdown(&ast);
+    assert_eq!(
+        markdown,
+        "# Plan\n\n- [x] parent\n  - [x] child\n- plain item\n"
+    );
+
+    let reparsed = Parser::new(markdown).unwrap().parse().unwrap();
+    assert_eq!(reparsed, ast);
+}
+
+#[test]
+fn test_set_task_state_rejects_non_tasks() {
+    let input = "Intro\n\n- [ ] task\n- plain item";
+    let mut ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+    let original = ast.clone();
+
+    assert!(!set_task_state(&mut ast, &[0], true));
+    assert!(!set_task_state(&mut ast, &[1, 1], true));
+    assert!(!set_task_state(&mut ast, &[1, 5], true));
+    assert!(!set_task_state(&mut ast, &[1], true));
+    assert!(!set_task_state(&mut ast, &[], true));
+    assert_eq!(ast, original);
+}
+
+#[test]
+fn test_set_task_state_inside_admonition() {
+    let input = "> [!NOTE]\n> - [x] reviewed";
+    let mut ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+
+    assert!(set_task_state(&mut ast, &[0, 0, 0], false));
+    assert_eq!(to_markdown(&ast), "> [!NOTE]\n> - [ ] reviewed\n");
+}
+
+#[test]
+fn test_to_markdown_round_trips_document() {
+    let input = "\
+# Title {#top .lead}
+
+Some **bold**, *italic*, ~~struck~~, `code`, [a link](https://example.com) and ![alt](img.png).
+
+1. first
+2. second
+  1. nested
+
+```rust
+fn main() {}
+```
+
+| Name | Score |
+| :--- | ---: |
+| a \\| b | 1<br>2 |
+
+Table: Results
+
+>> quoted
+
+---
+
+:::details More
+Hidden text.
+:::
+
+::: {#box .aside}
+Boxed :kbd[Ctrl]{.key}.
+:::
+
+<!-- note to self -->
+";
+    let config = ParserConfig {
+        enable_attributes: true,
+        enable_directives: true,
+        ..ParserConfig::default()
+    };
+    let ast = Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(to_markdown(&ast), input);
+}
+
+#[test]
+fn test_extract_tasks_with_headings_and_depth() {
+    let input = "\
+- [x] setup
+
+# Release
+
+- [ ] build
+  - plain note
+    - [x] nested check
+
+> [!NOTE]
+> - [ ] inside alert
+
+## Docs
+
+- [x] write guide
+";
+    let mut ast = Parser::new(input.to_string()).unwrap().parse().unwrap();
+    let tasks = extract_tasks(&ast);
+
+    let summary: Vec<(&str, bool, usize, Option<&str>)> = tasks
+        .iter()
+        .map(|task| {
+            (
+                task.text.as_str(),
+                task.checked,
+                task.depth,
+                task.heading.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("setup", true, 0, None),
+            ("build", false, 0, Some("Release")),
+            ("nested check", true, 2, Some("Release")),
+            ("inside alert", false, 0, Some("Release")),
+            ("write guide", true, 0, Some("Docs")),
+        ]
+    );
+
+    // Paths address the same items for set_task_state
+    assert!(set_task_state(&mut ast, &tasks[3].path, true));
+    assert!(extract_tasks(&ast)[3].checked);
+
+    let stats = TaskStats::from_tasks(&tasks);
+    assert_eq!(stats.total, 5);
+    assert_eq!(stats.completed, 3);
+    assert_eq!(stats.percent_complete(), Some(60.0));
+}
+
+#[test]
+fn test_extract_tasks_in_sections() {
+    let config = ParserConfig {
+        enable_sections: true,
+        ..ParserConfig::default()
+    };
+    let input = "# Plan\n\n## Week 1\n\n- [ ] kickoff\n\n# Notes\n\n- [ ] follow up";
+    let ast = Parser::with_config(input.to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    let tasks = extract_tasks(&ast);
+
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].heading.as_deref(), Some("Week 1"));
+    assert_eq!(tasks[0].path, vec![0, 0, 0, 0]);
+    assert_eq!(tasks[1].heading.as_deref(), Some("Notes"));
+    assert_eq!(TaskStats::from_tasks(&[]).percent_complete(), None);
+}
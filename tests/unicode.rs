@@ -0,0 +1,223 @@
+mod common;
+
+use common::{parse, text};
+use md_parser::{slugify, to_markdown, Attributes, Inline, Node, Parser, ParserConfig};
+
+#[test]
+fn test_headings_with_multibyte_text() {
+    let ast = parse("#🦀 Crab\n## 日本語の見出し\n### مرحبا بالعالم\n#### Cafe\u{301} 👩‍💻");
+    let headings: Vec<(u8, Vec<Inline>)> = ast
+        .into_iter()
+        .map(|node| match node {
+            Node::Heading { level, content, .. } => (level, content),
+            other => panic!("expected heading, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        headings,
+        vec![
+            (1, vec![text("🦀 Crab")]),
+            (2, vec![text("日本語の見出し")]),
+            (3, vec![text("مرحبا بالعالم")]),
+            (4, vec![text("Cafe\u{301} 👩‍💻")]),
+        ]
+    );
+}
+
+#[test]
+fn test_emoji_after_hash_with_strict_atx() {
+    let config = ParserConfig {
+        strict_atx: true,
+        ..ParserConfig::default()
+    };
+    let ast = Parser::with_config("#🦀".to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        ast,
+        vec![Node::Paragraph {
+            content: vec![text("#🦀")]
+        }]
+    );
+}
+
+#[test]
+fn test_inline_formatting_with_multibyte_text() {
+    let ast = parse("**太字** and *斜体* and ~~שלום~~ and `🦀code` and [リンク](https://例え.jp/パス) ![画像 🦀](猫.png)");
+    let Node::Paragraph { content } = &ast[0] else {
+        panic!("expected paragraph, got {:?}", ast[0]);
+    };
+    assert_eq!(
+        content,
+        &vec![
+            Inline::Bold {
+                content: vec![text("太字")]
+            },
+            text(" and "),
+            Inline::Italic {
+                content: vec![text("斜体")]
+            },
+            text(" and "),
+            Inline::Strikethrough {
+                content: vec![text("שלום")]
+            },
+            text(" and "),
+            Inline::Code {
+                content: "🦀code".into()
+            },
+            text(" and "),
+            Inline::Link {
+                text: vec![text("リンク")],
+                url: "https://例え.jp/パス".to_string()
+            },
+            text(" "),
+            Inline::Image {
+                alt: "画像 🦀".to_string(),
+                url: "猫.png".to_string(),
+                attributes: Attributes::default(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_gfm_emphasis_between_cjk_characters() {
+    let config = ParserConfig {
+        gfm_emphasis: true,
+        ..ParserConfig::default()
+    };
+    let ast = Parser::with_config("これは**太字**です".to_string(), config)
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        ast,
+        vec![Node::Paragraph {
+            content: vec![
+                text("これは"),
+                Inline::Bold {
+                    content: vec![text("太字")]
+                },
+                text("です"),
+            ]
+        }]
+    );
+}
+
+#[test]
+fn test_lists_and_tasks_with_multibyte_text() {
+    let ast = parse("- 項目一\n  - 👩‍💻 nested\n- [x] 完了 🇯🇵\n\n1. première\n2. مرحبا");
+    let Node::UnorderedList { items } = &ast[0] else {
+        panic!("expected list, got {:?}", ast[0]);
+    };
+    assert_eq!(items[0].content, vec![text("項目一")]);
+    assert_eq!(items[0].children[0].content, vec![text("👩‍💻 nested")]);
+    assert_eq!(items[1].checked, Some(true));
+    assert_eq!(items[1].content, vec![text("完了 🇯🇵")]);
+
+    let Node::OrderedList { items } = &ast[1] else {
+        panic!("expected ordered list, got {:?}", ast[1]);
+    };
+    assert_eq!(items[1].content, vec![text("مرحبا")]);
+}
+
+#[test]
+fn test_tables_and_blockquotes_with_multibyte_text() {
+    let ast = parse("| 名前 | 🦀 |\n|:---|---:|\n| 値 | שלום |\n\n> 引用 e\u{301}");
+    let Node::Table { headers, rows, .. } = &ast[0] else {
+        panic!("expected table, got {:?}", ast[0]);
+    };
+    assert_eq!(headers, &vec![vec![text("名前")], vec![text("🦀")]]);
+    assert_eq!(rows, &vec![vec![vec![text("値")], vec![text("שלום")]]]);
+    assert_eq!(
+        ast[1],
+        Node::Blockquote {
+            level: 1,
+            content: vec![text("引用 e\u{301}")],
+            blocks: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn test_blocks_with_multibyte_text() {
+    let input = "> [!NOTE]\n> 注意 👩‍💻\n\n:::details 詳細 🦀\n内容\n:::\n\n<!-- コメント 🦀 -->\n\n```rust\nlet s = \"🦀\";\n```\n\n```mermaid\ngraph TD\n    A[開始] --> B[終了 🦀]\n```";
+    let ast = parse(input);
+    assert_eq!(ast.len(), 5, "{:?}", ast);
+    assert!(matches!(&ast[0], Node::Admonition { content, .. }
+        if content == &vec![Node::Paragraph { content: vec![text("注意 👩‍💻")] }]));
+    assert!(matches!(&ast[1], Node::Details { summary, .. } if summary == &vec![text("詳細 🦀")]));
+    assert_eq!(
+        ast[2],
+        Node::Comment {
+            content: "コメント 🦀".to_string()
+        }
+    );
+    assert_eq!(
+        ast[3],
+        Node::CodeBlock {
+            lang: Some("rust".to_string()),
+            code: "let s = \"🦀\";".to_string(),
+            attributes: Attributes::default(),
+        }
+    );
+    assert!(matches!(&ast[4], Node::MermaidDiagram { diagram, .. } if diagram.contains("終了 🦀")));
+}
+
+#[test]
+fn test_markdown_roundtrip_keeps_multibyte_text() {
+    let input =
+        "# 日本語 🦀\n\n- [ ] مرحبا\n  - e\u{301}\n\n| 名前 | 🇯🇵 |\n| --- | --- |\n| 値 | ✓ |\n";
+    let ast = parse(input);
+    let written = to_markdown(&ast);
+    assert_eq!(parse(&written), ast, "{}", written);
+}
+
+#[test]
+fn test_slugify_keeps_scripts_and_combining_marks() {
+    assert_eq!(slugify("日本語の見出し"), "日本語の見出し");
+    assert_eq!(slugify("مرحبا بالعالم"), "مرحبا-بالعالم");
+    assert_eq!(slugify("Cafe\u{301} Menu"), "cafe\u{301}-menu");
+    assert_eq!(slugify("नमस्ते दुनिया"), "नमस्ते-दुनिया");
+    assert_eq!(slugify("🦀 Crab\u{200b}s"), "-crabs");
+}
+
+#[test]
+fn test_warning_columns_count_characters() {
+    let input = "日本 **太字";
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    parser.parse().unwrap();
+
+    let warning = &parser.warnings()[0];
+    assert_eq!(warning.span.column, Some(4));
+    let rendered = warning.display_with_source(input);
+    // `日本` is four columns wide in a terminal, so the caret sits after five spaces
+    assert!(
+        rendered.contains("\n1 | 日本 **太字\n  |      ^\n"),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+fn test_caret_aligned_after_wide_characters() {
+    let input = "前 {{名前}} {{🦀}} {{undefined_crab}}";
+    let config = ParserConfig {
+        enable_variables: true,
+        ..ParserConfig::default()
+    };
+    let mut parser = Parser::with_config(input.to_string(), config).unwrap();
+    parser.parse().unwrap();
+
+    let warning = &parser.warnings()[0];
+    assert_eq!(warning.span.column, Some(16));
+    let rendered = warning.display_with_source(input);
+    // `前` and `🦀` are two columns wide and `名前` is four
+    let padding = " ".repeat(19);
+    assert!(
+        rendered.ends_with(&format!("\n  | {}^", padding)),
+        "{}",
+        rendered
+    );
+}
@@ -0,0 +1,61 @@
+use md_parser::{Parser, RendererConfig, UrlRewriter};
+
+fn render(input: &str, config: &RendererConfig) -> String {
+    let mut parser = Parser::new(input.to_string()).unwrap();
+    parser.to_html_with_config(config).unwrap()
+}
+
+#[test]
+fn test_base_url_prefixes_relative_urls() {
+    let config = RendererConfig {
+        base_url: Some("https://cdn.example.com/docs/".to_string()),
+        ..RendererConfig::default()
+    };
+    let html = render("[Guide](guide.html) ![Logo](/img/logo.png)", &config);
+
+    assert!(html.contains("href=\"https://cdn.example.com/docs/guide.html\""));
+    assert!(html.contains("src=\"https://cdn.example.com/docs/img/logo.png\""));
+}
+
+#[test]
+fn test_base_url_leaves_absolute_and_fragment_urls() {
+    let config = RendererConfig {
+        base_url: Some("https://cdn.example.com".to_string()),
+        ..RendererConfig::default()
+    };
+    let html = render(
+        "[a](https://other.org/x) [b](#section) [c](mailto:me@example.com)",
+        &config,
+    );
+
+    assert!(html.contains("href=\"https://other.org/x\""));
+    assert!(html.contains("href=\"#section\""));
+    assert!(html.contains("href=\"mailto:me@example.com\""));
+}
+
+#[test]
+fn test_url_rewriter_converts_md_links() {
+    let config = RendererConfig {
+        url_rewriter: Some(UrlRewriter::new(|url| match url.strip_suffix(".md") {
+            Some(stem) => format!("{}.html", stem),
+            None => url.to_string(),
+        })),
+        ..RendererConfig::default()
+    };
+    let html = render("See [setup](setup.md) and ![x](diagram.png)", &config);
+
+    assert!(html.contains("href=\"setup.html\""));
+    assert!(html.contains("src=\"diagram.png\""));
+}
+
+#[test]
+fn test_rewriter_runs_before_base_url() {
+    let config = RendererConfig {
+        base_url: Some("https://site.example".to_string()),
+        url_rewriter: Some(UrlRewriter::new(|url| url.replace(".md", ".html"))),
+        ..RendererConfig::default()
+    };
+    let html = render("[Intro](intro.md)", &config);
+
+    assert!(html.contains("href=\"https://site.example/intro.html\""));
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use md_parser::{Config, Inline, Node, ParseError, Parser, ParserConfig, WarningCode};
+use std::collections::BTreeMap;
+use std::fs;
+
+fn config(variables: &[(&str, &str)]) -> ParserConfig {
+    ParserConfig {
+        enable_variables: true,
+        variables: variables
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<BTreeMap<_, _>>(),
+        ..ParserConfig::default()
+    }
+}
+
+#[test]
+fn test_variables_substituted_everywhere() {
+    let input = "# Release {{ version }}\n\nShipped on {{date}}.\n\n```sh\ncargo add md-parser@{{version}}\n```";
+    let ast = Parser::with_config(
+        input.to_string(),
+        config(&[("version", "1.4.0"), ("date", "2026-10-16")]),
+    )
+    .unwrap()
+    .parse()
+    .unwrap();
+
+    assert!(matches!(
+        &ast[0],
+        Node::Heading { content, .. }
+            if content == &vec![Inline::Text { content: "Release 1.4.0".into() }]
+    ));
+    assert!(matches!(
+        &ast[1],
+        Node::Paragraph { content }
+            if content == &vec![Inline::Text { content: "Shipped on 2026-10-16.".into() }]
+    ));
+    assert!(matches!(
+        &ast[2],
+        Node::CodeBlock { code, .. } if code == "cargo add md-parser@1.4.0"
+    ));
+}
+
+#[test]
+fn test_escaped_and_undefined_placeholders() {
+    let input = "Write \\{{version}} to get {{version}}.\n\nUnknown: {{missing}}";
+    let mut parser = Parser::with_config(input.to_string(), config(&[("version", "2")])).unwrap();
+    let ast = parser.parse().unwrap();
+
+    assert!(matches!(
+        &ast[0],
+        Node::Paragraph { content }
+            if content == &vec![Inline::Text { content: "Write {{version}} to get 2.".into() }]
+    ));
+    assert!(matches!(
+        &ast[1],
+        Node::Paragraph { content }
+            if content == &vec![Inline::Text { content: "Unknown: {{missing}}".into() }]
+    ));
+
+    let warnings = parser.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, WarningCode::UndefinedVariable);
+    assert_eq!(warnings[0].span.line, 3);
+    assert_eq!(warnings[0].span.column, Some(10));
+}
+
+#[test]
+fn test_variables_disabled_by_default_and_deniable() {
+    let ast = Parser::new("v{{version}}".to_string())
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(matches!(
+        &ast[0],
+        Node::Paragraph { content }
+            if content == &vec![Inline::Text { content: "v{{version}}".into() }]
+    ));
+
+    let mut strict = config(&[]);
+    strict.deny = vec![WarningCode::UndefinedVariable];
+    let result = Parser::with_config("{{version}}".to_string(), strict)
+        .unwrap()
+        .parse();
+    assert!(matches!(result, Err(ParseError::DeniedWarning(_))));
+}
+
+#[test]
+fn test_variables_from_config_file() {
+    let path = common::temp_path("variables_layer").with_extension("toml");
+    fs::write(
+        &path,
+        "[parser]\nenable_variables = true\n\n[parser.variables]\nproduct = \"Widget\"\n",
+    )
+    .unwrap();
+    let config = Config::from_layers(&[path]).unwrap();
+
+    let ast = Parser::with_config("{{product}} docs".to_string(), config.parser)
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(matches!(
+        &ast[0],
+        Node::Paragraph { content }
+            if content == &vec![Inline::Text { content: "Widget docs".into() }]
+    ));
+}
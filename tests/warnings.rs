@@ -0,0 +1,70 @@
+use md_parser::{ParseError, Parser, ParserConfig, Severity, WarningCode};
+
+fn parser_with_deny(input: &str, deny: Vec<WarningCode>) -> Parser {
+    let config = ParserConfig {
+        deny,
+        ..ParserConfig::default()
+    };
+    Parser::with_config(input.to_string(), config).unwrap()
+}
+
+#[test]
+fn test_unclosed_emphasis_warning_has_code_and_span() {
+    let mut parser =
+        Parser::new("Intro.\n\nFirst line\nthen **bold never ends".to_string()).unwrap();
+    parser.parse().unwrap();
+
+    let warnings = parser.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, WarningCode::UnclosedEmphasis);
+    assert_eq!(warnings[0].severity, Severity::Warning);
+    assert_eq!(warnings[0].span.line, 4);
+    assert_eq!(warnings[0].span.column, Some(6));
+    assert!(warnings[0].to_string().contains("[unclosed-emphasis]"));
+}
+
+#[test]
+fn test_balanced_and_arithmetic_asterisks_do_not_warn() {
+    let mut parser =
+        Parser::new("Some **bold** and *italic* text, 2*3 = 6 and a * b.".to_string()).unwrap();
+    parser.parse().unwrap();
+
+    assert!(parser.warnings().is_empty(), "{:?}", parser.warnings());
+}
+
+#[test]
+fn test_denied_warning_fails_the_parse() {
+    let mut parser = parser_with_deny(
+        "# Title\n\nThis is *unfinished",
+        vec![WarningCode::UnclosedEmphasis],
+    );
+
+    match parser.parse() {
+        Err(ParseError::DeniedWarning(warning)) => {
+            assert_eq!(warning.code, WarningCode::UnclosedEmphasis);
+            assert_eq!(warning.severity, Severity::Error);
+        }
+        other => panic!("expected denied warning, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_deny_only_affects_listed_codes() {
+    let mut parser = parser_with_deny("This is *unfinished", vec![WarningCode::InvalidMermaid]);
+
+    assert!(parser.parse().is_ok());
+    assert_eq!(parser.warnings()[0].severity, Severity::Warning);
+}
+
+#[test]
+fn test_deny_codes_load_from_toml() {
+    let config: ParserConfig = toml::from_str(
+        "max_heading_level = 6\ncode_fence_length = 3\ncode_fence_pattern = \"```\"\nmermaid_language = \"mermaid\"\ndeny = [\"unclosed-emphasis\"]",
+    )
+    .unwrap();
+    assert_eq!(config.deny, vec![WarningCode::UnclosedEmphasis]);
+    assert_eq!(
+        WarningCode::from_name("invalid-mermaid"),
+        Some(WarningCode::InvalidMermaid)
+    );
+}